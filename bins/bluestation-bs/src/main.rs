@@ -4,18 +4,34 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use tetra_config::bluestation::{PhyBackend, SharedConfig, StackMode, parsing};
-use tetra_core::{TdmaTime, debug};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{Sap, TdmaTime, debug};
 use tetra_entities::MessageRouter;
+use tetra_entities::backhaul::entity::BackhaulEntity;
 use tetra_entities::brew::entity::BrewEntity;
+use tetra_entities::monitor::EventLog;
+use tetra_entities::ms_console::NetEntityConsoleWorker;
+use tetra_entities::network::netentity::NetEntity;
+#[cfg(target_os = "linux")]
+use tetra_entities::sndcp::sndcp_bs::negotiated_mtu;
+#[cfg(target_os = "linux")]
+use tetra_entities::sndcp_net::SndcpWorkerTun;
+#[cfg(target_os = "linux")]
+use tetra_entities::sndcp_net::tun::TunInterface;
 use tetra_entities::{
-    cmce::cmce_bs::CmceBs,
+    cmce::{cmce_bs::CmceBs, cmce_ms::CmceMs},
     llc::llc_bs_ms::Llc,
-    lmac::lmac_bs::LmacBs,
-    mle::mle_bs::MleBs,
-    mm::mm_bs::MmBs,
-    phy::{components::soapy_dev::RxTxDevSoapySdr, phy_bs::PhyBs},
+    lmac::{lmac_bs::LmacBs, lmac_mon::LmacMon, lmac_ms::LmacMs},
+    mle::{mle_bs::MleBs, mle_ms::MleMs},
+    mm::{mm_bs::MmBs, mm_ms::MmMs},
+    phy::{
+        components::{file_dev::RxTxDevFile, soapy_dev::RxTxDevSoapySdr},
+        phy_bs::PhyBs,
+        phy_mon::PhyMon,
+        phy_ms::PhyMs,
+    },
     sndcp::sndcp_bs::Sndcp,
-    umac::umac_bs::UmacBs,
+    umac::{umac_bs::UmacBs, umac_mon::UmacMon, umac_ms::UmacMs},
 };
 
 /// Load configuration file
@@ -29,8 +45,19 @@ fn load_config_from_toml(cfg_path: &str) -> SharedConfig {
     }
 }
 
+/// Open the IQ files of the File backend. The stack stops once the RX capture has been replayed
+fn open_file_dev(cfg: &SharedConfig, running: &Arc<AtomicBool>) -> RxTxDevFile {
+    match RxTxDevFile::new(cfg, Some(running.clone())) {
+        Ok(dev) => dev,
+        Err(e) => {
+            println!("Failed to open phy_io files: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Start base station stack
-fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
+fn build_bs_stack(cfg: &mut SharedConfig, running: &Arc<AtomicBool>) -> MessageRouter {
     let mut router = MessageRouter::new(cfg.clone());
 
     // Add suitable Phy component based on PhyIo type
@@ -40,6 +67,11 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
             let phy = PhyBs::new(cfg.clone(), rxdev);
             router.register_entity(Box::new(phy));
         }
+        PhyBackend::File => {
+            let rxdev = open_file_dev(cfg, running);
+            let phy = PhyBs::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
         _ => {
             panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
         }
@@ -68,12 +100,177 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
         eprintln!(" -> Brew/TetraPack integration enabled");
     }
 
+    // Register inter-site backhaul if enabled
+    if let Some(backhaul) = cfg.config().backhaul.as_ref() {
+        let backhaul_entity = BackhaulEntity::new(cfg.clone());
+        router.register_entity(Box::new(backhaul_entity));
+        eprintln!(
+            " -> Inter-site backhaul enabled, site {} with {} peers",
+            backhaul.site_id,
+            backhaul.peers.len()
+        );
+    }
+
+    // Register packet data gateway if a TUN interface is configured
+    register_packet_gateway(&mut router, cfg);
+
     // Init network time
     router.set_dl_time(TdmaTime::default());
 
     router
 }
 
+/// Register the packet data gateway if a TUN interface is configured
+#[cfg(target_os = "linux")]
+fn register_packet_gateway(router: &mut MessageRouter, cfg: &SharedConfig) {
+    let config = cfg.config();
+    if let Some(sndcp_cfg) = config.sndcp.as_ref() {
+        if let Some(tun_device) = &sndcp_cfg.tun_device {
+            let (_, mtu) = negotiated_mtu(sndcp_cfg, config.cell.advanced_link);
+            let tun = match TunInterface::open(tun_device, mtu as usize) {
+                Ok(tun) => tun,
+                Err(e) => {
+                    println!("Failed to open packet data interface: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let gateway = NetEntity::<SndcpWorkerTun>::new(cfg.clone(), TetraEntity::PacketGateway, TetraEntity::Sndcp, Sap::SnSap, tun)
+                .expect("Failed to create packet data gateway");
+            router.register_entity(Box::new(gateway));
+            eprintln!(" -> Packet data gateway on {} enabled (MTU {})", tun_device, mtu);
+        }
+    }
+}
+
+/// TUN interfaces are only available on Linux, refuse to start with one configured
+#[cfg(not(target_os = "linux"))]
+fn register_packet_gateway(_router: &mut MessageRouter, cfg: &SharedConfig) {
+    if let Some(tun_device) = cfg.config().sndcp.as_ref().and_then(|sndcp_cfg| sndcp_cfg.tun_device.as_ref()) {
+        println!(
+            "Packet data interface {} configured, but TUN interfaces are only supported on Linux",
+            tun_device
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Start mobile station stack
+fn build_ms_stack(cfg: &mut SharedConfig, running: &Arc<AtomicBool>) -> MessageRouter {
+    let mut router = MessageRouter::new(cfg.clone());
+
+    let Some(ms_cfg) = cfg.config().ms.clone() else {
+        println!("MS mode requires an [ms] section in the configuration");
+        std::process::exit(1);
+    };
+
+    // Add suitable Phy component based on PhyIo type
+    match cfg.config().phy_io.backend {
+        PhyBackend::SoapySdr => {
+            let rxdev = RxTxDevSoapySdr::new(cfg);
+            let phy = PhyMs::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
+        PhyBackend::File => {
+            let rxdev = open_file_dev(cfg, running);
+            let phy = PhyMs::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
+        _ => {
+            panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
+        }
+    }
+
+    // Add remaining components
+    let lmac = LmacMs::new(cfg.clone());
+    let umac = UmacMs::new(cfg.clone());
+    let llc = Llc::new(cfg.clone());
+    let mle = MleMs::new(cfg.clone());
+    let mm = MmMs::new(cfg.clone());
+    let cmce = CmceMs::new(cfg.clone());
+    router.register_entity(Box::new(lmac));
+    router.register_entity(Box::new(umac));
+    router.register_entity(Box::new(llc));
+    router.register_entity(Box::new(mle));
+    router.register_entity(Box::new(mm));
+    router.register_entity(Box::new(cmce));
+
+    // Register the console as CC, SDS and MM user, fed with lines from stdin
+    if ms_cfg.console {
+        let (line_sender, line_receiver) = crossbeam_channel::unbounded();
+        std::thread::Builder::new()
+            .name("ms-console-stdin".to_string())
+            .spawn(move || {
+                for line in std::io::stdin().lines().map_while(Result::ok) {
+                    if line_sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to spawn console input thread");
+        let console =
+            NetEntity::<NetEntityConsoleWorker>::new(cfg.clone(), TetraEntity::User, TetraEntity::Cmce, Sap::TnccSap, line_receiver)
+                .expect("Failed to create MS console");
+        router.register_entity(Box::new(console));
+        eprintln!(" -> MS console enabled");
+    }
+    eprintln!(" -> MS ISSI {}, groups {:?}", ms_cfg.issi, ms_cfg.groups);
+
+    // Timing follows the serving cell once the MS has synchronized
+    router.set_dl_time(TdmaTime::default());
+
+    router
+}
+
+/// Start passive monitor stack
+fn build_mon_stack(cfg: &mut SharedConfig, running: &Arc<AtomicBool>) -> MessageRouter {
+    let mut router = MessageRouter::new(cfg.clone());
+    let mon_cfg = cfg.config().monitor.clone().unwrap_or_default();
+
+    // Add suitable Phy component based on PhyIo type
+    match cfg.config().phy_io.backend {
+        PhyBackend::SoapySdr => {
+            let rxdev = RxTxDevSoapySdr::new(cfg);
+            let phy = PhyMon::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
+        PhyBackend::File => {
+            let rxdev = open_file_dev(cfg, running);
+            let phy = PhyMon::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
+        _ => {
+            panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
+        }
+    }
+
+    let event_log = match &mon_cfg.event_log {
+        Some(path) => match EventLog::open(path) {
+            Ok(event_log) => event_log,
+            Err(e) => {
+                println!("Failed to open monitor event log {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => EventLog::stdout(),
+    };
+
+    // Decoding happens in the MAC, there are no upper layers in a monitor stack
+    let lmac = LmacMon::new(cfg.clone());
+    let umac = UmacMon::new(cfg.clone(), event_log);
+    router.register_entity(Box::new(lmac));
+    router.register_entity(Box::new(umac));
+    eprintln!(
+        " -> Monitoring downlink{}, events to {}",
+        if mon_cfg.uplink { " and uplink" } else { "" },
+        mon_cfg.event_log.as_deref().unwrap_or("stdout")
+    );
+
+    // Timing follows the monitored cell once synchronized
+    router.set_dl_time(TdmaTime::default());
+
+    router
+}
+
 #[derive(Parser, Debug)]
 #[command(
     author,
@@ -100,18 +297,16 @@ fn main() {
     let mut cfg = load_config_from_toml(&args.config);
     let _log_guard = debug::setup_logging_default(cfg.config().debug_log.clone());
 
+    // Cleared on Ctrl+C, or by the File backend at the end of its capture
+    let running = Arc::new(AtomicBool::new(true));
+
     let mut router = match cfg.config().stack_mode {
-        StackMode::Mon => {
-            unimplemented!("Monitor mode is not implemented");
-        }
-        StackMode::Ms => {
-            unimplemented!("MS mode is not implemented");
-        }
-        StackMode::Bs => build_bs_stack(&mut cfg),
+        StackMode::Mon => build_mon_stack(&mut cfg, &running),
+        StackMode::Ms => build_ms_stack(&mut cfg, &running),
+        StackMode::Bs => build_bs_stack(&mut cfg, &running),
     };
 
     // Set up Ctrl+C handler for graceful shutdown
-    let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
     ctrlc::set_handler(move || {
         r.store(false, Ordering::SeqCst);
@@ -1,10 +1,19 @@
 use serde::Deserialize;
 use std::sync::{Arc, RwLock};
-use tetra_core::freqs::FreqInfo;
+use tetra_core::{MAX_CARRIERS, TimeslotAllocator};
 
 use crate::bluestation::{CfgCellInfo, CfgNetInfo, CfgPhyIo, PhyBackend, StackState};
 
+use super::sec_auth::CfgAuth;
+use super::sec_backhaul::CfgBackhaul;
 use super::sec_brew::CfgBrew;
+use super::sec_encryption::{CfgEncryption, SecurityClass};
+use super::sec_monitor::CfgMonitor;
+use super::sec_ms::CfgMs;
+use super::sec_neighbour::CfgNeighbourCell;
+use super::sec_registration::CfgRegistrationStore;
+use super::sec_sds_store::CfgSdsStore;
+use super::sec_sndcp::CfgSndcp;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -18,6 +27,12 @@ pub enum StackMode {
 pub struct StackConfig {
     pub stack_mode: StackMode,
     pub debug_log: Option<String>,
+    /// File operator alarms, such as emergency calls, are appended to as JSON lines.
+    /// Alarms are always logged at error level as well
+    pub alarm_log: Option<String>,
+    /// Subscriber and group authorization database (TOML). When absent, every MS may register and
+    /// attach to any group. The file is reloaded when it changes
+    pub subscriber_db: Option<String>,
 
     pub phy_io: CfgPhyIo,
     pub net: CfgNetInfo,
@@ -25,6 +40,33 @@ pub struct StackConfig {
 
     /// Brew protocol (TetraPack/BrandMeister) configuration
     pub brew: Option<CfgBrew>,
+
+    /// Air-interface authentication configuration. When absent, no authentication is performed
+    pub auth: Option<CfgAuth>,
+
+    /// Air-interface encryption configuration. When absent, the cell operates in security class 1
+    pub encryption: Option<CfgEncryption>,
+
+    /// SNDCP packet data configuration. Required when the cell advertises the SNDCP service
+    pub sndcp: Option<CfgSndcp>,
+
+    /// Mobile station configuration. Required in MS stack mode
+    pub ms: Option<CfgMs>,
+
+    /// Passive monitor configuration. Defaults apply in Mon stack mode when absent
+    pub monitor: Option<CfgMonitor>,
+
+    /// Neighbour cells announced in D-NWRK-BROADCAST. Empty if none are configured
+    pub neighbour_cells: Vec<CfgNeighbourCell>,
+
+    /// Inter-site backhaul configuration. When absent, the site operates on its own
+    pub backhaul: Option<CfgBackhaul>,
+
+    /// Registration persistence. When absent, registrations are lost on restart
+    pub registration_store: Option<CfgRegistrationStore>,
+
+    /// SDS store-and-forward. When absent, SDS for subscribers that cannot be reached are dropped
+    pub sds_store: Option<CfgSdsStore>,
 }
 
 impl StackConfig {
@@ -37,6 +79,14 @@ impl StackConfig {
                     return Err("soapysdr configuration must be provided for Soapysdr backend");
                 };
             }
+            PhyBackend::File => {
+                if self.phy_io.file.is_none() {
+                    return Err("file configuration must be provided for File backend");
+                };
+                if self.cell.freq_info().is_err() {
+                    return Err("Invalid cell info frequency settings");
+                }
+            }
             PhyBackend::None => {} // For testing
             PhyBackend::Undefined => {
                 return Err("phy_io backend must be defined");
@@ -51,14 +101,7 @@ impl StackConfig {
                 .as_ref()
                 .expect("SoapySdr config must be set for SoapySdr PhyIo");
 
-            let Ok(freq_info) = FreqInfo::from_components(
-                self.cell.freq_band,
-                self.cell.main_carrier,
-                self.cell.freq_offset_hz,
-                self.cell.reverse_operation,
-                self.cell.duplex_spacing_id,
-                self.cell.custom_duplex_spacing,
-            ) else {
+            let Ok(freq_info) = self.cell.freq_info() else {
                 return Err("Invalid cell info frequency settings");
             };
 
@@ -75,6 +118,40 @@ impl StackConfig {
             };
         }
 
+        // Secondary carriers share the band settings of the main carrier
+        let carriers = &self.cell.secondary_carriers;
+        if carriers.len() >= MAX_CARRIERS {
+            return Err("cell_info.secondary_carriers has too many carriers");
+        }
+        for (i, carrier) in carriers.iter().enumerate() {
+            if *carrier == self.cell.main_carrier || carriers[..i].contains(carrier) {
+                return Err("cell_info.secondary_carriers must be unique and differ from main_carrier");
+            }
+            if self.cell.carrier_freq_info(i as u8 + 1).is_err() {
+                return Err("Invalid cell_info.secondary_carriers frequency settings");
+            }
+        }
+
+        // Don't advertise packet data without an address pool to serve it from
+        if self.cell.sndcp_service && self.sndcp.is_none() {
+            return Err("cell_info.sndcp_service requires an sndcp configuration section");
+        }
+
+        // Class 3 cells derive the keys for individual traffic during authentication
+        if self
+            .encryption
+            .as_ref()
+            .is_some_and(|enc| enc.security_class == SecurityClass::Class3)
+            && self.auth.is_none()
+        {
+            return Err("encryption security class 3 requires an authentication configuration section");
+        }
+
+        // An MS needs an identity to register with
+        if self.stack_mode == StackMode::Ms && self.ms.is_none() {
+            return Err("stack_mode Ms requires an ms configuration section");
+        }
+
         // Validate timezone if configured
         if let Some(ref tz) = self.cell.timezone {
             if tz.parse::<chrono_tz::Tz>().is_err() {
@@ -82,6 +159,25 @@ impl StackConfig {
             }
         }
 
+        // Cell reselection parameters are sent in 4 bits, as 2 dB steps
+        let reselect = [
+            self.cell.slow_reselect_threshold,
+            self.cell.slow_reselect_hysteresis,
+            self.cell.fast_reselect_threshold,
+            self.cell.fast_reselect_hysteresis,
+        ];
+        if reselect.iter().any(|db| *db > 30) {
+            return Err("cell_info reselect thresholds and hysteresis must be 0-30 dB");
+        }
+
+        if self.alarm_log.as_deref().is_some_and(|path| path.trim().is_empty()) {
+            return Err("Invalid alarm_log: path must not be empty");
+        }
+
+        if self.subscriber_db.as_deref().is_some_and(|path| path.trim().is_empty()) {
+            return Err("Invalid subscriber_db: path must not be empty");
+        }
+
         Ok(())
     }
 }
@@ -100,13 +196,18 @@ impl SharedConfig {
         Self::from_parts(cfg, StackState::default())
     }
 
-    pub fn from_parts(cfg: StackConfig, state: StackState) -> Self {
+    pub fn from_parts(cfg: StackConfig, mut state: StackState) -> Self {
         // Check config for validity before returning the SharedConfig object
         match cfg.validate() {
             Ok(_) => {}
             Err(e) => panic!("Invalid stack configuration: {}", e),
         }
 
+        // Traffic channels are allocated on all carriers of the cell
+        if state.timeslot_alloc.num_carriers() != cfg.cell.num_carriers() {
+            state.timeslot_alloc = TimeslotAllocator::new(cfg.cell.num_carriers());
+        }
+
         Self {
             cfg: Arc::new(cfg),
             state: Arc::new(RwLock::new(state)),
@@ -16,8 +16,41 @@ pub use sec_cell::*;
 pub mod sec_phy_soapy;
 pub use sec_phy_soapy::*;
 
+pub mod sec_phy_file;
+pub use sec_phy_file::*;
+
 pub mod sec_brew;
 pub use sec_brew::*;
 
+pub mod sec_auth;
+pub use sec_auth::*;
+
+pub mod sec_encryption;
+pub use sec_encryption::*;
+
+pub mod sec_sndcp;
+pub use sec_sndcp::*;
+
+pub mod sec_ms;
+pub use sec_ms::*;
+
+pub mod sec_monitor;
+pub use sec_monitor::*;
+
+pub mod sec_neighbour;
+pub use sec_neighbour::*;
+
+pub mod sec_backhaul;
+pub use sec_backhaul::*;
+
+pub mod sec_registration;
+pub use sec_registration::*;
+
+pub mod sec_sds_store;
+pub use sec_sds_store::*;
+
+pub mod subscriber_db;
+pub use subscriber_db::*;
+
 pub mod state;
 pub use state::*;
@@ -9,7 +9,17 @@ use toml::Value;
 use crate::bluestation::{CellInfoDto, NetInfoDto, cell_dto_to_cfg, net_dto_to_cfg};
 
 use super::config::{SharedConfig, StackConfig, StackMode};
+use super::sec_auth::{CfgAuthDto, auth_dto_to_cfg};
+use super::sec_backhaul::{CfgBackhaulDto, backhaul_dto_to_cfg};
 use super::sec_brew::{CfgBrewDto, apply_brew_patch};
+use super::sec_encryption::{CfgEncryptionDto, encryption_dto_to_cfg};
+use super::sec_monitor::{CfgMonitorDto, monitor_dto_to_cfg};
+use super::sec_ms::{CfgMsDto, ms_dto_to_cfg};
+use super::sec_neighbour::{CfgNeighbourCellDto, neighbour_cells_dto_to_cfg};
+use super::sec_registration::{CfgRegistrationStoreDto, registration_store_dto_to_cfg};
+use super::sec_sds_store::{CfgSdsStoreDto, sds_store_dto_to_cfg};
+use super::sec_sndcp::{CfgSndcpDto, sndcp_dto_to_cfg};
+use super::subscriber_db::SubscriberDb;
 use super::{PhyIoDto, StackState, phy_dto_to_cfg};
 
 /// Build `SharedConfig` from a TOML configuration file
@@ -42,6 +52,9 @@ pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error:
             return Err(format!("Unrecognized fields: phy_io.soapysdr::{:?}", extra_keys_filtered).into());
         }
     }
+    if let Some(extra) = root.phy_io.file.as_ref().map(|file| &file.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields: phy_io.file::{:?}", sorted_keys(extra)).into());
+    }
     if !root.net_info.extra.is_empty() {
         return Err(format!("Unrecognized fields in net_info: {:?}", sorted_keys(&root.net_info.extra)).into());
     }
@@ -56,20 +69,118 @@ pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error:
         }
     }
 
+    // Optional authentication section
+    if let Some(extra) = root
+        .authentication
+        .as_ref()
+        .map(|auth| &auth.extra)
+        .filter(|extra| !extra.is_empty())
+    {
+        return Err(format!("Unrecognized fields in authentication config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional encryption section
+    if let Some(extra) = root.encryption.as_ref().map(|enc| &enc.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields in encryption config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional sndcp section
+    if let Some(extra) = root.sndcp.as_ref().map(|sndcp| &sndcp.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields in sndcp config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional ms section
+    if let Some(extra) = root.ms.as_ref().map(|ms| &ms.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields in ms config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional monitor section
+    if let Some(extra) = root.monitor.as_ref().map(|mon| &mon.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields in monitor config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional backhaul section
+    if let Some(extra) = root.backhaul.as_ref().map(|bh| &bh.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields in backhaul config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional registration store section
+    if let Some(extra) = root
+        .registration_store
+        .as_ref()
+        .map(|store| &store.extra)
+        .filter(|extra| !extra.is_empty())
+    {
+        return Err(format!("Unrecognized fields in registration_store config: {:?}", sorted_keys(extra)).into());
+    }
+
+    // Optional SDS store section
+    if let Some(extra) = root.sds_store.as_ref().map(|store| &store.extra).filter(|extra| !extra.is_empty()) {
+        return Err(format!("Unrecognized fields in sds_store config: {:?}", sorted_keys(extra)).into());
+    }
+
     // Build config from required and optional values
     let mut cfg = StackConfig {
         stack_mode: root.stack_mode,
         debug_log: root.debug_log,
-        phy_io: phy_dto_to_cfg(root.phy_io),
+        alarm_log: root.alarm_log,
+        subscriber_db: root.subscriber_db,
+        phy_io: phy_dto_to_cfg(root.phy_io)?,
         net: net_dto_to_cfg(root.net_info),
         cell: cell_dto_to_cfg(root.cell_info),
         brew: None,
+        auth: None,
+        encryption: None,
+        sndcp: None,
+        ms: None,
+        monitor: None,
+        neighbour_cells: neighbour_cells_dto_to_cfg(root.neighbour_cells)?,
+        backhaul: None,
+        registration_store: None,
+        sds_store: None,
     };
 
     if let Some(brew) = root.brew {
         cfg.brew = Some(apply_brew_patch(brew));
     }
 
+    if let Some(auth) = root.authentication {
+        cfg.auth = Some(auth_dto_to_cfg(auth)?);
+    }
+
+    if let Some(encryption) = root.encryption {
+        cfg.encryption = Some(encryption_dto_to_cfg(encryption)?);
+    }
+
+    if let Some(sndcp) = root.sndcp {
+        cfg.sndcp = Some(sndcp_dto_to_cfg(sndcp)?);
+    }
+
+    if let Some(ms) = root.ms {
+        cfg.ms = Some(ms_dto_to_cfg(ms)?);
+    }
+
+    if let Some(monitor) = root.monitor {
+        cfg.monitor = Some(monitor_dto_to_cfg(monitor)?);
+    }
+
+    if let Some(backhaul) = root.backhaul {
+        cfg.backhaul = Some(backhaul_dto_to_cfg(backhaul)?);
+    }
+
+    if let Some(store) = root.registration_store {
+        cfg.registration_store = Some(registration_store_dto_to_cfg(store)?);
+    }
+
+    if let Some(store) = root.sds_store {
+        cfg.sds_store = Some(sds_store_dto_to_cfg(store)?);
+    }
+
+    // The subscriber database is loaded by MM, but a broken file should stop the stack from starting
+    if let Some(path) = &cfg.subscriber_db {
+        SubscriberDb::from_file(path)?;
+    }
+
     // Mutable runtime state
     let state = StackState::default();
 
@@ -105,12 +216,24 @@ struct TomlConfigRoot {
     config_version: String,
     stack_mode: StackMode,
     debug_log: Option<String>,
+    alarm_log: Option<String>,
+    subscriber_db: Option<String>,
 
     phy_io: PhyIoDto,
     net_info: NetInfoDto,
     cell_info: CellInfoDto,
 
     brew: Option<CfgBrewDto>,
+    authentication: Option<CfgAuthDto>,
+    encryption: Option<CfgEncryptionDto>,
+    sndcp: Option<CfgSndcpDto>,
+    ms: Option<CfgMsDto>,
+    monitor: Option<CfgMonitorDto>,
+    #[serde(default)]
+    neighbour_cells: Vec<CfgNeighbourCellDto>,
+    backhaul: Option<CfgBackhaulDto>,
+    registration_store: Option<CfgRegistrationStoreDto>,
+    sds_store: Option<CfgSdsStoreDto>,
 
     #[serde(flatten)]
     extra: HashMap<String, Value>,
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Whether registration on this cell requires successful air-interface authentication
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum AuthPolicy {
+    /// Never authenticate, accept every registering MS
+    Disabled,
+    /// Authenticate MSs for which a key is known, accept unknown MSs without authentication
+    Optional,
+    /// Only accept MSs that have a key and successfully authenticate
+    Required,
+}
+
+/// Air-interface authentication configuration
+#[derive(Debug, Clone)]
+pub struct CfgAuth {
+    /// Registration policy for this cell
+    pub policy: AuthPolicy,
+    /// Authentication key K per ISSI
+    pub keys: HashMap<u32, [u8; 16]>,
+}
+
+impl CfgAuth {
+    pub fn key_for(&self, issi: u32) -> Option<&[u8; 16]> {
+        self.keys.get(&issi)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CfgAuthDto {
+    /// Registration policy for this cell
+    #[serde(default = "default_auth_policy")]
+    pub policy: AuthPolicy,
+    /// Authentication key K per ISSI, as 32 hex characters
+    #[serde(default)]
+    pub keys: HashMap<String, String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_auth_policy() -> AuthPolicy {
+    AuthPolicy::Required
+}
+
+/// Convert a CfgAuthDto (from TOML) into a CfgAuth (used in the stack config)
+pub fn auth_dto_to_cfg(src: CfgAuthDto) -> Result<CfgAuth, String> {
+    let mut keys = HashMap::with_capacity(src.keys.len());
+    for (issi_str, key_str) in src.keys {
+        let issi = issi_str
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid ISSI in authentication.keys: {}", issi_str))?;
+        let key = parse_key(&key_str).ok_or_else(|| format!("Invalid key for ISSI {}: expected 32 hex characters", issi))?;
+        keys.insert(issi, key);
+    }
+    Ok(CfgAuth { policy: src.policy, keys })
+}
+
+fn parse_key(s: &str) -> Option<[u8; 16]> {
+    if s.len() != 32 || !s.is_ascii() {
+        return None;
+    }
+    let mut key = [0u8; 16];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_dto_to_cfg() {
+        let dto: CfgAuthDto = toml::from_str(
+            r#"
+            policy = "Optional"
+            [keys]
+            1000001 = "000102030405060708090a0b0c0d0e0f"
+            "#,
+        )
+        .unwrap();
+        let cfg = auth_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.policy, AuthPolicy::Optional);
+        assert_eq!(cfg.key_for(1000001).unwrap()[15], 0x0f);
+        assert!(cfg.key_for(1000002).is_none());
+    }
+
+    #[test]
+    fn test_auth_dto_rejects_bad_key() {
+        let dto: CfgAuthDto = toml::from_str(
+            r#"
+            [keys]
+            1000001 = "0001"
+            "#,
+        )
+        .unwrap();
+        assert!(auth_dto_to_cfg(dto).is_err());
+    }
+}
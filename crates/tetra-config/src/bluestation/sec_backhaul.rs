@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Inter-site backhaul configuration, links this site with the other BlueStation sites of the network
+#[derive(Debug, Clone)]
+pub struct CfgBackhaul {
+    /// Identifier of this site, unique within the network
+    pub site_id: u16,
+    /// Address the other sites connect to
+    pub listen: SocketAddr,
+    /// The other sites of the network
+    pub peers: Vec<CfgBackhaulPeer>,
+    /// Delay before reconnecting to an unreachable site
+    pub reconnect_delay: Duration,
+    /// Secret shared by all sites of the network, authenticates every message exchanged between them
+    pub shared_secret: String,
+}
+
+/// Another site of the network, reached over the backhaul
+#[derive(Debug, Clone)]
+pub struct CfgBackhaulPeer {
+    pub site_id: u16,
+    /// Address the site listens on
+    pub address: SocketAddr,
+}
+
+#[derive(Deserialize)]
+pub struct CfgBackhaulDto {
+    pub site_id: u16,
+    pub listen: SocketAddr,
+    #[serde(default)]
+    pub peers: Vec<CfgBackhaulPeerDto>,
+    #[serde(default = "default_backhaul_reconnect_delay")]
+    pub reconnect_delay_secs: u64,
+    pub shared_secret: String,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+pub struct CfgBackhaulPeerDto {
+    pub site_id: u16,
+    pub address: SocketAddr,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_backhaul_reconnect_delay() -> u64 {
+    5
+}
+
+/// Convert a CfgBackhaulDto (from TOML) into a CfgBackhaul (used in the stack config)
+pub fn backhaul_dto_to_cfg(src: CfgBackhaulDto) -> Result<CfgBackhaul, String> {
+    if src.shared_secret.is_empty() {
+        return Err("Invalid backhaul.shared_secret: must not be empty".to_string());
+    }
+
+    let mut peers: Vec<CfgBackhaulPeer> = Vec::with_capacity(src.peers.len());
+    for dto in src.peers {
+        if !dto.extra.is_empty() {
+            let mut keys: Vec<&str> = dto.extra.keys().map(|s| s.as_str()).collect();
+            keys.sort_unstable();
+            return Err(format!("Unrecognized fields in backhaul.peers: {:?}", keys));
+        }
+        if dto.site_id == src.site_id {
+            return Err(format!("backhaul.peers site_id {} is the site_id of this site", dto.site_id));
+        }
+        if peers.iter().any(|p| p.site_id == dto.site_id) {
+            return Err(format!("Duplicate backhaul.peers site_id {}", dto.site_id));
+        }
+        peers.push(CfgBackhaulPeer {
+            site_id: dto.site_id,
+            address: dto.address,
+        });
+    }
+
+    Ok(CfgBackhaul {
+        site_id: src.site_id,
+        listen: src.listen,
+        peers,
+        reconnect_delay: Duration::from_secs(src.reconnect_delay_secs),
+        shared_secret: src.shared_secret,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backhaul_dto_to_cfg() {
+        let dto: CfgBackhaulDto = toml::from_str(
+            r#"
+            site_id = 1
+            listen = "0.0.0.0:7700"
+            shared_secret = "s3cr3t"
+
+            [[peers]]
+            site_id = 2
+            address = "10.0.0.2:7700"
+
+            [[peers]]
+            site_id = 3
+            address = "10.0.0.3:7700"
+            "#,
+        )
+        .unwrap();
+        let cfg = backhaul_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.site_id, 1);
+        assert_eq!(cfg.peers.len(), 2);
+        assert_eq!(cfg.peers[1].address, "10.0.0.3:7700".parse().unwrap());
+        assert_eq!(cfg.reconnect_delay, Duration::from_secs(5));
+        assert_eq!(cfg.shared_secret, "s3cr3t");
+    }
+
+    #[test]
+    fn test_backhaul_rejects_own_site_id() {
+        let dto: CfgBackhaulDto = toml::from_str(
+            r#"
+            site_id = 1
+            listen = "0.0.0.0:7700"
+            shared_secret = "s3cr3t"
+
+            [[peers]]
+            site_id = 1
+            address = "10.0.0.2:7700"
+            "#,
+        )
+        .unwrap();
+        assert!(backhaul_dto_to_cfg(dto).is_err());
+    }
+
+    #[test]
+    fn test_backhaul_requires_shared_secret() {
+        let dto: CfgBackhaulDto = toml::from_str(
+            r#"
+            site_id = 1
+            listen = "0.0.0.0:7700"
+            shared_secret = ""
+            "#,
+        )
+        .unwrap();
+        assert!(backhaul_dto_to_cfg(dto).is_err());
+    }
+}
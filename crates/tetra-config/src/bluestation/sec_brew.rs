@@ -23,6 +23,8 @@ pub struct CfgBrew {
 
     /// Set to true when SDS between local and Brew clients is enabled
     pub feature_sds_enabled: bool,
+    /// Set to true when packet data between local PDP contexts and Brew is enabled
+    pub feature_packet_data_enabled: bool,
     /// If present, restrict Brew call to these remote SSIs
     pub whitelisted_ssis: Option<Vec<u32>>,
 }
@@ -54,6 +56,10 @@ pub struct CfgBrewDto {
     #[serde(default = "default_brew_feature_sds_enabled")]
     pub feature_sds_enabled: bool,
 
+    /// Set to true when packet data between local PDP contexts and Brew is enabled
+    #[serde(default)]
+    pub feature_packet_data_enabled: bool,
+
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -81,6 +87,7 @@ pub fn apply_brew_patch(src: CfgBrewDto) -> CfgBrew {
         reconnect_delay: Duration::from_secs(src.reconnect_delay_secs),
         jitter_initial_latency_frames: src.jitter_initial_latency_frames,
         feature_sds_enabled: src.feature_sds_enabled,
+        feature_packet_data_enabled: src.feature_packet_data_enabled,
         whitelisted_ssis: src.whitelisted_ssis,
     }
 }
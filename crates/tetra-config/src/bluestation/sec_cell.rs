@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use tetra_core::freqs::FreqInfo;
 use tetra_core::ranges::SortedDisjointSsiRanges;
 use toml::Value;
 
@@ -25,6 +26,9 @@ pub struct CfgCellInfo {
     pub custom_duplex_spacing: Option<u32>,
     /// 1 bits, from MAC SYSINFO
     pub reverse_operation: bool,
+    /// Additional carriers of the cell, in the band, offset and duplex spacing of the main carrier.
+    /// The control channel stays on the main carrier, secondary carriers only carry traffic.
+    pub secondary_carriers: Vec<u16>,
 
     // 14 bits, from 18.4.2.2 D-MLE-SYSINFO
     pub location_area: u16,
@@ -54,11 +58,50 @@ pub struct CfgCellInfo {
 
     pub local_ssi_ranges: SortedDisjointSsiRanges,
 
+    /// Cell reselection thresholds and hysteresis broadcast in D-NWRK-BROADCAST, in dB (0-30, 2 dB steps)
+    pub slow_reselect_threshold: u8,
+    pub slow_reselect_hysteresis: u8,
+    pub fast_reselect_threshold: u8,
+    pub fast_reselect_hysteresis: u8,
+
     /// IANA timezone name (e.g. "Europe/Amsterdam"). When set, enables D-NWRK-BROADCAST
     /// time broadcasting so MSs can synchronize their clocks.
     pub timezone: Option<String>,
 }
 
+impl CfgCellInfo {
+    /// Frequency information of the main carrier
+    pub fn freq_info(&self) -> Result<FreqInfo, String> {
+        self.carrier_freq_info(0)
+    }
+
+    /// Number of carriers of the cell, the main carrier included
+    pub fn num_carriers(&self) -> usize {
+        1 + self.secondary_carriers.len()
+    }
+
+    /// Carrier number of the carrier with the given index. Index 0 is the main carrier,
+    /// the secondary carriers follow in configuration order.
+    pub fn carrier_number(&self, carrier: u8) -> u16 {
+        match carrier {
+            0 => self.main_carrier,
+            _ => self.secondary_carriers[carrier as usize - 1],
+        }
+    }
+
+    /// Frequency information of the carrier with the given index, see carrier_number()
+    pub fn carrier_freq_info(&self, carrier: u8) -> Result<FreqInfo, String> {
+        FreqInfo::from_components(
+            self.freq_band,
+            self.carrier_number(carrier),
+            self.freq_offset_hz,
+            self.reverse_operation,
+            self.duplex_spacing_id,
+            self.custom_duplex_spacing,
+        )
+    }
+}
+
 #[derive(Default, Deserialize)]
 pub struct CellInfoDto {
     pub main_carrier: u16,
@@ -67,6 +110,7 @@ pub struct CellInfoDto {
     pub duplex_spacing: u8,
     pub reverse_operation: bool,
     pub custom_duplex_spacing: Option<u32>,
+    pub secondary_carriers: Option<Vec<u16>>,
 
     pub location_area: u16,
 
@@ -94,6 +138,11 @@ pub struct CellInfoDto {
 
     pub local_ssi_ranges: Option<Vec<(u32, u32)>>,
 
+    pub slow_reselect_threshold: Option<u8>,
+    pub slow_reselect_hysteresis: Option<u8>,
+    pub fast_reselect_threshold: Option<u8>,
+    pub fast_reselect_hysteresis: Option<u8>,
+
     pub timezone: Option<String>,
 
     #[serde(flatten)]
@@ -108,6 +157,7 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
         duplex_spacing_id: ci.duplex_spacing,
         reverse_operation: ci.reverse_operation,
         custom_duplex_spacing: ci.custom_duplex_spacing,
+        secondary_carriers: ci.secondary_carriers.unwrap_or_default(),
         location_area: ci.location_area,
         neighbor_cell_broadcast: ci.neighbor_cell_broadcast.unwrap_or(0),
         late_entry_supported: ci.late_entry_supported.unwrap_or(false),
@@ -133,6 +183,42 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
             .local_ssi_ranges
             .map(SortedDisjointSsiRanges::from_vec_tuple)
             .unwrap_or(SortedDisjointSsiRanges::from_vec_ssirange(vec![])),
+        slow_reselect_threshold: ci.slow_reselect_threshold.unwrap_or(10),
+        slow_reselect_hysteresis: ci.slow_reselect_hysteresis.unwrap_or(10),
+        fast_reselect_threshold: ci.fast_reselect_threshold.unwrap_or(6),
+        fast_reselect_hysteresis: ci.fast_reselect_hysteresis.unwrap_or(6),
         timezone: ci.timezone,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(extra: &str) -> CfgCellInfo {
+        let toml = format!(
+            "main_carrier = 1521\nfreq_band = 4\nfreq_offset = 0\nduplex_spacing = 4\nreverse_operation = false\nlocation_area = 2\n{}",
+            extra
+        );
+        cell_dto_to_cfg(toml::from_str(&toml).unwrap())
+    }
+
+    #[test]
+    fn test_single_carrier_default() {
+        let cell = parse("");
+        assert_eq!(cell.num_carriers(), 1);
+        assert_eq!(cell.carrier_number(0), 1521);
+    }
+
+    #[test]
+    fn test_secondary_carrier_freqs() {
+        let cell = parse("secondary_carriers = [1525, 1530]");
+        assert_eq!(cell.num_carriers(), 3);
+        assert_eq!(cell.carrier_number(2), 1530);
+
+        let (main_dl, main_ul) = cell.freq_info().unwrap().get_freqs();
+        let (dl, ul) = cell.carrier_freq_info(1).unwrap().get_freqs();
+        assert_eq!(dl - main_dl, 4 * 25000);
+        assert_eq!(ul - main_ul, 4 * 25000);
+    }
+}
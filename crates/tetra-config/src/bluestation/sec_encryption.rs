@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Air-interface encryption security class of the cell (EN 300 392-7 clause 6.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityClass {
+    /// No air-interface encryption
+    Class1,
+    /// Static cipher key (SCK) encryption
+    Class2,
+    /// Derived cipher key (DCK) for individual traffic, common cipher key (CCK) for group traffic
+    Class3,
+}
+
+/// Air-interface encryption configuration
+#[derive(Debug, Clone)]
+pub struct CfgEncryption {
+    pub security_class: SecurityClass,
+    /// Static cipher key, 80 bits. Required for security class 2
+    pub sck: Option<u128>,
+    /// SCK number (SCKN, 1-32) broadcast in SYSINFO
+    pub sck_number: u8,
+    /// Common cipher key, 80 bits. Required for security class 3
+    pub cck: Option<u128>,
+}
+
+#[derive(Deserialize)]
+pub struct CfgEncryptionDto {
+    /// Security class 1, 2 or 3
+    pub security_class: u8,
+    /// Static cipher key, as 20 hex characters
+    pub sck: Option<String>,
+    #[serde(default = "default_sck_number")]
+    pub sck_number: u8,
+    /// Common cipher key, as 20 hex characters
+    pub cck: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_sck_number() -> u8 {
+    1
+}
+
+/// Convert a CfgEncryptionDto (from TOML) into a CfgEncryption (used in the stack config)
+pub fn encryption_dto_to_cfg(src: CfgEncryptionDto) -> Result<CfgEncryption, String> {
+    let security_class = match src.security_class {
+        1 => SecurityClass::Class1,
+        2 => SecurityClass::Class2,
+        3 => SecurityClass::Class3,
+        c => return Err(format!("Invalid encryption.security_class {}: expected 1, 2 or 3", c)),
+    };
+    if !(1..=32).contains(&src.sck_number) {
+        return Err(format!("Invalid encryption.sck_number {}: expected 1-32", src.sck_number));
+    }
+    let sck = src
+        .sck
+        .map(|s| parse_cipher_key(&s).ok_or("Invalid encryption.sck: expected 20 hex characters"))
+        .transpose()?;
+    let cck = src
+        .cck
+        .map(|s| parse_cipher_key(&s).ok_or("Invalid encryption.cck: expected 20 hex characters"))
+        .transpose()?;
+
+    match security_class {
+        SecurityClass::Class2 if sck.is_none() => return Err("encryption.sck is required for security class 2".to_string()),
+        SecurityClass::Class3 if cck.is_none() => return Err("encryption.cck is required for security class 3".to_string()),
+        _ => {}
+    }
+
+    Ok(CfgEncryption {
+        security_class,
+        sck,
+        sck_number: src.sck_number,
+        cck,
+    })
+}
+
+fn parse_cipher_key(s: &str) -> Option<u128> {
+    if s.len() != 20 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u128::from_str_radix(s, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encryption_dto_to_cfg() {
+        let dto: CfgEncryptionDto = toml::from_str(
+            r#"
+            security_class = 2
+            sck = "00112233445566778899"
+            "#,
+        )
+        .unwrap();
+        let cfg = encryption_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.security_class, SecurityClass::Class2);
+        assert_eq!(cfg.sck, Some(0x00112233445566778899));
+        assert_eq!(cfg.sck_number, 1);
+        assert!(cfg.cck.is_none());
+    }
+
+    #[test]
+    fn test_encryption_dto_requires_class_key() {
+        let dto: CfgEncryptionDto = toml::from_str(
+            r#"
+            security_class = 3
+            sck = "00112233445566778899"
+            "#,
+        )
+        .unwrap();
+        assert!(encryption_dto_to_cfg(dto).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Passive monitor configuration, used when running in Mon stack mode
+#[derive(Debug, Clone, Default)]
+pub struct CfgMonitor {
+    /// Also receive and decode the uplink carrier paired with the monitored downlink
+    pub uplink: bool,
+    /// File the structured event log is appended to, one JSON object per line. Written to stdout if not set
+    pub event_log: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CfgMonitorDto {
+    #[serde(default)]
+    pub uplink: bool,
+    pub event_log: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Convert a CfgMonitorDto (from TOML) into a CfgMonitor (used in the stack config)
+pub fn monitor_dto_to_cfg(src: CfgMonitorDto) -> Result<CfgMonitor, String> {
+    if src.event_log.as_deref().is_some_and(|path| path.trim().is_empty()) {
+        return Err("Invalid monitor.event_log: path must not be empty".to_string());
+    }
+    Ok(CfgMonitor {
+        uplink: src.uplink,
+        event_log: src.event_log,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_dto_to_cfg() {
+        let dto: CfgMonitorDto = toml::from_str(
+            r#"
+            uplink = true
+            event_log = "/var/log/tetra_mon.jsonl"
+            "#,
+        )
+        .unwrap();
+        let cfg = monitor_dto_to_cfg(dto).unwrap();
+        assert!(cfg.uplink);
+        assert_eq!(cfg.event_log.as_deref(), Some("/var/log/tetra_mon.jsonl"));
+    }
+
+    #[test]
+    fn test_monitor_dto_defaults() {
+        let dto: CfgMonitorDto = toml::from_str("").unwrap();
+        let cfg = monitor_dto_to_cfg(dto).unwrap();
+        assert!(!cfg.uplink);
+        assert!(cfg.event_log.is_none());
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Mobile station configuration, used when running in MS stack mode
+#[derive(Debug, Clone)]
+pub struct CfgMs {
+    /// Individual short subscriber identity of this MS. Together with the net_info MCC/MNC this forms the ITSI
+    pub issi: u32,
+    /// Group identities attached to when registering
+    pub groups: Vec<u32>,
+    /// Read user commands (SDS, group calls) from stdin
+    pub console: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CfgMsDto {
+    pub issi: u32,
+    #[serde(default)]
+    pub groups: Vec<u32>,
+    #[serde(default = "default_console")]
+    pub console: bool,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_console() -> bool {
+    true
+}
+
+/// Largest value a 24-bit SSI can take, 0xFFFFFF is reserved as the broadcast address
+const SSI_MAX: u32 = 0xFFFFFE;
+
+/// Convert a CfgMsDto (from TOML) into a CfgMs (used in the stack config)
+pub fn ms_dto_to_cfg(src: CfgMsDto) -> Result<CfgMs, String> {
+    if src.issi == 0 || src.issi > SSI_MAX {
+        return Err(format!("Invalid ms.issi {}: must be between 1 and {}", src.issi, SSI_MAX));
+    }
+    if let Some(gssi) = src.groups.iter().find(|&&g| g == 0 || g > SSI_MAX) {
+        return Err(format!("Invalid group {} in ms.groups: must be between 1 and {}", gssi, SSI_MAX));
+    }
+    Ok(CfgMs {
+        issi: src.issi,
+        groups: src.groups,
+        console: src.console,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ms_dto_to_cfg() {
+        let dto: CfgMsDto = toml::from_str(
+            r#"
+            issi = 1000001
+            groups = [91, 92]
+            "#,
+        )
+        .unwrap();
+        let cfg = ms_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.issi, 1000001);
+        assert_eq!(cfg.groups, vec![91, 92]);
+        assert!(cfg.console);
+    }
+
+    #[test]
+    fn test_ms_dto_rejects_broadcast_issi() {
+        let dto: CfgMsDto = toml::from_str("issi = 16777215").unwrap();
+        assert!(ms_dto_to_cfg(dto).is_err());
+    }
+}
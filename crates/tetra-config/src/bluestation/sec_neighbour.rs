@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tetra_core::freqs::FreqInfo;
+use toml::Value;
+
+/// At most 7 neighbour cells fit the 3-bit count in D-NWRK-BROADCAST
+pub const MAX_NEIGHBOUR_CELLS: usize = 7;
+
+/// A neighbour cell announced to MSs in D-NWRK-BROADCAST, typically another BlueStation site
+#[derive(Debug, Clone)]
+pub struct CfgNeighbourCell {
+    /// Cell identifier (0-31), unique among the neighbour cells of this cell
+    pub cell_id: u8,
+    /// 12 bits, main carrier number of the neighbour cell
+    pub main_carrier: u16,
+    /// Frequency band, offset, duplex spacing and reverse operation of the neighbour cell.
+    /// Only sent when set; the serving cell values are used for the ones left unset.
+    pub freq_band: Option<u8>,
+    pub freq_offset_hz: Option<i16>,
+    pub duplex_spacing_id: Option<u8>,
+    pub reverse_operation: Option<bool>,
+    /// 14 bits
+    pub location_area: u16,
+    /// Network of the neighbour cell, only needed when it differs from ours
+    pub mcc: Option<u16>,
+    pub mnc: Option<u16>,
+    /// 2 bits, cell reselection types supported (clause 18.5.6)
+    pub reselection_types_supported: u8,
+    /// Whether the neighbour cell is synchronized with this cell
+    pub synchronized: bool,
+    /// 2 bits, cell service level (clause 18.5.7), 0 if unknown
+    pub service_level: u8,
+    /// 3 bits, maximum MS transmit power on the neighbour cell, if it differs from ours
+    pub max_ms_tx_power: Option<u8>,
+    /// 4 bits, minimum RX access level on the neighbour cell, if it differs from ours
+    pub min_rx_access_level: Option<u8>,
+}
+
+impl CfgNeighbourCell {
+    /// Whether the main carrier number extension is needed to describe the neighbour carrier
+    pub fn has_carrier_extension(&self) -> bool {
+        self.freq_band.is_some() || self.freq_offset_hz.is_some() || self.duplex_spacing_id.is_some() || self.reverse_operation.is_some()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CfgNeighbourCellDto {
+    pub cell_id: u8,
+    pub main_carrier: u16,
+    pub freq_band: Option<u8>,
+    pub freq_offset: Option<i16>,
+    pub duplex_spacing: Option<u8>,
+    pub reverse_operation: Option<bool>,
+    pub location_area: u16,
+    pub mcc: Option<u16>,
+    pub mnc: Option<u16>,
+    #[serde(default = "default_reselection_types_supported")]
+    pub reselection_types_supported: u8,
+    #[serde(default)]
+    pub synchronized: bool,
+    #[serde(default)]
+    pub service_level: u8,
+    pub max_ms_tx_power: Option<u8>,
+    pub min_rx_access_level: Option<u8>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_reselection_types_supported() -> u8 {
+    1
+}
+
+/// Convert the [[neighbour_cells]] entries (from TOML) into CfgNeighbourCells (used in the stack config)
+pub fn neighbour_cells_dto_to_cfg(src: Vec<CfgNeighbourCellDto>) -> Result<Vec<CfgNeighbourCell>, String> {
+    if src.len() > MAX_NEIGHBOUR_CELLS {
+        return Err(format!(
+            "Too many neighbour_cells: {}, at most {} can be broadcast",
+            src.len(),
+            MAX_NEIGHBOUR_CELLS
+        ));
+    }
+
+    let mut cells: Vec<CfgNeighbourCell> = Vec::with_capacity(src.len());
+    for dto in src {
+        if !dto.extra.is_empty() {
+            let mut keys: Vec<&str> = dto.extra.keys().map(|s| s.as_str()).collect();
+            keys.sort_unstable();
+            return Err(format!("Unrecognized fields in neighbour_cells: {:?}", keys));
+        }
+        if dto.cell_id > 31 {
+            return Err(format!("Invalid neighbour_cells.cell_id {}: expected 0-31", dto.cell_id));
+        }
+        if cells.iter().any(|c| c.cell_id == dto.cell_id) {
+            return Err(format!("Duplicate neighbour_cells.cell_id {}", dto.cell_id));
+        }
+        if dto.main_carrier > 0xFFF {
+            return Err(format!(
+                "Invalid neighbour_cells.main_carrier {}: expected 0-4095",
+                dto.main_carrier
+            ));
+        }
+        if dto.freq_band.is_some_and(|band| band > 15) {
+            return Err(format!("Invalid neighbour_cells.freq_band for cell {}: expected 0-15", dto.cell_id));
+        }
+        if dto
+            .freq_offset
+            .is_some_and(|offset| FreqInfo::freq_offset_hz_to_id(offset).is_none())
+        {
+            return Err(format!(
+                "Invalid neighbour_cells.freq_offset for cell {}: expected 0, 6250, -6250 or 12500",
+                dto.cell_id
+            ));
+        }
+        if dto.duplex_spacing.is_some_and(|spacing| spacing > 7) {
+            return Err(format!(
+                "Invalid neighbour_cells.duplex_spacing for cell {}: expected 0-7",
+                dto.cell_id
+            ));
+        }
+        if dto.location_area > 0x3FFF {
+            return Err(format!(
+                "Invalid neighbour_cells.location_area for cell {}: expected 0-16383",
+                dto.cell_id
+            ));
+        }
+        if dto.mcc.is_some_and(|mcc| mcc > 0x3FF) || dto.mnc.is_some_and(|mnc| mnc > 0x3FFF) {
+            return Err(format!("Invalid neighbour_cells.mcc or mnc for cell {}", dto.cell_id));
+        }
+        if dto.reselection_types_supported > 3 || dto.service_level > 3 {
+            return Err(format!(
+                "Invalid neighbour_cells.reselection_types_supported or service_level for cell {}: expected 0-3",
+                dto.cell_id
+            ));
+        }
+        if dto.max_ms_tx_power.is_some_and(|p| p > 7) || dto.min_rx_access_level.is_some_and(|l| l > 15) {
+            return Err(format!(
+                "Invalid neighbour_cells.max_ms_tx_power or min_rx_access_level for cell {}",
+                dto.cell_id
+            ));
+        }
+
+        cells.push(CfgNeighbourCell {
+            cell_id: dto.cell_id,
+            main_carrier: dto.main_carrier,
+            freq_band: dto.freq_band,
+            freq_offset_hz: dto.freq_offset,
+            duplex_spacing_id: dto.duplex_spacing,
+            reverse_operation: dto.reverse_operation,
+            location_area: dto.location_area,
+            mcc: dto.mcc,
+            mnc: dto.mnc,
+            reselection_types_supported: dto.reselection_types_supported,
+            synchronized: dto.synchronized,
+            service_level: dto.service_level,
+            max_ms_tx_power: dto.max_ms_tx_power,
+            min_rx_access_level: dto.min_rx_access_level,
+        });
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Root {
+        neighbour_cells: Vec<CfgNeighbourCellDto>,
+    }
+
+    #[test]
+    fn test_neighbour_cells_dto_to_cfg() {
+        let root: Root = toml::from_str(
+            r#"
+            [[neighbour_cells]]
+            cell_id = 1
+            main_carrier = 1521
+            location_area = 2
+
+            [[neighbour_cells]]
+            cell_id = 2
+            main_carrier = 1530
+            freq_offset = 6250
+            location_area = 3
+            synchronized = true
+            "#,
+        )
+        .unwrap();
+        let cells = neighbour_cells_dto_to_cfg(root.neighbour_cells).unwrap();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].main_carrier, 1521);
+        assert_eq!(cells[0].reselection_types_supported, 1);
+        assert!(!cells[0].has_carrier_extension());
+        assert!(cells[1].synchronized);
+        assert!(cells[1].has_carrier_extension());
+    }
+
+    #[test]
+    fn test_neighbour_cells_duplicate_id() {
+        let root: Root = toml::from_str(
+            r#"
+            [[neighbour_cells]]
+            cell_id = 1
+            main_carrier = 1521
+            location_area = 2
+
+            [[neighbour_cells]]
+            cell_id = 1
+            main_carrier = 1530
+            location_area = 3
+            "#,
+        )
+        .unwrap();
+        assert!(neighbour_cells_dto_to_cfg(root.neighbour_cells).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use serde::Deserialize;
 use toml::Value;
 
-use crate::bluestation::{CfgSoapySdr, SoapySdrDto};
+use crate::bluestation::{CfgFileIo, CfgFileIoDto, CfgSoapySdr, SoapySdrDto, file_dto_to_cfg};
 
 /// The PHY layer backend type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
@@ -12,6 +12,7 @@ pub enum PhyBackend {
     Undefined,
     None,
     SoapySdr,
+    File,
 }
 
 /// PHY layer I/O configuration
@@ -27,6 +28,8 @@ pub struct CfgPhyIo {
 
     /// For Soapysdr backend: SoapySDR configuration
     pub soapysdr: Option<CfgSoapySdr>,
+    /// For File backend: IQ file configuration
+    pub file: Option<CfgFileIo>,
 }
 
 #[derive(Deserialize)]
@@ -39,12 +42,13 @@ pub struct PhyIoDto {
     pub dl_input_file: Option<String>,
 
     pub soapysdr: Option<SoapySdrDto>,
+    pub file: Option<CfgFileIoDto>,
 
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
-pub fn phy_dto_to_cfg(src: PhyIoDto) -> CfgPhyIo {
+pub fn phy_dto_to_cfg(src: PhyIoDto) -> Result<CfgPhyIo, String> {
     let soapysdr = src.soapysdr.map(|soapy_dto| {
         CfgSoapySdr {
             ul_freq: soapy_dto.rx_freq,
@@ -93,12 +97,15 @@ pub fn phy_dto_to_cfg(src: PhyIoDto) -> CfgPhyIo {
         }
     });
 
-    CfgPhyIo {
+    let file = src.file.map(file_dto_to_cfg).transpose()?;
+
+    Ok(CfgPhyIo {
         backend: src.backend,
         dl_tx_file: src.dl_tx_file,
         ul_rx_file: src.ul_rx_file,
         ul_input_file: src.ul_input_file,
         dl_input_file: src.dl_input_file,
         soapysdr,
-    }
+        file,
+    })
 }
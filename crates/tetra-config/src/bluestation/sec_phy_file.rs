@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Sample format of IQ files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IqFormat {
+    /// Interleaved little-endian 32-bit floats
+    #[default]
+    Cf32,
+    /// Interleaved little-endian 16-bit signed integers, full scale 32768
+    Cs16,
+}
+
+impl IqFormat {
+    /// Size of a single complex sample in bytes
+    pub fn sample_size(&self) -> usize {
+        match self {
+            IqFormat::Cf32 => 8,
+            IqFormat::Cs16 => 4,
+        }
+    }
+}
+
+/// File backend configuration: replays an IQ capture instead of receiving from an SDR,
+/// and writes the transmitted signal to a file. Time advances with the samples read.
+#[derive(Debug, Clone)]
+pub struct CfgFileIo {
+    /// IQ capture to receive from: uplink for a BS, downlink for an MS or monitor.
+    /// If not set, silence is received
+    pub rx_file: Option<String>,
+    /// File the transmitted IQ signal is written to. Nothing is transmitted if not set
+    pub tx_file: Option<String>,
+    /// Sample format of both files
+    pub format: IqFormat,
+    /// Sample rate of both files in Hz
+    pub sample_rate: f64,
+    /// Center frequency of the RX capture in Hz. Defaults to the received carrier
+    pub rx_center_freq: Option<f64>,
+    /// Center frequency of the TX output in Hz. Defaults to the transmitted carrier
+    pub tx_center_freq: Option<f64>,
+    /// Restart the RX capture from the beginning when it ends, instead of stopping the stack
+    pub repeat: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CfgFileIoDto {
+    pub rx_file: Option<String>,
+    pub tx_file: Option<String>,
+    #[serde(default)]
+    pub format: IqFormat,
+    pub sample_rate: f64,
+    pub rx_center_freq: Option<f64>,
+    pub tx_center_freq: Option<f64>,
+    #[serde(default)]
+    pub repeat: bool,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// The demodulator runs at 72 kHz, which has to fit within the file bandwidth along with a 25 kHz channel
+const MIN_SAMPLE_RATE: f64 = 100_000.0;
+
+/// Convert a CfgFileIoDto (from TOML) into a CfgFileIo (used in the stack config)
+pub fn file_dto_to_cfg(src: CfgFileIoDto) -> Result<CfgFileIo, String> {
+    if !src.sample_rate.is_finite() || src.sample_rate < MIN_SAMPLE_RATE {
+        return Err(format!(
+            "Invalid phy_io.file.sample_rate {}: must be at least {} Hz",
+            src.sample_rate, MIN_SAMPLE_RATE
+        ));
+    }
+    if src.rx_file.is_none() && src.tx_file.is_none() {
+        return Err("phy_io.file requires at least one of rx_file and tx_file".to_string());
+    }
+    Ok(CfgFileIo {
+        rx_file: src.rx_file,
+        tx_file: src.tx_file,
+        format: src.format,
+        sample_rate: src.sample_rate,
+        rx_center_freq: src.rx_center_freq,
+        tx_center_freq: src.tx_center_freq,
+        repeat: src.repeat,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_dto_to_cfg() {
+        let dto: CfgFileIoDto = toml::from_str(
+            r#"
+            rx_file = "ul_capture.cs16"
+            format = "cs16"
+            sample_rate = 1000000
+            rx_center_freq = 433000000
+            "#,
+        )
+        .unwrap();
+        let cfg = file_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.rx_file.as_deref(), Some("ul_capture.cs16"));
+        assert!(cfg.tx_file.is_none());
+        assert_eq!(cfg.format, IqFormat::Cs16);
+        assert_eq!(cfg.sample_rate, 1e6);
+        assert_eq!(cfg.rx_center_freq, Some(433e6));
+        assert!(!cfg.repeat);
+    }
+
+    #[test]
+    fn test_file_dto_rejects_low_sample_rate() {
+        let dto: CfgFileIoDto = toml::from_str(
+            r#"
+            tx_file = "dl.cf32"
+            sample_rate = 36000
+            "#,
+        )
+        .unwrap();
+        assert!(file_dto_to_cfg(dto).is_err());
+    }
+}
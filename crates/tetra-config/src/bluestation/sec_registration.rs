@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Persistence of MS registrations and group affiliations across restarts
+#[derive(Debug, Clone)]
+pub struct CfgRegistrationStore {
+    /// State file registrations are saved to and restored from at startup
+    pub file: String,
+    /// Send D-LOCATION UPDATE COMMAND to every restored MS at startup, so it re-registers with a full group report
+    pub request_reregistration: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CfgRegistrationStoreDto {
+    pub file: String,
+    #[serde(default)]
+    pub request_reregistration: bool,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Convert a CfgRegistrationStoreDto (from TOML) into a CfgRegistrationStore (used in the stack config)
+pub fn registration_store_dto_to_cfg(src: CfgRegistrationStoreDto) -> Result<CfgRegistrationStore, String> {
+    if src.file.trim().is_empty() {
+        return Err("Invalid registration_store.file: path must not be empty".to_string());
+    }
+    Ok(CfgRegistrationStore {
+        file: src.file,
+        request_reregistration: src.request_reregistration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_store_dto_to_cfg() {
+        let dto: CfgRegistrationStoreDto = toml::from_str(
+            r#"
+            file = "/var/lib/bluestation/registrations.json"
+            request_reregistration = true
+            "#,
+        )
+        .unwrap();
+        let cfg = registration_store_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.file, "/var/lib/bluestation/registrations.json");
+        assert!(cfg.request_reregistration);
+    }
+
+    #[test]
+    fn test_registration_store_dto_invalid() {
+        let dto: CfgRegistrationStoreDto = toml::from_str("file = \" \"").unwrap();
+        assert!(registration_store_dto_to_cfg(dto).is_err());
+
+        // The state file is mandatory
+        assert!(toml::from_str::<CfgRegistrationStoreDto>("request_reregistration = true").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Longest validity period accepted. Stored messages are timed on the TDMA clock, whose differences
+/// wrap after about 23 days.
+const MAX_VALIDITY_PERIOD_SECS: u64 = 7 * 24 * 3600;
+
+/// Store-and-forward of SDS for subscribers that cannot be reached
+#[derive(Debug, Clone)]
+pub struct CfgSdsStore {
+    /// How long an undelivered message is kept before it expires
+    pub validity_period: Duration,
+    /// Most messages kept for a single ISSI. Further messages for it are refused
+    pub max_messages_per_issi: usize,
+}
+
+#[derive(Deserialize)]
+pub struct CfgSdsStoreDto {
+    #[serde(default = "default_sds_store_validity_period")]
+    pub validity_period_secs: u64,
+    #[serde(default = "default_sds_store_max_messages_per_issi")]
+    pub max_messages_per_issi: usize,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_sds_store_validity_period() -> u64 {
+    24 * 3600
+}
+
+fn default_sds_store_max_messages_per_issi() -> usize {
+    32
+}
+
+/// Convert a CfgSdsStoreDto (from TOML) into a CfgSdsStore (used in the stack config)
+pub fn sds_store_dto_to_cfg(src: CfgSdsStoreDto) -> Result<CfgSdsStore, String> {
+    if src.validity_period_secs == 0 || src.validity_period_secs > MAX_VALIDITY_PERIOD_SECS {
+        return Err(format!(
+            "Invalid sds_store.validity_period_secs {}: must be between 1 and {}",
+            src.validity_period_secs, MAX_VALIDITY_PERIOD_SECS
+        ));
+    }
+    if src.max_messages_per_issi == 0 {
+        return Err("Invalid sds_store.max_messages_per_issi: must be at least 1".to_string());
+    }
+    Ok(CfgSdsStore {
+        validity_period: Duration::from_secs(src.validity_period_secs),
+        max_messages_per_issi: src.max_messages_per_issi,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sds_store_dto_to_cfg() {
+        let dto: CfgSdsStoreDto = toml::from_str("validity_period_secs = 3600").unwrap();
+        let cfg = sds_store_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.validity_period, Duration::from_secs(3600));
+        assert_eq!(cfg.max_messages_per_issi, 32);
+    }
+
+    #[test]
+    fn test_sds_store_dto_invalid() {
+        let dto: CfgSdsStoreDto = toml::from_str("validity_period_secs = 0").unwrap();
+        assert!(sds_store_dto_to_cfg(dto).is_err());
+
+        let dto: CfgSdsStoreDto = toml::from_str("validity_period_secs = 2592000").unwrap();
+        assert!(sds_store_dto_to_cfg(dto).is_err());
+
+        let dto: CfgSdsStoreDto = toml::from_str("max_messages_per_issi = 0").unwrap();
+        assert!(sds_store_dto_to_cfg(dto).is_err());
+    }
+}
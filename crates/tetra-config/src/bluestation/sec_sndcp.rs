@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// SNDCP packet data configuration
+#[derive(Debug, Clone)]
+pub struct CfgSndcp {
+    /// First IPv4 address handed out to MSs activating a PDP context
+    pub ip_pool_start: Ipv4Addr,
+    /// Last IPv4 address handed out to MSs activating a PDP context (inclusive)
+    pub ip_pool_end: Ipv4Addr,
+    /// Maximum IP packet size offered to MSs, in bytes
+    pub mtu: u16,
+    /// TUN interface MS packet data is exchanged through. No packet gateway is started if unset
+    pub tun_device: Option<String>,
+}
+
+impl CfgSndcp {
+    /// Number of addresses in the pool
+    pub fn ip_pool_size(&self) -> u32 {
+        u32::from(self.ip_pool_end) - u32::from(self.ip_pool_start) + 1
+    }
+
+    pub fn ip_pool_contains(&self, addr: Ipv4Addr) -> bool {
+        (self.ip_pool_start..=self.ip_pool_end).contains(&addr)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CfgSndcpDto {
+    pub ip_pool_start: Ipv4Addr,
+    pub ip_pool_end: Ipv4Addr,
+    #[serde(default = "default_mtu")]
+    pub mtu: u16,
+    pub tun_device: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn default_mtu() -> u16 {
+    1006
+}
+
+/// Convert a CfgSndcpDto (from TOML) into a CfgSndcp (used in the stack config)
+pub fn sndcp_dto_to_cfg(src: CfgSndcpDto) -> Result<CfgSndcp, String> {
+    if src.ip_pool_end < src.ip_pool_start {
+        return Err(format!(
+            "Invalid sndcp ip pool: ip_pool_end {} lies before ip_pool_start {}",
+            src.ip_pool_end, src.ip_pool_start
+        ));
+    }
+    if src.mtu < 296 {
+        return Err(format!("Invalid sndcp.mtu {}: must be at least 296", src.mtu));
+    }
+    Ok(CfgSndcp {
+        ip_pool_start: src.ip_pool_start,
+        ip_pool_end: src.ip_pool_end,
+        mtu: src.mtu,
+        tun_device: src.tun_device,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sndcp_dto_to_cfg() {
+        let dto: CfgSndcpDto = toml::from_str(
+            r#"
+            ip_pool_start = "10.0.0.10"
+            ip_pool_end = "10.0.0.19"
+            "#,
+        )
+        .unwrap();
+        let cfg = sndcp_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.ip_pool_size(), 10);
+        assert_eq!(cfg.mtu, 1006);
+        assert!(cfg.ip_pool_contains(Ipv4Addr::new(10, 0, 0, 19)));
+        assert!(!cfg.ip_pool_contains(Ipv4Addr::new(10, 0, 0, 20)));
+        assert!(cfg.tun_device.is_none());
+    }
+
+    #[test]
+    fn test_sndcp_dto_rejects_inverted_pool() {
+        let dto: CfgSndcpDto = toml::from_str(
+            r#"
+            ip_pool_start = "10.0.0.10"
+            ip_pool_end = "10.0.0.1"
+            "#,
+        )
+        .unwrap();
+        assert!(sndcp_dto_to_cfg(dto).is_err());
+    }
+}
@@ -8,13 +8,24 @@ pub struct Subscriber {
     pub attached_groups: HashSet<u32>,
 }
 
+/// A subscriber registered at another site of the network, learned over the inter-site backhaul
+#[derive(Debug, Clone)]
+pub struct RemoteSubscriber {
+    pub site_id: u16,
+    // Set of attached GSSIs
+    pub attached_groups: HashSet<u32>,
+}
+
 /// Centralized subscriber registry tracking locally registered ISSIs and their group affiliations.
+/// Subscribers registered at the other sites of the network are tracked separately, see `register_remote`.
 #[derive(Debug, Clone)]
 pub struct SubscriberRegistry {
     /// Registered ISSIs → Subscriber information
     subscribers: HashMap<u32, Subscriber>,
     /// Set of all GSSIs with at least one local affiliate
     all_attached_groups: HashSet<u32>,
+    /// ISSIs registered at other sites → site and group information
+    remote_subscribers: HashMap<u32, RemoteSubscriber>,
 }
 
 impl SubscriberRegistry {
@@ -22,6 +33,7 @@ impl SubscriberRegistry {
         Self {
             subscribers: HashMap::new(),
             all_attached_groups: HashSet::new(),
+            remote_subscribers: HashMap::new(),
         }
     }
 
@@ -86,6 +98,102 @@ impl SubscriberRegistry {
     pub fn has_group_members(&self, gssi: u32) -> bool {
         self.all_attached_groups.contains(&gssi)
     }
+
+    /// Iterate over the locally registered subscribers
+    pub fn iter(&self) -> impl Iterator<Item = &Subscriber> {
+        self.subscribers.values()
+    }
+
+    /// Record an ISSI as registered at another site. Any earlier registration at another site is replaced.
+    pub fn register_remote(&mut self, issi: u32, site_id: u16) {
+        self.remote_subscribers.insert(
+            issi,
+            RemoteSubscriber {
+                site_id,
+                attached_groups: HashSet::new(),
+            },
+        );
+    }
+
+    /// Forget the registration of an ISSI at the given site. Ignored if the ISSI has since registered elsewhere.
+    pub fn deregister_remote(&mut self, issi: u32, site_id: u16) {
+        if self.remote_site(issi) == Some(site_id) {
+            self.remote_subscribers.remove(&issi);
+        }
+    }
+
+    /// Add GSSI to the attached groups of an ISSI registered at the given site
+    pub fn affiliate_remote(&mut self, issi: u32, site_id: u16, gssi: u32) {
+        if self.remote_site(issi) != Some(site_id) {
+            self.register_remote(issi, site_id);
+        }
+        if let Some(subscriber) = self.remote_subscribers.get_mut(&issi) {
+            subscriber.attached_groups.insert(gssi);
+        }
+    }
+
+    /// Remove GSSI from the attached groups of an ISSI registered at the given site
+    pub fn deaffiliate_remote(&mut self, issi: u32, site_id: u16, gssi: u32) {
+        if let Some(subscriber) = self.remote_subscribers.get_mut(&issi)
+            && subscriber.site_id == site_id
+        {
+            subscriber.attached_groups.remove(&gssi);
+        }
+    }
+
+    /// Forget all subscribers registered at the given site, e.g. when it becomes unreachable
+    pub fn forget_site(&mut self, site_id: u16) {
+        self.remote_subscribers.retain(|_, s| s.site_id != site_id);
+    }
+
+    /// Site at which an ISSI is registered, if it is registered at another site
+    pub fn remote_site(&self, issi: u32) -> Option<u16> {
+        self.remote_subscribers.get(&issi).map(|s| s.site_id)
+    }
+
+    /// Other sites with at least one subscriber affiliated with the given GSSI, in ascending order
+    pub fn remote_sites_with_group(&self, gssi: u32) -> Vec<u16> {
+        let mut sites: Vec<u16> = self
+            .remote_subscribers
+            .values()
+            .filter(|s| s.attached_groups.contains(&gssi))
+            .map(|s| s.site_id)
+            .collect();
+        sites.sort_unstable();
+        sites.dedup();
+        sites
+    }
+}
+
+/// Condition under which calls to a subscriber are forwarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForwardingCondition {
+    Unconditional,
+    Busy,
+    NotReachable,
+}
+
+/// Call forwarding set up by subscribers through the call forwarding supplementary services.
+/// Kept apart from the registrations, as forwarding also applies while a subscriber is not registered.
+#[derive(Debug, Clone, Default)]
+pub struct CallForwardingTable {
+    /// (Served ISSI, condition) → ISSI calls are forwarded to
+    entries: HashMap<(u32, ForwardingCondition), u32>,
+}
+
+impl CallForwardingTable {
+    pub fn activate(&mut self, issi: u32, condition: ForwardingCondition, forwarded_to: u32) {
+        self.entries.insert((issi, condition), forwarded_to);
+    }
+
+    pub fn deactivate(&mut self, issi: u32, condition: ForwardingCondition) {
+        self.entries.remove(&(issi, condition));
+    }
+
+    /// ISSI calls to the given ISSI are forwarded to under the given condition, if any
+    pub fn forwarded_to(&self, issi: u32, condition: ForwardingCondition) -> Option<u32> {
+        self.entries.get(&(issi, condition)).copied()
+    }
 }
 
 /// Mutable, stack-editable state (mutex-protected).
@@ -96,6 +204,8 @@ pub struct StackState {
     pub network_connected: bool,
     /// Centralized subscriber registry for local-first routing decisions.
     pub subscribers: SubscriberRegistry,
+    /// Call forwarding set up by subscribers, applied by CMCE to individual calls
+    pub call_forwarding: CallForwardingTable,
 }
 
 #[cfg(test)]
@@ -152,6 +262,41 @@ mod tests {
         assert!(!reg.has_group_members(999));
     }
 
+    #[test]
+    fn test_remote_subscribers() {
+        let mut reg = SubscriberRegistry::new();
+        reg.register_remote(1001, 2);
+        reg.affiliate_remote(1001, 2, 91);
+        reg.affiliate_remote(1002, 3, 91);
+        assert_eq!(reg.remote_site(1001), Some(2));
+        assert_eq!(reg.remote_sites_with_group(91), vec![2, 3]);
+        assert!(!reg.is_registered(1001));
+        assert!(!reg.has_group_members(91));
+
+        // Roamed to site 3, a late deregistration from site 2 must not remove it
+        reg.register_remote(1001, 3);
+        reg.deregister_remote(1001, 2);
+        assert_eq!(reg.remote_site(1001), Some(3));
+        assert_eq!(reg.remote_sites_with_group(91), vec![3]);
+
+        reg.forget_site(3);
+        assert_eq!(reg.remote_site(1001), None);
+        assert!(reg.remote_sites_with_group(91).is_empty());
+    }
+
+    #[test]
+    fn test_call_forwarding() {
+        let mut table = CallForwardingTable::default();
+        table.activate(1001, ForwardingCondition::Busy, 1002);
+        assert_eq!(table.forwarded_to(1001, ForwardingCondition::Busy), Some(1002));
+        assert_eq!(table.forwarded_to(1001, ForwardingCondition::Unconditional), None);
+
+        table.activate(1001, ForwardingCondition::Busy, 1003);
+        assert_eq!(table.forwarded_to(1001, ForwardingCondition::Busy), Some(1003));
+        table.deactivate(1001, ForwardingCondition::Busy);
+        assert_eq!(table.forwarded_to(1001, ForwardingCondition::Busy), None);
+    }
+
     #[test]
     fn test_register_overwrites_existing_subscriber() {
         let mut reg = SubscriberRegistry::new();
@@ -173,6 +318,7 @@ impl Default for StackState {
             timeslot_alloc: TimeslotAllocator::default(),
             network_connected: false,
             subscribers: SubscriberRegistry::new(),
+            call_forwarding: CallForwardingTable::default(),
         }
     }
 }
@@ -0,0 +1,161 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Authorization record of a single subscriber
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberEntry {
+    /// GSSIs this subscriber may attach to. None allows all groups of the network
+    pub groups: Option<HashSet<u32>>,
+    /// Subscriber class membership bitmap (ETSI 16.10.44). None places the subscriber in all classes
+    pub class: Option<u16>,
+    /// Registration barred, the subscriber is refused on location updating
+    pub barred: bool,
+}
+
+/// Subscriber and group authorization database, loaded from a separate TOML file.
+/// Only listed ISSIs may register, and only listed GSSIs exist on the network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriberDb {
+    pub groups: HashSet<u32>,
+    pub subscribers: HashMap<u32, SubscriberEntry>,
+}
+
+impl SubscriberDb {
+    pub fn from_toml_str(toml_str: &str) -> Result<Self, String> {
+        let dto: SubscriberDbDto = toml::from_str(toml_str).map_err(|e| e.to_string())?;
+        subscriber_db_dto_to_db(dto)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_toml_str(&contents).map_err(|e| format!("Invalid subscriber database {}: {}", path.display(), e))
+    }
+
+    pub fn subscriber(&self, issi: u32) -> Option<&SubscriberEntry> {
+        self.subscribers.get(&issi)
+    }
+
+    pub fn is_group(&self, gssi: u32) -> bool {
+        self.groups.contains(&gssi)
+    }
+
+    /// Returns true if the subscriber is known and permitted to attach to the given group
+    pub fn may_attach(&self, issi: u32, gssi: u32) -> bool {
+        self.subscriber(issi)
+            .is_some_and(|entry| entry.groups.as_ref().is_none_or(|groups| groups.contains(&gssi)))
+    }
+}
+
+#[derive(Deserialize)]
+struct SubscriberDbDto {
+    /// GSSIs of the groups on this network
+    #[serde(default)]
+    groups: Vec<u32>,
+    /// Subscribers keyed by ISSI
+    #[serde(default)]
+    subscribers: HashMap<String, SubscriberEntryDto>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct SubscriberEntryDto {
+    groups: Option<Vec<u32>>,
+    class: Option<u16>,
+    #[serde(default)]
+    barred: bool,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+fn subscriber_db_dto_to_db(src: SubscriberDbDto) -> Result<SubscriberDb, String> {
+    if !src.extra.is_empty() {
+        let mut keys: Vec<&str> = src.extra.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        return Err(format!("Unrecognized top-level fields: {:?}", keys));
+    }
+
+    let groups: HashSet<u32> = src.groups.into_iter().collect();
+    let mut subscribers = HashMap::with_capacity(src.subscribers.len());
+    for (issi_str, entry) in src.subscribers {
+        let issi = issi_str
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid ISSI in subscribers: {}", issi_str))?;
+        if !entry.extra.is_empty() {
+            let mut keys: Vec<&str> = entry.extra.keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            return Err(format!("Unrecognized fields for subscriber {}: {:?}", issi, keys));
+        }
+        if groups.contains(&issi) {
+            return Err(format!("ISSI {} is also listed as a group", issi));
+        }
+        if let Some(unknown) = entry.groups.iter().flatten().find(|gssi| !groups.contains(gssi)) {
+            return Err(format!("Subscriber {} lists unknown group {}", issi, unknown));
+        }
+        subscribers.insert(
+            issi,
+            SubscriberEntry {
+                groups: entry.groups.map(|groups| groups.into_iter().collect()),
+                class: entry.class,
+                barred: entry.barred,
+            },
+        );
+    }
+    Ok(SubscriberDb { groups, subscribers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriber_db_from_toml() {
+        let db = SubscriberDb::from_toml_str(
+            r#"
+            groups = [91, 92, 93]
+
+            [subscribers.1000001]
+            groups = [91, 92]
+            class = 0x0003
+
+            [subscribers.1000002]
+
+            [subscribers.1000003]
+            barred = true
+            "#,
+        )
+        .unwrap();
+
+        assert!(db.is_group(93));
+        assert!(!db.is_group(94));
+
+        let first = db.subscriber(1000001).unwrap();
+        assert_eq!(first.class, Some(3));
+        assert!(!first.barred);
+        assert!(db.may_attach(1000001, 91));
+        assert!(!db.may_attach(1000001, 93));
+
+        // Without a group list, all groups are permitted
+        assert!(db.may_attach(1000002, 93));
+        assert_eq!(db.subscriber(1000002).unwrap().class, None);
+
+        assert!(db.subscriber(1000003).unwrap().barred);
+        assert!(db.subscriber(1000004).is_none());
+        assert!(!db.may_attach(1000004, 91));
+    }
+
+    #[test]
+    fn test_subscriber_db_rejects_inconsistent_entries() {
+        assert!(SubscriberDb::from_toml_str("[subscribers.abc]").is_err());
+        assert!(SubscriberDb::from_toml_str("groups = [91]\n[subscribers.1000001]\ngroups = [92]").is_err());
+        assert!(SubscriberDb::from_toml_str("groups = [91]\n[subscribers.91]").is_err());
+        assert!(SubscriberDb::from_toml_str("[subscribers.1000001]\nbared = true").is_err());
+        assert!(SubscriberDb::from_toml_str("subscriber = []").is_err());
+    }
+}
@@ -37,6 +37,8 @@ pub enum Sap {
 
     /// MLE/SNDCP
     TlpdSap,
+    /// SNDCP/packet data user
+    SnSap,
 
     /// MM -> User
     TnmmSap,
@@ -23,4 +23,10 @@ pub enum TetraEntity {
 
     /// Brew protocol bridge (TetraPack/BrandMeister integration)
     Brew,
+
+    /// Packet data gateway, exchanges SNDCP IP traffic with an external network
+    PacketGateway,
+
+    /// Inter-site backhaul, links the BlueStation sites of a network
+    Backhaul,
 }
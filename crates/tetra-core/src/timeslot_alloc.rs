@@ -1,3 +1,28 @@
+/// Maximum number of carriers a cell may consist of, the main carrier included
+pub const MAX_CARRIERS: usize = 8;
+/// Number of traffic channel numbers available to a cell with MAX_CARRIERS carriers
+pub const MAX_TRAFFIC_CHANNELS: usize = 4 * MAX_CARRIERS;
+
+/// Traffic channels are numbered across the carriers of the cell, starting at 1: the four timeslots of the
+/// main carrier are channels 1-4, those of the first secondary carrier 5-8, etc. On the main carrier, the
+/// channel number thus equals the timeslot number.
+#[inline]
+pub const fn traffic_channel(carrier: u8, ts: u8) -> u8 {
+    carrier * 4 + ts
+}
+
+/// Carrier index of a traffic channel, 0 being the main carrier
+#[inline]
+pub const fn channel_carrier(chan: u8) -> u8 {
+    (chan - 1) / 4
+}
+
+/// Timeslot (1-4) of a traffic channel on its carrier
+#[inline]
+pub const fn channel_timeslot(chan: u8) -> u8 {
+    (chan - 1) % 4 + 1
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimeslotOwner {
     Brew,
@@ -21,29 +46,47 @@ pub enum TimeslotAllocErr {
     },
 }
 
+/// Allocates the traffic channels of the cell. Timeslots are given as traffic channel numbers, see
+/// traffic_channel(). TS1 of the main carrier carries the MCCH, all other timeslots may carry traffic.
 #[derive(Debug, Clone)]
 pub struct TimeslotAllocator {
-    // Index 0 = TS2, 1 = TS3, 2 = TS4
-    owners: [Option<TimeslotOwner>; 3],
+    // Index 0 = TS2 of the main carrier, 1 = TS3, 2 = TS4, 3 = TS1 of the first secondary carrier, ...
+    owners: Vec<Option<TimeslotOwner>>,
 }
 
 impl Default for TimeslotAllocator {
     fn default() -> Self {
-        Self {
-            owners: [None, None, None],
-        }
+        Self::new(1)
     }
 }
 
 impl TimeslotAllocator {
-    fn idx(ts: u8) -> Result<usize, TimeslotAllocErr> {
-        if (2..=4).contains(&ts) {
+    pub fn new(num_carriers: usize) -> Self {
+        assert!(
+            (1..=MAX_CARRIERS).contains(&num_carriers),
+            "TimeslotAllocator: invalid number of carriers {}",
+            num_carriers
+        );
+        Self {
+            owners: vec![None; num_carriers * 4 - 1],
+        }
+    }
+
+    /// Number of carriers whose timeslots are allocated
+    pub fn num_carriers(&self) -> usize {
+        (self.owners.len() + 1) / 4
+    }
+
+    fn idx(&self, ts: u8) -> Result<usize, TimeslotAllocErr> {
+        if (2..=self.owners.len() + 1).contains(&(ts as usize)) {
             Ok((ts - 2) as usize)
         } else {
             Err(TimeslotAllocErr::InvalidTimeslot(ts))
         }
     }
 
+    /// Allocates a free traffic channel. The main carrier is filled first, so MSs need not leave it
+    /// for as long as it has room.
     pub fn allocate_any(&mut self, owner: TimeslotOwner) -> Option<u8> {
         for (i, slot) in self.owners.iter_mut().enumerate() {
             if slot.is_none() {
@@ -55,7 +98,7 @@ impl TimeslotAllocator {
     }
 
     pub fn reserve(&mut self, owner: TimeslotOwner, ts: u8) -> Result<(), TimeslotAllocErr> {
-        let idx = Self::idx(ts)?;
+        let idx = self.idx(ts)?;
         match self.owners[idx] {
             None => {
                 self.owners[idx] = Some(owner);
@@ -66,7 +109,7 @@ impl TimeslotAllocator {
     }
 
     pub fn release(&mut self, owner: TimeslotOwner, ts: u8) -> Result<(), TimeslotAllocErr> {
-        let idx = Self::idx(ts)?;
+        let idx = self.idx(ts)?;
         match self.owners[idx] {
             None => Err(TimeslotAllocErr::NotAllocated { ts }),
             Some(existing) if existing != owner => Err(TimeslotAllocErr::OwnerMismatch {
@@ -82,10 +125,60 @@ impl TimeslotAllocator {
     }
 
     pub fn owner(&self, ts: u8) -> Option<TimeslotOwner> {
-        Self::idx(ts).ok().and_then(|idx| self.owners[idx])
+        self.idx(ts).ok().and_then(|idx| self.owners[idx])
     }
 
     pub fn is_free(&self, ts: u8) -> bool {
         self.owner(ts).is_none()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_numbering() {
+        assert_eq!(traffic_channel(0, 3), 3);
+        assert_eq!(traffic_channel(1, 1), 5);
+        assert_eq!(channel_carrier(4), 0);
+        assert_eq!(channel_carrier(5), 1);
+        assert_eq!(channel_timeslot(5), 1);
+        assert_eq!(channel_timeslot(12), 4);
+    }
+
+    #[test]
+    fn test_single_carrier() {
+        let mut alloc = TimeslotAllocator::default();
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), Some(2));
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), Some(3));
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Brew), Some(4));
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), None);
+        assert_eq!(alloc.reserve(TimeslotOwner::Cmce, 5), Err(TimeslotAllocErr::InvalidTimeslot(5)));
+        assert_eq!(alloc.reserve(TimeslotOwner::Cmce, 1), Err(TimeslotAllocErr::InvalidTimeslot(1)));
+    }
+
+    #[test]
+    fn test_secondary_carrier_after_main() {
+        let mut alloc = TimeslotAllocator::new(2);
+        assert_eq!(alloc.num_carriers(), 2);
+        for expected in [2, 3, 4, 5, 6, 7, 8] {
+            assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), Some(expected));
+        }
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), None);
+
+        // A released main carrier timeslot is preferred over nothing, and reused first
+        alloc.release(TimeslotOwner::Cmce, 3).unwrap();
+        alloc.release(TimeslotOwner::Cmce, 6).unwrap();
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), Some(3));
+        assert!(alloc.is_free(6));
+        assert_eq!(
+            alloc.release(TimeslotOwner::Brew, 5),
+            Err(TimeslotAllocErr::OwnerMismatch {
+                ts: 5,
+                owner: TimeslotOwner::Brew,
+                actual: TimeslotOwner::Cmce
+            })
+        );
+    }
+}
@@ -0,0 +1,585 @@
+//! Backhaul entity: shares registrations with the other sites and bridges group calls and SDS between them
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{Sap, TdmaTime, channel_timeslot, tetra_entities::TetraEntity};
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::sds::CmceSdsData;
+use tetra_saps::tmd::TmdCircuitDataReq;
+use tetra_saps::{SapMsg, SapMsgInner};
+use uuid::Uuid;
+
+use crate::umac::umac_bs::pack_ul_acelp_bits;
+use crate::{MessageQueue, TetraEntityTrait};
+
+use super::protocol::{SiteMessage, SiteSubscriber};
+use super::worker::{BackhaulCommand, BackhaulEvent, BackhaulWorker};
+
+/// Frames buffered before playout of a group call from another site starts
+const PLAYOUT_START_FRAMES: usize = 2;
+/// Maximum frames buffered per call before the oldest are dropped
+const PLAYOUT_MAX_FRAMES: usize = 12;
+
+/// Group call of another site, played out on a local circuit
+struct RemoteCall {
+    site_id: u16,
+    source_issi: u32,
+    dest_gssi: u32,
+    /// Traffic timeslot, None until CMCE has set up the call
+    ts: Option<u8>,
+    frames: VecDeque<Vec<u8>>,
+    playing: bool,
+}
+
+/// Local group call forwarded to the sites with members of the group
+struct ForwardedCall {
+    call: Uuid,
+    call_id: u16,
+    dest_gssi: u32,
+    sites: Vec<u16>,
+}
+
+pub struct BackhaulEntity {
+    config: SharedConfig,
+    site_id: u16,
+    dltime: TdmaTime,
+
+    /// Receive events from the worker thread
+    event_receiver: Receiver<BackhaulEvent>,
+    /// Send commands to the worker thread
+    command_sender: Sender<BackhaulCommand>,
+
+    /// Sites our connection is up to
+    connected_sites: HashSet<u16>,
+    /// Group calls of other sites, by call identifier
+    remote_calls: HashMap<Uuid, RemoteCall>,
+    /// Local group calls forwarded to other sites, by timeslot
+    forwarded_calls: HashMap<u8, ForwardedCall>,
+
+    /// Worker thread handle for graceful shutdown
+    worker_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl BackhaulEntity {
+    pub fn new(config: SharedConfig) -> Self {
+        let (event_sender, event_receiver) = unbounded::<BackhaulEvent>();
+        let (command_sender, command_receiver) = unbounded::<BackhaulCommand>();
+
+        let backhaul_config = config
+            .config()
+            .backhaul
+            .clone()
+            .expect("Backhaul entity requires a backhaul configuration");
+        let handle = thread::Builder::new()
+            .name("backhaul-worker".to_string())
+            .spawn(move || {
+                let mut worker = BackhaulWorker::new(backhaul_config, event_sender, command_receiver);
+                worker.run();
+            })
+            .expect("failed to spawn BackhaulWorker thread");
+
+        let mut entity = Self::with_link(config, event_receiver, command_sender);
+        entity.worker_handle = Some(handle);
+        entity
+    }
+
+    /// Create the entity on an existing link instead of spawning a worker, e.g. to connect sites in-process.
+    /// Commands sent to the link are to be delivered as events to the other sites.
+    pub fn with_link(config: SharedConfig, event_receiver: Receiver<BackhaulEvent>, command_sender: Sender<BackhaulCommand>) -> Self {
+        let site_id = config
+            .config()
+            .backhaul
+            .as_ref()
+            .expect("Backhaul entity requires a backhaul configuration")
+            .site_id;
+        Self {
+            config,
+            site_id,
+            dltime: TdmaTime::default(),
+            event_receiver,
+            command_sender,
+            connected_sites: HashSet::new(),
+            remote_calls: HashMap::new(),
+            forwarded_calls: HashMap::new(),
+            worker_handle: None,
+        }
+    }
+
+    fn send(&self, site_id: u16, msg: SiteMessage) {
+        let _ = self.command_sender.send(BackhaulCommand::Send { site_id, msg });
+    }
+
+    fn send_to_all(&self, msg: SiteMessage) {
+        for site_id in &self.connected_sites {
+            self.send(*site_id, msg.clone());
+        }
+    }
+
+    /// Connected sites with at least one subscriber affiliated with the group
+    fn sites_with_group(&self, gssi: u32) -> Vec<u16> {
+        let mut sites = self.config.state_read().subscribers.remote_sites_with_group(gssi);
+        sites.retain(|site_id| self.connected_sites.contains(site_id));
+        sites
+    }
+
+    /// Returns true if the site is one of the configured peers
+    fn is_peer(&self, site_id: u16) -> bool {
+        self.config
+            .config()
+            .backhaul
+            .as_ref()
+            .is_some_and(|bh| bh.peers.iter().any(|peer| peer.site_id == site_id))
+    }
+
+    /// Process all pending events from the worker thread
+    fn process_events(&mut self, queue: &mut MessageQueue) {
+        while let Ok(event) = self.event_receiver.try_recv() {
+            match event {
+                BackhaulEvent::SiteConnected { site_id } => {
+                    tracing::info!("BackhaulEntity: site {} connected", site_id);
+                    self.connected_sites.insert(site_id);
+                    let subscribers = self
+                        .config
+                        .state_read()
+                        .subscribers
+                        .iter()
+                        .map(|s| SiteSubscriber {
+                            issi: s.issi,
+                            groups: s.attached_groups.iter().copied().collect(),
+                        })
+                        .collect();
+                    self.send(site_id, SiteMessage::Hello { subscribers });
+                }
+                BackhaulEvent::SiteDisconnected { site_id } => {
+                    tracing::warn!("BackhaulEntity: site {} disconnected", site_id);
+                    self.site_lost(queue, site_id);
+                }
+                BackhaulEvent::Received { site_id, msg } => {
+                    if !self.is_peer(site_id) {
+                        tracing::warn!("BackhaulEntity: dropping message from unknown site {}", site_id);
+                        continue;
+                    }
+                    self.rx_site_message(queue, site_id, msg);
+                }
+            }
+        }
+    }
+
+    /// Forget what we know about an unreachable site and end its calls
+    fn site_lost(&mut self, queue: &mut MessageQueue, site_id: u16) {
+        self.connected_sites.remove(&site_id);
+        self.config.state_write().subscribers.forget_site(site_id);
+
+        let calls: Vec<Uuid> = self
+            .remote_calls
+            .iter()
+            .filter(|(_, call)| call.site_id == site_id)
+            .map(|(uuid, _)| *uuid)
+            .collect();
+        for uuid in calls {
+            self.remote_calls.remove(&uuid);
+            self.send_cmce(queue, CallControl::NetworkCallEnd { brew_uuid: uuid });
+        }
+        for fwd in self.forwarded_calls.values_mut() {
+            fwd.sites.retain(|id| *id != site_id);
+        }
+    }
+
+    fn rx_site_message(&mut self, queue: &mut MessageQueue, site_id: u16, msg: SiteMessage) {
+        tracing::debug!("BackhaulEntity: <- site {} {:?}", site_id, msg);
+        match msg {
+            SiteMessage::Hello { subscribers } => {
+                let mut state = self.config.state_write();
+                state.subscribers.forget_site(site_id);
+                for subscriber in subscribers {
+                    state.subscribers.register_remote(subscriber.issi, site_id);
+                    for gssi in subscriber.groups {
+                        state.subscribers.affiliate_remote(subscriber.issi, site_id, gssi);
+                    }
+                }
+            }
+            SiteMessage::Keepalive => {}
+            SiteMessage::Register { issi } => {
+                let moved_here = self.config.state_read().subscribers.is_registered(issi);
+                if moved_here {
+                    // The MS roamed to the other site, drop our registration of it
+                    tracing::info!(
+                        "BackhaulEntity: ISSI {} registered at site {}, removing local registration",
+                        issi,
+                        site_id
+                    );
+                    queue.push_back(SapMsg {
+                        sap: Sap::Control,
+                        src: TetraEntity::Backhaul,
+                        dest: TetraEntity::Mm,
+                        dltime: self.dltime,
+                        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+                            issi,
+                            groups: Vec::new(),
+                            action: BrewSubscriberAction::Deregister,
+                        }),
+                    });
+                }
+                self.config.state_write().subscribers.register_remote(issi, site_id);
+            }
+            SiteMessage::Deregister { issi } => {
+                self.config.state_write().subscribers.deregister_remote(issi, site_id);
+            }
+            SiteMessage::Affiliate { issi, groups } => {
+                let mut state = self.config.state_write();
+                for gssi in groups {
+                    state.subscribers.affiliate_remote(issi, site_id, gssi);
+                }
+            }
+            SiteMessage::Deaffiliate { issi, groups } => {
+                let mut state = self.config.state_write();
+                for gssi in groups {
+                    state.subscribers.deaffiliate_remote(issi, site_id, gssi);
+                }
+            }
+            SiteMessage::GroupCallStart {
+                call,
+                source_issi,
+                dest_gssi,
+                priority,
+            } => {
+                self.rx_group_call_start(queue, site_id, Uuid::from_u128(call), source_issi, dest_gssi, priority);
+            }
+            SiteMessage::GroupCallEnd { call } => {
+                let uuid = Uuid::from_u128(call);
+                if let Some(remote) = self.remote_calls.remove(&uuid) {
+                    tracing::info!(
+                        "BackhaulEntity: group call from site {} ended uuid={} gssi={}",
+                        site_id,
+                        uuid,
+                        remote.dest_gssi
+                    );
+                    self.send_cmce(queue, CallControl::NetworkCallEnd { brew_uuid: uuid });
+                }
+            }
+            SiteMessage::VoiceFrame { call, data } => {
+                if let Some(remote) = self.remote_calls.get_mut(&Uuid::from_u128(call)) {
+                    remote.frames.push_back(data);
+                    while remote.frames.len() > PLAYOUT_MAX_FRAMES {
+                        remote.frames.pop_front();
+                    }
+                }
+            }
+            SiteMessage::Sds {
+                source_issi,
+                dest_ssi,
+                data,
+            } => {
+                let deliverable = {
+                    let state = self.config.state_read();
+                    state.subscribers.is_registered(dest_ssi) || state.subscribers.has_group_members(dest_ssi)
+                };
+                if !deliverable {
+                    tracing::warn!(
+                        "BackhaulEntity: SDS from site {} for SSI {} which is not local, dropping",
+                        site_id,
+                        dest_ssi
+                    );
+                    return;
+                }
+                // Schedule on next ts1 to ensure it gets sent on the MCCH
+                queue.push_back(SapMsg {
+                    sap: Sap::Control,
+                    src: TetraEntity::Backhaul,
+                    dest: TetraEntity::Cmce,
+                    dltime: self.dltime.forward_to_timeslot(1),
+                    msg: SapMsgInner::CmceSdsData(CmceSdsData {
+                        source_issi,
+                        dest_issi: dest_ssi,
+                        user_defined_data: data.into(),
+                    }),
+                });
+            }
+        }
+    }
+
+    fn rx_group_call_start(&mut self, queue: &mut MessageQueue, site_id: u16, uuid: Uuid, source_issi: u32, dest_gssi: u32, priority: u8) {
+        if let Some(remote) = self.remote_calls.get_mut(&uuid) {
+            if remote.source_issi == source_issi {
+                return;
+            }
+            tracing::info!(
+                "BackhaulEntity: speaker change on call from site {} uuid={} new_speaker={}",
+                site_id,
+                uuid,
+                source_issi
+            );
+            remote.source_issi = source_issi;
+        } else {
+            if !self.config.state_read().subscribers.has_group_members(dest_gssi) {
+                tracing::debug!(
+                    "BackhaulEntity: ignoring call from site {} to gssi={} (no local members)",
+                    site_id,
+                    dest_gssi
+                );
+                return;
+            }
+            tracing::info!(
+                "BackhaulEntity: group call from site {} uuid={} src={} gssi={}",
+                site_id,
+                uuid,
+                source_issi,
+                dest_gssi
+            );
+            self.remote_calls.insert(
+                uuid,
+                RemoteCall {
+                    site_id,
+                    source_issi,
+                    dest_gssi,
+                    ts: None,
+                    frames: VecDeque::new(),
+                    playing: false,
+                },
+            );
+        }
+        self.send_cmce(
+            queue,
+            CallControl::NetworkCallStart {
+                brew_uuid: uuid,
+                source_issi,
+                dest_gssi,
+                priority,
+            },
+        );
+    }
+
+    fn send_cmce(&self, queue: &mut MessageQueue, call_control: CallControl) {
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Backhaul,
+            dest: TetraEntity::Cmce,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(call_control),
+        });
+    }
+
+    /// Feed one buffered frame of each call of another site at its traffic playout opportunity
+    fn drain_playout(&mut self, queue: &mut MessageQueue) {
+        if self.dltime.f == 18 {
+            return;
+        }
+        for remote in self.remote_calls.values_mut() {
+            let Some(ts) = remote.ts.filter(|&ts| channel_timeslot(ts) == self.dltime.t) else {
+                continue;
+            };
+            if !remote.playing && remote.frames.len() < PLAYOUT_START_FRAMES {
+                continue;
+            }
+            match remote.frames.pop_front() {
+                Some(data) => {
+                    remote.playing = true;
+                    queue.push_back(SapMsg {
+                        sap: Sap::TmdSap,
+                        src: TetraEntity::Backhaul,
+                        dest: TetraEntity::Umac,
+                        dltime: self.dltime,
+                        msg: SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq { ts, data }),
+                    });
+                }
+                None => remote.playing = false,
+            }
+        }
+    }
+
+    /// Share a change in the local registrations with the other sites
+    fn handle_subscriber_update(&mut self, update: MmSubscriberUpdate) {
+        let issi = update.issi;
+        let msg = match update.action {
+            BrewSubscriberAction::Register => {
+                // Registered here now, whatever site it came from
+                let mut state = self.config.state_write();
+                if let Some(old_site) = state.subscribers.remote_site(issi) {
+                    tracing::info!("BackhaulEntity: ISSI {} moved here from site {}", issi, old_site);
+                    state.subscribers.deregister_remote(issi, old_site);
+                }
+                SiteMessage::Register { issi }
+            }
+            BrewSubscriberAction::Deregister => SiteMessage::Deregister { issi },
+            BrewSubscriberAction::Affiliate => SiteMessage::Affiliate {
+                issi,
+                groups: update.groups,
+            },
+            BrewSubscriberAction::Deaffiliate => SiteMessage::Deaffiliate {
+                issi,
+                groups: update.groups,
+            },
+        };
+        self.send_to_all(msg);
+    }
+
+    /// Forward SDS to the site the destination is registered at, or the sites with members of the group
+    fn handle_sds_send(&self, sds: CmceSdsData) {
+        let dest = sds.dest_issi;
+        let sites = match self.config.state_read().subscribers.remote_site(dest) {
+            Some(site_id) => vec![site_id],
+            None => self.sites_with_group(dest),
+        };
+        if sites.is_empty() {
+            tracing::warn!("BackhaulEntity: no site for SDS {} -> {}, dropping", sds.source_issi, dest);
+            return;
+        }
+        for site_id in sites {
+            tracing::info!("BackhaulEntity: forwarding SDS {} -> {} to site {}", sds.source_issi, dest, site_id);
+            self.send(
+                site_id,
+                SiteMessage::Sds {
+                    source_issi: sds.source_issi,
+                    dest_ssi: dest,
+                    data: sds.user_defined_data.clone().into(),
+                },
+            );
+        }
+    }
+
+    /// A local MS got the floor in a group call, forward it to the sites with members of the group
+    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8, priority: u8) {
+        let sites = self.sites_with_group(dest_gssi);
+        let call = match self.forwarded_calls.get_mut(&ts) {
+            Some(fwd) if fwd.call_id == call_id => {
+                fwd.sites = sites;
+                fwd.call
+            }
+            _ => {
+                if sites.is_empty() {
+                    return;
+                }
+                let call = Uuid::new_v4();
+                self.forwarded_calls.insert(
+                    ts,
+                    ForwardedCall {
+                        call,
+                        call_id,
+                        dest_gssi,
+                        sites,
+                    },
+                );
+                call
+            }
+        };
+
+        let fwd = &self.forwarded_calls[&ts];
+        tracing::info!(
+            "BackhaulEntity: forwarding call_id={} src={} gssi={} to sites {:?}",
+            call_id,
+            source_issi,
+            dest_gssi,
+            fwd.sites
+        );
+        for site_id in &fwd.sites {
+            self.send(
+                *site_id,
+                SiteMessage::GroupCallStart {
+                    call: call.as_u128(),
+                    source_issi,
+                    dest_gssi,
+                    priority,
+                },
+            );
+        }
+    }
+
+    /// The local MS released the floor or the call ended
+    fn handle_local_call_stop(&mut self, ts: u8) {
+        if let Some(fwd) = self.forwarded_calls.remove(&ts) {
+            tracing::info!("BackhaulEntity: forwarded call_id={} gssi={} stopped", fwd.call_id, fwd.dest_gssi);
+            for site_id in fwd.sites {
+                self.send(site_id, SiteMessage::GroupCallEnd { call: fwd.call.as_u128() });
+            }
+        }
+    }
+
+    fn handle_ul_voice(&self, ts: u8, data: &[u8]) {
+        let Some(fwd) = self.forwarded_calls.get(&ts) else {
+            return;
+        };
+        let Some(packed) = pack_ul_acelp_bits(data) else {
+            tracing::warn!("BackhaulEntity: unsupported UL voice length {} on ts={}", data.len(), ts);
+            return;
+        };
+        for site_id in &fwd.sites {
+            self.send(
+                *site_id,
+                SiteMessage::VoiceFrame {
+                    call: fwd.call.as_u128(),
+                    data: packed.clone(),
+                },
+            );
+        }
+    }
+}
+
+impl TetraEntityTrait for BackhaulEntity {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Backhaul
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+        self.process_events(queue);
+        self.drain_playout(queue);
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        match message.msg {
+            SapMsgInner::TmdCircuitDataInd(prim) => {
+                self.handle_ul_voice(prim.ts, &prim.data);
+            }
+            SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+                call_id,
+                source_issi,
+                dest_gssi,
+                ts,
+                priority,
+            }) => {
+                self.handle_local_call_start(call_id, source_issi, dest_gssi, ts, priority);
+            }
+            SapMsgInner::CmceCallControl(CallControl::FloorReleased { ts, .. })
+            | SapMsgInner::CmceCallControl(CallControl::CallEnded { ts, .. }) => {
+                self.handle_local_call_stop(ts);
+            }
+            SapMsgInner::CmceCallControl(CallControl::NetworkCallReady { brew_uuid, ts, .. }) => {
+                if let Some(remote) = self.remote_calls.get_mut(&brew_uuid) {
+                    remote.ts = Some(ts);
+                }
+            }
+            SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }) => {
+                // CMCE could not or no longer carry the call here
+                if self.remote_calls.remove(&brew_uuid).is_some() {
+                    tracing::info!("BackhaulEntity: dropping call uuid={} (CMCE request)", brew_uuid);
+                }
+            }
+            SapMsgInner::MmSubscriberUpdate(update) => {
+                self.handle_subscriber_update(update);
+            }
+            SapMsgInner::CmceSdsData(sds) => {
+                self.handle_sds_send(sds);
+            }
+            _ => {
+                tracing::debug!("BackhaulEntity: unexpected rx_prim from {:?} on {:?}", message.src, message.sap);
+            }
+        }
+    }
+}
+
+impl Drop for BackhaulEntity {
+    fn drop(&mut self) {
+        let _ = self.command_sender.send(BackhaulCommand::Disconnect);
+        if let Some(handle) = self.worker_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Inter-site backhaul linking the BlueStation sites of a network: shared subscriber registry, group calls
+//! and SDS between sites, and migration of registrations when an MS roams
+
+pub mod entity;
+pub mod protocol;
+pub mod worker;
+
+use tetra_config::bluestation::SharedConfig;
+
+/// Returns true if the backhaul component is active
+#[inline]
+pub fn is_active(config: &SharedConfig) -> bool {
+    config.config().backhaul.is_some()
+}
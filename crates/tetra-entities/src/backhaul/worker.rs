@@ -0,0 +1,238 @@
+//! Backhaul worker thread: accepts the connections of the other sites and keeps a connection to each of them
+
+use std::net::IpAddr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, unbounded};
+use ring::hmac;
+use tetra_config::bluestation::{CfgBackhaul, CfgBackhaulPeer};
+
+use crate::network::transports::tcp::{TcpServerTransport, TcpTransport};
+use crate::network::transports::{NetworkAddress, NetworkTransport};
+
+use super::protocol::{self, ReplayGuard, SiteMessage, SitePdu};
+
+/// Interval at which idle connections are checked with a keepalive
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(2);
+/// Timeout for connecting to another site
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long the worker waits for commands before polling for received messages
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Events the backhaul worker sends to the BackhaulEntity
+#[derive(Debug)]
+pub enum BackhaulEvent {
+    /// Our connection to a site is up, nothing has been sent over it yet
+    SiteConnected { site_id: u16 },
+    /// Our connection to a site failed, it is retried after the reconnect delay
+    SiteDisconnected { site_id: u16 },
+    /// Message received from a site
+    Received { site_id: u16, msg: SiteMessage },
+}
+
+/// Commands the BackhaulEntity sends to the worker
+#[derive(Debug)]
+pub enum BackhaulCommand {
+    /// Send a message to a site. Dropped if the site is not connected.
+    Send { site_id: u16, msg: SiteMessage },
+    /// Close all connections and stop the worker
+    Disconnect,
+}
+
+pub struct BackhaulWorker {
+    config: CfgBackhaul,
+    key: hmac::Key,
+    replay_guard: ReplayGuard,
+    event_sender: Sender<BackhaulEvent>,
+    command_receiver: Receiver<BackhaulCommand>,
+}
+
+impl BackhaulWorker {
+    pub fn new(config: CfgBackhaul, event_sender: Sender<BackhaulEvent>, command_receiver: Receiver<BackhaulCommand>) -> Self {
+        let key = protocol::auth_key(&config.shared_secret);
+        Self {
+            config,
+            key,
+            replay_guard: ReplayGuard::default(),
+            event_sender,
+            command_receiver,
+        }
+    }
+
+    /// Returns true if the PDU comes from a configured site, over a connection from that site's address
+    fn is_from_peer(&self, pdu: &SitePdu, source: &NetworkAddress) -> bool {
+        let NetworkAddress::Tcp { host, .. } = source else {
+            return false;
+        };
+        let Ok(ip) = host.parse::<IpAddr>() else {
+            return false;
+        };
+        self.config
+            .peers
+            .iter()
+            .any(|peer| peer.site_id == pdu.site_id && peer.address.ip().to_canonical() == ip.to_canonical())
+    }
+
+    pub fn run(&mut self) {
+        let mut server = match TcpServerTransport::bind(self.config.listen) {
+            Ok(server) => Some(server),
+            Err(e) => {
+                // Other sites cannot reach us, but we can still reach them
+                tracing::error!("BackhaulWorker: {}", e);
+                None
+            }
+        };
+
+        // Each site gets its own thread, so an unreachable site does not hold up the others
+        let mut site_senders: Vec<(u16, Sender<SiteMessage>)> = Vec::new();
+        for peer in &self.config.peers {
+            let (sender, receiver) = unbounded::<SiteMessage>();
+            let link = SiteLink::new(
+                self.config.site_id,
+                self.key.clone(),
+                peer,
+                self.config.reconnect_delay,
+                self.event_sender.clone(),
+                receiver,
+            );
+            let spawned = thread::Builder::new()
+                .name(format!("backhaul-site-{}", peer.site_id))
+                .spawn(move || link.run());
+            match spawned {
+                Ok(_) => site_senders.push((peer.site_id, sender)),
+                Err(e) => tracing::error!("BackhaulWorker: failed to spawn link to site {}: {}", peer.site_id, e),
+            }
+        }
+
+        loop {
+            match self.command_receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(BackhaulCommand::Send { site_id, msg }) => {
+                    if let Some((_, sender)) = site_senders.iter().find(|(id, _)| *id == site_id) {
+                        let _ = sender.send(msg);
+                    } else {
+                        tracing::warn!("BackhaulWorker: no link to site {}, dropping {:?}", site_id, msg);
+                    }
+                    continue;
+                }
+                Ok(BackhaulCommand::Disconnect) | Err(RecvTimeoutError::Disconnected) => {
+                    tracing::debug!("BackhaulWorker: shutting down");
+                    // Dropping the senders stops the site links
+                    return;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            let Some(server) = server.as_mut() else {
+                continue;
+            };
+            for message in server.receive_reliable() {
+                match protocol::decode(&self.key, &message.payload) {
+                    Ok(pdu) if !self.is_from_peer(&pdu, &message.source) => {
+                        tracing::warn!(
+                            "BackhaulWorker: dropping message from {:?} claiming unknown site {}",
+                            message.source,
+                            pdu.site_id
+                        );
+                    }
+                    Ok(pdu) if !self.replay_guard.accept(&pdu) => {
+                        tracing::warn!(
+                            "BackhaulWorker: dropping replayed message from site {} with sequence number {}",
+                            pdu.site_id,
+                            pdu.seq
+                        );
+                    }
+                    Ok(pdu) if pdu.msg == SiteMessage::Keepalive => {}
+                    Ok(pdu) => {
+                        let _ = self.event_sender.send(BackhaulEvent::Received {
+                            site_id: pdu.site_id,
+                            msg: pdu.msg,
+                        });
+                    }
+                    Err(e) => tracing::warn!("BackhaulWorker: dropping message from {:?}: {}", message.source, e),
+                }
+            }
+        }
+    }
+}
+
+/// Our connection to one of the other sites
+struct SiteLink {
+    own_site_id: u16,
+    key: hmac::Key,
+    site_id: u16,
+    /// Sequence number of the last message sent to the site
+    seq: u64,
+    transport: Box<dyn NetworkTransport>,
+    reconnect_delay: Duration,
+    event_sender: Sender<BackhaulEvent>,
+    receiver: Receiver<SiteMessage>,
+}
+
+impl SiteLink {
+    fn new(
+        own_site_id: u16,
+        key: hmac::Key,
+        peer: &CfgBackhaulPeer,
+        reconnect_delay: Duration,
+        event_sender: Sender<BackhaulEvent>,
+        receiver: Receiver<SiteMessage>,
+    ) -> Self {
+        let address = NetworkAddress::Tcp {
+            host: peer.address.ip().to_string(),
+            port: peer.address.port(),
+        };
+        Self {
+            own_site_id,
+            key,
+            site_id: peer.site_id,
+            seq: 0,
+            transport: Box::new(TcpTransport::new(address, CONNECT_TIMEOUT, KEEPALIVE_INTERVAL)),
+            reconnect_delay,
+            event_sender,
+            receiver,
+        }
+    }
+
+    fn run(mut self) {
+        let mut connected = false;
+        let mut next_attempt = Instant::now();
+        loop {
+            if !connected && Instant::now() >= next_attempt {
+                match self.transport.connect() {
+                    Ok(()) => {
+                        tracing::info!("BackhaulWorker: connected to site {}", self.site_id);
+                        connected = true;
+                        self.seq = self.seq.max(protocol::initial_sequence());
+                        // Messages queued while disconnected are outdated
+                        while self.receiver.try_recv().is_ok() {}
+                        let _ = self.event_sender.send(BackhaulEvent::SiteConnected { site_id: self.site_id });
+                    }
+                    Err(e) => {
+                        tracing::debug!("BackhaulWorker: site {} unreachable: {}", self.site_id, e);
+                        next_attempt = Instant::now() + self.reconnect_delay;
+                    }
+                }
+            }
+
+            let msg = match self.receiver.recv_timeout(KEEPALIVE_INTERVAL) {
+                Ok(msg) => msg,
+                Err(RecvTimeoutError::Timeout) => SiteMessage::Keepalive,
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            if !connected {
+                continue;
+            }
+            self.seq += 1;
+            if let Err(e) = self
+                .transport
+                .send_reliable(&protocol::encode(&self.key, self.own_site_id, self.seq, msg))
+            {
+                tracing::warn!("BackhaulWorker: lost connection to site {}: {}", self.site_id, e);
+                connected = false;
+                next_attempt = Instant::now() + self.reconnect_delay;
+                let _ = self.event_sender.send(BackhaulEvent::SiteDisconnected { site_id: self.site_id });
+            }
+        }
+    }
+}
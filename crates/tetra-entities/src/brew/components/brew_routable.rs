@@ -1,5 +1,8 @@
 use tetra_config::bluestation::SharedConfig;
 
+/// Call priority of emergency calls (ETSI 14.8.14, pre-emptive priority 4)
+pub const EMERGENCY_CALL_PRIORITY: u8 = 15;
+
 /// Returns true if the Brew component is active
 #[inline]
 pub fn is_active(config: &SharedConfig) -> bool {
@@ -12,6 +15,12 @@ pub fn feature_sds_enabled(config: &SharedConfig) -> bool {
     config.config().brew.as_ref().map_or(false, |brew| brew.feature_sds_enabled)
 }
 
+/// Returns true if packet data over Brew is enabled
+#[inline]
+pub fn feature_packet_data_enabled(config: &SharedConfig) -> bool {
+    config.config().brew.as_ref().is_some_and(|brew| brew.feature_packet_data_enabled)
+}
+
 /// Returns true if the configured Brew server is TetraPack (core.tetrapack.online)
 fn is_tetrapack(config: &SharedConfig) -> bool {
     let Some(brew_config) = &config.config().brew else {
@@ -20,14 +29,18 @@ fn is_tetrapack(config: &SharedConfig) -> bool {
     brew_config.host == "core.tetrapack.online"
 }
 
+/// Returns true if the GSSI lies in the range TetraPack never routes (0..=90)
+fn is_tetrapack_reserved_gssi(config: &SharedConfig, gssi: u32) -> bool {
+    is_tetrapack(config) && gssi <= 90
+}
+
 /// Determine if a given GSSI should be routed over Brew, or is restricted to local handling
 pub fn is_brew_gssi_routable(config: &SharedConfig, ssi: u32) -> bool {
     let Some(brew_config) = &config.config().brew else {
         // Brew not configured, so no routing to Brew
         return false;
     };
-    if is_tetrapack(config) && ssi <= 90 {
-        // TetraPack doesn't route 0..=90
+    if is_tetrapack_reserved_gssi(config, ssi) {
         return false;
     }
     if config.config().cell.local_ssi_ranges.contains(ssi) {
@@ -50,6 +63,24 @@ pub fn is_brew_gssi_routable(config: &SharedConfig, ssi: u32) -> bool {
     true
 }
 
+/// Determine if a group call with the given call priority should be routed over Brew. Emergency calls
+/// bypass the whitelist and local range restrictions, so they always reach the network.
+pub fn is_brew_call_routable(config: &SharedConfig, gssi: u32, priority: u8) -> bool {
+    if priority == EMERGENCY_CALL_PRIORITY && is_active(config) && !is_tetrapack_reserved_gssi(config, gssi) {
+        return true;
+    }
+    is_brew_gssi_routable(config, gssi)
+}
+
+/// Determine if an individual call with the given call priority to the given ISSI should be routed over Brew.
+/// Emergency calls bypass the ISSI restrictions, so they always reach the network.
+pub fn is_brew_individual_call_routable(config: &SharedConfig, issi: u32, priority: u8) -> bool {
+    if priority == EMERGENCY_CALL_PRIORITY && is_active(config) {
+        return true;
+    }
+    is_brew_issi_routable(config, issi)
+}
+
 /// Determine if a given ISSI should be sent to the Brew server.
 /// On TetraPack, ISSIs must be exactly 7 digits (1_000_000..=9_999_999). Other servers allow all ISSIs.
 pub fn is_brew_issi_routable(config: &SharedConfig, issi: u32) -> bool {
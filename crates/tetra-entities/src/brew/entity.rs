@@ -11,8 +11,11 @@ use uuid::Uuid;
 
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::{CfgBrew, SharedConfig};
-use tetra_core::{Sap, TdmaTime, tetra_entities::TetraEntity};
+use tetra_core::{Sap, TdmaTime, channel_timeslot, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
 use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::IndividualCallSignal;
+use tetra_saps::sn::{SnDataInd, SnDataReq, SnPdpContextInd};
 use tetra_saps::{SapMsg, SapMsgInner, control::call_control::CallControl, tmd::TmdCircuitDataReq};
 
 use super::worker::{BrewCommand, BrewEvent, BrewWorker};
@@ -91,6 +94,23 @@ struct UlForwardedCall {
     frame_count: u64,
 }
 
+/// Tracks an individual call bridged between a local MS and a network subscriber
+#[derive(Debug)]
+struct IndividualBridge {
+    /// TETRA call identifier - None until NetworkCallReady received
+    call_id: Option<u16>,
+    /// Traffic timeslot of the local MS - None until NetworkCallReady received
+    ts: Option<u8>,
+    /// ISSI of the local MS
+    local_issi: u32,
+    /// ISSI of the network subscriber
+    remote_issi: u32,
+    /// True while the local MS holds the floor, its UL voice is then forwarded to TetraPack
+    local_tx: bool,
+    /// Number of voice frames received and forwarded
+    frame_count: u64,
+}
+
 #[derive(Debug)]
 struct JitterFrame {
     rx_seq: u64,
@@ -251,6 +271,12 @@ pub struct BrewEntity {
     /// UL calls being forwarded to TetraPack, keyed by timeslot
     ul_forwarded: HashMap<u8, UlForwardedCall>,
 
+    /// Individual calls bridged to network subscribers, keyed by session UUID
+    individual_calls: HashMap<Uuid, IndividualBridge>,
+
+    /// Packet data sessions of local PDP contexts, keyed by (ISSI, NSAPI)
+    packet_sessions: HashMap<(u32, u8), Uuid>,
+
     /// Registered subscriber groups (ISSI -> set of GSSIs)
     subscriber_groups: HashMap<u32, HashSet<u32>>,
 
@@ -268,7 +294,6 @@ impl BrewEntity {
         let (command_sender, command_receiver) = unbounded::<BrewCommand>();
 
         // Spawn worker thread
-        let brew_config = config.config().as_ref().brew.clone().unwrap(); // Never fails
         let worker_config = config.clone();
         let handle = thread::Builder::new()
             .name("brew-worker".to_string())
@@ -278,6 +303,15 @@ impl BrewEntity {
             })
             .expect("failed to spawn BrewWorker thread");
 
+        let mut entity = Self::with_link(config, event_receiver, command_sender);
+        entity.worker_handle = Some(handle);
+        entity
+    }
+
+    /// Create the entity on an existing link instead of spawning a worker, e.g. to drive it from a test.
+    /// Commands sent to the link are to be handled as the worker would.
+    pub fn with_link(config: SharedConfig, event_receiver: Receiver<BrewEvent>, command_sender: Sender<BrewCommand>) -> Self {
+        let brew_config = config.config().as_ref().brew.clone().unwrap(); // Never fails
         {
             let mut state = config.state_write();
             state.network_connected = false;
@@ -293,9 +327,11 @@ impl BrewEntity {
             dl_jitter: HashMap::new(),
             hanging_calls: HashMap::new(),
             ul_forwarded: HashMap::new(),
+            individual_calls: HashMap::new(),
+            packet_sessions: HashMap::new(),
             subscriber_groups: HashMap::new(),
             connected: false,
-            worker_handle: Some(handle),
+            worker_handle: None,
         }
     }
 
@@ -328,6 +364,9 @@ impl BrewEntity {
                 BrewEvent::GroupCallEnd { uuid, cause } => {
                     self.handle_group_call_end(queue, uuid, cause);
                 }
+                BrewEvent::IndividualCall { uuid, signal } => {
+                    self.handle_network_individual_call(queue, uuid, signal);
+                }
                 BrewEvent::VoiceFrame { uuid, length_bits, data } => {
                     self.handle_voice_frame(uuid, length_bits, data);
                 }
@@ -343,6 +382,12 @@ impl BrewEntity {
                 BrewEvent::SdsReport { uuid, status } => {
                     tracing::debug!("BrewEntity: SDS report uuid={} status={}", uuid, status);
                 }
+                BrewEvent::Dtmf { uuid, digits } => {
+                    self.handle_network_dtmf(queue, uuid, digits);
+                }
+                BrewEvent::PacketData { uuid, packet } => {
+                    self.handle_network_packet(queue, uuid, packet);
+                }
                 BrewEvent::SubscriberEvent { msg_type, issi, groups } => {
                     tracing::debug!("BrewEntity: subscriber event type={} issi={} groups={:?}", msg_type, issi, groups);
                 }
@@ -639,18 +684,27 @@ impl BrewEntity {
 
     /// Handle a voice frame from Brew — inject into the downlink
     fn handle_voice_frame(&mut self, uuid: Uuid, _length_bits: u16, data: Vec<u8>) {
-        let Some(call) = self.active_calls.get_mut(&uuid) else {
+        let (frame_count, ts) = if let Some(call) = self.active_calls.get_mut(&uuid) {
+            call.frame_count += 1;
+            (call.frame_count, call.ts)
+        } else if let Some(bridge) = self.individual_calls.get_mut(&uuid) {
+            if bridge.local_tx {
+                // The local MS holds the floor of the simplex call
+                tracing::trace!("BrewEntity: voice frame while local MS transmits, uuid={}, dropping", uuid);
+                return;
+            }
+            bridge.frame_count += 1;
+            (bridge.frame_count, bridge.ts)
+        } else {
             // Voice frame for unknown call — might arrive before GROUP_TX or after GROUP_IDLE
             tracing::trace!("BrewEntity: voice frame for unknown uuid={} ({} bytes)", uuid, data.len());
             return;
         };
 
-        call.frame_count += 1;
-
         // Check if resources have been allocated yet
-        let Some(ts) = call.ts else {
+        let Some(ts) = ts else {
             // Audio arrived before NetworkCallReady - drop it
-            if call.frame_count == 1 {
+            if frame_count == 1 {
                 tracing::debug!(
                     "BrewEntity: voice frame arrived before resources allocated, uuid={}, dropping",
                     uuid
@@ -660,10 +714,10 @@ impl BrewEntity {
         };
 
         // Log first voice frame per call
-        if call.frame_count == 1 {
+        if frame_count == 1 {
             tracing::info!(
                 "BrewEntity: voice frame #{} uuid={} len={} bytes ts={}",
-                call.frame_count,
+                frame_count,
                 uuid,
                 data.len(),
                 ts
@@ -691,11 +745,16 @@ impl BrewEntity {
 
         let mut to_send: Vec<(u8, Uuid, usize, JitterFrame)> = Vec::new();
 
-        for (uuid, call) in &self.active_calls {
-            let Some(ts) = call.ts else {
+        let calls = self
+            .active_calls
+            .iter()
+            .map(|(uuid, call)| (uuid, call.ts))
+            .chain(self.individual_calls.iter().map(|(uuid, bridge)| (uuid, bridge.ts)));
+        for (uuid, ts) in calls {
+            let Some(ts) = ts else {
                 continue;
             };
-            if ts != self.dltime.t {
+            if channel_timeslot(ts) != self.dltime.t {
                 continue;
             }
             let Some(jitter) = self.dl_jitter.get_mut(uuid) else {
@@ -744,6 +803,28 @@ impl BrewEntity {
             });
         }
 
+        // Release all bridged individual calls
+        for (uuid, bridge) in self.individual_calls.drain() {
+            tracing::info!(
+                "BrewEntity: releasing individual call uuid={} {} <-> {} (disconnected)",
+                uuid,
+                bridge.local_issi,
+                bridge.remote_issi
+            );
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Brew,
+                dest: TetraEntity::Cmce,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall {
+                    brew_uuid: uuid,
+                    signal: IndividualCallSignal::Release {
+                        cause: DisconnectCause::SwmiRequestedDisconnection.into_raw() as u8,
+                    },
+                }),
+            });
+        }
+
         // Clear hanging call tracking
         self.hanging_calls.clear();
         self.dl_jitter.clear();
@@ -760,7 +841,10 @@ impl BrewEntity {
         );
 
         // Update active call with CMCE-allocated resources
-        if let Some(call) = self.active_calls.get_mut(&brew_uuid) {
+        if let Some(bridge) = self.individual_calls.get_mut(&brew_uuid) {
+            bridge.call_id = Some(call_id);
+            bridge.ts = Some(ts);
+        } else if let Some(call) = self.active_calls.get_mut(&brew_uuid) {
             call.call_id = Some(call_id);
             call.ts = Some(ts);
             call.usage = Some(usage);
@@ -815,7 +899,7 @@ impl TetraEntityTrait for BrewEntity {
         self.expire_hanging_calls(queue);
     }
 
-    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         match message.msg {
             // UL voice from UMAC — forward to TetraPack if this timeslot is being forwarded
             SapMsgInner::TmdCircuitDataInd(prim) => {
@@ -827,8 +911,9 @@ impl TetraEntityTrait for BrewEntity {
                 source_issi,
                 dest_gssi,
                 ts,
+                priority,
             }) => {
-                self.handle_local_call_start(call_id, source_issi, dest_gssi, ts);
+                self.handle_local_call_start(call_id, source_issi, dest_gssi, ts, priority);
             }
             SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }) => {
                 self.handle_local_call_tx_stopped(call_id, ts);
@@ -847,6 +932,12 @@ impl TetraEntityTrait for BrewEntity {
             }) => {
                 self.rx_network_call_ready(brew_uuid, call_id, ts, usage);
             }
+            SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall { brew_uuid, signal }) => {
+                self.handle_local_individual_call(queue, brew_uuid, signal);
+            }
+            SapMsgInner::CmceCallControl(CallControl::CallDtmf { call_id, digits }) => {
+                self.handle_local_dtmf(call_id, digits);
+            }
             // UlInactivityTimeout is UMAC→CMCE only; Brew handles FloorReleased instead
             SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { .. }) => {}
             SapMsgInner::MmSubscriberUpdate(update) => {
@@ -855,6 +946,12 @@ impl TetraEntityTrait for BrewEntity {
             SapMsgInner::CmceSdsData(sds) => {
                 self.handle_sds_send(sds);
             }
+            SapMsgInner::SnDataInd(prim) => {
+                self.handle_local_packet(prim);
+            }
+            SapMsgInner::SnPdpContextInd(prim) => {
+                self.handle_pdp_context_ind(prim);
+            }
             _ => {
                 tracing::debug!("BrewEntity: unexpected rx_prim from {:?} on {:?}", message.src, message.sap);
             }
@@ -867,7 +964,8 @@ impl TetraEntityTrait for BrewEntity {
 impl BrewEntity {
     /// Handle notification that a local UL group call has started.
     /// If the group is subscribed (in config.groups), start forwarding to TetraPack.
-    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8) {
+    /// The call priority is passed on, so emergency calls are marked as such on the network.
+    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8, priority: u8) {
         if !self.connected {
             tracing::trace!("BrewEntity: not connected, ignoring local call start");
             return;
@@ -913,7 +1011,7 @@ impl BrewEntity {
                 uuid: fwd.uuid,
                 source_issi,
                 dest_gssi,
-                priority: 0,
+                priority,
                 service: 0, // TETRA encoded speech
             });
             return;
@@ -935,7 +1033,7 @@ impl BrewEntity {
             uuid,
             source_issi,
             dest_gssi,
-            priority: 0,
+            priority,
             service: 0, // TETRA encoded speech
         });
 
@@ -999,12 +1097,20 @@ impl BrewEntity {
     /// Handle UL voice data from UMAC. If the timeslot is being forwarded to TetraPack,
     /// convert to STE format and send.
     fn handle_ul_voice(&mut self, ts: u8, acelp_bits: Vec<u8>) {
-        let Some(fwd) = self.ul_forwarded.get_mut(&ts) else {
+        let uuid = if let Some(fwd) = self.ul_forwarded.get_mut(&ts) {
+            fwd.frame_count += 1;
+            fwd.uuid
+        } else if let Some((uuid, bridge)) = self
+            .individual_calls
+            .iter_mut()
+            .find(|(_, bridge)| bridge.ts == Some(ts) && bridge.local_tx)
+        {
+            bridge.frame_count += 1;
+            *uuid
+        } else {
             return; // Not forwarded to TetraPack
         };
 
-        fwd.frame_count += 1;
-
         // Convert ACELP bits to STE format.
         // Supported inputs:
         //   - 274 bytes (1-bit-per-byte) → pack to 35 bytes + header
@@ -1042,13 +1148,262 @@ impl BrewEntity {
         };
 
         let _ = self.command_sender.send(BrewCommand::SendVoiceFrame {
-            uuid: fwd.uuid,
+            uuid,
             length_bits: (ste_data.len() * 8) as u16,
             data: ste_data,
         });
     }
 }
 
+// ─── Individual call bridging ─────────────────────────────────────
+
+impl BrewEntity {
+    /// Handle individual call signalling from a network subscriber, forwarding it to CMCE
+    fn handle_network_individual_call(&mut self, queue: &mut MessageQueue, uuid: Uuid, signal: IndividualCallSignal) {
+        if let IndividualCallSignal::Setup {
+            calling_issi, called_issi, ..
+        } = signal
+        {
+            tracing::info!(
+                "BrewEntity: network individual call uuid={} from {} to local issi={}",
+                uuid,
+                calling_issi,
+                called_issi
+            );
+            self.individual_calls.insert(
+                uuid,
+                IndividualBridge {
+                    call_id: None,
+                    ts: None,
+                    local_issi: called_issi,
+                    remote_issi: calling_issi,
+                    local_tx: false,
+                    frame_count: 0,
+                },
+            );
+            self.dl_jitter
+                .entry(uuid)
+                .or_insert_with(|| VoiceJitterBuffer::with_initial_latency(self.brew_config.jitter_initial_latency_frames as usize));
+        } else if !self.track_individual_call(uuid, &signal) {
+            return;
+        }
+
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Brew,
+            dest: TetraEntity::Cmce,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall { brew_uuid: uuid, signal }),
+        });
+    }
+
+    /// Track floor control and release of a bridged individual call. Returns false if the call is unknown.
+    fn track_individual_call(&mut self, uuid: Uuid, signal: &IndividualCallSignal) -> bool {
+        let Some(bridge) = self.individual_calls.get_mut(&uuid) else {
+            tracing::debug!("BrewEntity: {:?} for unknown individual call uuid={}", signal, uuid);
+            return false;
+        };
+        match signal {
+            IndividualCallSignal::TxGranted { issi } => bridge.local_tx = *issi == bridge.local_issi,
+            IndividualCallSignal::TxCeased => bridge.local_tx = false,
+            IndividualCallSignal::Release { cause } => {
+                tracing::info!(
+                    "BrewEntity: individual call released uuid={} call_id={:?} cause={} frames={}",
+                    uuid,
+                    bridge.call_id,
+                    cause,
+                    bridge.frame_count
+                );
+                self.individual_calls.remove(&uuid);
+                self.dl_jitter.remove(&uuid);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Handle individual call signalling from CMCE for a local MS, forwarding it to TetraPack
+    fn handle_local_individual_call(&mut self, queue: &mut MessageQueue, uuid: Uuid, signal: IndividualCallSignal) {
+        if let IndividualCallSignal::Setup {
+            calling_issi, called_issi, ..
+        } = signal
+        {
+            if !self.connected {
+                tracing::info!(
+                    "BrewEntity: not connected, rejecting individual call from {} to {}",
+                    calling_issi,
+                    called_issi
+                );
+                queue.push_back(SapMsg {
+                    sap: Sap::Control,
+                    src: TetraEntity::Brew,
+                    dest: TetraEntity::Cmce,
+                    dltime: self.dltime,
+                    msg: SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall {
+                        brew_uuid: uuid,
+                        signal: IndividualCallSignal::Release {
+                            cause: DisconnectCause::CalledPartyNotReachable.into_raw() as u8,
+                        },
+                    }),
+                });
+                return;
+            }
+            tracing::info!(
+                "BrewEntity: bridging individual call uuid={} from local issi={} to {}",
+                uuid,
+                calling_issi,
+                called_issi
+            );
+            self.individual_calls.insert(
+                uuid,
+                IndividualBridge {
+                    call_id: None,
+                    ts: None,
+                    local_issi: calling_issi,
+                    remote_issi: called_issi,
+                    local_tx: false,
+                    frame_count: 0,
+                },
+            );
+            self.dl_jitter
+                .entry(uuid)
+                .or_insert_with(|| VoiceJitterBuffer::with_initial_latency(self.brew_config.jitter_initial_latency_frames as usize));
+        } else if !self.track_individual_call(uuid, &signal) {
+            return;
+        }
+
+        let _ = self.command_sender.send(BrewCommand::SendIndividualCall { uuid, signal });
+    }
+}
+
+// ─── DTMF and packet data ─────────────────────────────────────────
+
+impl BrewEntity {
+    /// Returns the CMCE call identifier of the call carried by a Brew session, if it is known yet
+    fn call_id_of_session(&self, uuid: Uuid) -> Option<u16> {
+        if let Some(call) = self.active_calls.get(&uuid) {
+            return call.call_id;
+        }
+        if let Some(bridge) = self.individual_calls.get(&uuid) {
+            return bridge.call_id;
+        }
+        self.hanging_calls
+            .values()
+            .find(|call| call.uuid == uuid)
+            .map(|call| call.call_id)
+            .or_else(|| self.ul_forwarded.values().find(|fwd| fwd.uuid == uuid).map(|fwd| fwd.call_id))
+    }
+
+    /// Returns the Brew session carrying a CMCE call, if the call is bridged
+    fn session_of_call(&self, call_id: u16) -> Option<Uuid> {
+        self.ul_forwarded
+            .values()
+            .find(|fwd| fwd.call_id == call_id)
+            .map(|fwd| fwd.uuid)
+            .or_else(|| {
+                self.active_calls
+                    .values()
+                    .find(|call| call.call_id == Some(call_id))
+                    .map(|call| call.uuid)
+            })
+            .or_else(|| {
+                self.hanging_calls
+                    .values()
+                    .find(|call| call.call_id == call_id)
+                    .map(|call| call.uuid)
+            })
+            .or_else(|| {
+                self.individual_calls
+                    .iter()
+                    .find(|(_, bridge)| bridge.call_id == Some(call_id))
+                    .map(|(uuid, _)| *uuid)
+            })
+    }
+
+    /// Handle DTMF digits from the network, forwarding them to CMCE for the local parties of the call
+    fn handle_network_dtmf(&mut self, queue: &mut MessageQueue, uuid: Uuid, digits: String) {
+        let Some(call_id) = self.call_id_of_session(uuid) else {
+            tracing::debug!("BrewEntity: dropping DTMF {} for uuid={} without local call", digits, uuid);
+            return;
+        };
+        tracing::info!("BrewEntity: DTMF {} uuid={} -> call_id={}", digits, uuid, call_id);
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Brew,
+            dest: TetraEntity::Cmce,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::CallDtmf { call_id, digits }),
+        });
+    }
+
+    /// Handle DTMF digits dialled by a local MS, forwarding them on the Brew session of the call
+    fn handle_local_dtmf(&mut self, call_id: u16, digits: String) {
+        if !self.connected {
+            tracing::debug!("BrewEntity: not connected, dropping DTMF for call_id={}", call_id);
+            return;
+        }
+        let Some(uuid) = self.session_of_call(call_id) else {
+            tracing::debug!("BrewEntity: dropping DTMF for call_id={} not bridged to Brew", call_id);
+            return;
+        };
+        tracing::info!("BrewEntity: DTMF {} call_id={} -> uuid={}", digits, call_id, uuid);
+        let _ = self.command_sender.send(BrewCommand::SendDtmf { uuid, digits });
+    }
+
+    /// Handle an IP packet from the network, handing it to SNDCP which delivers it to the MS owning the
+    /// destination address
+    fn handle_network_packet(&mut self, queue: &mut MessageQueue, uuid: Uuid, packet: Vec<u8>) {
+        tracing::trace!("BrewEntity: packet data uuid={} {} bytes -> SNDCP", uuid, packet.len());
+        queue.push_back(SapMsg {
+            sap: Sap::SnSap,
+            src: TetraEntity::Brew,
+            dest: TetraEntity::Sndcp,
+            dltime: self.dltime,
+            msg: SapMsgInner::SnDataReq(SnDataReq { packet }),
+        });
+    }
+
+    /// Handle an IP packet sent by a local MS, forwarding it on the Brew session of its PDP context
+    fn handle_local_packet(&mut self, prim: SnDataInd) {
+        if !self.connected {
+            tracing::debug!("BrewEntity: not connected, dropping packet from {}:{}", prim.issi, prim.nsapi);
+            return;
+        }
+        let uuid = *self.packet_sessions.entry((prim.issi, prim.nsapi)).or_insert_with(Uuid::new_v4);
+        tracing::trace!(
+            "BrewEntity: packet data from {}:{} {} bytes -> uuid={}",
+            prim.issi,
+            prim.nsapi,
+            prim.packet.len(),
+            uuid
+        );
+        let _ = self.command_sender.send(BrewCommand::SendPacketData { uuid, packet: prim.packet });
+    }
+
+    /// Track PDP context activation, each context gets a Brew session of its own
+    fn handle_pdp_context_ind(&mut self, prim: SnPdpContextInd) {
+        let key = (prim.issi, prim.nsapi);
+        if prim.active {
+            let uuid = Uuid::new_v4();
+            tracing::info!(
+                "BrewEntity: PDP context {}:{} with address {} uses packet data uuid={}",
+                prim.issi,
+                prim.nsapi,
+                prim.ip_addr,
+                uuid
+            );
+            self.packet_sessions.insert(key, uuid);
+        } else if let Some(uuid) = self.packet_sessions.remove(&key) {
+            tracing::info!(
+                "BrewEntity: PDP context {}:{} closed, packet data uuid={}",
+                prim.issi,
+                prim.nsapi,
+                uuid
+            );
+        }
+    }
+}
+
 // ─── SDS handling ─────────────────────────────────────────────────
 
 impl BrewEntity {
@@ -5,9 +5,13 @@ pub mod entity;
 pub mod protocol;
 pub mod worker;
 
-pub use components::brew_routable::feature_sds_enabled;
 /// Convenience re-export of commonly externally used functions
+pub use components::brew_routable::EMERGENCY_CALL_PRIORITY;
+pub use components::brew_routable::feature_packet_data_enabled;
+pub use components::brew_routable::feature_sds_enabled;
 pub use components::brew_routable::is_active;
+pub use components::brew_routable::is_brew_call_routable;
 pub use components::brew_routable::is_brew_gssi_routable;
+pub use components::brew_routable::is_brew_individual_call_routable;
 pub use components::brew_routable::is_brew_issi_routable;
 pub use components::brew_routable::is_tetrapack_sds_service_issi;
@@ -78,6 +78,17 @@ pub struct BrewGroupTransmission {
     pub service: u16, // Speech service
 }
 
+/// Individual call set-up data, part of CALL_STATE_SETUP_REQUEST
+#[derive(Debug, Clone)]
+pub struct BrewCallSetup {
+    pub source: u32,      // ISSI of caller
+    pub destination: u32, // ISSI of called party
+    pub priority: u8,
+    pub duplex: bool,
+    pub hook: bool,   // Hook signalling (true) or direct call set-up (false)
+    pub service: u16, // Speech service
+}
+
 /// Call control (0xf1)
 #[derive(Debug, Clone)]
 pub struct BrewCallControlMessage {
@@ -93,8 +104,13 @@ pub enum BrewCallPayload {
     GroupTransmission(BrewGroupTransmission),
     /// CALL_STATE_GROUP_IDLE, CALL_STATE_SETUP_REJECT, CALL_STATE_CALL_RELEASE
     Cause(u8),
-    /// CALL_STATE_SETUP_ACCEPT, CALL_STATE_CALL_ALERT — no extra payload
+    /// CALL_STATE_SETUP_REQUEST
+    CallSetup(BrewCallSetup),
+    /// CALL_STATE_SETUP_ACCEPT, CALL_STATE_CALL_ALERT, CALL_STATE_CONNECT_REQUEST,
+    /// CALL_STATE_CONNECT_CONFIRM, CALL_STATE_SIMPLEX_IDLE — no extra payload
     Empty,
+    /// CALL_STATE_SIMPLEX_GRANTED (ISSI given the floor)
+    SimplexGrant { source: u32 },
     /// CALL_STATE_SHORT_TRANSFER (SDS header)
     ShortTransfer { source: u32, destination: u32 },
     /// Unknown/unhandled call state
@@ -265,11 +281,40 @@ fn parse_call_control(call_state: u8, data: &[u8]) -> Result<BrewMessage, BrewPa
             BrewCallPayload::Cause(payload_data[0])
         }
 
-        CALL_STATE_SETUP_ACCEPT | CALL_STATE_CALL_ALERT => {
+        CALL_STATE_SETUP_REQUEST => {
+            // BrewCallSetup: source(4) + destination(4) + number[32](char) + priority(1) + duplex(1) + method(1) + service(2) = 45 bytes
+            if payload_data.len() < 45 {
+                return Err(BrewParseError::TooShort(data.len()));
+            }
+            BrewCallPayload::CallSetup(BrewCallSetup {
+                source: read_u32_le(payload_data, 0),
+                destination: read_u32_le(payload_data, 4),
+                priority: payload_data[40],
+                duplex: payload_data[41] != 0,
+                hook: payload_data[42] != 0,
+                service: read_u16_le(payload_data, 43),
+            })
+        }
+
+        CALL_STATE_SETUP_ACCEPT
+        | CALL_STATE_CALL_ALERT
+        | CALL_STATE_CONNECT_REQUEST
+        | CALL_STATE_CONNECT_CONFIRM
+        | CALL_STATE_SIMPLEX_IDLE => {
             // No extra payload
             BrewCallPayload::Empty
         }
 
+        CALL_STATE_SIMPLEX_GRANTED => {
+            // ISSI of the transmitting party
+            if payload_data.len() < 4 {
+                return Err(BrewParseError::TooShort(data.len()));
+            }
+            BrewCallPayload::SimplexGrant {
+                source: read_u32_le(payload_data, 0),
+            }
+        }
+
         CALL_STATE_SHORT_TRANSFER => {
             // BrewShortData: source(4) + destination(4) + number[32](char) = 40 bytes
             if payload_data.len() < 8 {
@@ -457,6 +502,63 @@ pub fn build_group_idle(session_uuid: &Uuid, cause: u8) -> Vec<u8> {
     buf
 }
 
+/// Build an individual call set-up request (SETUP_REQUEST)
+/// Sent when a local radio calls a network subscriber
+pub fn build_setup_request(
+    session_uuid: &Uuid,
+    source_issi: u32,
+    dest_issi: u32,
+    priority: u8,
+    duplex: bool,
+    hook: bool,
+    service: u16,
+) -> Vec<u8> {
+    // kind(1) + type(1) + uuid(16) + source(4) + dest(4) + number[32] + priority(1) + duplex(1) + method(1) + service(2) = 63
+    let mut buf = Vec::with_capacity(63);
+    buf.push(BREW_CLASS_CALL_CONTROL);
+    buf.push(CALL_STATE_SETUP_REQUEST);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    write_u32_le(&mut buf, source_issi);
+    write_u32_le(&mut buf, dest_issi);
+    // number field: 32 bytes, zero-filled (external subscriber number not supported)
+    buf.extend_from_slice(&[0u8; 32]);
+    buf.push(priority);
+    buf.push(duplex as u8);
+    buf.push(hook as u8);
+    write_u16_le(&mut buf, service);
+    buf
+}
+
+/// Build a call control message without payload (SETUP_ACCEPT, CALL_ALERT, CONNECT_REQUEST,
+/// CONNECT_CONFIRM, SIMPLEX_IDLE)
+pub fn build_call_state(session_uuid: &Uuid, call_state: u8) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(18);
+    buf.push(BREW_CLASS_CALL_CONTROL);
+    buf.push(call_state);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    buf
+}
+
+/// Build a call control message carrying a cause (SETUP_REJECT, CALL_RELEASE)
+pub fn build_call_cause(session_uuid: &Uuid, call_state: u8, cause: u8) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(19);
+    buf.push(BREW_CLASS_CALL_CONTROL);
+    buf.push(call_state);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    buf.push(cause);
+    buf
+}
+
+/// Build a SIMPLEX_GRANTED message, giving the floor of an individual call to the given ISSI
+pub fn build_simplex_granted(session_uuid: &Uuid, source_issi: u32) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(22);
+    buf.push(BREW_CLASS_CALL_CONTROL);
+    buf.push(CALL_STATE_SIMPLEX_GRANTED);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    write_u32_le(&mut buf, source_issi);
+    buf
+}
+
 /// Build a CALL_STATE_SHORT_TRANSFER message (SDS header with source/dest/number)
 pub fn build_short_transfer(session_uuid: &Uuid, source: u32, destination: u32) -> Vec<u8> {
     // kind(1) + type(1) + uuid(16) + source(4) + destination(4) + number[32](1 byte each) = 58
@@ -495,6 +597,45 @@ pub fn build_sds_report(session_uuid: &Uuid, status: u8) -> Vec<u8> {
     buf
 }
 
+/// Build a FRAME_TYPE_DTMF_DATA message, carrying the digits as ASCII characters
+pub fn build_dtmf_frame(session_uuid: &Uuid, digits: &str) -> Vec<u8> {
+    build_data_frame(FRAME_TYPE_DTMF_DATA, session_uuid, digits.as_bytes())
+}
+
+/// Build a FRAME_TYPE_PACKET_DATA message, carrying a single IP packet
+pub fn build_packet_data_frame(session_uuid: &Uuid, packet: &[u8]) -> Vec<u8> {
+    build_data_frame(FRAME_TYPE_PACKET_DATA, session_uuid, packet)
+}
+
+/// Build a frame whose payload is a whole number of bytes
+fn build_data_frame(frame_type: u8, session_uuid: &Uuid, data: &[u8]) -> Vec<u8> {
+    // kind(1) + type(1) + uuid(16) + length(2) + data = 20 + data.len()
+    let mut buf = Vec::with_capacity(20 + data.len());
+    buf.push(BREW_CLASS_FRAME);
+    buf.push(frame_type);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    write_u16_le(&mut buf, (data.len() * 8) as u16);
+    buf.extend_from_slice(data);
+    buf
+}
+
+/// Returns the payload bytes of a frame, bounded by its length in bits
+pub fn frame_payload(frame: &BrewFrameMessage) -> &[u8] {
+    let len = (frame.length_bits as usize).div_ceil(8).min(frame.data.len());
+    &frame.data[..len]
+}
+
+/// Extract the digits of a FRAME_TYPE_DTMF_DATA message. Returns None if the frame carries no digits or
+/// anything else than the DTMF digits 0-9, *, # and A-D.
+pub fn parse_dtmf_digits(frame: &BrewFrameMessage) -> Option<String> {
+    let payload = frame_payload(frame);
+    let is_dtmf = |c: &u8| matches!(c.to_ascii_uppercase(), b'0'..=b'9' | b'*' | b'#' | b'A'..=b'D');
+    if payload.is_empty() || !payload.iter().all(is_dtmf) {
+        return None;
+    }
+    Some(payload.iter().map(|c| c.to_ascii_uppercase() as char).collect())
+}
+
 /// Build a service query (query subscriber profiles)
 pub fn build_query_subscribers(issis: &[u32]) -> Vec<u8> {
     let json = serde_json::to_string(issis).unwrap_or_else(|_| "[]".to_string());
@@ -643,6 +784,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_build_parse_setup_request() {
+        let uuid = Uuid::new_v4();
+        let built = build_setup_request(&uuid, 2620001, 2620002, 15, false, true, 0);
+        assert_eq!(built.len(), 63);
+
+        let msg = parse_brew_message(&built).unwrap();
+        if let BrewMessage::CallControl(cc) = msg {
+            assert_eq!(cc.call_state, CALL_STATE_SETUP_REQUEST);
+            assert_eq!(cc.identifier, uuid);
+            if let BrewCallPayload::CallSetup(setup) = cc.payload {
+                assert_eq!(setup.source, 2620001);
+                assert_eq!(setup.destination, 2620002);
+                assert_eq!(setup.priority, 15);
+                assert!(!setup.duplex);
+                assert!(setup.hook);
+                assert_eq!(setup.service, 0);
+            } else {
+                panic!("Expected CallSetup payload");
+            }
+        } else {
+            panic!("Expected CallControl message");
+        }
+
+        // Truncated set-up requests are rejected
+        assert!(parse_brew_message(&built[..50]).is_err());
+    }
+
+    #[test]
+    fn test_build_parse_individual_call_states() {
+        let uuid = Uuid::new_v4();
+
+        let msg = parse_brew_message(&build_simplex_granted(&uuid, 2620001)).unwrap();
+        let BrewMessage::CallControl(cc) = msg else {
+            panic!("Expected CallControl message");
+        };
+        assert_eq!(cc.call_state, CALL_STATE_SIMPLEX_GRANTED);
+        assert!(matches!(cc.payload, BrewCallPayload::SimplexGrant { source: 2620001 }));
+
+        for call_state in [CALL_STATE_CALL_ALERT, CALL_STATE_CONNECT_REQUEST, CALL_STATE_SIMPLEX_IDLE] {
+            let msg = parse_brew_message(&build_call_state(&uuid, call_state)).unwrap();
+            let BrewMessage::CallControl(cc) = msg else {
+                panic!("Expected CallControl message");
+            };
+            assert_eq!(cc.call_state, call_state);
+            assert!(matches!(cc.payload, BrewCallPayload::Empty));
+        }
+
+        let msg = parse_brew_message(&build_call_cause(&uuid, CALL_STATE_SETUP_REJECT, 2)).unwrap();
+        let BrewMessage::CallControl(cc) = msg else {
+            panic!("Expected CallControl message");
+        };
+        assert_eq!(cc.call_state, CALL_STATE_SETUP_REJECT);
+        assert!(matches!(cc.payload, BrewCallPayload::Cause(2)));
+    }
+
     #[test]
     fn test_build_parse_sds_report() {
         let uuid = Uuid::new_v4();
@@ -662,4 +859,46 @@ mod tests {
             panic!("Expected Frame message");
         }
     }
+
+    #[test]
+    fn test_build_parse_dtmf_frame() {
+        let uuid = Uuid::new_v4();
+        let built = build_dtmf_frame(&uuid, "12#");
+        assert_eq!(built.len(), 23);
+
+        let BrewMessage::Frame(frame) = parse_brew_message(&built).unwrap() else {
+            panic!("Expected Frame message");
+        };
+        assert_eq!(frame.frame_type, FRAME_TYPE_DTMF_DATA);
+        assert_eq!(frame.identifier, uuid);
+        assert_eq!(frame.length_bits, 24);
+        assert_eq!(parse_dtmf_digits(&frame).as_deref(), Some("12#"));
+
+        // Lower case digits are accepted, anything else is not
+        let BrewMessage::Frame(frame) = parse_brew_message(&build_dtmf_frame(&uuid, "*a")).unwrap() else {
+            panic!("Expected Frame message");
+        };
+        assert_eq!(parse_dtmf_digits(&frame).as_deref(), Some("*A"));
+        let BrewMessage::Frame(frame) = parse_brew_message(&build_dtmf_frame(&uuid, "1E")).unwrap() else {
+            panic!("Expected Frame message");
+        };
+        assert_eq!(parse_dtmf_digits(&frame), None);
+    }
+
+    #[test]
+    fn test_build_parse_packet_data_frame() {
+        let uuid = Uuid::new_v4();
+        let packet: Vec<u8> = (0..28).collect();
+        let mut built = build_packet_data_frame(&uuid, &packet);
+        assert_eq!(built[1], FRAME_TYPE_PACKET_DATA);
+
+        // Trailing bytes beyond the signalled length are not part of the payload
+        built.push(0xff);
+        let BrewMessage::Frame(frame) = parse_brew_message(&built).unwrap() else {
+            panic!("Expected Frame message");
+        };
+        assert_eq!(frame.frame_type, FRAME_TYPE_PACKET_DATA);
+        assert_eq!(frame.length_bits, 28 * 8);
+        assert_eq!(frame_payload(&frame), packet.as_slice());
+    }
 }
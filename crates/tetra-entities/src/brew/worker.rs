@@ -9,6 +9,7 @@ use std::time::{Duration, Instant};
 use crossbeam_channel::{Receiver, Sender};
 use tetra_config::bluestation::CfgBrew;
 use tetra_config::bluestation::SharedConfig;
+use tetra_saps::control::call_control::IndividualCallSignal;
 use tungstenite::{Message, WebSocket, stream::MaybeTlsStream};
 use uuid::Uuid;
 
@@ -42,6 +43,9 @@ pub enum BrewEvent {
     /// Group call ended
     GroupCallEnd { uuid: Uuid, cause: u8 },
 
+    /// Individual call signalling received for a network subscriber
+    IndividualCall { uuid: Uuid, signal: IndividualCallSignal },
+
     /// Voice frame received (ACELP traffic)
     VoiceFrame { uuid: Uuid, length_bits: u16, data: Vec<u8> },
 
@@ -60,6 +64,12 @@ pub enum BrewEvent {
     /// SDS report received
     SdsReport { uuid: Uuid, status: u8 },
 
+    /// DTMF digits received during a call
+    Dtmf { uuid: Uuid, digits: String },
+
+    /// IP packet received, to be delivered to the MS owning its destination address
+    PacketData { uuid: Uuid, packet: Vec<u8> },
+
     /// Error from server
     ServerError { error_type: u8, data: Vec<u8> },
 }
@@ -94,6 +104,9 @@ pub enum BrewCommand {
     /// Send GROUP_IDLE to TetraPack (transmission ended)
     SendGroupIdle { uuid: Uuid, cause: u8 },
 
+    /// Send individual call signalling of a local radio to TetraPack
+    SendIndividualCall { uuid: Uuid, signal: IndividualCallSignal },
+
     /// Send SDS to TetraPack (SHORT_TRANSFER + SDS_TRANSFER)
     SendSds {
         uuid: Uuid,
@@ -106,6 +119,12 @@ pub enum BrewCommand {
     /// Send SDS report to Brew (delivery acknowledgement)
     SendSdsReport { uuid: Uuid, status: u8 },
 
+    /// Send DTMF digits dialled by a local radio during a call
+    SendDtmf { uuid: Uuid, digits: String },
+
+    /// Send an IP packet from a PDP context of a local radio
+    SendPacketData { uuid: Uuid, packet: Vec<u8> },
+
     /// Disconnect gracefully
     Disconnect,
 }
@@ -274,6 +293,9 @@ pub struct BrewWorker {
     subscriber_groups: HashMap<u32, HashSet<u32>>,
     /// Pending SDS transfers keyed by UUID, awaiting matching SDS_TRANSFER frame
     pending_sds: HashMap<Uuid, PendingSds>,
+    /// Individual call sessions keyed by UUID, with whether the set-up was accepted.
+    /// A release before acceptance is sent as SETUP_REJECT, afterwards as CALL_RELEASE.
+    individual_calls: HashMap<Uuid, bool>,
 }
 
 impl BrewWorker {
@@ -286,6 +308,7 @@ impl BrewWorker {
             command_receiver,
             subscriber_groups: HashMap::new(),
             pending_sds: HashMap::new(),
+            individual_calls: HashMap::new(),
         }
     }
 
@@ -313,6 +336,8 @@ impl BrewWorker {
                         self.brew_config.reconnect_delay
                     );
                     let _ = self.event_sender.send(BrewEvent::Disconnected(e.clone()));
+                    // The entity releases all calls on disconnect
+                    self.individual_calls.clear();
                     std::thread::sleep(self.brew_config.reconnect_delay);
                 }
             }
@@ -702,6 +727,9 @@ impl BrewWorker {
                             tracing::debug!("BrewWorker: sent GROUP_IDLE uuid={} cause={}", uuid, cause);
                         }
                     }
+                    BrewCommand::SendIndividualCall { uuid, signal } => {
+                        self.send_individual_call(ws, uuid, signal);
+                    }
                     BrewCommand::SendSds {
                         uuid,
                         source,
@@ -742,6 +770,29 @@ impl BrewWorker {
                             tracing::debug!("BrewWorker: sent SDS_REPORT uuid={} status={}", uuid, status);
                         }
                     }
+                    BrewCommand::SendDtmf { uuid, digits } => {
+                        let msg = build_dtmf_frame(&uuid, &digits);
+                        if let Err(e) = ws.send(Message::Binary(msg)) {
+                            tracing::error!("BrewWorker: failed to send DTMF_DATA: {}", e);
+                        } else {
+                            tracing::debug!("BrewWorker: sent DTMF_DATA uuid={} digits={}", uuid, digits);
+                        }
+                    }
+                    BrewCommand::SendPacketData { uuid, packet } => {
+                        if !brew::feature_packet_data_enabled(&self.config) {
+                            tracing::warn!(
+                                "BrewWorker: ignoring SendPacketData command because packet data over Brew is disabled in config"
+                            );
+                            continue;
+                        }
+
+                        let msg = build_packet_data_frame(&uuid, &packet);
+                        if let Err(e) = ws.send(Message::Binary(msg)) {
+                            tracing::error!("BrewWorker: failed to send PACKET_DATA: {}", e);
+                        } else {
+                            tracing::trace!("BrewWorker: sent PACKET_DATA uuid={} {} bytes", uuid, packet.len());
+                        }
+                    }
                     BrewCommand::Disconnect => {
                         self.graceful_teardown(ws);
                         return Ok(());
@@ -751,6 +802,56 @@ impl BrewWorker {
         }
     }
 
+    /// Encode individual call signalling of a local radio as the matching Brew call state
+    fn send_individual_call(&mut self, ws: &mut WebSocket<MaybeTlsStream<TcpStream>>, uuid: Uuid, signal: IndividualCallSignal) {
+        let msg = match signal {
+            IndividualCallSignal::Setup {
+                calling_issi,
+                called_issi,
+                priority,
+                hook_method,
+            } => {
+                // Calls from local radios are set up as simplex calls
+                self.individual_calls.insert(uuid, true);
+                build_setup_request(&uuid, calling_issi, called_issi, priority, false, hook_method, 0)
+            }
+            IndividualCallSignal::Accept => {
+                self.individual_calls.insert(uuid, true);
+                build_call_state(&uuid, CALL_STATE_SETUP_ACCEPT)
+            }
+            IndividualCallSignal::Alert => build_call_state(&uuid, CALL_STATE_CALL_ALERT),
+            IndividualCallSignal::Connect => build_call_state(&uuid, CALL_STATE_CONNECT_REQUEST),
+            IndividualCallSignal::ConnectConfirm => build_call_state(&uuid, CALL_STATE_CONNECT_CONFIRM),
+            IndividualCallSignal::TxGranted { issi } => build_simplex_granted(&uuid, issi),
+            IndividualCallSignal::TxCeased => build_call_state(&uuid, CALL_STATE_SIMPLEX_IDLE),
+            IndividualCallSignal::Release { cause } => {
+                if self.individual_calls.remove(&uuid).unwrap_or(true) {
+                    build_call_cause(&uuid, CALL_STATE_CALL_RELEASE, cause)
+                } else {
+                    build_call_cause(&uuid, CALL_STATE_SETUP_REJECT, cause)
+                }
+            }
+        };
+        let call_state = msg[1];
+        if let Err(e) = ws.send(Message::Binary(msg)) {
+            tracing::error!("BrewWorker: failed to send call state {}: {}", call_state, e);
+        } else {
+            tracing::debug!("BrewWorker: sent call state {} uuid={}", call_state, uuid);
+        }
+    }
+
+    /// Forward individual call signalling for a known session to the entity
+    fn forward_individual_call(&mut self, uuid: Uuid, signal: IndividualCallSignal) {
+        if !self.individual_calls.contains_key(&uuid) {
+            tracing::debug!("BrewWorker: {:?} for unknown individual call uuid={}", signal, uuid);
+            return;
+        }
+        if matches!(signal, IndividualCallSignal::Release { .. }) {
+            self.individual_calls.remove(&uuid);
+        }
+        let _ = self.event_sender.send(BrewEvent::IndividualCall { uuid, signal });
+    }
+
     /// Parse an incoming binary Brew message and forward as event
     fn handle_incoming_binary(&mut self, data: &[u8]) {
         match parse_brew_message(data) {
@@ -797,7 +898,7 @@ impl BrewWorker {
                         gt.priority,
                         gt.service
                     );
-                    if !brew::is_brew_gssi_routable(&self.config, gt.destination) {
+                    if !brew::is_brew_call_routable(&self.config, gt.destination, gt.priority) {
                         tracing::warn!("BrewWorker: dropping GROUP_TX to non-routable GSSI {}", gt.destination);
                         return;
                     };
@@ -822,6 +923,10 @@ impl BrewWorker {
             CALL_STATE_CALL_RELEASE => {
                 let cause = if let BrewCallPayload::Cause(c) = cc.payload { c } else { 0 };
                 tracing::info!("BrewWorker: CALL_RELEASE uuid={} cause={}", cc.identifier, cause);
+                if self.individual_calls.contains_key(&cc.identifier) {
+                    self.forward_individual_call(cc.identifier, IndividualCallSignal::Release { cause });
+                    return;
+                }
                 // TODO FIXME we could check whether this call is indeed a brew call here
                 let _ = self.event_sender.send(BrewEvent::GroupCallEnd {
                     uuid: cc.identifier,
@@ -847,6 +952,45 @@ impl BrewWorker {
                     );
                 }
             }
+            CALL_STATE_SETUP_REQUEST => {
+                if let BrewCallPayload::CallSetup(setup) = cc.payload {
+                    tracing::info!(
+                        "BrewWorker: SETUP_REQUEST uuid={} src={} dst={} prio={} duplex={} hook={}",
+                        cc.identifier,
+                        setup.source,
+                        setup.destination,
+                        setup.priority,
+                        setup.duplex,
+                        setup.hook
+                    );
+                    // Duplex requests are downgraded, network calls are bridged as simplex calls
+                    self.individual_calls.insert(cc.identifier, false);
+                    let _ = self.event_sender.send(BrewEvent::IndividualCall {
+                        uuid: cc.identifier,
+                        signal: IndividualCallSignal::Setup {
+                            calling_issi: setup.source,
+                            called_issi: setup.destination,
+                            priority: setup.priority,
+                            hook_method: setup.hook,
+                        },
+                    });
+                }
+            }
+            CALL_STATE_SETUP_ACCEPT => self.forward_individual_call(cc.identifier, IndividualCallSignal::Accept),
+            CALL_STATE_SETUP_REJECT => {
+                let cause = if let BrewCallPayload::Cause(c) = cc.payload { c } else { 0 };
+                tracing::info!("BrewWorker: SETUP_REJECT uuid={} cause={}", cc.identifier, cause);
+                self.forward_individual_call(cc.identifier, IndividualCallSignal::Release { cause });
+            }
+            CALL_STATE_CALL_ALERT => self.forward_individual_call(cc.identifier, IndividualCallSignal::Alert),
+            CALL_STATE_CONNECT_REQUEST => self.forward_individual_call(cc.identifier, IndividualCallSignal::Connect),
+            CALL_STATE_CONNECT_CONFIRM => self.forward_individual_call(cc.identifier, IndividualCallSignal::ConnectConfirm),
+            CALL_STATE_SIMPLEX_GRANTED => {
+                if let BrewCallPayload::SimplexGrant { source } = cc.payload {
+                    self.forward_individual_call(cc.identifier, IndividualCallSignal::TxGranted { issi: source });
+                }
+            }
+            CALL_STATE_SIMPLEX_IDLE => self.forward_individual_call(cc.identifier, IndividualCallSignal::TxCeased),
             state => {
                 tracing::debug!("BrewWorker: unhandled call state {} uuid={}", state, cc.identifier);
             }
@@ -913,6 +1057,33 @@ impl BrewWorker {
                     status,
                 });
             }
+            FRAME_TYPE_DTMF_DATA => {
+                let Some(digits) = parse_dtmf_digits(&frame) else {
+                    tracing::warn!(
+                        "BrewWorker: ignoring invalid DTMF_DATA uuid={} {} bytes",
+                        frame.identifier,
+                        frame.data.len()
+                    );
+                    return;
+                };
+                tracing::debug!("BrewWorker: DTMF_DATA uuid={} digits={}", frame.identifier, digits);
+                let _ = self.event_sender.send(BrewEvent::Dtmf {
+                    uuid: frame.identifier,
+                    digits,
+                });
+            }
+            FRAME_TYPE_PACKET_DATA => {
+                if !brew::feature_packet_data_enabled(&self.config) {
+                    tracing::debug!("BrewWorker: ignoring incoming PACKET_DATA because packet data over Brew is disabled in config");
+                    return;
+                }
+                let packet = frame_payload(&frame).to_vec();
+                tracing::trace!("BrewWorker: PACKET_DATA uuid={} {} bytes", frame.identifier, packet.len());
+                let _ = self.event_sender.send(BrewEvent::PacketData {
+                    uuid: frame.identifier,
+                    packet,
+                });
+            }
             ft => {
                 tracing::debug!("BrewWorker: unhandled frame type {} uuid={}", ft, frame.identifier);
             }
@@ -24,7 +24,7 @@ impl CmceBs {
             config: config.clone(),
             sds: SdsBsSubentity::new(config.clone()),
             cc: CcBsSubentity::new(config.clone()),
-            ss: SsBsSubentity::new(),
+            ss: SsBsSubentity::new(config.clone()),
         }
     }
 
@@ -63,8 +63,7 @@ impl CmceBs {
                 self.sds.route_rf_deliver(_queue, message);
             }
             CmcePduTypeUl::UFacility => {
-                unimplemented_log!("{:?}", pdu_type);
-                // self.ss.route_xx_deliver(_queue, message);
+                self.ss.route_re_deliver(_queue, message);
             }
             CmcePduTypeUl::CmceFunctionNotSupported => {
                 unimplemented_log!("{:?}", pdu_type);
@@ -85,6 +84,7 @@ impl TetraEntityTrait for CmceBs {
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
         // Propagate tick to subentities
         self.cc.tick_start(queue, ts);
+        self.sds.tick_start(queue, ts);
     }
 
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -105,10 +105,11 @@ impl TetraEntityTrait for CmceBs {
                     self.cc.rx_call_control(queue, message);
                 }
                 SapMsgInner::MmSubscriberUpdate(update) => {
+                    self.sds.handle_subscriber_update(message.dltime, &update);
                     self.cc.handle_subscriber_update(queue, update);
                 }
                 SapMsgInner::CmceSdsData(_) => {
-                    self.sds.rx_sds_from_brew(queue, message);
+                    self.sds.rx_sds_from_network(queue, message);
                 }
                 _ => {
                     panic!("Unexpected control message: {:?}", message.msg);
@@ -1,7 +1,7 @@
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
-use tetra_core::Sap;
 use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{Sap, TdmaTime, unimplemented_log};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
@@ -21,9 +21,9 @@ pub struct CmceMs {
 impl CmceMs {
     pub fn new(config: SharedConfig) -> Self {
         Self {
-            config,
-            sds: SdsMsSubentity::new(),
-            cc: CcMsSubentity::new(),
+            config: config.clone(),
+            sds: SdsMsSubentity::new(config.clone()),
+            cc: CcMsSubentity::new(config.clone()),
             ss: SsMsSubentity::new(),
         }
     }
@@ -68,7 +68,49 @@ impl CmceMs {
                 self.cc.route_rd_deliver(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_unitdata_ind: {}", pdu_type);
+            }
+        }
+    }
+
+    fn rx_lcmc_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        match message.msg {
+            SapMsgInner::LcmcMleUnitdataInd(_) => {
+                self.rx_unitdata_ind(queue, message);
+            }
+            _ => {
+                unimplemented_log!("rx_lcmc_prim: {:?}", message.msg);
+            }
+        }
+    }
+
+    /// Requests from the CC user (TNCC-SAP)
+    fn rx_tncc_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        match message.msg {
+            SapMsgInner::TnccSetupReq(prim) => {
+                self.cc.rx_tncc_setup_req(queue, prim.called_ssi);
+            }
+            SapMsgInner::TnccTxReq(prim) => {
+                self.cc.rx_tncc_tx_req(queue, prim.call_id, prim.demand);
+            }
+            SapMsgInner::TnccReleaseReq(prim) => {
+                self.cc.rx_tncc_release_req(queue, prim.call_id);
+            }
+            _ => {
+                unimplemented_log!("rx_tncc_prim: {:?}", message.msg);
+            }
+        }
+    }
+
+    /// Requests from the SDS user (TNSDS-SAP)
+    fn rx_tnsds_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        match message.msg {
+            SapMsgInner::TnsdsUnitdataReq(prim) => {
+                self.sds
+                    .rx_tnsds_unitdata_req(queue, message.dltime, prim.called_ssi, prim.user_data);
+            }
+            _ => {
+                unimplemented_log!("rx_tnsds_prim: {:?}", message.msg);
             }
         }
     }
@@ -87,16 +129,17 @@ impl TetraEntityTrait for CmceMs {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for CMCE
-        assert!(message.sap == Sap::LcmcSap);
-
-        match message.msg {
-            SapMsgInner::LcmcMleUnitdataInd(_) => {
-                self.rx_unitdata_ind(queue, message);
-            }
+        match message.sap {
+            Sap::LcmcSap => self.rx_lcmc_prim(queue, message),
+            Sap::TnccSap => self.rx_tncc_prim(queue, message),
+            Sap::TnsdsSap => self.rx_tnsds_prim(queue, message),
             _ => {
-                panic!();
+                unimplemented_log!("rx_prim: unexpected sap {:?}: {:?}", message.sap, message.msg);
             }
         }
     }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.cc.tick_start(queue, ts);
+    }
 }
@@ -0,0 +1,76 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use tetra_core::TdmaTime;
+
+/// Events operators need to act on
+#[derive(Debug, Serialize)]
+#[serde(tag = "alarm", rename_all = "snake_case")]
+pub enum Alarm {
+    /// An MS started an emergency call (call priority 15)
+    EmergencyCall {
+        calling_issi: u32,
+        called_ssi: u32,
+        /// True for an individual call, false for a group call
+        individual: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct AlarmRecord<'a> {
+    time: String,
+    tdma: String,
+    #[serde(flatten)]
+    alarm: &'a Alarm,
+}
+
+/// Raises operator alarms. Each alarm is logged at error level, and appended to the alarm log file
+/// as a JSON object per line if one is configured.
+pub struct AlarmLog {
+    writer: Option<Box<dyn Write + Send>>,
+}
+
+impl AlarmLog {
+    pub fn new(writer: Option<Box<dyn Write + Send>>) -> Self {
+        Self { writer }
+    }
+
+    /// Appends to the given file, creating it if needed. Without a file, alarms only go to the log.
+    pub fn open(path: Option<&str>) -> Self {
+        let Some(path) = path else {
+            return Self::new(None);
+        };
+        match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => Self::new(Some(Box::new(file))),
+            Err(e) => {
+                tracing::error!("AlarmLog: failed to open {}: {}, alarms are only logged", path, e);
+                Self::new(None)
+            }
+        }
+    }
+
+    pub fn raise(&mut self, dltime: TdmaTime, alarm: &Alarm) {
+        tracing::error!(ts=%dltime, "ALARM: {:?}", alarm);
+
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let record = AlarmRecord {
+            time: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            tdma: dltime.to_string(),
+            alarm,
+        };
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("AlarmLog: failed to serialize {:?}: {}", alarm, e);
+                return;
+            }
+        };
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            tracing::error!("AlarmLog: write failed: {}", e);
+        }
+    }
+}
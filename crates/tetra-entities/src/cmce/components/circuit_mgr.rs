@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 
-use tetra_core::{Direction, TdmaTime, TimeslotAllocator, TimeslotOwner, frames, multiframes};
+use tetra_core::{Direction, MAX_TRAFFIC_CHANNELS, TdmaTime, TimeslotAllocator, TimeslotOwner, frames, multiframes};
 use tetra_pdus::cmce::structs::cmce_circuit::CmceCircuit;
 use tetra_saps::{
     control::enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType},
@@ -26,12 +26,12 @@ pub struct CircuitMgr {
     pub dltime: TdmaTime,
 
     /// Holds any Dl and Dl+Ul circuits
-    pub dl: [Option<CmceCircuit>; 4],
+    pub dl: [Option<CmceCircuit>; MAX_TRAFFIC_CHANNELS],
     /// Holds any Ul-only circuits, with no recipients on this cell
-    pub ul_only: [Option<CmceCircuit>; 4],
+    pub ul_only: [Option<CmceCircuit>; MAX_TRAFFIC_CHANNELS],
 
-    /// Data blocks queued to be transmitted, per timeslot
-    pub tx_data: [VecDeque<Vec<u8>>; 4],
+    /// Data blocks queued to be transmitted, per traffic channel
+    pub tx_data: [VecDeque<Vec<u8>>; MAX_TRAFFIC_CHANNELS],
 
     /// 14-bit call identifier. Zero value is reserved.
     pub next_call_identifier: u16,
@@ -43,9 +43,9 @@ impl CircuitMgr {
     pub fn new() -> Self {
         Self {
             dltime: TdmaTime::default(),
-            dl: [None, None, None, None],
-            ul_only: [None, None, None, None],
-            tx_data: [VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            dl: [const { None }; MAX_TRAFFIC_CHANNELS],
+            ul_only: [const { None }; MAX_TRAFFIC_CHANNELS],
+            tx_data: [const { VecDeque::new() }; MAX_TRAFFIC_CHANNELS],
             next_call_identifier: 4,
             next_usage_number: 4,
         }
@@ -162,6 +162,7 @@ impl CircuitMgr {
             simplex_duplex: false,   // TODO, simplex only for now
             speech_service: Some(0), // TODO, only TETRA encoded speech for now
             etee_encrypted: false,   // TODO, no encryption for now
+            peer_ts: None,
         };
 
         // Register circuit and return
@@ -175,11 +176,25 @@ impl CircuitMgr {
         comm_type: CommunicationType,
         timeslot_alloc: &mut TimeslotAllocator,
         owner: TimeslotOwner,
+    ) -> Result<&CmceCircuit, CircuitErr> {
+        let call_id = self.get_next_call_id();
+        self.allocate_circuit_for_call(call_id, dir, comm_type, timeslot_alloc, owner)
+    }
+
+    /// Allocate circuit using centralized timeslot allocator, for a call identifier that was
+    /// already handed out earlier (e.g. individual calls, where the call id is assigned at
+    /// U-SETUP but the traffic channel is only allocated once the called party connects)
+    pub fn allocate_circuit_for_call(
+        &mut self,
+        call_id: CallId,
+        dir: Direction,
+        comm_type: CommunicationType,
+        timeslot_alloc: &mut TimeslotAllocator,
+        owner: TimeslotOwner,
     ) -> Result<&CmceCircuit, CircuitErr> {
         // Get timeslot from centralized allocator
         let ts = timeslot_alloc.allocate_any(owner).ok_or(CircuitErr::NoCircuitFree)?;
 
-        let call_id = self.get_next_call_id();
         let usage = self.get_next_usage_number();
 
         // Create circuit
@@ -194,12 +209,67 @@ impl CircuitMgr {
             simplex_duplex: false,
             speech_service: Some(0),
             etee_encrypted: false,
+            peer_ts: None,
         };
 
         // Register circuit and return
         Ok(self.open_circuit(dir, circuit)?)
     }
 
+    /// Allocate the two circuits of a duplex individual call, one DL+UL circuit per party.
+    /// The UL of each circuit is cross-connected to the DL of the other (see `peer_ts`).
+    /// Returns (calling party circuit, called party circuit). Either both or none are allocated.
+    pub fn allocate_duplex_circuits_for_call(
+        &mut self,
+        call_id: CallId,
+        comm_type: CommunicationType,
+        timeslot_alloc: &mut TimeslotAllocator,
+        owner: TimeslotOwner,
+    ) -> Result<(CmceCircuit, CmceCircuit), CircuitErr> {
+        let ts_a = timeslot_alloc.allocate_any(owner).ok_or(CircuitErr::NoCircuitFree)?;
+        let Some(ts_b) = timeslot_alloc.allocate_any(owner) else {
+            if let Err(err) = timeslot_alloc.release(owner, ts_a) {
+                tracing::warn!("CircuitMgr: failed to release timeslot ts={} err={:?}", ts_a, err);
+            }
+            return Err(CircuitErr::NoCircuitFree);
+        };
+
+        let mut circuits = Vec::with_capacity(2);
+        for (ts, peer_ts) in [(ts_a, ts_b), (ts_b, ts_a)] {
+            let usage = self.get_next_usage_number();
+            let circuit = CmceCircuit {
+                ts_created: self.dltime,
+                direction: Direction::Both,
+                ts,
+                call_id,
+                usage,
+                circuit_mode: CircuitModeType::TchS,
+                comm_type,
+                simplex_duplex: true,
+                speech_service: Some(0),
+                etee_encrypted: false,
+                peer_ts: Some(peer_ts),
+            };
+            match self.open_circuit(Direction::Both, circuit) {
+                Ok(circuit) => circuits.push(circuit.clone()),
+                Err(err) => {
+                    // Roll back whatever was opened so far
+                    for opened in &circuits {
+                        let _ = self.close_circuit(Direction::Both, opened.ts);
+                    }
+                    for ts in [ts_a, ts_b] {
+                        let _ = timeslot_alloc.release(owner, ts);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        let called = circuits.pop().unwrap();
+        let calling = circuits.pop().unwrap();
+        Ok((calling, called))
+    }
+
     /// Closes any active circuits for given timeslot and direction.
     /// Returns the CmceCircuit
     /// When direction is Both, closes both directions
@@ -310,6 +380,10 @@ impl CircuitMgr {
             // Late entry: resend D-SETUP every 5 seconds
             for circuit in self.dl.iter() {
                 if let Some(circuit) = circuit {
+                    // Individual calls have no late entry, both parties are already on the channel
+                    if circuit.comm_type == CommunicationType::P2p {
+                        continue;
+                    }
                     let age = circuit.ts_created.age(dltime);
 
                     // Send D-SETUP for the initial frame + 1 backup frame after circuit creation.
@@ -1 +1,6 @@
+pub mod alarm_log;
 pub mod circuit_mgr;
+pub mod sds_reassembly;
+pub mod sds_store;
+pub mod sds_tl_tracker;
+pub mod ss;
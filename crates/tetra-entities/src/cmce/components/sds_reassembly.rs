@@ -0,0 +1,89 @@
+use std::collections::{BTreeMap, HashMap};
+
+use tetra_core::{TdmaTime, hyperframes};
+use tetra_pdus::cmce::enums::sds_protocol_id::SdsProtocolId;
+use tetra_pdus::cmce::sds_tl::sds_transfer::SdsTransfer;
+use tetra_pdus::cmce::sds_tl::user_data_header::UserDataHeader;
+
+/// How long the parts of a concatenated message are kept while waiting for the missing parts
+const REASSEMBLY_TIMEOUT: i32 = hyperframes!(5);
+
+/// A concatenated message of which some parts have arrived
+struct PartialMessage {
+    total_parts: u8,
+    /// Part number → user data header and payload of the part
+    parts: BTreeMap<u8, (UserDataHeader, Vec<u8>)>,
+    started_at: TdmaTime,
+}
+
+/// Reassembly of concatenated SDS-TL messages, per sender, destination and concatenation reference
+#[derive(Default)]
+pub struct SdsReassembly {
+    partial: HashMap<(u32, u32, u16), PartialMessage>,
+}
+
+impl SdsReassembly {
+    /// Takes an SDS-TRANSFER from `source` to `dest`, returning the whole message once it is complete. Messages that
+    /// are not part of a concatenated message are returned as is. The whole message has the header fields and
+    /// message reference of the part completing it, and the user data header of the first part without its
+    /// concatenation element, followed by the payloads of all parts in order. A repeated part replaces the earlier copy.
+    pub fn add(&mut self, source: u32, dest: u32, transfer: SdsTransfer, now: TdmaTime) -> Option<SdsTransfer> {
+        let uses_header = matches!(
+            SdsProtocolId::try_from(transfer.protocol_identifier as u64),
+            Ok(SdsProtocolId::MessageWithUserDataHeader | SdsProtocolId::ConcatenatedSdsMessageSdsTl)
+        );
+        if !uses_header {
+            return Some(transfer);
+        }
+        let Ok((header, payload)) = UserDataHeader::split(&transfer.user_data) else {
+            return Some(transfer);
+        };
+        let Some(concatenation) = header.concatenation() else {
+            return Some(transfer);
+        };
+        let payload = payload.to_vec();
+
+        let key = (source, dest, concatenation.reference);
+        let partial = self.partial.entry(key).or_insert_with(|| PartialMessage {
+            total_parts: concatenation.total_parts,
+            parts: BTreeMap::new(),
+            started_at: now,
+        });
+        if partial.total_parts != concatenation.total_parts {
+            // The reference was reused for a new message
+            partial.total_parts = concatenation.total_parts;
+            partial.parts.clear();
+            partial.started_at = now;
+        }
+        partial.parts.insert(concatenation.part_number, (header, payload));
+
+        let complete = (1..=partial.total_parts).all(|part_number| partial.parts.contains_key(&part_number));
+        if !complete {
+            return None;
+        }
+
+        let mut parts = self.partial.remove(&key)?.parts.into_values();
+        let (mut header, first_payload) = parts.next()?;
+        header.remove_concatenation();
+        let mut user_data = header.to_bytes();
+        user_data.extend(first_payload);
+        for (_, payload) in parts {
+            user_data.extend(payload);
+        }
+        Some(SdsTransfer { user_data, ..transfer })
+    }
+
+    /// Drops incomplete messages whose missing parts did not arrive in time. Returns (sender, destination)
+    /// of each dropped message.
+    pub fn take_expired(&mut self, now: TdmaTime) -> Vec<(u32, u32)> {
+        let mut expired = Vec::new();
+        self.partial.retain(|(source, dest, _), partial| {
+            let keep = partial.started_at.age(now) <= REASSEMBLY_TIMEOUT;
+            if !keep {
+                expired.push((*source, *dest));
+            }
+            keep
+        });
+        expired
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use tetra_config::bluestation::CfgSdsStore;
+use tetra_core::{TdmaTime, TxReporter, TxState, hyperframes, multiframes};
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+
+/// Delay between a registration and the delivery of the messages stored for it, so that the
+/// registration exchange is completed first
+const DELIVERY_DELAY: i32 = multiframes!(1);
+
+/// Delay before delivery is attempted again to a registered ISSI that did not acknowledge a message
+const RETRY_INTERVAL: i32 = hyperframes!(1);
+
+/// An SDS waiting for its destination to become reachable
+#[derive(Debug, Clone)]
+pub struct StoredSds {
+    pub source_issi: u32,
+    pub dest_issi: u32,
+    pub user_defined_data: SdsUserData,
+    pub stored_at: TdmaTime,
+}
+
+/// Store-and-forward queue for SDS to ISSIs that are not registered or did not acknowledge delivery.
+/// Messages are delivered once their destination registers, and expire after the validity period.
+pub struct SdsStore {
+    /// Validity period, in timeslots
+    validity: i32,
+    max_messages_per_issi: usize,
+    /// Destination ISSI → stored messages, oldest first
+    queues: BTreeMap<u32, VecDeque<StoredSds>>,
+    /// Destination ISSI → time at which its stored messages are to be delivered
+    next_attempt: HashMap<u32, TdmaTime>,
+    /// Messages sent to their destination, awaiting the layer 2 outcome
+    in_flight: Vec<(TxReporter, StoredSds)>,
+}
+
+impl SdsStore {
+    pub fn new(cfg: &CfgSdsStore) -> Self {
+        // A timeslot lasts 85/6 ms
+        let validity = (cfg.validity_period.as_millis() * 6 / 85) as i32;
+        Self {
+            validity,
+            max_messages_per_issi: cfg.max_messages_per_issi,
+            queues: BTreeMap::new(),
+            next_attempt: HashMap::new(),
+            in_flight: Vec::new(),
+        }
+    }
+
+    /// Queue a message for later delivery. Returns false if the queue of the destination is full.
+    pub fn store(&mut self, msg: StoredSds) -> bool {
+        let queue = self.queues.entry(msg.dest_issi).or_default();
+        if queue.len() >= self.max_messages_per_issi {
+            return false;
+        }
+        queue.push_back(msg);
+        true
+    }
+
+    /// Number of messages stored for the given ISSI, excluding messages being delivered
+    pub fn stored_for(&self, issi: u32) -> usize {
+        self.queues.get(&issi).map_or(0, VecDeque::len)
+    }
+
+    /// The ISSI registered: deliver its stored messages shortly
+    pub fn registered(&mut self, issi: u32, now: TdmaTime) {
+        if self.stored_for(issi) > 0 {
+            self.next_attempt.insert(issi, now.add_timeslots(DELIVERY_DELAY));
+        }
+    }
+
+    /// Takes the stored messages that are due for delivery, for destinations accepted by `reachable`.
+    /// Messages for destinations that are not reachable stay stored until their next registration.
+    pub fn take_due(&mut self, now: TdmaTime, reachable: impl Fn(u32) -> bool) -> Vec<StoredSds> {
+        let due: Vec<u32> = self
+            .next_attempt
+            .iter()
+            .filter(|(_, at)| at.age(now) >= 0)
+            .map(|(issi, _)| *issi)
+            .collect();
+
+        let mut msgs = Vec::new();
+        for issi in due {
+            self.next_attempt.remove(&issi);
+            if reachable(issi)
+                && let Some(queue) = self.queues.remove(&issi)
+            {
+                msgs.extend(queue);
+            }
+        }
+        msgs
+    }
+
+    /// Keep track of a message sent to its destination, so it can be stored again if delivery fails
+    pub fn track(&mut self, reporter: TxReporter, msg: StoredSds) {
+        self.in_flight.push((reporter, msg));
+    }
+
+    /// Stores the messages whose delivery failed again, to be retried later
+    pub fn check_deliveries(&mut self, now: TdmaTime) {
+        let mut failed = Vec::new();
+        self.in_flight.retain(|(reporter, msg)| match reporter.get_state() {
+            TxState::Lost | TxState::Discarded => {
+                failed.push(msg.clone());
+                false
+            }
+            _ => !reporter.is_in_final_state(),
+        });
+
+        // Failed messages go back to the head of their queue, keeping the delivery order
+        for msg in failed.into_iter().rev() {
+            tracing::info!("SDS store: delivery to ISSI {} failed, storing message", msg.dest_issi);
+            let issi = msg.dest_issi;
+            self.queues.entry(issi).or_default().push_front(msg);
+            self.next_attempt.insert(issi, now.add_timeslots(RETRY_INTERVAL));
+        }
+    }
+
+    /// Takes the messages whose validity period has passed. Messages still being delivered when
+    /// they expire are no longer tracked.
+    pub fn take_expired(&mut self, now: TdmaTime) -> Vec<StoredSds> {
+        let validity = self.validity;
+        self.in_flight.retain(|(_, msg)| msg.stored_at.age(now) <= validity);
+
+        let mut expired = Vec::new();
+        for queue in self.queues.values_mut() {
+            while queue.front().is_some_and(|msg| msg.stored_at.age(now) > validity) {
+                expired.extend(queue.pop_front());
+            }
+        }
+        self.queues.retain(|_, queue| !queue.is_empty());
+        self.next_attempt.retain(|issi, _| self.queues.contains_key(issi));
+        expired
+    }
+}
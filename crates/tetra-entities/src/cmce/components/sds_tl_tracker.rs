@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use tetra_core::{TdmaTime, hyperframes};
+use tetra_pdus::cmce::enums::{delivery_report_request::DeliveryReportRequest, delivery_status::DeliveryStatus};
+use tetra_pdus::cmce::sds_tl::sds_transfer::SdsTransfer;
+
+/// How long a message is remembered while waiting for the reports on it
+const REPORT_TIMEOUT: i32 = hyperframes!(60);
+
+/// An SDS-TRANSFER whose sender requested delivery reports
+struct AwaitingReport {
+    protocol_identifier: u8,
+    delivery_report_request: DeliveryReportRequest,
+    sent_at: TdmaTime,
+}
+
+/// Tracks the message references of SDS-TL messages awaiting delivery reports, per sender and destination.
+/// The SDS-SHORT REPORT does not carry the protocol identifier, which is recovered from the reported message.
+#[derive(Default)]
+pub struct SdsTlTracker {
+    /// (sender ISSI, destination SSI, message reference) → message awaiting reports
+    awaiting: HashMap<(u32, u32, u8), AwaitingReport>,
+}
+
+impl SdsTlTracker {
+    /// Remember an SDS-TRANSFER from `source` to `dest`, if delivery reports are requested for it.
+    /// A message reusing the reference of an earlier message replaces it.
+    pub fn transfer(&mut self, source: u32, dest: u32, transfer: &SdsTransfer, now: TdmaTime) {
+        if !transfer.delivery_report_request.any() {
+            return;
+        }
+        self.awaiting.insert(
+            (source, dest, transfer.message_reference),
+            AwaitingReport {
+                protocol_identifier: transfer.protocol_identifier,
+                delivery_report_request: transfer.delivery_report_request,
+                sent_at: now,
+            },
+        );
+    }
+
+    /// A report from `reporter` on message `message_reference` of `recipient`, who sent the reported message.
+    /// Returns the protocol identifier of the reported message, if known. The message is forgotten once no
+    /// further reports are expected on it.
+    pub fn report(&mut self, reporter: u32, recipient: u32, message_reference: u8, delivery_status: u8) -> Option<u8> {
+        let key = (recipient, reporter, message_reference);
+        let awaiting = self.awaiting.get(&key)?;
+        let protocol_identifier = awaiting.protocol_identifier;
+
+        let more_expected = match DeliveryStatus::try_from(delivery_status as u64) {
+            Ok(DeliveryStatus::ReceiptAcknowledgedByDestination) => awaiting.delivery_report_request.consumed(),
+            // Stored by the SwMI, the destination reports later
+            Ok(
+                DeliveryStatus::CongestionMessageStored
+                | DeliveryStatus::MessageStored
+                | DeliveryStatus::DestinationNotReachableMessageStored,
+            ) => true,
+            _ => false,
+        };
+        if !more_expected {
+            self.awaiting.remove(&key);
+        }
+        Some(protocol_identifier)
+    }
+
+    /// Forget messages that have been awaiting reports for too long
+    pub fn expire(&mut self, now: TdmaTime) {
+        self.awaiting.retain(|_, awaiting| awaiting.sent_at.age(now) <= REPORT_TIMEOUT);
+    }
+}
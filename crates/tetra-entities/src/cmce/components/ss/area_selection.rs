@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::BitBuffer;
+use tetra_pdus::cmce::enums::{ss_pdu_type::SsPduType, ss_type::SsType};
+
+use super::{SsHandler, SsReply};
+
+/// Area selection.
+///
+/// Activate carries the 4-bit area an MS selects for its calls, 0 standing for all areas. Results carry the
+/// active flag, followed by the selected area while a selection is active. The selection is only recorded:
+/// a single cell forms one area, so it does not change how calls are set up.
+#[derive(Default)]
+pub struct AreaSelection {
+    /// ISSI → selected area
+    selections: HashMap<u32, u8>,
+}
+
+impl AreaSelection {
+    fn result(area: Option<u8>) -> SsReply {
+        let mut data = BitBuffer::new_autoexpand(5);
+        data.write_bit(area.is_some() as u8);
+        if let Some(area) = area {
+            data.write_bits(area as u64, 4);
+        }
+        SsReply::Result(data)
+    }
+}
+
+impl SsHandler for AreaSelection {
+    fn ss_types(&self) -> &[SsType] {
+        &[SsType::AreaSelection]
+    }
+
+    fn handle(
+        &mut self,
+        _config: &SharedConfig,
+        issi: u32,
+        _ss_type: SsType,
+        pdu_type: SsPduType,
+        data: &mut BitBuffer,
+    ) -> Option<SsReply> {
+        match pdu_type {
+            SsPduType::Activate => {
+                let Some(area) = data.read_bits(4) else {
+                    tracing::info!("SS: rejecting area selection by ISSI {}: no area given", issi);
+                    return Some(SsReply::Reject);
+                };
+                tracing::info!("SS: ISSI {} selected area {}", issi, area);
+                self.selections.insert(issi, area as u8);
+                Some(Self::result(Some(area as u8)))
+            }
+            SsPduType::Deactivate => {
+                self.selections.remove(&issi);
+                Some(Self::result(None))
+            }
+            SsPduType::Interrogate => Some(Self::result(self.selections.get(&issi).copied())),
+            SsPduType::Result | SsPduType::Reject => None,
+        }
+    }
+}
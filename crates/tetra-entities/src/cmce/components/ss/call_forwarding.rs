@@ -0,0 +1,64 @@
+use tetra_config::bluestation::{ForwardingCondition, SharedConfig};
+use tetra_core::BitBuffer;
+use tetra_pdus::cmce::enums::{ss_pdu_type::SsPduType, ss_type::SsType};
+
+use super::{SsHandler, SsReply};
+
+/// Call forwarding unconditional, on busy and on not reachable.
+///
+/// Activate carries the 24-bit ISSI calls are forwarded to. Results carry the active flag, followed by the
+/// forwarded-to ISSI while forwarding is active. Forwarding is recorded in the stack state, where call control
+/// applies it to individual calls. Forwarding on no reply needs a reply timer and is not offered.
+pub struct CallForwarding;
+
+impl CallForwarding {
+    fn condition(ss_type: SsType) -> ForwardingCondition {
+        match ss_type {
+            SsType::Cfu => ForwardingCondition::Unconditional,
+            SsType::Cfb => ForwardingCondition::Busy,
+            SsType::Cfnrc => ForwardingCondition::NotReachable,
+            _ => panic!("not a call forwarding service: {}", ss_type),
+        }
+    }
+
+    fn result(forwarded_to: Option<u32>) -> SsReply {
+        let mut data = BitBuffer::new_autoexpand(25);
+        data.write_bit(forwarded_to.is_some() as u8);
+        if let Some(issi) = forwarded_to {
+            data.write_bits(issi as u64, 24);
+        }
+        SsReply::Result(data)
+    }
+}
+
+impl SsHandler for CallForwarding {
+    fn ss_types(&self) -> &[SsType] {
+        &[SsType::Cfu, SsType::Cfb, SsType::Cfnrc]
+    }
+
+    fn handle(&mut self, config: &SharedConfig, issi: u32, ss_type: SsType, pdu_type: SsPduType, data: &mut BitBuffer) -> Option<SsReply> {
+        let condition = Self::condition(ss_type);
+        match pdu_type {
+            SsPduType::Activate => {
+                let forwarded_to = data.read_bits(24).map(|ssi| ssi as u32);
+                let Some(forwarded_to) = forwarded_to.filter(|ssi| *ssi != 0 && *ssi != issi) else {
+                    tracing::info!("SS: rejecting {} activation by ISSI {}: invalid forwarded-to ISSI", ss_type, issi);
+                    return Some(SsReply::Reject);
+                };
+                tracing::info!("SS: ISSI {} activated {}, forwarded to ISSI {}", issi, ss_type, forwarded_to);
+                config.state_write().call_forwarding.activate(issi, condition, forwarded_to);
+                Some(Self::result(Some(forwarded_to)))
+            }
+            SsPduType::Deactivate => {
+                tracing::info!("SS: ISSI {} deactivated {}", issi, ss_type);
+                config.state_write().call_forwarding.deactivate(issi, condition);
+                Some(Self::result(None))
+            }
+            SsPduType::Interrogate => {
+                let forwarded_to = config.state_read().call_forwarding.forwarded_to(issi, condition);
+                Some(Self::result(forwarded_to))
+            }
+            SsPduType::Result | SsPduType::Reject => None,
+        }
+    }
+}
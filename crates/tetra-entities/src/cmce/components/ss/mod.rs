@@ -0,0 +1,35 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::BitBuffer;
+use tetra_pdus::cmce::enums::{ss_pdu_type::SsPduType, ss_type::SsType};
+
+pub mod area_selection;
+pub mod call_forwarding;
+pub mod talking_party_identification;
+
+/// Answer of an SS handler to a request from an MS, sent back in D-FACILITY
+pub enum SsReply {
+    /// The request was carried out, with the SS-specific information reporting the state of the service
+    Result(BitBuffer),
+    /// The request was understood but refused
+    Reject,
+}
+
+/// Handler for the SS PDUs of one or more supplementary services
+///
+/// The SS sub-entity dispatches each U-FACILITY to the handler registered for its SS-type.
+/// Requests no handler takes care of are answered with CMCE FUNCTION NOT SUPPORTED.
+pub trait SsHandler: Send {
+    /// SS-types served by this handler
+    fn ss_types(&self) -> &[SsType];
+
+    /// Handle an SS PDU from the given MS, with `data` positioned at the SS-specific information.
+    /// Returns None if the SS PDU type is not supported by the service.
+    fn handle(&mut self, config: &SharedConfig, issi: u32, ss_type: SsType, pdu_type: SsPduType, data: &mut BitBuffer) -> Option<SsReply>;
+}
+
+/// SS-specific information holding only the active flag
+fn active_flag(active: bool) -> BitBuffer {
+    let mut data = BitBuffer::new_autoexpand(1);
+    data.write_bit(active as u8);
+    data
+}
@@ -0,0 +1,35 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::BitBuffer;
+use tetra_pdus::cmce::enums::{ss_pdu_type::SsPduType, ss_type::SsType};
+
+use super::{SsHandler, SsReply, active_flag};
+
+/// Talking party identification.
+///
+/// The identity of the talking party is always given in D-TX GRANTED, so the service is permanently active:
+/// activation and interrogation report it active, deactivation is rejected.
+pub struct TalkingPartyIdentification;
+
+impl SsHandler for TalkingPartyIdentification {
+    fn ss_types(&self) -> &[SsType] {
+        &[SsType::Tpi]
+    }
+
+    fn handle(
+        &mut self,
+        _config: &SharedConfig,
+        issi: u32,
+        _ss_type: SsType,
+        pdu_type: SsPduType,
+        _data: &mut BitBuffer,
+    ) -> Option<SsReply> {
+        match pdu_type {
+            SsPduType::Activate | SsPduType::Interrogate => Some(SsReply::Result(active_flag(true))),
+            SsPduType::Deactivate => {
+                tracing::info!("SS: rejecting TPI deactivation by ISSI {}, TPI is always provided", issi);
+                Some(SsReply::Reject)
+            }
+            SsPduType::Result | SsPduType::Reject => None,
+        }
+    }
+}
@@ -1,19 +1,20 @@
 use std::collections::{HashMap, HashSet};
 
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{ForwardingCondition, SharedConfig};
 use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
-use tetra_core::{Layer2Service, TimeslotOwner, TxReporter, TxState};
+use tetra_core::{Layer2Service, TimeslotOwner, TxReporter, TxState, multiframes};
 use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
 use tetra_pdus::cmce::{
     enums::{
-        call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase, cmce_pdu_type_ul::CmcePduTypeUl,
-        transmission_grant::TransmissionGrant,
+        call_status::CallStatus, call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase,
+        cmce_pdu_type_ul::CmcePduTypeUl, dtmf_type::DtmfType, transmission_grant::TransmissionGrant,
     },
-    fields::basic_service_information::BasicServiceInformation,
+    fields::{basic_service_information::BasicServiceInformation, dtmf::Dtmf},
     pdus::{
-        d_call_proceeding::DCallProceeding, d_connect::DConnect, d_release::DRelease, d_setup::DSetup, d_tx_ceased::DTxCeased,
-        d_tx_granted::DTxGranted, u_disconnect::UDisconnect, u_release::URelease, u_setup::USetup, u_tx_ceased::UTxCeased,
-        u_tx_demand::UTxDemand,
+        d_alert::DAlert, d_call_proceeding::DCallProceeding, d_call_restore::DCallRestore, d_connect::DConnect,
+        d_connect_acknowledge::DConnectAcknowledge, d_info::DInfo, d_release::DRelease, d_setup::DSetup, d_tx_ceased::DTxCeased,
+        d_tx_granted::DTxGranted, d_tx_interrupt::DTxInterrupt, u_alert::UAlert, u_call_restore::UCallRestore, u_connect::UConnect,
+        u_disconnect::UDisconnect, u_info::UInfo, u_release::URelease, u_setup::USetup, u_tx_ceased::UTxCeased, u_tx_demand::UTxDemand,
     },
     structs::cmce_circuit::CmceCircuit,
 };
@@ -21,7 +22,7 @@ use tetra_saps::{
     SapMsg, SapMsgInner,
     control::{
         brew::{BrewSubscriberAction, MmSubscriberUpdate},
-        call_control::{CallControl, Circuit},
+        call_control::{CallControl, Circuit, IndividualCallSignal},
         enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType},
     },
     lcmc::{
@@ -31,10 +32,12 @@ use tetra_saps::{
     },
 };
 
+use crate::backhaul;
 use crate::brew;
 use crate::{
     MessageQueue,
-    cmce::components::circuit_mgr::{CircuitMgr, CircuitMgrCmd},
+    cmce::components::alarm_log::{Alarm, AlarmLog},
+    cmce::components::circuit_mgr::{CircuitErr, CircuitMgr, CircuitMgrCmd},
 };
 
 /// Clause 11 Call Control CMCE sub-entity
@@ -50,8 +53,18 @@ pub struct CcBsSubentity {
     subscriber_groups: HashMap<u32, HashSet<u32>>,
     /// Listener counts per GSSI
     group_listeners: HashMap<u32, usize>,
+    /// Individual (point-to-point) calls: call_id -> call info
+    individual_calls: HashMap<u16, IndividualCall>,
+    /// Group calls waiting for a traffic channel, highest priority first, FIFO within a priority
+    queued_calls: Vec<QueuedCall>,
+    alarms: AlarmLog,
 }
 
+/// Call priority levels 12 and up are pre-emptive, 15 being emergency (ETSI 14.8.14)
+const PREEMPTIVE_PRIORITY_MIN: u8 = 12;
+/// Queued calls that did not get a traffic channel within this many timeslots are released
+const QUEUED_CALL_TIMEOUT: i32 = multiframes!(60);
+
 /// Origin of a group call
 #[derive(Clone)]
 enum CallOrigin {
@@ -59,9 +72,10 @@ enum CallOrigin {
     Local {
         caller_addr: TetraAddress, // For D-CALL-PROCEEDING, D-CONNECT routing
     },
-    /// Network-initiated call from TetraPack/Brew
+    /// Network-initiated call from TetraPack/Brew or another site over the backhaul
     Network {
         brew_uuid: uuid::Uuid, // For Brew tracking
+        entity: TetraEntity,   // Entity that started the call, Brew or Backhaul
     },
 }
 
@@ -73,6 +87,8 @@ struct ActiveCall {
     source_issi: u32, // Current speaker
     ts: u8,
     usage: u8,
+    /// Call priority (ETSI 14.8.14), used to pick the call to pre-empt when no timeslot is free
+    priority: u8,
     /// True if someone is currently transmitting
     tx_active: bool,
     /// When PTT was released (for hangtime). None if transmitting.
@@ -82,8 +98,98 @@ struct ActiveCall {
     brew_uuid: Option<uuid::Uuid>,
 }
 
+/// A local group call set-up that is waiting for a traffic channel to become free
+struct QueuedCall {
+    call_id: u16,
+    priority: u8,
+    /// The U-SETUP indication, needed to route the responses back to the calling MS
+    message: SapMsg,
+    pdu: USetup,
+    queued_at: TdmaTime,
+}
+
+/// Progress of an individual call through the set-up phase (ETSI 14.5.1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IndividualCallState {
+    /// D-SETUP sent to the called MS, waiting for U-ALERT or U-CONNECT
+    Setup,
+    /// Called user is being alerted (hook signalling), waiting for U-CONNECT
+    Alerting,
+    /// Both parties through-connected on a traffic channel
+    Active,
+}
+
+/// Network subscriber taking part in an individual call, reached over Brew
+#[derive(Clone, Copy)]
+struct NetworkParty {
+    brew_uuid: uuid::Uuid,
+    issi: u32,
+}
+
+/// Tracks an individual call between two local MSs, or between a local MS and a network subscriber
+#[derive(Clone)]
+struct IndividualCall {
+    calling_addr: TetraAddress,
+    called_addr: TetraAddress,
+    /// Hook method selection from U-SETUP: true for hook signalling, false for direct call set-up
+    hook_method: bool,
+    simplex_duplex: bool,
+    basic_service_information: BasicServiceInformation,
+    state: IndividualCallState,
+    /// Time the D-SETUP was sent, used for the set-up phase timeout
+    setup_start: TdmaTime,
+    /// Traffic timeslot and usage marker, allocated once the called party connects.
+    /// For simplex calls both parties share this circuit, for duplex calls it belongs to the calling party.
+    ts: Option<u8>,
+    usage: Option<u8>,
+    /// Timeslot of the called party's circuit, only used for duplex calls
+    called_ts: Option<u8>,
+    /// ISSI currently holding the floor (simplex only), None if nobody is transmitting
+    tx_owner: Option<u32>,
+    /// Call priority from U-SETUP (ETSI 14.8.14)
+    priority: u8,
+    /// Set if the call is bridged to a network subscriber, which is then the calling or called party
+    network_party: Option<NetworkParty>,
+}
+
+impl IndividualCall {
+    fn involves(&self, issi: u32) -> bool {
+        self.calling_addr.ssi == issi || self.called_addr.ssi == issi
+    }
+
+    /// Returns true if the party is an MS on this cell rather than a network subscriber
+    fn is_local(&self, issi: u32) -> bool {
+        self.network_party.is_none_or(|party| party.issi != issi)
+    }
+
+    /// Parties of the call on this cell, the ones that are sent CMCE PDUs
+    fn local_parties(&self) -> impl Iterator<Item = TetraAddress> {
+        [self.calling_addr, self.called_addr]
+            .into_iter()
+            .filter(|addr| self.is_local(addr.ssi))
+    }
+
+    /// Traffic timeslot on which the party receives, None before the call is connected
+    fn ts_of(&self, issi: u32) -> Option<u8> {
+        if self.called_addr.ssi == issi {
+            self.called_ts.or(self.ts)
+        } else {
+            self.ts
+        }
+    }
+
+    fn peer_of(&self, issi: u32) -> TetraAddress {
+        if self.calling_addr.ssi == issi {
+            self.called_addr
+        } else {
+            self.calling_addr
+        }
+    }
+}
+
 impl CcBsSubentity {
     pub fn new(config: SharedConfig) -> Self {
+        let alarms = AlarmLog::open(config.config().alarm_log.as_deref());
         CcBsSubentity {
             config,
             dltime: TdmaTime::default(),
@@ -92,6 +198,9 @@ impl CcBsSubentity {
             active_calls: HashMap::new(),
             subscriber_groups: HashMap::new(),
             group_listeners: HashMap::new(),
+            individual_calls: HashMap::new(),
+            queued_calls: Vec::new(),
+            alarms,
         }
     }
 
@@ -106,12 +215,11 @@ impl CcBsSubentity {
         tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
 
         // Construct ChanAlloc descriptor for the allocated timeslot
-        let mut timeslots = [false; 4];
-        timeslots[ts as usize - 1] = true;
+        let (carrier, timeslots) = CmceChanAllocReq::channel_to_carrier_and_timeslots(ts);
         let chan_alloc = CmceChanAllocReq {
             usage: Some(usage),
             alloc_type: ChanAllocType::Replace,
-            carrier: None,
+            carrier,
             timeslots,
             ul_dl_assigned: ul_dl,
         };
@@ -151,11 +259,10 @@ impl CcBsSubentity {
 
     fn build_sapmsg_stealing(sdu: BitBuffer, dltime: TdmaTime, address: TetraAddress, ts: u8) -> SapMsg {
         // For FACCH stealing on traffic channel, must specify target timeslot
-        let mut timeslots = [false; 4];
-        timeslots[(ts - 1) as usize] = true;
+        let (carrier, timeslots) = CmceChanAllocReq::channel_to_carrier_and_timeslots(ts);
         let chan_alloc = CmceChanAllocReq {
             usage: None,
-            carrier: None,
+            carrier,
             timeslots,
             alloc_type: ChanAllocType::Replace,
             ul_dl_assigned: UlDlAssignment::Both,
@@ -224,30 +331,51 @@ impl CcBsSubentity {
             return;
         }
 
-        let to_drop: Vec<(u16, CallOrigin)> = self
+        let to_drop: Vec<(u16, CallOrigin, u8)> = self
             .active_calls
             .iter()
             .filter(|(_, call)| call.dest_gssi == gssi)
-            .map(|(call_id, call)| (*call_id, call.origin.clone()))
+            .map(|(call_id, call)| (*call_id, call.origin.clone(), call.priority))
             .collect();
 
-        for (call_id, origin) in to_drop {
+        for (call_id, origin, priority) in to_drop {
             tracing::info!("CMCE: dropping call_id={} gssi={} (no listeners)", call_id, gssi);
-            if let CallOrigin::Network { brew_uuid } = origin {
-                if brew::is_brew_gssi_routable(&self.config, gssi) {
-                    queue.push_back(SapMsg {
-                        sap: Sap::Control,
-                        src: TetraEntity::Cmce,
-                        dest: TetraEntity::Brew,
-                        dltime: self.dltime,
-                        msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
-                    });
-                };
+            if let CallOrigin::Network { brew_uuid, entity } = origin
+                && (entity != TetraEntity::Brew || brew::is_brew_call_routable(&self.config, gssi, priority))
+            {
+                queue.push_back(SapMsg {
+                    sap: Sap::Control,
+                    src: TetraEntity::Cmce,
+                    dest: entity,
+                    dltime: self.dltime,
+                    msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+                });
             };
             self.release_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection);
         }
     }
 
+    /// Entities bridging local calls on this group to the network: Brew if the call is cleared for it,
+    /// and the inter-site backhaul if loaded
+    fn network_bridges(&self, gssi: u32, priority: u8) -> Vec<TetraEntity> {
+        let mut bridges = Vec::new();
+        if brew::is_brew_call_routable(&self.config, gssi, priority) {
+            bridges.push(TetraEntity::Brew);
+        }
+        if backhaul::is_active(&self.config) {
+            bridges.push(TetraEntity::Backhaul);
+        }
+        bridges
+    }
+
+    /// Returns true if a network bridge that may carry a call on this group is up: Brew connected with the call
+    /// cleared for it, or another site with members of the group linked over the backhaul
+    fn network_bridge_up(&self, gssi: u32, priority: u8) -> bool {
+        let brew_routable = brew::is_brew_call_routable(&self.config, gssi, priority);
+        let state = self.config.state_read();
+        (brew_routable && state.network_connected) || !state.subscribers.remote_sites_with_group(gssi).is_empty()
+    }
+
     pub fn handle_subscriber_update(&mut self, queue: &mut MessageQueue, update: MmSubscriberUpdate) {
         let issi = update.issi;
         let groups = update.groups;
@@ -285,6 +413,7 @@ impl CcBsSubentity {
                     tracing::debug!("CMCE: affiliate ignored (no new groups) issi={}", issi);
                 } else {
                     tracing::info!("CMCE: subscriber affiliate issi={} groups={:?}", issi, new_groups);
+                    self.send_late_entry_for_groups(queue, issi, &new_groups);
                 }
             }
             BrewSubscriberAction::Deaffiliate => {
@@ -318,7 +447,15 @@ impl CcBsSubentity {
         }
     }
 
-    fn send_d_call_proceeding(&mut self, queue: &mut MessageQueue, message: &SapMsg, pdu_request: &USetup, call_id: u16) {
+    /// Acknowledge a U-SETUP. With call_status Callqueued, tells the calling MS to wait for a traffic channel.
+    fn send_d_call_proceeding(
+        &mut self,
+        queue: &mut MessageQueue,
+        message: &SapMsg,
+        pdu_request: &USetup,
+        call_id: u16,
+        call_status: Option<CallStatus>,
+    ) {
         tracing::trace!("send_d_call_proceeding");
 
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
@@ -327,11 +464,16 @@ impl CcBsSubentity {
 
         let pdu_response = DCallProceeding {
             call_identifier: call_id,
-            call_time_out_set_up_phase: CallTimeoutSetupPhase::T10s,
+            // A queued call may wait up to QUEUED_CALL_TIMEOUT for its traffic channel
+            call_time_out_set_up_phase: if call_status == Some(CallStatus::Callqueued) {
+                CallTimeoutSetupPhase::T60s
+            } else {
+                CallTimeoutSetupPhase::T10s
+            },
             hook_method_selection: pdu_request.hook_method_selection,
             simplex_duplex_selection: pdu_request.simplex_duplex_selection,
             basic_service_information: None, // Only needed if different from requested
-            call_status: None,
+            call_status,
             notification_indicator: None,
             facility: None,
             proprietary: None,
@@ -366,7 +508,8 @@ impl CcBsSubentity {
         queue.push_back(msg);
     }
 
-    fn signal_umac_circuit_open(queue: &mut MessageQueue, call: &CmceCircuit, dltime: TdmaTime) {
+    /// Opens a circuit in the UMAC. Individual calls pass the (UL sender, DL receiver) ISSIs of the circuit.
+    fn signal_umac_circuit_open(queue: &mut MessageQueue, call: &CmceCircuit, individual_parties: Option<(u32, u32)>, dltime: TdmaTime) {
         let circuit = Circuit {
             direction: call.direction,
             ts: call.ts,
@@ -374,6 +517,8 @@ impl CcBsSubentity {
             circuit_mode: call.circuit_mode,
             speech_service: call.speech_service,
             etee_encrypted: call.etee_encrypted,
+            peer_ts: call.peer_ts,
+            individual_parties,
         };
         let cmd = SapMsg {
             sap: Sap::Control,
@@ -425,10 +570,17 @@ impl CcBsSubentity {
             tracing::warn!("U-SETUP without called_party_ssi, ignoring");
             return;
         };
-        let dest_gssi = dest_gssi as u32;
-        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
+        // Individual calls follow their own set-up procedure (ETSI 14.5.1)
+        if pdu.basic_service_information.communication_type == CommunicationType::P2p {
+            self.rx_u_setup_individual(queue, &message, pdu);
+            return;
+        }
 
-        if !self.has_listener(dest_gssi) {
+        // Emergency calls go ahead without local listeners if a network bridge may still pick them up
+        let dest_gssi = dest_gssi as u32;
+        let emergency = pdu.call_priority == brew::EMERGENCY_CALL_PRIORITY;
+        let reachable = self.has_listener(dest_gssi) || (emergency && self.network_bridge_up(dest_gssi, pdu.call_priority));
+        if !reachable {
             tracing::info!(
                 "CMCE: rejecting U-SETUP from issi={} to gssi={} (no listeners)",
                 calling_party.ssi,
@@ -437,23 +589,251 @@ impl CcBsSubentity {
             return;
         }
 
-        // Allocate circuit (DL+UL for group call)
-        let circuit = match {
-            let mut state = self.config.state_write();
-            self.circuits.allocate_circuit_with_allocator(
-                Direction::Both,
-                pdu.basic_service_information.communication_type,
-                &mut state.timeslot_alloc,
-                TimeslotOwner::Cmce,
-            )
-        } {
-            Ok(circuit) => circuit.clone(),
+        // Allocate circuit (DL+UL for group call). If all timeslots are taken, a pre-emptive call may
+        // take over the timeslot of a lower priority call, otherwise the call is queued.
+        let call_id = self.circuits.get_next_call_id();
+        let priority = pdu.call_priority;
+        let comm_type = pdu.basic_service_information.communication_type;
+        let mut allocated = self.allocate_group_circuit(call_id, comm_type);
+        if allocated.is_err() && priority >= PREEMPTIVE_PRIORITY_MIN && self.preempt_lower_priority_call(queue, priority) {
+            allocated = self.allocate_group_circuit(call_id, comm_type);
+        }
+        let circuit = match allocated {
+            Ok(circuit) => circuit,
             Err(e) => {
-                tracing::error!("Failed to allocate circuit for U-SETUP: {:?}", e);
+                tracing::info!("rx_u_setup: no circuit for call_id={} ({:?}), queuing", call_id, e);
+                self.raise_emergency_alarm(&pdu, calling_party.ssi, dest_gssi, false);
+                self.queue_group_call(queue, message, pdu, call_id);
                 return;
             }
         };
 
+        self.raise_emergency_alarm(&pdu, calling_party.ssi, dest_gssi, false);
+        let dltime = message.dltime;
+        self.start_local_group_call(queue, &message, &pdu, circuit, dltime, false);
+    }
+
+    /// Raise the emergency call alarm for an accepted (granted or queued) emergency call set-up
+    fn raise_emergency_alarm(&mut self, pdu: &USetup, calling_issi: u32, called_ssi: u32, individual: bool) {
+        if pdu.call_priority != brew::EMERGENCY_CALL_PRIORITY {
+            return;
+        }
+        let alarm = Alarm::EmergencyCall {
+            calling_issi,
+            called_ssi,
+            individual,
+        };
+        self.alarms.raise(self.dltime, &alarm);
+    }
+
+    fn allocate_group_circuit(&mut self, call_id: u16, comm_type: CommunicationType) -> Result<CmceCircuit, CircuitErr> {
+        let mut state = self.config.state_write();
+        self.circuits
+            .allocate_circuit_for_call(call_id, Direction::Both, comm_type, &mut state.timeslot_alloc, TimeslotOwner::Cmce)
+            .cloned()
+    }
+
+    /// Queue a group call set-up for which no traffic channel is free (ETSI 14.5.1.1.3). The calling MS is
+    /// told to wait through the call status of the D-CALL-PROCEEDING; the call is set up from tick_start
+    /// once a timeslot frees up.
+    fn queue_group_call(&mut self, queue: &mut MessageQueue, message: SapMsg, pdu: USetup, call_id: u16) {
+        self.send_d_call_proceeding(queue, &message, &pdu, call_id, Some(CallStatus::Callqueued));
+
+        let priority = pdu.call_priority;
+        let pos = self
+            .queued_calls
+            .iter()
+            .position(|queued| queued.priority < priority)
+            .unwrap_or(self.queued_calls.len());
+        self.queued_calls.insert(
+            pos,
+            QueuedCall {
+                call_id,
+                priority,
+                message,
+                pdu,
+                queued_at: self.dltime,
+            },
+        );
+    }
+
+    /// Set up queued calls for as long as timeslots are free, and release those that waited too long
+    fn serve_queued_calls(&mut self, queue: &mut MessageQueue) {
+        let dltime = self.dltime;
+        let (expired, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queued_calls)
+            .into_iter()
+            .partition(|queued| queued.queued_at.age(dltime) > QUEUED_CALL_TIMEOUT);
+        self.queued_calls = waiting;
+        for queued in expired {
+            tracing::info!("Queued call_id={} got no traffic channel in time, releasing", queued.call_id);
+            Self::release_queued_call(queue, queued, DisconnectCause::ExpiryOfTimer, dltime);
+        }
+
+        // The group may have lost all its listeners while the call was waiting
+        let (unlistened, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queued_calls).into_iter().partition(|queued| {
+            let gssi = queued.pdu.called_party_ssi.unwrap_or_default() as u32;
+            !self.has_listener(gssi) && !self.network_bridge_up(gssi, queued.priority)
+        });
+        self.queued_calls = waiting;
+        for queued in unlistened {
+            tracing::info!("Queued call_id={} has no listeners left, releasing", queued.call_id);
+            Self::release_queued_call(queue, queued, DisconnectCause::CalledPartyNotReachable, dltime);
+        }
+
+        while let Some(head) = self.queued_calls.first() {
+            let call_id = head.call_id;
+            let comm_type = head.pdu.basic_service_information.communication_type;
+            let Ok(circuit) = self.allocate_group_circuit(call_id, comm_type) else {
+                break;
+            };
+            let queued = self.queued_calls.remove(0);
+            tracing::info!("Serving queued call_id={} on ts={}", call_id, circuit.ts);
+            self.start_local_group_call(queue, &queued.message, &queued.pdu, circuit, dltime, true);
+        }
+    }
+
+    /// Send D-RELEASE to the calling MS of a call that is still queued
+    fn release_queued_call(queue: &mut MessageQueue, queued: QueuedCall, disconnect_cause: DisconnectCause, dltime: TdmaTime) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &queued.message.msg else {
+            panic!()
+        };
+        let sdu = Self::build_d_release(queued.call_id, disconnect_cause);
+        Self::send_individual(queue, sdu, dltime, prim.received_tetra_address, None);
+    }
+
+    /// Removes a queued call on request of its calling MS. Returns false if the call is not queued.
+    fn cancel_queued_call(&mut self, queue: &mut MessageQueue, call_id: u16, sender: u32, send_release: bool) -> bool {
+        let Some(pos) = self.queued_calls.iter().position(|queued| {
+            queued.call_id == call_id
+                && matches!(&queued.message.msg, SapMsgInner::LcmcMleUnitdataInd(prim) if prim.received_tetra_address.ssi == sender)
+        }) else {
+            return false;
+        };
+        let queued = self.queued_calls.remove(pos);
+        tracing::info!("Queued call_id={} cancelled by ISSI {}", call_id, sender);
+        if send_release {
+            Self::release_queued_call(queue, queued, DisconnectCause::UserRequestedDisconnection, self.dltime);
+        }
+        true
+    }
+
+    /// Allocate the circuit(s) for an individual call. Simplex calls get a single DL+UL circuit shared by
+    /// both parties, duplex calls get one DL+UL circuit per party, cross-connected in UMAC.
+    fn allocate_individual_circuits(
+        &mut self,
+        call_id: u16,
+        comm_type: CommunicationType,
+        duplex: bool,
+    ) -> Result<(CmceCircuit, Option<CmceCircuit>), CircuitErr> {
+        let mut state = self.config.state_write();
+        if duplex {
+            self.circuits
+                .allocate_duplex_circuits_for_call(call_id, comm_type, &mut state.timeslot_alloc, TimeslotOwner::Cmce)
+                .map(|(calling, called)| (calling, Some(called)))
+        } else {
+            self.circuits
+                .allocate_circuit_for_call(call_id, Direction::Both, comm_type, &mut state.timeslot_alloc, TimeslotOwner::Cmce)
+                .map(|circuit| (circuit.clone(), None))
+        }
+    }
+
+    /// Release the lowest priority call below the given priority to free up its timeslot.
+    /// The speaker of a group call is interrupted with D-TX INTERRUPT, after which the call is released
+    /// with D-RELEASE. Calls in hangtime are preferred over calls with an active speaker. Returns false
+    /// if no call can be pre-empted.
+    fn preempt_lower_priority_call(&mut self, queue: &mut MessageQueue, priority: u8) -> bool {
+        let group_victim = self
+            .active_calls
+            .iter()
+            .filter(|(_, call)| call.priority < priority)
+            .min_by_key(|(_, call)| (call.priority, call.tx_active))
+            .map(|(call_id, call)| (*call_id, (call.priority, call.tx_active)));
+        let individual_victim = self
+            .individual_calls
+            .iter()
+            .filter(|(_, call)| call.ts.is_some() && call.priority < priority)
+            .min_by_key(|(_, call)| (call.priority, call.simplex_duplex || call.tx_owner.is_some()))
+            .map(|(call_id, call)| (*call_id, (call.priority, call.simplex_duplex || call.tx_owner.is_some())));
+
+        let call_id = match (group_victim, individual_victim) {
+            (None, None) => {
+                tracing::info!("No call with priority below {} to pre-empt", priority);
+                return false;
+            }
+            (Some((call_id, group_key)), Some((_, individual_key))) if group_key <= individual_key => call_id,
+            (Some((call_id, _)), None) => call_id,
+            (_, Some((call_id, _))) => {
+                tracing::info!("Pre-empting individual call_id={} for a priority {} call", call_id, priority);
+                self.release_individual_call(queue, call_id, DisconnectCause::PreEmptiveUseOfResource, None);
+                return true;
+            }
+        };
+        let victim = self.active_calls.get(&call_id).cloned().unwrap();
+
+        tracing::info!(
+            "Pre-empting call_id={} gssi={} ts={} (priority {}) for a priority {} call",
+            call_id,
+            victim.dest_gssi,
+            victim.ts,
+            victim.priority,
+            priority
+        );
+
+        let d_tx_interrupt = DTxInterrupt {
+            call_identifier: call_id,
+            transmission_grant: TransmissionGrant::NotGranted.into_raw() as u8,
+            transmission_request_permission: true, // Not allowed to request transmission
+            encryption_control: false,
+            reserved: false,
+            notification_indicator: None,
+            transmitting_party_type_identifier: None,
+            transmitting_party_address_ssi: None,
+            transmitting_party_extension: None,
+            external_subscriber_number: None,
+            facility: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        d_tx_interrupt.to_bitbuf(&mut sdu).expect("Failed to serialize DTxInterrupt");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_tx_interrupt, sdu.dump_bin());
+        let dest_addr = TetraAddress::new(victim.dest_gssi, SsiType::Gssi);
+        queue.push_back(Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, victim.ts));
+
+        // The network side of a network-initiated call has to stop streaming as well
+        if let CallOrigin::Network { brew_uuid, entity } = victim.origin {
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: entity,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+            });
+        }
+
+        self.release_call(queue, call_id, DisconnectCause::PreEmptiveUseOfResource);
+        true
+    }
+
+    /// Set up a local group call on its freshly allocated circuit. For queued calls, the D-CALL-PROCEEDING
+    /// was already sent when the call was queued.
+    fn start_local_group_call(
+        &mut self,
+        queue: &mut MessageQueue,
+        message: &SapMsg,
+        pdu: &USetup,
+        circuit: CmceCircuit,
+        dltime: TdmaTime,
+        was_queued: bool,
+    ) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+            panic!()
+        };
+        let calling_party = prim.received_tetra_address;
+        let dest_gssi = pdu.called_party_ssi.unwrap_or_default() as u32;
+        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
+
         tracing::info!(
             "rx_u_setup: call from ISSI {} to GSSI {} → ts={} call_id={} usage={}",
             calling_party.ssi,
@@ -464,25 +844,23 @@ impl CcBsSubentity {
         );
 
         // Signal UMAC to open DL+UL circuits
-        Self::signal_umac_circuit_open(queue, &circuit, message.dltime);
+        Self::signal_umac_circuit_open(queue, &circuit, None, dltime);
 
         // Build channel allocation timeslot mask for this call
-        let mut timeslots = [false; 4];
-        timeslots[circuit.ts as usize - 1] = true;
+        let (carrier, timeslots) = CmceChanAllocReq::channel_to_carrier_and_timeslots(circuit.ts);
 
         // Extract UL message routing info (handle, link_id, endpoint_id) for
         // individually-addressed responses. These are needed so MLE can route
         // the response back to the correct radio via the established LLC link.
-        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
-            panic!()
-        };
         let ul_handle = prim.handle;
         let ul_link_id = prim.link_id;
         let ul_endpoint_id = prim.endpoint_id;
 
         // === 1) Send D-CALL-PROCEEDING to the calling MS (individually addressed) ===
         // This acknowledges the U-SETUP and keeps the radio from timing out.
-        self.send_d_call_proceeding(queue, &message, &pdu, circuit.call_id);
+        if !was_queued {
+            self.send_d_call_proceeding(queue, message, pdu, circuit.call_id, None);
+        }
 
         // === 2) Send D-CONNECT to the calling MS with Granted + channel allocation ===
         // This transitions the calling MS from "Call Setup" to "Active".
@@ -513,7 +891,7 @@ impl CcBsSubentity {
             sap: Sap::LcmcSap,
             src: TetraEntity::Cmce,
             dest: TetraEntity::Mle,
-            dltime: message.dltime,
+            dltime,
             msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu: connect_sdu,
                 handle: ul_handle,
@@ -527,7 +905,7 @@ impl CcBsSubentity {
                 chan_alloc: Some(CmceChanAllocReq {
                     usage: Some(circuit.usage),
                     alloc_type: ChanAllocType::Replace,
-                    carrier: None,
+                    carrier,
                     timeslots,
                     ul_dl_assigned: UlDlAssignment::Both,
                 }),
@@ -568,7 +946,7 @@ impl CcBsSubentity {
         let setup_msg = Self::build_sapmsg(
             setup_sdu,
             Some(setup_chan_alloc),
-            message.dltime,
+            dltime,
             dest_addr,
             Layer2Service::Unacknowledged,
             None,
@@ -586,6 +964,7 @@ impl CcBsSubentity {
                 source_issi: calling_party.ssi,
                 ts: circuit.ts,
                 usage: circuit.usage,
+                priority: pdu.call_priority,
                 tx_active: true,
                 hangtime_start: None,
                 brew_uuid: None,
@@ -593,18 +972,19 @@ impl CcBsSubentity {
         );
 
         // Notify Brew entity about this local call if Brew is loaded and the SSI is cleared for Brew
-        // It can then forward to TetraPack if the group is subscribed
-        if brew::is_brew_gssi_routable(&self.config, dest_gssi) {
+        // It can then forward to TetraPack if the group is subscribed. Same for the inter-site backhaul.
+        for dest in self.network_bridges(dest_gssi, pdu.call_priority) {
             let msg = SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                dltime: message.dltime,
+                dest,
+                dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
                     call_id: circuit.call_id,
                     source_issi: calling_party.ssi,
                     dest_gssi,
                     ts: circuit.ts,
+                    priority: pdu.call_priority,
                 }),
             };
             queue.push_back(msg);
@@ -633,11 +1013,11 @@ impl CcBsSubentity {
             CmcePduTypeUl::UTxDemand => self.rx_u_tx_demand(_queue, message),
             CmcePduTypeUl::URelease => self.rx_u_release(_queue, message),
             CmcePduTypeUl::UDisconnect => self.rx_u_disconnect(_queue, message),
-            CmcePduTypeUl::UAlert
-            | CmcePduTypeUl::UConnect
-            | CmcePduTypeUl::UInfo
-            | CmcePduTypeUl::UStatus
-            | CmcePduTypeUl::UCallRestore => {
+            CmcePduTypeUl::UAlert => self.rx_u_alert(_queue, message),
+            CmcePduTypeUl::UConnect => self.rx_u_connect(_queue, message),
+            CmcePduTypeUl::UCallRestore => self.rx_u_call_restore(_queue, message),
+            CmcePduTypeUl::UInfo => self.rx_u_info(_queue, message),
+            CmcePduTypeUl::UStatus => {
                 unimplemented_log!("{}", pdu_type);
             }
             _ => {
@@ -646,12 +1026,71 @@ impl CcBsSubentity {
         }
     }
 
+    /// Re-send the cached D-SETUP of an ongoing group call, so MSs that missed the call set-up join the
+    /// call (late entry). The tx receipt is kept to throttle the next periodic re-send.
+    fn send_late_entry_d_setup(&mut self, queue: &mut MessageQueue, call_id: u16, usage: u8, ts: u8) {
+        let Some((pdu, dest_addr, receipt)) = self.cached_setups.get_mut(&call_id) else {
+            tracing::error!("No cached D-SETUP for call id {}", call_id);
+            return;
+        };
+
+        // Update transmission_grant based on current call state:
+        // During hangtime (nobody transmitting), use NotGranted;
+        // during active TX, use GrantedToOtherUser.
+        if let Some(active) = self.active_calls.get(&call_id) {
+            pdu.transmission_grant = if active.tx_active {
+                TransmissionGrant::GrantedToOtherUser
+            } else {
+                TransmissionGrant::NotGranted
+            };
+        }
+        let dest_addr = *dest_addr;
+        let (sdu, chan_alloc) = Self::build_d_setup_prim(pdu, usage, ts, UlDlAssignment::Both);
+
+        // Create a fresh txreporter for this re-send. D-SETUP goes out unacknowledged, so the receipt is
+        // final once UMAC has transmitted it.
+        let reporter = TxReporter::new_unacked();
+        *receipt = Some(reporter.clone());
+
+        let prim = Self::build_sapmsg(
+            sdu,
+            Some(chan_alloc),
+            self.dltime,
+            dest_addr,
+            Layer2Service::Unacknowledged,
+            Some(reporter),
+        );
+        queue.push_back(prim);
+    }
+
+    /// Send D-SETUP right away for calls in progress on groups an MS just attached to, instead of
+    /// letting it wait for the next periodic late-entry re-send. Calls in hangtime are skipped, like
+    /// the periodic re-sends.
+    fn send_late_entry_for_groups(&mut self, queue: &mut MessageQueue, issi: u32, groups: &[u32]) {
+        let calls: Vec<(u16, u8, u8)> = self
+            .active_calls
+            .iter()
+            .filter(|(_, call)| groups.contains(&call.dest_gssi) && call.hangtime_start.is_none())
+            .map(|(call_id, call)| (*call_id, call.usage, call.ts))
+            .collect();
+        for (call_id, usage, ts) in calls {
+            tracing::info!("CMCE: late entry D-SETUP for call_id={} to newly attached issi={}", call_id, issi);
+            self.send_late_entry_d_setup(queue, call_id, usage, ts);
+        }
+    }
+
     pub fn tick_start(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
         self.dltime = dltime;
 
         // Check hangtime expiry for active local calls
         self.check_hangtime_expiry(queue);
 
+        // Check set-up phase expiry for individual calls that were never answered
+        self.check_individual_setup_expiry(queue);
+
+        // Set up queued group calls if timeslots were freed up
+        self.serve_queued_calls(queue);
+
         if let Some(tasks) = self.circuits.tick_start(dltime) {
             for task in tasks {
                 match task {
@@ -664,8 +1103,7 @@ impl CcBsSubentity {
                             }
                         }
 
-                        // Get our cached D-SETUP, build a prim and send it down the stack
-                        let Some((pdu, dest_addr, receipt)) = self.cached_setups.get_mut(&call_id) else {
+                        let Some((_, _, receipt)) = self.cached_setups.get(&call_id) else {
                             tracing::error!("No cached D-SETUP for call id {}", call_id);
                             continue;
                         };
@@ -686,36 +1124,28 @@ impl CcBsSubentity {
                             }
                         }
 
-                        // Update transmission_grant based on current call state:
-                        // During hangtime (nobody transmitting), use NotGranted;
-                        // during active TX, use GrantedToOtherUser.
-                        if let Some(active) = self.active_calls.get(&call_id) {
-                            pdu.transmission_grant = if active.tx_active {
-                                TransmissionGrant::GrantedToOtherUser
-                            } else {
-                                TransmissionGrant::NotGranted
-                            };
-                        }
-                        let dest_addr = *dest_addr;
-                        let (sdu, chan_alloc) = Self::build_d_setup_prim(pdu, usage, ts, UlDlAssignment::Both);
-
-                        // Create a fresh txreporter for this re-send
-                        let reporter = TxReporter::new();
-
-                        let prim = Self::build_sapmsg(
-                            sdu,
-                            Some(chan_alloc),
-                            self.dltime,
-                            dest_addr,
-                            Layer2Service::Unacknowledged,
-                            Some(reporter),
-                        );
-                        queue.push_back(prim);
+                        self.send_late_entry_d_setup(queue, call_id, usage, ts);
                     }
 
                     CircuitMgrCmd::SendClose(call_id, circuit) => {
                         tracing::warn!("need to send CLOSE for call id {}", call_id);
                         let ts = circuit.ts;
+                        if self.individual_calls.contains_key(&call_id) {
+                            // Circuit is already closed in CircuitMgr, release_individual_call
+                            // only needs to notify UMAC, free the timeslot and inform both parties
+                            Self::signal_umac_circuit_close(queue, circuit, self.dltime);
+                            self.release_timeslot(ts);
+                            if let Some(call) = self.individual_calls.get_mut(&call_id) {
+                                // Forget the expired circuit, so only the remaining one (if any) is released below
+                                if call.ts == Some(ts) {
+                                    call.ts = None;
+                                } else if call.called_ts == Some(ts) {
+                                    call.called_ts = None;
+                                }
+                            }
+                            self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+                            continue;
+                        }
                         // Get our cached D-SETUP, build D-RELEASE and send
                         if let Some((pdu, dest_addr, _)) = self.cached_setups.get(&call_id) {
                             let dest_addr = *dest_addr;
@@ -739,61 +1169,879 @@ impl CcBsSubentity {
         }
     }
 
-    /// Check if any active calls in hangtime have expired, and if so, release them
-    fn check_hangtime_expiry(&mut self, queue: &mut MessageQueue) {
-        // Hangtime: 5 multiframes = ~5 seconds
-        const HANGTIME_FRAMES: i32 = 5 * 18 * 4;
+    /// Check if any active calls in hangtime have expired, and if so, release them
+    fn check_hangtime_expiry(&mut self, queue: &mut MessageQueue) {
+        // Hangtime: 5 multiframes = ~5 seconds
+        const HANGTIME_FRAMES: i32 = 5 * 18 * 4;
+
+        let expired: Vec<u16> = self
+            .active_calls
+            .iter()
+            .filter_map(|(&call_id, call)| {
+                if let Some(hangtime_start) = call.hangtime_start {
+                    if hangtime_start.age(self.dltime) > HANGTIME_FRAMES {
+                        return Some(call_id);
+                    }
+                }
+                None
+            })
+            .collect();
+
+        for call_id in expired {
+            tracing::info!("Hangtime expired for call_id={}, releasing", call_id);
+            self.release_call(queue, call_id, DisconnectCause::ExpiryOfTimer);
+        }
+    }
+
+    fn release_timeslot(&mut self, ts: u8) {
+        let mut state = self.config.state_write();
+        if let Err(err) = state.timeslot_alloc.release(TimeslotOwner::Cmce, ts) {
+            tracing::warn!("CcBsSubentity: failed to release timeslot ts={} err={:?}", ts, err);
+        }
+    }
+
+    /// Release a call: send D-RELEASE, close circuits, clean up state
+    fn release_call(&mut self, queue: &mut MessageQueue, call_id: u16, disconnect_cause: DisconnectCause) {
+        let Some((pdu, dest_addr, _)) = self.cached_setups.get(&call_id) else {
+            tracing::error!("No cached D-SETUP for call_id={}", call_id);
+            return;
+        };
+        let dest_addr = *dest_addr;
+
+        // Send D-RELEASE to group
+        let sdu = Self::build_d_release_from_d_setup(pdu, disconnect_cause);
+        let prim = Self::build_sapmsg(sdu, None, self.dltime, dest_addr, Layer2Service::Unacknowledged, None);
+        queue.push_back(prim);
+
+        // Close the circuit in CircuitMgr and notify Brew
+        if let Some(call) = self.active_calls.get(&call_id) {
+            let ts = call.ts;
+            let dest_ssi = call.dest_gssi;
+            let priority = call.priority;
+            let is_local = matches!(call.origin, CallOrigin::Local { .. });
+
+            if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, ts) {
+                Self::signal_umac_circuit_close(queue, circuit, self.dltime);
+            }
+
+            // Ensure UMAC clears hangtime even if the CMCE circuit was already closed above.
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Umac,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
+            });
+
+            self.release_timeslot(ts);
+
+            // Notify Brew only for local calls on SSIs that are cleared for Brew, and the backhaul for local calls
+            if is_local {
+                for dest in self.network_bridges(dest_ssi, priority) {
+                    let notify = SapMsg {
+                        sap: Sap::Control,
+                        src: TetraEntity::Cmce,
+                        dest,
+                        dltime: self.dltime,
+                        msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
+                    };
+                    queue.push_back(notify);
+                }
+            }
+        }
+
+        // Clean up
+        self.cached_setups.remove(&call_id);
+        self.active_calls.remove(&call_id);
+    }
+
+    /// Serialize a CMCE PDU-generated SDU and send it individually addressed to an MS on the MCCH
+    fn send_individual(
+        queue: &mut MessageQueue,
+        sdu: BitBuffer,
+        dltime: TdmaTime,
+        addr: TetraAddress,
+        chan_alloc: Option<CmceChanAllocReq>,
+    ) {
+        // Channel allocations are sent unacknowledged so the MS can move to the traffic channel right away,
+        // in line with the group call D-CONNECT
+        let layer2service = if chan_alloc.is_some() {
+            Layer2Service::Unacknowledged
+        } else {
+            Layer2Service::Acknowledged
+        };
+        let msg = Self::build_sapmsg(sdu, chan_alloc, dltime, addr, layer2service, None);
+        queue.push_back(msg);
+    }
+
+    fn build_d_release(call_id: u16, disconnect_cause: DisconnectCause) -> BitBuffer {
+        let pdu = DRelease {
+            call_identifier: call_id,
+            disconnect_cause,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DRelease");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+        sdu
+    }
+
+    fn build_individual_chan_alloc(ts: u8, usage: u8) -> CmceChanAllocReq {
+        let (carrier, timeslots) = CmceChanAllocReq::channel_to_carrier_and_timeslots(ts);
+        CmceChanAllocReq {
+            usage: Some(usage),
+            alloc_type: ChanAllocType::Replace,
+            carrier,
+            timeslots,
+            ul_dl_assigned: UlDlAssignment::Both,
+        }
+    }
+
+    /// Returns the call_id of the individual call this ISSI takes part in, if any
+    fn individual_call_of(&self, issi: u32) -> Option<u16> {
+        self.individual_calls
+            .iter()
+            .find(|(_, call)| call.involves(issi))
+            .map(|(call_id, _)| *call_id)
+    }
+
+    /// Returns the call_id of the individual call bridged over the given Brew session, if any
+    fn individual_call_of_network(&self, brew_uuid: uuid::Uuid) -> Option<u16> {
+        self.individual_calls
+            .iter()
+            .find(|(_, call)| call.network_party.is_some_and(|party| party.brew_uuid == brew_uuid))
+            .map(|(call_id, _)| *call_id)
+    }
+
+    /// Send individual call signalling on behalf of the local party of a bridged call to Brew
+    fn signal_network_party(queue: &mut MessageQueue, dltime: TdmaTime, brew_uuid: uuid::Uuid, signal: IndividualCallSignal) {
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Brew,
+            dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall { brew_uuid, signal }),
+        });
+    }
+
+    /// Page the called party of an individual call with D-SETUP, without channel allocation
+    fn send_individual_d_setup(queue: &mut MessageQueue, dltime: TdmaTime, call_id: u16, call: &IndividualCall) {
+        let d_setup = DSetup {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m,
+            hook_method_selection: call.hook_method,
+            simplex_duplex_selection: call.simplex_duplex,
+            basic_service_information: call.basic_service_information.clone(),
+            transmission_grant: TransmissionGrant::NotGranted,
+            transmission_request_permission: false,
+            call_priority: call.priority,
+            notification_indicator: None,
+            temporary_address: None,
+            calling_party_address_ssi: Some(call.calling_addr.ssi),
+            calling_party_extension: None,
+            external_subscriber_number: None,
+            facility: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(80);
+        d_setup.to_bitbuf(&mut sdu).expect("Failed to serialize DSetup");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_setup, sdu.dump_bin());
+        Self::send_individual(queue, sdu, dltime, call.called_addr, None);
+    }
+
+    /// Handle U-SETUP for an individual call (ETSI 14.5.1.1)
+    /// Calling MS gets D-CALL-PROCEEDING, the called MS is paged with D-SETUP. The traffic channel
+    /// is only allocated once the called MS answers with U-CONNECT. A called ISSI that is not registered on
+    /// this cell is set up over Brew, if connected, and the network takes the role of the called MS.
+    fn rx_u_setup_individual(&mut self, queue: &mut MessageQueue, message: &SapMsg, mut pdu: USetup) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+            panic!()
+        };
+        let calling_party = prim.received_tetra_address;
+        let called_issi = self.apply_call_forwarding(pdu.called_party_ssi.expect("checked by rx_u_setup") as u32);
+        let called_addr = TetraAddress::new(called_issi, SsiType::Issi);
+
+        let brew_routable = brew::is_brew_individual_call_routable(&self.config, called_issi, pdu.call_priority);
+        let (called_registered, network_connected) = {
+            let state = self.config.state_read();
+            (state.subscribers.is_registered(called_issi), state.network_connected)
+        };
+        let bridged = !called_registered && brew_routable && network_connected;
+
+        // Emergency calls take over the lower priority individual calls either party is in
+        if pdu.call_priority == brew::EMERGENCY_CALL_PRIORITY && called_issi != calling_party.ssi && (called_registered || bridged) {
+            for issi in [calling_party.ssi, called_issi] {
+                if let Some(other) = self.individual_call_of(issi)
+                    && self.individual_calls[&other].priority < pdu.call_priority
+                {
+                    tracing::info!("Pre-empting individual call_id={} of ISSI {} for an emergency call", other, issi);
+                    self.release_individual_call(queue, other, DisconnectCause::PreEmptiveUseOfResource, None);
+                }
+            }
+        }
+
+        let reject_cause = if called_issi == calling_party.ssi {
+            Some(DisconnectCause::NotAllowedTrafficCase)
+        } else if !called_registered && !bridged {
+            Some(DisconnectCause::CalledPartyNotReachable)
+        } else if self.individual_call_of(called_issi).is_some() {
+            Some(DisconnectCause::CalledPartyBusy)
+        } else if self.individual_call_of(calling_party.ssi).is_some() {
+            Some(DisconnectCause::ConcurrentSetUpNotSupported)
+        } else {
+            None
+        };
+
+        let call_id = self.circuits.get_next_call_id();
+
+        if let Some(cause) = reject_cause {
+            tracing::info!(
+                "CMCE: rejecting individual U-SETUP from issi={} to issi={}: {:?}",
+                calling_party.ssi,
+                called_issi,
+                cause
+            );
+            let sdu = Self::build_d_release(call_id, cause);
+            Self::send_individual(queue, sdu, message.dltime, calling_party, None);
+            return;
+        }
+        self.raise_emergency_alarm(&pdu, calling_party.ssi, called_issi, true);
+
+        tracing::info!(
+            "rx_u_setup: individual call from ISSI {} to ISSI {} call_id={} hook={} bridged={}",
+            calling_party.ssi,
+            called_issi,
+            call_id,
+            pdu.hook_method_selection,
+            bridged
+        );
+
+        // Calls to network subscribers are bridged on a single circuit, so only simplex is offered
+        if bridged {
+            pdu.simplex_duplex_selection = false;
+        }
+        let call = IndividualCall {
+            calling_addr: calling_party,
+            called_addr,
+            hook_method: pdu.hook_method_selection,
+            simplex_duplex: pdu.simplex_duplex_selection,
+            basic_service_information: pdu.basic_service_information.clone(),
+            state: IndividualCallState::Setup,
+            setup_start: message.dltime,
+            ts: None,
+            usage: None,
+            called_ts: None,
+            tx_owner: None,
+            priority: pdu.call_priority,
+            network_party: bridged.then(|| NetworkParty {
+                brew_uuid: uuid::Uuid::new_v4(),
+                issi: called_issi,
+            }),
+        };
+
+        // === 1) Acknowledge the U-SETUP towards the calling MS ===
+        self.send_d_call_proceeding(queue, message, &pdu, call_id, None);
+
+        // === 2) Page the called MS with D-SETUP, no channel allocation yet, or set up the call over Brew ===
+        if let Some(party) = call.network_party {
+            let signal = IndividualCallSignal::Setup {
+                calling_issi: calling_party.ssi,
+                called_issi,
+                priority: call.priority,
+                hook_method: call.hook_method,
+            };
+            Self::signal_network_party(queue, message.dltime, party.brew_uuid, signal);
+        } else {
+            Self::send_individual_d_setup(queue, message.dltime, call_id, &call);
+        }
+
+        self.individual_calls.insert(call_id, call);
+    }
+
+    /// Applies the call forwarding set up by the called subscriber through the SS sub-entity, returning the ISSI to call.
+    /// Calls are forwarded once, the forwarding set up by the forwarded-to subscriber is not followed.
+    fn apply_call_forwarding(&self, called_issi: u32) -> u32 {
+        let brew_routable = brew::is_brew_issi_routable(&self.config, called_issi);
+        let reachable = {
+            let state = self.config.state_read();
+            state.subscribers.is_registered(called_issi) || (brew_routable && state.network_connected)
+        };
+        let busy = self.individual_call_of(called_issi).is_some();
+
+        let state = self.config.state_read();
+        let forwarding = &state.call_forwarding;
+        let forwarded_to = forwarding
+            .forwarded_to(called_issi, ForwardingCondition::Unconditional)
+            .or_else(|| (!reachable).then(|| forwarding.forwarded_to(called_issi, ForwardingCondition::NotReachable))?)
+            .or_else(|| busy.then(|| forwarding.forwarded_to(called_issi, ForwardingCondition::Busy))?);
+        match forwarded_to {
+            Some(issi) => {
+                tracing::info!("CMCE: call to ISSI {} forwarded to ISSI {}", called_issi, issi);
+                issi
+            }
+            None => called_issi,
+        }
+    }
+
+    /// Handle U-ALERT: the called user is being alerted (hook signalling). Relay as D-ALERT to the caller.
+    fn rx_u_alert(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address;
+
+        let pdu = match UAlert::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-ALERT: {:?}", e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            tracing::warn!("U-ALERT for unknown call_id={}", call_id);
+            return;
+        };
+        if call.called_addr.ssi != sender.ssi {
+            tracing::warn!(
+                "U-ALERT from ISSI {} which is not the called party of call_id={}",
+                sender.ssi,
+                call_id
+            );
+            return;
+        }
+        if call.state != IndividualCallState::Setup {
+            tracing::debug!("U-ALERT for call_id={} in state {:?}, ignoring", call_id, call.state);
+            return;
+        }
+
+        tracing::info!("U-ALERT: ISSI {} alerting on call_id={}", sender.ssi, call_id);
+        self.alert_individual_call(queue, call_id);
+    }
+
+    /// The called user of an individual call is being alerted: relay D-ALERT to the calling MS, or to the
+    /// network if the caller is a network subscriber
+    fn alert_individual_call(&mut self, queue: &mut MessageQueue, call_id: u16) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        call.state = IndividualCallState::Alerting;
+        // Restart the set-up phase timer, the called user now has the full alerting period to answer
+        call.setup_start = self.dltime;
+
+        if let Some(party) = call.network_party.filter(|party| party.issi == call.calling_addr.ssi) {
+            Self::signal_network_party(queue, self.dltime, party.brew_uuid, IndividualCallSignal::Alert);
+            return;
+        }
+
+        let d_alert = DAlert {
+            call_identifier: call_id,
+            call_time_out_set_up_phase: CallTimeoutSetupPhase::T60s.into_raw() as u8,
+            reserved: true, // Shall be set to 1, see note 1 of 14.7.1.1
+            simplex_duplex_selection: call.simplex_duplex,
+            call_queued: false,
+            basic_service_information: None,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        d_alert.to_bitbuf(&mut sdu).expect("Failed to serialize DAlert");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_alert, sdu.dump_bin());
+        Self::send_individual(queue, sdu, self.dltime, call.calling_addr, None);
+    }
+
+    /// Handle U-CONNECT: the called MS answers. Allocate the traffic channel, send D-CONNECT to the
+    /// caller (who gets the floor) and D-CONNECT ACKNOWLEDGE to the called MS.
+    fn rx_u_connect(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address;
+
+        let pdu = match UConnect::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-CONNECT: {:?}", e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+        let Some(call) = self.individual_calls.get(&call_id) else {
+            tracing::warn!("U-CONNECT for unknown call_id={}", call_id);
+            return;
+        };
+        if call.called_addr.ssi != sender.ssi {
+            tracing::warn!(
+                "U-CONNECT from ISSI {} which is not the called party of call_id={}",
+                sender.ssi,
+                call_id
+            );
+            return;
+        }
+        if call.state == IndividualCallState::Active {
+            tracing::debug!("U-CONNECT for already active call_id={}, ignoring", call_id);
+            return;
+        }
+        // The called party may only downgrade a duplex request to simplex, never the other way around
+        let duplex = call.simplex_duplex && pdu.simplex_duplex_selection;
+        self.connect_individual_call(queue, call_id, duplex);
+    }
+
+    /// The called party of an individual call answered. Allocate the traffic channel, send D-CONNECT to
+    /// the caller (who gets the floor) and D-CONNECT ACKNOWLEDGE to the called party. For a bridged call,
+    /// the network party is told instead and Brew is given the traffic channel.
+    fn connect_individual_call(&mut self, queue: &mut MessageQueue, call_id: u16, duplex: bool) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        call.simplex_duplex = duplex;
+        let comm_type = call.basic_service_information.communication_type;
+        let priority = call.priority;
+
+        let mut allocated = self.allocate_individual_circuits(call_id, comm_type, duplex);
+        if allocated.is_err() && priority >= PREEMPTIVE_PRIORITY_MIN && self.preempt_lower_priority_call(queue, priority) {
+            allocated = self.allocate_individual_circuits(call_id, comm_type, duplex);
+        }
+        let (circuit, called_circuit) = match allocated {
+            Ok(circuits) => circuits,
+            Err(e) => {
+                tracing::error!("Failed to allocate circuit for individual call_id={}: {:?}", call_id, e);
+                self.release_individual_call(queue, call_id, DisconnectCause::CongestionInInfrastructure, None);
+                return;
+            }
+        };
+
+        let call = self.individual_calls.get_mut(&call_id).unwrap();
+        call.state = IndividualCallState::Active;
+        call.ts = Some(circuit.ts);
+        call.usage = Some(circuit.usage);
+        call.called_ts = called_circuit.as_ref().map(|c| c.ts);
+        // In duplex calls both parties transmit simultaneously, there is no floor to hold
+        call.tx_owner = if duplex { None } else { Some(call.calling_addr.ssi) };
+        let call = call.clone();
+
+        tracing::info!(
+            "U-CONNECT: individual call_id={} {} <-> {} connected on ts={} usage={} duplex={}",
+            call_id,
+            call.calling_addr.ssi,
+            call.called_addr.ssi,
+            circuit.ts,
+            circuit.usage,
+            duplex
+        );
+
+        // In duplex, each party sends and receives on its own circuit. In simplex, the calling MS
+        // holds the floor first and the called MS listens.
+        let (calling, called) = (call.calling_addr.ssi, call.called_addr.ssi);
+        let parties = if duplex { (calling, calling) } else { (calling, called) };
+        Self::signal_umac_circuit_open(queue, &circuit, Some(parties), self.dltime);
+        if let Some(called_circuit) = &called_circuit {
+            Self::signal_umac_circuit_open(queue, called_circuit, Some((called, called)), self.dltime);
+        }
+
+        // D-CONNECT to the calling MS: through-connect and grant the floor
+        let d_connect = DConnect {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m,
+            hook_method_selection: call.hook_method,
+            simplex_duplex_selection: call.simplex_duplex,
+            transmission_grant: TransmissionGrant::Granted,
+            transmission_request_permission: false,
+            call_ownership: false, // Individual calls have no call owner
+            call_priority: None,
+            basic_service_information: None,
+            temporary_address: None,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_connect.to_bitbuf(&mut sdu).expect("Failed to serialize DConnect");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_connect, sdu.dump_bin());
+        let chan_alloc = Self::build_individual_chan_alloc(circuit.ts, circuit.usage);
+        if call.is_local(call.calling_addr.ssi) {
+            Self::send_individual(queue, sdu, self.dltime, call.calling_addr, Some(chan_alloc));
+        }
+
+        // D-CONNECT ACKNOWLEDGE to the called MS: through-connect. In simplex the other party has
+        // the floor, in duplex the called MS may transmit right away on its own circuit.
+        let (called_ts, called_usage, called_grant) = match &called_circuit {
+            Some(c) => (c.ts, c.usage, TransmissionGrant::Granted),
+            None => (circuit.ts, circuit.usage, TransmissionGrant::GrantedToOtherUser),
+        };
+        let d_connect_ack = DConnectAcknowledge {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m.into_raw() as u8,
+            transmission_grant: called_grant.into_raw() as u8,
+            transmission_request_permission: false,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_connect_ack.to_bitbuf(&mut sdu).expect("Failed to serialize DConnectAcknowledge");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_connect_ack, sdu.dump_bin());
+        let chan_alloc = Self::build_individual_chan_alloc(called_ts, called_usage);
+        if call.is_local(call.called_addr.ssi) {
+            Self::send_individual(queue, sdu, self.dltime, call.called_addr, Some(chan_alloc));
+        }
+
+        // Bridged call: Brew carries the network party's traffic on the circuit of the local MS
+        if let Some(party) = call.network_party {
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Brew,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
+                    brew_uuid: party.brew_uuid,
+                    call_id,
+                    ts: circuit.ts,
+                    usage: circuit.usage,
+                }),
+            });
+            if party.issi == call.called_addr.ssi {
+                // The local caller is through-connected and holds the floor
+                Self::signal_network_party(queue, self.dltime, party.brew_uuid, IndividualCallSignal::ConnectConfirm);
+                let signal = IndividualCallSignal::TxGranted {
+                    issi: call.calling_addr.ssi,
+                };
+                Self::signal_network_party(queue, self.dltime, party.brew_uuid, signal);
+            } else {
+                Self::signal_network_party(queue, self.dltime, party.brew_uuid, IndividualCallSignal::Connect);
+            }
+        }
+    }
+
+    /// Handle U-CALL RESTORE (ETSI 14.5.1.3): an MS that reselected to this cell during a call asks to continue it here.
+    /// The call is identified by the group for group calls, the MS itself takes part in an individual call.
+    /// A call that can't be restored is answered with D-RELEASE; MLE turns that into D-RESTORE-FAIL.
+    fn rx_u_call_restore(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address;
+
+        let pdu = match UCallRestore::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-CALL RESTORE: {:?}", e);
+                return;
+            }
+        };
+
+        // Group call in progress on this cell for the other party
+        let group_call = pdu.other_party_ssi.and_then(|gssi| {
+            self.active_calls
+                .iter()
+                .find(|(_, call)| call.dest_gssi as u64 == gssi)
+                .map(|(call_id, call)| (*call_id, call.clone()))
+        });
+
+        let (call_id, ts, usage, grant) = if let Some((call_id, call)) = group_call {
+            // The MS listens to the group here from now on, as if it affiliated on this cell
+            self.handle_subscriber_update(
+                queue,
+                MmSubscriberUpdate {
+                    issi: sender.ssi,
+                    groups: vec![call.dest_gssi],
+                    action: BrewSubscriberAction::Affiliate,
+                },
+            );
+            let grant = if call.tx_active {
+                TransmissionGrant::GrantedToOtherUser
+            } else {
+                TransmissionGrant::NotGranted
+            };
+            (call_id, call.ts, call.usage, grant)
+        } else if let Some(call_id) = self.individual_call_of(sender.ssi)
+            && let Some(call) = self.individual_calls.get(&call_id)
+            && call.state == IndividualCallState::Active
+            && let (Some(ts), Some(usage)) = (call.ts, call.usage)
+        {
+            // In duplex calls the called party has a circuit of its own
+            let ts = if call.called_addr.ssi == sender.ssi {
+                call.called_ts.unwrap_or(ts)
+            } else {
+                ts
+            };
+            let grant = match call.tx_owner {
+                _ if call.simplex_duplex => TransmissionGrant::Granted, // Duplex, both parties transmit
+                Some(owner) if owner == sender.ssi => TransmissionGrant::Granted,
+                Some(_) => TransmissionGrant::GrantedToOtherUser,
+                None => TransmissionGrant::NotGranted,
+            };
+            (call_id, ts, usage, grant)
+        } else {
+            tracing::info!(
+                "U-CALL RESTORE from ISSI {}: no call to restore for call_id={} other party {:?}",
+                sender.ssi,
+                pdu.call_identifier,
+                pdu.other_party_ssi
+            );
+            let sdu = Self::build_d_release(pdu.call_identifier, DisconnectCause::InvalidCallIdentifier);
+            Self::send_individual(queue, sdu, self.dltime, sender, None);
+            return;
+        };
+
+        tracing::info!(
+            "U-CALL RESTORE: ISSI {} restored into call_id={} (was call_id={}) on ts={}",
+            sender.ssi,
+            call_id,
+            pdu.call_identifier,
+            ts
+        );
+        let d_call_restore = DCallRestore {
+            call_identifier: pdu.call_identifier,
+            transmission_grant: grant.into_raw() as u8,
+            transmission_request_permission: false,
+            reset_call_time_out_timer_t310_: true,
+            new_call_identifier: (call_id != pdu.call_identifier).then_some(call_id as u64),
+            call_time_out: None,
+            call_status: None,
+            modify: None,
+            notification_indicator: None,
+            facility: None,
+            temporary_address: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(40);
+        d_call_restore.to_bitbuf(&mut sdu).expect("Failed to serialize DCallRestore");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_call_restore, sdu.dump_bin());
+        let chan_alloc = Self::build_individual_chan_alloc(ts, usage);
+        Self::send_individual(queue, sdu, self.dltime, sender, Some(chan_alloc));
+    }
+
+    /// Handle U-TX CEASED in a simplex individual call: inform both parties, enter signalling mode
+    fn rx_u_tx_ceased_individual(&mut self, queue: &mut MessageQueue, call_id: u16, sender_issi: u32) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        if call.simplex_duplex {
+            tracing::debug!("U-TX CEASED on duplex individual call_id={}, ignoring", call_id);
+            return;
+        }
+        let Some(ts) = call.ts else {
+            tracing::warn!("U-TX CEASED for individual call_id={} without traffic channel", call_id);
+            return;
+        };
+        if call.tx_owner != Some(sender_issi) {
+            tracing::debug!(
+                "U-TX CEASED from ISSI {} not holding the floor on call_id={}, ignoring",
+                sender_issi,
+                call_id
+            );
+            return;
+        }
+
+        tracing::info!("U-TX CEASED: ISSI {} released floor on individual call_id={}", sender_issi, call_id);
+        self.cease_individual_floor(queue, call_id, ts);
+    }
+
+    /// The floor holder of a simplex individual call stopped transmitting: inform the local parties with
+    /// D-TX CEASED, enter signalling mode, and tell the network if the floor was held by the local MS
+    fn cease_individual_floor(&mut self, queue: &mut MessageQueue, call_id: u16, ts: u8) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        let owner = call.tx_owner.take();
+        let parties: Vec<TetraAddress> = call.local_parties().collect();
+        if let Some(party) = call.network_party
+            && owner.is_some_and(|owner| owner != party.issi)
+        {
+            Self::signal_network_party(queue, self.dltime, party.brew_uuid, IndividualCallSignal::TxCeased);
+        }
+
+        let d_tx_ceased = DTxCeased {
+            call_identifier: call_id,
+            transmission_request_permission: false,
+            notification_indicator: None,
+            facility: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+        for addr in parties {
+            let mut sdu = BitBuffer::new_autoexpand(25);
+            d_tx_ceased.to_bitbuf(&mut sdu).expect("Failed to serialize DTxCeased");
+            sdu.seek(0);
+            tracing::info!("-> FACCH {:?} sdu {}", d_tx_ceased, sdu.dump_bin());
+            queue.push_back(Self::build_sapmsg_stealing(sdu, self.dltime, addr, ts));
+        }
+
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+        });
+    }
+
+    /// Handle U-TX DEMAND in a simplex individual call: grant the floor if the other party is not transmitting
+    fn rx_u_tx_demand_individual(&mut self, queue: &mut MessageQueue, call_id: u16, requesting_issi: u32) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        if call.simplex_duplex {
+            tracing::debug!("U-TX DEMAND on duplex individual call_id={}, ignoring", call_id);
+            return;
+        }
+        let Some(ts) = call.ts else {
+            tracing::warn!("U-TX DEMAND for individual call_id={} without traffic channel", call_id);
+            return;
+        };
+        if !call.involves(requesting_issi) {
+            tracing::warn!(
+                "U-TX DEMAND from ISSI {} which is not a party of call_id={}",
+                requesting_issi,
+                call_id
+            );
+            return;
+        }
+        if let Some(owner) = call.tx_owner.filter(|owner| *owner != requesting_issi) {
+            tracing::info!(
+                "U-TX DEMAND from ISSI {} rejected, ISSI {} transmitting on individual call_id={}",
+                requesting_issi,
+                owner,
+                call_id
+            );
+            return;
+        }
 
-        let expired: Vec<u16> = self
-            .active_calls
-            .iter()
-            .filter_map(|(&call_id, call)| {
-                if let Some(hangtime_start) = call.hangtime_start {
-                    if hangtime_start.age(self.dltime) > HANGTIME_FRAMES {
-                        return Some(call_id);
-                    }
-                }
-                None
-            })
-            .collect();
+        tracing::info!(
+            "U-TX DEMAND: ISSI {} granted floor on individual call_id={}",
+            requesting_issi,
+            call_id
+        );
+        self.grant_individual_floor(queue, call_id, requesting_issi, ts);
+    }
 
-        for call_id in expired {
-            tracing::info!("Hangtime expired for call_id={}, releasing", call_id);
-            self.release_call(queue, call_id, DisconnectCause::ExpiryOfTimer);
+    /// Give the floor of a simplex individual call to the given party: D-TX GRANTED to the local parties,
+    /// leave signalling mode, and tell the network if the floor goes to the local MS
+    fn grant_individual_floor(&mut self, queue: &mut MessageQueue, call_id: u16, issi: u32, ts: u8) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        call.tx_owner = Some(issi);
+        let requester = TetraAddress::new(issi, SsiType::Issi);
+        let peer = call.peer_of(issi);
+        let priority = call.priority;
+        if let Some(party) = call.network_party
+            && party.issi != issi
+        {
+            Self::signal_network_party(queue, self.dltime, party.brew_uuid, IndividualCallSignal::TxGranted { issi });
         }
-    }
 
-    fn release_timeslot(&mut self, ts: u8) {
-        let mut state = self.config.state_write();
-        if let Err(err) = state.timeslot_alloc.release(TimeslotOwner::Cmce, ts) {
-            tracing::warn!("CcBsSubentity: failed to release timeslot ts={} err={:?}", ts, err);
+        for (addr, grant) in [
+            (requester, TransmissionGrant::Granted),
+            (peer, TransmissionGrant::GrantedToOtherUser),
+        ] {
+            if !call.is_local(addr.ssi) {
+                continue;
+            }
+            let pdu = DTxGranted {
+                call_identifier: call_id,
+                transmission_grant: grant.into_raw() as u8,
+                transmission_request_permission: false,
+                encryption_control: false,
+                reserved: false,
+                notification_indicator: None,
+                transmitting_party_type_identifier: Some(1), // SSI
+                transmitting_party_address_ssi: Some(issi as u64),
+                transmitting_party_extension: None,
+                external_subscriber_number: None,
+                facility: None,
+                dm_ms_address: None,
+                proprietary: None,
+            };
+            let mut sdu = BitBuffer::new_autoexpand(50);
+            pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DTxGranted");
+            sdu.seek(0);
+            tracing::info!("-> FACCH {:?} sdu {}", pdu, sdu.dump_bin());
+            queue.push_back(Self::build_sapmsg_stealing(sdu, self.dltime, addr, ts));
         }
+
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+                call_id,
+                source_issi: issi,
+                dest_gssi: peer.ssi,
+                ts,
+                priority,
+            }),
+        });
     }
 
-    /// Release a call: send D-RELEASE, close circuits, clean up state
-    fn release_call(&mut self, queue: &mut MessageQueue, call_id: u16, disconnect_cause: DisconnectCause) {
-        let Some((pdu, dest_addr, _)) = self.cached_setups.get(&call_id) else {
-            tracing::error!("No cached D-SETUP for call_id={}", call_id);
+    /// Tear down an individual call: D-RELEASE to the involved parties, close the circuit.
+    /// If `released_by` is set, that MS already released its side (U-RELEASE) and is not sent a D-RELEASE.
+    fn release_individual_call(
+        &mut self,
+        queue: &mut MessageQueue,
+        call_id: u16,
+        disconnect_cause: DisconnectCause,
+        released_by: Option<u32>,
+    ) {
+        let Some(call) = self.individual_calls.remove(&call_id) else {
+            tracing::debug!("release_individual_call: unknown call_id={}", call_id);
             return;
         };
-        let dest_addr = *dest_addr;
 
-        // Send D-RELEASE to group
-        let sdu = Self::build_d_release_from_d_setup(pdu, disconnect_cause);
-        let prim = Self::build_sapmsg(sdu, None, self.dltime, dest_addr, Layer2Service::Unacknowledged, None);
-        queue.push_back(prim);
+        tracing::info!(
+            "CMCE: releasing individual call_id={} {} <-> {} cause={:?}",
+            call_id,
+            call.calling_addr.ssi,
+            call.called_addr.ssi,
+            disconnect_cause
+        );
 
-        // Close the circuit in CircuitMgr and notify Brew
-        if let Some(call) = self.active_calls.get(&call_id) {
-            let ts = call.ts;
-            let dest_ssi = call.dest_gssi;
-            let is_local = matches!(call.origin, CallOrigin::Local { .. });
+        for addr in call.local_parties() {
+            if released_by == Some(addr.ssi) {
+                continue;
+            }
+            let sdu = Self::build_d_release(call_id, disconnect_cause);
+            Self::send_individual(queue, sdu, self.dltime, addr, None);
+        }
+        if let Some(party) = call.network_party
+            && released_by != Some(party.issi)
+        {
+            let signal = IndividualCallSignal::Release {
+                cause: disconnect_cause.into_raw() as u8,
+            };
+            Self::signal_network_party(queue, self.dltime, party.brew_uuid, signal);
+        }
 
+        for ts in [call.ts, call.called_ts].into_iter().flatten() {
             if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, ts) {
                 Self::signal_umac_circuit_close(queue, circuit, self.dltime);
             }
-
-            // Ensure UMAC clears hangtime even if the CMCE circuit was already closed above.
             queue.push_back(SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
@@ -801,27 +2049,27 @@ impl CcBsSubentity {
                 dltime: self.dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
             });
-
             self.release_timeslot(ts);
-
-            // Notify Brew only for local calls on SSIs that are cleared for Brew
-            if brew::is_brew_gssi_routable(&self.config, dest_ssi) {
-                if is_local {
-                    let notify = SapMsg {
-                        sap: Sap::Control,
-                        src: TetraEntity::Cmce,
-                        dest: TetraEntity::Brew,
-                        dltime: self.dltime,
-                        msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
-                    };
-                    queue.push_back(notify);
-                }
-            }
         }
+    }
 
-        // Clean up
-        self.cached_setups.remove(&call_id);
-        self.active_calls.remove(&call_id);
+    /// Release individual calls whose called party did not answer within the set-up phase timeout
+    fn check_individual_setup_expiry(&mut self, queue: &mut MessageQueue) {
+        // Generous bound on the set-up phase: covers the T60s alerting period we signal in D-ALERT
+        const SETUP_TIMEOUT_TIMESLOTS: i32 = 61 * 18 * 4;
+
+        let expired: Vec<u16> = self
+            .individual_calls
+            .iter()
+            .filter(|(_, call)| call.state != IndividualCallState::Active)
+            .filter(|(_, call)| call.setup_start.age(self.dltime) > SETUP_TIMEOUT_TIMESLOTS)
+            .map(|(call_id, _)| *call_id)
+            .collect();
+
+        for call_id in expired {
+            tracing::info!("Set-up phase expired for individual call_id={}, releasing", call_id);
+            self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+        }
     }
 
     fn feature_check_u_setup(pdu: &USetup) -> bool {
@@ -831,12 +2079,9 @@ impl CcBsSubentity {
             unimplemented_log!("Area selection not supported: {}", pdu.area_selection);
             supported = false;
         };
-        if pdu.hook_method_selection == true {
-            unimplemented_log!("Hook method selection not supported: {}", pdu.hook_method_selection);
-            supported = false;
-        };
-        if pdu.simplex_duplex_selection != false {
-            unimplemented_log!("Only simplex calls supported: {}", pdu.simplex_duplex_selection);
+        // Duplex is only meaningful for individual calls, where each party gets its own circuit
+        if pdu.simplex_duplex_selection && pdu.basic_service_information.communication_type != CommunicationType::P2p {
+            unimplemented_log!("Duplex only supported for individual calls: {}", pdu.simplex_duplex_selection);
             supported = false;
         };
         // if pdu.basic_service_information != 0xFC {
@@ -889,6 +2134,11 @@ impl CcBsSubentity {
 
         let call_id = pdu.call_identifier;
 
+        if self.individual_calls.contains_key(&call_id) {
+            self.rx_u_tx_ceased_individual(queue, call_id, prim.received_tetra_address.ssi);
+            return;
+        }
+
         // Look up the active call
         let Some(call) = self.active_calls.get_mut(&call_id) else {
             tracing::warn!("U-TX CEASED for unknown call_id={}", call_id);
@@ -905,6 +2155,7 @@ impl CcBsSubentity {
 
         let ts = call.ts;
         let dest_ssi = call.dest_gssi;
+        let priority = call.priority;
         call.tx_active = false;
         call.hangtime_start = Some(self.dltime);
 
@@ -944,11 +2195,11 @@ impl CcBsSubentity {
         });
 
         // Notify Brew to stop forwarding audio, if this SSI is cleared for Br
-        if brew::is_brew_gssi_routable(&self.config, dest_ssi) {
+        for dest in self.network_bridges(dest_ssi, priority) {
             queue.push_back(SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
+                dest,
                 dltime: self.dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
             });
@@ -976,6 +2227,11 @@ impl CcBsSubentity {
 
         let call_id = pdu.call_identifier;
 
+        if self.individual_calls.contains_key(&call_id) {
+            self.rx_u_tx_demand_individual(queue, call_id, requesting_party.ssi);
+            return;
+        }
+
         let Some(call) = self.active_calls.get_mut(&call_id) else {
             tracing::warn!("U-TX DEMAND for unknown call_id={}", call_id);
             return;
@@ -997,6 +2253,7 @@ impl CcBsSubentity {
 
         // Grant the floor to the requesting MS
         let ts = call.ts;
+        let priority = call.priority;
         call.tx_active = true;
         call.hangtime_start = None;
         call.source_issi = requesting_party.ssi;
@@ -1052,24 +2309,26 @@ impl CcBsSubentity {
                 source_issi: requesting_party.ssi,
                 dest_gssi: dest_addr.ssi,
                 ts,
+                priority,
             }),
         });
 
-        // Notify Brew of speaker change (local MS taking floor)
-        if brew::is_brew_gssi_routable(&self.config, dest_addr.ssi) {
-            let Some(call) = self.active_calls.get(&call_id) else {
-                return;
-            };
+        // Notify Brew and the backhaul of speaker change (local MS taking floor)
+        let Some(call_ts) = self.active_calls.get(&call_id).map(|call| call.ts) else {
+            return;
+        };
+        for dest in self.network_bridges(dest_addr.ssi, priority) {
             queue.push_back(SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
+                dest,
                 dltime: self.dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
                     call_id,
                     source_issi: requesting_party.ssi,
                     dest_gssi: dest_addr.ssi,
-                    ts: call.ts,
+                    ts: call_ts,
+                    priority,
                 }),
             });
         }
@@ -1094,6 +2353,16 @@ impl CcBsSubentity {
 
         let call_id = pdu.call_identifier;
         tracing::info!("U-RELEASE: call_id={} cause={}", call_id, pdu.disconnect_cause);
+        // U-RELEASE ends the call without a response, also while it is still queued
+        if self.cancel_queued_call(queue, call_id, prim.received_tetra_address.ssi, false) {
+            return;
+        }
+        if self.individual_calls.contains_key(&call_id) {
+            // Either party may release; the other one is informed with the cause given by the releasing MS
+            let sender = prim.received_tetra_address.ssi;
+            self.release_individual_call(queue, call_id, pdu.disconnect_cause, Some(sender));
+            return;
+        }
         self.release_call(queue, call_id, DisconnectCause::UserRequestedDisconnection);
     }
 
@@ -1123,6 +2392,20 @@ impl CcBsSubentity {
         let call_id = pdu.call_identifier;
         let disconnect_cause = pdu.disconnect_cause;
 
+        if self.cancel_queued_call(queue, call_id, sender.ssi, true) {
+            return;
+        }
+
+        if let Some(call) = self.individual_calls.get(&call_id) {
+            if !call.involves(sender.ssi) {
+                tracing::warn!("U-DISCONNECT from ISSI {} which is not a party of call_id={}", sender.ssi, call_id);
+                return;
+            }
+            tracing::info!("U-DISCONNECT: ISSI {} disconnecting individual call_id={}", sender.ssi, call_id);
+            self.release_individual_call(queue, call_id, disconnect_cause, None);
+            return;
+        }
+
         let Some(call) = self.active_calls.get(&call_id) else {
             tracing::debug!("U-DISCONNECT for unknown call_id={} (likely duplicate)", call_id);
             return;
@@ -1182,8 +2465,137 @@ impl CcBsSubentity {
         }
     }
 
-    /// Handle incoming CallControl messages from Brew
+    /// Handle U-INFO. Only DTMF is supported: the digits are relayed to the other parties of the call,
+    /// and to Brew if the call is bridged to the network.
+    fn rx_u_info(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address;
+
+        let pdu = match UInfo::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-INFO: {:?}", e);
+                return;
+            }
+        };
+        let Some(field) = &pdu.dtmf else {
+            unimplemented_log!("U-INFO without DTMF from ISSI {}", sender.ssi);
+            return;
+        };
+        let dtmf = match Dtmf::from_type3(field) {
+            Ok(dtmf) => dtmf,
+            Err(e) => {
+                tracing::warn!("Failed parsing DTMF in U-INFO from ISSI {}: {:?}", sender.ssi, e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+        let to_network = if let Some(call) = self.individual_calls.get(&call_id) {
+            if !call.involves(sender.ssi) || call.state != IndividualCallState::Active {
+                tracing::warn!("U-INFO from ISSI {} not connected to call_id={}", sender.ssi, call_id);
+                return;
+            }
+            let peer = call.peer_of(sender.ssi);
+            if !call.is_local(peer.ssi) {
+                true
+            } else {
+                Self::send_d_info_dtmf(queue, self.dltime, call_id, &dtmf, peer, call.ts_of(peer.ssi));
+                false
+            }
+        } else if let Some(call) = self.active_calls.get(&call_id) {
+            // Only the party holding the floor dials digits into a group call
+            if !call.tx_active || call.source_issi != sender.ssi {
+                tracing::warn!("U-INFO from ISSI {} not transmitting on call_id={}", sender.ssi, call_id);
+                return;
+            }
+            let group = TetraAddress::new(call.dest_gssi, SsiType::Gssi);
+            Self::send_d_info_dtmf(queue, self.dltime, call_id, &dtmf, group, Some(call.ts));
+            brew::is_brew_call_routable(&self.config, call.dest_gssi, call.priority)
+        } else {
+            tracing::warn!("U-INFO for unknown call_id={}", call_id);
+            return;
+        };
+
+        // The network only carries the digits, the end of a tone is not signalled
+        if to_network && dtmf.dtmf_type == DtmfType::ToneStart {
+            tracing::info!("CMCE: DTMF {:?} on call_id={} -> Brew", dtmf.digits, call_id);
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Brew,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::CallDtmf {
+                    call_id,
+                    digits: dtmf.digits,
+                }),
+            });
+        }
+    }
+
+    /// Handle DTMF digits received from the network party of a call, delivered to the local parties with D-INFO
+    fn rx_network_dtmf(&mut self, queue: &mut MessageQueue, call_id: u16, digits: String) {
+        let Some(dtmf) = Dtmf::tone_start(&digits) else {
+            tracing::warn!("CMCE: ignoring invalid network DTMF digits {:?} on call_id={}", digits, call_id);
+            return;
+        };
+
+        if let Some(call) = self.individual_calls.get(&call_id) {
+            if call.network_party.is_none() || call.state != IndividualCallState::Active {
+                tracing::warn!("CMCE: network DTMF for call_id={} which is not bridged and connected", call_id);
+                return;
+            }
+            for addr in call.local_parties() {
+                Self::send_d_info_dtmf(queue, self.dltime, call_id, &dtmf, addr, call.ts_of(addr.ssi));
+            }
+        } else if let Some(call) = self.active_calls.get(&call_id) {
+            let group = TetraAddress::new(call.dest_gssi, SsiType::Gssi);
+            Self::send_d_info_dtmf(queue, self.dltime, call_id, &dtmf, group, Some(call.ts));
+        } else {
+            tracing::warn!("CMCE: network DTMF for unknown call_id={}", call_id);
+        }
+    }
+
+    /// Send D-INFO with DTMF. Parties on a traffic channel get it via FACCH stealing, others on the MCCH.
+    fn send_d_info_dtmf(queue: &mut MessageQueue, dltime: TdmaTime, call_id: u16, dtmf: &Dtmf, addr: TetraAddress, ts: Option<u8>) {
+        let pdu = DInfo {
+            call_identifier: call_id,
+            reset_call_time_out_timer_t310_: false,
+            poll_request: false,
+            new_call_identifier: None,
+            call_time_out: None,
+            call_time_out_set_up_phase_t301_t302_: None,
+            call_ownership: None,
+            modify: None,
+            call_status: None,
+            temporary_address: None,
+            notification_indicator: None,
+            poll_response_percentage: None,
+            poll_response_number: None,
+            dtmf: Some(dtmf.to_type3()),
+            facility: None,
+            poll_response_addresses: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(48);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DInfo");
+        sdu.seek(0);
+        tracing::info!("-> {:?} {} sdu {}", pdu, dtmf, sdu.dump_bin());
+        match ts {
+            Some(ts) => queue.push_back(Self::build_sapmsg_stealing(sdu, dltime, addr, ts)),
+            None => Self::send_individual(queue, sdu, dltime, addr, None),
+        }
+    }
+
+    /// Handle incoming CallControl messages from Brew or the backhaul
     pub fn rx_call_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let src = message.src;
         let SapMsgInner::CmceCallControl(call_control) = message.msg else {
             panic!("Expected CmceCallControl message");
         };
@@ -1195,7 +2607,7 @@ impl CcBsSubentity {
                 dest_gssi,
                 priority,
             } => {
-                self.rx_network_call_start(queue, brew_uuid, source_issi, dest_gssi, priority);
+                self.rx_network_call_start(queue, src, brew_uuid, source_issi, dest_gssi, priority);
             }
             CallControl::NetworkCallEnd { brew_uuid } => {
                 self.rx_network_call_end(queue, brew_uuid);
@@ -1203,15 +2615,31 @@ impl CcBsSubentity {
             CallControl::UlInactivityTimeout { ts } => {
                 self.handle_ul_inactivity_timeout(queue, ts);
             }
+            CallControl::NetworkIndividualCall { brew_uuid, signal } => {
+                self.rx_network_individual_call(queue, brew_uuid, signal);
+            }
+            CallControl::CallDtmf { call_id, digits } => {
+                self.rx_network_dtmf(queue, call_id, digits);
+            }
             _ => {
                 tracing::warn!("Unexpected CallControl message: {:?}", call_control);
             }
         }
     }
 
-    /// Handle network-initiated group call start
-    fn rx_network_call_start(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid, source_issi: u32, dest_gssi: u32, _priority: u8) {
-        assert!(brew::is_brew_gssi_routable(&self.config, dest_gssi));
+    /// Handle network-initiated group call start, from Brew or another site (src)
+    fn rx_network_call_start(
+        &mut self,
+        queue: &mut MessageQueue,
+        src: TetraEntity,
+        brew_uuid: uuid::Uuid,
+        source_issi: u32,
+        dest_gssi: u32,
+        priority: u8,
+    ) {
+        // Call priority is a 4-bit field on the air interface
+        let priority = priority.min(15);
+        assert!(src != TetraEntity::Brew || brew::is_brew_call_routable(&self.config, dest_gssi, priority));
 
         if !self.has_listener(dest_gssi) {
             tracing::info!(
@@ -1225,7 +2653,7 @@ impl CcBsSubentity {
             queue.push_back(SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
+                dest: src,
                 dltime: self.dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
             });
@@ -1244,7 +2672,7 @@ impl CcBsSubentity {
                 queue.push_back(SapMsg {
                     sap: Sap::Control,
                     src: TetraEntity::Cmce,
-                    dest: TetraEntity::Brew,
+                    dest: src,
                     dltime: self.dltime,
                     msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
                 });
@@ -1264,11 +2692,15 @@ impl CcBsSubentity {
             call.hangtime_start = None;
             call.brew_uuid = Some(brew_uuid);
 
-            if let CallOrigin::Network { brew_uuid: old_uuid } = call.origin {
+            if let CallOrigin::Network {
+                brew_uuid: old_uuid,
+                entity,
+            } = call.origin
+            {
                 // Update UUID if different (shouldn't happen but handle it)
-                if old_uuid != brew_uuid {
+                if old_uuid != brew_uuid || entity != src {
                     tracing::warn!("CMCE: brew_uuid changed during speaker change");
-                    call.origin = CallOrigin::Network { brew_uuid };
+                    call.origin = CallOrigin::Network { brew_uuid, entity: src };
                 }
             }
 
@@ -1276,6 +2708,7 @@ impl CcBsSubentity {
             let call_id_val = *call_id;
             let ts = call.ts;
             let usage = call.usage;
+            let priority = call.priority;
 
             // End the mutable borrow
             let _ = call;
@@ -1294,6 +2727,7 @@ impl CcBsSubentity {
                     source_issi,
                     dest_gssi,
                     ts,
+                    priority,
                 }),
             });
 
@@ -1301,7 +2735,7 @@ impl CcBsSubentity {
             queue.push_back(SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
+                dest: src,
                 dltime: self.dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
                     brew_uuid,
@@ -1313,17 +2747,14 @@ impl CcBsSubentity {
             return;
         }
 
-        // New network call - allocate circuit
-        let circuit = match {
-            let mut state = self.config.state_write();
-            self.circuits.allocate_circuit_with_allocator(
-                Direction::Both,
-                CommunicationType::P2Mp,
-                &mut state.timeslot_alloc,
-                TimeslotOwner::Cmce,
-            )
-        } {
-            Ok(c) => c.clone(),
+        // New network call - allocate circuit, pre-empting a lower priority call if needed and allowed.
+        let call_id = self.circuits.get_next_call_id();
+        let mut allocated = self.allocate_group_circuit(call_id, CommunicationType::P2Mp);
+        if allocated.is_err() && priority >= PREEMPTIVE_PRIORITY_MIN && self.preempt_lower_priority_call(queue, priority) {
+            allocated = self.allocate_group_circuit(call_id, CommunicationType::P2Mp);
+        }
+        let circuit = match allocated {
+            Ok(c) => c,
             Err(err) => {
                 tracing::warn!("CMCE: failed to allocate circuit for network call: {:?}", err);
                 return;
@@ -1344,7 +2775,7 @@ impl CcBsSubentity {
         );
 
         // Signal UMAC to open DL and UL circuits
-        Self::signal_umac_circuit_open(queue, &circuit, self.dltime);
+        Self::signal_umac_circuit_open(queue, &circuit, None, self.dltime);
 
         tracing::debug!(
             "CMCE: sending D-SETUP for NEW call call_id={} gssi={} (network-initiated)",
@@ -1368,7 +2799,7 @@ impl CcBsSubentity {
             },
             transmission_grant: TransmissionGrant::GrantedToOtherUser,
             transmission_request_permission: false,
-            call_priority: 0,
+            call_priority: priority,
             notification_indicator: None,
             temporary_address: None,
             calling_party_address_ssi: Some(source_issi),
@@ -1443,11 +2874,12 @@ impl CcBsSubentity {
         self.active_calls.insert(
             call_id,
             ActiveCall {
-                origin: CallOrigin::Network { brew_uuid },
+                origin: CallOrigin::Network { brew_uuid, entity: src },
                 dest_gssi,
                 source_issi,
                 ts,
                 usage,
+                priority,
                 tx_active: true,
                 hangtime_start: None,
                 brew_uuid: Some(brew_uuid),
@@ -1458,7 +2890,7 @@ impl CcBsSubentity {
         queue.push_back(SapMsg {
             sap: Sap::Control,
             src: TetraEntity::Cmce,
-            dest: TetraEntity::Brew,
+            dest: src,
             dltime: self.dltime,
             msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
                 brew_uuid,
@@ -1518,6 +2950,159 @@ impl CcBsSubentity {
     }
 
     /// Send D-TX GRANTED via FACCH stealing
+    /// Handle individual call signalling from a network subscriber, relayed by Brew
+    fn rx_network_individual_call(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid, signal: IndividualCallSignal) {
+        if let IndividualCallSignal::Setup {
+            calling_issi,
+            called_issi,
+            priority,
+            hook_method,
+        } = signal
+        {
+            self.rx_network_individual_setup(queue, brew_uuid, calling_issi, called_issi, priority, hook_method);
+            return;
+        }
+
+        let Some(call_id) = self.individual_call_of_network(brew_uuid) else {
+            tracing::debug!("Network {:?} for unknown individual call uuid={}", signal, brew_uuid);
+            return;
+        };
+        let call = &self.individual_calls[&call_id];
+        let Some(party) = call.network_party else {
+            return;
+        };
+        let network_calls = party.issi == call.calling_addr.ssi;
+
+        match signal {
+            IndividualCallSignal::Setup { .. } => unreachable!(),
+            IndividualCallSignal::Accept | IndividualCallSignal::ConnectConfirm => {
+                tracing::debug!("Network {:?} on individual call_id={}", signal, call_id);
+            }
+            IndividualCallSignal::Alert => {
+                if network_calls || call.state != IndividualCallState::Setup {
+                    tracing::debug!("Network alert for call_id={} in state {:?}, ignoring", call_id, call.state);
+                    return;
+                }
+                tracing::info!("Network: ISSI {} alerting on call_id={}", party.issi, call_id);
+                self.alert_individual_call(queue, call_id);
+            }
+            IndividualCallSignal::Connect => {
+                if network_calls || call.state == IndividualCallState::Active {
+                    tracing::debug!("Network connect for call_id={} in state {:?}, ignoring", call_id, call.state);
+                    return;
+                }
+                tracing::info!("Network: ISSI {} answered call_id={}", party.issi, call_id);
+                self.connect_individual_call(queue, call_id, false);
+            }
+            IndividualCallSignal::TxGranted { issi } => {
+                let Some(ts) = call.ts else {
+                    tracing::warn!("Network floor grant for individual call_id={} without traffic channel", call_id);
+                    return;
+                };
+                if issi != party.issi {
+                    tracing::debug!("Network floor grant to ISSI {} on call_id={}, ignoring", issi, call_id);
+                    return;
+                }
+                if let Some(owner) = call.tx_owner.filter(|owner| *owner != party.issi) {
+                    tracing::info!(
+                        "Network floor grant to ISSI {} rejected, ISSI {} transmitting on individual call_id={}",
+                        issi,
+                        owner,
+                        call_id
+                    );
+                    return;
+                }
+                tracing::info!("Network: ISSI {} granted floor on individual call_id={}", issi, call_id);
+                self.grant_individual_floor(queue, call_id, issi, ts);
+            }
+            IndividualCallSignal::TxCeased => {
+                let Some(ts) = call.ts else {
+                    return;
+                };
+                if call.tx_owner != Some(party.issi) {
+                    tracing::debug!("Network floor release on call_id={} while not holding the floor, ignoring", call_id);
+                    return;
+                }
+                tracing::info!("Network: ISSI {} released floor on individual call_id={}", party.issi, call_id);
+                self.cease_individual_floor(queue, call_id, ts);
+            }
+            IndividualCallSignal::Release { cause } => {
+                let cause = DisconnectCause::try_from(cause as u64).unwrap_or(DisconnectCause::CauseNotDefinedOrUnknown);
+                self.release_individual_call(queue, call_id, cause, Some(party.issi));
+            }
+        }
+    }
+
+    /// Handle a call from a network subscriber to a local MS: page the called MS with D-SETUP, or reject
+    /// the call towards the network if the called MS can't take it
+    fn rx_network_individual_setup(
+        &mut self,
+        queue: &mut MessageQueue,
+        brew_uuid: uuid::Uuid,
+        calling_issi: u32,
+        called_issi: u32,
+        priority: u8,
+        hook_method: bool,
+    ) {
+        let reject_cause = if !self.config.state_read().subscribers.is_registered(called_issi) {
+            Some(DisconnectCause::CalledPartyNotReachable)
+        } else if self.individual_call_of(called_issi).is_some() {
+            Some(DisconnectCause::CalledPartyBusy)
+        } else {
+            None
+        };
+        if let Some(cause) = reject_cause {
+            tracing::info!(
+                "CMCE: rejecting network individual call from issi={} to issi={}: {:?}",
+                calling_issi,
+                called_issi,
+                cause
+            );
+            let signal = IndividualCallSignal::Release {
+                cause: cause.into_raw() as u8,
+            };
+            Self::signal_network_party(queue, self.dltime, brew_uuid, signal);
+            return;
+        }
+
+        let call_id = self.circuits.get_next_call_id();
+        tracing::info!(
+            "CMCE: network individual call from ISSI {} to ISSI {} call_id={} hook={}",
+            calling_issi,
+            called_issi,
+            call_id,
+            hook_method
+        );
+        let call = IndividualCall {
+            calling_addr: TetraAddress::new(calling_issi, SsiType::Issi),
+            called_addr: TetraAddress::new(called_issi, SsiType::Issi),
+            hook_method,
+            // Bridged on a single circuit, so always simplex
+            simplex_duplex: false,
+            basic_service_information: BasicServiceInformation {
+                circuit_mode_type: CircuitModeType::TchS,
+                encryption_flag: false,
+                communication_type: CommunicationType::P2p,
+                slots_per_frame: None,
+                speech_service: Some(0), // TETRA encoded speech
+            },
+            state: IndividualCallState::Setup,
+            setup_start: self.dltime,
+            ts: None,
+            usage: None,
+            called_ts: None,
+            tx_owner: None,
+            priority: priority.min(15),
+            network_party: Some(NetworkParty {
+                brew_uuid,
+                issi: calling_issi,
+            }),
+        };
+        Self::send_individual_d_setup(queue, self.dltime, call_id, &call);
+        self.individual_calls.insert(call_id, call);
+        Self::signal_network_party(queue, self.dltime, brew_uuid, IndividualCallSignal::Accept);
+    }
+
     fn send_d_tx_granted_facch(&mut self, queue: &mut MessageQueue, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8) {
         let pdu = DTxGranted {
             call_identifier: call_id,
@@ -1564,6 +3149,7 @@ impl CcBsSubentity {
         tracing::warn!("UL inactivity timeout on ts={}, forcing TX ceased for call_id={}", ts, call_id);
 
         let dest_gssi = call.dest_gssi;
+        let priority = call.priority;
         call.tx_active = false;
         call.hangtime_start = Some(self.dltime);
 
@@ -1579,12 +3165,12 @@ impl CcBsSubentity {
             msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
         });
 
-        // Notify Brew to stop forwarding audio
-        if brew::is_brew_gssi_routable(&self.config, dest_gssi) {
+        // Notify Brew and the backhaul to stop forwarding audio
+        for dest in self.network_bridges(dest_gssi, priority) {
             queue.push_back(SapMsg {
                 sap: Sap::Control,
                 src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
+                dest,
                 dltime: self.dltime,
                 msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
             });
@@ -1,18 +1,576 @@
-use tetra_core::unimplemented_log;
-use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
-use tetra_saps::{SapMsg, SapMsgInner};
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{
+    BitBuffer, Direction, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log,
+};
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::{
+    enums::{cmce_pdu_type_dl::CmcePduTypeDl, transmission_grant::TransmissionGrant},
+    fields::basic_service_information::BasicServiceInformation,
+    pdus::{
+        d_call_proceeding::DCallProceeding, d_connect::DConnect, d_disconnect::DDisconnect, d_release::DRelease, d_setup::DSetup,
+        d_tx_ceased::DTxCeased, d_tx_granted::DTxGranted, u_disconnect::UDisconnect, u_release::URelease, u_setup::USetup,
+        u_tx_ceased::UTxCeased, u_tx_demand::UTxDemand,
+    },
+};
+use tetra_saps::tncc::{TnccReleaseInd, TnccSetupCon, TnccSetupInd, TnccTxInd, TnccTxState};
+use tetra_saps::{
+    SapMsg, SapMsgInner,
+    control::{
+        call_control::{CallControl, Circuit},
+        enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType},
+    },
+    lcmc::{LcmcMleUnitdataReq, fields::chan_alloc_req::CmceChanAllocReq},
+};
 
 use crate::MessageQueue;
 
+/// Time to wait for the SwMI to connect a call we set up, in timeslots (~10 s)
+const CC_SETUP_TIMEOUT: i32 = 10 * 18 * 4;
+
+/// Group call we requested with U-SETUP, waiting for D-CONNECT
+struct PendingSetup {
+    called_ssi: u32,
+    /// Known once D-CALL PROCEEDING has been received
+    call_id: Option<u16>,
+    deadline: TdmaTime,
+}
+
+/// Group call we take part in, either set up by us or joined after a D-SETUP
+struct MsCall {
+    call_id: u16,
+    called_ssi: u32,
+    /// Traffic timeslot and usage marker, from the channel allocation
+    ts: Option<u8>,
+    usage: Option<u8>,
+    /// Whether we hold the floor, and our uplink circuit is open
+    transmitting: bool,
+}
+
 /// Clause 11 Call Control CMCE sub-entity
-pub struct CcMsSubentity {}
+/// Supports simplex group calls only. The MS takes part in at most one call at a time.
+pub struct CcMsSubentity {
+    issi: u32,
+    dltime: TdmaTime,
+    setup: Option<PendingSetup>,
+    call: Option<MsCall>,
+}
 
 impl CcMsSubentity {
-    pub fn new() -> Self {
-        CcMsSubentity {}
+    pub fn new(config: SharedConfig) -> Self {
+        let issi = config.config().ms.as_ref().expect("MS stack requires ms config").issi;
+        CcMsSubentity {
+            issi,
+            dltime: TdmaTime::default(),
+            setup: None,
+            call: None,
+        }
+    }
+
+    fn own_address(&self) -> TetraAddress {
+        TetraAddress::new(self.issi, SsiType::Ssi)
+    }
+
+    /// Returns the first timeslot set in a channel allocation
+    fn chan_alloc_ts(chan_alloc: &CmceChanAllocReq) -> Option<u8> {
+        chan_alloc.timeslots.iter().position(|&t| t).map(|i| i as u8 + 1)
+    }
+
+    fn send_cmce_pdu(&self, queue: &mut MessageQueue, sdu: BitBuffer) {
+        let msg = SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime: self.dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: Layer2Service::Acknowledged,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                main_address: self.own_address(),
+                chan_alloc: None,
+                tx_reporter: None,
+            }),
+        };
+        queue.push_back(msg);
+    }
+
+    fn send_user(&self, queue: &mut MessageQueue, msg: SapMsgInner) {
+        queue.push_back(SapMsg {
+            sap: Sap::TnccSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::User,
+            dltime: self.dltime,
+            msg,
+        });
+    }
+
+    fn send_tx_ind(&self, queue: &mut MessageQueue, call_id: u16, state: TnccTxState, talker_ssi: Option<u32>) {
+        tracing::info!("call {}: transmission {:?} talker {:?}", call_id, state, talker_ssi);
+        self.send_user(
+            queue,
+            SapMsgInner::TnccTxInd(TnccTxInd {
+                call_id,
+                state,
+                talker_ssi,
+            }),
+        );
+    }
+
+    fn signal_umac_circuit(&self, queue: &mut MessageQueue, call: &MsCall, direction: Direction, open: bool) {
+        let Some(ts) = call.ts else {
+            tracing::warn!(
+                "call {}: no traffic channel allocated, not switching {:?} circuit",
+                call.call_id,
+                direction
+            );
+            return;
+        };
+        let cmd = if open {
+            CallControl::Open(Circuit {
+                direction,
+                ts,
+                usage: call.usage.unwrap_or(0),
+                circuit_mode: CircuitModeType::TchS,
+                speech_service: Some(0),
+                etee_encrypted: false,
+                peer_ts: None,
+                individual_parties: None,
+            })
+        } else {
+            CallControl::Close(direction, ts)
+        };
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(cmd),
+        });
+    }
+
+    /// Takes the floor on our call: opens the uplink circuit
+    fn start_transmitting(&mut self, queue: &mut MessageQueue) {
+        let Some(call) = self.call.take() else { return };
+        if !call.transmitting {
+            self.signal_umac_circuit(queue, &call, Direction::Ul, true);
+        }
+        self.call = Some(MsCall {
+            transmitting: true,
+            ..call
+        });
+    }
+
+    /// Gives up the floor on our call: closes the uplink circuit
+    fn stop_transmitting(&mut self, queue: &mut MessageQueue) {
+        let Some(call) = self.call.take() else { return };
+        if call.transmitting {
+            self.signal_umac_circuit(queue, &call, Direction::Ul, false);
+        }
+        self.call = Some(MsCall {
+            transmitting: false,
+            ..call
+        });
+    }
+
+    /// Drops our call, closes its circuits and informs the user
+    fn release_local(&mut self, queue: &mut MessageQueue, cause: DisconnectCause) {
+        if let Some(call) = self.call.take() {
+            tracing::info!("call {}: released, cause {}", call.call_id, cause);
+            let dir = if call.transmitting { Direction::Both } else { Direction::Dl };
+            self.signal_umac_circuit(queue, &call, dir, false);
+            self.send_user(
+                queue,
+                SapMsgInner::TnccReleaseInd(TnccReleaseInd {
+                    call_id: Some(call.call_id),
+                    cause: cause.into_raw() as u8,
+                }),
+            );
+        } else if let Some(setup) = self.setup.take() {
+            tracing::info!("call setup to {} failed, cause {}", setup.called_ssi, cause);
+            self.send_user(
+                queue,
+                SapMsgInner::TnccReleaseInd(TnccReleaseInd {
+                    call_id: setup.call_id,
+                    cause: cause.into_raw() as u8,
+                }),
+            );
+        }
+    }
+
+    /// Returns true if the call identifier belongs to the call we take part in
+    fn in_call(&self, call_id: u16) -> bool {
+        self.call.as_ref().is_some_and(|c| c.call_id == call_id)
+    }
+
+    /// Returns true if the call identifier belongs to our call, or to the call we are setting up
+    fn is_our_call(&self, call_id: u16) -> bool {
+        self.in_call(call_id) || self.setup.as_ref().is_some_and(|s| s.call_id == Some(call_id))
+    }
+
+    pub fn rx_tncc_setup_req(&mut self, queue: &mut MessageQueue, called_ssi: u32) {
+        tracing::trace!("rx_tncc_setup_req");
+
+        if self.call.is_some() || self.setup.is_some() {
+            tracing::warn!("rx_tncc_setup_req: already in a call, rejecting setup to {}", called_ssi);
+            self.send_user(
+                queue,
+                SapMsgInner::TnccReleaseInd(TnccReleaseInd {
+                    call_id: None,
+                    cause: DisconnectCause::ConcurrentSetUpNotSupported.into_raw() as u8,
+                }),
+            );
+            return;
+        }
+
+        let pdu = USetup {
+            area_selection: 0,
+            hook_method_selection: false,
+            simplex_duplex_selection: false,
+            basic_service_information: BasicServiceInformation {
+                circuit_mode_type: CircuitModeType::TchS,
+                encryption_flag: false,
+                communication_type: CommunicationType::P2Mp,
+                slots_per_frame: None,
+                speech_service: Some(0),
+            },
+            request_to_transmit_send_data: true,
+            call_priority: 0,
+            clir_control: 0,
+            called_party_type_identifier: PartyTypeIdentifier::Ssi,
+            called_party_short_number_address: None,
+            called_party_ssi: Some(called_ssi as u64),
+            called_party_extension: None,
+            external_subscriber_number: None,
+            facility: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize USetup");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+        self.send_cmce_pdu(queue, sdu);
+
+        self.setup = Some(PendingSetup {
+            called_ssi,
+            call_id: None,
+            deadline: self.dltime.add_timeslots(CC_SETUP_TIMEOUT),
+        });
+    }
+
+    pub fn rx_tncc_tx_req(&mut self, queue: &mut MessageQueue, call_id: u16, demand: bool) {
+        tracing::trace!("rx_tncc_tx_req");
+
+        if !self.in_call(call_id) {
+            tracing::warn!("rx_tncc_tx_req: not in call {}", call_id);
+            return;
+        }
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        if demand {
+            let pdu = UTxDemand {
+                call_identifier: call_id,
+                tx_demand_priority: 0,
+                encryption_control: false,
+                reserved: false,
+                facility: None,
+                dm_ms_address: None,
+                proprietary: None,
+            };
+            pdu.to_bitbuf(&mut sdu).expect("Failed to serialize UTxDemand");
+            tracing::info!("-> {:?}", pdu);
+        } else {
+            // Stop sending traffic first, the U-TX CEASED then goes out as normal signalling
+            self.stop_transmitting(queue);
+            let pdu = UTxCeased {
+                call_identifier: call_id,
+                facility: None,
+                dm_ms_address: None,
+                proprietary: None,
+            };
+            pdu.to_bitbuf(&mut sdu).expect("Failed to serialize UTxCeased");
+            tracing::info!("-> {:?}", pdu);
+        }
+        sdu.seek(0);
+        self.send_cmce_pdu(queue, sdu);
+    }
+
+    pub fn rx_tncc_release_req(&mut self, queue: &mut MessageQueue, call_id: u16) {
+        tracing::trace!("rx_tncc_release_req");
+
+        if !self.is_our_call(call_id) {
+            tracing::warn!("rx_tncc_release_req: not in call {}", call_id);
+            return;
+        }
+
+        let pdu = UDisconnect {
+            call_identifier: call_id,
+            disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize UDisconnect");
+        sdu.seek(0);
+        tracing::info!("-> {:?}", pdu);
+        self.send_cmce_pdu(queue, sdu);
+
+        // We don't wait for the D-RELEASE; a group call carries on without us anyway
+        self.release_local(queue, DisconnectCause::UserRequestedDisconnection);
+    }
+
+    fn rx_d_call_proceeding(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DCallProceeding::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DCallProceeding: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::info!("<- {:?}", pdu);
+
+        match self.setup.as_mut() {
+            Some(setup) if setup.call_id.is_none() => setup.call_id = Some(pdu.call_identifier),
+            _ => tracing::debug!("rx_d_call_proceeding: no call setup in progress"),
+        }
+    }
+
+    fn rx_d_connect(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DConnect::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DConnect: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::info!("<- {:?}", pdu);
+
+        let Some(setup) = self.setup.take_if(|s| s.call_id.is_none_or(|id| id == pdu.call_identifier)) else {
+            tracing::debug!("rx_d_connect: not setting up call {}", pdu.call_identifier);
+            return;
+        };
+
+        let call = MsCall {
+            call_id: pdu.call_identifier,
+            called_ssi: setup.called_ssi,
+            ts: prim.chan_alloc.as_ref().and_then(Self::chan_alloc_ts),
+            usage: prim.chan_alloc.as_ref().and_then(|c| c.usage),
+            transmitting: false,
+        };
+        tracing::info!("call {}: connected to {} on ts {:?}", call.call_id, call.called_ssi, call.ts);
+        self.signal_umac_circuit(queue, &call, Direction::Dl, true);
+        self.send_user(
+            queue,
+            SapMsgInner::TnccSetupCon(TnccSetupCon {
+                call_id: call.call_id,
+                called_ssi: call.called_ssi,
+            }),
+        );
+        self.call = Some(call);
+
+        match pdu.transmission_grant {
+            TransmissionGrant::Granted => {
+                self.start_transmitting(queue);
+                self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::Granted, Some(self.issi));
+            }
+            TransmissionGrant::NotGranted => self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::NotGranted, None),
+            TransmissionGrant::RequestQueued => self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::Queued, None),
+            TransmissionGrant::GrantedToOtherUser => self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::OtherParty, None),
+        }
+    }
+
+    fn rx_d_setup(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DSetup::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DSetup: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        // The SwMI repeats D-SETUP for late entry, and also sends it to the group we are calling
+        if self.is_our_call(pdu.call_identifier) {
+            tracing::trace!("rx_d_setup: already in call {}", pdu.call_identifier);
+            return;
+        }
+        tracing::info!("<- {:?}", pdu);
+
+        if pdu.basic_service_information.communication_type == CommunicationType::P2p {
+            unimplemented_log!("rx_d_setup: individual calls not supported, ignoring call {}", pdu.call_identifier);
+            return;
+        }
+        if self.call.is_some() || self.setup.is_some() {
+            tracing::info!("rx_d_setup: busy, not joining call {}", pdu.call_identifier);
+            return;
+        }
+
+        let call = MsCall {
+            call_id: pdu.call_identifier,
+            called_ssi: prim.received_tetra_address.ssi,
+            ts: prim.chan_alloc.as_ref().and_then(Self::chan_alloc_ts),
+            usage: prim.chan_alloc.as_ref().and_then(|c| c.usage),
+            transmitting: false,
+        };
+        let calling_ssi = pdu.calling_party_address_ssi.unwrap_or(0);
+        tracing::info!(
+            "call {}: joined group {} from {} on ts {:?}",
+            call.call_id,
+            call.called_ssi,
+            calling_ssi,
+            call.ts
+        );
+        self.signal_umac_circuit(queue, &call, Direction::Dl, true);
+        self.send_user(
+            queue,
+            SapMsgInner::TnccSetupInd(TnccSetupInd {
+                call_id: call.call_id,
+                calling_ssi,
+                called_ssi: call.called_ssi,
+            }),
+        );
+        self.call = Some(call);
+
+        if pdu.transmission_grant == TransmissionGrant::GrantedToOtherUser {
+            self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::OtherParty, pdu.calling_party_address_ssi);
+        } else {
+            self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::Ceased, None);
+        }
+    }
+
+    fn rx_d_tx_granted(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DTxGranted::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DTxGranted: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::info!("<- {:?}", pdu);
+
+        if !self.in_call(pdu.call_identifier) {
+            tracing::debug!("rx_d_tx_granted: not in call {}", pdu.call_identifier);
+            return;
+        }
+        let Ok(grant) = TransmissionGrant::try_from(pdu.transmission_grant as u64) else {
+            tracing::warn!("rx_d_tx_granted: invalid transmission grant {}", pdu.transmission_grant);
+            return;
+        };
+        let talker = pdu.transmitting_party_address_ssi.map(|ssi| ssi as u32);
+        let to_us = prim.received_tetra_address.ssi == self.issi;
+
+        match grant {
+            TransmissionGrant::Granted if to_us => {
+                self.start_transmitting(queue);
+                self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::Granted, Some(self.issi));
+            }
+            TransmissionGrant::Granted | TransmissionGrant::GrantedToOtherUser => {
+                if talker == Some(self.issi) {
+                    // Group copy of our own grant
+                    return;
+                }
+                self.stop_transmitting(queue);
+                self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::OtherParty, talker);
+            }
+            TransmissionGrant::NotGranted => self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::NotGranted, talker),
+            TransmissionGrant::RequestQueued => self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::Queued, talker),
+        }
+    }
+
+    fn rx_d_tx_ceased(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DTxCeased::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DTxCeased: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::info!("<- {:?}", pdu);
+
+        if !self.in_call(pdu.call_identifier) {
+            tracing::debug!("rx_d_tx_ceased: not in call {}", pdu.call_identifier);
+            return;
+        }
+        self.stop_transmitting(queue);
+        self.send_tx_ind(queue, pdu.call_identifier, TnccTxState::Ceased, None);
+    }
+
+    fn rx_d_release(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DRelease::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DRelease: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::info!("<- {:?}", pdu);
+
+        if !self.is_our_call(pdu.call_identifier) {
+            tracing::debug!("rx_d_release: not in call {}", pdu.call_identifier);
+            return;
+        }
+        self.release_local(queue, pdu.disconnect_cause);
     }
 
-    pub fn route_rd_deliver(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    fn rx_d_disconnect(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DDisconnect::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DDisconnect: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::info!("<- {:?}", pdu);
+
+        if !self.is_our_call(pdu.call_identifier) {
+            tracing::debug!("rx_d_disconnect: not in call {}", pdu.call_identifier);
+            return;
+        }
+
+        // Clause 14.5.1.3: the MS answers a D-DISCONNECT with U-RELEASE
+        let reply = URelease {
+            call_identifier: pdu.call_identifier,
+            disconnect_cause: pdu.disconnect_cause,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        reply.to_bitbuf(&mut sdu).expect("Failed to serialize URelease");
+        sdu.seek(0);
+        tracing::info!("-> {:?}", reply);
+        self.send_cmce_pdu(queue, sdu);
+
+        self.release_local(queue, pdu.disconnect_cause);
+    }
+
+    pub fn route_rd_deliver(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("route_rd_deliver");
 
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
@@ -34,37 +592,37 @@ impl CcMsSubentity {
                 unimplemented_log!("{}", pdu_type);
             }
             CmcePduTypeDl::DCallProceeding => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_call_proceeding(queue, message);
             }
             CmcePduTypeDl::DCallRestore => {
                 unimplemented_log!("{}", pdu_type);
             }
             CmcePduTypeDl::DConnect => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_connect(queue, message);
             }
             CmcePduTypeDl::DConnectAcknowledge => {
                 unimplemented_log!("{}", pdu_type);
             }
             CmcePduTypeDl::DDisconnect => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_disconnect(queue, message);
             }
             CmcePduTypeDl::DInfo => {
                 unimplemented_log!("{}", pdu_type);
             }
             CmcePduTypeDl::DRelease => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_release(queue, message);
             }
             CmcePduTypeDl::DSetup => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_setup(queue, message);
             }
             CmcePduTypeDl::DTxCeased => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_tx_ceased(queue, message);
             }
             CmcePduTypeDl::DTxContinue => {
                 unimplemented_log!("{}", pdu_type);
             }
             CmcePduTypeDl::DTxGranted => {
-                unimplemented_log!("{}", pdu_type);
+                self.rx_d_tx_granted(queue, message);
             }
             CmcePduTypeDl::DTxInterrupt => {
                 unimplemented_log!("{}", pdu_type);
@@ -77,4 +635,13 @@ impl CcMsSubentity {
             }
         }
     }
+
+    pub fn tick_start(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        self.dltime = dltime;
+
+        if self.setup.as_ref().is_some_and(|s| dltime.diff(s.deadline) >= 0) {
+            tracing::warn!("call setup timed out");
+            self.release_local(queue, DisconnectCause::ExpiryOfTimer);
+        }
+    }
 }
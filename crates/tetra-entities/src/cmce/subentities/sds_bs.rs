@@ -1,8 +1,12 @@
 use tetra_config::bluestation::SharedConfig;
-use tetra_core::Layer2Service;
-use tetra_core::{BitBuffer, Sap, SsiType, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
+use tetra_core::{Layer2Service, TxReporter};
+use tetra_pdus::cmce::enums::delivery_status::DeliveryStatus;
 use tetra_pdus::cmce::enums::pre_coded_status::PreCodedStatus;
-use tetra_pdus::cmce::enums::short_report_type::ShortReportType;
+use tetra_pdus::cmce::enums::sds_protocol_id::SdsProtocolId;
+use tetra_pdus::cmce::fields::sds_short_report::SdsShortReport;
+use tetra_pdus::cmce::sds_tl::{SdsTlPdu, sds_report::SdsReport, sds_transfer::SdsTransfer};
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
 use tetra_saps::control::enums::sds_user_data::SdsUserData;
 use tetra_saps::control::sds::CmceSdsData;
 use tetra_saps::lcmc::LcmcMleUnitdataReq;
@@ -15,16 +19,89 @@ use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
 use tetra_pdus::cmce::pdus::u_status::UStatus;
 
 use crate::MessageQueue;
+use crate::backhaul;
 use crate::brew;
+use crate::cmce::components::sds_reassembly::SdsReassembly;
+use crate::cmce::components::sds_store::{SdsStore, StoredSds};
+use crate::cmce::components::sds_tl_tracker::SdsTlTracker;
 
 /// Clause 13 Short Data Service CMCE sub-entity
 pub struct SdsBsSubentity {
     config: SharedConfig,
+    /// Store-and-forward of messages for unreachable ISSIs, if enabled
+    store: Option<SdsStore>,
+    /// SDS-TL messages awaiting delivery reports
+    reports: SdsTlTracker,
+    /// Concatenated SDS-TL messages being reassembled for services
+    reassembly: SdsReassembly,
 }
 
 impl SdsBsSubentity {
     pub fn new(config: SharedConfig) -> Self {
-        SdsBsSubentity { config }
+        let store = config.config().sds_store.as_ref().map(SdsStore::new);
+        SdsBsSubentity {
+            config,
+            store,
+            reports: SdsTlTracker::default(),
+            reassembly: SdsReassembly::default(),
+        }
+    }
+
+    pub fn tick_start(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        self.reports.expire(dltime);
+        for (source_issi, dest_ssi) in self.reassembly.take_expired(dltime) {
+            tracing::info!(
+                "SDS-TL: parts of concatenated message from ISSI {} to SSI {} missing, dropping",
+                source_issi,
+                dest_ssi
+            );
+        }
+
+        let Some(store) = self.store.as_mut() else {
+            return;
+        };
+        store.check_deliveries(dltime);
+
+        for msg in store.take_expired(dltime) {
+            tracing::info!(
+                "SDS store: message from ISSI {} to ISSI {} expired undelivered",
+                msg.source_issi,
+                msg.dest_issi
+            );
+            if let Some(transfer) = Self::transfer_requesting_report(&msg.user_defined_data) {
+                let status = if transfer.delivery_report_request.received() {
+                    DeliveryStatus::ExpiredNotReceived
+                } else {
+                    DeliveryStatus::ExpiredNotConsumed
+                };
+                let report = SdsReport::new(transfer.protocol_identifier, status, transfer.message_reference);
+                self.send_sds_tl_report(queue, dltime, &msg, report);
+            }
+        }
+
+        let config = self.config.clone();
+        let due = self
+            .store
+            .as_mut()
+            .unwrap()
+            .take_due(dltime, |issi| config.state_read().subscribers.is_registered(issi));
+        for msg in due {
+            tracing::info!(
+                "SDS store: delivering stored message from ISSI {} to ISSI {}",
+                msg.source_issi,
+                msg.dest_issi
+            );
+            self.deliver_individual(queue, dltime, msg);
+        }
+    }
+
+    /// Deliver the messages stored for subscribers that register
+    pub fn handle_subscriber_update(&mut self, dltime: TdmaTime, update: &MmSubscriberUpdate) {
+        if update.action == BrewSubscriberAction::Register
+            && let Some(store) = self.store.as_mut()
+        {
+            store.registered(update.issi, dltime);
+        }
     }
 
     /// Handle incoming U-SDS-DATA from a local MS (via RF uplink)
@@ -64,19 +141,104 @@ impl SdsBsSubentity {
             pdu.user_defined_data.type_identifier()
         );
 
-        // Route: local delivery (ISSI or GSSI), Brew forward, or drop
+        // Route: local delivery (ISSI or GSSI), other sites over the backhaul, Brew forward, or drop
         let is_local_issi = self.config.state_read().subscribers.is_registered(dest_ssi);
         let is_local_group = !is_local_issi && self.config.state_read().subscribers.has_group_members(dest_ssi);
+        let (is_remote_issi, is_remote_group) = if !is_local_issi && backhaul::is_active(&self.config) {
+            let state = self.config.state_read();
+            (
+                state.subscribers.remote_site(dest_ssi).is_some(),
+                !state.subscribers.remote_sites_with_group(dest_ssi).is_empty(),
+            )
+        } else {
+            (false, false)
+        };
+        let is_remote = is_remote_issi || is_remote_group;
+        let is_group = is_local_group || (is_remote_group && !is_remote_issi);
+
+        let sds_tl = SdsTlPdu::from_user_data(&pdu.user_defined_data).ok();
+        if let Some(sds_tl) = &sds_tl {
+            tracing::debug!("SDS-TL: {:?}", sds_tl);
+            if is_group {
+                // Group members do not report on messages, so the sender is told so instead
+                if let SdsTlPdu::Transfer(transfer) = sds_tl
+                    && transfer.delivery_report_request.any()
+                {
+                    let reported = StoredSds {
+                        source_issi: source_ssi,
+                        dest_issi: dest_ssi,
+                        user_defined_data: pdu.user_defined_data.clone(),
+                        stored_at: message.dltime,
+                    };
+                    let report = SdsReport::new(
+                        transfer.protocol_identifier,
+                        DeliveryStatus::SentToGroupAcknowledgementsPrevented,
+                        transfer.message_reference,
+                    );
+                    self.send_sds_tl_report(queue, message.dltime, &reported, report);
+                }
+            } else {
+                self.track_sds_tl(source_ssi, dest_ssi, sds_tl, message.dltime);
+            }
+        }
+
+        if is_remote {
+            tracing::info!("SDS: forwarding to other sites: {} -> {}", source_ssi, dest_ssi);
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Backhaul,
+                dltime: message.dltime,
+                msg: SapMsgInner::CmceSdsData(CmceSdsData {
+                    source_issi: source_ssi,
+                    dest_issi: dest_ssi,
+                    user_defined_data: pdu.user_defined_data.clone(),
+                }),
+            });
+        }
 
         if is_local_issi {
             tracing::info!("SDS: local delivery: {} -> {}", source_ssi, dest_ssi);
-            self.send_d_sds_data(queue, message.dltime, source_ssi, dest_ssi, SsiType::Issi, pdu.user_defined_data);
+            let msg = StoredSds {
+                source_issi: source_ssi,
+                dest_issi: dest_ssi,
+                user_defined_data: pdu.user_defined_data,
+                stored_at: message.dltime,
+            };
+            self.deliver_individual(queue, message.dltime, msg);
         } else if is_local_group {
             tracing::info!("SDS: group delivery: {} -> GSSI {}", source_ssi, dest_ssi);
-            self.send_d_sds_data(queue, message.dltime, source_ssi, dest_ssi, SsiType::Gssi, pdu.user_defined_data);
+            self.send_d_sds_data(
+                queue,
+                message.dltime,
+                source_ssi,
+                TetraAddress::new(dest_ssi, SsiType::Gssi),
+                pdu.user_defined_data,
+                None,
+            );
+        } else if is_remote {
+            // Forwarded above
         } else if brew::feature_sds_enabled(&self.config)
             && (brew::is_brew_issi_routable(&self.config, dest_ssi) || brew::is_tetrapack_sds_service_issi(&self.config, dest_ssi))
         {
+            let mut user_defined_data = pdu.user_defined_data;
+            if brew::is_tetrapack_sds_service_issi(&self.config, dest_ssi)
+                && let Some(SdsTlPdu::Transfer(transfer)) = sds_tl
+            {
+                // Services are handed whole messages, so concatenated messages are reassembled first
+                match self.reassembly.add(source_ssi, dest_ssi, transfer.clone(), message.dltime) {
+                    Some(whole) if whole == transfer => {}
+                    Some(whole) => {
+                        tracing::info!("SDS-TL: reassembled concatenated message, {} octets", whole.user_data.len());
+                        user_defined_data = SdsTlPdu::Transfer(whole).to_user_data();
+                    }
+                    None => {
+                        self.part_received(queue, message.dltime, source_ssi, dest_ssi, &transfer);
+                        return;
+                    }
+                }
+            }
+
             tracing::info!("SDS: forwarding to Brew: {} -> {}", source_ssi, dest_ssi);
             queue.push_back(SapMsg {
                 sap: Sap::Control,
@@ -86,44 +248,222 @@ impl SdsBsSubentity {
                 msg: SapMsgInner::CmceSdsData(CmceSdsData {
                     source_issi: source_ssi,
                     dest_issi: dest_ssi,
-                    user_defined_data: pdu.user_defined_data,
+                    user_defined_data,
                 }),
             });
+        } else if self.store.is_some() {
+            let msg = StoredSds {
+                source_issi: source_ssi,
+                dest_issi: dest_ssi,
+                user_defined_data: pdu.user_defined_data,
+                stored_at: message.dltime,
+            };
+            self.store_message(queue, message.dltime, msg);
         } else {
             tracing::warn!("SDS: dest SSI {} not local and not Brew-routable, dropping", dest_ssi);
         }
     }
 
-    /// Handle incoming SDS data from Brew entity (network-originated SDS)
-    pub fn rx_sds_from_brew(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+    /// Handle incoming SDS data from Brew entity or another site (network-originated SDS)
+    pub fn rx_sds_from_network(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         let SapMsgInner::CmceSdsData(sds) = message.msg else {
             panic!("Expected CmceSdsData message");
         };
 
         tracing::info!(
-            "SDS: received from Brew: {} -> {}, type={}, {} bits",
+            "SDS: received from {:?}: {} -> {}, type={}, {} bits",
+            message.src,
             sds.source_issi,
             sds.dest_issi,
             sds.user_defined_data.type_identifier(),
             sds.user_defined_data.length_bits()
         );
 
-        if !self.config.state_read().subscribers.is_registered(sds.dest_issi) {
-            tracing::warn!("SDS: dest ISSI {} from Brew is not locally registered, dropping", sds.dest_issi);
-            return;
+        if let Ok(sds_tl) = SdsTlPdu::from_user_data(&sds.user_defined_data) {
+            tracing::debug!("SDS-TL: {:?}", sds_tl);
+            self.track_sds_tl(sds.source_issi, sds.dest_issi, &sds_tl, message.dltime);
+        }
+
+        // Send D-SDS-DATA downlink to the local MS or group. Schedule on next ts1 to ensure it gets sent on the MCCH
+        let dltime = message.dltime.forward_to_timeslot(1);
+        let msg = StoredSds {
+            source_issi: sds.source_issi,
+            dest_issi: sds.dest_issi,
+            user_defined_data: sds.user_defined_data,
+            stored_at: message.dltime,
+        };
+
+        if self.config.state_read().subscribers.is_registered(sds.dest_issi) {
+            self.deliver_individual(queue, dltime, msg);
+        } else if message.src == TetraEntity::Backhaul && self.config.state_read().subscribers.has_group_members(sds.dest_issi) {
+            self.send_d_sds_data(
+                queue,
+                dltime,
+                msg.source_issi,
+                TetraAddress::new(msg.dest_issi, SsiType::Gssi),
+                msg.user_defined_data,
+                None,
+            );
+        } else if self.store.is_some() && message.src != TetraEntity::Backhaul {
+            self.store_message(queue, dltime, msg);
+        } else {
+            tracing::warn!(
+                "SDS: dest ISSI {} from {:?} is not locally registered, dropping",
+                sds.dest_issi,
+                message.src
+            );
+        }
+    }
+
+    /// Store a message for an ISSI that cannot be reached. If the sender requested an SDS-TL delivery
+    /// report, it is told the message was stored, or that the queue of the destination is full.
+    fn store_message(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, msg: StoredSds) {
+        let report_request = Self::transfer_requesting_report(&msg.user_defined_data);
+        let stored = self.store.as_mut().expect("store enabled").store(msg.clone());
+        if stored {
+            tracing::info!(
+                "SDS store: ISSI {} unreachable, stored message from ISSI {}",
+                msg.dest_issi,
+                msg.source_issi
+            );
+        } else {
+            tracing::warn!(
+                "SDS store: queue for ISSI {} full, dropping message from ISSI {}",
+                msg.dest_issi,
+                msg.source_issi
+            );
+        }
+
+        if let Some(transfer) = report_request {
+            let status = if stored {
+                DeliveryStatus::DestinationNotReachableMessageStored
+            } else {
+                DeliveryStatus::DestinationQueueFull
+            };
+            let report = SdsReport::new(transfer.protocol_identifier, status, transfer.message_reference);
+            self.send_sds_tl_report(queue, dltime, &msg, report);
         }
+    }
 
-        // Send D-SDS-DATA downlink to the local MS. Schedule on next ts1 to ensure it gets sent on the MCCH
+    /// Send an SDS to a registered ISSI. With the store enabled, delivery is acknowledged on layer 2,
+    /// and the message is stored if the MS does not acknowledge it.
+    fn deliver_individual(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, msg: StoredSds) {
+        let reporter = self.store.as_mut().map(|store| {
+            let reporter = TxReporter::new();
+            store.track(reporter.clone(), msg.clone());
+            reporter
+        });
         self.send_d_sds_data(
             queue,
-            message.dltime.forward_to_timeslot(1),
-            sds.source_issi,
-            sds.dest_issi,
-            SsiType::Issi,
-            sds.user_defined_data,
+            dltime,
+            msg.source_issi,
+            TetraAddress::new(msg.dest_issi, SsiType::Issi),
+            msg.user_defined_data,
+            reporter,
         );
     }
 
+    /// Send an SDS-REPORT on a message to its sender, on behalf of the destination of the message.
+    /// The report is routed like any other SDS, and stored itself if the sender cannot be reached.
+    fn send_sds_tl_report(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, msg: &StoredSds, report: SdsReport) {
+        tracing::info!(
+            "SDS-TL: reporting status 0x{:02x} for MR={} to ISSI {}",
+            report.delivery_status,
+            report.message_reference,
+            msg.source_issi
+        );
+        let report = StoredSds {
+            source_issi: msg.dest_issi,
+            dest_issi: msg.source_issi,
+            user_defined_data: SdsTlPdu::Report(report).to_user_data(),
+            stored_at: dltime,
+        };
+
+        if self.config.state_read().subscribers.is_registered(report.dest_issi) {
+            self.deliver_individual(queue, dltime, report);
+        } else if brew::feature_sds_enabled(&self.config) && brew::is_brew_issi_routable(&self.config, report.dest_issi) {
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Brew,
+                dltime,
+                msg: SapMsgInner::CmceSdsData(CmceSdsData {
+                    source_issi: report.source_issi,
+                    dest_issi: report.dest_issi,
+                    user_defined_data: report.user_defined_data,
+                }),
+            });
+        } else if let Some(store) = self.store.as_mut()
+            && !store.store(report)
+        {
+            tracing::warn!("SDS store: queue for ISSI {} full, dropping report", msg.source_issi);
+        }
+    }
+
+    /// Keep track of the message references of SDS-TL messages from `source_issi` to `dest_ssi`
+    fn track_sds_tl(&mut self, source_issi: u32, dest_ssi: u32, sds_tl: &SdsTlPdu, dltime: TdmaTime) {
+        match sds_tl {
+            SdsTlPdu::Transfer(transfer) => self.reports.transfer(source_issi, dest_ssi, transfer, dltime),
+            SdsTlPdu::Report(report) => {
+                let known = self
+                    .reports
+                    .report(source_issi, dest_ssi, report.message_reference, report.delivery_status);
+                if known.is_none() {
+                    tracing::debug!(
+                        "SDS-TL: report from {} on unknown MR={} of {}",
+                        source_issi,
+                        report.message_reference,
+                        dest_ssi
+                    );
+                }
+            }
+            SdsTlPdu::Ack(_) => {}
+        }
+    }
+
+    /// A part of a concatenated message was taken for reassembly. The BS is the receiving end of the parts, so it
+    /// reports on them itself.
+    fn part_received(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, source_issi: u32, dest_ssi: u32, part: &SdsTransfer) {
+        tracing::info!(
+            "SDS-TL: holding part MR={} of concatenated message from ISSI {} to ISSI {}",
+            part.message_reference,
+            source_issi,
+            dest_ssi
+        );
+        if !part.delivery_report_request.any() {
+            return;
+        }
+        let reported = StoredSds {
+            source_issi,
+            dest_issi: dest_ssi,
+            user_defined_data: SdsTlPdu::Transfer(part.clone()).to_user_data(),
+            stored_at: dltime,
+        };
+        let report = SdsReport::new(
+            part.protocol_identifier,
+            DeliveryStatus::ConcatenationPartReceived,
+            part.message_reference,
+        );
+        self.send_sds_tl_report(queue, dltime, &reported, report);
+    }
+
+    /// Returns the SDS-TRANSFER in the user data, if it requests a delivery report
+    fn transfer_requesting_report(user_defined_data: &SdsUserData) -> Option<SdsTransfer> {
+        match SdsTlPdu::from_user_data(user_defined_data) {
+            Ok(SdsTlPdu::Transfer(transfer)) if transfer.delivery_report_request.any() => Some(transfer),
+            _ => None,
+        }
+    }
+
+    /// Converts an SDS-SHORT REPORT (clause 29.4.2.3) into the equivalent SDS-REPORT, as the SwMI may do
+    /// (clause 29.3.3.4.4). The short report does not carry the protocol identifier, which is taken from the
+    /// reported message if it is known, and assumed to be SDS-TL text messaging otherwise.
+    fn short_report_to_sds_report(protocol_identifier: Option<u8>, report: &SdsShortReport) -> SdsUserData {
+        let protocol_identifier = protocol_identifier.unwrap_or(SdsProtocolId::TextMessagingSdsTl.into_raw() as u8);
+        let delivery_status = DeliveryStatus::from(report.short_report_type());
+        SdsTlPdu::Report(SdsReport::new(protocol_identifier, delivery_status, report.message_reference())).to_user_data()
+    }
+
     /// Handle incoming U-STATUS from a local MS (via RF uplink)
     pub fn route_status_deliver(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("SDS route_status_deliver");
@@ -161,6 +501,15 @@ impl SdsBsSubentity {
             pdu.pre_coded_status
         );
 
+        // The protocol identifier of the message a short report is on, so that it can be converted to an SDS-REPORT
+        let reported_protocol = if let PreCodedStatus::SdsTl(report) = &pdu.pre_coded_status {
+            let delivery_status = DeliveryStatus::from(report.short_report_type()).into_raw() as u8;
+            self.reports
+                .report(source_ssi, dest_ssi, report.message_reference(), delivery_status)
+        } else {
+            None
+        };
+
         // Route: local delivery, Brew forward, or drop
         if self.config.state_read().subscribers.is_registered(dest_ssi) {
             tracing::info!("SDS-STATUS: local delivery: {} -> {}", source_ssi, dest_ssi);
@@ -175,22 +524,11 @@ impl SdsBsSubentity {
             // Non-SDS-TL pre-coded statuses are forwarded as-is (Type1).
             // Local delivery (D-STATUS) is not affected, it stays as pre-coded status above.
             let user_defined_data = if let PreCodedStatus::SdsTl(report) = &pdu.pre_coded_status {
-                let delivery_status = match report.short_report_type() {
-                    ShortReportType::MessageReceived => 0x00,
-                    ShortReportType::MessageConsumed => 0x00,
-                    ShortReportType::DestMemFull => 0x02,
-                    ShortReportType::ProtOrEncodingNotSupported => 0x01,
-                };
-                // PID 0x82 = SDS-TL text messaging. Hardcoded because the SDS-SHORT REPORT
-                // PDU does not carry a Protocol Identifier (ETSI 29.4.3.11). In practice
-                // all observed SDS-TL traffic uses PID 0x82.
-                let sds_tl_report = vec![0x82, 0x10, delivery_status, report.message_reference()];
                 tracing::info!(
-                    "SDS-STATUS: converting SDS-TL short report to Type4 for Brew: MR={} status=0x{:02x}",
-                    report.message_reference(),
-                    delivery_status
+                    "SDS-STATUS: converting SDS-TL short report to Type4 for Brew: MR={}",
+                    report.message_reference()
                 );
-                SdsUserData::Type4(32, sds_tl_report)
+                Self::short_report_to_sds_report(reported_protocol, report)
             } else {
                 SdsUserData::Type1(pdu.pre_coded_status.into_raw())
             };
@@ -207,6 +545,17 @@ impl SdsBsSubentity {
                     user_defined_data,
                 }),
             });
+        } else if let PreCodedStatus::SdsTl(report) = &pdu.pre_coded_status
+            && self.store.is_some()
+        {
+            // Short reports can't be stored as a status, so the equivalent SDS-REPORT is stored instead
+            let msg = StoredSds {
+                source_issi: source_ssi,
+                dest_issi: dest_ssi,
+                user_defined_data: Self::short_report_to_sds_report(reported_protocol, report),
+                stored_at: message.dltime,
+            };
+            self.store_message(queue, message.dltime, msg);
         } else {
             tracing::warn!(
                 "SDS-STATUS: dest ISSI {} not locally registered and not Brew-routable, dropping",
@@ -272,9 +621,9 @@ impl SdsBsSubentity {
         queue: &mut MessageQueue,
         dltime: tetra_core::TdmaTime,
         source_issi: u32,
-        dest_ssi: u32,
-        dest_ssi_type: SsiType,
+        dest_addr: TetraAddress,
         user_defined_data: SdsUserData,
+        tx_reporter: Option<TxReporter>,
     ) {
         let pdu = DSdsData {
             calling_party_type_identifier: PartyTypeIdentifier::Ssi,
@@ -294,8 +643,7 @@ impl SdsBsSubentity {
         }
         sdu.seek(0);
 
-        let dest_addr = TetraAddress::new(dest_ssi, dest_ssi_type);
-        let layer2service = match dest_ssi_type {
+        let layer2service = match dest_addr.ssi_type {
             SsiType::Issi => Layer2Service::Acknowledged,
             SsiType::Gssi => Layer2Service::Unacknowledged,
             _ => panic!(),
@@ -317,7 +665,7 @@ impl SdsBsSubentity {
                 stealing_repeats_flag: false,
                 chan_alloc: None,
                 main_address: dest_addr,
-                tx_reporter: None,
+                tx_reporter,
             }),
         };
         queue.push_back(msg);
@@ -1,25 +1,80 @@
-use tetra_core::unimplemented_log;
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
 use tetra_pdus::cmce::{enums::cmce_pdu_type_dl::CmcePduTypeDl, pdus::d_sds_data::DSdsData};
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::lcmc::LcmcMleUnitdataReq;
+use tetra_saps::tnsds::TnsdsUnitdataInd;
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::MessageQueue;
 
 /// Clause 13 Short Data Service CMCE sub-entity
-pub struct SdsMsSubentity {}
+pub struct SdsMsSubentity {
+    issi: u32,
+}
 
 impl SdsMsSubentity {
     /// Create a new instance of the SdsSubentity
-    pub fn new() -> Self {
-        SdsMsSubentity {}
+    pub fn new(config: SharedConfig) -> Self {
+        let issi = config.config().ms.as_ref().expect("MS stack requires ms config").issi;
+        SdsMsSubentity { issi }
     }
 
-    pub fn rx_sds_data(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    /// Send user data from the SDS user as U-SDS-DATA
+    pub fn rx_tnsds_unitdata_req(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, called_ssi: u32, user_data: SdsUserData) {
+        tracing::trace!("rx_tnsds_unitdata_req");
+
+        let pdu = USdsData {
+            area_selection: 0,
+            called_party_type_identifier: PartyTypeIdentifier::Ssi,
+            called_party_short_number_address: None,
+            called_party_ssi: Some(called_ssi as u64),
+            called_party_extension: None,
+            user_defined_data: user_data,
+            external_subscriber_number: None,
+            dm_ms_address: None,
+        };
+        tracing::debug!("-> U-SDS-DATA {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(128);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::error!("Failed to serialize U-SDS-DATA: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+
+        let msg = SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: Layer2Service::Acknowledged,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: TetraAddress::new(self.issi, SsiType::Ssi),
+                tx_reporter: None,
+            }),
+        };
+        queue.push_back(msg);
+    }
+
+    pub fn rx_sds_data(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_sds_data");
 
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
             panic!();
         };
-        let _pdu = match DSdsData::from_bitbuf(&mut prim.sdu) {
+        let pdu = match DSdsData::from_bitbuf(&mut prim.sdu) {
             Ok(pdu) => {
                 tracing::debug!("Received DSdsData: {:?}", pdu);
                 pdu
@@ -30,7 +85,28 @@ impl SdsMsSubentity {
             }
         };
 
-        unimplemented_log!("rx_sds_data not implemented");
+        let Some(calling_ssi) = pdu.calling_party_address_ssi else {
+            unimplemented_log!("rx_sds_data: only SSI calling party addressing supported");
+            return;
+        };
+
+        tracing::info!(
+            "SDS: D-SDS-DATA from {} to {}, type={}",
+            calling_ssi,
+            prim.received_tetra_address.ssi,
+            pdu.user_defined_data.type_identifier()
+        );
+        queue.push_back(SapMsg {
+            sap: Sap::TnsdsSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::User,
+            dltime: message.dltime,
+            msg: SapMsgInner::TnsdsUnitdataInd(TnsdsUnitdataInd {
+                calling_ssi: calling_ssi as u32,
+                called_ssi: prim.received_tetra_address.ssi,
+                user_data: pdu.user_defined_data,
+            }),
+        });
     }
 
     /// Poor man's rx_prim, as this is a subcomponent and not governed by the MessageRouter
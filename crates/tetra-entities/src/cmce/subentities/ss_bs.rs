@@ -1,18 +1,148 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{BitBuffer, Layer2Service, Sap, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, ss_pdu_type::SsPduType, ss_type::SsType};
+use tetra_pdus::cmce::fields::ss_facility::SsFacility;
+use tetra_pdus::cmce::pdus::{cmce_function_not_supported::CmceFunctionNotSupported, d_facility::DFacility, u_facility::UFacility};
+use tetra_saps::lcmc::LcmcMleUnitdataReq;
+use tetra_saps::{SapMsg, SapMsgInner};
+
 use crate::MessageQueue;
-use tetra_saps::SapMsg;
+use crate::cmce::components::ss::{
+    SsHandler, SsReply, area_selection::AreaSelection, call_forwarding::CallForwarding,
+    talking_party_identification::TalkingPartyIdentification,
+};
 
 /// Clause 12 Supplementary Services CMCE sub-entity
-pub struct SsBsSubentity {}
+pub struct SsBsSubentity {
+    config: SharedConfig,
+    handlers: Vec<Box<dyn SsHandler>>,
+}
 
 impl SsBsSubentity {
-    pub fn new() -> Self {
-        SsBsSubentity {}
+    pub fn new(config: SharedConfig) -> Self {
+        let mut ss = SsBsSubentity {
+            config,
+            handlers: Vec::new(),
+        };
+        ss.register(Box::new(CallForwarding));
+        ss.register(Box::new(TalkingPartyIdentification));
+        ss.register(Box::new(AreaSelection::default()));
+        ss
+    }
+
+    /// Add a handler for the SS-types it serves, taking precedence over earlier handlers of the same SS-types
+    pub fn register(&mut self, handler: Box<dyn SsHandler>) {
+        self.handlers.insert(0, handler);
+    }
+
+    /// Handle U-FACILITY from a local MS, answering with D-FACILITY, or with CMCE FUNCTION NOT SUPPORTED
+    /// if no handler supports the request
+    pub fn route_re_deliver(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("SS route_re_deliver");
+
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let served_addr = prim.received_tetra_address;
+
+        let mut pdu = match UFacility::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-FACILITY: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let ss_type = SsType::try_from(pdu.facility.ss_type as u64).ok();
+        let pdu_type = SsPduType::try_from(pdu.facility.ss_pdu_type as u64).ok();
+        let reply = match (ss_type, pdu_type) {
+            (Some(ss_type), Some(pdu_type)) => {
+                let config = &self.config;
+                self.handlers
+                    .iter_mut()
+                    .find(|handler| handler.ss_types().contains(&ss_type))
+                    .and_then(|handler| handler.handle(config, served_addr.ssi, ss_type, pdu_type, &mut pdu.facility.ss_data))
+            }
+            _ => None,
+        };
+
+        let Some(reply) = reply else {
+            tracing::info!(
+                "SS: unsupported request from ISSI {}: ss_type={} ss_pdu_type={}",
+                served_addr.ssi,
+                pdu.facility.ss_type,
+                pdu.facility.ss_pdu_type
+            );
+            self.send_function_not_supported(queue, message.dltime, served_addr);
+            return;
+        };
+
+        let (ss_pdu_type, ss_data) = match reply {
+            SsReply::Result(data) => (SsPduType::Result, data),
+            SsReply::Reject => (SsPduType::Reject, BitBuffer::new(0)),
+        };
+        let facility = SsFacility::new(pdu.facility.ss_type, ss_pdu_type.into_raw() as u8, ss_data);
+        self.send_d_facility(queue, message.dltime, served_addr, facility);
+    }
+
+    fn send_d_facility(&self, queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, facility: SsFacility) {
+        let pdu = DFacility { facility };
+        tracing::debug!("-> D-FACILITY {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(48);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::error!("Failed to serialize D-FACILITY: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+        Self::send_individual(queue, dltime, addr, sdu);
     }
 
-    pub fn route_re_deliver(&mut self, _queue: &mut MessageQueue, mut _message: SapMsg) {
-        tracing::trace!("route_re_deliver");
+    /// Refuse the whole U-FACILITY. Pointing at the unsupported element would need the received PDU extract,
+    /// so the pointer is left zero.
+    fn send_function_not_supported(&self, queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress) {
+        let pdu = CmceFunctionNotSupported {
+            not_supported_pdu_type: CmcePduTypeUl::UFacility.into_raw() as u8,
+            call_identifier_present: false,
+            call_identifier: None,
+            function_not_supported_pointer: 0,
+            length_of_received_pdu_extract: None,
+            received_pdu_extract: None,
+        };
+        tracing::debug!("-> CMCE FUNCTION NOT SUPPORTED {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::error!("Failed to serialize CMCE FUNCTION NOT SUPPORTED: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+        Self::send_individual(queue, dltime, addr, sdu);
+    }
 
-        // Handle the incoming unit data indication
-        unimplemented!();
+    fn send_individual(queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, sdu: BitBuffer) {
+        queue.push_back(SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: Layer2Service::Acknowledged,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: addr,
+                tx_reporter: None,
+            }),
+        });
     }
 }
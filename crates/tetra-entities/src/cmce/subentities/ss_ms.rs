@@ -1,3 +1,4 @@
+use tetra_core::unimplemented_log;
 use tetra_saps::SapMsg;
 
 use crate::MessageQueue;
@@ -14,6 +15,6 @@ impl SsMsSubentity {
         tracing::trace!("route_re_deliver");
 
         // Handle the incoming unit data indication
-        unimplemented!();
+        unimplemented_log!("route_re_deliver: supplementary services not supported");
     }
 }
@@ -7,13 +7,17 @@ pub mod lmac;
 pub mod messagerouter;
 pub mod mle;
 pub mod mm;
+pub mod monitor;
 pub mod phy;
 pub mod sndcp;
 pub mod umac;
 
+pub mod ms_console;
 pub mod network;
+pub mod sndcp_net;
 pub mod tnmm_net;
 
+pub mod backhaul;
 pub mod brew;
 
 // Re-export commonly used items from router
@@ -0,0 +1,881 @@
+//! Advanced link, Clause 22.3.3: set-up negotiation, windowed selective-repeat transfer of segmented TL-SDUs,
+//! reconnection and disconnection, and the unacknowledged advanced link service.
+//!
+//! The state machines only deal with LLC PDUs and timers. The Llc wraps the PDUs they emit into TMA-UNITDATA
+//! requests and turns their events into TLA-SAP primitives.
+
+use std::collections::VecDeque;
+
+use tetra_core::{BitBuffer, EndpointId, LinkId, TdmaTime, TetraAddress, TxReporter};
+use tetra_pdus::llc::consts::consts::*;
+use tetra_pdus::llc::consts::timers::*;
+use tetra_pdus::llc::enums::al_disc_report::AlDiscReport;
+use tetra_pdus::llc::enums::al_reconnect_report::AlReconnectReport;
+use tetra_pdus::llc::enums::al_setup_report::AlSetupReport;
+use tetra_pdus::llc::pdus::al_ack::AlAck;
+use tetra_pdus::llc::pdus::al_data::AlData;
+use tetra_pdus::llc::pdus::al_disc::AlDisc;
+use tetra_pdus::llc::pdus::al_reconnect::AlReconnect;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
+use tetra_pdus::llc::pdus::al_udata::AlUdata;
+use tetra_saps::tla::AlReport;
+
+use super::fcs;
+
+/// Length of a TL-SDU segment. Along with the AL-DATA header, it fits in a single MAC-RESOURCE on SCH/F.
+pub const AL_SEGMENT_LEN_BITS: usize = 192;
+
+/// Segments of a link handed to the MAC but not transmitted yet. Keeps the MAC queue short, so that
+/// retransmissions and the PDUs of other links don't queue up behind a long TL-SDU.
+const AL_MAX_SEGMENTS_IN_MAC: usize = 4;
+
+/// TL-SDU sequence numbers N(S) and N(R) are 3 bits
+const AL_SEQ_MODULO: u8 = 8;
+
+const FCS_LEN_BITS: usize = 32;
+
+/// Advanced link parameters, as negotiated with AL-SETUP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlParams {
+    /// Maximum TL-SDU length including the FCS, as 32 << n octets (N.271)
+    pub max_tlsdu_len: u8,
+    /// N.264
+    pub num_timeslots: u8,
+    /// N.272
+    pub window_size: u8,
+    /// N.273
+    pub max_tlsdu_retransmissions: u8,
+    /// N.274
+    pub max_segment_retransmissions: u8,
+}
+
+impl Default for AlParams {
+    fn default() -> Self {
+        Self {
+            max_tlsdu_len: (N271_AL_MAX_TLSDU_LEN / 32).ilog2() as u8,
+            num_timeslots: N264_AL_NUM_DQPSK_TIMESLOTS as u8,
+            window_size: N272_AL_WINDOW_SIZE_TLSDU_ACKED as u8,
+            max_tlsdu_retransmissions: N273_AL_MAX_TLSDU_RETRANSMISSIONS as u8,
+            max_segment_retransmissions: N274_AL_MAX_SEGMENT_RETRANSMISSIONS as u8,
+        }
+    }
+}
+
+impl AlParams {
+    fn from_setup(pdu: &AlSetup) -> Self {
+        Self {
+            max_tlsdu_len: pdu.max_tlsdu_len,
+            num_timeslots: pdu.num_timeslots,
+            window_size: pdu.window_size,
+            max_tlsdu_retransmissions: pdu.max_tlsdu_retransmissions,
+            max_segment_retransmissions: pdu.max_segment_retransmissions,
+        }
+    }
+
+    /// Parameters acceptable to both ends: the smaller of each
+    fn negotiate(&self, proposed: &AlParams) -> Self {
+        Self {
+            max_tlsdu_len: self.max_tlsdu_len.min(proposed.max_tlsdu_len),
+            num_timeslots: self.num_timeslots.min(proposed.num_timeslots),
+            window_size: self.window_size.min(proposed.window_size),
+            max_tlsdu_retransmissions: self.max_tlsdu_retransmissions.min(proposed.max_tlsdu_retransmissions),
+            max_segment_retransmissions: self.max_segment_retransmissions.min(proposed.max_segment_retransmissions),
+        }
+    }
+
+    fn to_setup(self, setup_report: AlSetupReport) -> AlSetup {
+        AlSetup {
+            unacknowledged: false,
+            max_tlsdu_len: self.max_tlsdu_len,
+            upgrade: false,
+            num_timeslots: self.num_timeslots,
+            max_tlsdu_retransmissions: self.max_tlsdu_retransmissions,
+            max_segment_retransmissions: self.max_segment_retransmissions,
+            window_size: self.window_size,
+            setup_report,
+        }
+    }
+
+    fn max_tlsdu_len_bits(&self) -> usize {
+        (32usize << self.max_tlsdu_len) * 8
+    }
+}
+
+/// LLC PDU of an advanced link, to be sent to the peer
+pub struct AlPdu {
+    pub pdu: BitBuffer,
+    pub tx_reporter: Option<TxReporter>,
+}
+
+/// Event for the layer 2 service user
+#[derive(Debug)]
+pub enum AlEvent {
+    ConnectInd,
+    ConnectConf(AlReport),
+    DataInd(BitBuffer),
+    DisconnectInd(AlReport),
+    DisconnectConf(AlReport),
+    ReconnectConf(AlReport),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AlState {
+    /// AL-SETUP sent, waiting for the peer to accept (T.261, N.262)
+    Setup {
+        retries: u32,
+        t_sent: TdmaTime,
+    },
+    Connected,
+    /// AL-RECONNECT sent, waiting for the peer to confirm (T.265, N.265)
+    Reconnecting {
+        retries: u32,
+        t_sent: TdmaTime,
+    },
+    /// AL-DISC sent, waiting for the peer to confirm (T.263, N.263)
+    Disconnecting {
+        retries: u32,
+        t_sent: TdmaTime,
+    },
+    /// Nothing left to do, the link can be dropped
+    Released,
+}
+
+#[derive(Default)]
+struct TxSegment {
+    acked: bool,
+    /// Has to be (re)sent
+    pending: bool,
+    /// Set while the segment is queued in the MAC
+    in_mac: Option<TxReporter>,
+    retransmissions: u8,
+}
+
+/// TL-SDU in the transmit window
+struct TxTlsdu {
+    ns: u8,
+    /// TL-SDU with its FCS, kept for a new set-up of the link
+    sdu: BitBuffer,
+    segments: Vec<BitBuffer>,
+    state: Vec<TxSegment>,
+    retransmissions: u8,
+    /// Reporter of the segment carrying the outstanding acknowledgement request
+    ar_reporter: Option<TxReporter>,
+    /// Time the outstanding acknowledgement request was transmitted, starts T.252
+    t_ar_sent: Option<TdmaTime>,
+    tx_reporter: Option<TxReporter>,
+}
+
+impl TxTlsdu {
+    fn new(ns: u8, mut sdu: BitBuffer, tx_reporter: Option<TxReporter>) -> Self {
+        sdu.seek(0);
+        let mut segments = Vec::new();
+        while sdu.get_len_remaining() > 0 {
+            let len = sdu.get_len_remaining().min(AL_SEGMENT_LEN_BITS);
+            let mut segment = BitBuffer::new(len);
+            segment.copy_bits(&mut sdu, len);
+            segment.seek(0);
+            segments.push(segment);
+        }
+        sdu.seek(0);
+        let state = segments
+            .iter()
+            .map(|_| TxSegment {
+                pending: true,
+                ..Default::default()
+            })
+            .collect();
+        Self {
+            ns,
+            sdu,
+            segments,
+            state,
+            retransmissions: 0,
+            ar_reporter: None,
+            t_ar_sent: None,
+            tx_reporter,
+        }
+    }
+}
+
+/// TL-SDU being reassembled
+#[derive(Default)]
+struct RxTlsdu {
+    segments: Vec<Option<BitBuffer>>,
+    /// Known once the final segment was received
+    num_segments: Option<usize>,
+    /// Reassembled TL-SDU, without FCS, waiting for in-sequence delivery
+    complete: Option<BitBuffer>,
+}
+
+impl RxTlsdu {
+    /// Stores a segment. Returns the TL-SDU without its FCS once all segments are in.
+    /// If the FCS is wrong, all segments are dropped to have them sent again.
+    fn add_segment(&mut self, ss: u8, is_final: bool, segment: BitBuffer) -> Option<BitBuffer> {
+        let ss = ss as usize;
+        if self.segments.len() <= ss {
+            self.segments.resize(ss + 1, None);
+        }
+        self.segments[ss] = Some(segment);
+        if is_final {
+            self.num_segments = Some(ss + 1);
+        }
+
+        let num_segments = self.num_segments?;
+        if self.segments.len() < num_segments || self.segments[..num_segments].iter().any(Option::is_none) {
+            return None;
+        }
+        let mut sdu = BitBuffer::new_autoexpand(num_segments * AL_SEGMENT_LEN_BITS);
+        for segment in self.segments[..num_segments].iter_mut().flatten() {
+            segment.seek(0);
+            let len = segment.get_len();
+            sdu.copy_bits(segment, len);
+        }
+        sdu.seek(0);
+        if sdu.get_len() < FCS_LEN_BITS || !fcs::check_fcs(&sdu) {
+            tracing::warn!("advanced link TL-SDU with wrong FCS, requesting all segments again");
+            self.segments = vec![None; num_segments];
+            return None;
+        }
+        let len = sdu.get_len() - FCS_LEN_BITS;
+        let mut ret = BitBuffer::new(len);
+        ret.copy_bits(&mut sdu, len);
+        ret.seek(0);
+        Some(ret)
+    }
+
+    /// Segment map for AL-ACK
+    fn segments_received(&self) -> Vec<bool> {
+        let len = self.num_segments.unwrap_or(self.segments.len()).max(1);
+        (0..len).map(|ss| matches!(self.segments.get(ss), Some(Some(_)))).collect()
+    }
+}
+
+/// Appends the FCS over the whole TL-SDU
+fn with_fcs(mut sdu: BitBuffer) -> BitBuffer {
+    sdu.seek(0);
+    let len = sdu.get_len();
+    let mut ret = BitBuffer::new_autoexpand(len + FCS_LEN_BITS);
+    ret.copy_bits(&mut sdu, len);
+    ret.write_bits(fcs::compute_fcs(&ret, 0, len) as u64, FCS_LEN_BITS);
+    ret.seek(0);
+    ret
+}
+
+/// Marks the TxReporter of a TL-SDU that could not be delivered
+fn fail_reporter(tx_reporter: Option<TxReporter>) {
+    if let Some(tx_reporter) = tx_reporter {
+        if tx_reporter.is_transmitted() {
+            tx_reporter.mark_lost();
+        } else if !tx_reporter.is_in_final_state() {
+            tx_reporter.mark_discarded();
+        }
+    }
+}
+
+pub fn build_reconnect(reconnect_report: AlReconnectReport) -> BitBuffer {
+    let pdu = AlReconnect { reconnect_report };
+    let mut buf = BitBuffer::new_autoexpand(8);
+    pdu.to_bitbuf(&mut buf);
+    buf.seek(0);
+    tracing::debug!("-> {:?}", pdu);
+    buf
+}
+
+pub fn build_disc(disc_report: AlDiscReport) -> BitBuffer {
+    let pdu = AlDisc { disc_report };
+    let mut buf = BitBuffer::new_autoexpand(8);
+    pdu.to_bitbuf(&mut buf);
+    buf.seek(0);
+    tracing::debug!("-> {:?}", pdu);
+    buf
+}
+
+/// Acknowledged advanced link with one peer
+pub struct AdvancedLink {
+    pub addr: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    /// Timeslot the PDUs of this link are sent on
+    pub ts: u8,
+
+    state: AlState,
+    params: AlParams,
+
+    /// TL-SDUs with FCS waiting for room in the window
+    tx_queue: VecDeque<(BitBuffer, Option<TxReporter>)>,
+    tx_window: VecDeque<TxTlsdu>,
+    /// Send state variable V(S), N(S) of the next TL-SDU to enter the window
+    vs: u8,
+    /// Receive state variable V(R), N(S) of the next TL-SDU to deliver
+    vr: u8,
+    rx_window: [Option<RxTlsdu>; AL_SEQ_MODULO as usize],
+    /// Set when the peer reported not to be ready with AL-RNR, sending resumes at the latest after T.271
+    peer_busy_since: Option<TdmaTime>,
+
+    out: VecDeque<AlPdu>,
+    events: VecDeque<AlEvent>,
+}
+
+impl AdvancedLink {
+    fn new(addr: TetraAddress, link_id: LinkId, endpoint_id: EndpointId, ts: u8, state: AlState) -> Self {
+        Self {
+            addr,
+            link_id,
+            endpoint_id,
+            ts,
+            state,
+            params: AlParams::default(),
+            tx_queue: VecDeque::new(),
+            tx_window: VecDeque::new(),
+            vs: 0,
+            vr: 0,
+            rx_window: Default::default(),
+            peer_busy_since: None,
+            out: VecDeque::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Starts the set-up of a link, on TL-CONNECT request
+    pub fn connect(addr: TetraAddress, link_id: LinkId, endpoint_id: EndpointId, ts: u8, now: TdmaTime) -> Self {
+        let mut link = Self::new(addr, link_id, endpoint_id, ts, AlState::Setup { retries: 0, t_sent: now });
+        link.send_setup(AlSetupReport::ServiceDefinition);
+        link
+    }
+
+    /// Accepts a link set up by the peer
+    pub fn accept(addr: TetraAddress, link_id: LinkId, endpoint_id: EndpointId, ts: u8, pdu: &AlSetup) -> Self {
+        let mut link = Self::new(addr, link_id, endpoint_id, ts, AlState::Connected);
+        link.rx_setup(pdu);
+        link
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.state == AlState::Connected
+    }
+
+    pub fn is_released(&self) -> bool {
+        self.state == AlState::Released
+    }
+
+    pub fn pop_pdu(&mut self) -> Option<AlPdu> {
+        self.out.pop_front()
+    }
+
+    pub fn pop_event(&mut self) -> Option<AlEvent> {
+        self.events.pop_front()
+    }
+
+    fn push_pdu(&mut self, pdu: BitBuffer) {
+        self.out.push_back(AlPdu { pdu, tx_reporter: None });
+    }
+
+    fn send_setup(&mut self, setup_report: AlSetupReport) {
+        let pdu = self.params.to_setup(setup_report);
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        tracing::debug!("-> {:?}", pdu);
+        self.push_pdu(buf);
+    }
+
+    fn send_ack(&mut self, nr: u8, segments_received: Option<Vec<bool>>) {
+        let pdu = AlAck {
+            rnr: false,
+            nr,
+            segments_received,
+        };
+        let mut buf = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        tracing::debug!("-> {:?}", pdu);
+        self.push_pdu(buf);
+    }
+
+    /// TL-CONNECT request on a link which exists already
+    pub fn request_connect(&mut self) {
+        match self.state {
+            AlState::Connected => self.events.push_back(AlEvent::ConnectConf(AlReport::Success)),
+            // Confirmed once the set-up in progress completes
+            AlState::Setup { .. } => {}
+            _ => self.events.push_back(AlEvent::ConnectConf(AlReport::Rejected)),
+        }
+    }
+
+    /// TL-DATA request. The TL-SDU is sent once the link is connected and there is room in the window.
+    pub fn send(&mut self, sdu: BitBuffer, tx_reporter: Option<TxReporter>) {
+        match self.state {
+            AlState::Disconnecting { .. } | AlState::Released => {
+                tracing::warn!("advanced link to {} is being released, dropping TL-SDU", self.addr.ssi);
+                fail_reporter(tx_reporter);
+            }
+            _ => self.tx_queue.push_back((with_fcs(sdu), tx_reporter)),
+        }
+    }
+
+    /// TL-DISCONNECT request
+    pub fn disconnect(&mut self, now: TdmaTime) {
+        match self.state {
+            AlState::Disconnecting { .. } => {}
+            AlState::Released => self.events.push_back(AlEvent::DisconnectConf(AlReport::Success)),
+            _ => {
+                self.drop_tlsdus();
+                self.state = AlState::Disconnecting { retries: 0, t_sent: now };
+                self.push_pdu(build_disc(AlDiscReport::Request));
+            }
+        }
+    }
+
+    /// TL-RECONNECT request
+    pub fn reconnect(&mut self, now: TdmaTime) {
+        match self.state {
+            AlState::Connected => {
+                self.state = AlState::Reconnecting { retries: 0, t_sent: now };
+                self.push_pdu(build_reconnect(AlReconnectReport::Propose));
+            }
+            AlState::Reconnecting { .. } => {}
+            _ => self.events.push_back(AlEvent::ReconnectConf(AlReport::Rejected)),
+        }
+    }
+
+    pub fn rx_setup(&mut self, pdu: &AlSetup) {
+        match pdu.setup_report {
+            AlSetupReport::ServiceDefinition => {
+                // The peer (re)starts the link. This also resolves set-ups crossing each other.
+                let initiated = matches!(self.state, AlState::Setup { .. });
+                self.restart_sequence();
+                self.params = AlParams::default().negotiate(&AlParams::from_setup(pdu));
+                self.state = AlState::Connected;
+                self.send_setup(AlSetupReport::ServiceAccept);
+                self.events.push_back(if initiated {
+                    AlEvent::ConnectConf(AlReport::Success)
+                } else {
+                    AlEvent::ConnectInd
+                });
+            }
+            AlSetupReport::ServiceChange | AlSetupReport::ServiceAccept => {
+                if !matches!(self.state, AlState::Setup { .. }) {
+                    tracing::debug!("ignoring {} from {}, not setting up", pdu.setup_report, self.addr.ssi);
+                    return;
+                }
+                self.params = self.params.negotiate(&AlParams::from_setup(pdu));
+                self.state = AlState::Connected;
+                if pdu.setup_report == AlSetupReport::ServiceChange {
+                    self.send_setup(AlSetupReport::ServiceAccept);
+                }
+                self.events.push_back(AlEvent::ConnectConf(AlReport::Success));
+            }
+        }
+    }
+
+    pub fn rx_data(&mut self, pdu: &AlData, segment: BitBuffer) {
+        if !matches!(self.state, AlState::Connected | AlState::Reconnecting { .. }) {
+            tracing::debug!("ignoring AL-DATA from {}, link not connected", self.addr.ssi);
+            return;
+        }
+
+        let window_size = self.params.window_size;
+        let offset = (pdu.ns + AL_SEQ_MODULO - self.vr) % AL_SEQ_MODULO;
+        if offset >= window_size {
+            // Delivered already, our acknowledgement may have been lost
+            if pdu.ar && offset >= AL_SEQ_MODULO - window_size {
+                self.send_ack(pdu.ns, None);
+            }
+            return;
+        }
+
+        let entry = self.rx_window[pdu.ns as usize].get_or_insert_default();
+        if entry.complete.is_none() {
+            entry.complete = entry.add_segment(pdu.ss, pdu.is_final, segment);
+        }
+        if pdu.ar {
+            let segments_received = if entry.complete.is_some() {
+                None
+            } else {
+                Some(entry.segments_received())
+            };
+            self.send_ack(pdu.ns, segments_received);
+        }
+
+        // Deliver in sequence
+        while let Some(entry) = self.rx_window[self.vr as usize].as_mut()
+            && let Some(sdu) = entry.complete.take()
+        {
+            self.rx_window[self.vr as usize] = None;
+            self.vr = (self.vr + 1) % AL_SEQ_MODULO;
+            self.events.push_back(AlEvent::DataInd(sdu));
+        }
+    }
+
+    pub fn rx_ack(&mut self, pdu: &AlAck, now: TdmaTime) {
+        if !matches!(self.state, AlState::Connected | AlState::Reconnecting { .. }) {
+            tracing::debug!("ignoring AL-ACK from {}, link not connected", self.addr.ssi);
+            return;
+        }
+        self.peer_busy_since = if pdu.rnr { Some(now) } else { None };
+
+        let Some(index) = self.tx_window.iter().position(|tlsdu| tlsdu.ns == pdu.nr) else {
+            tracing::debug!("AL-ACK from {} for N(R) {} not in the window", self.addr.ssi, pdu.nr);
+            return;
+        };
+        let max_segment_retransmissions = self.params.max_segment_retransmissions;
+        let tlsdu = &mut self.tx_window[index];
+        tlsdu.ar_reporter = None;
+        tlsdu.t_ar_sent = None;
+
+        let Some(segments_received) = &pdu.segments_received else {
+            let tlsdu = self.tx_window.remove(index).unwrap(); // Never fails
+            tracing::debug!("advanced link to {}: N(S) {} acknowledged", self.addr.ssi, tlsdu.ns);
+            if let Some(tx_reporter) = tlsdu.tx_reporter {
+                if !tx_reporter.is_transmitted() {
+                    tx_reporter.mark_transmitted();
+                }
+                tx_reporter.mark_acknowledged();
+            }
+            return;
+        };
+
+        // Selective retransmission of the missing segments
+        for (segment, received) in tlsdu.state.iter_mut().zip(segments_received) {
+            if *received {
+                segment.acked = true;
+                segment.pending = false;
+            } else if !segment.acked && !segment.pending && segment.in_mac.is_none() {
+                segment.retransmissions += 1;
+                if segment.retransmissions > max_segment_retransmissions {
+                    self.fail_link();
+                    return;
+                }
+                segment.pending = true;
+            }
+        }
+    }
+
+    pub fn rx_reconnect(&mut self, pdu: &AlReconnect) {
+        match pdu.reconnect_report {
+            AlReconnectReport::Propose => match self.state {
+                AlState::Connected | AlState::Reconnecting { .. } => {
+                    self.push_pdu(build_reconnect(AlReconnectReport::Confirm));
+                    if !self.is_connected() {
+                        self.state = AlState::Connected;
+                        self.events.push_back(AlEvent::ReconnectConf(AlReport::Success));
+                    }
+                    self.resend_outstanding();
+                }
+                _ => self.push_pdu(build_reconnect(AlReconnectReport::Fail)),
+            },
+            AlReconnectReport::Confirm => {
+                if matches!(self.state, AlState::Reconnecting { .. }) {
+                    self.state = AlState::Connected;
+                    self.events.push_back(AlEvent::ReconnectConf(AlReport::Success));
+                    self.resend_outstanding();
+                }
+            }
+            AlReconnectReport::Fail => {
+                if matches!(self.state, AlState::Reconnecting { .. }) {
+                    self.drop_tlsdus();
+                    self.state = AlState::Released;
+                    self.events.push_back(AlEvent::ReconnectConf(AlReport::Rejected));
+                }
+            }
+        }
+    }
+
+    pub fn rx_disc(&mut self, pdu: &AlDisc) {
+        match pdu.disc_report {
+            AlDiscReport::Request => {
+                self.push_pdu(build_disc(AlDiscReport::Confirm));
+                match self.state {
+                    AlState::Released => {}
+                    AlState::Disconnecting { .. } => {
+                        self.state = AlState::Released;
+                        self.events.push_back(AlEvent::DisconnectConf(AlReport::Success));
+                    }
+                    _ => {
+                        self.drop_tlsdus();
+                        self.state = AlState::Released;
+                        self.events.push_back(AlEvent::DisconnectInd(AlReport::PeerDisconnect));
+                    }
+                }
+            }
+            AlDiscReport::Confirm => {
+                if matches!(self.state, AlState::Disconnecting { .. }) {
+                    self.state = AlState::Released;
+                    self.events.push_back(AlEvent::DisconnectConf(AlReport::Success));
+                }
+            }
+        }
+    }
+
+    /// Runs the timers and hands new segments to the MAC
+    pub fn tick(&mut self, now: TdmaTime) {
+        match self.state {
+            AlState::Setup { retries, t_sent } if now.diff(t_sent) as u32 >= T261_SETUP_WAITING_TIMER => {
+                if retries < N262_AL_MAX_CONNECTION_SETUP_RETRIES {
+                    self.state = AlState::Setup {
+                        retries: retries + 1,
+                        t_sent: now,
+                    };
+                    self.send_setup(AlSetupReport::ServiceDefinition);
+                } else {
+                    tracing::warn!("advanced link set-up to {} not answered", self.addr.ssi);
+                    self.drop_tlsdus();
+                    self.state = AlState::Released;
+                    self.events.push_back(AlEvent::ConnectConf(AlReport::NoResponse));
+                }
+            }
+            AlState::Reconnecting { retries, t_sent } if now.diff(t_sent) as u32 >= T265_RECONNECT_WAITING_TIMER => {
+                if retries < N265_AL_MAX_RECONNECTION_RETRIES {
+                    self.state = AlState::Reconnecting {
+                        retries: retries + 1,
+                        t_sent: now,
+                    };
+                    self.push_pdu(build_reconnect(AlReconnectReport::Propose));
+                } else {
+                    tracing::warn!("advanced link reconnection to {} not answered", self.addr.ssi);
+                    self.drop_tlsdus();
+                    self.state = AlState::Released;
+                    self.events.push_back(AlEvent::ReconnectConf(AlReport::NoResponse));
+                }
+            }
+            AlState::Disconnecting { retries, t_sent } if now.diff(t_sent) as u32 >= T263_DISCONNECT_WAITING_TIMER => {
+                if retries < N263_AL_MAX_DISCONNECTION_RETRIES {
+                    self.state = AlState::Disconnecting {
+                        retries: retries + 1,
+                        t_sent: now,
+                    };
+                    self.push_pdu(build_disc(AlDiscReport::Request));
+                } else {
+                    // Released locally all the same
+                    self.state = AlState::Released;
+                    self.events.push_back(AlEvent::DisconnectConf(AlReport::NoResponse));
+                }
+            }
+            AlState::Connected => {
+                self.check_ack_timers(now);
+                if let Some(t) = self.peer_busy_since
+                    && now.diff(t) as u32 >= T271_RECEIVER_NOT_READY_FOR_TX_TIMER
+                {
+                    self.peer_busy_since = None;
+                }
+                if self.is_connected() && self.peer_busy_since.is_none() {
+                    self.fill_window();
+                    self.submit_segments();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// T.252: polls again for an acknowledgement which did not come, up to N.273 times per TL-SDU
+    fn check_ack_timers(&mut self, now: TdmaTime) {
+        let max_tlsdu_retransmissions = self.params.max_tlsdu_retransmissions;
+        let mut failed = false;
+        for tlsdu in self.tx_window.iter_mut() {
+            if tlsdu.t_ar_sent.is_none() && tlsdu.ar_reporter.as_ref().is_some_and(|r| r.is_transmitted()) {
+                tlsdu.t_ar_sent = Some(now);
+            }
+            let Some(t_ar_sent) = tlsdu.t_ar_sent else {
+                continue;
+            };
+            if (now.diff(t_ar_sent) as u32) < T252_ACK_WAITING_TIMER {
+                continue;
+            }
+            tlsdu.ar_reporter = None;
+            tlsdu.t_ar_sent = None;
+            tlsdu.retransmissions += 1;
+            if tlsdu.retransmissions > max_tlsdu_retransmissions {
+                failed = true;
+                break;
+            }
+            tracing::info!(
+                "advanced link to {}: no acknowledgement for N(S) {}, attempt {}",
+                self.addr.ssi,
+                tlsdu.ns,
+                tlsdu.retransmissions
+            );
+            // Send the last unacknowledged segment again, which carries the new acknowledgement request
+            if let Some(segment) = tlsdu.state.iter_mut().rev().find(|s| !s.acked) {
+                segment.pending = true;
+            }
+        }
+        if failed {
+            self.fail_link();
+        }
+    }
+
+    fn fill_window(&mut self) {
+        let max_len = self.params.max_tlsdu_len_bits();
+        while self.tx_window.len() < self.params.window_size as usize
+            && let Some((sdu, tx_reporter)) = self.tx_queue.pop_front()
+        {
+            if sdu.get_len() > max_len {
+                tracing::warn!(
+                    "TL-SDU of {} bits exceeds the advanced link maximum of {} bits, dropping",
+                    sdu.get_len(),
+                    max_len
+                );
+                fail_reporter(tx_reporter);
+                continue;
+            }
+            self.tx_window.push_back(TxTlsdu::new(self.vs, sdu, tx_reporter));
+            self.vs = (self.vs + 1) % AL_SEQ_MODULO;
+        }
+    }
+
+    fn submit_segments(&mut self) {
+        // Segments the MAC had to discard are sent again, without counting as a retransmission
+        let mut in_mac = 0;
+        for segment in self.tx_window.iter_mut().flat_map(|tlsdu| tlsdu.state.iter_mut()) {
+            if let Some(tx_reporter) = &segment.in_mac {
+                if tx_reporter.is_discarded() {
+                    segment.pending = !segment.acked;
+                    segment.in_mac = None;
+                } else if tx_reporter.is_transmitted() {
+                    segment.in_mac = None;
+                } else {
+                    in_mac += 1;
+                }
+            }
+        }
+
+        let mut budget = AL_MAX_SEGMENTS_IN_MAC.saturating_sub(in_mac);
+        for tlsdu in self.tx_window.iter_mut() {
+            // The acknowledgement request goes with the last segment sent in this round
+            let last_pending = tlsdu.state.iter().rposition(|s| s.pending);
+            let num_segments = tlsdu.segments.len();
+            for ss in 0..num_segments {
+                if budget == 0 {
+                    return;
+                }
+                if !tlsdu.state[ss].pending {
+                    continue;
+                }
+                let header = AlData {
+                    is_final: ss == num_segments - 1,
+                    ar: Some(ss) == last_pending,
+                    ns: tlsdu.ns,
+                    ss: ss as u8,
+                };
+                let segment = &mut tlsdu.segments[ss];
+                let mut pdu = BitBuffer::new_autoexpand(17 + segment.get_len());
+                header.to_bitbuf(&mut pdu);
+                segment.seek(0);
+                let len = segment.get_len();
+                pdu.copy_bits(segment, len);
+                pdu.seek(0);
+                tracing::debug!("-> {:?}", header);
+
+                let tx_reporter = TxReporter::new_unacked();
+                tlsdu.state[ss].pending = false;
+                tlsdu.state[ss].in_mac = Some(tx_reporter.clone());
+                if header.ar {
+                    tlsdu.ar_reporter = Some(tx_reporter.clone());
+                    tlsdu.t_ar_sent = None;
+                }
+                self.out.push_back(AlPdu {
+                    pdu,
+                    tx_reporter: Some(tx_reporter),
+                });
+                budget -= 1;
+            }
+        }
+    }
+
+    /// After a reconnection, whatever was not acknowledged may have been lost with the old cell
+    fn resend_outstanding(&mut self) {
+        for tlsdu in self.tx_window.iter_mut() {
+            tlsdu.ar_reporter = None;
+            tlsdu.t_ar_sent = None;
+            for segment in tlsdu.state.iter_mut().filter(|s| !s.acked) {
+                segment.pending = true;
+                segment.in_mac = None;
+            }
+        }
+    }
+
+    /// A new set-up restarts the sequence numbering. TL-SDUs in transfer are sent again from the start.
+    fn restart_sequence(&mut self) {
+        while let Some(tlsdu) = self.tx_window.pop_back() {
+            self.tx_queue.push_front((tlsdu.sdu, tlsdu.tx_reporter));
+        }
+        self.vs = 0;
+        self.vr = 0;
+        self.rx_window = Default::default();
+        self.peer_busy_since = None;
+    }
+
+    fn drop_tlsdus(&mut self) {
+        for tlsdu in self.tx_window.drain(..) {
+            fail_reporter(tlsdu.tx_reporter);
+        }
+        for (_, tx_reporter) in self.tx_queue.drain(..) {
+            fail_reporter(tx_reporter);
+        }
+    }
+
+    /// Retransmissions exhausted: the link is released, and the peer asked to release it too
+    fn fail_link(&mut self) {
+        tracing::warn!("advanced link to {} failed, releasing", self.addr.ssi);
+        self.drop_tlsdus();
+        self.push_pdu(build_disc(AlDiscReport::Request));
+        self.state = AlState::Released;
+        self.events.push_back(AlEvent::DisconnectInd(AlReport::LinkFailure));
+    }
+}
+
+/// Segments a TL-SDU for the unacknowledged advanced link, and repeats the whole sequence N.282 times.
+/// The TxReporter goes with the very last PDU.
+pub fn build_unitdata(ns: u8, sdu: BitBuffer, tx_reporter: Option<TxReporter>) -> Vec<AlPdu> {
+    let tlsdu = TxTlsdu::new(ns, with_fcs(sdu), None);
+    let num_segments = tlsdu.segments.len();
+    let mut pdus = Vec::new();
+    for _ in 0..=N282_AL_NUM_REPETITIONS_UNACKED {
+        for (ss, segment) in tlsdu.segments.iter().enumerate() {
+            let header = AlUdata {
+                is_final: ss == num_segments - 1,
+                ns,
+                ss: ss as u8,
+            };
+            let mut segment = segment.clone();
+            let mut pdu = BitBuffer::new_autoexpand(13 + segment.get_len());
+            header.to_bitbuf(&mut pdu);
+            let len = segment.get_len();
+            pdu.copy_bits(&mut segment, len);
+            pdu.seek(0);
+            pdus.push(AlPdu { pdu, tx_reporter: None });
+        }
+    }
+    if let Some(last) = pdus.last_mut() {
+        last.tx_reporter = tx_reporter;
+    }
+    pdus
+}
+
+/// Receiving end of the unacknowledged advanced link service from one peer
+#[derive(Default)]
+pub struct AlUnitdataRx {
+    rx_window: [Option<RxTlsdu>; AL_SEQ_MODULO as usize],
+    /// Repetitions of the last delivered TL-SDU are dropped
+    last_delivered: Option<u8>,
+}
+
+impl AlUnitdataRx {
+    /// Returns the TL-SDU once complete
+    pub fn rx(&mut self, pdu: &AlUdata, segment: BitBuffer) -> Option<BitBuffer> {
+        if self.last_delivered == Some(pdu.ns) {
+            return None;
+        }
+        // TL-SDUs too far behind won't be completed anymore
+        for (ns, entry) in self.rx_window.iter_mut().enumerate() {
+            if (pdu.ns + AL_SEQ_MODULO - ns as u8) % AL_SEQ_MODULO >= N281_AL_WINDOW_SIZE_TLSDU_UNACKED as u8 {
+                *entry = None;
+            }
+        }
+        let entry = self.rx_window[pdu.ns as usize].get_or_insert_default();
+        let sdu = entry.add_segment(pdu.ss, pdu.is_final, segment)?;
+        self.rx_window[pdu.ns as usize] = None;
+        self.last_delivered = Some(pdu.ns);
+        Some(sdu)
+    }
+}
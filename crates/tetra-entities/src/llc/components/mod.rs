@@ -1 +1,2 @@
+pub mod advanced_link;
 pub mod fcs;
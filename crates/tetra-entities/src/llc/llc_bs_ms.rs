@@ -4,18 +4,31 @@ use std::panic;
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, unimplemented_log};
+use tetra_core::{BitBuffer, EndpointId, Layer2Service, LinkId, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, unimplemented_log};
 use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
-use tetra_saps::tla::{TlaTlDataIndBl, TlaTlUnitdataIndBl};
+use tetra_saps::tla::{
+    AlReport, TlaTlConnectConf, TlaTlConnectInd, TlaTlDataIndAl, TlaTlDataIndBl, TlaTlDisconnectConf, TlaTlDisconnectInd,
+    TlaTlReconnectConf, TlaTlUnitdataIndAl, TlaTlUnitdataIndBl,
+};
 use tetra_saps::tma::TmaUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use crate::llc::components::advanced_link::{self, AdvancedLink, AlEvent, AlPdu, AlUnitdataRx};
 use crate::llc::components::fcs;
 use tetra_pdus::llc::consts::consts::N252_BL_MAX_TLSDU_RETRANSMITS_ACKED;
 use tetra_pdus::llc::consts::timers::T251_SENDER_RETRY_TIMER;
+use tetra_pdus::llc::enums::al_disc_report::AlDiscReport;
+use tetra_pdus::llc::enums::al_reconnect_report::AlReconnectReport;
+use tetra_pdus::llc::enums::al_setup_report::AlSetupReport;
 use tetra_pdus::llc::enums::llc_pdu_type::LlcPduType;
+use tetra_pdus::llc::pdus::al_ack::AlAck;
+use tetra_pdus::llc::pdus::al_data::AlData;
+use tetra_pdus::llc::pdus::al_disc::AlDisc;
+use tetra_pdus::llc::pdus::al_reconnect::AlReconnect;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
+use tetra_pdus::llc::pdus::al_udata::AlUdata;
 use tetra_pdus::llc::pdus::bl_ack::BlAck;
 use tetra_pdus::llc::pdus::bl_adata::BlAdata;
 use tetra_pdus::llc::pdus::bl_data::BlData;
@@ -78,6 +91,13 @@ pub struct Llc {
 
     /// Per-link send sequence variable per SSI. Alternates between 0 and 1.
     link_send_seq: HashMap<u32, u8>,
+
+    /// Acknowledged advanced links, per SSI
+    advanced_links: HashMap<u32, AdvancedLink>,
+    /// Reassembly of unacknowledged advanced link TL-SDUs, per SSI
+    al_unitdata_rx: HashMap<u32, AlUnitdataRx>,
+    /// N(S) of the next unacknowledged advanced link TL-SDU, per SSI
+    al_unitdata_seq: HashMap<u32, u8>,
 }
 
 impl Llc {
@@ -89,6 +109,9 @@ impl Llc {
             outbound_messages: VecDeque::new(),
             outbound_udata_messages: VecDeque::new(),
             link_send_seq: HashMap::new(),
+            advanced_links: HashMap::new(),
+            al_unitdata_rx: HashMap::new(),
+            al_unitdata_seq: HashMap::new(),
         }
     }
 
@@ -347,6 +370,13 @@ impl Llc {
             SapMsgInner::TlaTlUnitdataReqBl(_) => {
                 self.rx_tla_tlunitdata_req_bl(queue, message);
             }
+            SapMsgInner::TlaTlConnectReq(_)
+            | SapMsgInner::TlaTlDataReqAl(_)
+            | SapMsgInner::TlaTlUnitdataReqAl(_)
+            | SapMsgInner::TlaTlDisconnectReq(_)
+            | SapMsgInner::TlaTlReconnectReq(_) => {
+                self.rx_tla_prim_al(queue, message);
+            }
             _ => panic!(),
         }
     }
@@ -401,7 +431,7 @@ impl Llc {
             | LlcPduType::AlAckAlRnr
             | LlcPduType::AlReconnect
             | LlcPduType::AlDisc => {
-                unimplemented_log!("LlcPduType Advanced Link: {}", pdu_type);
+                self.rx_tma_unitdata_ind_al(queue, message, pdu_type);
             }
 
             _ => {
@@ -519,7 +549,7 @@ impl Llc {
                 air_interface_encryption: prim.air_interface_encryption,
                 chan_change_resp_req: prim.chan_change_response_req,
                 chan_change_handle: prim.chan_change_handle,
-                chan_info: prim.chan_info,
+                chan_info: prim.chan_info.take(),
                 report: None, // TODO FIXME
             };
             SapMsg {
@@ -544,7 +574,7 @@ impl Llc {
                 air_interface_encryption: prim.air_interface_encryption,
                 chan_change_resp_req: prim.chan_change_response_req,
                 chan_change_handle: prim.chan_change_handle,
-                chan_info: prim.chan_info,
+                chan_info: prim.chan_info.take(),
                 req_handle: 0, // TODO FIXME
             };
             SapMsg {
@@ -559,6 +589,313 @@ impl Llc {
         queue.push_back(s);
     }
 
+    /// TLA-SAP primitives of the advanced link, see Clause 22.3.3
+    fn rx_tla_prim_al(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tla_prim_al");
+        // Released links are dropped first, so a new link can take their place
+        self.flush_advanced_links(queue);
+        let now = self.dltime;
+
+        match message.msg {
+            SapMsgInner::TlaTlConnectReq(prim) => {
+                let ssi = prim.main_address.ssi;
+                if prim.unacknowledged {
+                    // The unacknowledged service does not need a set-up
+                    let event = AlEvent::ConnectConf(AlReport::Success);
+                    queue.push_back(Self::al_event_to_mle(now, prim.main_address, prim.link_id, prim.endpoint_id, event));
+                } else if let Some(link) = self.advanced_links.get_mut(&ssi) {
+                    link.request_connect();
+                } else {
+                    let link = AdvancedLink::connect(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime.t, now);
+                    self.advanced_links.insert(ssi, link);
+                }
+            }
+            SapMsgInner::TlaTlDataReqAl(mut prim) => match self.advanced_links.get_mut(&prim.main_address.ssi) {
+                Some(link) => link.send(prim.tl_sdu, prim.tx_reporter.take()),
+                None => {
+                    tracing::warn!("TL-DATA request without advanced link to {}, dropping", prim.main_address.ssi);
+                    if let Some(tx_reporter) = prim.tx_reporter {
+                        tx_reporter.mark_discarded();
+                    }
+                }
+            },
+            SapMsgInner::TlaTlUnitdataReqAl(mut prim) => {
+                let ns = self.al_unitdata_seq.entry(prim.main_address.ssi).or_insert(0);
+                let pdus = advanced_link::build_unitdata(*ns, prim.tl_sdu, prim.tx_reporter.take());
+                *ns = (*ns + 1) % 8;
+                for pdu in pdus {
+                    queue.push_back(Self::al_pdu_to_umac(
+                        now,
+                        prim.main_address,
+                        prim.endpoint_id,
+                        message.dltime.t,
+                        pdu,
+                    ));
+                }
+            }
+            SapMsgInner::TlaTlDisconnectReq(prim) => match self.advanced_links.get_mut(&prim.main_address.ssi) {
+                Some(link) => link.disconnect(now),
+                None => {
+                    let event = AlEvent::DisconnectConf(AlReport::Success);
+                    queue.push_back(Self::al_event_to_mle(now, prim.main_address, prim.link_id, prim.endpoint_id, event));
+                }
+            },
+            SapMsgInner::TlaTlReconnectReq(prim) => match self.advanced_links.get_mut(&prim.main_address.ssi) {
+                Some(link) => link.reconnect(now),
+                None => {
+                    let event = AlEvent::ReconnectConf(AlReport::Rejected);
+                    queue.push_back(Self::al_event_to_mle(now, prim.main_address, prim.link_id, prim.endpoint_id, event));
+                }
+            },
+            _ => panic!(),
+        }
+    }
+
+    /// Handles the advanced link PDUs, see Clause 22.3.3
+    fn rx_tma_unitdata_ind_al(&mut self, queue: &mut MessageQueue, mut message: SapMsg, pdu_type: LlcPduType) {
+        tracing::trace!("rx_tma_unitdata_ind_al");
+        self.flush_advanced_links(queue);
+
+        let SapMsgInner::TmaUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let Some(mut pdu) = prim.pdu.take() else {
+            panic!("no pdu");
+        };
+        let addr = prim.main_address;
+        let endpoint_id = prim.endpoint_id;
+        // Timeslot the PDU was received on, as for the basic link. Replies are sent on the same timeslot.
+        let ts = message.dltime.add_timeslots(-2).t;
+        let link_id = ts as LinkId;
+
+        match pdu_type {
+            LlcPduType::AlSetup => {
+                let setup = match AlSetup::from_bitbuf(&mut pdu) {
+                    Ok(setup) => setup,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlSetup: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                };
+                tracing::debug!(ts=%self.dltime, "<- {:?}", setup);
+                if setup.unacknowledged {
+                    unimplemented_log!("AL-SETUP for the unacknowledged service");
+                    return;
+                }
+                match self.advanced_links.get_mut(&addr.ssi) {
+                    Some(link) => link.rx_setup(&setup),
+                    None if setup.setup_report == AlSetupReport::ServiceDefinition => {
+                        let link = AdvancedLink::accept(addr, link_id, endpoint_id, ts, &setup);
+                        self.advanced_links.insert(addr.ssi, link);
+                    }
+                    None => tracing::debug!("ignoring {:?} from {}, no advanced link", setup, addr.ssi),
+                }
+            }
+            LlcPduType::AlDataAlFinal => {
+                let header = match AlData::from_bitbuf(&mut pdu) {
+                    Ok(header) => header,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlData: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                };
+                tracing::debug!(ts=%self.dltime, "<- {:?}", header);
+                match self.advanced_links.get_mut(&addr.ssi) {
+                    Some(link) => link.rx_data(&header, Self::take_segment(&mut pdu)),
+                    None => tracing::warn!("AL-DATA from {} without advanced link, dropping", addr.ssi),
+                }
+            }
+            LlcPduType::AlAlUdataAlUfinal => {
+                let header = match AlUdata::from_bitbuf(&mut pdu) {
+                    Ok(header) => header,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlUdata: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                };
+                tracing::debug!(ts=%self.dltime, "<- {:?}", header);
+                let rx = self.al_unitdata_rx.entry(addr.ssi).or_default();
+                if let Some(tl_sdu) = rx.rx(&header, Self::take_segment(&mut pdu)) {
+                    queue.push_back(SapMsg {
+                        sap: Sap::TlaSap,
+                        src: TetraEntity::Llc,
+                        dest: TetraEntity::Mle,
+                        dltime: message.dltime,
+                        msg: SapMsgInner::TlaTlUnitdataIndAl(TlaTlUnitdataIndAl {
+                            main_address: addr,
+                            link_id,
+                            endpoint_id,
+                            tl_sdu: Some(tl_sdu),
+                        }),
+                    });
+                }
+            }
+            LlcPduType::AlAckAlRnr => {
+                let ack = match AlAck::from_bitbuf(&mut pdu) {
+                    Ok(ack) => ack,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlAck: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                };
+                tracing::debug!(ts=%self.dltime, "<- {:?}", ack);
+                match self.advanced_links.get_mut(&addr.ssi) {
+                    Some(link) => link.rx_ack(&ack, self.dltime),
+                    None => tracing::debug!("ignoring {:?} from {}, no advanced link", ack, addr.ssi),
+                }
+            }
+            LlcPduType::AlReconnect => {
+                let reconnect = match AlReconnect::from_bitbuf(&mut pdu) {
+                    Ok(reconnect) => reconnect,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlReconnect: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                };
+                tracing::debug!(ts=%self.dltime, "<- {:?}", reconnect);
+                match self.advanced_links.get_mut(&addr.ssi) {
+                    Some(link) => link.rx_reconnect(&reconnect),
+                    None if reconnect.reconnect_report == AlReconnectReport::Propose => {
+                        let pdu = AlPdu {
+                            pdu: advanced_link::build_reconnect(AlReconnectReport::Fail),
+                            tx_reporter: None,
+                        };
+                        queue.push_back(Self::al_pdu_to_umac(self.dltime, addr, endpoint_id, ts, pdu));
+                    }
+                    None => tracing::debug!("ignoring {:?} from {}, no advanced link", reconnect, addr.ssi),
+                }
+            }
+            LlcPduType::AlDisc => {
+                let disc = match AlDisc::from_bitbuf(&mut pdu) {
+                    Ok(disc) => disc,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlDisc: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                };
+                tracing::debug!(ts=%self.dltime, "<- {:?}", disc);
+                match self.advanced_links.get_mut(&addr.ssi) {
+                    Some(link) => link.rx_disc(&disc),
+                    None if disc.disc_report == AlDiscReport::Request => {
+                        // Confirm all the same, the peer may have missed our earlier confirmation
+                        let pdu = AlPdu {
+                            pdu: advanced_link::build_disc(AlDiscReport::Confirm),
+                            tx_reporter: None,
+                        };
+                        queue.push_back(Self::al_pdu_to_umac(self.dltime, addr, endpoint_id, ts, pdu));
+                    }
+                    None => tracing::debug!("ignoring {:?} from {}, no advanced link", disc, addr.ssi),
+                }
+            }
+            _ => panic!(),
+        }
+    }
+
+    /// Copies the TL-SDU segment following an AL-DATA or AL-UDATA header
+    fn take_segment(pdu: &mut BitBuffer) -> BitBuffer {
+        let len = pdu.get_len_remaining();
+        let mut segment = BitBuffer::new(len);
+        segment.copy_bits(pdu, len);
+        segment.seek(0);
+        segment
+    }
+
+    fn al_pdu_to_umac(dltime: TdmaTime, addr: TetraAddress, endpoint_id: EndpointId, ts: u8, pdu: AlPdu) -> SapMsg {
+        SapMsg {
+            sap: Sap::TmaSap,
+            src: TetraEntity::Llc,
+            dest: TetraEntity::Umac,
+            dltime: dltime.forward_to_timeslot(ts),
+            msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+                req_handle: 0, // TODO FIXME
+                pdu: pdu.pdu,
+                main_address: addr,
+                endpoint_id,
+                stealing_permission: false,
+                subscriber_class: 0,            // TODO FIXME
+                air_interface_encryption: None, // TODO FIXME
+                stealing_repeats_flag: None,
+                data_category: None,
+                chan_alloc: None,
+                tx_reporter: pdu.tx_reporter,
+            }),
+        }
+    }
+
+    fn al_event_to_mle(dltime: TdmaTime, main_address: TetraAddress, link_id: LinkId, endpoint_id: EndpointId, event: AlEvent) -> SapMsg {
+        let msg = match event {
+            AlEvent::ConnectInd => SapMsgInner::TlaTlConnectInd(TlaTlConnectInd {
+                main_address,
+                link_id,
+                endpoint_id,
+                unacknowledged: false,
+            }),
+            AlEvent::ConnectConf(report) => SapMsgInner::TlaTlConnectConf(TlaTlConnectConf {
+                main_address,
+                link_id,
+                endpoint_id,
+                req_handle: 0, // TODO FIXME
+                report,
+            }),
+            AlEvent::DataInd(tl_sdu) => SapMsgInner::TlaTlDataIndAl(TlaTlDataIndAl {
+                main_address,
+                link_id,
+                endpoint_id,
+                tl_sdu: Some(tl_sdu),
+            }),
+            AlEvent::DisconnectInd(report) => SapMsgInner::TlaTlDisconnectInd(TlaTlDisconnectInd {
+                main_address,
+                link_id,
+                endpoint_id,
+                report,
+            }),
+            AlEvent::DisconnectConf(report) => SapMsgInner::TlaTlDisconnectConf(TlaTlDisconnectConf {
+                main_address,
+                link_id,
+                endpoint_id,
+                report,
+            }),
+            AlEvent::ReconnectConf(report) => SapMsgInner::TlaTlReconnectConf(TlaTlReconnectConf {
+                main_address,
+                link_id,
+                endpoint_id,
+                report,
+            }),
+        };
+        SapMsg {
+            sap: Sap::TlaSap,
+            src: TetraEntity::Llc,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg,
+        }
+    }
+
+    /// Passes the PDUs and events of the advanced links on, and drops released links
+    fn flush_advanced_links(&mut self, queue: &mut MessageQueue) -> bool {
+        let mut had_activity = false;
+        for link in self.advanced_links.values_mut() {
+            while let Some(pdu) = link.pop_pdu() {
+                queue.push_back(Self::al_pdu_to_umac(self.dltime, link.addr, link.endpoint_id, link.ts, pdu));
+                had_activity = true;
+            }
+            while let Some(event) = link.pop_event() {
+                queue.push_back(Self::al_event_to_mle(self.dltime, link.addr, link.link_id, link.endpoint_id, event));
+                had_activity = true;
+            }
+        }
+        self.advanced_links.retain(|_, link| !link.is_released());
+        had_activity
+    }
+
+    /// Runs the advanced link timers and sends what is due
+    fn submit_advanced_links_to_umac(&mut self, queue: &mut MessageQueue) -> bool {
+        for link in self.advanced_links.values_mut() {
+            link.tick(self.dltime);
+        }
+        self.flush_advanced_links(queue)
+    }
+
     fn submit_retransmissions_to_umac(&mut self, queue: &mut MessageQueue) -> bool {
         let mut had_activity = false;
         let dltime = self.dltime;
@@ -790,21 +1127,24 @@ impl TetraEntityTrait for Llc {
     fn tick_end(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
         let mut had_activity = false;
 
-        // Step 1 / 4: Check if we have any transmitted messages that were not acked within the expected window
+        // Step 1 / 5: Check if we have any transmitted messages that were not acked within the expected window
         // Schedule a retransmission if appropriate.
         had_activity |= self.submit_retransmissions_to_umac(queue);
 
-        // Step 2 / 4: Check if there are any messages that were not yet sent down, that we can now send down the stack
+        // Step 2 / 5: Check if there are any messages that were not yet sent down, that we can now send down the stack
         // Messages may be kept since the target SSI has not yet acked them . If the link is now free, we can send the message down and register that we expect an ACK for it.
         had_activity |= self.submit_free_messages_to_umac(queue);
 
-        // Step 3 / 4: Check if any unsent ACKs are still here
+        // Step 3 / 5: Check if any unsent ACKs are still here
         // Take oldest element from scheduled_out_acks, and remove it from the list
         had_activity |= self.submit_ack_replies_to_umac(queue);
 
-        // Step 4 / 4: Send any U-DATA messages
+        // Step 4 / 5: Send any U-DATA messages
         had_activity |= self.submit_udata_msgs_to_umac(queue);
 
+        // Step 5 / 5: Advanced link timers, segments and replies, and indications to the MLE
+        had_activity |= self.submit_advanced_links_to_umac(queue);
+
         had_activity
     }
 }
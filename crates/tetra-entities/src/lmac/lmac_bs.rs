@@ -1,6 +1,6 @@
 use tetra_config::bluestation::{SharedConfig, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BurstType, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence};
+use tetra_core::{BurstType, Direction, MAX_CARRIERS, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence, traffic_channel};
 use tetra_saps::tmv::TmvUnitdataInd;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
@@ -47,10 +47,10 @@ pub struct LmacBs {
     /// Timeslot time, provided by upper layer and then maintained in sync here
     dltime: TdmaTime,
 
-    /// Per-timeslot UL physical channel indicator from UMAC.
+    /// Per-carrier, per-timeslot UL physical channel indicator from UMAC.
     /// UL bursts arrive 2 timeslots after the corresponding DL slot, so we must
     /// keep this keyed by timeslot rather than a single "latest" value.
-    uplink_phy_chan: [PhysicalChannel; 4],
+    uplink_phy_chan: [[PhysicalChannel; 4]; MAX_CARRIERS],
 
     /// Signalled by Umac per timeslot. Set to true when in a traffic burst, the 1st stolen block shows that the 2nd slot is also stolen
     blk2_stolen: bool,
@@ -82,7 +82,7 @@ impl LmacBs {
             scrambling_code: sc,
 
             dltime: TdmaTime::default(),
-            uplink_phy_chan: [PhysicalChannel::Unallocated; 4],
+            uplink_phy_chan: [[PhysicalChannel::Unallocated; 4]; MAX_CARRIERS],
             blk2_stolen: false,
         }
     }
@@ -176,6 +176,8 @@ impl LmacBs {
     }
 
     fn rx_blk_traffic(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel, ul_time: TdmaTime) {
+        let chan = traffic_channel(blk.carrier, ul_time.t);
+
         // Only full-slot TCH/S supported for now
         if lchan != LogicalChannel::TchS || blk.block_num != PhyBlockNum::Both {
             tracing::trace!(
@@ -207,7 +209,7 @@ impl LmacBs {
             src: TetraEntity::Lmac,
             dest: TetraEntity::Umac,
             dltime: ul_time,
-            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts: ul_time.t, data }),
+            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts: chan, data }),
         };
         queue.push_back(msg);
     }
@@ -248,6 +250,7 @@ impl LmacBs {
                 block_num,
                 crc_pass,
                 scrambling_code: self.scrambling_code,
+                direction: Direction::Ul,
             }),
         };
 
@@ -265,7 +268,7 @@ impl LmacBs {
 
         // let pchan = self.determine_phy_chan_ul();
         let ts_idx = ul_time.t as usize - 1;
-        let pchan = self.uplink_phy_chan[ts_idx];
+        let pchan = self.uplink_phy_chan[prim.carrier as usize][ts_idx];
         let lchan = Self::determine_logical_channel_ul(&prim, pchan == PhysicalChannel::Tp, self.blk2_stolen);
 
         // Sanity checks
@@ -278,6 +281,11 @@ impl LmacBs {
             "blk2_stolen must be false when not in a traffic burst"
         );
 
+        // The stealing indication only applies to block 2 of the same burst, bursts of other carriers follow in this timeslot
+        let is_block2 = prim.block_num == PhyBlockNum::Block2;
+
+        // UL signalling on the traffic channels of secondary carriers is passed up as if it
+        // was received in the same timeslot of the main carrier
         match lchan {
             LogicalChannel::Clch => {}
             LogicalChannel::TchS | LogicalChannel::Tch24 | LogicalChannel::Tch48 | LogicalChannel::Tch72 => {
@@ -290,6 +298,9 @@ impl LmacBs {
                 panic!()
             }
         }
+        if is_block2 {
+            self.blk2_stolen = false;
+        }
     }
 
     fn rx_tmv_configure_req(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
@@ -310,7 +321,7 @@ impl LmacBs {
 
         // Update per-timeslot UL physical channel indicator
         let ts_idx = prim.ts.t as usize - 1;
-        self.uplink_phy_chan[ts_idx] = prim.ul_phy_chan;
+        self.uplink_phy_chan[prim.carrier as usize][ts_idx] = prim.ul_phy_chan;
 
         assert!(prim.bbk.is_some(), "rx_tmv_unitdata_req_slot: bbk must be present");
         assert!(prim.blk1.is_some(), "rx_tmv_unitdata_req_slot: blk1 must be present");
@@ -353,6 +364,7 @@ impl LmacBs {
             bbk: None,
             blk1: None,
             blk2: None,
+            carrier: prim.carrier,
         };
 
         // Encode blk1 and optionally blk2
@@ -0,0 +1,106 @@
+use crate::lmac::lmac_ms::LmacMs;
+use crate::{MessageQueue, TetraEntityTrait};
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BurstType, Direction, Sap, TdmaTime, TrainingSequence};
+use tetra_saps::tmv::TmvUnitdataInd;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::tp::TpUnitdataInd;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::lmac::components::errorcontrol;
+
+/// Passive monitor LMAC
+///
+/// Downlink blocks are handled exactly like on an MS, which also recovers the cell's time and
+/// scrambling code. Uplink blocks are decoded with those, and passed to the Umac marked as uplink.
+pub struct LmacMon {
+    /// Follows the downlink of the monitored cell
+    dl: LmacMs,
+}
+
+impl LmacMon {
+    pub fn new(config: SharedConfig) -> Self {
+        Self { dl: LmacMs::new(config) }
+    }
+
+    /// Without the uplink schedule we can't tell traffic from signalling, so every uplink block is
+    /// tried as signalling. Traffic blocks fail the CRC and are dropped.
+    fn determine_logical_channel_ul(blk: &TpUnitdataInd) -> LogicalChannel {
+        match (blk.burst_type, blk.train_type) {
+            (BurstType::CUB, _) => LogicalChannel::SchHu,
+            (BurstType::NUB, TrainingSequence::NormalTrainSeq2) => LogicalChannel::Stch,
+            _ => LogicalChannel::SchF,
+        }
+    }
+
+    fn rx_blk_ul(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd) {
+        let (Some(dltime), Some(scrambling_code)) = (self.dl.tdma_time(), self.dl.scrambling_code()) else {
+            tracing::trace!("rx_blk_ul: not synchronized to the downlink, dropping {:?}", blk.burst_type);
+            return;
+        };
+        // The uplink slot we just received is two slots behind the downlink
+        let ul_time = dltime.add_timeslots(-2);
+
+        let lchan = Self::determine_logical_channel_ul(&blk);
+        let block_num = blk.block_num;
+        let (type1bits, crc_pass) = errorcontrol::decode_cp(lchan, blk, Some(scrambling_code));
+        let Some(type1bits) = type1bits else {
+            return;
+        };
+        tracing::debug!(
+            "rx_blk_ul {:?} {:?} CRC: {}",
+            lchan,
+            block_num,
+            if crc_pass { "ok" } else { "WRONG" }
+        );
+        if !crc_pass {
+            return;
+        }
+
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Umac,
+            dltime: ul_time,
+            msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+                pdu: type1bits,
+                block_num,
+                logical_channel: lchan,
+                crc_pass,
+                scrambling_code,
+                direction: Direction::Ul,
+            }),
+        };
+        queue.push_back(m);
+    }
+}
+
+impl TetraEntityTrait for LmacMon {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Lmac
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match (&message.sap, &message.msg) {
+            (Sap::TpSap, SapMsgInner::TpUnitdataInd(prim)) if matches!(prim.burst_type, BurstType::NUB | BurstType::CUB) => {
+                let SapMsgInner::TpUnitdataInd(prim) = message.msg else {
+                    unreachable!()
+                };
+                self.rx_blk_ul(queue, prim);
+            }
+            (Sap::TpSap, SapMsgInner::TpUnitdataInd(_)) | (Sap::TmvSap, SapMsgInner::TmvConfigureReq(_)) => {
+                self.dl.rx_prim(queue, message);
+            }
+            _ => {
+                tracing::warn!("rx_prim: unexpected {:?} on {:?}", message.msg, message.sap);
+            }
+        }
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dl.tick_start(queue, ts);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{PhyBlockNum, PhyBlockType, Sap, TdmaTime, unimplemented_log};
-use tetra_saps::tmv::TmvUnitdataInd;
+use tetra_core::{BurstType, Direction, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
+use tetra_saps::tmd::TmdCircuitDataInd;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
-use tetra_saps::tp::TpUnitdataInd;
+use tetra_saps::tmv::{TmvUnitdataInd, TmvUnitdataReq};
+use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::lmac::components::{errorcontrol, scrambler};
@@ -29,7 +30,7 @@ impl Default for LmacTrafficChan {
 pub struct CurBurst {
     pub is_traffic: bool,
     pub usage: Option<u8>,
-    pub blk1_stolen: bool,
+    /// Signalled by the Umac when the first half of a stolen traffic burst says the second half is stolen too
     pub blk2_stolen: bool,
 }
 
@@ -63,6 +64,16 @@ impl LmacMs {
         }
     }
 
+    /// Downlink time of the slot being received, None until the first SYNC
+    pub fn tdma_time(&self) -> Option<TdmaTime> {
+        self.ts
+    }
+
+    /// Scrambling code of the serving cell, None until known
+    pub fn scrambling_code(&self) -> Option<u32> {
+        self.scrambling_code
+    }
+
     fn rx_bbk(&mut self, queue: &mut MessageQueue, bbk: TpUnitdataInd) {
         // tracing::trace!("rx_bbk: {:?}", bbk.block.dump_bin());
 
@@ -89,6 +100,7 @@ impl LmacMs {
                 logical_channel: LogicalChannel::Aach,
                 crc_pass: true,
                 scrambling_code,
+                direction: Direction::Dl,
             }),
         };
 
@@ -109,11 +121,10 @@ impl LmacMs {
         }
 
         // Sanity check: this should not be a mandatory BSCH block
-        assert!(
-            !(t.is_mandatory_bsch() && blk.block_num == PhyBlockNum::Block1),
-            "Mandatory BSCH block should be be SB1, not {:?}",
-            blk.block_type
-        );
+        // If it is, our time is off, which gets corrected by the next SYNC
+        if t.is_mandatory_bsch() && blk.block_num == PhyBlockNum::Block1 {
+            tracing::warn!("Mandatory BSCH block should be be SB1, not {:?}", blk.block_type);
+        }
 
         // SB2 is broadcast if scheduled according to time
         if blk.block_type == PhyBlockType::SB2 && t.is_mandatory_bnch() {
@@ -121,11 +132,10 @@ impl LmacMs {
         }
 
         // is_traffic was previously extracted from the BBK block
-        // If traffic, but block was stolen, we're still signalling (e.g. SCH_HD)
+        // If traffic, but block was stolen, we're still signalling (STCH). A traffic burst only
+        // comes in halves (normal training sequence 2) when its first half is stolen (Clause 9.4.4.3.2)
         if self.cur_burst.is_traffic {
-            if (blk.block_num == PhyBlockNum::Block1 && self.cur_burst.blk1_stolen)
-                || (blk.block_num == PhyBlockNum::Block2 && self.cur_burst.blk2_stolen)
-            {
+            if blk.block_num == PhyBlockNum::Block1 || (blk.block_num == PhyBlockNum::Block2 && self.cur_burst.blk2_stolen) {
                 // This block is stolen traffic
                 return LogicalChannel::Stch;
             } else {
@@ -143,8 +153,42 @@ impl LmacMs {
         }
     }
 
-    fn rx_blk_traffic(&mut self, _queue: &mut MessageQueue, _blk: TpUnitdataInd, _lchan: LogicalChannel) {
-        unimplemented_log!("rx_blk_traffic: Traffic channel reception not implemented yet");
+    fn rx_blk_traffic(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel) {
+        // Only full-slot TCH/S supported for now
+        if lchan != LogicalChannel::TchS || blk.block_num != PhyBlockNum::Both {
+            tracing::trace!(
+                "rx_blk_traffic: ignoring partial/unsupported lchan={:?} blk_num={:?}",
+                lchan,
+                blk.block_num
+            );
+            return;
+        }
+        let (Some(scrambling_code), Some(ts)) = (self.scrambling_code, self.ts) else {
+            return;
+        };
+
+        let (decoded, crc_ok) = errorcontrol::decode_tp(lchan, blk.block, scrambling_code);
+        let Some(mut acelp_bits) = decoded else {
+            tracing::warn!("rx_blk_traffic: decode_tp returned None");
+            return;
+        };
+        if !crc_ok {
+            tracing::trace!("rx_blk_traffic: CRC fail (BFI), still forwarding for concealment");
+        }
+
+        // Convert ACELP BitBuffer to Vec<u8> (one bit per byte, 274 bytes)
+        let mut data = vec![0u8; acelp_bits.get_len()];
+        acelp_bits.seek(0);
+        acelp_bits.to_bitarr(&mut data);
+
+        let msg = SapMsg {
+            sap: Sap::TmdSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Umac,
+            dltime: ts,
+            msg: SapMsgInner::TmdCircuitDataInd(TmdCircuitDataInd { ts: ts.t, data }),
+        };
+        queue.push_back(msg);
     }
 
     fn rx_blk_cp(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel) {
@@ -189,9 +233,16 @@ impl LmacMs {
                     logical_channel: lchan,
                     crc_pass,
                     scrambling_code: scramb_code,
+                    direction: Direction::Dl,
                 }),
             };
-            queue.push_back(m);
+            if lchan == LogicalChannel::Bsch || (lchan == LogicalChannel::Stch && block_num == PhyBlockNum::Block1) {
+                // The SYNC sets time and scrambling code, which the remaining blocks of the burst need.
+                // The first STCH half tells the Umac whether the second half is stolen as well.
+                queue.push_prio(m, MessagePrio::Immediate);
+            } else {
+                queue.push_back(m);
+            }
         }
     }
 
@@ -199,6 +250,10 @@ impl LmacMs {
         tracing::debug!("rx_tp_prim: time: {:?} msg {:?}", self.ts, message);
 
         let SapMsgInner::TpUnitdataInd(prim) = message.msg else { panic!() };
+        if self.ts.is_none() && prim.block_type != PhyBlockType::SB1 {
+            tracing::trace!("rx_tp_prim: waiting for SYNC, dropping {:?}", prim.block_type);
+            return;
+        }
         let lchan = self.determine_logical_channel_dl(&prim, self.ts.as_ref().unwrap_or(&TdmaTime::default()));
 
         match lchan {
@@ -234,6 +289,71 @@ impl LmacMs {
             self.cur_burst.is_traffic = is_traffic;
             tracing::debug!("rx_tmv_configure_req: set cur_burst.is_traffic {}", is_traffic);
         }
+
+        if let Some(stolen) = prim.blk2_stolen {
+            self.cur_burst.blk2_stolen = stolen;
+            tracing::debug!("rx_tmv_configure_req: set cur_burst.blk2_stolen {}", stolen);
+        }
+    }
+
+    /// Encode the blocks for an uplink slot and pass them to the Phy
+    fn rx_tmv_unitdata_req_slot(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::debug!("rx_tmv_unitdata_req_slot");
+        let SapMsgInner::TmvUnitdataReq(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let blk1 = prim.blk1.take();
+        let blk2 = prim.blk2.take();
+        let lchan = blk1
+            .as_ref()
+            .or(blk2.as_ref())
+            .expect("rx_tmv_unitdata_req_slot: no block to transmit")
+            .logical_channel;
+
+        // Determine train and burst type
+        let (burst_type, train_type) = match lchan {
+            LogicalChannel::SchHu => {
+                // Control uplink burst in one or both subslots
+                (BurstType::CUB, TrainingSequence::ExtendedTrainSeq)
+            }
+            LogicalChannel::SchF | LogicalChannel::TchS => {
+                // Single full block
+                assert!(blk2.is_none());
+                (BurstType::NUB, TrainingSequence::NormalTrainSeq1)
+            }
+            LogicalChannel::Stch => {
+                // Two half-blocks, the first one stolen from traffic
+                assert!(blk1.is_some() && blk2.is_some());
+                (BurstType::NUB, TrainingSequence::NormalTrainSeq2)
+            }
+            _ => panic!("rx_tmv_unitdata_req_slot: unsupported logical channel {:?}", lchan),
+        };
+
+        let encode = |blk: TmvUnitdataReq, blk_num: u8| {
+            if blk.logical_channel.is_traffic() {
+                errorcontrol::encode_tp(blk, blk_num)
+            } else {
+                errorcontrol::encode_cp(blk)
+            }
+        };
+        let prim_phy = TpUnitdataReqSlot {
+            train_type,
+            burst_type,
+            bbk: None,
+            blk1: blk1.map(|blk| encode(blk, 1)),
+            blk2: blk2.map(|blk| encode(blk, 2)),
+            carrier: 0,
+        };
+
+        let m = SapMsg {
+            sap: Sap::TpSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Phy,
+            dltime: message.dltime,
+            msg: SapMsgInner::TpUnitdataReq(prim_phy),
+        };
+        queue.push_back(m);
     }
 
     fn rx_tmv_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -244,7 +364,7 @@ impl LmacMs {
                 self.rx_tmv_configure_req(queue, message);
             }
             SapMsgInner::TmvUnitdataReq(_) => {
-                unimplemented_log!("TmvUnitdataReq")
+                self.rx_tmv_unitdata_req_slot(queue, message);
             }
             _ => {
                 panic!();
@@ -279,11 +399,11 @@ impl TetraEntityTrait for LmacMs {
         // Reset current burst state
         self.cur_burst = CurBurst::default();
 
-        // Increase TDMA time if it has been set
+        // Increase TDMA time if it has been set. In MS mode, time is recovered from SYNC,
+        // independent of the router's tick counter.
         if let Some(mod_time) = self.ts {
-            assert!(mod_time == ts, "time out of sync"); // TODO handle properly
             self.ts = Some(mod_time.add_timeslots(1));
-            tracing::debug!("tick: new TdmaTime: {:?}", self.ts.unwrap()); // Guaranteed in BS mode
+            tracing::debug!("tick: new TdmaTime: {:?} (tick {})", self.ts.unwrap(), ts); // Guaranteed, just set
         }
     }
 }
@@ -1,5 +1,8 @@
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{CfgCellInfo, CfgNeighbourCell, SharedConfig};
+use tetra_core::freqs::FreqInfo;
 use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::mle::fields::cell_reselect_parameters::CellReselectParameters;
+use tetra_pdus::mle::fields::neighbour_cell_info::NeighbourCellInfoCa;
 use tetra_pdus::mle::{enums::mle_protocol_discriminator::MleProtocolDiscriminator, pdus::d_nwrk_broadcast::DNwrkBroadcast};
 use tetra_saps::{SapMsg, SapMsgInner, tla::TlaTlUnitdataReqBl};
 
@@ -10,6 +13,7 @@ pub enum BroadcastType {
     /// Initial value and value when no broadcast types are enabled
     None,
     NetworkTime,
+    NeighbourCells,
 }
 
 pub struct MleBroadcast {
@@ -37,23 +41,64 @@ impl MleBroadcast {
             BroadcastType::NetworkTime => {
                 self.send_d_nwrk_broadcast(queue, ts);
             }
+            BroadcastType::NeighbourCells => {
+                self.send_d_nwrk_broadcast_neighbours(queue, ts);
+            }
             BroadcastType::None => {
                 // No broadcast to send
             }
         }
     }
 
-    /// Deterines the next type for the next broadcast message
+    /// Deterines the next type for the next broadcast message, cycling through the enabled types
     fn determine_next_broadcast_type(&self) -> BroadcastType {
+        let time_enabled = self.time_broadcast.is_some();
+        let neighbours_enabled = !self.config.config().neighbour_cells.is_empty();
         match self.last_broadcast_type {
-            BroadcastType::None => {
-                if self.time_broadcast.is_some() {
-                    BroadcastType::NetworkTime
-                } else {
-                    BroadcastType::None
-                }
-            }
-            BroadcastType::NetworkTime => BroadcastType::NetworkTime,
+            BroadcastType::None | BroadcastType::NeighbourCells if time_enabled => BroadcastType::NetworkTime,
+            BroadcastType::None | BroadcastType::NetworkTime if neighbours_enabled => BroadcastType::NeighbourCells,
+            _ if time_enabled => BroadcastType::NetworkTime,
+            _ => BroadcastType::None,
+        }
+    }
+
+    fn cell_reselect_parameters(cell: &CfgCellInfo) -> CellReselectParameters {
+        CellReselectParameters::from_db(
+            cell.slow_reselect_threshold,
+            cell.slow_reselect_hysteresis,
+            cell.fast_reselect_threshold,
+            cell.fast_reselect_hysteresis,
+        )
+    }
+
+    /// Builds the neighbour cell information element. Carrier settings left unset are those of the serving cell.
+    fn neighbour_cell_info(cell: &CfgCellInfo, neighbour: &CfgNeighbourCell) -> NeighbourCellInfoCa {
+        let main_carrier_number_extension = neighbour.has_carrier_extension().then(|| {
+            let band = neighbour.freq_band.unwrap_or(cell.freq_band);
+            let offset_hz = neighbour.freq_offset_hz.unwrap_or(cell.freq_offset_hz);
+            // Offsets are validated at config parse time
+            let offset = FreqInfo::freq_offset_hz_to_id(offset_hz).unwrap_or(0);
+            let duplex = neighbour.duplex_spacing_id.unwrap_or(cell.duplex_spacing_id);
+            let reverse = neighbour.reverse_operation.unwrap_or(cell.reverse_operation);
+            ((band as u16) << 6) | ((offset as u16) << 4) | ((duplex as u16) << 1) | reverse as u16
+        });
+
+        NeighbourCellInfoCa {
+            cell_identifier_ca: neighbour.cell_id,
+            cell_reselection_types_supported: neighbour.reselection_types_supported,
+            neighbour_cell_synchronized: neighbour.synchronized,
+            cell_service_level: neighbour.service_level,
+            main_carrier_number: neighbour.main_carrier,
+            main_carrier_number_extension,
+            mcc: neighbour.mcc,
+            mnc: neighbour.mnc,
+            location_area: Some(neighbour.location_area),
+            maximum_ms_transmit_power: neighbour.max_ms_tx_power,
+            minimum_rx_access_level: neighbour.min_rx_access_level,
+            subscriber_class: None,
+            bs_service_details: None,
+            timeshare_cell_or_security_parameters: None,
+            tdma_frame_offset: None,
         }
     }
 
@@ -63,15 +108,39 @@ impl MleBroadcast {
         let time_value = network_time::encode_tetra_network_time(tz).unwrap();
 
         let pdu = DNwrkBroadcast {
-            cell_re_select_parameters: 0,
+            cell_re_select_parameters: Self::cell_reselect_parameters(&self.config.config().cell),
             cell_load_ca: 0,
             tetra_network_time: Some(time_value),
             number_of_ca_neighbour_cells: Some(0),
-            neighbour_cell_information_for_ca: None,
+            neighbour_cell_information_for_ca: vec![],
         };
+        self.submit_d_nwrk_broadcast(queue, ts, pdu);
+        tracing::info!("D-NWRK-BROADCAST sent (tz={}, time=0x{:012X})", tz, time_value);
+    }
+
+    fn send_d_nwrk_broadcast_neighbours(&self, queue: &mut MessageQueue, ts: TdmaTime) {
+        let config = self.config.config();
+        let neighbours: Vec<NeighbourCellInfoCa> = config
+            .neighbour_cells
+            .iter()
+            .map(|neighbour| Self::neighbour_cell_info(&config.cell, neighbour))
+            .collect();
+        let num_neighbours = neighbours.len();
 
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: Self::cell_reselect_parameters(&config.cell),
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(num_neighbours as u64),
+            neighbour_cell_information_for_ca: neighbours,
+        };
+        self.submit_d_nwrk_broadcast(queue, ts, pdu);
+        tracing::info!("D-NWRK-BROADCAST sent ({} neighbour cells)", num_neighbours);
+    }
+
+    fn submit_d_nwrk_broadcast(&self, queue: &mut MessageQueue, ts: TdmaTime, pdu: DNwrkBroadcast) {
         // Serialize the PDU (includes 3-bit MLE PDU type)
-        let mut pdu_buf = BitBuffer::new(128);
+        let mut pdu_buf = BitBuffer::new_autoexpand(128);
         if let Err(e) = pdu.to_bitbuf(&mut pdu_buf) {
             tracing::warn!("Failed to serialize D-NWRK-BROADCAST: {:?}", e);
             return;
@@ -117,6 +186,5 @@ impl MleBroadcast {
             }),
         };
         queue.push_back(sapmsg);
-        tracing::info!("D-NWRK-BROADCAST sent (tz={}, time=0x{:012X})", tz, time_value);
     }
 }
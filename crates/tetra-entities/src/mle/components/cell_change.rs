@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, Sap, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::mle::enums::{
+    channel_command_valid::ChannelCommandValid, mle_fail_cause::MleFailCause, mle_protocol_discriminator::MleProtocolDiscriminator,
+};
+use tetra_pdus::mle::pdus::{
+    d_new_cell::DNewCell, d_prepare_fail::DPrepareFail, d_restore_ack::DRestoreAck, d_restore_fail::DRestoreFail, u_prepare::UPrepare,
+    u_restore::URestore,
+};
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_saps::{SapMsg, SapMsgInner, tla::TlaTlDataReqBl};
+
+use crate::MessageQueue;
+
+/// How long MM or CMCE get to answer the SDU of a U-PREPARE or U-RESTORE, about 10 seconds
+const CELL_CHANGE_TIMEOUT_TIMESLOTS: i32 = 10 * 18 * 4;
+
+/// Announced cell reselection (clause 18.3.4.7) as seen by the SwMI. On the old cell the MS prepares the reselection
+/// with U-PREPARE, on the new cell it restores its calls with U-RESTORE. The SDUs these carry are handled by MM and
+/// CMCE, whose responses are returned to the MS inside D-NEW-CELL, D-PREPARE-FAIL, D-RESTORE-ACK or D-RESTORE-FAIL.
+pub struct MleCellChange {
+    config: SharedConfig,
+    /// U-PREPARE whose forward registration is being handled by MM: ISSI -> time received
+    pending_prepares: HashMap<u32, TdmaTime>,
+    /// U-RESTORE whose U-CALL RESTORE is being handled by CMCE: ISSI -> time received
+    pending_restores: HashMap<u32, TdmaTime>,
+}
+
+impl MleCellChange {
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            pending_prepares: HashMap::new(),
+            pending_restores: HashMap::new(),
+        }
+    }
+
+    /// Handles U-PREPARE from an MS about to leave for a neighbour cell. Returns the MM PDU it carries, if any,
+    /// which is to be passed to MM. The MS is then answered once MM responds.
+    pub fn rx_u_prepare(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, pdu: UPrepare) -> Option<BitBuffer> {
+        tracing::info!("<- {} from {}", pdu, addr);
+
+        let Some(cell_id) = pdu.cell_identifier_ca else {
+            // The MS asks which neighbour cells there are, it learns them from D-NWRK-BROADCAST instead
+            Self::send_d_prepare_fail(queue, dltime, addr, MleFailCause::NeighbourCellEnquiryNotAvailable, None);
+            return None;
+        };
+        if !self
+            .config
+            .config()
+            .neighbour_cells
+            .iter()
+            .any(|cell| cell.cell_id as u64 == cell_id)
+        {
+            tracing::info!("U-PREPARE from {} for unknown neighbour cell {}", addr, cell_id);
+            Self::send_d_prepare_fail(queue, dltime, addr, MleFailCause::CellReselectionTypeNotSupported, None);
+            return None;
+        }
+
+        match pdu.sdu {
+            Some(sdu) => {
+                // Forward registration, D-NEW-CELL follows with the MM response
+                self.pending_prepares.insert(addr.ssi, dltime);
+                Some(sdu)
+            }
+            None => {
+                Self::send_d_new_cell(queue, dltime, addr, None);
+                None
+            }
+        }
+    }
+
+    /// Handles U-RESTORE from an MS that arrived on this cell. Returns the U-CALL RESTORE it carries, if any,
+    /// which is to be passed to CMCE. The MS is then answered once CMCE responds.
+    pub fn rx_u_restore(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, pdu: URestore) -> Option<BitBuffer> {
+        tracing::info!("<- {} from {}", pdu, addr);
+
+        match pdu.sdu {
+            Some(sdu) => {
+                self.pending_restores.insert(addr.ssi, dltime);
+                Some(sdu)
+            }
+            None => {
+                // Nothing to restore besides the C-plane itself
+                let tl_sdu = Self::build_tl_sdu(|buf| DRestoreAck { sdu: None }.to_bitbuf(buf));
+                Self::send_tl_sdu(queue, dltime, addr, tl_sdu);
+                None
+            }
+        }
+    }
+
+    /// If the MM PDU for this MS answers the forward registration of a U-PREPARE, returns the TL-SDU that carries it
+    /// to the MS instead: D-NEW-CELL if the registration was accepted, D-PREPARE-FAIL if it was rejected.
+    pub fn wrap_mm_pdu(&mut self, addr: TetraAddress, mm_pdu: &BitBuffer) -> Option<BitBuffer> {
+        if !self.pending_prepares.contains_key(&addr.ssi) {
+            return None;
+        }
+        let pdu_type = mm_pdu.peek_bits_startoffset(0, 4).and_then(|bits| MmPduTypeDl::try_from(bits).ok());
+        let tl_sdu = match pdu_type {
+            Some(MmPduTypeDl::DLocationUpdateAccept) => {
+                let pdu = DNewCell {
+                    channel_command_valid: ChannelCommandValid::ChangeChannelImmediately.into_raw() as u8,
+                    sdu: Some(mm_pdu.clone()),
+                };
+                tracing::info!("-> {} to {}", pdu, addr);
+                Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf))
+            }
+            Some(MmPduTypeDl::DLocationUpdateReject) => {
+                let pdu = DPrepareFail {
+                    fail_cause: MleFailCause::CellReselectionTypeNotSupported.into_raw() as u8,
+                    sdu: Some(mm_pdu.clone()),
+                };
+                tracing::info!("-> {} to {}", pdu, addr);
+                Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf))
+            }
+            _ => {
+                // Intermediate MM signalling such as authentication goes to the MS as usual
+                return None;
+            }
+        };
+        self.pending_prepares.remove(&addr.ssi);
+        Some(tl_sdu)
+    }
+
+    /// If the CMCE PDU for this MS answers the U-CALL RESTORE of a U-RESTORE, returns the TL-SDU that replaces it:
+    /// D-RESTORE-ACK carrying the D-CALL RESTORE, or D-RESTORE-FAIL if CMCE released the call instead.
+    pub fn wrap_cmce_pdu(&mut self, addr: TetraAddress, cmce_pdu: &BitBuffer) -> Option<BitBuffer> {
+        if !self.pending_restores.contains_key(&addr.ssi) {
+            return None;
+        }
+        let pdu_type = cmce_pdu
+            .peek_bits_startoffset(0, 5)
+            .and_then(|bits| CmcePduTypeDl::try_from(bits).ok());
+        let tl_sdu = match pdu_type {
+            Some(CmcePduTypeDl::DCallRestore) => {
+                let pdu = DRestoreAck {
+                    sdu: Some(cmce_pdu.clone()),
+                };
+                tracing::info!("-> {} to {}", pdu, addr);
+                Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf))
+            }
+            Some(CmcePduTypeDl::DRelease) => {
+                let pdu = DRestoreFail {
+                    fail_cause: MleFailCause::RestorationCannotBeDoneOnCell.into_raw() as u8,
+                };
+                tracing::info!("-> {} to {}", pdu, addr);
+                Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf))
+            }
+            _ => return None,
+        };
+        self.pending_restores.remove(&addr.ssi);
+        Some(tl_sdu)
+    }
+
+    /// Answers the MSs for which MM or CMCE never responded
+    pub fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        let expired: Vec<u32> = self
+            .pending_prepares
+            .iter()
+            .filter(|(_, received)| received.age(ts) > CELL_CHANGE_TIMEOUT_TIMESLOTS)
+            .map(|(ssi, _)| *ssi)
+            .collect();
+        for ssi in expired {
+            // Let the MS go, it registers on the new cell by itself
+            tracing::warn!("No MM response to U-PREPARE from ISSI {}, sending D-NEW-CELL", ssi);
+            self.pending_prepares.remove(&ssi);
+            Self::send_d_new_cell(queue, ts, TetraAddress::issi(ssi), None);
+        }
+
+        let expired: Vec<u32> = self
+            .pending_restores
+            .iter()
+            .filter(|(_, received)| received.age(ts) > CELL_CHANGE_TIMEOUT_TIMESLOTS)
+            .map(|(ssi, _)| *ssi)
+            .collect();
+        for ssi in expired {
+            tracing::warn!("No CMCE response to U-RESTORE from ISSI {}, sending D-RESTORE-FAIL", ssi);
+            self.pending_restores.remove(&ssi);
+            let pdu = DRestoreFail {
+                fail_cause: MleFailCause::RestorationCannotBeDoneOnCell.into_raw() as u8,
+            };
+            Self::send_tl_sdu(queue, ts, TetraAddress::issi(ssi), Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf)));
+        }
+    }
+
+    fn send_d_new_cell(queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, sdu: Option<BitBuffer>) {
+        let pdu = DNewCell {
+            channel_command_valid: ChannelCommandValid::ChangeChannelImmediately.into_raw() as u8,
+            sdu,
+        };
+        tracing::info!("-> {} to {}", pdu, addr);
+        Self::send_tl_sdu(queue, dltime, addr, Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf)));
+    }
+
+    fn send_d_prepare_fail(queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, cause: MleFailCause, sdu: Option<BitBuffer>) {
+        let pdu = DPrepareFail {
+            fail_cause: cause.into_raw() as u8,
+            sdu,
+        };
+        tracing::info!("-> {} to {}", pdu, addr);
+        Self::send_tl_sdu(queue, dltime, addr, Self::build_tl_sdu(|buf| pdu.to_bitbuf(buf)));
+    }
+
+    /// Serializes an MLE PDU, prefixed with the MLE protocol discriminator
+    fn build_tl_sdu(write_pdu: impl FnOnce(&mut BitBuffer) -> Result<(), PduParseErr>) -> BitBuffer {
+        let mut tl_sdu = BitBuffer::new_autoexpand(64);
+        tl_sdu.write_bits(MleProtocolDiscriminator::Mle.into_raw(), 3);
+        write_pdu(&mut tl_sdu).expect("Failed to serialize MLE PDU");
+        let len = tl_sdu.get_pos();
+        tl_sdu.seek(0);
+        let mut sized = BitBuffer::new(len);
+        sized.copy_bits(&mut tl_sdu, len);
+        sized.seek(0);
+        sized
+    }
+
+    /// Sends an MLE TL-SDU to the MS over the acknowledged basic link
+    fn send_tl_sdu(queue: &mut MessageQueue, dltime: TdmaTime, addr: TetraAddress, tl_sdu: BitBuffer) {
+        queue.push_back(SapMsg {
+            sap: Sap::TlaSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Llc,
+            dltime,
+            msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+                main_address: addr,
+                link_id: 0,
+                endpoint_id: 0,
+                tl_sdu,
+                stealing_permission: false,
+                subscriber_class: 0,
+                fcs_flag: false,
+                air_interface_encryption: None,
+                stealing_repeats_flag: None,
+                data_class_info: None,
+                req_handle: 0,
+                graceful_degradation: None,
+                chan_alloc: None,
+                tx_reporter: None,
+            }),
+        });
+    }
+}
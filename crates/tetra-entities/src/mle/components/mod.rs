@@ -1,3 +1,4 @@
 pub mod broadcast;
+pub mod cell_change;
 pub mod mle_router;
 pub mod network_time;
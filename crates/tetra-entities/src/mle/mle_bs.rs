@@ -1,22 +1,26 @@
 use crate::mle::components::broadcast::MleBroadcast;
+use crate::mle::components::cell_change::MleCellChange;
 use crate::mle::components::mle_router::MleRouter;
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, TdmaTime, unimplemented_log};
+use tetra_core::{BitBuffer, EndpointId, Layer2Service, LinkId, Sap, TdmaTime, TetraAddress, unimplemented_log};
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
 use tetra_saps::lmm::LmmMleUnitdataInd;
 use tetra_saps::ltpd::LtpdMleUnitdataInd;
-use tetra_saps::tla::{TlaTlDataReqBl, TlaTlUnitdataReqBl};
+use tetra_saps::tla::{TlaTlConnectReq, TlaTlDataReqAl, TlaTlDataReqBl, TlaTlUnitdataReqAl, TlaTlUnitdataReqBl};
 use tetra_saps::{SapMsg, SapMsgInner};
 
-use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use tetra_pdus::llc::consts::consts::N251_BL_MAX_TLSDU_LEN_BITS;
+use tetra_pdus::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mle::pdus::{u_prepare::UPrepare, u_restore::URestore};
 
 pub struct MleBs {
     config: SharedConfig,
     router: MleRouter,
     broadcast: MleBroadcast,
+    cell_change: MleCellChange,
 }
 
 /// Multiframe at which D-NWRK-BROADCAST is sent within each hyperframe, 1-60
@@ -28,64 +32,67 @@ const MLE_BROADCAST_FRAME: u8 = 1;
 impl MleBs {
     pub fn new(config: SharedConfig) -> Self {
         let broadcast = MleBroadcast::new(config.clone());
+        let cell_change = MleCellChange::new(config.clone());
         Self {
             config,
             router: MleRouter::new(),
             broadcast,
+            cell_change,
         }
     }
 
-    fn rx_tla_mle_pdu(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    fn rx_tla_mle_pdu(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        main_address: TetraAddress,
+        link_id: LinkId,
+        endpoint_id: EndpointId,
+        mut sdu: BitBuffer,
+    ) {
         tracing::trace!("rx_tla_mle_pdu");
 
-        // Extract tm_sdu from whatever primitive we have
-        let tm_sdu = {
-            match message.msg {
-                SapMsgInner::TlaTlDataIndBl(prim) => prim.tl_sdu,
-                _ => {
-                    panic!();
-                }
-            }
-        };
-        let Some(sdu) = tm_sdu else {
-            tracing::debug!("rx_tla_mle_pdu: no tm_sdu");
-            return;
-        };
-
-        // Determine which type of TL-SDU we have and call handler function
+        // Determine which type of MLE PDU we have and call handler function
         let Some(bits) = sdu.peek_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
             return;
         };
-        let Ok(pdu_type) = MlePduTypeDl::try_from(bits) else {
+        let Ok(pdu_type) = MlePduTypeUl::try_from(bits) else {
             tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
             return;
         };
 
         match pdu_type {
-            MlePduTypeDl::DNewCell => {
-                unimplemented_log!("DNewCell")
-            }
-            MlePduTypeDl::DPrepareFail => {
-                unimplemented_log!("DPrepareFail")
-            }
-            MlePduTypeDl::DNwrkBroadcast => {
-                unimplemented_log!("DNwrkBroadcast")
-            }
-            MlePduTypeDl::DNwrkBroadcastExt => {
-                unimplemented_log!("DNwrkBroadcastExt")
-            } // TODO FIXME CHECK this option and assocaited int
-            MlePduTypeDl::DRestoreAck => {
-                unimplemented_log!("DRestoreAck")
-            }
-            MlePduTypeDl::DRestoreFail => {
-                unimplemented_log!("DRestoreFail")
+            MlePduTypeUl::UPrepare => {
+                let pdu = match UPrepare::from_bitbuf(&mut sdu) {
+                    Ok(pdu) => pdu,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing U-PREPARE: {:?}", e);
+                        return;
+                    }
+                };
+                if let Some(mm_sdu) = self.cell_change.rx_u_prepare(queue, dltime, main_address, pdu) {
+                    self.deliver_to_mm(queue, dltime, main_address, link_id, endpoint_id, mm_sdu);
+                }
             }
-            MlePduTypeDl::DChannelResponse => {
-                unimplemented_log!("DChannelResponse")
+            MlePduTypeUl::URestore => {
+                let pdu = match URestore::from_bitbuf(&mut sdu) {
+                    Ok(pdu) => pdu,
+                    Err(e) => {
+                        tracing::warn!("Failed parsing U-RESTORE: {:?}", e);
+                        return;
+                    }
+                };
+                if let Some(cmce_sdu) = self.cell_change.rx_u_restore(queue, dltime, main_address, pdu) {
+                    self.deliver_to_cmce(queue, dltime, main_address, link_id, endpoint_id, cmce_sdu);
+                }
             }
-            MlePduTypeDl::ExtPdu => {
-                unimplemented_log!("ExtPdu")
+            MlePduTypeUl::UPrepareDa
+            | MlePduTypeUl::UIrregularChannelAdvice
+            | MlePduTypeUl::UChannelClassAdvice
+            | MlePduTypeUl::UChannelRequest
+            | MlePduTypeUl::ExtPdu => {
+                unimplemented_log!("{}", pdu_type);
             }
         }
     }
@@ -93,12 +100,19 @@ impl MleBs {
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_prim");
         match message.msg {
-            SapMsgInner::TlaTlDataIndBl(_) => {
-                self.rx_tla_data_ind_bl(queue, message);
+            SapMsgInner::TlaTlDataIndBl(_) | SapMsgInner::TlaTlDataIndAl(_) => {
+                self.rx_tla_data_ind(queue, message);
+            }
+            SapMsgInner::TlaTlUnitdataIndBl(_) | SapMsgInner::TlaTlUnitdataIndAl(_) => {
+                self.rx_tla_unitdata_ind(queue, message);
             }
-            SapMsgInner::TlaTlUnitdataIndBl(_) => {
-                // self.rx_tla_unitdata_ind_bl(queue, message);
-                panic!("BS can't receive TL-UNITDATA");
+            SapMsgInner::TlaTlConnectInd(_)
+            | SapMsgInner::TlaTlConnectConf(_)
+            | SapMsgInner::TlaTlDisconnectInd(_)
+            | SapMsgInner::TlaTlDisconnectConf(_)
+            | SapMsgInner::TlaTlReconnectConf(_) => {
+                // The LLC manages the advanced links by itself, the outcome of transfers is tracked with the TxReporter
+                tracing::debug!("advanced link: {:?}", message.msg);
             }
             _ => {
                 panic!();
@@ -106,12 +120,14 @@ impl MleBs {
         }
     }
 
-    fn rx_tla_data_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+    fn rx_tla_data_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         // Take ownership of bitbuf and read protocol discriminator
-        let SapMsgInner::TlaTlDataIndBl(prim) = &mut message.msg else {
-            panic!()
+        let (main_address, link_id, endpoint_id, sdu) = match &mut message.msg {
+            SapMsgInner::TlaTlDataIndBl(prim) => (prim.main_address, prim.link_id, prim.endpoint_id, prim.tl_sdu.take()),
+            SapMsgInner::TlaTlDataIndAl(prim) => (prim.main_address, prim.link_id, prim.endpoint_id, prim.tl_sdu.take()),
+            _ => panic!(),
         };
-        let Some(mut sdu) = prim.tl_sdu.take() else { panic!("no tl_sdu") };
+        let Some(mut sdu) = sdu else { panic!("no tl_sdu") };
         assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
         let Some(bits) = sdu.read_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
@@ -125,65 +141,31 @@ impl MleBs {
         // Dispatch to appropriate component (or to self if for MLE)
         match pdu_type {
             MleProtocolDiscriminator::Mm => {
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-                let m = LmmMleUnitdataInd {
-                    sdu,
-                    handle,
-                    received_address: prim.main_address,
-                };
-                let msg = SapMsg {
-                    sap: Sap::LmmSap,
-                    src: TetraEntity::Mle,
-                    dest: TetraEntity::Mm,
-                    dltime: message.dltime,
-                    msg: SapMsgInner::LmmMleUnitdataInd(m),
-                };
-                queue.push_back(msg);
+                self.deliver_to_mm(queue, message.dltime, main_address, link_id, endpoint_id, sdu);
             }
             MleProtocolDiscriminator::Cmce => {
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-                let m = LcmcMleUnitdataInd {
-                    sdu,
-                    handle,
-                    received_tetra_address: prim.main_address,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
-                    chan_change_resp_req: false, // TODO FIXME
-                    chan_change_handle: None,    // TODO FIXME
-                };
-                let msg = SapMsg {
-                    sap: Sap::LcmcSap,
-                    src: TetraEntity::Mle,
-                    dest: TetraEntity::Cmce,
-                    dltime: message.dltime,
-                    msg: SapMsgInner::LcmcMleUnitdataInd(m),
-                };
-                queue.push_back(msg);
+                self.deliver_to_cmce(queue, message.dltime, main_address, link_id, endpoint_id, sdu);
             }
             MleProtocolDiscriminator::Sndcp => {
                 let m = LtpdMleUnitdataInd {
                     sdu,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
-                    received_tetra_address: prim.main_address,
+                    endpoint_id,
+                    link_id,
+                    received_tetra_address: main_address,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
                 };
                 let msg = SapMsg {
-                    sap: Sap::LcmcSap,
+                    sap: Sap::TlpdSap,
                     src: TetraEntity::Mle,
-                    dest: TetraEntity::Cmce,
+                    dest: TetraEntity::Sndcp,
                     dltime: message.dltime,
                     msg: SapMsgInner::LtpdMleUnitdataInd(m),
                 };
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
-                self.rx_tla_mle_pdu(queue, message);
+                self.rx_tla_mle_pdu(queue, message.dltime, main_address, link_id, endpoint_id, sdu);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
                 unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
@@ -191,99 +173,99 @@ impl MleBs {
         }
     }
 
-    // fn rx_tla_unitdata_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
-    //     // TODO FIXME NOTE: This function is the same as the rx_tla_data_ind_bl.
-    //     // A cursory glance at the spec does not make clear the difference, except for the relation with
-    //     // either udata or data at the llc.
-    //     // It seems only the SNDCP uses unacknowledged TL-UNITDATA.
-    //     // We should investigate the exact differences and account for them
-
-    //     // Take ownership of bitbuf and read protocol discriminator
-    //     let SapMsgInner::TlaTlUnitdataIndBl(prim) = &mut message.msg else {
-    //         panic!()
-    //     };
-    //     let Some(mut sdu) = prim.tl_sdu.take() else { panic!("no tl_sdu") };
-    //     assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
-
-    //     let Some(bits) = sdu.read_bits(3) else {
-    //         tracing::warn!("insufficient bits: {}", sdu.dump_bin());
-    //         return;
-    //     };
-    //     let Ok(pdu_type) = MleProtocolDiscriminator::try_from(bits) else {
-    //         tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
-    //         return;
-    //     };
-
-    //     // Dispatch to appropriate component (or to self if for MLE)
-    //     match pdu_type {
-    //         MleProtocolDiscriminator::Mm => {
-    //             tracing::warn!("TM-UNITDATA for MM?"); // todo fixme find if ever used
-    //             let handle = self
-    //                 .router
-    //                 .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-    //             let m = LmmMleUnitdataInd {
-    //                 sdu,
-    //                 handle,
-    //                 received_address: prim.main_address,
-    //             };
-    //             let msg = SapMsg {
-    //                 sap: Sap::LmmSap,
-    //                 src: TetraEntity::Mle,
-    //                 dest: TetraEntity::Mm,
-    //                 dltime: message.dltime,
-    //                 msg: SapMsgInner::LmmMleUnitdataInd(m),
-    //             };
-    //             queue.push_back(msg);
-    //         }
-    //         MleProtocolDiscriminator::Cmce => {
-    //             tracing::warn!("TM-UNITDATA for MM?"); // todo fixme find if ever used
-    //             let handle = self
-    //                 .router
-    //                 .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-    //             let m = LcmcMleUnitdataInd {
-    //                 sdu,
-    //                 handle,
-    //                 endpoint_id: prim.endpoint_id,
-    //                 link_id: prim.link_id,
-    //                 received_tetra_address: prim.main_address,
-    //                 chan_change_resp_req: false, // TODO FIXME
-    //                 chan_change_handle: None,    // TODO FIXME
-    //             };
-    //             let msg = SapMsg {
-    //                 sap: Sap::LcmcSap,
-    //                 src: TetraEntity::Mle,
-    //                 dest: TetraEntity::Cmce,
-    //                 dltime: message.dltime,
-    //                 msg: SapMsgInner::LcmcMleUnitdataInd(m),
-    //             };
-    //             queue.push_back(msg);
-    //         }
-    //         MleProtocolDiscriminator::Sndcp => {
-    //             let m = LtpdMleUnitdataInd {
-    //                 sdu,
-    //                 endpoint_id: prim.endpoint_id,
-    //                 link_id: prim.link_id,
-    //                 received_tetra_address: prim.main_address,
-    //                 chan_change_resp_req: false, // TODO FIXME
-    //                 chan_change_handle: None,    // TODO FIXME
-    //             };
-    //             let msg = SapMsg {
-    //                 sap: Sap::LcmcSap,
-    //                 src: TetraEntity::Mle,
-    //                 dest: TetraEntity::Cmce,
-    //                 dltime: message.dltime,
-    //                 msg: SapMsgInner::LtpdMleUnitdataInd(m),
-    //             };
-    //             queue.push_back(msg);
-    //         }
-    //         MleProtocolDiscriminator::Mle => {
-    //             self.rx_tla_mle_pdu(queue, message);
-    //         }
-    //         MleProtocolDiscriminator::TetraManagementEntity => {
-    //             unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
-    //         }
-    //     }
-    // }
+    fn deliver_to_mm(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        main_address: TetraAddress,
+        link_id: LinkId,
+        endpoint_id: EndpointId,
+        sdu: BitBuffer,
+    ) {
+        let handle = self.router.create_handle(main_address, link_id, endpoint_id, dltime);
+        let m = LmmMleUnitdataInd {
+            sdu,
+            handle,
+            received_address: main_address,
+        };
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Mm,
+            dltime,
+            msg: SapMsgInner::LmmMleUnitdataInd(m),
+        };
+        queue.push_back(msg);
+    }
+
+    fn deliver_to_cmce(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        main_address: TetraAddress,
+        link_id: LinkId,
+        endpoint_id: EndpointId,
+        sdu: BitBuffer,
+    ) {
+        let handle = self.router.create_handle(main_address, link_id, endpoint_id, dltime);
+        let m = LcmcMleUnitdataInd {
+            sdu,
+            handle,
+            received_tetra_address: main_address,
+            endpoint_id,
+            link_id,
+            chan_change_resp_req: false, // TODO FIXME
+            chan_change_handle: None,    // TODO FIXME
+            chan_alloc: None,
+        };
+        let msg = SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Cmce,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataInd(m),
+        };
+        queue.push_back(msg);
+    }
+
+    /// Handles TL-UNITDATA, received over the unacknowledged basic or advanced link. Only SNDCP uses this service.
+    fn rx_tla_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let (main_address, link_id, endpoint_id, sdu) = match &mut message.msg {
+            SapMsgInner::TlaTlUnitdataIndBl(prim) => (prim.main_address, prim.link_id, prim.endpoint_id, prim.tl_sdu.take()),
+            SapMsgInner::TlaTlUnitdataIndAl(prim) => (prim.main_address, prim.link_id, prim.endpoint_id, prim.tl_sdu.take()),
+            _ => panic!(),
+        };
+        let Some(mut sdu) = sdu else { panic!("no tl_sdu") };
+        assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
+        let Some(bits) = sdu.read_bits(3) else {
+            tracing::warn!("insufficient bits: {}", sdu.dump_bin());
+            return;
+        };
+        let Ok(pdu_type) = MleProtocolDiscriminator::try_from(bits) else {
+            tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
+            return;
+        };
+        if pdu_type != MleProtocolDiscriminator::Sndcp {
+            tracing::warn!("unexpected TL-UNITDATA for {}, dropping", pdu_type);
+            return;
+        }
+
+        let m = LtpdMleUnitdataInd {
+            sdu,
+            endpoint_id,
+            link_id,
+            received_tetra_address: main_address,
+            chan_change_resp_req: false, // TODO FIXME
+            chan_change_handle: None,    // TODO FIXME
+        };
+        queue.push_back(SapMsg {
+            sap: Sap::TlpdSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Sndcp,
+            dltime: message.dltime,
+            msg: SapMsgInner::LtpdMleUnitdataInd(m),
+        });
+    }
 
     fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
         tracing::trace!("rx_tlmc_prim");
@@ -301,12 +283,19 @@ impl MleBs {
             panic!()
         };
 
-        let mle_prot_discriminator = MleProtocolDiscriminator::Mm;
-        let sdu_len = prim.sdu.get_len();
-        let mut pdu = BitBuffer::new(3 + sdu_len);
-        pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
-        pdu.copy_bits(&mut prim.sdu, sdu_len);
-        pdu.seek(0);
+        // The response to a forward registration goes back inside D-NEW-CELL or D-PREPARE-FAIL
+        let pdu = match self.cell_change.wrap_mm_pdu(prim.address, &prim.sdu) {
+            Some(pdu) => pdu,
+            None => {
+                let mle_prot_discriminator = MleProtocolDiscriminator::Mm;
+                let sdu_len = prim.sdu.get_len();
+                let mut pdu = BitBuffer::new(3 + sdu_len);
+                pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
+                pdu.copy_bits(&mut prim.sdu, sdu_len);
+                pdu.seek(0);
+                pdu
+            }
+        };
 
         assert!(prim.layer2service != Layer2Service::Unacknowledged, "not implemented");
 
@@ -347,14 +336,144 @@ impl MleBs {
         }
     }
 
-    fn rx_tlpd_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_ltpd_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_ltpd_mle_unitdata_req");
+        let SapMsgInner::LtpdMleUnitdataReq(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let mle_prot_discriminator = MleProtocolDiscriminator::Sndcp;
+        let sdu_len = prim.sdu.get_len();
+        let mut pdu = BitBuffer::new(3 + sdu_len);
+        pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
+        pdu.copy_bits(&mut prim.sdu, sdu_len);
+        pdu.seek(0);
+
+        // TL-SDUs beyond the basic link maximum (N.251) go over the advanced link, if the cell offers it
+        if self.config.config().cell.advanced_link && pdu.get_len() > N251_BL_MAX_TLSDU_LEN_BITS as usize {
+            let unacknowledged = prim.layer2service == Layer2Service::Unacknowledged;
+            Self::tx_tl_sdu_al(
+                queue,
+                message.dltime,
+                prim.main_address,
+                prim.link_id,
+                prim.endpoint_id,
+                unacknowledged,
+                pdu,
+            );
+            return;
+        }
+
+        let sapmsg = if prim.layer2service == Layer2Service::Unacknowledged {
+            SapMsg {
+                sap: Sap::TlaSap,
+                src: TetraEntity::Mle,
+                dest: TetraEntity::Llc,
+                dltime: message.dltime,
+                msg: SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
+                    main_address: prim.main_address,
+                    link_id: prim.link_id,
+                    endpoint_id: prim.endpoint_id,
+                    tl_sdu: pdu,
+                    stealing_permission: prim.stealing_permission,
+                    subscriber_class: 0, // TODO fixme
+                    fcs_flag: prim.fcs_flag,
+                    air_interface_encryption: None,
+                    packet_data_flag: prim.packet_data_flag,
+                    n_tlsdu_repeats: 0,
+                    data_class_info: None,
+                    req_handle: 0,
+                    chan_alloc: None,
+                    tx_reporter: None,
+                }),
+            }
+        } else {
+            SapMsg {
+                sap: Sap::TlaSap,
+                src: TetraEntity::Mle,
+                dest: TetraEntity::Llc,
+                dltime: message.dltime,
+                msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+                    main_address: prim.main_address,
+                    link_id: prim.link_id,
+                    endpoint_id: prim.endpoint_id,
+                    tl_sdu: pdu,
+                    stealing_permission: prim.stealing_permission,
+                    subscriber_class: 0, // TODO fixme
+                    fcs_flag: prim.fcs_flag,
+                    air_interface_encryption: None,
+                    stealing_repeats_flag: None,
+                    data_class_info: None,
+                    req_handle: 0, // TODO FIXME
+                    graceful_degradation: None,
+                    chan_alloc: None,
+                    tx_reporter: None,
+                }),
+            }
+        };
+        queue.push_back(sapmsg);
+    }
+
+    /// Sends a TL-SDU over the advanced link. For the acknowledged service, the link is set up first if needed;
+    /// the LLC holds the TL-SDU until the set-up completes.
+    fn tx_tl_sdu_al(
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        main_address: TetraAddress,
+        link_id: LinkId,
+        endpoint_id: EndpointId,
+        unacknowledged: bool,
+        tl_sdu: BitBuffer,
+    ) {
+        let msg = if unacknowledged {
+            SapMsgInner::TlaTlUnitdataReqAl(TlaTlUnitdataReqAl {
+                main_address,
+                link_id,
+                endpoint_id,
+                tl_sdu,
+                req_handle: 0,
+                tx_reporter: None,
+            })
+        } else {
+            queue.push_back(SapMsg {
+                sap: Sap::TlaSap,
+                src: TetraEntity::Mle,
+                dest: TetraEntity::Llc,
+                dltime,
+                msg: SapMsgInner::TlaTlConnectReq(TlaTlConnectReq {
+                    main_address,
+                    link_id,
+                    endpoint_id,
+                    unacknowledged: false,
+                    req_handle: 0,
+                }),
+            });
+            SapMsgInner::TlaTlDataReqAl(TlaTlDataReqAl {
+                main_address,
+                link_id,
+                endpoint_id,
+                tl_sdu,
+                req_handle: 0, // TODO FIXME
+                tx_reporter: None,
+            })
+        };
+        queue.push_back(SapMsg {
+            sap: Sap::TlaSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Llc,
+            dltime,
+            msg,
+        });
+    }
+
+    fn rx_tlpd_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlpd_prim");
-        unimplemented!("rx_tlpd_prim");
-        // match &message.msg {
-        //     _ => {
-        //         panic!();
-        //     }
-        // }
+        match &message.msg {
+            SapMsgInner::LtpdMleUnitdataReq(_) => {
+                self.rx_ltpd_mle_unitdata_req(queue, message);
+            }
+            _ => panic!(),
+        }
     }
 
     fn rx_lcmc_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -363,12 +482,19 @@ impl MleBs {
             panic!()
         };
 
-        let mle_prot_discriminator = MleProtocolDiscriminator::Cmce;
-        let sdu_len = prim.sdu.get_len();
-        let mut pdu = BitBuffer::new(3 + sdu_len);
-        pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
-        pdu.copy_bits(&mut prim.sdu, sdu_len);
-        pdu.seek(0);
+        // The response to a call restoration goes back inside D-RESTORE-ACK or D-RESTORE-FAIL
+        let pdu = match self.cell_change.wrap_cmce_pdu(prim.main_address, &prim.sdu) {
+            Some(pdu) => pdu,
+            None => {
+                let mle_prot_discriminator = MleProtocolDiscriminator::Cmce;
+                let sdu_len = prim.sdu.get_len();
+                let mut pdu = BitBuffer::new(3 + sdu_len);
+                pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
+                pdu.copy_bits(&mut prim.sdu, sdu_len);
+                pdu.seek(0);
+                pdu
+            }
+        };
 
         // let (_addr, link, endpoint) = self.router.use_handle(prim.handle, message.dltime);
         // assert_eq!(link, prim.link_id);
@@ -447,7 +573,9 @@ impl TetraEntityTrait for MleBs {
     }
 
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
-        // Broadcast D-NWRK-BROADCAST once per hyperframe if timezone is configured.
+        self.cell_change.tick_start(queue, ts);
+
+        // Broadcast D-NWRK-BROADCAST once per hyperframe if timezone or neighbour cells are configured.
         // Use a constant multiframe/frame offset to avoid congestion with other
         // hyperframe-triggered events.
         if ts.m == MLE_BROADCAST_MULTIFRAME && ts.f == MLE_BROADCAST_FRAME && ts.t == 1 {
@@ -1,24 +1,35 @@
 use crate::mle::components::mle_router::MleRouter;
-use crate::mle::components::network_time::encode_tetra_network_time;
-use crate::{MessageQueue, TetraEntityTrait};
+use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, unimplemented_log};
+use tetra_core::{BitBuffer, Sap, unimplemented_log};
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
-use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::lmm::{LmmMleActivateConf, LmmMleUnitdataInd};
 use tetra_saps::ltpd::LtpdMleUnitdataInd;
 use tetra_saps::tla::TlaTlDataReqBl;
+use tetra_saps::tlmc::{TlmcConfigureReq, TlmcValidAddress};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
 use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
 use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
-use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+
+/// Serving cell, as learned from the broadcast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ServingCell {
+    mcc: u16,
+    mnc: u16,
+    /// Location area, known once a D-MLE-SYSINFO was received
+    la: Option<u16>,
+}
 
 pub struct MleMs {
     config: SharedConfig,
     router: MleRouter,
+
+    /// Cell we are camped on, if any
+    cell: Option<ServingCell>,
 }
 
 impl MleMs {
@@ -26,6 +37,7 @@ impl MleMs {
         Self {
             config,
             router: MleRouter::new(),
+            cell: None,
         }
     }
 
@@ -36,6 +48,7 @@ impl MleMs {
         let tm_sdu = {
             match message.msg {
                 SapMsgInner::TlaTlDataIndBl(prim) => prim.tl_sdu,
+                SapMsgInner::TlaTlDataIndAl(prim) => prim.tl_sdu,
                 _ => {
                     panic!();
                 }
@@ -87,11 +100,19 @@ impl MleMs {
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_prim");
         match message.msg {
-            SapMsgInner::TlaTlDataIndBl(_) => {
-                self.rx_tla_data_ind_bl(queue, message);
+            SapMsgInner::TlaTlDataIndBl(_) | SapMsgInner::TlaTlDataIndAl(_) => {
+                self.rx_tla_data_ind(queue, message);
+            }
+            SapMsgInner::TlaTlUnitdataIndBl(_) | SapMsgInner::TlaTlUnitdataIndAl(_) => {
+                self.rx_tla_unitdata_ind(queue, message);
             }
-            SapMsgInner::TlaTlUnitdataIndBl(_) => {
-                self.rx_tla_unitdata_ind_bl(queue, message);
+            SapMsgInner::TlaTlConnectInd(_)
+            | SapMsgInner::TlaTlConnectConf(_)
+            | SapMsgInner::TlaTlDisconnectInd(_)
+            | SapMsgInner::TlaTlDisconnectConf(_)
+            | SapMsgInner::TlaTlReconnectConf(_) => {
+                // The LLC manages the advanced links by itself, the outcome of transfers is tracked with the TxReporter
+                tracing::debug!("advanced link: {:?}", message.msg);
             }
             _ => {
                 panic!();
@@ -99,12 +120,20 @@ impl MleMs {
         }
     }
 
-    fn rx_tla_data_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+    fn rx_tla_data_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         // Take ownership of bitbuf and read protocol discriminator
-        let SapMsgInner::TlaTlDataIndBl(prim) = &mut message.msg else {
-            panic!()
+        let (main_address, link_id, endpoint_id, sdu, chan_info) = match &mut message.msg {
+            SapMsgInner::TlaTlDataIndBl(prim) => (
+                prim.main_address,
+                prim.link_id,
+                prim.endpoint_id,
+                prim.tl_sdu.take(),
+                prim.chan_info.take(),
+            ),
+            SapMsgInner::TlaTlDataIndAl(prim) => (prim.main_address, prim.link_id, prim.endpoint_id, prim.tl_sdu.take(), None),
+            _ => panic!(),
         };
-        let Some(mut sdu) = prim.tl_sdu.take() else { panic!("no tl_sdu") };
+        let Some(mut sdu) = sdu else { panic!("no tl_sdu") };
         assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
         let Some(bits) = sdu.read_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
@@ -118,17 +147,15 @@ impl MleMs {
         // Dispatch to appropriate component (or to self if for MLE)
         match pdu_type {
             MleProtocolDiscriminator::Mm => {
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
+                let handle = self.router.create_handle(main_address, link_id, endpoint_id, message.dltime);
                 let m = LmmMleUnitdataInd {
                     sdu,
                     handle,
-                    received_address: prim.main_address,
+                    received_address: main_address,
                 };
                 let msg = SapMsg {
                     sap: Sap::LmmSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Mm,
                     dltime: message.dltime,
                     msg: SapMsgInner::LmmMleUnitdataInd(m),
@@ -136,21 +163,20 @@ impl MleMs {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Cmce => {
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
+                let handle = self.router.create_handle(main_address, link_id, endpoint_id, message.dltime);
                 let m = LcmcMleUnitdataInd {
                     sdu,
                     handle,
-                    received_tetra_address: prim.main_address,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
+                    received_tetra_address: main_address,
+                    endpoint_id,
+                    link_id,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
+                    chan_alloc: chan_info,
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Cmce,
                     dltime: message.dltime,
                     msg: SapMsgInner::LcmcMleUnitdataInd(m),
@@ -160,16 +186,16 @@ impl MleMs {
             MleProtocolDiscriminator::Sndcp => {
                 let m = LtpdMleUnitdataInd {
                     sdu,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
-                    received_tetra_address: prim.main_address,
+                    endpoint_id,
+                    link_id,
+                    received_tetra_address: main_address,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
                 };
                 let msg = SapMsg {
-                    sap: Sap::LcmcSap,
-                    src: self.self_component,
-                    dest: TetraEntity::Cmce,
+                    sap: Sap::TlpdSap,
+                    src: TetraEntity::Mle,
+                    dest: TetraEntity::Sndcp,
                     dltime: message.dltime,
                     msg: SapMsgInner::LtpdMleUnitdataInd(m),
                 };
@@ -184,18 +210,26 @@ impl MleMs {
         }
     }
 
-    fn rx_tla_unitdata_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
-        // TODO FIXME NOTE: This function is the same as the rx_tla_data_ind_bl.
+    fn rx_tla_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        // TODO FIXME NOTE: This function is the same as the rx_tla_data_ind.
         // A cursory glance at the spec does not make clear the difference, except for the relation with
         // either udata or data at the llc.
         // It seems only the SNDCP uses unacknowledged TL-UNITDATA.
         // We should investigate the exact differences and account for them
 
         // Take ownership of bitbuf and read protocol discriminator
-        let SapMsgInner::TlaTlUnitdataIndBl(prim) = &mut message.msg else {
-            panic!()
+        let (main_address, link_id, endpoint_id, sdu, chan_info) = match &mut message.msg {
+            SapMsgInner::TlaTlUnitdataIndBl(prim) => (
+                prim.main_address,
+                prim.link_id,
+                prim.endpoint_id,
+                prim.tl_sdu.take(),
+                prim.chan_info.take(),
+            ),
+            SapMsgInner::TlaTlUnitdataIndAl(prim) => (prim.main_address, prim.link_id, prim.endpoint_id, prim.tl_sdu.take(), None),
+            _ => panic!(),
         };
-        let Some(mut sdu) = prim.tl_sdu.take() else { panic!("no tl_sdu") };
+        let Some(mut sdu) = sdu else { panic!("no tl_sdu") };
         assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         let Some(bits) = sdu.read_bits(3) else {
@@ -211,17 +245,15 @@ impl MleMs {
         match pdu_type {
             MleProtocolDiscriminator::Mm => {
                 tracing::warn!("TM-UNITDATA for MM?"); // todo fixme find if ever used
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
+                let handle = self.router.create_handle(main_address, link_id, endpoint_id, message.dltime);
                 let m = LmmMleUnitdataInd {
                     sdu,
                     handle,
-                    received_address: prim.main_address,
+                    received_address: main_address,
                 };
                 let msg = SapMsg {
                     sap: Sap::LmmSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Mm,
                     dltime: message.dltime,
                     msg: SapMsgInner::LmmMleUnitdataInd(m),
@@ -229,22 +261,21 @@ impl MleMs {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Cmce => {
-                tracing::warn!("TM-UNITDATA for MM?"); // todo fixme find if ever used
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
+                tracing::warn!("TM-UNITDATA for CMCE?"); // todo fixme find if ever used
+                let handle = self.router.create_handle(main_address, link_id, endpoint_id, message.dltime);
                 let m = LcmcMleUnitdataInd {
                     sdu,
                     handle,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
-                    received_tetra_address: prim.main_address,
+                    endpoint_id,
+                    link_id,
+                    received_tetra_address: main_address,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
+                    chan_alloc: chan_info,
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Cmce,
                     dltime: message.dltime,
                     msg: SapMsgInner::LcmcMleUnitdataInd(m),
@@ -254,16 +285,16 @@ impl MleMs {
             MleProtocolDiscriminator::Sndcp => {
                 let m = LtpdMleUnitdataInd {
                     sdu,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
-                    received_tetra_address: prim.main_address,
+                    endpoint_id,
+                    link_id,
+                    received_tetra_address: main_address,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
                 };
                 let msg = SapMsg {
-                    sap: Sap::LcmcSap,
-                    src: self.self_component,
-                    dest: TetraEntity::Cmce,
+                    sap: Sap::TlpdSap,
+                    src: TetraEntity::Mle,
+                    dest: TetraEntity::Sndcp,
                     dltime: message.dltime,
                     msg: SapMsgInner::LtpdMleUnitdataInd(m),
                 };
@@ -293,7 +324,7 @@ impl MleMs {
         }
     }
 
-    pub fn rx_tlmb_tl_sysinfo_ind(&self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tlmb_tl_sysinfo_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tlmb_tl_sysinfo_ind");
 
         let SapMsgInner::TlmbSysinfoInd(inner) = &mut message.msg else {
@@ -301,9 +332,9 @@ impl MleMs {
         };
 
         // Parse the TL-SDU
-        let _pdu = match DMleSysinfo::from_bitbuf(&mut inner.tl_sdu) {
+        let pdu = match DMleSysinfo::from_bitbuf(&mut inner.tl_sdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::trace!("<- {:?}", pdu);
                 pdu
             }
             Err(e) => {
@@ -312,47 +343,42 @@ impl MleMs {
             }
         };
 
-        unimplemented_log!("rx_tlmb_tl_sysinfo_ind");
-        // let need_global_state_update = {
-        //     let cfg = self.config.read();
-
-        //     pdu.location_area != cfg.la_info.location_area
-        //     || pdu.subscriber_class != cfg.la_info.subscriber_class
-        //     || pdu.bs_service_details.registration != cfg.la_info.registration
-        //     || pdu.bs_service_details.deregistration != cfg.la_info.deregistration
-        //     || pdu.bs_service_details.priority_cell != cfg.la_info.priority_cell
-        //     || pdu.bs_service_details.no_minimum_mode != cfg.la_info.no_minimum_mode
-        //     || pdu.bs_service_details.migration != cfg.la_info.migration
-        //     || pdu.bs_service_details.system_wide_services != cfg.la_info.system_wide_services
-        //     || pdu.bs_service_details.voice_service != cfg.la_info.voice_service
-        //     || pdu.bs_service_details.circuit_mode_data_service != cfg.la_info.circuit_mode_data_service
-        //     || pdu.bs_service_details.sndcp_service != cfg.la_info.sndcp_service
-        //     || pdu.bs_service_details.aie_service != cfg.la_info.aie_service
-        //     || pdu.bs_service_details.advanced_link != cfg.la_info.advanced_link
-        // };
-
-        // if need_global_state_update {
-        //     let mut cfg = self.config.write();
-        //     cfg.la_info.location_area = pdu.location_area;
-        //     cfg.la_info.subscriber_class = pdu.subscriber_class;
-        //     cfg.la_info.registration = pdu.bs_service_details.registration;
-        //     cfg.la_info.deregistration = pdu.bs_service_details.deregistration;
-        //     cfg.la_info.priority_cell = pdu.bs_service_details.priority_cell;
-        //     cfg.la_info.no_minimum_mode = pdu.bs_service_details.no_minimum_mode;
-        //     cfg.la_info.migration = pdu.bs_service_details.migration;
-        //     cfg.la_info.system_wide_services = pdu.bs_service_details.system_wide_services;
-        //     cfg.la_info.voice_service = pdu.bs_service_details.voice_service;
-        //     cfg.la_info.circuit_mode_data_service = pdu.bs_service_details.circuit_mode_data_service;
-        //     cfg.la_info.sndcp_service = pdu.bs_service_details.sndcp_service;
-        //     cfg.la_info.aie_service = pdu.bs_service_details.aie_service;
-        //     cfg.la_info.advanced_link = pdu.bs_service_details.advanced_link;
-        //     tracing::info!("Updated TetraGlobalState: {:?}", pdu);
-        // } else {
-        //     tracing::trace!("rx_tlmb_tl_sysinfo_ind: TetraGlobalState update not required");
-        // }
+        // Without a D-MLE-SYNC we don't know which network this cell belongs to
+        let Some(cell) = self.cell.as_mut() else {
+            tracing::debug!("rx_tlmb_tl_sysinfo_ind: no D-MLE-SYNC received yet");
+            return;
+        };
+        if cell.la == Some(pdu.location_area) {
+            return;
+        }
+
+        // Cell selected or location area changed, MM decides whether to (re-)register
+        tracing::info!(
+            "Camped on cell MCC {} MNC {} LA {}, registration {}",
+            cell.mcc,
+            cell.mnc,
+            pdu.location_area,
+            if pdu.bs_service_details.registration {
+                "required"
+            } else {
+                "not required"
+            }
+        );
+        cell.la = Some(pdu.location_area);
+        queue.push_back(SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Mm,
+            dltime: message.dltime,
+            msg: SapMsgInner::LmmMleActivateConf(LmmMleActivateConf {
+                registration_required: pdu.bs_service_details.registration,
+                la: pdu.location_area,
+                cell_type: 0, // TODO FIXME
+            }),
+        });
     }
 
-    pub fn rx_tlmb_tl_sync_ind(&self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tlmb_tl_sync_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tlmb_tl_sync_ind");
 
         let SapMsgInner::TlmbSyncInd(inner) = &mut message.msg else {
@@ -360,9 +386,9 @@ impl MleMs {
         };
 
         // Parse the TL-SDU
-        let _pdu = match DMleSync::from_bitbuf(&mut inner.tl_sdu) {
+        let pdu = match DMleSync::from_bitbuf(&mut inner.tl_sdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::trace!("<- {:?}", pdu);
                 pdu
             }
             Err(e) => {
@@ -371,57 +397,51 @@ impl MleMs {
             }
         };
 
-        unimplemented_log!("rx_tlmb_tl_sync_ind");
-
-        // let need_global_state_update = {
-        //     let cfg = self.config.read();
-        //         pdu.mcc  != cfg.la_info.mcc
-        //         || pdu.mnc  != cfg.la_info.mnc
-        //         || pdu.neighbor_cell_broadcast != cfg.la_info.neighbor_cell_broadcast
-        //         || pdu.cell_load_ca            != cfg.la_info.cell_load_ca
-        //         || pdu.late_entry_supported    != cfg.la_info.late_entry_supported
-        // };
-
-        // // Update global state if needed
-        // if need_global_state_update {
-        //     let mut cfg = self.config.write();
-        //     cfg.la_info.mcc                    = pdu.mcc;
-        //     cfg.la_info.mnc                    = pdu.mnc;
-        //     cfg.la_info.neighbor_cell_broadcast = pdu.neighbor_cell_broadcast;
-        //     cfg.la_info.cell_load_ca           = pdu.cell_load_ca;
-        //     cfg.la_info.late_entry_supported   = pdu.late_entry_supported;
-        //     tracing::info!("Updated TetraGlobalState: {:?}", pdu);
-
-        //     // TODO FIXME: This is ugly. We should pass the message through all the intermediate layers
-        //     let m = SapMsg {
-        //         sap: Sap::TlmcSap,
-        //         src: self.self_component,
-        //         dest: TetraComponent::Umac,
-        //         t_submit: message.t_submit,
-        //         msg: SapMsgInner::TlmcConfigureReq(
-        //             TlmcConfigureReq{
-        //                 valid_addresses: Some(TlmcValidAddress {
-        //                     mcc: cfg.la_info.mcc,
-        //                     mnc: cfg.la_info.mnc,
-        //                 }),
-        //                 ..Default::default()
-        //             }
-        //         )
-        //     };
-        //     queue.push_back(m);
-        // } else {
-        //     tracing::trace!("rx_tlmb_tl_sysinfo_ind: TetraGlobalState update not required");
-        // }
+        if self.cell.is_some_and(|c| c.mcc == pdu.mcc && c.mnc == pdu.mnc) {
+            return;
+        }
+
+        {
+            let cfg = self.config.config();
+            if pdu.mcc != cfg.net.mcc || pdu.mnc != cfg.net.mnc {
+                tracing::warn!(
+                    "Cell belongs to MCC {} MNC {}, we are configured for MCC {} MNC {}",
+                    pdu.mcc,
+                    pdu.mnc,
+                    cfg.net.mcc,
+                    cfg.net.mnc
+                );
+            }
+        }
+
+        tracing::info!("Synchronized to MCC {} MNC {}", pdu.mcc, pdu.mnc);
+        self.cell = Some(ServingCell {
+            mcc: pdu.mcc,
+            mnc: pdu.mnc,
+            la: None,
+        });
+
+        // The MAC needs the MCC and MNC for the scrambling code before it can decode anything beyond
+        // the BSCH. Deliver immediately, so the remaining blocks of this very burst are decoded properly.
+        let m = SapMsg {
+            sap: Sap::TlmcSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Umac,
+            dltime: message.dltime,
+            msg: SapMsgInner::TlmcConfigureReq(TlmcConfigureReq {
+                valid_addresses: Some(TlmcValidAddress {
+                    mcc: pdu.mcc,
+                    mnc: pdu.mnc,
+                }),
+                ..Default::default()
+            }),
+        };
+        queue.push_prio(m, MessagePrio::Immediate);
     }
 
-    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlmc_prim");
-        unimplemented!("rx_tlmc_prim");
-        // match &message.msg {
-        //     _ => {
-        //         panic!();
-        //     }
-        // }
+        unimplemented_log!("rx_tlmc_prim: {:?}", message.msg);
     }
 
     fn rx_lmm_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -441,7 +461,7 @@ impl MleMs {
         // assert_eq!(addr.ssi, prim.address.ssi);
         let sapmsg = SapMsg {
             sap: Sap::TlaSap,
-            src: self.self_component,
+            src: TetraEntity::Mle,
             dest: TetraEntity::Llc,
             dltime: message.dltime,
             msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
@@ -474,14 +494,9 @@ impl MleMs {
         }
     }
 
-    fn rx_tlpd_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlpd_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlpd_prim");
-        unimplemented!("rx_tlpd_prim");
-        // match &message.msg {
-        //     _ => {
-        //         panic!();
-        //     }
-        // }
+        unimplemented_log!("rx_tlpd_prim: {:?}", message.msg);
     }
 
     fn rx_lcmc_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -505,7 +520,7 @@ impl MleMs {
 
         let sapmsg = SapMsg {
             sap: Sap::TlaSap,
-            src: self.self_component,
+            src: TetraEntity::Mle,
             dest: TetraEntity::Llc,
             dltime: message.dltime,
             msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
@@ -544,6 +559,10 @@ impl TetraEntityTrait for MleMs {
         TetraEntity::Mle
     }
 
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
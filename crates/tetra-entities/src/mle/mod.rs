@@ -1,3 +1,4 @@
 pub mod components;
 
 pub mod mle_bs;
+pub mod mle_ms;
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use tetra_core::TdmaTime;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+
+/// Authentication key K, session authentication keys KS/KS' are also 128 bits
+pub type AuthKey = [u8; 16];
+
+/// Time an MS gets to answer a D-AUTHENTICATION DEMAND, in timeslots (~30 seconds)
+pub const AUTH_RESPONSE_TIMEOUT: i32 = 2118;
+
+/// The TAA1-style algorithm set used for air-interface authentication (EN 300 392-7 clause 4.4).
+/// Random challenges and seeds are 80 bits, carried in the low bits of a u128.
+/// TAA1 itself is only available under NDA, so it is plugged in through this trait.
+pub trait AuthAlgorithm: Send {
+    /// TA11: derive session authentication key KS from K and random seed RS
+    fn ta11(&self, k: &AuthKey, rs: u128) -> AuthKey;
+    /// TA12: compute response RES1 and derived cipher key DCK1 (80 bits) from KS and challenge RAND1
+    fn ta12(&self, ks: &AuthKey, rand1: u128) -> (u32, u128);
+    /// TA21: derive session authentication key KS' from K and random seed RS
+    fn ta21(&self, k: &AuthKey, rs: u128) -> AuthKey;
+    /// TA22: compute response RES2 from KS' and challenge RAND2
+    fn ta22(&self, ks_: &AuthKey, rand2: u128) -> u32;
+}
+
+/// Non-standard stand-in for TAA1, based on MD5. Follows the TA11/TA12/TA21/TA22 structure,
+/// so test radios or simulators implementing the same functions can authenticate against us.
+/// Provides no real security and will not interoperate with TAA1 radios.
+pub struct TestAuthAlgorithm;
+
+impl TestAuthAlgorithm {
+    fn digest(label: &[u8], key: &AuthKey, random: u128) -> [u8; 16] {
+        let mut input = Vec::with_capacity(label.len() + 16 + 10);
+        input.extend_from_slice(label);
+        input.extend_from_slice(key);
+        input.extend_from_slice(&random.to_be_bytes()[6..]);
+        md5::compute(&input).0
+    }
+}
+
+impl AuthAlgorithm for TestAuthAlgorithm {
+    fn ta11(&self, k: &AuthKey, rs: u128) -> AuthKey {
+        Self::digest(b"TA11", k, rs)
+    }
+
+    fn ta12(&self, ks: &AuthKey, rand1: u128) -> (u32, u128) {
+        let d = Self::digest(b"TA12", ks, rand1);
+        let res1 = u32::from_be_bytes([d[0], d[1], d[2], d[3]]);
+        let mut dck1 = [0u8; 16];
+        dck1[6..].copy_from_slice(&d[4..14]);
+        (res1, u128::from_be_bytes(dck1))
+    }
+
+    fn ta21(&self, k: &AuthKey, rs: u128) -> AuthKey {
+        Self::digest(b"TA21", k, rs)
+    }
+
+    fn ta22(&self, ks_: &AuthKey, rand2: u128) -> u32 {
+        let d = Self::digest(b"TA22", ks_, rand2);
+        u32::from_be_bytes([d[0], d[1], d[2], d[3]])
+    }
+}
+
+/// Outstanding challenge towards an MS
+pub struct PendingAuth {
+    pub random_seed: u128,
+    /// Expected response XRES1
+    pub expected_response: u32,
+    /// Derived cipher key, available once the MS is authenticated
+    pub dck: u128,
+    pub sent: TdmaTime,
+    /// The registration that triggered this authentication, completed on success
+    pub location_update: Option<(ULocationUpdateDemand, u32)>,
+}
+
+/// Outcome of checking an MS response against its outstanding challenge
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuthOutcome {
+    Success { dck: u128 },
+    Failure,
+    NoChallenge,
+}
+
+/// Runs the infrastructure side of the authentication exchange: issues challenges,
+/// checks responses and answers MS challenges during mutual authentication.
+pub struct Authenticator {
+    algorithm: Box<dyn AuthAlgorithm>,
+    pending: HashMap<u32, PendingAuth>,
+}
+
+impl Authenticator {
+    pub fn new(algorithm: Box<dyn AuthAlgorithm>) -> Self {
+        Self {
+            algorithm,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Box<dyn AuthAlgorithm>) {
+        self.algorithm = algorithm;
+    }
+
+    /// Creates a new challenge for the given MS, replacing any outstanding one.
+    /// Returns (RAND1, RS) to be sent in the D-AUTHENTICATION DEMAND.
+    pub fn start(&mut self, issi: u32, k: &AuthKey, now: TdmaTime, location_update: Option<(ULocationUpdateDemand, u32)>) -> (u128, u128) {
+        let rand1 = rand::random::<u128>() & ((1 << 80) - 1);
+        let rs = rand::random::<u128>() & ((1 << 80) - 1);
+        let ks = self.algorithm.ta11(k, rs);
+        let (xres1, dck1) = self.algorithm.ta12(&ks, rand1);
+        self.pending.insert(
+            issi,
+            PendingAuth {
+                random_seed: rs,
+                expected_response: xres1,
+                dck: dck1,
+                sent: now,
+                location_update,
+            },
+        );
+        (rand1, rs)
+    }
+
+    /// Checks RES1 from the MS against the outstanding challenge. The challenge is consumed
+    /// and returned, so the caller can continue the interrupted registration.
+    pub fn check_response(&mut self, issi: u32, res1: u32) -> (AuthOutcome, Option<PendingAuth>) {
+        let Some(pending) = self.pending.remove(&issi) else {
+            return (AuthOutcome::NoChallenge, None);
+        };
+        let outcome = if pending.expected_response == res1 {
+            AuthOutcome::Success { dck: pending.dck }
+        } else {
+            AuthOutcome::Failure
+        };
+        (outcome, Some(pending))
+    }
+
+    /// Computes RES2 in answer to a challenge from the MS, using the seed of the just completed exchange
+    pub fn mutual_response(&self, k: &AuthKey, rs: u128, rand2: u128) -> u32 {
+        let ks_ = self.algorithm.ta21(k, rs);
+        self.algorithm.ta22(&ks_, rand2)
+    }
+
+    /// Answers a challenge from the MS outside of an exchange we started, under a fresh random seed.
+    /// Returns (RS, RES2).
+    pub fn answer_challenge(&self, k: &AuthKey, rand2: u128) -> (u128, u32) {
+        let rs = rand::random::<u128>() & ((1 << 80) - 1);
+        (rs, self.mutual_response(k, rs, rand2))
+    }
+
+    /// Drops the outstanding challenge for an MS, returning it if there was one
+    pub fn abort(&mut self, issi: u32) -> Option<PendingAuth> {
+        self.pending.remove(&issi)
+    }
+
+    /// Removes and returns the ISSIs whose challenge went unanswered for too long
+    pub fn expire(&mut self, now: TdmaTime) -> Vec<u32> {
+        let expired: Vec<u32> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| p.sent.age(now) > AUTH_RESPONSE_TIMEOUT)
+            .map(|(issi, _)| *issi)
+            .collect();
+        for issi in &expired {
+            self.pending.remove(issi);
+        }
+        expired
+    }
+}
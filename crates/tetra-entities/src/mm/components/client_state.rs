@@ -1,9 +1,36 @@
+use tetra_config::bluestation::SubscriberDb;
+
 #[derive(Debug)]
 pub enum ClientMgrErr {
-    ClientNotFound { issi: u32 },
-    GroupNotFound { gssi: u32 },
-    IssiInGroupRange { issi: u32 },
-    GssiInClientRange { gssi: u32 },
+    ClientNotFound {
+        issi: u32,
+    },
+    GroupNotFound {
+        gssi: u32,
+    },
+    IssiInGroupRange {
+        issi: u32,
+    },
+    GssiInClientRange {
+        gssi: u32,
+    },
+    /// ISSI not listed in the subscriber database
+    SubscriberUnknown {
+        issi: u32,
+    },
+    /// Subscriber barred in the subscriber database
+    SubscriberBarred {
+        issi: u32,
+    },
+    /// None of the subscriber's classes is allowed on this cell
+    SubscriberClassNotAllowed {
+        issi: u32,
+    },
+    /// Subscriber not permitted to attach to this group
+    GroupNotPermitted {
+        issi: u32,
+        gssi: u32,
+    },
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -17,6 +44,8 @@ pub struct MmClientProperties {
     pub ssi: u32,
     pub state: MmClientState,
     pub groups: std::collections::HashSet<u32>,
+    /// Derived cipher key DCK from a successful authentication, None if not authenticated
+    pub dck: Option<u128>,
     // pub last_seen: TdmaTime,
 }
 
@@ -26,40 +55,71 @@ impl MmClientProperties {
             ssi,
             state: MmClientState::Unknown,
             groups: std::collections::HashSet::new(),
+            dck: None,
             // last_seen: TdmaTime::default(),
         }
     }
 }
 
-/// Stub function, to be replaced with checks based on configuration file
-fn is_individual(_issi: u32) -> bool {
-    return true;
-}
-/// Stub function, to be replaced with checks based on configuration file
-fn in_group_range(_gssi: u32) -> bool {
-    return true;
-}
-/// Stub function, to be replaced with checks based on configuration file
-fn is_group(_gssi: u32) -> bool {
-    return true;
-}
-/// Stub function, to be replaced with checks based on configuration file
-fn may_attach(_issi: u32, _gssi: u32) -> bool {
-    return true;
-}
-
 pub struct MmClientMgr {
     clients: std::collections::HashMap<u32, MmClientProperties>,
+    /// Subscriber and group authorization database. Without one, every SSI is accepted
+    db: Option<SubscriberDb>,
 }
 
 impl MmClientMgr {
     pub fn new() -> Self {
         MmClientMgr {
             clients: std::collections::HashMap::new(),
+            db: None,
         }
     }
 
-    pub fn get_client_by_issi(&mut self, issi: u32) -> Option<&MmClientProperties> {
+    /// Replaces the authorization database. Registered clients keep their state, the new rules
+    /// apply from their next location update or group attachment.
+    pub fn set_subscriber_db(&mut self, db: Option<SubscriberDb>) {
+        self.db = db;
+    }
+
+    fn is_individual(&self, issi: u32) -> bool {
+        self.db.as_ref().is_none_or(|db| !db.is_group(issi))
+    }
+
+    fn in_group_range(&self, gssi: u32) -> bool {
+        self.db.as_ref().is_none_or(|db| db.subscriber(gssi).is_none())
+    }
+
+    fn is_group(&self, gssi: u32) -> bool {
+        self.db.as_ref().is_none_or(|db| db.is_group(gssi))
+    }
+
+    fn may_attach(&self, issi: u32, gssi: u32) -> bool {
+        self.db.as_ref().is_none_or(|db| db.may_attach(issi, gssi))
+    }
+
+    /// Checks whether the subscriber may register on a cell that admits the given subscriber classes
+    pub fn may_register(&self, issi: u32, cell_subscriber_class: u16) -> Result<(), ClientMgrErr> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+        let Some(entry) = db.subscriber(issi) else {
+            return Err(ClientMgrErr::SubscriberUnknown { issi });
+        };
+        if entry.barred {
+            return Err(ClientMgrErr::SubscriberBarred { issi });
+        }
+        if entry.class.is_some_and(|class| class & cell_subscriber_class == 0) {
+            return Err(ClientMgrErr::SubscriberClassNotAllowed { issi });
+        }
+        Ok(())
+    }
+
+    /// Subscriber class bitmap of the subscriber, if the database assigns one
+    pub fn subscriber_class(&self, issi: u32) -> Option<u16> {
+        self.db.as_ref()?.subscriber(issi)?.class
+    }
+
+    pub fn get_client_by_issi(&self, issi: u32) -> Option<&MmClientProperties> {
         self.clients.get(&issi)
     }
 
@@ -76,10 +136,20 @@ impl MmClientMgr {
         }
     }
 
+    /// Records a successful authentication of a client, along with the derived cipher key
+    pub fn set_client_authenticated(&mut self, issi: u32, dck: u128) -> Result<(), ClientMgrErr> {
+        if let Some(client) = self.clients.get_mut(&issi) {
+            client.dck = Some(dck);
+            Ok(())
+        } else {
+            Err(ClientMgrErr::ClientNotFound { issi })
+        }
+    }
+
     /// Registers a fresh state for a client, based on ssi
     /// If client is already registered, previous state is discarded.
     pub fn try_register_client(&mut self, issi: u32, attached: bool) -> Result<bool, ClientMgrErr> {
-        if !is_individual(issi) {
+        if !self.is_individual(issi) {
             return Err(ClientMgrErr::IssiInGroupRange { issi });
         };
 
@@ -98,6 +168,11 @@ impl MmClientMgr {
         Ok(true)
     }
 
+    /// Iterates over all known clients
+    pub fn iter(&self) -> impl Iterator<Item = &MmClientProperties> {
+        self.clients.values()
+    }
+
     /// Removes a client from the registry, returning its properties if found
     pub fn remove_client(&mut self, ssi: u32) -> Option<MmClientProperties> {
         self.clients.remove(&ssi)
@@ -115,16 +190,18 @@ impl MmClientMgr {
 
     /// Attaches or detaches a client from a group
     pub fn client_group_attach(&mut self, issi: u32, gssi: u32, do_attach: bool) -> Result<bool, ClientMgrErr> {
-        // Checks
-        if !in_group_range(gssi) {
-            return Err(ClientMgrErr::GssiInClientRange { gssi });
-        };
-        if !is_group(gssi) {
-            return Err(ClientMgrErr::GroupNotFound { gssi });
-        };
-        if !may_attach(issi, gssi) {
-            return Err(ClientMgrErr::GroupNotFound { gssi });
-        };
+        // Checks. Detaching is always allowed, so an MS can leave a group it is no longer permitted on
+        if do_attach {
+            if !self.in_group_range(gssi) {
+                return Err(ClientMgrErr::GssiInClientRange { gssi });
+            };
+            if !self.is_group(gssi) {
+                return Err(ClientMgrErr::GroupNotFound { gssi });
+            };
+            if !self.may_attach(issi, gssi) {
+                return Err(ClientMgrErr::GroupNotPermitted { issi, gssi });
+            };
+        }
 
         if let Some(client) = self.clients.get_mut(&issi) {
             if do_attach {
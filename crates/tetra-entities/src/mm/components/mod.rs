@@ -1,2 +1,5 @@
+pub mod authentication;
 pub mod client_state;
 pub mod not_supported;
+pub mod registration_store;
+pub mod subscriber_db_file;
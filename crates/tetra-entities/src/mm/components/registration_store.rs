@@ -0,0 +1,89 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Registered ISSIs and the groups they are attached to
+pub type Registrations = BTreeMap<u32, BTreeSet<u32>>;
+
+#[derive(Serialize, Deserialize)]
+struct StateFile {
+    registrations: Vec<StoredRegistration>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRegistration {
+    issi: u32,
+    groups: Vec<u32>,
+}
+
+/// Persists registrations to a JSON state file, so they survive a restart of the stack
+pub struct RegistrationStore {
+    path: String,
+    /// Registrations as last loaded or saved, to skip writes when nothing changed
+    saved: Registrations,
+}
+
+impl RegistrationStore {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            saved: Registrations::new(),
+        }
+    }
+
+    /// Reads the registrations from the state file. A missing or unreadable file yields no registrations.
+    pub fn load(&mut self) -> Registrations {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Registrations::new(),
+            Err(e) => {
+                tracing::error!("RegistrationStore: failed to read {}: {}", self.path, e);
+                return Registrations::new();
+            }
+        };
+        let state: StateFile = match serde_json::from_str(&contents) {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::error!("RegistrationStore: ignoring invalid state file {}: {}", self.path, e);
+                return Registrations::new();
+            }
+        };
+        self.saved = state
+            .registrations
+            .into_iter()
+            .map(|reg| (reg.issi, reg.groups.into_iter().collect()))
+            .collect();
+        self.saved.clone()
+    }
+
+    /// Writes the registrations if they differ from what was last loaded or saved. The file is replaced
+    /// atomically, so a crash halfway leaves the previous state intact.
+    pub fn save_if_changed(&mut self, registrations: Registrations) {
+        if registrations == self.saved {
+            return;
+        }
+        let state = StateFile {
+            registrations: registrations
+                .iter()
+                .map(|(issi, groups)| StoredRegistration {
+                    issi: *issi,
+                    groups: groups.iter().copied().collect(),
+                })
+                .collect(),
+        };
+        let json = match serde_json::to_string_pretty(&state) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("RegistrationStore: failed to serialize registrations: {}", e);
+                return;
+            }
+        };
+        let tmp_path = format!("{}.tmp", self.path);
+        if let Err(e) = std::fs::write(&tmp_path, json).and_then(|_| std::fs::rename(&tmp_path, &self.path)) {
+            tracing::error!("RegistrationStore: failed to write {}: {}", self.path, e);
+            return;
+        }
+        tracing::debug!("RegistrationStore: saved {} registrations", registrations.len());
+        self.saved = registrations;
+    }
+}
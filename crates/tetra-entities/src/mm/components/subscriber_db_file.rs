@@ -0,0 +1,53 @@
+use std::time::SystemTime;
+
+use tetra_config::bluestation::SubscriberDb;
+
+/// Tracks the subscriber database file, so it can be reloaded while the stack is running
+pub struct SubscriberDbFile {
+    path: String,
+    /// Modification time and size of the file at the last load attempt
+    version: Option<(SystemTime, u64)>,
+}
+
+impl SubscriberDbFile {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            version: None,
+        }
+    }
+
+    /// Loads the database if the file changed since the last attempt. A file that fails to load is
+    /// reported once, after which the previous database stays in use until the file changes again.
+    pub fn load_if_changed(&mut self) -> Option<SubscriberDb> {
+        let version = match std::fs::metadata(&self.path).and_then(|meta| Ok((meta.modified()?, meta.len()))) {
+            Ok(version) => version,
+            Err(e) => {
+                if self.version.take().is_some() {
+                    tracing::error!("Subscriber database {} unavailable: {}", self.path, e);
+                }
+                return None;
+            }
+        };
+        if self.version == Some(version) {
+            return None;
+        }
+        self.version = Some(version);
+
+        match SubscriberDb::from_file(&self.path) {
+            Ok(db) => {
+                tracing::info!(
+                    "Loaded subscriber database {}: {} subscribers, {} groups",
+                    self.path,
+                    db.subscribers.len(),
+                    db.groups.len()
+                );
+                Some(db)
+            }
+            Err(e) => {
+                tracing::error!("{}, keeping previous subscriber database", e);
+                None
+            }
+        }
+    }
+}
@@ -1,38 +1,191 @@
-use crate::{MessageQueue, TetraEntityTrait, brew};
-use tetra_config::bluestation::SharedConfig;
+use crate::{MessageQueue, TetraEntityTrait, backhaul, brew};
+use tetra_config::bluestation::{AuthPolicy, SecurityClass, SharedConfig};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, assert_warn, unimplemented_log};
 use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::cipher::MmCipherKeyUpdate;
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
-use crate::mm::components::client_state::{MmClientMgr, MmClientState};
+use crate::mm::components::authentication::{AuthAlgorithm, AuthOutcome, Authenticator, TestAuthAlgorithm};
+use crate::mm::components::client_state::{ClientMgrErr, MmClientMgr, MmClientState};
 use crate::mm::components::not_supported::make_ul_mm_pdu_function_not_supported;
+use crate::mm::components::registration_store::{RegistrationStore, Registrations};
+use crate::mm::components::subscriber_db_file::SubscriberDbFile;
+use tetra_pdus::mm::enums::authentication_sub_type::AuthenticationSubType;
 use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
 use tetra_pdus::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
 use tetra_pdus::mm::enums::status_uplink::StatusUplink;
+use tetra_pdus::mm::fields::authentication_downlink::AuthenticationDownlink;
 use tetra_pdus::mm::fields::group_identity_attachment::GroupIdentityAttachment;
 use tetra_pdus::mm::fields::group_identity_downlink::GroupIdentityDownlink;
 use tetra_pdus::mm::fields::group_identity_location_accept::GroupIdentityLocationAccept;
 use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
 use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_authentication_demand::DAuthenticationDemand;
+use tetra_pdus::mm::pdus::d_authentication_reject::DAuthenticationReject;
+use tetra_pdus::mm::pdus::d_authentication_response::DAuthenticationResponse;
+use tetra_pdus::mm::pdus::d_authentication_result::DAuthenticationResult;
 use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
 use tetra_pdus::mm::pdus::d_location_update_command::DLocationUpdateCommand;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
 use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_authentication_demand::UAuthenticationDemand;
+use tetra_pdus::mm::pdus::u_authentication_reject::UAuthenticationReject;
+use tetra_pdus::mm::pdus::u_authentication_response::UAuthenticationResponse;
+use tetra_pdus::mm::pdus::u_authentication_result::UAuthenticationResult;
 use tetra_pdus::mm::pdus::u_itsi_detach::UItsiDetach;
 use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
 use tetra_pdus::mm::pdus::u_mm_status::UMmStatus;
 
+/// Clause 16.10.42 Reject cause values used in D-LOCATION UPDATE REJECT
+const REJECT_CAUSE_ITSI_UNKNOWN: u8 = 1;
+const REJECT_CAUSE_ILLEGAL_MS: u8 = 2;
+const REJECT_CAUSE_LA_NOT_ALLOWED: u8 = 3;
+const REJECT_CAUSE_AUTHENTICATION_FAILURE: u8 = 19;
+
+/// What to do with a registering MS under the configured authentication policy
+enum AuthRequirement {
+    /// Register without authentication
+    None,
+    /// Refuse registration, no key is known for this MS
+    Reject,
+    /// Challenge the MS with the given key before registering
+    Challenge([u8; 16]),
+}
+
 pub struct MmBs {
     config: SharedConfig,
     pub client_mgr: MmClientMgr,
+    auth: Authenticator,
+    /// Subscriber database file, checked for changes once per multiframe
+    subscriber_db: Option<SubscriberDbFile>,
+    /// Registration state file, saved once per multiframe if registrations changed
+    registration_store: Option<RegistrationStore>,
+    /// Registrations loaded from the state file, restored on the first tick
+    pending_restore: Option<Registrations>,
 }
 
 impl MmBs {
     pub fn new(config: SharedConfig) -> Self {
+        let mut client_mgr = MmClientMgr::new();
+        let subscriber_db = config.config().subscriber_db.as_deref().map(|path| {
+            let mut file = SubscriberDbFile::new(path);
+            let db = file.load_if_changed().unwrap_or_else(|| {
+                tracing::error!("Subscriber database {} could not be loaded, refusing all subscribers", path);
+                Default::default()
+            });
+            client_mgr.set_subscriber_db(Some(db));
+            file
+        });
+        let mut registration_store = config
+            .config()
+            .registration_store
+            .as_ref()
+            .map(|store_cfg| RegistrationStore::new(&store_cfg.file));
+        let pending_restore = registration_store.as_mut().map(|store| store.load());
         Self {
             config,
-            client_mgr: MmClientMgr::new(),
+            client_mgr,
+            auth: Authenticator::new(Box::new(TestAuthAlgorithm)),
+            subscriber_db,
+            registration_store,
+            pending_restore,
+        }
+    }
+
+    /// Re-registers the MSs saved before a restart, and announces them and their groups to CMCE and Brew.
+    /// The backhaul is not told, as it shares the full registry with each site it connects to. MSs
+    /// that the subscriber database no longer admits are left out.
+    fn restore_registrations(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, registrations: Registrations) {
+        let cell_subscriber_class = self.config.config().cell.subscriber_class;
+        let request_reregistration = self
+            .config
+            .config()
+            .registration_store
+            .as_ref()
+            .is_some_and(|store_cfg| store_cfg.request_reregistration);
+
+        let mut restored = 0;
+        for (issi, groups) in registrations {
+            if let Err(e) = self.client_mgr.may_register(issi, cell_subscriber_class) {
+                tracing::info!("Not restoring registration of MS {}: {:?}", issi, e);
+                continue;
+            }
+            if let Err(e) = self.client_mgr.try_register_client(issi, true) {
+                tracing::warn!("Failed restoring registration of MS {}: {:?}", issi, e);
+                continue;
+            }
+            self.config.state_write().subscribers.register(issi);
+            self.emit_local_subscriber_update(queue, dltime, issi, Vec::new(), BrewSubscriberAction::Register);
+
+            let mut attached = Vec::new();
+            for gssi in groups {
+                match self.client_mgr.client_group_attach(issi, gssi, true) {
+                    Ok(_) => attached.push(gssi),
+                    Err(e) => tracing::info!("Not restoring attachment of MS {} to group {}: {:?}", issi, gssi, e),
+                }
+            }
+            if !attached.is_empty() {
+                {
+                    let mut state = self.config.state_write();
+                    for &gssi in &attached {
+                        state.subscribers.affiliate(issi, gssi);
+                    }
+                }
+                self.emit_local_subscriber_update(queue, dltime, issi, attached, BrewSubscriberAction::Affiliate);
+            }
+
+            if request_reregistration {
+                Self::send_d_location_update_command(queue, dltime, issi, 0);
+            }
+            restored += 1;
+        }
+        tracing::info!("Restored {} registrations", restored);
+    }
+
+    fn save_registrations(&mut self) {
+        let Some(store) = self.registration_store.as_mut() else {
+            return;
+        };
+        let registrations = self
+            .client_mgr
+            .iter()
+            .map(|client| (client.ssi, client.groups.iter().copied().collect()))
+            .collect();
+        store.save_if_changed(registrations);
+    }
+
+    /// Replaces the authentication algorithm set, e.g. with a TAA1 implementation
+    pub fn set_auth_algorithm(&mut self, algorithm: Box<dyn AuthAlgorithm>) {
+        self.auth.set_algorithm(algorithm);
+    }
+
+    fn auth_requirement(&self, issi: u32, location_update_type: LocationUpdateType) -> AuthRequirement {
+        let config = self.config.config();
+        // A class 3 cell carries individual traffic under DCKs only, so every MS has to authenticate to register
+        let class3 = config.encryption.as_ref().map(|enc| enc.security_class) == Some(SecurityClass::Class3);
+        let Some(auth_cfg) = &config.auth else {
+            return if class3 { AuthRequirement::Reject } else { AuthRequirement::None };
+        };
+        let policy = if class3 { AuthPolicy::Required } else { auth_cfg.policy };
+        if policy == AuthPolicy::Disabled {
+            return AuthRequirement::None;
+        }
+
+        // Periodic or infrastructure-demanded updates from an MS we already authenticated need no new challenge
+        let authenticated = self.client_mgr.get_client_by_issi(issi).is_some_and(|client| client.dck.is_some());
+        if authenticated
+            && (location_update_type == LocationUpdateType::PeriodicLocationUpdating
+                || location_update_type == LocationUpdateType::DemandLocationUpdating)
+        {
+            return AuthRequirement::None;
+        }
+
+        match (auth_cfg.key_for(issi), policy) {
+            (Some(k), _) => AuthRequirement::Challenge(*k),
+            (None, AuthPolicy::Required) => AuthRequirement::Reject,
+            (None, _) => AuthRequirement::None,
         }
     }
 
@@ -43,6 +196,33 @@ impl MmBs {
         issi: u32,
         groups: Vec<u32>,
         action: BrewSubscriberAction,
+    ) {
+        // Share all registration changes with the other sites
+        if backhaul::is_active(&self.config) {
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Mm,
+                dest: TetraEntity::Backhaul,
+                dltime,
+                msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+                    issi,
+                    groups: groups.clone(),
+                    action,
+                }),
+            });
+        }
+
+        self.emit_local_subscriber_update(queue, dltime, issi, groups, action);
+    }
+
+    /// Forward a subscriber update to the entities of this site: Brew, if active, and CMCE
+    fn emit_local_subscriber_update(
+        &self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        groups: Vec<u32>,
+        action: BrewSubscriberAction,
     ) {
         // If brew is active, forward subscriber updates to the Brew entity.
         // Register/Deregister must always be sent for brew-routable ISSIs,
@@ -112,20 +292,40 @@ impl MmBs {
         }
 
         let ssi = prim.received_address.ssi;
-        let detached_client = self.client_mgr.remove_client(ssi);
-        if let Some(client) = detached_client {
-            self.config.state_write().subscribers.deregister(ssi);
-            if !client.groups.is_empty() {
-                let groups: Vec<u32> = client.groups.iter().copied().collect();
-                self.emit_subscriber_update(_queue, message.dltime, ssi, groups, BrewSubscriberAction::Deaffiliate);
-            }
-            self.emit_subscriber_update(_queue, message.dltime, ssi, Vec::new(), BrewSubscriberAction::Deregister);
-        } else {
+        if !self.remove_registration(_queue, message.dltime, ssi) {
             tracing::warn!("Received UItsiDetach for unknown client with SSI: {}", ssi);
             // return;
         };
     }
 
+    /// Drop the registration of an MS and its group attachments. Returns false if the MS was not registered.
+    fn remove_registration(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, ssi: u32) -> bool {
+        let Some(client) = self.client_mgr.remove_client(ssi) else {
+            return false;
+        };
+        self.config.state_write().subscribers.deregister(ssi);
+        if client.dck.is_some() {
+            self.emit_cipher_key_update(queue, dltime, ssi, None);
+        }
+        if !client.groups.is_empty() {
+            let groups: Vec<u32> = client.groups.iter().copied().collect();
+            self.emit_subscriber_update(queue, dltime, ssi, groups, BrewSubscriberAction::Deaffiliate);
+        }
+        self.emit_subscriber_update(queue, dltime, ssi, Vec::new(), BrewSubscriberAction::Deregister);
+        true
+    }
+
+    /// Handle a subscriber update from the inter-site backhaul: the MS registered at another site
+    fn rx_backhaul_subscriber_update(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, update: MmSubscriberUpdate) {
+        if update.action != BrewSubscriberAction::Deregister {
+            tracing::warn!("Unexpected subscriber update from backhaul: {:?}", update);
+            return;
+        }
+        if self.remove_registration(queue, dltime, update.issi) {
+            tracing::info!("MS {} registered at another site, registration removed", update.issi);
+        }
+    }
+
     fn rx_u_location_update_demand(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_location_update_demand");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
@@ -149,6 +349,52 @@ impl MmBs {
             return;
         }
 
+        let issi = prim.received_address.ssi;
+        let handle = prim.handle;
+
+        // Check the subscriber database before anything else. A registered MS that is no longer
+        // admitted loses its registration on its next location update.
+        let cell_subscriber_class = self.config.config().cell.subscriber_class;
+        if let Err(e) = self.client_mgr.may_register(issi, cell_subscriber_class) {
+            let reject_cause = match e {
+                ClientMgrErr::SubscriberBarred { .. } => REJECT_CAUSE_ILLEGAL_MS,
+                ClientMgrErr::SubscriberClassNotAllowed { .. } => REJECT_CAUSE_LA_NOT_ALLOWED,
+                _ => REJECT_CAUSE_ITSI_UNKNOWN,
+            };
+            tracing::info!("Rejecting registration of MS {}: {:?}", issi, e);
+            self.remove_registration(queue, message.dltime, issi);
+            Self::send_d_location_update_reject(queue, message.dltime, issi, handle, pdu.location_update_type, reject_cause);
+            return;
+        }
+
+        match self.auth_requirement(issi, pdu.location_update_type) {
+            AuthRequirement::None => {}
+            AuthRequirement::Reject => {
+                tracing::info!("Rejecting registration of MS {}: no authentication key known", issi);
+                Self::send_d_location_update_reject(
+                    queue,
+                    message.dltime,
+                    issi,
+                    handle,
+                    pdu.location_update_type,
+                    REJECT_CAUSE_ITSI_UNKNOWN,
+                );
+                return;
+            }
+            AuthRequirement::Challenge(k) => {
+                // Registration continues once the MS has answered the challenge
+                let (rand1, rs) = self.auth.start(issi, &k, message.dltime, Some((pdu, handle)));
+                tracing::info!("Authenticating MS {} before registration", issi);
+                Self::send_d_authentication_demand(queue, message.dltime, issi, handle, rand1, rs);
+                return;
+            }
+        }
+
+        self.accept_location_update(queue, message.dltime, issi, handle, pdu);
+    }
+
+    /// Registers the MS and answers its U-LOCATION UPDATE DEMAND with a D-LOCATION UPDATE ACCEPT
+    fn accept_location_update(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, handle: u32, pdu: ULocationUpdateDemand) {
         // Handle Energy Saving Mode request
         // TODO FIXME this does not yet seem to be functional, and prevents the MS from remaining
         // properly registered.
@@ -167,14 +413,12 @@ impl MmBs {
         let esi = None;
 
         // Try to register the client
-        let issi = prim.received_address.ssi;
-        let handle = prim.handle;
         let is_new = !self.client_mgr.client_is_known(issi);
         if is_new {
             match self.client_mgr.try_register_client(issi, true) {
                 Ok(_) => {
                     self.config.state_write().subscribers.register(issi);
-                    self.emit_subscriber_update(queue, message.dltime, issi, Vec::new(), BrewSubscriberAction::Register);
+                    self.emit_subscriber_update(queue, dltime, issi, Vec::new(), BrewSubscriberAction::Register);
                 }
                 Err(e) => {
                     tracing::warn!("Failed registering roaming MS {}: {:?}", issi, e);
@@ -190,13 +434,16 @@ impl MmBs {
         // Process optional GroupIdentityLocationDemand field
         let gila = if let Some(gild) = pdu.group_identity_location_demand {
             // Try to attach to requested groups, then build GroupIdentityLocationAccept element
-            let accepted_groups = if let Some(giu) = &gild.group_identity_uplink {
-                Some(self.try_attach_detach_groups(queue, message.dltime, issi, &giu))
-            } else {
-                None
+            let accepted_groups = gild
+                .group_identity_uplink
+                .as_ref()
+                .map(|giu| self.try_attach_detach_groups(queue, dltime, issi, giu));
+            let all_accepted = match (&gild.group_identity_uplink, &accepted_groups) {
+                (Some(giu), Some(accepted)) => accepted.len() == giu.len(),
+                _ => true,
             };
             let gila = GroupIdentityLocationAccept {
-                group_identity_accept_reject: 0, // Accept
+                group_identity_accept_reject: if all_accepted { 0 } else { 1 }, // Accept, or reject of some groups
                 group_identity_downlink: accepted_groups,
             };
 
@@ -206,19 +453,31 @@ impl MmBs {
             None
         };
 
+        // Answer the challenge of an MS authenticating us while registering
+        let authentication_downlink = pdu.authentication_uplink.as_ref().and_then(|auth_ul| {
+            let answer = self.answer_ms_challenge(issi, auth_ul.random_challenge);
+            if answer.is_none() {
+                tracing::warn!("Cannot answer authentication challenge of MS {}: no key known", issi);
+            }
+            answer.map(|(random_seed, response_value)| AuthenticationDownlink {
+                random_seed,
+                response_value,
+            })
+        });
+
         // Build D-LOCATION UPDATE ACCEPT pdu
         let pdu_response = DLocationUpdateAccept {
             location_update_accept_type: pdu.location_update_type, // Practically identical besides minor migration-related difference
             ssi: Some(issi as u64),
             address_extension: None,
-            subscriber_class: None,
+            subscriber_class: self.client_mgr.subscriber_class(issi).map(u64::from),
             energy_saving_information: esi,
             scch_information_and_distribution_on_18th_frame: None,
             new_registered_area: None,
             security_downlink: None,
             group_identity_location_accept: gila,
             default_group_attachment_lifetime: None,
-            authentication_downlink: None,
+            authentication_downlink,
             group_identity_security_related_information: None,
             cell_type_control: None,
             proprietary: None,
@@ -241,10 +500,10 @@ impl MmBs {
             sap: Sap::LmmSap,
             src: TetraEntity::Mm,
             dest: TetraEntity::Mle,
-            dltime: message.dltime,
+            dltime,
             msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
                 sdu,
-                handle: prim.handle,
+                handle,
                 address: addr,
                 layer2service: Layer2Service::Todo,
                 stealing_permission: false,
@@ -260,7 +519,206 @@ impl MmBs {
         // re-register with full group report via D-LOCATION UPDATE COMMAND
         if is_new && pdu.location_update_type != LocationUpdateType::ItsiAttach {
             tracing::info!("Sending D-LOCATION UPDATE COMMAND to returning MS {} to request group report", issi);
-            Self::send_d_location_update_command(queue, message.dltime, issi, handle);
+            Self::send_d_location_update_command(queue, dltime, issi, handle);
+        }
+    }
+
+    fn rx_u_authentication(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_authentication");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let Some(sub_type) = prim.sdu.peek_bits_posoffset(4, 2) else {
+            tracing::warn!("insufficient bits: {}", prim.sdu.dump_bin());
+            return;
+        };
+        let issi = prim.received_address.ssi;
+        let handle = prim.handle;
+
+        match AuthenticationSubType::try_from(sub_type) {
+            Ok(AuthenticationSubType::Response) => {
+                let pdu = match UAuthenticationResponse::from_bitbuf(&mut prim.sdu) {
+                    Ok(pdu) => {
+                        tracing::debug!("<- {}", pdu);
+                        pdu
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed parsing UAuthenticationResponse: {:?} {}", e, prim.sdu.dump_bin());
+                        return;
+                    }
+                };
+                self.rx_u_authentication_response(queue, message.dltime, issi, handle, pdu);
+            }
+            Ok(AuthenticationSubType::Reject) => {
+                match UAuthenticationReject::from_bitbuf(&mut prim.sdu) {
+                    Ok(pdu) => tracing::debug!("<- {}", pdu),
+                    Err(e) => tracing::warn!("Failed parsing UAuthenticationReject: {:?} {}", e, prim.sdu.dump_bin()),
+                };
+                tracing::info!("MS {} refused authentication", issi);
+                self.fail_authentication(queue, message.dltime, issi);
+            }
+            Ok(AuthenticationSubType::Demand) => {
+                let pdu = match UAuthenticationDemand::from_bitbuf(&mut prim.sdu) {
+                    Ok(pdu) => {
+                        tracing::debug!("<- {}", pdu);
+                        pdu
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed parsing UAuthenticationDemand: {:?} {}", e, prim.sdu.dump_bin());
+                        return;
+                    }
+                };
+                self.rx_u_authentication_demand(queue, message.dltime, issi, handle, pdu);
+            }
+            Ok(AuthenticationSubType::Result) => {
+                // Outcome of the MS checking our RES2. An MS that failed to authenticate us must not keep
+                // its registration, nor the cipher key derived during the exchange
+                let pdu = match UAuthenticationResult::from_bitbuf(&mut prim.sdu) {
+                    Ok(pdu) => {
+                        tracing::debug!("<- {}", pdu);
+                        pdu
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed parsing UAuthenticationResult: {:?} {}", e, prim.sdu.dump_bin());
+                        return;
+                    }
+                };
+                if !pdu.authentication_result {
+                    tracing::warn!("MS {} failed to authenticate the infrastructure, dropping its registration", issi);
+                    self.remove_registration(queue, message.dltime, issi);
+                }
+            }
+            Err(_) => unreachable!(), // 2-bit field, all values defined
+        }
+    }
+
+    fn rx_u_authentication_response(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        pdu: UAuthenticationResponse,
+    ) {
+        let (outcome, pending) = self.auth.check_response(issi, pdu.response_value);
+        let Some(pending) = pending else {
+            tracing::warn!("U-AUTHENTICATION RESPONSE from MS {} without outstanding challenge", issi);
+            return;
+        };
+        let AuthOutcome::Success { dck } = outcome else {
+            tracing::warn!("Authentication of MS {} failed: wrong response", issi);
+            Self::send_d_authentication_result(queue, dltime, issi, handle, false, None);
+            if let Some((demand, demand_handle)) = pending.location_update {
+                Self::send_d_location_update_reject(
+                    queue,
+                    dltime,
+                    issi,
+                    demand_handle,
+                    demand.location_update_type,
+                    REJECT_CAUSE_AUTHENTICATION_FAILURE,
+                );
+            }
+            return;
+        };
+
+        // Answer the MS challenge, if it asked us to prove ourselves as well. The challenge comes either with
+        // the response, or with the registration that triggered the exchange; it is answered only once.
+        let mut location_update = pending.location_update;
+        let registration_rand2 = location_update
+            .as_mut()
+            .and_then(|(demand, _)| demand.authentication_uplink.take())
+            .map(|auth_ul| auth_ul.random_challenge);
+        let res2 = pdu.random_challenge.or(registration_rand2).and_then(|rand2| {
+            let config = self.config.config();
+            let k = config.auth.as_ref()?.key_for(issi)?;
+            Some(self.auth.mutual_response(k, pending.random_seed, rand2))
+        });
+        tracing::info!("MS {} authenticated successfully", issi);
+        Self::send_d_authentication_result(queue, dltime, issi, handle, true, res2);
+
+        if let Some((demand, demand_handle)) = location_update {
+            self.accept_location_update(queue, dltime, issi, demand_handle, demand);
+        }
+        if let Err(e) = self.client_mgr.set_client_authenticated(issi, dck) {
+            tracing::warn!("Failed storing authentication state for MS {}: {:?}", issi, e);
+            return;
+        }
+        self.emit_cipher_key_update(queue, dltime, issi, Some(dck));
+    }
+
+    /// Hands the derived cipher key of an MS to the UMAC. Only security class 3 cells use DCKs.
+    fn emit_cipher_key_update(&self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, dck: Option<u128>) {
+        let config = self.config.config();
+        if config.encryption.as_ref().map(|enc| enc.security_class) != Some(SecurityClass::Class3) {
+            return;
+        }
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Umac,
+            dltime,
+            msg: SapMsgInner::MmCipherKeyUpdate(MmCipherKeyUpdate { issi, dck }),
+        });
+    }
+
+    /// Handles an MS authenticating the infrastructure outside of an exchange we started
+    fn rx_u_authentication_demand(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        pdu: UAuthenticationDemand,
+    ) {
+        let mut sdu = BitBuffer::new_autoexpand(128);
+        if let Some((random_seed, response_value)) = self.answer_ms_challenge(issi, pdu.random_challenge) {
+            tracing::info!("Answering authentication challenge of MS {}", issi);
+            let pdu = DAuthenticationResponse {
+                random_seed,
+                response_value,
+                mutual_authentication_flag: false,
+                random_challenge: None,
+                proprietary: None,
+            };
+            pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+            tracing::debug!("-> {}", pdu);
+        } else {
+            tracing::info!("Cannot answer authentication challenge of MS {}: no key known", issi);
+            let pdu = DAuthenticationReject {
+                authentication_reject_reason: 0, // Authentication not supported
+                proprietary: None,
+            };
+            pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+            tracing::debug!("-> {}", pdu);
+        }
+        sdu.seek(0);
+        Self::send_mm_pdu(queue, dltime, issi, handle, sdu);
+    }
+
+    /// Answers a challenge RAND2 from the MS under a fresh random seed, returning (RS, RES2).
+    /// Returns None if no authentication key is known for the MS.
+    fn answer_ms_challenge(&self, issi: u32, rand2: u128) -> Option<(u128, u32)> {
+        let config = self.config.config();
+        let k = config.auth.as_ref()?.key_for(issi)?;
+        Some(self.auth.answer_challenge(k, rand2))
+    }
+
+    /// Ends an outstanding authentication exchange unsuccessfully, refusing the registration it was part of
+    fn fail_authentication(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32) {
+        let Some(pending) = self.auth.abort(issi) else {
+            tracing::warn!("No outstanding authentication for MS {}", issi);
+            return;
+        };
+        if let Some((demand, demand_handle)) = pending.location_update {
+            Self::send_d_location_update_reject(
+                queue,
+                dltime,
+                issi,
+                demand_handle,
+                demand.location_update_type,
+                REJECT_CAUSE_AUTHENTICATION_FAILURE,
+            );
         }
     }
 
@@ -349,16 +807,21 @@ impl MmBs {
         // If group_identity_attach_detach_mode == 1, we first detach all groups
         if pdu.group_identity_attach_detach_mode == true {
             if !self.client_mgr.client_is_known(issi) {
-                // Client unknown (e.g. never registered via location update).
-                // Re-register so group attachment can proceed.
-                match self.client_mgr.try_register_client(issi, true) {
-                    Ok(_) => {
-                        self.config.state_write().subscribers.register(issi);
-                        self.emit_subscriber_update(queue, message.dltime, issi, Vec::new(), BrewSubscriberAction::Register);
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed re-registering MS {} on group attach: {:?}", issi, e);
-                        return;
+                // An MS the subscriber database does not admit stays unregistered, so all its groups are rejected
+                if let Err(e) = self.client_mgr.may_register(issi, self.config.config().cell.subscriber_class) {
+                    tracing::info!("Not re-registering MS {} on group attach: {:?}", issi, e);
+                } else {
+                    // Client unknown (e.g. never registered via location update).
+                    // Re-register so group attachment can proceed.
+                    match self.client_mgr.try_register_client(issi, true) {
+                        Ok(_) => {
+                            self.config.state_write().subscribers.register(issi);
+                            self.emit_subscriber_update(queue, message.dltime, issi, Vec::new(), BrewSubscriberAction::Register);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed re-registering MS {} on group attach: {:?}", issi, e);
+                            return;
+                        }
                     }
                 }
             } else {
@@ -390,12 +853,14 @@ impl MmBs {
 
         // Try to attach to requested groups, and retrieve list of accepted GroupIdentityDownlink elements
         // We can unwrap since we did compat check earlier
-        let accepted_gid = self.try_attach_detach_groups(queue, message.dltime, issi, &pdu.group_identity_uplink.unwrap());
+        let giu = pdu.group_identity_uplink.unwrap();
+        let accepted_gid = self.try_attach_detach_groups(queue, message.dltime, issi, &giu);
+        let all_accepted = accepted_gid.len() == giu.len();
 
         // Build reply PDU
         let pdu_response = DAttachDetachGroupIdentityAcknowledgement {
-            group_identity_accept_reject: 0, // Accept
-            reserved: false,                 // TODO FIXME Guessed proper value of reserved field
+            group_identity_accept_reject: if all_accepted { 0 } else { 1 }, // Accept, or reject of some groups
+            reserved: false,                                                // TODO FIXME Guessed proper value of reserved field
             proprietary: None,
             group_identity_downlink: Some(accepted_gid),
             group_identity_security_related_information: None,
@@ -449,7 +914,7 @@ impl MmBs {
         };
 
         match pdu_type {
-            MmPduTypeUl::UAuthentication => unimplemented_log!("UAuthentication"),
+            MmPduTypeUl::UAuthentication => self.rx_u_authentication(queue, message),
             MmPduTypeUl::UItsiDetach => self.rx_u_itsi_detach(queue, message),
             MmPduTypeUl::ULocationUpdateDemand => self.rx_u_location_update_demand(queue, message),
             MmPduTypeUl::UMmStatus => self.rx_u_mm_status(queue, message),
@@ -583,6 +1048,91 @@ impl MmBs {
         queue.push_back(msg);
     }
 
+    fn send_d_authentication_demand(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, handle: u32, rand1: u128, rs: u128) {
+        let pdu = DAuthenticationDemand {
+            random_challenge: rand1,
+            random_seed: rs,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(167);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_mm_pdu(queue, dltime, issi, handle, sdu);
+    }
+
+    fn send_d_authentication_result(
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        result: bool,
+        response_value: Option<u32>,
+    ) {
+        let pdu = DAuthenticationResult {
+            authentication_result: result,
+            mutual_authentication_flag: response_value.is_some(),
+            response_value,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(41);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_mm_pdu(queue, dltime, issi, handle, sdu);
+    }
+
+    fn send_d_location_update_reject(
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        location_update_type: LocationUpdateType,
+        reject_cause: u8,
+    ) {
+        let pdu = DLocationUpdateReject {
+            location_update_type: location_update_type.into_raw() as u8,
+            reject_cause,
+            cipher_control: false,
+            ciphering_parameters: None,
+            address_extension: None,
+            cell_type_control: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(14);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_mm_pdu(queue, dltime, issi, handle, sdu);
+    }
+
+    /// Wraps an MM PDU in an LMM-UNITDATA request towards the given ISSI
+    fn send_mm_pdu(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, handle: u32, sdu: BitBuffer) {
+        let addr = TetraAddress {
+            encrypted: false,
+            ssi_type: SsiType::Ssi,
+            ssi: issi,
+        };
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+                sdu,
+                handle,
+                address: addr,
+                layer2service: Layer2Service::Todo,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                encryption_flag: false,
+                is_null_pdu: false,
+                tx_reporter: None,
+            }),
+        };
+        queue.push_back(msg);
+    }
+
     fn feature_check_u_itsi_detach(pdu: &UItsiDetach) -> bool {
         let supported = true;
         if pdu.address_extension.is_some() {
@@ -632,9 +1182,6 @@ impl MmBs {
         if pdu.group_report_response.is_some() {
             unimplemented_log!("Unsupported group_report_response present");
         }
-        if pdu.authentication_uplink.is_some() {
-            unimplemented_log!("Unsupported authentication_uplink present");
-        }
         if pdu.extended_capabilities.is_some() {
             unimplemented_log!("Unsupported extended_capabilities present");
         }
@@ -676,11 +1223,39 @@ impl TetraEntityTrait for MmBs {
         self.config = config;
     }
 
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        if let Some(registrations) = self.pending_restore.take() {
+            self.restore_registrations(queue, ts, registrations);
+        }
+
+        for issi in self.auth.expire(ts) {
+            tracing::warn!("MS {} did not answer authentication challenge, registration abandoned", issi);
+        }
+
+        if ts.f == 1
+            && ts.t == 1
+            && let Some(db) = self.subscriber_db.as_mut().and_then(|file| file.load_if_changed())
+        {
+            self.client_mgr.set_subscriber_db(Some(db));
+        }
+
+        if ts.f == 1 && ts.t == 1 {
+            self.save_registrations();
+        }
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for MM
+        // Besides the LMM SAP, MM only takes subscriber updates from the backhaul
+        if message.sap == Sap::Control {
+            let SapMsgInner::MmSubscriberUpdate(update) = message.msg else {
+                panic!("Unexpected control message: {:?}", message.msg);
+            };
+            self.rx_backhaul_subscriber_update(queue, message.dltime, update);
+            return;
+        }
         assert!(message.sap == Sap::LmmSap);
 
         match message.msg {
@@ -1,22 +1,433 @@
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{Sap, unimplemented_log};
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, unimplemented_log};
+use tetra_saps::lmm::LmmMleUnitdataReq;
+use tetra_saps::tnmm::TnmmRegistrationInd;
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
 use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::fields::group_identity_downlink::GroupIdentityDownlink;
+use tetra_pdus::mm::fields::group_identity_location_demand::GroupIdentityLocationDemand;
+use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity::DAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_command::DLocationUpdateCommand;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+
+/// Time to wait for an answer to a U-LOCATION UPDATE DEMAND or U-ATTACH/DETACH GROUP IDENTITY, in timeslots (~5 s)
+const MM_RESPONSE_TIMEOUT: i32 = 5 * 18 * 4;
+/// Number of times a request is sent before we give up until the next cell selection
+const MM_MAX_ATTEMPTS: u8 = 3;
+
+/// Outstanding request towards the SwMI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MmPending {
+    LocationUpdate(LocationUpdateType),
+    GroupAttach,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MmRequest {
+    kind: MmPending,
+    attempts: u8,
+    deadline: TdmaTime,
+}
 
 pub struct MmMs {
-    // config: Option<SharedConfig>,
     config: SharedConfig,
+    issi: u32,
+
+    /// Last seen downlink time, used as timebase for the response timers
+    dltime: TdmaTime,
+    /// Whether the SwMI accepted our ITSI attach. Later registrations are roaming location updates
+    registered: bool,
+    /// Groups the SwMI confirmed our attachment to
+    attached_groups: Vec<u32>,
+    pending: Option<MmRequest>,
 }
 
 impl MmMs {
     pub fn new(config: SharedConfig) -> Self {
-        Self { config }
+        let issi = config.config().ms.as_ref().expect("MS stack requires ms config").issi;
+        Self {
+            config,
+            issi,
+            dltime: TdmaTime::default(),
+            registered: false,
+            attached_groups: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Group identities we want to be attached to, from the configuration
+    fn wanted_groups(&self) -> Vec<u32> {
+        self.config.config().ms.as_ref().map(|ms| ms.groups.clone()).unwrap_or_default()
+    }
+
+    fn group_identity_uplink(&self) -> Option<Vec<GroupIdentityUplink>> {
+        let groups = self.wanted_groups();
+        if groups.is_empty() {
+            return None;
+        }
+        Some(
+            groups
+                .into_iter()
+                .map(|gssi| GroupIdentityUplink {
+                    class_of_usage: Some(4), // Ordinary group, no priority
+                    group_identity_detachment_uplink: None,
+                    gssi: Some(gssi),
+                    address_extension: None,
+                    vgssi: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn rx_lmm_mle_activate_conf(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::LmmMleActivateConf(prim) = &message.msg else {
+            panic!()
+        };
+
+        if prim.registration_required {
+            let lu_type = if self.registered {
+                LocationUpdateType::RoamingLocationUpdating
+            } else {
+                LocationUpdateType::ItsiAttach
+            };
+            tracing::info!("Registering in LA {} ({})", prim.la, lu_type);
+            self.start_request(queue, MmPending::LocationUpdate(lu_type));
+        } else {
+            // Cell does not want registrations; consider ourselves registered and attach to our groups directly
+            tracing::info!("Registration not required in LA {}", prim.la);
+            self.registered = true;
+            self.send_registration_ind(queue);
+            if self.group_identity_uplink().is_some() {
+                self.start_request(queue, MmPending::GroupAttach);
+            }
+        }
+    }
+
+    /// Sends the first attempt of a request and arms its response timer
+    fn start_request(&mut self, queue: &mut MessageQueue, kind: MmPending) {
+        self.pending = Some(MmRequest {
+            kind,
+            attempts: 0,
+            deadline: self.dltime,
+        });
+        self.send_pending(queue);
+    }
+
+    fn send_pending(&mut self, queue: &mut MessageQueue) {
+        let Some(req) = self.pending.as_mut() else {
+            return;
+        };
+        req.attempts += 1;
+        req.deadline = self.dltime.add_timeslots(MM_RESPONSE_TIMEOUT);
+        let kind = req.kind;
+
+        match kind {
+            MmPending::LocationUpdate(lu_type) => self.send_u_location_update_demand(queue, lu_type),
+            MmPending::GroupAttach => self.send_u_attach_detach_group_identity(queue),
+        }
+    }
+
+    fn check_pending_timeout(&mut self, queue: &mut MessageQueue) {
+        let Some(req) = self.pending else {
+            return;
+        };
+        if self.dltime.diff(req.deadline) < 0 {
+            return;
+        }
+        if req.attempts < MM_MAX_ATTEMPTS {
+            tracing::warn!("No response to {:?}, retrying ({}/{})", req.kind, req.attempts + 1, MM_MAX_ATTEMPTS);
+            self.send_pending(queue);
+            return;
+        }
+
+        tracing::warn!("No response to {:?} after {} attempts, giving up", req.kind, req.attempts);
+        self.pending = None;
+        if matches!(req.kind, MmPending::LocationUpdate(_)) {
+            self.registered = false;
+            self.attached_groups.clear();
+            self.send_registration_ind(queue);
+        }
+    }
+
+    fn send_u_location_update_demand(&mut self, queue: &mut MessageQueue, lu_type: LocationUpdateType) {
+        let pdu = ULocationUpdateDemand {
+            location_update_type: lu_type,
+            request_to_append_la: false,
+            cipher_control: false,
+            ciphering_parameters: None,
+            class_of_ms: None,
+            energy_saving_mode: None,
+            la_information: None,
+            ssi: None,
+            address_extension: None,
+            group_identity_location_demand: self.group_identity_uplink().map(|giu| GroupIdentityLocationDemand {
+                group_identity_attach_detach_mode: 1, // Replace any previous attachments
+                group_identity_uplink: Some(giu),
+            }),
+            group_report_response: None,
+            authentication_uplink: None,
+            extended_capabilities: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        self.send_mm_pdu(queue, sdu);
+    }
+
+    fn send_u_attach_detach_group_identity(&mut self, queue: &mut MessageQueue) {
+        let pdu = UAttachDetachGroupIdentity {
+            group_identity_report: false,
+            group_identity_attach_detach_mode: true, // Replace any previous attachments
+            group_report_response: None,
+            group_identity_uplink: self.group_identity_uplink(),
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+        self.send_mm_pdu(queue, sdu);
+    }
+
+    /// Wraps an MM PDU in an LMM-UNITDATA request towards the SwMI
+    fn send_mm_pdu(&self, queue: &mut MessageQueue, sdu: BitBuffer) {
+        let addr = TetraAddress {
+            encrypted: false,
+            ssi_type: SsiType::Ssi,
+            ssi: self.issi,
+        };
+        queue.push_back(SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Mle,
+            dltime: self.dltime,
+            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+                sdu,
+                handle: 0,
+                address: addr,
+                layer2service: Layer2Service::Todo,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                encryption_flag: false,
+                is_null_pdu: false,
+                tx_reporter: None,
+            }),
+        });
+    }
+
+    /// Informs the user of our current registration state
+    fn send_registration_ind(&self, queue: &mut MessageQueue) {
+        queue.push_back(SapMsg {
+            sap: Sap::TnmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::User,
+            dltime: self.dltime,
+            msg: SapMsgInner::TnmmRegistrationInd(TnmmRegistrationInd {
+                issi: self.issi,
+                registered: self.registered,
+                groups: self.attached_groups.clone(),
+            }),
+        });
+    }
+
+    /// Applies a list of Group identity downlink elements to our attached groups
+    fn apply_group_identity_downlink(&mut self, gid_vec: &[GroupIdentityDownlink]) {
+        for gid in gid_vec {
+            let Some(gssi) = gid.gssi else {
+                unimplemented_log!("Only support GroupIdentityDownlink with gssi");
+                continue;
+            };
+            let is_attach = gid.group_identity_attachment.is_some();
+            let known = self.attached_groups.contains(&gssi);
+            if is_attach && !known {
+                self.attached_groups.push(gssi);
+            } else if !is_attach && known {
+                self.attached_groups.retain(|&g| g != gssi);
+            }
+        }
+    }
+
+    fn rx_d_location_update_accept(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DLocationUpdateAccept::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DLocationUpdateAccept: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        if !matches!(
+            self.pending,
+            Some(MmRequest {
+                kind: MmPending::LocationUpdate(_),
+                ..
+            })
+        ) {
+            tracing::debug!("Unsolicited D-LOCATION UPDATE ACCEPT");
+        }
+        self.pending = None;
+        self.registered = true;
+
+        match pdu.group_identity_location_accept {
+            Some(gila) => {
+                self.attached_groups.clear();
+                if gila.group_identity_accept_reject != 0 {
+                    tracing::warn!("SwMI rejected our group attachments");
+                }
+                self.apply_group_identity_downlink(gila.group_identity_downlink.as_deref().unwrap_or_default());
+            }
+            None if self.group_identity_uplink().is_some() => {
+                // Groups were not handled as part of the registration, attach separately
+                self.start_request(queue, MmPending::GroupAttach);
+            }
+            None => {}
+        }
+
+        tracing::info!("Registered as ISSI {}, attached to groups {:?}", self.issi, self.attached_groups);
+        self.send_registration_ind(queue);
     }
 
-    fn rx_lmm_mle_unitdata_ind(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    fn rx_d_location_update_reject(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DLocationUpdateReject::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DLocationUpdateReject: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        // No automatic retry; a rejection stands until the next cell selection
+        tracing::warn!("Registration rejected with cause {}", pdu.reject_cause);
+        self.pending = None;
+        self.registered = false;
+        self.attached_groups.clear();
+        self.send_registration_ind(queue);
+    }
+
+    fn rx_d_location_update_command(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DLocationUpdateCommand::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DLocationUpdateCommand: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+        if pdu.cipher_control {
+            unimplemented_log!("Unsupported cipher_control == true");
+        }
+
+        // Our demand always carries the full group report
+        tracing::info!("SwMI demands location update");
+        self.start_request(queue, MmPending::LocationUpdate(LocationUpdateType::DemandLocationUpdating));
+    }
+
+    fn rx_d_attach_detach_group_identity(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DAttachDetachGroupIdentity::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DAttachDetachGroupIdentity: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        if pdu.group_identity_attach_detach_mode {
+            self.attached_groups.clear();
+        }
+        self.apply_group_identity_downlink(pdu.group_identity_downlink.as_deref().unwrap_or_default());
+        tracing::info!("SwMI changed our group attachments to {:?}", self.attached_groups);
+        self.send_registration_ind(queue);
+
+        if pdu.group_identity_acknowledgement_request {
+            let ack = UAttachDetachGroupIdentityAcknowledgement {
+                group_identity_acknowledgement_type: false, // Accept
+                group_identity_uplink: None,
+                proprietary: None,
+            };
+            let mut sdu = BitBuffer::new_autoexpand(8);
+            ack.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+            sdu.seek(0);
+            tracing::debug!("-> {:?} sdu {}", ack, sdu.dump_bin());
+            self.send_mm_pdu(queue, sdu);
+        }
+    }
+
+    fn rx_d_attach_detach_group_identity_ack(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let pdu = match DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed parsing DAttachDetachGroupIdentityAcknowledgement: {:?} {}",
+                    e,
+                    prim.sdu.dump_bin()
+                );
+                return;
+            }
+        };
+
+        if matches!(
+            self.pending,
+            Some(MmRequest {
+                kind: MmPending::GroupAttach,
+                ..
+            })
+        ) {
+            self.pending = None;
+        }
+        if pdu.group_identity_accept_reject != 0 {
+            tracing::warn!("SwMI rejected our group attachments");
+        }
+        // We always send attach/detach mode 1, so the answer replaces our previous attachments
+        self.attached_groups.clear();
+        self.apply_group_identity_downlink(pdu.group_identity_downlink.as_deref().unwrap_or_default());
+        tracing::info!("Attached to groups {:?}", self.attached_groups);
+        self.send_registration_ind(queue);
+    }
+
+    fn rx_lmm_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
@@ -37,12 +448,12 @@ impl MmMs {
             MmPduTypeDl::DCkChangeDemand => unimplemented_log!("DCkChangeDemand"),
             MmPduTypeDl::DDisable => unimplemented_log!("DDisable"),
             MmPduTypeDl::DEnable => unimplemented_log!("DEnable"),
-            MmPduTypeDl::DLocationUpdateAccept => unimplemented_log!("DLocationUpdateAccept"),
-            MmPduTypeDl::DLocationUpdateCommand => unimplemented_log!("DLocationUpdateCommand"),
-            MmPduTypeDl::DLocationUpdateReject => unimplemented_log!("DLocationUpdateReject"),
+            MmPduTypeDl::DLocationUpdateAccept => self.rx_d_location_update_accept(queue, message),
+            MmPduTypeDl::DLocationUpdateCommand => self.rx_d_location_update_command(queue, message),
+            MmPduTypeDl::DLocationUpdateReject => self.rx_d_location_update_reject(queue, message),
             MmPduTypeDl::DLocationUpdateProceeding => unimplemented_log!("DLocationUpdateProceeding"),
-            MmPduTypeDl::DAttachDetachGroupIdentity => unimplemented_log!("DAttachDetachGroupIdentity"),
-            MmPduTypeDl::DAttachDetachGroupIdentityAcknowledgement => unimplemented_log!("DAttachDetachGroupIdentityAcknowledgement"),
+            MmPduTypeDl::DAttachDetachGroupIdentity => self.rx_d_attach_detach_group_identity(queue, message),
+            MmPduTypeDl::DAttachDetachGroupIdentityAcknowledgement => self.rx_d_attach_detach_group_identity_ack(queue, message),
             MmPduTypeDl::DMmStatus => unimplemented_log!("DMmStatus"),
             MmPduTypeDl::MmPduFunctionNotSupported => unimplemented_log!("MmPduFunctionNotSupported"),
         };
@@ -58,6 +469,11 @@ impl TetraEntityTrait for MmMs {
         self.config = config;
     }
 
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+        self.check_pending_timeout(queue);
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
@@ -69,8 +485,11 @@ impl TetraEntityTrait for MmMs {
             SapMsgInner::LmmMleUnitdataInd(_) => {
                 self.rx_lmm_mle_unitdata_ind(queue, message);
             }
+            SapMsgInner::LmmMleActivateConf(_) => {
+                self.rx_lmm_mle_activate_conf(queue, message);
+            }
             _ => {
-                panic!();
+                unimplemented_log!("rx_prim: {:?}", message.msg);
             }
         }
     }
@@ -0,0 +1,308 @@
+use tetra_core::{BitBuffer, Direction, TetraAddress};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use tetra_pdus::cmce::pdus::d_connect::DConnect;
+use tetra_pdus::cmce::pdus::d_disconnect::DDisconnect;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::d_status::DStatus;
+use tetra_pdus::cmce::pdus::d_tx_granted::DTxGranted;
+use tetra_pdus::cmce::pdus::u_disconnect::UDisconnect;
+use tetra_pdus::cmce::pdus::u_release::URelease;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::cmce::pdus::u_status::UStatus;
+use tetra_pdus::llc::enums::llc_pdu_type::LlcPduType;
+use tetra_pdus::llc::pdus::bl_adata::BlAdata;
+use tetra_pdus::llc::pdus::bl_data::BlData;
+use tetra_pdus::llc::pdus::bl_udata::BlUdata;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity::DAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+
+use crate::llc::components::fcs;
+use crate::monitor::events::MonitorEvent;
+
+/// Decodes a TM-SDU through the LLC and MLE into an MM or CMCE event, if it carries one of interest.
+/// The address is the one the MAC PDU was sent to (downlink) or by (uplink).
+pub fn decode_tm_sdu(sdu: BitBuffer, addr: TetraAddress, direction: Direction) -> Option<MonitorEvent> {
+    let tl_sdu = decode_llc(sdu)?;
+    decode_mle(tl_sdu, addr.ssi, direction)
+}
+
+/// Strips the basic link header and FCS, returning the TL-SDU
+fn decode_llc(mut pdu: BitBuffer) -> Option<BitBuffer> {
+    let bits = pdu.peek_bits(4)?;
+    let Ok(pdu_type) = LlcPduType::try_from(bits) else {
+        tracing::debug!("decode_llc: invalid pdu type {}", bits);
+        return None;
+    };
+
+    let has_fcs = match pdu_type {
+        LlcPduType::BlAdata | LlcPduType::BlAdataFcs => BlAdata::from_bitbuf(&mut pdu).ok()?.has_fcs,
+        LlcPduType::BlData | LlcPduType::BlDataFcs => BlData::from_bitbuf(&mut pdu).ok()?.has_fcs,
+        LlcPduType::BlUdata | LlcPduType::BlUdataFcs => BlUdata::from_bitbuf(&mut pdu).ok()?.has_fcs,
+        _ => {
+            // Acknowledgements carry no payload; the advanced link is not followed
+            tracing::trace!("decode_llc: skipping {}", pdu_type);
+            return None;
+        }
+    };
+
+    if has_fcs {
+        if !fcs::check_fcs(&pdu) {
+            tracing::debug!("decode_llc: FCS check failed");
+            return None;
+        }
+        pdu.set_raw_end(pdu.get_raw_end() - 32);
+    }
+    pdu.set_raw_start(pdu.get_raw_pos());
+    (pdu.get_len_remaining() > 0).then_some(pdu)
+}
+
+fn decode_mle(mut sdu: BitBuffer, ssi: u32, direction: Direction) -> Option<MonitorEvent> {
+    let bits = sdu.read_bits(3)?;
+    match MleProtocolDiscriminator::try_from(bits) {
+        Ok(MleProtocolDiscriminator::Mm) => match direction {
+            Direction::Dl => decode_mm_dl(sdu, ssi),
+            _ => decode_mm_ul(sdu, ssi),
+        },
+        Ok(MleProtocolDiscriminator::Cmce) => match direction {
+            Direction::Dl => decode_cmce_dl(sdu, ssi),
+            _ => decode_cmce_ul(sdu, ssi),
+        },
+        other => {
+            tracing::trace!("decode_mle: skipping {:?}", other);
+            None
+        }
+    }
+}
+
+fn decode_mm_dl(mut sdu: BitBuffer, ssi: u32) -> Option<MonitorEvent> {
+    let pdu_type = MmPduTypeDl::try_from(sdu.peek_bits(4)?).ok()?;
+    let event = match pdu_type {
+        MmPduTypeDl::DLocationUpdateAccept => {
+            let pdu = parse(DLocationUpdateAccept::from_bitbuf(&mut sdu))?;
+            let groups = pdu
+                .group_identity_location_accept
+                .and_then(|gila| gila.group_identity_downlink)
+                .unwrap_or_default()
+                .iter()
+                .filter(|gid| gid.group_identity_attachment.is_some())
+                .filter_map(|gid| gid.gssi)
+                .collect();
+            MonitorEvent::RegistrationAccept {
+                issi: pdu.ssi.map(|s| s as u32).unwrap_or(ssi),
+                accept_type: format!("{:?}", pdu.location_update_accept_type),
+                groups,
+            }
+        }
+        MmPduTypeDl::DLocationUpdateReject => {
+            let pdu = parse(DLocationUpdateReject::from_bitbuf(&mut sdu))?;
+            MonitorEvent::RegistrationReject {
+                issi: ssi,
+                cause: pdu.reject_cause,
+            }
+        }
+        MmPduTypeDl::DAttachDetachGroupIdentity => {
+            let pdu = parse(DAttachDetachGroupIdentity::from_bitbuf(&mut sdu))?;
+            let gids = pdu.group_identity_downlink.unwrap_or_default();
+            MonitorEvent::GroupAttach {
+                issi: ssi,
+                attach: gids
+                    .iter()
+                    .filter(|gid| gid.group_identity_attachment.is_some())
+                    .filter_map(|gid| gid.gssi)
+                    .collect(),
+                detach: gids
+                    .iter()
+                    .filter(|gid| gid.group_identity_detachment_uplink.is_some())
+                    .filter_map(|gid| gid.gssi)
+                    .collect(),
+            }
+        }
+        _ => {
+            tracing::trace!("decode_mm_dl: skipping {:?}", pdu_type);
+            return None;
+        }
+    };
+    Some(event)
+}
+
+fn decode_mm_ul(mut sdu: BitBuffer, ssi: u32) -> Option<MonitorEvent> {
+    let pdu_type = MmPduTypeUl::try_from(sdu.peek_bits(4)?).ok()?;
+    let event = match pdu_type {
+        MmPduTypeUl::ULocationUpdateDemand => {
+            let pdu = parse(ULocationUpdateDemand::from_bitbuf(&mut sdu))?;
+            let groups = pdu
+                .group_identity_location_demand
+                .and_then(|gild| gild.group_identity_uplink)
+                .unwrap_or_default()
+                .iter()
+                .filter(|gid| gid.group_identity_detachment_uplink.is_none())
+                .filter_map(|gid| gid.gssi)
+                .collect();
+            MonitorEvent::RegistrationDemand {
+                issi: pdu.ssi.map(|s| s as u32).unwrap_or(ssi),
+                update_type: format!("{:?}", pdu.location_update_type),
+                groups,
+            }
+        }
+        MmPduTypeUl::UItsiDetach => MonitorEvent::Detach { issi: ssi },
+        MmPduTypeUl::UAttachDetachGroupIdentity => {
+            let pdu = parse(UAttachDetachGroupIdentity::from_bitbuf(&mut sdu))?;
+            let gids = pdu.group_identity_uplink.unwrap_or_default();
+            let (detach, attach): (Vec<_>, Vec<_>) = gids.iter().partition(|gid| gid.group_identity_detachment_uplink.is_some());
+            MonitorEvent::GroupAttachDemand {
+                issi: ssi,
+                attach: attach.iter().filter_map(|gid| gid.gssi).collect(),
+                detach: detach.iter().filter_map(|gid| gid.gssi).collect(),
+            }
+        }
+        _ => {
+            tracing::trace!("decode_mm_ul: skipping {:?}", pdu_type);
+            return None;
+        }
+    };
+    Some(event)
+}
+
+fn decode_cmce_dl(mut sdu: BitBuffer, ssi: u32) -> Option<MonitorEvent> {
+    let pdu_type = CmcePduTypeDl::try_from(sdu.peek_bits(5)?).ok()?;
+    let event = match pdu_type {
+        CmcePduTypeDl::DSetup => {
+            let pdu = parse(DSetup::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallSetup {
+                call_id: pdu.call_identifier,
+                called: ssi,
+                calling: pdu.calling_party_address_ssi,
+                priority: pdu.call_priority,
+            }
+        }
+        CmcePduTypeDl::DConnect => {
+            let pdu = parse(DConnect::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallConnect {
+                call_id: pdu.call_identifier,
+                issi: ssi,
+            }
+        }
+        CmcePduTypeDl::DTxGranted => {
+            let pdu = parse(DTxGranted::from_bitbuf(&mut sdu))?;
+            MonitorEvent::TxGranted {
+                call_id: pdu.call_identifier,
+                talker: pdu.transmitting_party_address_ssi.map(|s| s as u32),
+                grant: pdu.transmission_grant,
+            }
+        }
+        CmcePduTypeDl::DRelease => {
+            let pdu = parse(DRelease::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallRelease {
+                call_id: pdu.call_identifier,
+                issi: ssi,
+                cause: format!("{:?}", pdu.disconnect_cause),
+            }
+        }
+        CmcePduTypeDl::DDisconnect => {
+            let pdu = parse(DDisconnect::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallRelease {
+                call_id: pdu.call_identifier,
+                issi: ssi,
+                cause: format!("{:?}", pdu.disconnect_cause),
+            }
+        }
+        CmcePduTypeDl::DSdsData => {
+            let pdu = parse(DSdsData::from_bitbuf(&mut sdu))?;
+            sds_event(pdu.calling_party_address_ssi.map(|s| s as u32), Some(ssi), &pdu.user_defined_data)
+        }
+        CmcePduTypeDl::DStatus => {
+            let pdu = parse(DStatus::from_bitbuf(&mut sdu))?;
+            MonitorEvent::Status {
+                calling: pdu.calling_party_address_ssi.map(|s| s as u32),
+                called: Some(ssi),
+                status: pdu.pre_coded_status.into_raw(),
+            }
+        }
+        _ => {
+            tracing::trace!("decode_cmce_dl: skipping {:?}", pdu_type);
+            return None;
+        }
+    };
+    Some(event)
+}
+
+fn decode_cmce_ul(mut sdu: BitBuffer, ssi: u32) -> Option<MonitorEvent> {
+    let pdu_type = CmcePduTypeUl::try_from(sdu.peek_bits(5)?).ok()?;
+    let event = match pdu_type {
+        CmcePduTypeUl::USetup => {
+            let pdu = parse(USetup::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallSetupRequest {
+                calling: ssi,
+                called: pdu.called_party_ssi.map(|s| s as u32),
+                priority: pdu.call_priority,
+            }
+        }
+        CmcePduTypeUl::UDisconnect => {
+            let pdu = parse(UDisconnect::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallRelease {
+                call_id: pdu.call_identifier,
+                issi: ssi,
+                cause: format!("{:?}", pdu.disconnect_cause),
+            }
+        }
+        CmcePduTypeUl::URelease => {
+            let pdu = parse(URelease::from_bitbuf(&mut sdu))?;
+            MonitorEvent::CallRelease {
+                call_id: pdu.call_identifier,
+                issi: ssi,
+                cause: format!("{:?}", pdu.disconnect_cause),
+            }
+        }
+        CmcePduTypeUl::USdsData => {
+            let pdu = parse(USdsData::from_bitbuf(&mut sdu))?;
+            sds_event(Some(ssi), pdu.called_party_ssi.map(|s| s as u32), &pdu.user_defined_data)
+        }
+        CmcePduTypeUl::UStatus => {
+            let pdu = parse(UStatus::from_bitbuf(&mut sdu))?;
+            MonitorEvent::Status {
+                calling: Some(ssi),
+                called: pdu.called_party_ssi.map(|s| s as u32),
+                status: pdu.pre_coded_status.into_raw(),
+            }
+        }
+        _ => {
+            tracing::trace!("decode_cmce_ul: skipping {:?}", pdu_type);
+            return None;
+        }
+    };
+    Some(event)
+}
+
+fn sds_event(calling: Option<u32>, called: Option<u32>, user_data: &SdsUserData) -> MonitorEvent {
+    MonitorEvent::Sds {
+        calling,
+        called,
+        sds_type: user_data.type_identifier() + 1,
+        data: user_data.to_arr().iter().map(|b| format!("{:02x}", b)).collect(),
+    }
+}
+
+/// Logs and discards parse errors; a monitor sees plenty of PDUs we can't fully parse
+fn parse<T: core::fmt::Debug, E: core::fmt::Debug>(result: Result<T, E>) -> Option<T> {
+    match result {
+        Ok(pdu) => {
+            tracing::debug!("<- {:?}", pdu);
+            Some(pdu)
+        }
+        Err(e) => {
+            tracing::debug!("Failed parsing PDU: {:?}", e);
+            None
+        }
+    }
+}
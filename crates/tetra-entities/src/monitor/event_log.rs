@@ -0,0 +1,60 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use tetra_core::{Direction, TdmaTime};
+
+use crate::monitor::events::MonitorEvent;
+
+#[derive(Serialize)]
+struct EventLogRecord<'a> {
+    time: String,
+    tdma: String,
+    link: &'static str,
+    #[serde(flatten)]
+    event: &'a MonitorEvent,
+}
+
+/// Writes monitor events as JSON lines, one object per event
+pub struct EventLog {
+    writer: Box<dyn Write + Send>,
+}
+
+impl EventLog {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer }
+    }
+
+    /// Appends to the given file, creating it if needed
+    pub fn open(path: &str) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(Box::new(file)))
+    }
+
+    pub fn stdout() -> Self {
+        Self::new(Box::new(io::stdout()))
+    }
+
+    pub fn log(&mut self, dltime: TdmaTime, direction: Direction, event: &MonitorEvent) {
+        let record = EventLogRecord {
+            time: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            tdma: dltime.to_string(),
+            link: if direction == Direction::Ul { "ul" } else { "dl" },
+            event,
+        };
+        tracing::info!(ts=%dltime, "monitor {:?}: {:?}", direction, event);
+
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("EventLog: failed to serialize {:?}: {}", event, e);
+                return;
+            }
+        };
+        // Flush every line, so the log can be followed while the monitor runs
+        if let Err(e) = writeln!(self.writer, "{}", line).and_then(|_| self.writer.flush()) {
+            tracing::error!("EventLog: write failed: {}", e);
+        }
+    }
+}
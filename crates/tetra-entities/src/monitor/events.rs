@@ -0,0 +1,55 @@
+use serde::Serialize;
+
+/// Signalling event observed on the air interface, as written to the event log
+///
+/// SSIs are those of the MS or group the PDU was addressed to on the downlink, or sent by on the
+/// uplink, unless the PDU itself carries the other party.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MonitorEvent {
+    /// D-MLE-SYNC of a cell we (re)synchronized to
+    CellSync { mcc: u16, mnc: u16 },
+
+    /// U-LOCATION UPDATE DEMAND
+    RegistrationDemand { issi: u32, update_type: String, groups: Vec<u32> },
+    /// D-LOCATION UPDATE ACCEPT
+    RegistrationAccept { issi: u32, accept_type: String, groups: Vec<u32> },
+    /// D-LOCATION UPDATE REJECT
+    RegistrationReject { issi: u32, cause: u8 },
+    /// U-ITSI DETACH
+    Detach { issi: u32 },
+    /// U-ATTACH/DETACH GROUP IDENTITY
+    GroupAttachDemand { issi: u32, attach: Vec<u32>, detach: Vec<u32> },
+    /// D-ATTACH/DETACH GROUP IDENTITY
+    GroupAttach { issi: u32, attach: Vec<u32>, detach: Vec<u32> },
+
+    /// U-SETUP
+    CallSetupRequest { calling: u32, called: Option<u32>, priority: u8 },
+    /// D-SETUP
+    CallSetup {
+        call_id: u16,
+        called: u32,
+        calling: Option<u32>,
+        priority: u8,
+    },
+    /// D-CONNECT
+    CallConnect { call_id: u16, issi: u32 },
+    /// D-TX GRANTED
+    TxGranted { call_id: u16, talker: Option<u32>, grant: u8 },
+    /// D-RELEASE, D-DISCONNECT, U-DISCONNECT or U-RELEASE
+    CallRelease { call_id: u16, issi: u32, cause: String },
+
+    /// D-SDS-DATA or U-SDS-DATA, with the SDS type (1 to 4) and the user data in hex
+    Sds {
+        calling: Option<u32>,
+        called: Option<u32>,
+        sds_type: u8,
+        data: String,
+    },
+    /// D-STATUS or U-STATUS
+    Status {
+        calling: Option<u32>,
+        called: Option<u32>,
+        status: u16,
+    },
+}
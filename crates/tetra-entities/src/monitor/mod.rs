@@ -0,0 +1,8 @@
+//! Passive monitor: decodes the signalling of an existing cell into a structured event log
+
+pub mod decoder;
+pub mod event_log;
+pub mod events;
+
+pub use event_log::EventLog;
+pub use events::MonitorEvent;
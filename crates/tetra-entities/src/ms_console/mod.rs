@@ -0,0 +1,3 @@
+pub mod net_entity_console_worker;
+
+pub use net_entity_console_worker::NetEntityConsoleWorker;
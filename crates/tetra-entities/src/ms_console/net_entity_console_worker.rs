@@ -0,0 +1,243 @@
+use crossbeam_channel::{Receiver, Sender, select};
+
+use crate::network::netentity::NetEntityWorker;
+use tetra_core::{TdmaTime, tetra_common::Sap, tetra_entities::TetraEntity};
+use tetra_saps::{
+    control::enums::sds_user_data::SdsUserData,
+    sapmsg::{SapMsg, SapMsgInner},
+    tncc::{TnccReleaseReq, TnccSetupReq, TnccTxReq, TnccTxState},
+    tnsds::{TnsdsUnitdataInd, TnsdsUnitdataReq},
+};
+
+/// SDS-TL protocol identifier for simple text messaging (Clause 29.5.2)
+const SDS_PI_SIMPLE_TEXT: u8 = 0x02;
+/// Text coding scheme ISO/IEC 8859-1 Latin 1 (Clause 29.5.4.1)
+const SDS_TEXT_CODING_LATIN1: u8 = 0x01;
+
+/// Command typed on the MS console
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConsoleCmd {
+    Sds { ssi: u32, text: String },
+    Call { gssi: u32 },
+    Ptt,
+    Unptt,
+    Release,
+    Help,
+}
+
+fn parse_command(line: &str) -> Result<ConsoleCmd, String> {
+    let line = line.trim();
+    let (cmd, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let parse_ssi = |s: &str| s.parse::<u32>().map_err(|_| format!("invalid ssi '{}'", s));
+    match cmd {
+        "sds" => {
+            let (ssi, text) = rest.split_once(char::is_whitespace).ok_or("usage: sds <ssi> <text>")?;
+            Ok(ConsoleCmd::Sds {
+                ssi: parse_ssi(ssi)?,
+                text: text.trim().to_string(),
+            })
+        }
+        "call" => Ok(ConsoleCmd::Call { gssi: parse_ssi(rest)? }),
+        "ptt" => Ok(ConsoleCmd::Ptt),
+        "unptt" => Ok(ConsoleCmd::Unptt),
+        "release" => Ok(ConsoleCmd::Release),
+        "help" | "?" => Ok(ConsoleCmd::Help),
+        _ => Err(format!("unknown command '{}', try 'help'", cmd)),
+    }
+}
+
+/// Wraps text as an SDS-TL simple text message in type 4 user data
+fn text_to_sds(text: &str) -> SdsUserData {
+    let mut data = vec![SDS_PI_SIMPLE_TEXT, SDS_TEXT_CODING_LATIN1];
+    // Latin 1 maps onto the first 256 code points; anything else becomes '?'
+    data.extend(text.chars().map(|c| u8::try_from(c as u32).unwrap_or(b'?')));
+    SdsUserData::Type4(data.len() as u16 * 8, data)
+}
+
+/// Renders received user data; simple text messages as text, anything else as hex
+fn sds_to_string(user_data: &SdsUserData) -> String {
+    match user_data {
+        SdsUserData::Type4(_, data) if data.len() >= 2 && data[0] == SDS_PI_SIMPLE_TEXT => {
+            format!("\"{}\"", data[2..].iter().map(|&b| b as char).collect::<String>())
+        }
+        SdsUserData::Type1(v) => format!("status 0x{:04x}", v),
+        other => format!(
+            "type {} data {}",
+            other.type_identifier(),
+            other.to_arr().iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        ),
+    }
+}
+
+/// Worker thread driving the MS stack from text commands, acting as CC, SDS and MM user
+///
+/// Command lines come from the transport channel, typically fed from stdin. Indications from the
+/// stack are printed on stdout.
+pub struct NetEntityConsoleWorker {
+    /// Debug label for this worker
+    label: &'static str,
+    /// TETRA entity type this network entity represents
+    entity_self: TetraEntity,
+    /// Destination entity, the other entity on our SAP
+    entity_dest: TetraEntity,
+    /// Command lines typed by the user
+    lines: Receiver<String>,
+    /// Call we take part in, as indicated by the CMCE
+    call_id: Option<u16>,
+    /// Requests receiver from main thread
+    e2w_receiver: Receiver<SapMsg>,
+    /// Response sender back to main thread
+    w2e_sender: Sender<SapMsg>,
+}
+
+impl NetEntityWorker for NetEntityConsoleWorker {
+    type Transport = Receiver<String>;
+
+    fn new(
+        entity_self: TetraEntity,
+        entity_dest: TetraEntity,
+        _sap: Sap,
+        w2e_sender: Sender<SapMsg>,
+        e2w_receiver: Receiver<SapMsg>,
+        transport: Self::Transport,
+    ) -> Self {
+        Self {
+            label: "NetEntityConsoleWorker",
+            entity_self,
+            entity_dest,
+            lines: transport,
+            call_id: None,
+            w2e_sender,
+            e2w_receiver,
+        }
+    }
+
+    fn run(&mut self) {
+        tracing::info!("{} thread started", self.label);
+        println!("MS console ready, type 'help' for commands");
+
+        loop {
+            select! {
+                recv(self.e2w_receiver) -> msg => match msg {
+                    Ok(msg) => self.handle_stack_message(msg),
+                    Err(_) => break,
+                },
+                recv(self.lines) -> line => match line {
+                    Ok(line) => self.handle_line(&line),
+                    Err(_) => {
+                        // Input closed, keep printing indications until the stack goes away
+                        while let Ok(msg) = self.e2w_receiver.recv() {
+                            self.handle_stack_message(msg);
+                        }
+                        break;
+                    }
+                },
+            }
+        }
+        tracing::info!("{} thread stopped", self.label);
+    }
+}
+
+impl NetEntityConsoleWorker {
+    fn send(&self, sap: Sap, msg: SapMsgInner) {
+        let msg = SapMsg {
+            sap,
+            src: self.entity_self,
+            dest: self.entity_dest,
+            dltime: TdmaTime::default(),
+            msg,
+        };
+        if let Err(e) = self.w2e_sender.send(msg) {
+            tracing::error!("{} failed to send to main thread: {:?}", self.label, e);
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        let cmd = match parse_command(line) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+
+        match cmd {
+            ConsoleCmd::Sds { ssi, text } => {
+                self.send(
+                    Sap::TnsdsSap,
+                    SapMsgInner::TnsdsUnitdataReq(TnsdsUnitdataReq {
+                        called_ssi: ssi,
+                        user_data: text_to_sds(&text),
+                    }),
+                );
+            }
+            ConsoleCmd::Call { gssi } => {
+                self.send(Sap::TnccSap, SapMsgInner::TnccSetupReq(TnccSetupReq { called_ssi: gssi }));
+            }
+            ConsoleCmd::Ptt | ConsoleCmd::Unptt | ConsoleCmd::Release => {
+                let Some(call_id) = self.call_id else {
+                    println!("not in a call");
+                    return;
+                };
+                let msg = match cmd {
+                    ConsoleCmd::Ptt => SapMsgInner::TnccTxReq(TnccTxReq { call_id, demand: true }),
+                    ConsoleCmd::Unptt => SapMsgInner::TnccTxReq(TnccTxReq { call_id, demand: false }),
+                    _ => SapMsgInner::TnccReleaseReq(TnccReleaseReq { call_id }),
+                };
+                self.send(Sap::TnccSap, msg);
+            }
+            ConsoleCmd::Help => {
+                println!("  sds <ssi> <text>   send a text message");
+                println!("  call <gssi>        start a group call");
+                println!("  ptt / unptt        request / release transmission");
+                println!("  release            leave the current call");
+            }
+        }
+    }
+
+    fn handle_stack_message(&mut self, msg: SapMsg) {
+        match msg.msg {
+            SapMsgInner::TnmmRegistrationInd(ind) => {
+                if ind.registered {
+                    println!("registered as {}, groups {:?}", ind.issi, ind.groups);
+                } else {
+                    println!("not registered");
+                }
+            }
+            SapMsgInner::TnsdsUnitdataInd(TnsdsUnitdataInd {
+                calling_ssi,
+                called_ssi,
+                user_data,
+            }) => {
+                println!("sds {} -> {}: {}", calling_ssi, called_ssi, sds_to_string(&user_data));
+            }
+            SapMsgInner::TnccSetupInd(ind) => {
+                self.call_id = Some(ind.call_id);
+                println!("call {}: {} calling group {}", ind.call_id, ind.calling_ssi, ind.called_ssi);
+            }
+            SapMsgInner::TnccSetupCon(con) => {
+                self.call_id = Some(con.call_id);
+                println!("call {}: connected to group {}", con.call_id, con.called_ssi);
+            }
+            SapMsgInner::TnccTxInd(ind) => match (ind.state, ind.talker_ssi) {
+                (TnccTxState::Granted, _) => println!("call {}: transmit", ind.call_id),
+                (TnccTxState::OtherParty, Some(talker)) => println!("call {}: {} talking", ind.call_id, talker),
+                (state, _) => println!("call {}: {:?}", ind.call_id, state),
+            },
+            SapMsgInner::TnccReleaseInd(ind) => {
+                if ind.call_id.is_none() || ind.call_id == self.call_id {
+                    self.call_id = None;
+                }
+                println!("call {:?}: released, cause {}", ind.call_id, ind.cause);
+            }
+            SapMsgInner::TmdCircuitDataInd(_) => {
+                // No audio path on the console
+            }
+            inner => tracing::debug!("{} ignoring {:?}", self.label, inner),
+        }
+    }
+}
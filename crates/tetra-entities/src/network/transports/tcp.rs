@@ -1,5 +1,5 @@
 use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::time::{Duration, Instant};
 
 use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport};
@@ -245,3 +245,125 @@ impl NetworkTransport for TcpTransport {
         }
     }
 }
+
+/// Largest message accepted on a TCP connection
+const TCP_MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+/// Server side of the TCP transport: accepts connections from any number of clients and receives the
+/// length-prefixed messages they send with TcpTransport. Replies, if any, go over a connection of our own.
+pub struct TcpServerTransport {
+    listener: TcpListener,
+    connections: Vec<TcpServerConnection>,
+}
+
+struct TcpServerConnection {
+    stream: TcpStream,
+    source: NetworkAddress,
+    /// Received bytes not yet forming a complete message
+    buffer: Vec<u8>,
+}
+
+impl TcpServerTransport {
+    /// Start listening for connections on the given address
+    pub fn bind(addr: SocketAddr) -> Result<Self, NetworkError> {
+        let listener =
+            TcpListener::bind(addr).map_err(|e| NetworkError::ConnectionFailed(format!("Failed to listen on {}: {}", addr, e)))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| NetworkError::ConnectionFailed(format!("Failed to set non-blocking mode: {}", e)))?;
+        Ok(Self {
+            listener,
+            connections: Vec::new(),
+        })
+    }
+
+    /// Address the server listens on, useful when bound to port 0
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr().ok()
+    }
+
+    /// Accept pending connections and receive pending messages from all clients (non-blocking)
+    pub fn receive_reliable(&mut self) -> Vec<NetworkMessage> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, peer)) => {
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        tracing::warn!("TcpServerTransport: dropping connection from {}: {}", peer, e);
+                        continue;
+                    }
+                    tracing::debug!("TcpServerTransport: accepted connection from {}", peer);
+                    self.connections.push(TcpServerConnection {
+                        stream,
+                        source: NetworkAddress::Tcp {
+                            host: peer.ip().to_string(),
+                            port: peer.port(),
+                        },
+                        buffer: Vec::new(),
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    tracing::warn!("TcpServerTransport: accept failed: {}", e);
+                    break;
+                }
+            }
+        }
+
+        let mut messages = Vec::new();
+        self.connections.retain_mut(|conn| {
+            let open = conn.read_available();
+            conn.take_messages(&mut messages) && open
+        });
+        messages
+    }
+}
+
+impl TcpServerConnection {
+    /// Reads all bytes available on the connection. Returns false once the connection is closed.
+    fn read_available(&mut self) -> bool {
+        let mut chunk = [0u8; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => {
+                    tracing::debug!("TcpServerTransport: connection from {:?} closed", self.source);
+                    return false;
+                }
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return true,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    tracing::debug!("TcpServerTransport: connection from {:?} failed: {}", self.source, e);
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Moves the complete messages out of the receive buffer. Returns false if the client sent garbage.
+    fn take_messages(&mut self, messages: &mut Vec<NetworkMessage>) -> bool {
+        let mut pos = 0;
+        while self.buffer.len() - pos >= 4 {
+            let len_bytes: [u8; 4] = self.buffer[pos..pos + 4].try_into().unwrap(); // Never fails
+            let payload_len = u32::from_be_bytes(len_bytes) as usize;
+            if payload_len > TCP_MAX_MESSAGE_LEN {
+                tracing::warn!(
+                    "TcpServerTransport: message too large from {:?}: {} bytes",
+                    self.source,
+                    payload_len
+                );
+                return false;
+            }
+            if self.buffer.len() - pos - 4 < payload_len {
+                break;
+            }
+            messages.push(NetworkMessage {
+                source: self.source.clone(),
+                payload: self.buffer[pos + 4..pos + 4 + payload_len].to_vec(),
+                timestamp: Instant::now(),
+            });
+            pos += 4 + payload_len;
+        }
+        self.buffer.drain(..pos);
+        true
+    }
+}
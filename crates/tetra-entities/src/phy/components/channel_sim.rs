@@ -0,0 +1,203 @@
+//! Radio channel model for simulated links: propagation delay, flat Rayleigh fading,
+//! carrier frequency offset and additive white Gaussian noise, applied in that order.
+//!
+//! Impairments are computed from the absolute sample counter and a seeded generator,
+//! so a simulation is reproducible regardless of how the samples are split into blocks.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::dsp_types::*;
+
+/// Power of a continuously transmitted carrier at the output of the modulator, per sample.
+/// Does not depend on the sample rate.
+pub const CARRIER_POWER: f64 = 1.0 / 16.0;
+
+/// TETRA symbol rate, used as the noise bandwidth for Es/N0
+const SYMBOL_RATE: f64 = 18000.0;
+
+/// Number of sinusoids summed for Rayleigh fading
+const FADING_PATHS: usize = 16;
+
+/// Impairments of one direction of a simulated link.
+/// The default is an ideal channel.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelParams {
+    /// Es/N0 in dB, as carrier power over noise power in the symbol rate bandwidth.
+    /// No noise is added if not set
+    pub es_n0_db: Option<f64>,
+    /// Carrier frequency offset in Hz
+    pub freq_offset_hz: f64,
+    /// Propagation delay in samples
+    pub delay_samples: usize,
+    /// Maximum Doppler frequency in Hz of flat Rayleigh fading. The channel is static if not set
+    pub rayleigh_doppler_hz: Option<f64>,
+    /// Seed for noise and fading
+    pub seed: u64,
+}
+
+/// Flat Rayleigh fading as a sum of sinusoids with random arrival angles and phases (Clarke's model),
+/// normalized to unity mean power
+struct RayleighFading {
+    /// Doppler shift of each path, in radians per sample
+    path_freqs: [f64; FADING_PATHS],
+    path_phases: [f64; FADING_PATHS],
+}
+
+impl RayleighFading {
+    fn new(rng: &mut StdRng, doppler_hz: f64, sample_rate: f64) -> Self {
+        let theta = rng.random::<f64>() * std::f64::consts::TAU;
+        Self {
+            path_freqs: std::array::from_fn(|n| {
+                let angle = (std::f64::consts::TAU * n as f64 + theta) / FADING_PATHS as f64;
+                std::f64::consts::TAU * doppler_hz * angle.cos() / sample_rate
+            }),
+            path_phases: std::array::from_fn(|_| rng.random::<f64>() * std::f64::consts::TAU),
+        }
+    }
+
+    fn gain(&self, count: SampleCount) -> ComplexSample {
+        let (re, im) = self
+            .path_freqs
+            .iter()
+            .zip(self.path_phases.iter())
+            .fold((0.0, 0.0), |(re, im), (freq, phase)| {
+                let (sin, cos) = (freq * count as f64 + phase).sin_cos();
+                (re + cos, im + sin)
+            });
+        let scale = 1.0 / (FADING_PATHS as f64).sqrt();
+        ComplexSample::new((re * scale) as RealSample, (im * scale) as RealSample)
+    }
+}
+
+/// Applies the impairments of ChannelParams to a sample stream
+pub struct ChannelSim {
+    delay: SampleCount,
+    /// Frequency offset in cycles per sample
+    freq_offset: f64,
+    /// Standard deviation of the noise in each of I and Q
+    noise_std: RealSample,
+    fading: Option<RayleighFading>,
+    rng: StdRng,
+}
+
+impl ChannelSim {
+    pub fn new(params: &ChannelParams, sample_rate: f64) -> Self {
+        let mut rng = StdRng::seed_from_u64(params.seed);
+        let noise_power = params.es_n0_db.map_or(0.0, |es_n0_db| {
+            CARRIER_POWER / 10f64.powf(es_n0_db / 10.0) * sample_rate / SYMBOL_RATE
+        });
+        Self {
+            delay: params.delay_samples as SampleCount,
+            freq_offset: params.freq_offset_hz / sample_rate,
+            noise_std: (noise_power / 2.0).sqrt() as RealSample,
+            fading: params
+                .rayleigh_doppler_hz
+                .map(|doppler_hz| RayleighFading::new(&mut rng, doppler_hz, sample_rate)),
+            rng,
+        }
+    }
+
+    /// Propagation delay in samples. Applying it is left to the caller,
+    /// which knows the transmitted samples.
+    pub fn delay(&self) -> SampleCount {
+        self.delay
+    }
+
+    /// Apply fading, frequency offset and noise in place,
+    /// to samples received starting at sample counter `count`.
+    pub fn apply(&mut self, count: SampleCount, samples: &mut [ComplexSample]) {
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let n = count + i as SampleCount;
+            if let Some(fading) = &self.fading {
+                *sample *= fading.gain(n);
+            }
+            if self.freq_offset != 0.0 {
+                // Phase wrapped in f64 so it stays accurate for long simulations
+                let phase = (self.freq_offset * n as f64).rem_euclid(1.0) * std::f64::consts::TAU;
+                *sample *= ComplexSample::from_polar(1.0, phase as RealSample);
+            }
+            if self.noise_std > 0.0 {
+                *sample += self.gaussian() * self.noise_std;
+            }
+        }
+    }
+
+    /// Complex Gaussian sample with unit variance in each of I and Q (Box-Muller)
+    fn gaussian(&mut self) -> ComplexSample {
+        let u1: f64 = 1.0 - self.rng.random::<f64>();
+        let u2: f64 = self.rng.random::<f64>();
+        let r = (-2.0 * u1.ln()).sqrt();
+        let (sin, cos) = (std::f64::consts::TAU * u2).sin_cos();
+        ComplexSample::new((r * cos) as RealSample, (r * sin) as RealSample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_power(samples: &[ComplexSample]) -> f64 {
+        samples.iter().map(|s| s.norm_sqr() as f64).sum::<f64>() / samples.len() as f64
+    }
+
+    #[test]
+    fn test_ideal_channel() {
+        let mut sim = ChannelSim::new(&ChannelParams::default(), 256e3);
+        let input: Vec<ComplexSample> = (0..100).map(|i| ComplexSample::new(i as RealSample, 1.0)).collect();
+        let mut samples = input.clone();
+        sim.apply(1234, &mut samples);
+        assert_eq!(samples, input);
+        assert_eq!(sim.delay(), 0);
+    }
+
+    #[test]
+    fn test_noise_power() {
+        let sample_rate = 256e3;
+        let params = ChannelParams {
+            es_n0_db: Some(10.0),
+            ..Default::default()
+        };
+        let mut samples = vec![ComplexSample::default(); 100_000];
+        ChannelSim::new(&params, sample_rate).apply(0, &mut samples);
+
+        // Noise in the symbol rate bandwidth is 10 dB below the carrier
+        let in_band = mean_power(&samples) * SYMBOL_RATE / sample_rate;
+        let expected = CARRIER_POWER / 10.0;
+        assert!((in_band / expected - 1.0).abs() < 0.03, "{} != {}", in_band, expected);
+    }
+
+    #[test]
+    fn test_freq_offset() {
+        let sample_rate = 100e3;
+        let params = ChannelParams {
+            freq_offset_hz: 1000.0,
+            ..Default::default()
+        };
+        let mut sim = ChannelSim::new(&params, sample_rate);
+        // The phase depends on the sample counter only, not on how the stream is split
+        let mut a = vec![ComplexSample::new(1.0, 0.0); 50];
+        let mut b = vec![ComplexSample::new(1.0, 0.0); 25];
+        sim.apply(0, &mut a);
+        sim.apply(25, &mut b);
+        assert_eq!(a[25..], b[..]);
+        // A quarter turn after a quarter period
+        assert!((a[25] - ComplexSample::new(0.0, 1.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn test_rayleigh_fading_power() {
+        let sample_rate = 100e3;
+        let params = ChannelParams {
+            rayleigh_doppler_hz: Some(200.0),
+            seed: 7,
+            ..Default::default()
+        };
+        let mut samples = vec![ComplexSample::new(1.0, 0.0); 500_000];
+        ChannelSim::new(&params, sample_rate).apply(0, &mut samples);
+        let power = mean_power(&samples);
+        assert!((power - 1.0).abs() < 0.2, "mean fading power {}", power);
+        // There are deep fades
+        assert!(samples.iter().any(|s| s.norm() < 0.1));
+    }
+}
@@ -390,6 +390,13 @@ impl Demodulator {
         }
     }
 
+    /// Sample counter value at the beginning of hyperframe number 0,
+    /// if synchronized to a downlink signal.
+    /// An MS uses it to time its uplink transmissions.
+    pub fn synchronized_reference_time(&self) -> Option<SampleCount> {
+        (self.mode == Mode::Dl).then_some(self.reference_time)
+    }
+
     pub fn demodulated_slot_available(&self) -> bool {
         self.demodulated_slot_available
     }
@@ -0,0 +1,350 @@
+//! RX/TX device on IQ files, for running the stack without an SDR.
+//!
+//! Received samples are read from a capture and transmitted samples are written to a file.
+//! There is no hardware clock: time advances with the samples read from the capture,
+//! and the transmitted signal is timed against that, so a replay is fully deterministic.
+
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tetra_config::bluestation::{CfgFileIo, IqFormat, SharedConfig, StackMode};
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+
+use super::dsp_types::*;
+use super::phy_io_file::{PhyIoError, PhyIoFile, PhyIoFileMode};
+use super::sdr_io::{RxResult, SdrIo};
+use super::soapy_dev::RxTxDevSdr;
+
+/// RX/TX device reading and writing IQ files
+pub type RxTxDevFile = RxTxDevSdr<FileIo>;
+
+impl RxTxDevFile {
+    /// Open the files configured in phy_io.file. Once the RX capture ends, `rxtx_timeslot`
+    /// returns RxEndOfData and `running`, if given, is cleared so the stack stops.
+    pub fn new(cfg: &SharedConfig, running: Option<Arc<AtomicBool>>) -> io::Result<Self> {
+        let config = cfg.config();
+        let file_cfg = config.phy_io.file.as_ref().expect("File config must be set for File PhyIo");
+        let freq_info = config.cell.freq_info().map_err(io::Error::other)?;
+        let (dl_freq, ul_freq) = freq_info.get_freqs();
+        let (dl_freq, ul_freq) = (dl_freq as f64, ul_freq as f64);
+
+        // Carriers received and transmitted in this stack mode
+        let (rx_freq, tx_freq) = match config.stack_mode {
+            StackMode::Bs => (ul_freq, Some(dl_freq)),
+            StackMode::Ms => (dl_freq, Some(ul_freq)),
+            StackMode::Mon if config.monitor.as_ref().is_some_and(|m| m.uplink) => ((dl_freq + ul_freq) / 2.0, None),
+            StackMode::Mon => (dl_freq, None),
+        };
+        tracing::info!(
+            "Freqs: DL / UL: {:.6} MHz / {:.6} MHz, RX file {:?}, TX file {:?}",
+            dl_freq / 1e6,
+            ul_freq / 1e6,
+            file_cfg.rx_file,
+            file_cfg.tx_file
+        );
+
+        let io = FileIo::new(file_cfg, rx_freq, tx_freq, running)?;
+        drop(config);
+        Ok(Self::with_carriers(cfg, io, dl_freq, ul_freq))
+    }
+}
+
+pub struct FileIo {
+    format: IqFormat,
+    sample_rate: f64,
+    rx_center_freq: f64,
+    tx_center_freq: f64,
+
+    /// RX capture. Silence is received when not set
+    rx: Option<PhyIoFile>,
+    /// Set once the RX capture has been read to the end
+    rx_ended: bool,
+    /// TX output, None if not transmitting
+    tx: Option<PhyIoFile>,
+
+    /// Sample counter of the next sample to be received. This is the simulated sample clock
+    rx_next_count: SampleCount,
+    /// Sample counter of the next sample to be written to the TX file
+    tx_next_count: SampleCount,
+
+    /// Cleared when the RX capture ends
+    running: Option<Arc<AtomicBool>>,
+    /// Conversion buffer between file bytes and samples
+    bytes: Vec<u8>,
+}
+
+impl FileIo {
+    /// Open the configured files. Without a TX carrier frequency, transmitting is disabled
+    pub fn new(cfg: &CfgFileIo, rx_freq: f64, tx_freq: Option<f64>, running: Option<Arc<AtomicBool>>) -> io::Result<Self> {
+        let rx_mode = if cfg.repeat {
+            PhyIoFileMode::ReadRepeat
+        } else {
+            PhyIoFileMode::Read
+        };
+        let rx = cfg.rx_file.as_ref().map(|f| PhyIoFile::new(f, rx_mode)).transpose()?;
+        let tx = match (&cfg.tx_file, tx_freq) {
+            (Some(f), Some(_)) => Some(PhyIoFile::new(f, PhyIoFileMode::Write)?),
+            (Some(f), None) => {
+                tracing::warn!("Not transmitting in this stack mode, ignoring tx_file {}", f);
+                None
+            }
+            (None, _) => None,
+        };
+
+        Ok(Self {
+            format: cfg.format,
+            sample_rate: cfg.sample_rate,
+            rx_center_freq: cfg.rx_center_freq.unwrap_or(rx_freq),
+            tx_center_freq: cfg.tx_center_freq.or(tx_freq).unwrap_or(0.0),
+            rx,
+            rx_ended: false,
+            tx,
+            rx_next_count: 0,
+            tx_next_count: 0,
+            running,
+            bytes: Vec::new(),
+        })
+    }
+
+    fn end_of_capture(&mut self) {
+        tracing::info!("End of RX capture after {} samples", self.rx_next_count);
+        self.rx = None;
+        self.rx_ended = true;
+        if let Some(running) = &self.running {
+            running.store(false, Ordering::SeqCst);
+        }
+    }
+
+    fn write_samples(&mut self, samples: &[ComplexSample]) -> Result<(), RxTxDevError> {
+        let Some(tx) = &mut self.tx else {
+            return Err(RxTxDevError::RxReadError);
+        };
+        self.bytes.clear();
+        encode_samples(self.format, samples, &mut self.bytes);
+        tx.write_block(&self.bytes).map_err(|e| {
+            tracing::error!("Failed writing TX file: {:?}", e);
+            RxTxDevError::RxReadError
+        })?;
+        self.tx_next_count += samples.len() as SampleCount;
+        Ok(())
+    }
+}
+
+impl SdrIo for FileIo {
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError> {
+        if self.rx_ended {
+            return Err(RxTxDevError::RxEndOfData);
+        }
+        let count = self.rx_next_count;
+
+        if let Some(rx) = &mut self.rx {
+            self.bytes.resize(buffer.len() * self.format.sample_size(), 0);
+            match rx.read_block(&mut self.bytes) {
+                Ok(()) => decode_samples(self.format, &self.bytes, buffer),
+                Err(PhyIoError::Eof) => {
+                    // A partial block at the end is dropped
+                    self.end_of_capture();
+                    return Err(RxTxDevError::RxEndOfData);
+                }
+                Err(PhyIoError::Io(e)) => {
+                    tracing::error!("Failed reading RX file: {}", e);
+                    return Err(RxTxDevError::RxReadError);
+                }
+            }
+        } else {
+            buffer.fill(num::zero());
+        }
+
+        self.rx_next_count += buffer.len() as SampleCount;
+        Ok(RxResult { len: buffer.len(), count })
+    }
+
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+        let count = count.unwrap_or(self.tx_next_count);
+        if count > self.tx_next_count {
+            // Nothing was transmitted in between
+            let gap = vec![num::zero(); (count - self.tx_next_count) as usize];
+            self.write_samples(&gap)?;
+        }
+        // Samples before the end of the file can't be written anymore
+        let skip = ((self.tx_next_count - count) as usize).min(buffer.len());
+        if skip > 0 {
+            tracing::warn!("TX block overlaps previous one, dropping {} samples", skip);
+        }
+        self.write_samples(&buffer[skip..])
+    }
+
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+        // TX follows the RX clock, as with an SDR using equal sample rates
+        Ok(self.rx_next_count - 1)
+    }
+
+    fn tx_possible(&self) -> bool {
+        self.tx_enabled()
+    }
+
+    fn rx_sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    fn tx_sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    fn rx_center_frequency(&self) -> f64 {
+        self.rx_center_freq
+    }
+
+    fn tx_center_frequency(&self) -> f64 {
+        self.tx_center_freq
+    }
+
+    fn rx_enabled(&self) -> bool {
+        // Always receive, even if only silence, as received samples drive the clock
+        true
+    }
+
+    fn tx_enabled(&self) -> bool {
+        self.tx.is_some()
+    }
+}
+
+impl Drop for FileIo {
+    fn drop(&mut self) {
+        if let Some(tx) = &mut self.tx {
+            let _ = tx.flush();
+        }
+    }
+}
+
+fn decode_samples(format: IqFormat, bytes: &[u8], samples: &mut [ComplexSample]) {
+    match format {
+        IqFormat::Cf32 => {
+            for (sample, b) in samples.iter_mut().zip(bytes.chunks_exact(8)) {
+                *sample = ComplexSample::new(
+                    f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                    f32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+                );
+            }
+        }
+        IqFormat::Cs16 => {
+            for (sample, b) in samples.iter_mut().zip(bytes.chunks_exact(4)) {
+                *sample = ComplexSample::new(
+                    i16::from_le_bytes([b[0], b[1]]) as RealSample / 32768.0,
+                    i16::from_le_bytes([b[2], b[3]]) as RealSample / 32768.0,
+                );
+            }
+        }
+    }
+}
+
+fn encode_samples(format: IqFormat, samples: &[ComplexSample], bytes: &mut Vec<u8>) {
+    match format {
+        IqFormat::Cf32 => {
+            for sample in samples {
+                bytes.extend_from_slice(&sample.re.to_le_bytes());
+                bytes.extend_from_slice(&sample.im.to_le_bytes());
+            }
+        }
+        IqFormat::Cs16 => {
+            let to_i16 = |v: RealSample| (v * 32768.0).round().clamp(i16::MIN as RealSample, i16::MAX as RealSample) as i16;
+            for sample in samples {
+                bytes.extend_from_slice(&to_i16(sample.re).to_le_bytes());
+                bytes.extend_from_slice(&to_i16(sample.im).to_le_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("file_dev_test_{}_{}.bin", name, nanos))
+    }
+
+    fn file_cfg(rx_file: Option<&std::path::Path>, tx_file: Option<&std::path::Path>, format: IqFormat) -> CfgFileIo {
+        CfgFileIo {
+            rx_file: rx_file.map(|p| p.to_str().unwrap().to_string()),
+            tx_file: tx_file.map(|p| p.to_str().unwrap().to_string()),
+            format,
+            sample_rate: 1e6,
+            rx_center_freq: None,
+            tx_center_freq: None,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn test_sample_format_roundtrip() {
+        let samples = [
+            ComplexSample::new(0.5, -0.25),
+            ComplexSample::new(-1.0, 0.0),
+            ComplexSample::new(0.125, 0.999),
+        ];
+        for format in [IqFormat::Cf32, IqFormat::Cs16] {
+            let mut bytes = Vec::new();
+            encode_samples(format, &samples, &mut bytes);
+            assert_eq!(bytes.len(), samples.len() * format.sample_size());
+            let mut decoded = [ComplexSample::default(); 3];
+            decode_samples(format, &bytes, &mut decoded);
+            for (a, b) in samples.iter().zip(decoded.iter()) {
+                assert!((a - b).norm() < 1e-4, "{:?}: {} != {}", format, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transmit_fills_gaps() {
+        let path = temp_path("tx");
+        let cfg = file_cfg(None, Some(&path), IqFormat::Cf32);
+        {
+            let mut io = FileIo::new(&cfg, 400e6, Some(410e6), None).unwrap();
+            assert_eq!(io.tx_center_frequency(), 410e6);
+            let block = [ComplexSample::new(1.0, 1.0); 4];
+            io.transmit(&block, Some(2)).unwrap();
+            io.transmit(&block, Some(4)).unwrap();
+        }
+        let bytes = std::fs::read(&path).unwrap();
+        let mut samples = vec![ComplexSample::default(); bytes.len() / 8];
+        decode_samples(IqFormat::Cf32, &bytes, &mut samples);
+        let _ = std::fs::remove_file(&path);
+
+        // Two samples of silence, then the first block, then the non-overlapping part of the second
+        assert_eq!(samples.len(), 8);
+        assert_eq!(samples[..2], [ComplexSample::default(); 2]);
+        assert_eq!(samples[2..], [ComplexSample::new(1.0, 1.0); 6]);
+    }
+
+    #[test]
+    fn test_receive_end_of_capture() {
+        let path = temp_path("rx");
+        let mut bytes = Vec::new();
+        encode_samples(IqFormat::Cs16, &[ComplexSample::new(0.5, -0.5); 6], &mut bytes);
+        std::fs::write(&path, &bytes).unwrap();
+
+        let running = Arc::new(AtomicBool::new(true));
+        let cfg = file_cfg(Some(&path), None, IqFormat::Cs16);
+        let mut io = FileIo::new(&cfg, 400e6, None, Some(running.clone())).unwrap();
+        assert!(!io.tx_enabled());
+
+        let mut buf = [ComplexSample::default(); 4];
+        let result = io.receive(&mut buf).unwrap();
+        assert_eq!((result.count, result.len), (0, 4));
+        assert_eq!(buf, [ComplexSample::new(0.5, -0.5); 4]);
+        assert!(running.load(Ordering::SeqCst));
+
+        // Only two samples left: the capture ends, for good
+        assert_eq!(io.receive(&mut buf).err(), Some(RxTxDevError::RxEndOfData));
+        assert!(!running.load(Ordering::SeqCst));
+        assert_eq!(io.receive(&mut buf).err(), Some(RxTxDevError::RxEndOfData));
+        assert_eq!(io.tx_current_count().unwrap(), 3);
+        let _ = std::fs::remove_file(&path);
+    }
+}
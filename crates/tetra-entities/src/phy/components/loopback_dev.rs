@@ -0,0 +1,339 @@
+//! Simulated radio link between a BS and an MS stack running in the same process.
+//!
+//! Each stack runs the full modem DSP on its end of a LoopbackChannel, typically in a thread of its own.
+//! The downlink signal of the BS is passed through a ChannelSim to the MS and the uplink signal of the MS
+//! back to the BS. There is no hardware clock: each end advances as it receives samples and waits for the
+//! other end to catch up, so both share a common sample clock and a simulation is reproducible.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use tetra_config::bluestation::{SharedConfig, StackMode};
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+
+use super::channel_sim::{ChannelParams, ChannelSim};
+use super::dsp_types::*;
+use super::sdr_io::{RxResult, SdrIo};
+use super::soapy_dev::RxTxDevSdr;
+
+/// The demodulator runs at 72 kHz, which has to fit within the link bandwidth along with a 25 kHz channel
+const MIN_SAMPLE_RATE: f64 = 100_000.0;
+
+/// How far an end may receive beyond the receive position of the other end.
+///
+/// The TX DSP never produces samples less than two processing blocks ahead of its receive position,
+/// so everything the other end transmits up to there is final. This has to be less than a processing
+/// block, which is 150 samples at the minimum sample rate.
+const RX_LEAD: SampleCount = 128;
+
+/// End of the link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkEnd {
+    Bs,
+    Ms,
+}
+
+impl LinkEnd {
+    fn index(self) -> usize {
+        match self {
+            LinkEnd::Bs => 0,
+            LinkEnd::Ms => 1,
+        }
+    }
+
+    fn peer(self) -> LinkEnd {
+        match self {
+            LinkEnd::Bs => LinkEnd::Ms,
+            LinkEnd::Ms => LinkEnd::Bs,
+        }
+    }
+}
+
+/// One direction of the link
+struct Path {
+    /// Transmitted samples not yet received, the first one at sample counter `start`
+    samples: VecDeque<ComplexSample>,
+    start: SampleCount,
+    sim: ChannelSim,
+}
+
+impl Path {
+    fn new(params: &ChannelParams, sample_rate: f64) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            start: 0,
+            sim: ChannelSim::new(params, sample_rate),
+        }
+    }
+
+    fn write(&mut self, buffer: &[ComplexSample], count: SampleCount) {
+        // Samples which have been received already can't be changed anymore
+        let skip = ((self.start - count).max(0) as usize).min(buffer.len());
+        if skip > 0 {
+            tracing::warn!("Transmitted {} samples too late for the receiver, dropping them", skip);
+        }
+        if skip == buffer.len() {
+            return;
+        }
+        let offset = (count + skip as SampleCount - self.start) as usize;
+        if offset > self.samples.len() {
+            // Nothing was transmitted in between
+            self.samples.resize(offset, num::zero());
+        }
+        for (i, sample) in buffer[skip..].iter().enumerate() {
+            match self.samples.get_mut(offset + i) {
+                Some(s) => *s = *sample,
+                None => self.samples.push_back(*sample),
+            }
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [ComplexSample], count: SampleCount) {
+        let tx_count = count - self.sim.delay();
+        for (i, sample) in buffer.iter_mut().enumerate() {
+            let index = tx_count + i as SampleCount - self.start;
+            *sample = if index >= 0 {
+                self.samples.get(index as usize).copied().unwrap_or_else(num::zero)
+            } else {
+                num::zero()
+            };
+        }
+        self.sim.apply(count, buffer);
+
+        // Received samples are no longer needed
+        let consumed = (tx_count + buffer.len() as SampleCount - self.start).max(0) as usize;
+        self.samples.drain(..consumed.min(self.samples.len()));
+        self.start += consumed as SampleCount;
+    }
+}
+
+struct LinkState {
+    /// Downlink and uplink paths, indexed by the transmitting end
+    paths: [Path; 2],
+    /// Sample counter of the next sample to be received, per end
+    rx_next_count: [SampleCount; 2],
+    /// Set when an end has been dropped
+    closed: [bool; 2],
+}
+
+struct Link {
+    state: Mutex<LinkState>,
+    /// Notified whenever an end receives or closes
+    changed: Condvar,
+}
+
+impl Link {
+    fn lock(&self) -> MutexGuard<'_, LinkState> {
+        self.state.lock().expect("loopback link lock poisoned")
+    }
+}
+
+/// Simulated radio link with one BS and one MS end
+pub struct LoopbackChannel {
+    link: Arc<Link>,
+    sample_rate: f64,
+}
+
+impl LoopbackChannel {
+    /// Create a link at the given sample rate, with the impairments of each direction
+    pub fn new(sample_rate: f64, downlink: &ChannelParams, uplink: &ChannelParams) -> Self {
+        assert!(
+            sample_rate >= MIN_SAMPLE_RATE,
+            "Loopback sample rate must be at least {} Hz",
+            MIN_SAMPLE_RATE
+        );
+        Self {
+            link: Arc::new(Link {
+                state: Mutex::new(LinkState {
+                    paths: [Path::new(downlink, sample_rate), Path::new(uplink, sample_rate)],
+                    rx_next_count: [0; 2],
+                    closed: [false; 2],
+                }),
+                changed: Condvar::new(),
+            }),
+            sample_rate,
+        }
+    }
+
+    /// Sample stream for one end of the link. Each end should only be taken once.
+    pub fn end(&self, end: LinkEnd) -> LoopbackIo {
+        LoopbackIo {
+            link: self.link.clone(),
+            end,
+            sample_rate: self.sample_rate,
+            rx_center_freq: 0.0,
+            tx_center_freq: 0.0,
+            rx_next_count: 0,
+        }
+    }
+}
+
+/// RX/TX device on one end of a LoopbackChannel
+pub type RxTxDevLoopback = RxTxDevSdr<LoopbackIo>;
+
+impl RxTxDevLoopback {
+    /// Attach a BS or MS stack to its end of the link, on the carriers of the cell config
+    pub fn new(cfg: &SharedConfig, mut io: LoopbackIo) -> Self {
+        let config = cfg.config();
+        let freq_info = config.cell.freq_info().expect("Invalid cell frequency config");
+        let (dl_freq, ul_freq) = freq_info.get_freqs();
+        let (dl_freq, ul_freq) = (dl_freq as f64, ul_freq as f64);
+        match (config.stack_mode, io.end) {
+            (StackMode::Bs, LinkEnd::Bs) => io.set_carriers(ul_freq, dl_freq),
+            (StackMode::Ms, LinkEnd::Ms) => io.set_carriers(dl_freq, ul_freq),
+            (mode, end) => panic!("Can't attach a {:?} stack to the {:?} end of a loopback link", mode, end),
+        }
+        drop(config);
+        Self::with_carriers(cfg, io, dl_freq, ul_freq)
+    }
+}
+
+/// Sample stream on one end of a LoopbackChannel
+pub struct LoopbackIo {
+    link: Arc<Link>,
+    end: LinkEnd,
+    sample_rate: f64,
+    rx_center_freq: f64,
+    tx_center_freq: f64,
+    /// Sample counter of the next sample to be received. TX follows this clock
+    rx_next_count: SampleCount,
+}
+
+impl LoopbackIo {
+    /// Center the received and transmitted streams on the given carriers
+    pub fn set_carriers(&mut self, rx_center_freq: f64, tx_center_freq: f64) {
+        self.rx_center_freq = rx_center_freq;
+        self.tx_center_freq = tx_center_freq;
+    }
+}
+
+impl SdrIo for LoopbackIo {
+    /// Receive from the other end, waiting for it to catch up if needed.
+    /// Returns RxEndOfData once the other end has been dropped.
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError> {
+        let me = self.end.index();
+        let peer = self.end.peer().index();
+        let count = self.rx_next_count;
+
+        let mut state = self.link.lock();
+        while !state.closed[peer] && state.rx_next_count[peer] + RX_LEAD <= count {
+            state = self.link.changed.wait(state).expect("loopback link lock poisoned");
+        }
+        if state.closed[peer] {
+            return Err(RxTxDevError::RxEndOfData);
+        }
+
+        let len = buffer.len().min((state.rx_next_count[peer] + RX_LEAD - count) as usize);
+        state.paths[peer].read(&mut buffer[..len], count);
+        self.rx_next_count += len as SampleCount;
+        state.rx_next_count[me] = self.rx_next_count;
+        drop(state);
+        self.link.changed.notify_all();
+
+        Ok(RxResult { len, count })
+    }
+
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+        let mut state = self.link.lock();
+        if !state.closed[self.end.peer().index()] {
+            let count = count.unwrap_or(self.rx_next_count);
+            state.paths[self.end.index()].write(buffer, count);
+        }
+        Ok(())
+    }
+
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+        Ok(self.rx_next_count - 1)
+    }
+
+    fn tx_possible(&self) -> bool {
+        true
+    }
+
+    fn rx_sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    fn tx_sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    fn rx_center_frequency(&self) -> f64 {
+        self.rx_center_freq
+    }
+
+    fn tx_center_frequency(&self) -> f64 {
+        self.tx_center_freq
+    }
+
+    fn rx_enabled(&self) -> bool {
+        true
+    }
+
+    fn tx_enabled(&self) -> bool {
+        true
+    }
+}
+
+impl Drop for LoopbackIo {
+    fn drop(&mut self) {
+        // Let the other end stop instead of waiting for us forever
+        self.link.lock().closed[self.end.index()] = true;
+        self.link.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 100e3;
+
+    #[test]
+    fn test_loopback_delay() {
+        let channel = LoopbackChannel::new(
+            SAMPLE_RATE,
+            &ChannelParams {
+                delay_samples: 3,
+                ..Default::default()
+            },
+            &ChannelParams::default(),
+        );
+        let mut bs = channel.end(LinkEnd::Bs);
+        let mut ms = channel.end(LinkEnd::Ms);
+
+        let tx: Vec<ComplexSample> = (0..10).map(|i| ComplexSample::new(i as RealSample + 1.0, 0.0)).collect();
+        bs.transmit(&tx, Some(5)).unwrap();
+
+        let mut rx = [ComplexSample::default(); 20];
+        let result = ms.receive(&mut rx).unwrap();
+        assert_eq!((result.count, result.len), (0, 20));
+        // Silence before the transmission and after the delay
+        assert_eq!(rx[..8], [ComplexSample::default(); 8]);
+        assert_eq!(rx[8..18], tx[..]);
+        assert_eq!(rx[18..], [ComplexSample::default(); 2]);
+
+        // Uplink is independent
+        ms.transmit(&tx[..2], Some(0)).unwrap();
+        let result = bs.receive(&mut rx[..4]).unwrap();
+        assert_eq!((result.count, result.len), (0, 4));
+        assert_eq!(rx[..2], tx[..2]);
+    }
+
+    #[test]
+    fn test_loopback_lead_and_close() {
+        let channel = LoopbackChannel::new(SAMPLE_RATE, &ChannelParams::default(), &ChannelParams::default());
+        let mut bs = channel.end(LinkEnd::Bs);
+        let ms = channel.end(LinkEnd::Ms);
+
+        // The BS can't get ahead of the MS by more than RX_LEAD
+        let mut rx = vec![ComplexSample::default(); 1000];
+        let result = bs.receive(&mut rx).unwrap();
+        assert_eq!(result.len, RX_LEAD as usize);
+        assert_eq!(bs.tx_current_count().unwrap(), RX_LEAD - 1);
+
+        // Instead of waiting for an MS which is gone, it stops
+        drop(ms);
+        assert_eq!(bs.receive(&mut rx).err(), Some(RxTxDevError::RxEndOfData));
+    }
+}
@@ -12,6 +12,11 @@ pub mod soapy_settings;
 pub mod soapy_time;
 pub mod soapyio;
 
+pub mod sdr_io;
+
+pub mod channel_sim;
+pub mod file_dev;
+pub mod loopback_dev;
 pub mod soapy_dev;
 // pub mod _rxtxdev_buffer;
 
@@ -2,6 +2,7 @@ use tetra_core::TdmaTime;
 
 use tetra_pdus::phy::traits::rxtx_dev::TxSlotBits;
 
+use crate::phy::components::burst_consts::*;
 use crate::phy::components::dsp_types::*;
 use crate::phy::components::fir;
 use crate::phy::components::modem_common::*;
@@ -15,10 +16,19 @@ const SAMPLES_SLOT: SampleCount = SPS * 255;
 /// Output sample rate
 pub const SAMPLE_RATE: f64 = 18000.0 * SPS as f64;
 
+/// Start of an uplink burst from the beginning of its (sub)slot, after ramping and guard symbols
+const UL_BURST_START: SampleCount = (NUB_HEADBITS_OFFSET / DQPSK4_BITS_PER_SYM) as SampleCount * SPS;
+
+/// Start of subslot 2 from the beginning of the slot
+const SUBSLOT2_START: SampleCount = SAMPLES_SLOT / 2;
+
 #[derive(PartialEq)]
 pub enum Mode {
     /// Downlink modulation.
     Dl,
+    /// Uplink modulation, transmitting normal or control uplink bursts
+    /// and silence in between.
+    Ul,
 }
 
 pub struct Modulator {
@@ -46,6 +56,12 @@ impl Modulator {
         }
     }
 
+    /// Set the sample counter value at the beginning of hyperframe number 0.
+    /// An MS takes it from its downlink demodulator.
+    pub fn set_reference_time(&mut self, reference_time: SampleCount) {
+        self.reference_time = reference_time;
+    }
+
     /// Produce one output sample.
     pub fn sample(&mut self, sample_counter: SampleCount, tx_slot: &TxSlotBits) -> Result<ComplexSample, Error> {
         // Compensate for delay of pulse shaping filter in sample count
@@ -76,6 +92,33 @@ impl Modulator {
                     }
                 }
             }
+            Mode::Ul => {
+                let sample_in_slot = sample_counter - slot_begin;
+                if sample_in_slot >= SAMPLES_SLOT {
+                    return Err(Error::NeedMoreData);
+                }
+                // Either a full slot burst, or a control burst in one or both subslots
+                let burst = if sample_in_slot < 0 {
+                    None
+                } else if let Some(bits) = tx_slot.slot {
+                    Some((bits, sample_in_slot - UL_BURST_START))
+                } else if sample_in_slot < SUBSLOT2_START {
+                    tx_slot.subslot1.map(|bits| (bits, sample_in_slot - UL_BURST_START))
+                } else {
+                    tx_slot
+                        .subslot2
+                        .map(|bits| (bits, sample_in_slot - SUBSLOT2_START - UL_BURST_START))
+                };
+                if let Some((bits, sample_in_burst)) = burst
+                    && sample_in_burst >= 0
+                    && sample_in_burst % SPS == 0
+                {
+                    let symbol_i = (sample_in_burst / SPS) as usize;
+                    if symbol_i * 2 < bits.len() {
+                        sample = self.dqpsk.symbol(bits[symbol_i * 2] != 0, bits[symbol_i * 2 + 1] != 0);
+                    }
+                }
+            }
         }
         Ok(self.filter.sample(&CHANNEL_FILTER_TAPS, sample))
     }
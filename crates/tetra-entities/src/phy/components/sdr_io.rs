@@ -0,0 +1,34 @@
+//! Sample stream interface between the modem DSP and a source/sink of IQ samples,
+//! being either an SDR or a file.
+
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+
+use super::dsp_types::*;
+
+pub struct RxResult {
+    /// Number of samples read
+    pub len: usize,
+    /// Sample counter for the first sample read
+    pub count: SampleCount,
+}
+
+pub trait SdrIo {
+    /// Read samples into buffer, returning how many were read and the sample counter of the first one
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError>;
+
+    /// Transmit samples, starting at the given sample counter if given
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError>;
+
+    /// Current time as TX sample count
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError>;
+
+    /// Whether transmit timing is known, so TX can start
+    fn tx_possible(&self) -> bool;
+
+    fn rx_sample_rate(&self) -> f64;
+    fn tx_sample_rate(&self) -> f64;
+    fn rx_center_frequency(&self) -> f64;
+    fn tx_center_frequency(&self) -> f64;
+    fn rx_enabled(&self) -> bool;
+    fn tx_enabled(&self) -> bool;
+}
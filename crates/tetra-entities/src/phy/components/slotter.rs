@@ -193,6 +193,45 @@ pub fn build_ndb(
     type5
 }
 
+/// Constructs a Normal Uplink Burst (Clause 9.4.4.2.1) from two blocks
+/// Training sequence determines whether blk1 and blk2 are to be considered one full slot or two half slots
+/// blk1: 216-bit BKN1 type5 bits
+/// blk2: 216-bit BKN2 type5 bits
+pub fn build_nub(train_seq: TrainingSequence, blk1: &[u8; NUB_BLK_BITS], blk2: &[u8; NUB_BLK_BITS]) -> [u8; NUB_BITS] {
+    let mut type5 = [0u8; NUB_BITS];
+
+    type5[0..NUB_BLK1_OFFSET].copy_from_slice(&bitseq::t);
+    type5[NUB_BLK1_OFFSET..NUB_TRAINING_OFFSET].copy_from_slice(blk1);
+    match train_seq {
+        TrainingSequence::NormalTrainSeq1 => {
+            type5[NUB_TRAINING_OFFSET..NUB_BLK2_OFFSET].copy_from_slice(&bitseq::n);
+        }
+        TrainingSequence::NormalTrainSeq2 => {
+            type5[NUB_TRAINING_OFFSET..NUB_BLK2_OFFSET].copy_from_slice(&bitseq::p);
+        }
+        _ => panic!("Unsupported training sequence for NUB burst"),
+    }
+    type5[NUB_BLK2_OFFSET..NUB_TAILBITS_OFFSET].copy_from_slice(blk2);
+    type5[NUB_TAILBITS_OFFSET..].copy_from_slice(&bitseq::t);
+
+    type5
+}
+
+/// Constructs a Control Uplink Burst (Clause 9.4.4.2.2), transmitted in a single subslot
+/// blk1: 84-bit SSN1 type5 bits (first half of the SCH/HU block)
+/// blk2: 84-bit SSN2 type5 bits (second half of the SCH/HU block)
+pub fn build_cub(blk1: &[u8; CUB_BLK_BITS], blk2: &[u8; CUB_BLK_BITS]) -> [u8; CUB_BITS] {
+    let mut type5 = [0u8; CUB_BITS];
+
+    type5[0..CUB_BLK1_OFFSET].copy_from_slice(&bitseq::t);
+    type5[CUB_BLK1_OFFSET..CUB_TRAINING_OFFSET].copy_from_slice(blk1);
+    type5[CUB_TRAINING_OFFSET..CUB_BLK2_OFFSET].copy_from_slice(&bitseq::x);
+    type5[CUB_BLK2_OFFSET..CUB_TAILBITS_OFFSET].copy_from_slice(blk2);
+    type5[CUB_TAILBITS_OFFSET..].copy_from_slice(&bitseq::t);
+
+    type5
+}
+
 #[cfg(test)]
 mod tests {
     use tetra_core::bitbuffer::BitBuffer;
@@ -268,4 +307,19 @@ mod tests {
             BitBuffer::from_bitarr(&expected_burst).dump_bin()
         );
     }
+
+    #[test]
+    fn test_build_ul_bursts() {
+        let blk1 = [1u8; NUB_BLK_BITS];
+        let blk2 = [0u8; NUB_BLK_BITS];
+        let nub = build_nub(TrainingSequence::NormalTrainSeq2, &blk1, &blk2);
+        assert_eq!(nub[NUB_TRAINING_OFFSET..NUB_BLK2_OFFSET], SEQ_NORM2_AS_ARR);
+        assert!(nub[NUB_BLK1_OFFSET..NUB_TRAINING_OFFSET].iter().all(|&b| b == 1));
+        assert!(nub[NUB_BLK2_OFFSET..NUB_TAILBITS_OFFSET].iter().all(|&b| b == 0));
+
+        let cub = build_cub(&[1u8; CUB_BLK_BITS], &[1u8; CUB_BLK_BITS]);
+        assert_eq!(cub[CUB_TRAINING_OFFSET..CUB_BLK2_OFFSET], SEQ_EXT_AS_ARR);
+        assert_eq!(cub[..CUB_BLK1_OFFSET], bitseq::t);
+        assert_eq!(cub[CUB_TAILBITS_OFFSET..], bitseq::t);
+    }
 }
@@ -2,7 +2,7 @@
 //! between SDR device and modulator/demodulator code.
 
 use rustfft;
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{SharedConfig, StackMode};
 
 use tetra_pdus::phy::traits::rxtx_dev::RxSlotBits;
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDev;
@@ -15,6 +15,7 @@ use super::demodulator;
 use super::dsp_types::*;
 use super::fcfb;
 use super::modulator;
+use super::sdr_io::SdrIo;
 use super::soapyio;
 
 pub struct SdrConfig<'a> {
@@ -35,21 +36,25 @@ pub struct PhyConfig<'a> {
     pub bs_dl_frequencies: &'a [f64],
     /// Uplink carrier frequencies for a BS.
     pub bs_ul_frequencies: &'a [f64],
+    /// Uplink carrier frequencies for an MS.
+    /// Transmission is timed from the first downlink monitor.
+    pub ms_ul_frequencies: &'a [f64],
 }
 
-pub struct RxTxDevSoapySdr {
-    sdr: soapyio::SoapyIo,
+/// RX/TX device running the modem DSP on the sample stream of an SdrIo
+pub struct RxTxDevSdr<S: SdrIo> {
+    sdr: S,
     rx_dsp: Option<RxDsp>,
     tx_dsp: Option<TxDsp>,
 }
 
+/// RX/TX device for an SDR accessed through SoapySDR
+pub type RxTxDevSoapySdr = RxTxDevSdr<soapyio::SoapyIo>;
+
 type FftPlanner = rustfft::FftPlanner<RealSample>;
 
 impl RxTxDevSoapySdr {
     pub fn new(cfg: &SharedConfig) -> Self {
-        let mut fft_planner = rustfft::FftPlanner::new();
-
-        // TODO FIXME currently no MS and MON support in the below statement; need to fix
         let config_guard = cfg.config();
         let soapy_cfg = config_guard
             .as_ref()
@@ -71,14 +76,76 @@ impl RxTxDevSoapySdr {
             dl_corrected / 1e6,
             ul_corrected / 1e6
         );
+        drop(config_guard);
 
-        let phy_config = soapy_dev::PhyConfig {
-            bs_dl_frequencies: &[dl_corrected],
-            bs_ul_frequencies: &[ul_corrected],
-            ..Default::default()
-        };
+        let sdr = soapyio::SoapyIo::new(cfg).unwrap();
+        Self::with_carriers(cfg, sdr, dl_corrected, ul_corrected)
+    }
+}
 
-        let mut sdr = soapyio::SoapyIo::new(cfg).unwrap();
+impl<S: SdrIo> RxTxDevSdr<S> {
+    /// Set up modulators and demodulators for the stack mode, on the given downlink and uplink carrier frequencies
+    pub fn with_carriers(cfg: &SharedConfig, mut sdr: S, dl_freq: f64, ul_freq: f64) -> Self {
+        let mut fft_planner = rustfft::FftPlanner::new();
+        let config_guard = cfg.config();
+
+        // Secondary carriers are placed relative to the main carrier, so a tuning correction applied to it carries over
+        let mut bs_dl_freqs = vec![dl_freq];
+        let mut bs_ul_freqs = vec![ul_freq];
+        let cell = &config_guard.cell;
+        if let Ok(main) = cell.freq_info() {
+            let (main_dl, main_ul) = main.get_freqs();
+            for carrier in 1..cell.num_carriers() as u8 {
+                let Ok(info) = cell.carrier_freq_info(carrier) else {
+                    continue; // Rejected by config validation
+                };
+                let (dl, ul) = info.get_freqs();
+                bs_dl_freqs.push(dl_freq * dl as f64 / main_dl as f64);
+                bs_ul_freqs.push(ul_freq * ul as f64 / main_ul as f64);
+            }
+        }
+        if bs_dl_freqs.len() > 1 {
+            let check_bandwidth = |freqs: &[f64], center: f64, sample_rate: f64, dir: &str| {
+                for freq in freqs {
+                    if (freq - center).abs() + 12500.0 > sample_rate / 2.0 {
+                        tracing::error!(
+                            "{} carrier at {:.6} MHz is outside the SDR bandwidth of {:.3} MHz around {:.6} MHz",
+                            dir,
+                            freq / 1e6,
+                            sample_rate / 1e6,
+                            center / 1e6
+                        );
+                    }
+                }
+            };
+            if sdr.tx_enabled() {
+                check_bandwidth(&bs_dl_freqs, sdr.tx_center_frequency(), sdr.tx_sample_rate(), "DL");
+            }
+            if sdr.rx_enabled() {
+                check_bandwidth(&bs_ul_freqs, sdr.rx_center_frequency(), sdr.rx_sample_rate(), "UL");
+            }
+        }
+
+        let ms_monitor = [(dl_freq, None)];
+        let mon_uplink = config_guard.monitor.as_ref().is_some_and(|m| m.uplink);
+        let mon_monitor = [(dl_freq, mon_uplink.then_some(ul_freq))];
+        let phy_config = match config_guard.stack_mode {
+            StackMode::Ms => soapy_dev::PhyConfig {
+                monitor_frequencies: &ms_monitor,
+                ms_ul_frequencies: &[ul_freq],
+                ..Default::default()
+            },
+            // Receive only, the uplink demodulator follows the downlink timing
+            StackMode::Mon => soapy_dev::PhyConfig {
+                monitor_frequencies: &mon_monitor,
+                ..Default::default()
+            },
+            StackMode::Bs => soapy_dev::PhyConfig {
+                bs_dl_frequencies: &bs_dl_freqs,
+                bs_ul_frequencies: &bs_ul_freqs,
+                ..Default::default()
+            },
+        };
 
         Self {
             rx_dsp: if sdr.rx_enabled() {
@@ -114,6 +181,10 @@ impl RxTxDevSoapySdr {
     /// or if it wants to wait before producing more.
     fn process_tx_block(&mut self, tx_slot: &[TxSlotBits]) -> Result<bool, RxTxDevError> {
         if let Some(tx_dsp) = &mut self.tx_dsp {
+            // An MS transmits relative to the downlink timing
+            if let Some(reference_time) = self.rx_dsp.as_ref().and_then(|rx_dsp| rx_dsp.dl_reference_time()) {
+                tx_dsp.set_reference_time(reference_time);
+            }
             if self.sdr.tx_possible() {
                 tx_dsp.process_block(&mut self.sdr, self.rx_dsp.as_ref().map(|rx_dsp| rx_dsp.rx_block_count), tx_slot)
             } else {
@@ -125,7 +196,7 @@ impl RxTxDevSoapySdr {
     }
 }
 
-impl RxTxDev for RxTxDevSoapySdr {
+impl<S: SdrIo> RxTxDev for RxTxDevSdr<S> {
     fn rxtx_timeslot<'a>(
         &'a mut self,
         tx_slot: &[TxSlotBits],
@@ -161,14 +232,14 @@ struct RxDsp {
 }
 
 impl RxDsp {
-    fn new(fft_planner: &mut FftPlanner, sdr: &mut soapyio::SoapyIo, phy_config: &PhyConfig) -> Self {
+    fn new(fft_planner: &mut FftPlanner, sdr: &mut impl SdrIo, phy_config: &PhyConfig) -> Self {
         let sdr_sample_rate = sdr.rx_sample_rate();
         let rx_fcfb_params = fcfb::AnalysisInputParameters {
             // Use a bin spacing of 500 Hz.
             // This is a submultiple of the 72 kHz modem sample rate
             // and allows tuning in steps of 500 Hz.
             fft_size: (sdr_sample_rate / 500.0).round() as usize,
-            center_frequency: sdr.rx_center_frequency().unwrap(),
+            center_frequency: sdr.rx_center_frequency(),
             sample_rate: sdr_sample_rate,
             overlap: fcfb::Overlap::O1_4,
         };
@@ -202,7 +273,7 @@ impl RxDsp {
         }
     }
 
-    fn process_block(&mut self, sdr: &mut soapyio::SoapyIo) -> Result<bool, RxTxDevError> {
+    fn process_block(&mut self, sdr: &mut impl SdrIo) -> Result<bool, RxTxDevError> {
         self.receive_block(sdr)?;
 
         let fcfb_result = self.rx_fcfb.process(&self.rx_buffer[..], self.rx_block_count);
@@ -226,7 +297,7 @@ impl RxDsp {
         Ok(continue_processing)
     }
 
-    fn receive_block(&mut self, sdr: &mut soapyio::SoapyIo) -> Result<(), RxTxDevError> {
+    fn receive_block(&mut self, sdr: &mut impl SdrIo) -> Result<(), RxTxDevError> {
         self.rx_block_count += 1;
 
         // Copy overlapping part from previous block to the beginning
@@ -284,6 +355,13 @@ impl RxDsp {
         }
     }
 
+    /// Downlink timing of the first monitored carrier, once synchronized
+    fn dl_reference_time(&self) -> Option<SampleCount> {
+        self.monitors
+            .first()
+            .and_then(|pair| pair.dl.demodulator.synchronized_reference_time())
+    }
+
     fn take_slot_bits<'a>(&'a mut self) -> Vec<Option<RxSlotBits<'a>>> {
         // TODO: avoid dynamic allocation here?
         let mut slot_bits = Vec::with_capacity(2 * self.monitors.len() + self.ul_demodulators.len());
@@ -313,11 +391,11 @@ struct TxDsp {
 }
 
 impl TxDsp {
-    fn new(fft_planner: &mut FftPlanner, sdr: &mut soapyio::SoapyIo, phy_config: &PhyConfig) -> Self {
+    fn new(fft_planner: &mut FftPlanner, sdr: &mut impl SdrIo, phy_config: &PhyConfig) -> Self {
         let sdr_sample_rate = sdr.tx_sample_rate();
         let fcfb_params = fcfb::SynthesisOutputParameters {
             ifft_size: (sdr_sample_rate / 500.0).round() as usize,
-            center_frequency: sdr.tx_center_frequency().unwrap(),
+            center_frequency: sdr.tx_center_frequency(),
             sample_rate: sdr_sample_rate,
             overlap: fcfb::Overlap::O1_4,
         };
@@ -328,6 +406,9 @@ impl TxDsp {
         for dl_freq in phy_config.bs_dl_frequencies {
             modulators.push(ModulatorChannel::new(fft_planner, fcfb_params, *dl_freq, modulator::Mode::Dl));
         }
+        for ul_freq in phy_config.ms_ul_frequencies {
+            modulators.push(ModulatorChannel::new(fft_planner, fcfb_params, *ul_freq, modulator::Mode::Ul));
+        }
 
         Self {
             fcfb,
@@ -337,9 +418,15 @@ impl TxDsp {
         }
     }
 
+    fn set_reference_time(&mut self, reference_time: SampleCount) {
+        for modulator in self.modulators.iter_mut() {
+            modulator.modulator.set_reference_time(reference_time);
+        }
+    }
+
     fn process_block(
         &mut self,
-        sdr: &mut soapyio::SoapyIo,
+        sdr: &mut impl SdrIo,
         latest_rx_block: Option<fcfb::BlockCount>,
         tx_slot: &[TxSlotBits],
     ) -> Result<bool, RxTxDevError> {
@@ -4,6 +4,7 @@ use tetra_config::bluestation::{SharedConfig, StackMode, sec_phy_soapy::CfgSoapy
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
 
 use super::dsp_types::*;
+use super::sdr_io::{RxResult, SdrIo};
 use super::soapy_settings;
 use super::soapy_settings::{SdrSettings, SupportedDevice};
 use super::soapy_time::{ticks_to_time_ns, time_ns_to_ticks};
@@ -11,13 +12,6 @@ use super::soapy_time::{ticks_to_time_ns, time_ns_to_ticks};
 type StreamType = ComplexSample;
 const SOAPY_FREQ_OFFSET: f64 = 20000.0;
 
-pub struct RxResult {
-    /// Number of samples read
-    pub len: usize,
-    /// Sample counter for the first sample read
-    pub count: SampleCount,
-}
-
 pub struct SoapyIo {
     rx_ch: usize,
     tx_ch: usize,
@@ -90,7 +84,20 @@ impl SoapyIo {
                 Some(ul_corrected),
             ),
             StackMode::Mon => {
-                unimplemented!("Monitor mode not implemented yet");
+                if binding.monitor.as_ref().is_some_and(|m| m.uplink) {
+                    // Center between the carriers, so both fall within the receive bandwidth
+                    let separation = (dl_corrected - ul_corrected).abs();
+                    if separation > 0.8 * sdr_settings.fs {
+                        tracing::error!(
+                            "Uplink is {:.3} MHz away from the downlink, too far to receive both at {:.3} MHz sample rate",
+                            separation / 1e6,
+                            sdr_settings.fs / 1e6
+                        );
+                    }
+                    (Some((dl_corrected + ul_corrected) / 2.0), None)
+                } else {
+                    (Some(dl_corrected - SOAPY_FREQ_OFFSET), None)
+                }
             }
         };
 
@@ -195,7 +202,25 @@ impl SoapyIo {
         })
     }
 
-    pub fn receive(&mut self, buffer: &mut [StreamType]) -> Result<RxResult, RxTxDevError> {
+    pub fn current_time(&self) -> Result<i64, RxTxDevError> {
+        self.dev.get_hardware_time(None).map_err(|_| RxTxDevError::RxReadError)
+    }
+
+    /// Current hardware time as RX sample count
+    pub fn rx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+        if !self.rx_enabled() {
+            return Ok(0);
+        }
+        if self.use_get_hardware_time {
+            Ok(time_ns_to_ticks(self.current_time()? - self.initial_time.unwrap_or(0), self.rx_fs))
+        } else {
+            Ok(self.rx_next_count - 1)
+        }
+    }
+}
+
+impl SdrIo for SoapyIo {
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError> {
         if let Some(rx) = &mut self.rx {
             // RX is enabled
             match rx.read(&mut [buffer], 1000000) {
@@ -254,7 +279,7 @@ impl SoapyIo {
         }
     }
 
-    pub fn transmit(&mut self, buffer: &[StreamType], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
         if let Some(tx) = &mut self.tx {
             if let Some(initial_time) = self.initial_time {
                 tx.write_all(
@@ -274,24 +299,8 @@ impl SoapyIo {
         }
     }
 
-    pub fn current_time(&self) -> Result<i64, RxTxDevError> {
-        self.dev.get_hardware_time(None).map_err(|_| RxTxDevError::RxReadError)
-    }
-
-    /// Current hardware time as RX sample count
-    pub fn rx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
-        if !self.rx_enabled() {
-            return Ok(0);
-        }
-        if self.use_get_hardware_time {
-            Ok(time_ns_to_ticks(self.current_time()? - self.initial_time.unwrap_or(0), self.rx_fs))
-        } else {
-            Ok(self.rx_next_count - 1)
-        }
-    }
-
     /// Current hardware time as TX sample count
-    pub fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
         if !self.tx_enabled() {
             return Ok(0);
         }
@@ -305,33 +314,33 @@ impl SoapyIo {
         }
     }
 
-    pub fn tx_possible(&self) -> bool {
+    fn tx_possible(&self) -> bool {
         // initial_time is obtained from the first RX read (that includes a timestamp),
         // so prevent TX before it is available.
         self.tx_enabled() && self.initial_time.is_some()
     }
 
-    pub fn rx_sample_rate(&self) -> f64 {
+    fn rx_sample_rate(&self) -> f64 {
         self.rx_fs
     }
 
-    pub fn tx_sample_rate(&self) -> f64 {
+    fn tx_sample_rate(&self) -> f64 {
         self.tx_fs
     }
 
-    pub fn rx_center_frequency(&self) -> Result<f64, soapysdr::Error> {
-        self.dev.frequency(soapysdr::Direction::Rx, self.rx_ch)
+    fn rx_center_frequency(&self) -> f64 {
+        self.dev.frequency(soapysdr::Direction::Rx, self.rx_ch).unwrap()
     }
 
-    pub fn tx_center_frequency(&self) -> Result<f64, soapysdr::Error> {
-        self.dev.frequency(soapysdr::Direction::Tx, self.tx_ch)
+    fn tx_center_frequency(&self) -> f64 {
+        self.dev.frequency(soapysdr::Direction::Tx, self.tx_ch).unwrap()
     }
 
-    pub fn rx_enabled(&self) -> bool {
+    fn rx_enabled(&self) -> bool {
         self.rx.is_some()
     }
 
-    pub fn tx_enabled(&self) -> bool {
+    fn tx_enabled(&self) -> bool {
         self.tx.is_some()
     }
 }
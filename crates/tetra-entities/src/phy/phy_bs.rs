@@ -5,8 +5,8 @@ use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
 use tetra_pdus::phy::traits::rxtx_dev::RxBurstBits;
-use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, TxSlotBits};
-use tetra_saps::tp::TpUnitdataInd;
+use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, RxTxDevError, TxSlotBits};
+use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::phy::components::phy_io_file::{FileWriteMsg, PhyIoFileMode};
@@ -33,11 +33,16 @@ pub struct PhyBs<D: RxTxDev> {
     /// RX/TX device
     rxtxdev: D,
 
+    /// Bursts of the secondary carriers for the upcoming slot, transmitted along with the main carrier burst.
+    /// Index 0 is the first secondary carrier.
+    secondary_bursts: Vec<Option<[u8; TIMESLOT_TYPE4_BITS]>>,
+
     tick: u64,
 }
 
 impl<D: RxTxDev> PhyBs<D> {
     pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
+        let num_carriers = config.config().cell.num_carriers();
         let c = &config.config().phy_io;
 
         // Create async writers for file logging of generated DL and received UL signals
@@ -70,19 +75,12 @@ impl<D: RxTxDev> PhyBs<D> {
             dl_input_file,
             ul_input_file,
             rxtxdev,
+            secondary_bursts: vec![None; num_carriers - 1],
             tick: 0,
         }
     }
 
-    fn send_rxblock_to_lmac(
-        queue: &mut MessageQueue,
-        train_type: TrainingSequence,
-        burst_type: BurstType,
-        block_type: PhyBlockType,
-        block_num: PhyBlockNum,
-        bits: BitBuffer,
-        dltime: TdmaTime,
-    ) {
+    fn send_rxblock_to_lmac(queue: &mut MessageQueue, prim: TpUnitdataInd, dltime: TdmaTime) {
         // Uplink timeslot is two after downlink. Thus was transmitted at dltime - 2
         let msg_ts = dltime.add_timeslots(-2);
         let sapmsg = SapMsg {
@@ -90,18 +88,12 @@ impl<D: RxTxDev> PhyBs<D> {
             src: TetraEntity::Phy,
             dest: TetraEntity::Lmac,
             dltime: msg_ts,
-            msg: SapMsgInner::TpUnitdataInd(TpUnitdataInd {
-                train_type,
-                burst_type,
-                block_type,
-                block_num,
-                block: bits,
-            }),
+            msg: SapMsgInner::TpUnitdataInd(prim),
         };
         queue.push_back(sapmsg);
     }
 
-    fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, dltime: TdmaTime) {
+    pub(crate) fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, dltime: TdmaTime, carrier: u8) {
         let train_seq = burst.train_type;
         match train_seq {
             TrainingSequence::NormalTrainSeq1 => {
@@ -112,7 +104,18 @@ impl<D: RxTxDev> PhyBs<D> {
                 blk.copy_bits_from_bitarr(&burst.bits[NUB_BLK2_OFFSET..NUB_BLK2_OFFSET + NUB_BLK_BITS]);
                 blk.seek(0);
 
-                Self::send_rxblock_to_lmac(queue, train_seq, BurstType::NUB, PhyBlockType::NUB, PhyBlockNum::Both, blk, dltime);
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    TpUnitdataInd {
+                        train_type: train_seq,
+                        burst_type: BurstType::NUB,
+                        block_type: PhyBlockType::NUB,
+                        block_num: PhyBlockNum::Both,
+                        block: blk,
+                        carrier,
+                    },
+                    dltime,
+                );
             }
 
             TrainingSequence::NormalTrainSeq2 => {
@@ -123,20 +126,26 @@ impl<D: RxTxDev> PhyBs<D> {
 
                 Self::send_rxblock_to_lmac(
                     queue,
-                    train_seq,
-                    BurstType::NUB,
-                    PhyBlockType::NUB,
-                    PhyBlockNum::Block1,
-                    blk1,
+                    TpUnitdataInd {
+                        train_type: train_seq,
+                        burst_type: BurstType::NUB,
+                        block_type: PhyBlockType::NUB,
+                        block_num: PhyBlockNum::Block1,
+                        block: blk1,
+                        carrier,
+                    },
                     dltime,
                 );
                 Self::send_rxblock_to_lmac(
                     queue,
-                    train_seq,
-                    BurstType::NUB,
-                    PhyBlockType::NUB,
-                    PhyBlockNum::Block2,
-                    blk2,
+                    TpUnitdataInd {
+                        train_type: train_seq,
+                        burst_type: BurstType::NUB,
+                        block_type: PhyBlockType::NUB,
+                        block_num: PhyBlockNum::Block2,
+                        block: blk2,
+                        carrier,
+                    },
                     dltime,
                 );
             }
@@ -150,11 +159,14 @@ impl<D: RxTxDev> PhyBs<D> {
 
                 Self::send_rxblock_to_lmac(
                     queue,
-                    train_seq,
-                    BurstType::CUB,
-                    PhyBlockType::SSN1,
-                    PhyBlockNum::Block1,
-                    blk,
+                    TpUnitdataInd {
+                        train_type: train_seq,
+                        burst_type: BurstType::CUB,
+                        block_type: PhyBlockType::SSN1,
+                        block_num: PhyBlockNum::Block1,
+                        block: blk,
+                        carrier,
+                    },
                     dltime,
                 );
             }
@@ -163,73 +175,98 @@ impl<D: RxTxDev> PhyBs<D> {
         }
     }
 
+    /// Build the NDB or SDB burst for a slot from the LMAC blocks
+    fn build_dl_burst(prim: TpUnitdataReqSlot) -> [u8; TIMESLOT_TYPE4_BITS] {
+        // Convert BBK block to bitarr
+        assert!(prim.bbk.is_some());
+        let mut bbk = [0u8; 30];
+        prim.bbk.unwrap().to_bitarr(&mut bbk);
+
+        // Build NDB or SDB burst
+        match prim.burst_type {
+            BurstType::SDB => {
+                // SDB burst
+                assert!(prim.train_type == TrainingSequence::SyncTrainSeq);
+                assert!(prim.blk1.is_some() && prim.blk2.is_some());
+
+                let mut blk1 = [0u8; 120];
+                let mut blk2 = [0u8; 216];
+                prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for SDB
+                prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for SDB
+
+                slotter::build_sdb(&blk1, &bbk, &blk2)
+            }
+            BurstType::NDB => {
+                let mut blk1 = [0u8; 216];
+                let mut blk2 = [0u8; 216];
+
+                match prim.train_type {
+                    TrainingSequence::NormalTrainSeq1 => {
+                        // Single large block
+                        assert!(prim.blk1.is_some() && prim.blk2.is_none());
+                        let mut blk1_src = prim.blk1.unwrap(); // Guaranteed for NDB
+                        blk1_src.to_bitarr(&mut blk1);
+                        blk1_src.to_bitarr(&mut blk2);
+                    }
+                    TrainingSequence::NormalTrainSeq2 => {
+                        // Two half slots
+                        assert!(prim.blk1.is_some() && prim.blk2.is_some());
+                        prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for NDB
+                        prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for NDB trainseq 2
+                    }
+                    _ => panic!("Unsupported training sequence for NDB burst"),
+                }
+
+                slotter::build_ndb(prim.train_type, &blk1, &bbk, &blk2)
+            }
+            _ => panic!(),
+        }
+    }
+
     fn rx_tpsap_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         // Handle TpUnitdataReq with a TX slot
         // Prepare TxSlotBits for transmission
         // TODO FIXME: optimize
 
-        self.tick += 1;
-
         let SapMsgInner::TpUnitdataReq(prim) = message.msg else { panic!() };
 
+        // Secondary carrier bursts are sent before the main carrier burst of the same slot,
+        // keep them until the main carrier burst arrives and all carriers can be transmitted at once
+        if prim.carrier != 0 {
+            let Some(burst) = self.secondary_bursts.get_mut(prim.carrier as usize - 1) else {
+                tracing::warn!("rx_tpsap_prim: dropping burst for unknown carrier {}", prim.carrier);
+                return;
+            };
+            *burst = Some(Self::build_dl_burst(prim));
+            return;
+        }
+
+        self.tick += 1;
+
         // Generate block (from file or from LMAC data)
         let mut dl_burst = [0u8; TIMESLOT_TYPE4_BITS];
         if let Some(dl_input_file) = &mut self.dl_input_file {
             // Code for testing mode, when replaying from DL input file
             dl_input_file.read_block(&mut dl_burst).expect("Failed to read dl_input_file data");
         } else {
-            // We received data from LMAC, convert BBK block to bitarr
-            assert!(prim.bbk.is_some());
-            let mut bbk = [0u8; 30];
-            prim.bbk.unwrap().to_bitarr(&mut bbk);
-
-            // Build NDB or SDB burst
-            dl_burst = match prim.burst_type {
-                BurstType::SDB => {
-                    // SDB burst
-                    assert!(prim.train_type == TrainingSequence::SyncTrainSeq);
-                    assert!(prim.blk1.is_some() && prim.blk2.is_some());
-
-                    let mut blk1 = [0u8; 120];
-                    let mut blk2 = [0u8; 216];
-                    prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for SDB
-                    prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for SDB
-
-                    slotter::build_sdb(&blk1, &bbk, &blk2)
-                }
-                BurstType::NDB => {
-                    let mut blk1 = [0u8; 216];
-                    let mut blk2 = [0u8; 216];
-
-                    match prim.train_type {
-                        TrainingSequence::NormalTrainSeq1 => {
-                            // Single large block
-                            assert!(prim.blk1.is_some() && prim.blk2.is_none());
-                            let mut blk1_src = prim.blk1.unwrap(); // Guaranteed for NDB
-                            blk1_src.to_bitarr(&mut blk1);
-                            blk1_src.to_bitarr(&mut blk2);
-                        }
-                        TrainingSequence::NormalTrainSeq2 => {
-                            // Two half slots
-                            assert!(prim.blk1.is_some() && prim.blk2.is_some());
-                            prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for NDB
-                            prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for NDB trainseq 2
-                        }
-                        _ => panic!("Unsupported training sequence for NDB burst"),
-                    }
-
-                    slotter::build_ndb(prim.train_type, &blk1, &bbk, &blk2)
-                }
-                _ => panic!(),
-            };
+            dl_burst = Self::build_dl_burst(prim);
         }
 
-        // Prepare the TX slot for the tx device
-        let tx_slot: [TxSlotBits; 1] = [TxSlotBits {
-            time: message.dltime.add_timeslots(MACSCHED_TX_AHEAD as i32),
+        // Prepare the TX slot of every carrier for the tx device, the main carrier first.
+        // Secondary carriers without a burst stay silent in this slot.
+        let time = message.dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
+        let secondary_bursts: Vec<_> = self.secondary_bursts.iter_mut().map(Option::take).collect();
+        let mut tx_slot = Vec::with_capacity(1 + secondary_bursts.len());
+        tx_slot.push(TxSlotBits {
+            time,
             slot: Some(&dl_burst),
             ..Default::default()
-        }];
+        });
+        tx_slot.extend(secondary_bursts.iter().map(|burst| TxSlotBits {
+            time,
+            slot: burst.as_ref().map(|burst| &burst[..]),
+            ..Default::default()
+        }));
 
         // Code for testing mode, when capturing all DL output to file
         if let Some(dl_tx_sender) = &self.dl_tx_sender {
@@ -239,7 +276,12 @@ impl<D: RxTxDev> PhyBs<D> {
         // Transmit slot and receive rx data (if any trainseq was found)
         // This function is blocking and the source of timing sync in the whole stack
         // let tick_done = std::time::Instant::now();
-        let rx = self.rxtxdev.rxtx_timeslot(&tx_slot).expect("Got error from rxtx_timeslot");
+        let rx = match self.rxtxdev.rxtx_timeslot(&tx_slot) {
+            Ok(rx) => rx,
+            // Replayed input has ended, the stack is about to stop
+            Err(RxTxDevError::RxEndOfData) => return,
+            Err(e) => panic!("Got error from rxtx_timeslot: {:?}", e),
+        };
         // let new_tick_start = std::time::Instant::now();
         // let elapsed = new_tick_start.duration_since(tick_done);
         // tracing::debug!("rxtx_timeslot: tick_done {:?}, new_tick_start {:?}, elapsed {:?}", tick_done, new_tick_start, elapsed);
@@ -248,7 +290,9 @@ impl<D: RxTxDev> PhyBs<D> {
         // In exceptional cases, we might receive multiple slots (multiple possible detected bursts in one timeslot)
         // This may be due to two subslots, or due to false psoitives in training seq detection
         // The Lmac error correction will eliminate the false positives
-        for rx_slot in rx {
+        // The device returns one slot per uplink carrier, in carrier order
+        for (carrier, rx_slot) in rx.into_iter().enumerate() {
+            let carrier = carrier as u8;
             if let Some(rx_slot) = rx_slot {
                 let mut slot_sent = false;
                 if rx_slot.slot.train_type != TrainingSequence::NotFound {
@@ -259,7 +303,7 @@ impl<D: RxTxDev> PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(3, self.tick, rx_slot.slot.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, self.dltime, carrier);
                     slot_sent = true;
                 }
                 if rx_slot.subslot1.train_type != TrainingSequence::NotFound {
@@ -272,7 +316,7 @@ impl<D: RxTxDev> PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(1, self.tick, rx_slot.subslot1.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot1, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot1, self.dltime, carrier);
                     slot_sent = true;
                 }
                 if rx_slot.subslot2.train_type != TrainingSequence::NotFound {
@@ -285,7 +329,7 @@ impl<D: RxTxDev> PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(2, self.tick, rx_slot.subslot2.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot2, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot2, self.dltime, carrier);
                 }
             }
         }
@@ -0,0 +1,107 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{Sap, TdmaTime, TrainingSequence};
+use tetra_pdus::phy::traits::rxtx_dev::{RxBurstBits, RxTxDev, RxTxDevError};
+use tetra_saps::SapMsg;
+
+use crate::phy::phy_bs::PhyBs;
+use crate::phy::phy_ms::PhyMs;
+use crate::{MessageQueue, TetraEntityTrait};
+
+/// Passive monitor PHY
+///
+/// Receive only. Each tick receives one downlink slot and, if the uplink is monitored as well,
+/// the uplink slot demodulated alongside it. Downlink bursts are split like on an MS and uplink
+/// bursts like on a BS, so the LMAC gets the same blocks either of them would.
+pub struct PhyMon<D: RxTxDev> {
+    config: SharedConfig,
+    dltime: TdmaTime,
+
+    /// RX device slot number of the latest received downlink slot
+    last_rx_slot: Option<TdmaTime>,
+
+    /// RX device
+    rxtxdev: D,
+}
+
+impl<D: RxTxDev> PhyMon<D> {
+    pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
+        Self {
+            config,
+            dltime: TdmaTime::default(), // updated in tick_start
+            last_rx_slot: None,
+            rxtxdev,
+        }
+    }
+
+    fn send_ul_burst_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, dltime: TdmaTime) {
+        match burst.train_type {
+            TrainingSequence::NormalTrainSeq1 | TrainingSequence::NormalTrainSeq2 | TrainingSequence::ExtendedTrainSeq => {
+                PhyBs::<D>::split_rxslot_and_send_to_lmac(queue, burst, dltime, 0);
+            }
+            TrainingSequence::NotFound => {}
+            other => tracing::debug!("Ignoring {:?} in uplink slot", other),
+        }
+    }
+}
+
+impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyMon<D> {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Phy
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        // Nothing is ever transmitted in monitor mode
+        tracing::warn!("rx_prim: dropping {:?} on {:?}", message.msg, message.sap);
+        debug_assert!(message.sap != Sap::TpSap, "monitor LMAC must not transmit");
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+
+        // Blocks until the next downlink slot has been received
+        let rx = match self.rxtxdev.rxtx_timeslot(&[]) {
+            Ok(rx) => rx,
+            // Replayed input has ended, the stack is about to stop
+            Err(RxTxDevError::RxEndOfData) => return,
+            Err(e) => panic!("Got error from rxtx_timeslot: {:?}", e),
+        };
+
+        // The monitor pair yields the downlink slot, followed by the uplink slot if monitored
+        let mut rx = rx.into_iter();
+        let dl_slot = rx.next().flatten();
+        let ul_slot = rx.next().flatten();
+
+        if let Some(dl_slot) = dl_slot {
+            if let Some(last) = self.last_rx_slot
+                && last.add_timeslots(1) != dl_slot.time
+            {
+                tracing::warn!(
+                    "Downlink slot gap: {} -> {}, MAC timing is off until the next SYNC",
+                    last,
+                    dl_slot.time
+                );
+            }
+            self.last_rx_slot = Some(dl_slot.time);
+
+            if dl_slot.slot.train_type != TrainingSequence::NotFound {
+                tracing::debug!(ts=%self.dltime, "tick_start got {:?} on downlink", dl_slot.slot.train_type);
+                PhyMs::<D>::split_rxslot_and_send_to_lmac(queue, &dl_slot.slot, self.dltime);
+            }
+        }
+
+        // Uplink bursts are sent to the LMAC after the downlink ones, which keep the LMAC in sync
+        if let Some(ul_slot) = ul_slot {
+            for burst in [&ul_slot.slot, &ul_slot.subslot1, &ul_slot.subslot2] {
+                if burst.train_type != TrainingSequence::NotFound {
+                    tracing::debug!(ts=%self.dltime, "tick_start got {:?} on uplink", burst.train_type);
+                }
+                Self::send_ul_burst_to_lmac(queue, burst, self.dltime);
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
+use tetra_pdus::phy::traits::rxtx_dev::{RxBurstBits, RxTxDev, RxTxDevError, TxSlotBits};
+use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::phy::components::{burst_consts::*, slotter, train_consts::TIMESLOT_TYPE4_BITS};
+use crate::{MessageQueue, TetraEntityTrait};
+
+/// Uplink bursts to be transmitted in the next uplink slot
+#[derive(Default)]
+struct UlBursts {
+    slot: Option<[u8; NUB_BITS]>,
+    subslot1: Option<[u8; CUB_BITS]>,
+    subslot2: Option<[u8; CUB_BITS]>,
+}
+
+/// Mobile station PHY
+///
+/// Each tick receives one downlink slot. The RX device is the source of timing:
+/// uplink slot n is transmitted two slots after downlink slot n has been received,
+/// so the uplink burst the MAC produced while processing a downlink slot
+/// goes out with the next call to the RX device.
+pub struct PhyMs<D: RxTxDev> {
+    config: SharedConfig,
+    dltime: TdmaTime,
+
+    /// RX device slot number of the latest received downlink slot
+    last_rx_slot: Option<TdmaTime>,
+
+    /// Bursts handed to us by the LMAC for the uplink slot paired with the latest downlink slot
+    ul_bursts: UlBursts,
+
+    /// RX/TX device
+    rxtxdev: D,
+}
+
+impl<D: RxTxDev> PhyMs<D> {
+    pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
+        Self {
+            config,
+            dltime: TdmaTime::default(), // updated in tick_start
+            last_rx_slot: None,
+            ul_bursts: UlBursts::default(),
+            rxtxdev,
+        }
+    }
+
+    fn send_rxblock_to_lmac(
+        queue: &mut MessageQueue,
+        train_type: TrainingSequence,
+        burst_type: BurstType,
+        block_type: PhyBlockType,
+        block_num: PhyBlockNum,
+        bits: BitBuffer,
+        dltime: TdmaTime,
+    ) {
+        let sapmsg = SapMsg {
+            sap: Sap::TpSap,
+            src: TetraEntity::Phy,
+            dest: TetraEntity::Lmac,
+            dltime,
+            msg: SapMsgInner::TpUnitdataInd(TpUnitdataInd {
+                train_type,
+                burst_type,
+                block_type,
+                block_num,
+                block: bits,
+                carrier: 0,
+            }),
+        };
+        queue.push_back(sapmsg);
+    }
+
+    /// Split a downlink burst into its broadcast block and one or two logical channel blocks.
+    /// The broadcast block is sent first, as it determines how the other blocks are interpreted.
+    pub(crate) fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, dltime: TdmaTime) {
+        let train_seq = burst.train_type;
+        assert!(burst.bits.len() == TIMESLOT_TYPE4_BITS);
+        let bits = burst.bits;
+
+        match train_seq {
+            TrainingSequence::SyncTrainSeq => {
+                let bbk = BitBuffer::from_bitarr(&bits[SB_BBK_OFFSET..SB_BBK_OFFSET + SB_BBK_BITS]);
+                let blk1 = BitBuffer::from_bitarr(&bits[SB_BLK1_OFFSET..SB_BLK1_OFFSET + SB_BLK1_BITS]);
+                let blk2 = BitBuffer::from_bitarr(&bits[SB_BLK2_OFFSET..SB_BLK2_OFFSET + SB_BLK2_BITS]);
+
+                // SB1 goes first, as the BSCH carries the scrambling code needed for the other blocks
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_seq,
+                    BurstType::SDB,
+                    PhyBlockType::SB1,
+                    PhyBlockNum::Block1,
+                    blk1,
+                    dltime,
+                );
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_seq,
+                    BurstType::SDB,
+                    PhyBlockType::BBK,
+                    PhyBlockNum::Undefined,
+                    bbk,
+                    dltime,
+                );
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_seq,
+                    BurstType::SDB,
+                    PhyBlockType::SB2,
+                    PhyBlockNum::Block2,
+                    blk2,
+                    dltime,
+                );
+            }
+
+            TrainingSequence::NormalTrainSeq1 | TrainingSequence::NormalTrainSeq2 => {
+                let mut bbk = BitBuffer::new(NDB_BBK_BITS);
+                bbk.copy_bits_from_bitarr(&bits[NDB_BBK1_OFFSET..NDB_BBK1_OFFSET + NDB_BBK1_BITS]);
+                bbk.copy_bits_from_bitarr(&bits[NDB_BBK2_OFFSET..NDB_BBK2_OFFSET + NDB_BBK2_BITS]);
+                bbk.seek(0);
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_seq,
+                    BurstType::NDB,
+                    PhyBlockType::BBK,
+                    PhyBlockNum::Undefined,
+                    bbk,
+                    dltime,
+                );
+
+                if train_seq == TrainingSequence::NormalTrainSeq1 {
+                    let mut blk = BitBuffer::new(NDB_BLK_BITS * 2);
+                    blk.copy_bits_from_bitarr(&bits[NDB_BLK1_OFFSET..NDB_BLK1_OFFSET + NDB_BLK_BITS]);
+                    blk.copy_bits_from_bitarr(&bits[NDB_BLK2_OFFSET..NDB_BLK2_OFFSET + NDB_BLK_BITS]);
+                    blk.seek(0);
+                    Self::send_rxblock_to_lmac(queue, train_seq, BurstType::NDB, PhyBlockType::NDB, PhyBlockNum::Both, blk, dltime);
+                } else {
+                    let blk1 = BitBuffer::from_bitarr(&bits[NDB_BLK1_OFFSET..NDB_BLK1_OFFSET + NDB_BLK_BITS]);
+                    let blk2 = BitBuffer::from_bitarr(&bits[NDB_BLK2_OFFSET..NDB_BLK2_OFFSET + NDB_BLK_BITS]);
+                    Self::send_rxblock_to_lmac(
+                        queue,
+                        train_seq,
+                        BurstType::NDB,
+                        PhyBlockType::NDB,
+                        PhyBlockNum::Block1,
+                        blk1,
+                        dltime,
+                    );
+                    Self::send_rxblock_to_lmac(
+                        queue,
+                        train_seq,
+                        BurstType::NDB,
+                        PhyBlockType::NDB,
+                        PhyBlockNum::Block2,
+                        blk2,
+                        dltime,
+                    );
+                }
+            }
+
+            _ => tracing::warn!("Unexpected {:?} in downlink slot", train_seq),
+        }
+    }
+
+    /// Build the uplink burst(s) for a slot from LMAC blocks
+    fn rx_tpsap_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::TpUnitdataReq(prim) = message.msg else { panic!() };
+        let TpUnitdataReqSlot {
+            train_type,
+            burst_type,
+            blk1,
+            blk2,
+            ..
+        } = prim;
+
+        match burst_type {
+            BurstType::NUB => {
+                let mut bits1 = [0u8; NUB_BLK_BITS];
+                let mut bits2 = [0u8; NUB_BLK_BITS];
+                match train_type {
+                    TrainingSequence::NormalTrainSeq1 => {
+                        // Single full slot block
+                        let mut blk = blk1.expect("NUB needs a block");
+                        assert!(blk.get_len() == NUB_BLK_BITS * 2);
+                        let mut bits = [0u8; NUB_BLK_BITS * 2];
+                        blk.to_bitarr(&mut bits);
+                        bits1.copy_from_slice(&bits[..NUB_BLK_BITS]);
+                        bits2.copy_from_slice(&bits[NUB_BLK_BITS..]);
+                    }
+                    TrainingSequence::NormalTrainSeq2 => {
+                        // Two half slots, e.g. stolen traffic
+                        blk1.expect("NUB needs block 1").to_bitarr(&mut bits1);
+                        blk2.expect("NUB needs block 2").to_bitarr(&mut bits2);
+                    }
+                    _ => panic!("Unsupported training sequence for NUB burst"),
+                }
+                self.ul_bursts.slot = Some(slotter::build_nub(train_type, &bits1, &bits2));
+            }
+            BurstType::CUB => {
+                // A control uplink burst in either or both subslots, each carrying one SCH/HU block
+                for (blk, subslot) in [(blk1, &mut self.ul_bursts.subslot1), (blk2, &mut self.ul_bursts.subslot2)] {
+                    if let Some(mut blk) = blk {
+                        assert!(blk.get_len() == CUB_BLK_BITS * 2);
+                        let mut bits = [0u8; CUB_BLK_BITS * 2];
+                        blk.to_bitarr(&mut bits);
+                        let (ssn1, ssn2) = bits.split_at(CUB_BLK_BITS);
+                        *subslot = Some(slotter::build_cub(ssn1.try_into().unwrap(), ssn2.try_into().unwrap()));
+                    }
+                }
+            }
+            _ => panic!("Unsupported burst type {:?} for uplink", burst_type),
+        }
+    }
+
+    fn rx_tpc_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+        unimplemented!();
+    }
+}
+
+impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyMs<D> {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Phy
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match message.sap {
+            Sap::TpSap => {
+                self.rx_tpsap_prim(queue, message);
+            }
+            Sap::TpcSap => {
+                self.rx_tpc_prim(queue, message);
+            }
+            _ => {
+                panic!();
+            }
+        }
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+
+        // Transmit whatever the MAC produced for the uplink slot paired with the previous downlink slot
+        let ul = std::mem::take(&mut self.ul_bursts);
+        let tx_slot = [TxSlotBits {
+            time: self.last_rx_slot.map(|t| t.add_timeslots(2)).unwrap_or_default(),
+            slot: ul.slot.as_ref().map(|b| b.as_slice()),
+            subslot1: ul.subslot1.as_ref().map(|b| b.as_slice()),
+            subslot2: ul.subslot2.as_ref().map(|b| b.as_slice()),
+        }];
+
+        // Blocks until the next downlink slot has been received
+        let rx = match self.rxtxdev.rxtx_timeslot(&tx_slot) {
+            Ok(rx) => rx,
+            // Replayed input has ended, the stack is about to stop
+            Err(RxTxDevError::RxEndOfData) => return,
+            Err(e) => panic!("Got error from rxtx_timeslot: {:?}", e),
+        };
+
+        // Only the downlink monitor produces slots, take the first one
+        let Some(rx_slot) = rx.into_iter().flatten().next() else {
+            return;
+        };
+        if let Some(last) = self.last_rx_slot
+            && last.add_timeslots(1) != rx_slot.time
+        {
+            tracing::warn!(
+                "Downlink slot gap: {} -> {}, MAC timing is off until the next SYNC",
+                last,
+                rx_slot.time
+            );
+        }
+        self.last_rx_slot = Some(rx_slot.time);
+
+        if rx_slot.slot.train_type != TrainingSequence::NotFound {
+            tracing::debug!(ts=%self.dltime, "tick_start got {:?}", rx_slot.slot.train_type);
+            Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, self.dltime);
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod pdp_context;
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use tetra_config::bluestation::CfgSndcp;
+
+#[derive(Debug, PartialEq)]
+pub enum PdpContextErr {
+    InvalidNsapi { nsapi: u8 },
+    NsapiInUse { issi: u32, nsapi: u8 },
+    PoolExhausted,
+    AddressNotInPool { addr: Ipv4Addr },
+    AddressInUse { addr: Ipv4Addr },
+}
+
+/// An active PDP context, binding an NSAPI of an MS to an IPv4 address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdpContext {
+    pub issi: u32,
+    pub nsapi: u8,
+    pub ip_addr: Ipv4Addr,
+}
+
+/// Tracks the PDP contexts of all MSs and assigns their addresses from the configured pool
+pub struct PdpContextMgr {
+    pool_start: Ipv4Addr,
+    pool_end: Ipv4Addr,
+    /// Active contexts, by (ISSI, NSAPI)
+    contexts: HashMap<(u32, u8), PdpContext>,
+    /// Reverse lookup from assigned address to (ISSI, NSAPI), for routing downlink packets
+    by_addr: HashMap<Ipv4Addr, (u32, u8)>,
+}
+
+impl PdpContextMgr {
+    pub fn new(cfg: &CfgSndcp) -> Self {
+        Self {
+            pool_start: cfg.ip_pool_start,
+            pool_end: cfg.ip_pool_end,
+            contexts: HashMap::new(),
+            by_addr: HashMap::new(),
+        }
+    }
+
+    /// NSAPI 0 and 15 are reserved, 1-14 may be used for PDP contexts
+    pub fn nsapi_is_valid(nsapi: u8) -> bool {
+        (1..=14).contains(&nsapi)
+    }
+
+    /// Activates a PDP context. With requested_addr set, the MS asks for a static address,
+    /// which must lie within the pool and be free. Otherwise, the first free address is assigned.
+    pub fn activate(&mut self, issi: u32, nsapi: u8, requested_addr: Option<Ipv4Addr>) -> Result<PdpContext, PdpContextErr> {
+        if !Self::nsapi_is_valid(nsapi) {
+            return Err(PdpContextErr::InvalidNsapi { nsapi });
+        }
+        if self.contexts.contains_key(&(issi, nsapi)) {
+            return Err(PdpContextErr::NsapiInUse { issi, nsapi });
+        }
+
+        let ip_addr = match requested_addr {
+            Some(addr) => {
+                if !(self.pool_start..=self.pool_end).contains(&addr) {
+                    return Err(PdpContextErr::AddressNotInPool { addr });
+                }
+                if self.by_addr.contains_key(&addr) {
+                    return Err(PdpContextErr::AddressInUse { addr });
+                }
+                addr
+            }
+            None => (u32::from(self.pool_start)..=u32::from(self.pool_end))
+                .map(Ipv4Addr::from)
+                .find(|addr| !self.by_addr.contains_key(addr))
+                .ok_or(PdpContextErr::PoolExhausted)?,
+        };
+
+        let ctx = PdpContext { issi, nsapi, ip_addr };
+        self.contexts.insert((issi, nsapi), ctx);
+        self.by_addr.insert(ip_addr, (issi, nsapi));
+        Ok(ctx)
+    }
+
+    /// Deactivates a PDP context and releases its address. Returns the context if it existed.
+    pub fn deactivate(&mut self, issi: u32, nsapi: u8) -> Option<PdpContext> {
+        let ctx = self.contexts.remove(&(issi, nsapi))?;
+        self.by_addr.remove(&ctx.ip_addr);
+        Some(ctx)
+    }
+
+    /// Deactivates all PDP contexts of an MS. Returns the contexts that were removed.
+    pub fn deactivate_all(&mut self, issi: u32) -> Vec<PdpContext> {
+        let mut nsapis: Vec<u8> = self.contexts.keys().filter(|(i, _)| *i == issi).map(|(_, n)| *n).collect();
+        nsapis.sort_unstable();
+        nsapis.into_iter().filter_map(|nsapi| self.deactivate(issi, nsapi)).collect()
+    }
+
+    pub fn get(&self, issi: u32, nsapi: u8) -> Option<&PdpContext> {
+        self.contexts.get(&(issi, nsapi))
+    }
+
+    /// Finds the context to which an address is assigned
+    pub fn get_by_addr(&self, addr: Ipv4Addr) -> Option<&PdpContext> {
+        self.by_addr.get(&addr).and_then(|key| self.contexts.get(key))
+    }
+
+    pub fn num_active(&self) -> usize {
+        self.contexts.len()
+    }
+}
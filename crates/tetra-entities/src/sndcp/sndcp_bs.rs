@@ -1,32 +1,442 @@
+use std::net::Ipv4Addr;
+
+use crate::brew;
+use crate::sndcp::components::pdp_context::{PdpContext, PdpContextErr, PdpContextMgr};
 use crate::{MessageQueue, TetraEntityTrait};
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{CfgSndcp, SharedConfig};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{Sap, unimplemented_log};
-use tetra_saps::SapMsg;
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, unimplemented_log};
+use tetra_pdus::llc::consts::consts::{N251_BL_MAX_TLSDU_LEN_BITS, N271_AL_MAX_TLSDU_LEN};
+use tetra_pdus::sndcp::enums::activation_reject_cause::ActivationRejectCause;
+use tetra_pdus::sndcp::enums::address_type::SndcpAddressType;
+use tetra_pdus::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_accept::SnActivatePdpContextAccept;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_demand::SnActivatePdpContextDemand;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_reject::SnActivatePdpContextReject;
+use tetra_pdus::sndcp::pdus::sn_data::SnData;
+use tetra_pdus::sndcp::pdus::sn_deactivate_pdp_context_accept::SnDeactivatePdpContextAccept;
+use tetra_pdus::sndcp::pdus::sn_deactivate_pdp_context_demand::{DEACTIVATE_ALL_NSAPIS, SnDeactivatePdpContextDemand};
+use tetra_pdus::sndcp::pdus::sn_unitdata::SnUnitdata;
+use tetra_saps::ltpd::LtpdMleUnitdataReq;
+use tetra_saps::sn::{SnDataInd, SnPdpContextInd};
+use tetra_saps::{SapMsg, SapMsgInner};
+
+/// Highest PDU priority the MS may use on its PDP contexts
+const PDU_PRIORITY_MAX: u8 = 7;
+/// READY timer, STANDBY timer and response wait time codes sent in SN-ACTIVATE PDP CONTEXT ACCEPT.
+/// We don't run the SNDCP state machine timers ourselves, the MS uses these to pace its own transitions.
+const READY_TIMER: u8 = 6;
+const STANDBY_TIMER: u8 = 6;
+const RESPONSE_WAIT_TIME: u8 = 4;
+
+/// Maximum transmission unit element values and the packet size they represent
+const MTU_CODES: [(u64, u16); 5] = [(1, 296), (2, 576), (3, 1006), (4, 1500), (5, 2002)];
+
+/// Bits preceding the N-PDU in an SN-DATA or SN-UNITDATA TL-SDU: MLE protocol discriminator, PDU type, NSAPI, DCOMP, PCOMP
+const SN_DATA_OVERHEAD_BITS: u32 = 3 + 4 + 4 + 4 + 4;
+
+/// Largest N-PDU that fits in a single basic link TL-SDU
+const BL_MAX_N_PDU_LEN: u16 = ((N251_BL_MAX_TLSDU_LEN_BITS - SN_DATA_OVERHEAD_BITS) / 8) as u16;
+
+/// Largest N-PDU that fits in a single advanced link TL-SDU, whose length includes the FCS
+const AL_MAX_N_PDU_LEN: u16 = ((N271_AL_MAX_TLSDU_LEN * 8 - 32 - SN_DATA_OVERHEAD_BITS) / 8) as u16;
+
+/// Maximum transmission unit offered to MSs, as (element value, bytes). SNDCP does not segment N-PDUs,
+/// so the MTU is bounded by both the configured MTU and the room left in a basic link TL-SDU, or in an
+/// advanced link TL-SDU when the cell offers the advanced link.
+pub fn negotiated_mtu(cfg: &CfgSndcp, advanced_link: bool) -> (u64, u16) {
+    let link_limit = if advanced_link { AL_MAX_N_PDU_LEN } else { BL_MAX_N_PDU_LEN };
+    let limit = cfg.mtu.min(link_limit);
+    MTU_CODES
+        .iter()
+        .rev()
+        .find(|(_, size)| *size <= limit)
+        .copied()
+        .unwrap_or(MTU_CODES[0])
+}
 
 pub struct Sndcp {
-    // config: Option<SharedConfig>,
     config: SharedConfig,
+    /// PDP contexts of all MSs, None if packet data is not configured
+    contexts: Option<PdpContextMgr>,
 }
 
 impl Sndcp {
     pub fn new(config: SharedConfig) -> Self {
-        Self { config }
+        let contexts = config.config().sndcp.as_ref().map(PdpContextMgr::new);
+        Self { config, contexts }
+    }
+
+    /// Returns the context bound to an IPv4 address, if any
+    pub fn get_context_by_addr(&self, addr: Ipv4Addr) -> Option<&PdpContext> {
+        self.contexts.as_ref()?.get_by_addr(addr)
+    }
+
+    /// Entity that IP packets from MSs are handed to: Brew if packet data over Brew is enabled,
+    /// the local packet data gateway otherwise
+    fn packet_data_user(&self) -> TetraEntity {
+        if brew::feature_packet_data_enabled(&self.config) {
+            TetraEntity::Brew
+        } else {
+            TetraEntity::PacketGateway
+        }
+    }
+
+    /// Negotiated MTU as (element value, bytes), None if packet data is not configured
+    fn mtu(&self) -> Option<(u64, u16)> {
+        let config = self.config.config();
+        config.sndcp.as_ref().map(|cfg| negotiated_mtu(cfg, config.cell.advanced_link))
+    }
+
+    fn rx_ltpd_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_ltpd_mle_unitdata_ind");
+        let SapMsgInner::LtpdMleUnitdataInd(prim) = message.msg else {
+            panic!()
+        };
+        let mut sdu = prim.sdu;
+        let issi = prim.received_tetra_address.ssi;
+
+        let Some(bits) = sdu.peek_bits(4) else {
+            tracing::warn!("insufficient bits: {}", sdu.dump_bin());
+            return;
+        };
+        let Ok(pdu_type) = SndcpPduType::try_from(bits) else {
+            tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
+            return;
+        };
+
+        match pdu_type {
+            SndcpPduType::SnActivatePdpContext => {
+                self.rx_sn_activate_pdp_context_demand(queue, message.dltime, issi, &mut sdu);
+            }
+            SndcpPduType::SnDeactivatePdpContextDemand => {
+                self.rx_sn_deactivate_pdp_context_demand(queue, message.dltime, issi, &mut sdu);
+            }
+            SndcpPduType::SnData => match SnData::from_bitbuf(&mut sdu) {
+                // SN-DATA carries the same fields as SN-UNITDATA
+                Ok(pdu) => self.rx_n_pdu(
+                    queue,
+                    message.dltime,
+                    issi,
+                    SnUnitdata {
+                        nsapi: pdu.nsapi,
+                        dcomp: pdu.dcomp,
+                        pcomp: pdu.pcomp,
+                        n_pdu: pdu.n_pdu,
+                    },
+                ),
+                Err(e) => tracing::warn!("Failed parsing SnData: {:?} {}", e, sdu.dump_bin()),
+            },
+            SndcpPduType::SnUnitdata => match SnUnitdata::from_bitbuf(&mut sdu) {
+                Ok(pdu) => self.rx_n_pdu(queue, message.dltime, issi, pdu),
+                Err(e) => tracing::warn!("Failed parsing SnUnitdata: {:?} {}", e, sdu.dump_bin()),
+            },
+            SndcpPduType::SnDeactivatePdpContextAccept => {
+                // We never initiate deactivation
+                tracing::debug!("ignoring SnDeactivatePdpContextAccept from {}", issi);
+            }
+            _ => {
+                unimplemented_log!("{}", pdu_type);
+            }
+        }
+    }
+
+    fn rx_sn_activate_pdp_context_demand(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sdu: &mut BitBuffer) {
+        tracing::trace!("rx_sn_activate_pdp_context_demand");
+        let pdu = match SnActivatePdpContextDemand::from_bitbuf(sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing SnActivatePdpContextDemand: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::debug!("<- {} from {}", pdu, issi);
+
+        let mtu = self.mtu().map(|(code, _)| code);
+        let user = self.packet_data_user();
+        let Some(contexts) = self.contexts.as_mut() else {
+            tracing::info!("rejecting PDP context activation by {}: packet data not configured", issi);
+            Self::send_activate_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::MsNotProvisionedForPacketData);
+            return;
+        };
+
+        let requested_addr = match SndcpAddressType::try_from(pdu.address_type as u64) {
+            Ok(SndcpAddressType::Ipv4Static) => pdu.ip_address.map(Ipv4Addr::from),
+            Ok(SndcpAddressType::Ipv4Dynamic) => None,
+            Ok(SndcpAddressType::Ipv6) => {
+                Self::send_activate_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::Ipv6NotSupported);
+                return;
+            }
+            _ => {
+                tracing::info!("rejecting PDP context activation by {}: address type {}", issi, pdu.address_type);
+                Self::send_activate_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::AnyReason);
+                return;
+            }
+        };
+
+        // A demand for an NSAPI that is already active replaces the old context, e.g. after the MS restarted
+        if let Some(old) = contexts.deactivate(issi, pdu.nsapi) {
+            tracing::info!("PDP context {}:{} reactivated, releasing {}", issi, pdu.nsapi, old.ip_addr);
+            Self::send_context_ind(queue, user, dltime, &old, false);
+        }
+
+        let ctx = match contexts.activate(issi, pdu.nsapi, requested_addr) {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                tracing::info!("rejecting PDP context activation by {}: {:?}", issi, e);
+                let cause = match e {
+                    PdpContextErr::PoolExhausted => ActivationRejectCause::InsufficientResources,
+                    PdpContextErr::AddressNotInPool { .. } | PdpContextErr::AddressInUse { .. } => {
+                        ActivationRejectCause::Ipv4StaticAddressNotCorrect
+                    }
+                    PdpContextErr::InvalidNsapi { .. } | PdpContextErr::NsapiInUse { .. } => ActivationRejectCause::AnyReason,
+                };
+                Self::send_activate_reject(queue, dltime, issi, pdu.nsapi, cause);
+                return;
+            }
+        };
+        tracing::info!("PDP context {}:{} activated with address {}", issi, ctx.nsapi, ctx.ip_addr);
+
+        let accept = SnActivatePdpContextAccept {
+            nsapi: ctx.nsapi,
+            pdu_priority_max: PDU_PRIORITY_MAX,
+            ready_timer: READY_TIMER,
+            standby_timer: STANDBY_TIMER,
+            response_wait_time: RESPONSE_WAIT_TIME,
+            address_type: pdu.address_type,
+            ip_address: Some(u32::from(ctx.ip_addr)),
+            pcomp_negotiation: 0, // No header compression
+            mtu,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        accept.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        tracing::debug!("-> {} to {}", accept, issi);
+        Self::send_sn_pdu(queue, dltime, issi, sdu);
+        Self::send_context_ind(queue, user, dltime, &ctx, true);
+    }
+
+    fn rx_sn_deactivate_pdp_context_demand(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sdu: &mut BitBuffer) {
+        tracing::trace!("rx_sn_deactivate_pdp_context_demand");
+        let pdu = match SnDeactivatePdpContextDemand::from_bitbuf(sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing SnDeactivatePdpContextDemand: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+        tracing::debug!("<- {} from {}", pdu, issi);
+
+        let released = match (self.contexts.as_mut(), pdu.nsapi) {
+            (Some(contexts), _) if pdu.deactivation_type == DEACTIVATE_ALL_NSAPIS => contexts.deactivate_all(issi),
+            (Some(contexts), Some(nsapi)) => contexts.deactivate(issi, nsapi).into_iter().collect(),
+            _ => vec![],
+        };
+        for ctx in released.iter() {
+            tracing::info!("PDP context {}:{} deactivated, releasing {}", issi, ctx.nsapi, ctx.ip_addr);
+            Self::send_context_ind(queue, self.packet_data_user(), dltime, ctx, false);
+        }
+
+        // Accept even if nothing was active, the MS considers its contexts gone either way
+        let accept = SnDeactivatePdpContextAccept {
+            deactivation_type: pdu.deactivation_type,
+            nsapi: pdu.nsapi,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        accept.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        tracing::debug!("-> {} to {}", accept, issi);
+        Self::send_sn_pdu(queue, dltime, issi, sdu);
+    }
+
+    /// Handles an N-PDU received in SN-DATA or SN-UNITDATA and passes it on to the packet data gateway
+    fn rx_n_pdu(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, pdu: SnUnitdata) {
+        let SnUnitdata {
+            nsapi,
+            dcomp,
+            pcomp,
+            n_pdu: packet,
+        } = pdu;
+        let Some(ctx) = self.contexts.as_ref().and_then(|c| c.get(issi, nsapi)) else {
+            tracing::warn!("dropping N-PDU from {} on inactive NSAPI {}", issi, nsapi);
+            return;
+        };
+        if dcomp != 0 || pcomp != 0 {
+            // We never negotiate compression
+            tracing::warn!(
+                "dropping compressed N-PDU from {}:{} (dcomp {} pcomp {})",
+                issi,
+                nsapi,
+                dcomp,
+                pcomp
+            );
+            return;
+        }
+        if ipv4_src(&packet) != Some(ctx.ip_addr) {
+            tracing::warn!(
+                "dropping N-PDU from {}:{} not sourced from its address {}",
+                issi,
+                nsapi,
+                ctx.ip_addr
+            );
+            return;
+        }
+
+        tracing::debug!("N-PDU from {}:{}, {} bytes", issi, nsapi, packet.len());
+        queue.push_back(SapMsg {
+            sap: Sap::SnSap,
+            src: TetraEntity::Sndcp,
+            dest: self.packet_data_user(),
+            dltime,
+            msg: SapMsgInner::SnDataInd(SnDataInd { issi, nsapi, packet }),
+        });
+    }
+
+    fn rx_sn_data_req(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_sn_data_req");
+        let SapMsgInner::SnDataReq(prim) = message.msg else { panic!() };
+
+        let Some(dst) = ipv4_dst(&prim.packet) else {
+            tracing::warn!("dropping non-IPv4 packet of {} bytes", prim.packet.len());
+            return;
+        };
+        let Some(ctx) = self.get_context_by_addr(dst).copied() else {
+            tracing::debug!("dropping packet for {}: no PDP context", dst);
+            return;
+        };
+        let mtu = self.mtu().map_or(u16::MAX, |(_, mtu)| mtu);
+        if prim.packet.len() > mtu as usize {
+            tracing::warn!("dropping packet for {}: {} bytes exceeds MTU {}", dst, prim.packet.len(), mtu);
+            return;
+        }
+
+        let pdu = SnUnitdata {
+            nsapi: ctx.nsapi,
+            dcomp: 0,
+            pcomp: 0,
+            n_pdu: prim.packet,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        tracing::debug!("-> {} to {}", pdu, ctx.issi);
+        Self::send_sn_pdu(queue, message.dltime, ctx.issi, sdu);
+    }
+
+    fn send_activate_reject(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, nsapi: u8, cause: ActivationRejectCause) {
+        let pdu = SnActivatePdpContextReject {
+            nsapi,
+            reject_cause: cause.into_raw() as u8,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        tracing::debug!("-> {} to {}", pdu, issi);
+        Self::send_sn_pdu(queue, dltime, issi, sdu);
+    }
+
+    fn send_context_ind(queue: &mut MessageQueue, dest: TetraEntity, dltime: TdmaTime, ctx: &PdpContext, active: bool) {
+        queue.push_back(SapMsg {
+            sap: Sap::SnSap,
+            src: TetraEntity::Sndcp,
+            dest,
+            dltime,
+            msg: SapMsgInner::SnPdpContextInd(SnPdpContextInd {
+                issi: ctx.issi,
+                nsapi: ctx.nsapi,
+                ip_addr: ctx.ip_addr,
+                active,
+            }),
+        });
+    }
+
+    /// Sends a serialized SN-PDU to the MS over the acknowledged basic link
+    fn send_sn_pdu(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, mut sdu: BitBuffer) {
+        sdu.seek(0);
+
+        queue.push_back(SapMsg {
+            sap: Sap::TlpdSap,
+            src: TetraEntity::Sndcp,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LtpdMleUnitdataReq(LtpdMleUnitdataReq {
+                sdu,
+                handle: 0,
+                layer2service: Layer2Service::Acknowledged,
+                unacked_bl_repetitions: 0,
+                pdu_prio: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                channel_advice_flag: false,
+                data_class_info: 0,
+                data_prio: 0,
+                mle_data_prio_flag: false,
+                packet_data_flag: true,
+                scheduled_data_status: 0,
+                max_schedule_interval: 0,
+                fcs_flag: false,
+                main_address: TetraAddress::new(issi, SsiType::Ssi),
+            }),
+        });
+    }
+
+    fn rx_tlpd_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tlpd_prim");
+        match &message.msg {
+            SapMsgInner::LtpdMleUnitdataInd(_) => {
+                self.rx_ltpd_mle_unitdata_ind(queue, message);
+            }
+            _ => panic!(),
+        }
+    }
+
+    fn rx_sn_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_sn_prim");
+        match &message.msg {
+            SapMsgInner::SnDataReq(_) => {
+                self.rx_sn_data_req(queue, message);
+            }
+            _ => panic!(),
+        }
     }
 }
 
+/// Source address of an IPv4 packet, None if the packet is not IPv4
+pub fn ipv4_src(packet: &[u8]) -> Option<Ipv4Addr> {
+    if packet.len() < 20 || packet[0] >> 4 != 4 {
+        return None;
+    }
+    Some(Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]))
+}
+
+/// Destination address of an IPv4 packet, None if the packet is not IPv4
+pub fn ipv4_dst(packet: &[u8]) -> Option<Ipv4Addr> {
+    if packet.len() < 20 || packet[0] >> 4 != 4 {
+        return None;
+    }
+    Some(Ipv4Addr::new(packet[16], packet[17], packet[18], packet[19]))
+}
+
 impl TetraEntityTrait for Sndcp {
     fn entity(&self) -> TetraEntity {
         TetraEntity::Sndcp
     }
 
-    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
-        // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for SNDCP
-        // OR.. SN-SAP? TODO FIXME check docs
-        assert!(message.sap == Sap::TlpdSap);
-        unimplemented_log!("sndcp not implemented");
+        match message.sap {
+            Sap::TlpdSap => {
+                self.rx_tlpd_prim(queue, message);
+            }
+            Sap::SnSap => {
+                self.rx_sn_prim(queue, message);
+            }
+            _ => {
+                panic!();
+            }
+        }
     }
 }
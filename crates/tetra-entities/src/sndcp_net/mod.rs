@@ -0,0 +1,14 @@
+pub mod net_entity_sndcp_worker;
+pub mod packet_interface;
+#[cfg(target_os = "linux")]
+pub mod tun;
+
+use net_entity_sndcp_worker::NetEntitySndcpWorker;
+use packet_interface::ChannelInterface;
+
+/// Packet data gateway worker on a Linux TUN interface
+#[cfg(target_os = "linux")]
+pub type SndcpWorkerTun = NetEntitySndcpWorker<tun::TunInterface>;
+
+/// Packet data gateway worker on a user-space interface
+pub type SndcpWorkerChannel = NetEntitySndcpWorker<ChannelInterface>;
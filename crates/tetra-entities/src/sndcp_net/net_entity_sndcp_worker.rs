@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+
+use crate::network::netentity::NetEntityWorker;
+use crate::sndcp::sndcp_bs::{ipv4_dst, ipv4_src};
+use crate::sndcp_net::packet_interface::PacketInterface;
+use tetra_core::{TdmaTime, tetra_common::Sap, tetra_entities::TetraEntity};
+use tetra_saps::{
+    sapmsg::{SapMsg, SapMsgInner},
+    sn::{SnDataInd, SnDataReq, SnPdpContextInd},
+};
+
+/// How long to wait for host packets before checking for messages from the stack again
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// Back-off after the interface failed, to avoid flooding the log
+const ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// PDP context an MS address is routed to
+#[derive(Debug, Clone, Copy)]
+struct Route {
+    issi: u32,
+    nsapi: u8,
+}
+
+/// Worker thread forwarding IP packets between the SNDCP and a host packet interface
+///
+/// Generic over interface type `I` - a TUN device, or a user-space stand-in.
+pub struct NetEntitySndcpWorker<I: PacketInterface> {
+    /// Debug label for this worker
+    label: &'static str,
+    /// TETRA entity type this network entity represents
+    entity_self: TetraEntity,
+    /// Destination entity, the other entity on our SAP
+    entity_dest: TetraEntity,
+    /// The SAP we're listening on
+    sap: Sap,
+    /// The host packet interface
+    iface: I,
+    /// Active PDP contexts by MS address
+    routes: HashMap<Ipv4Addr, Route>,
+    /// Requests receiver from main thread
+    e2w_receiver: Receiver<SapMsg>,
+    /// Response sender back to main thread
+    w2e_sender: Sender<SapMsg>,
+}
+
+impl<I: PacketInterface + 'static> NetEntityWorker for NetEntitySndcpWorker<I> {
+    type Transport = I;
+
+    fn new(
+        entity_self: TetraEntity,
+        entity_dest: TetraEntity,
+        sap: Sap,
+        w2e_sender: Sender<SapMsg>,
+        e2w_receiver: Receiver<SapMsg>,
+        transport: Self::Transport,
+    ) -> Self {
+        Self {
+            label: "NetEntitySndcpWorker",
+            entity_self,
+            entity_dest,
+            sap,
+            iface: transport,
+            routes: HashMap::new(),
+            w2e_sender,
+            e2w_receiver,
+        }
+    }
+
+    fn run(&mut self) {
+        tracing::info!("{} thread started, mtu {}", self.label, self.iface.mtu());
+
+        loop {
+            // Drain everything the stack sent us
+            loop {
+                match self.e2w_receiver.try_recv() {
+                    Ok(msg) => self.handle_stack_message(msg),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        tracing::info!("{} thread stopped", self.label);
+                        return;
+                    }
+                }
+            }
+
+            match self.iface.recv_packet(POLL_INTERVAL) {
+                Ok(Some(packet)) => self.handle_host_packet(packet),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!("{} failed to receive packet: {:?}", self.label, e);
+                    std::thread::sleep(ERROR_BACKOFF);
+                }
+            }
+        }
+    }
+}
+
+impl<I: PacketInterface> NetEntitySndcpWorker<I> {
+    fn handle_stack_message(&mut self, msg: SapMsg) {
+        match msg.msg {
+            SapMsgInner::SnPdpContextInd(ind) => self.handle_context_ind(ind),
+            SapMsgInner::SnDataInd(ind) => self.handle_data_ind(ind),
+            inner => panic!("{} Unhandled message: {:?}", self.label, inner),
+        }
+    }
+
+    /// Keep the routing table in sync with PDP context (de)activations
+    fn handle_context_ind(&mut self, ind: SnPdpContextInd) {
+        if ind.active {
+            tracing::info!("{} route {} via ISSI {} NSAPI {}", self.label, ind.ip_addr, ind.issi, ind.nsapi);
+            self.routes.insert(
+                ind.ip_addr,
+                Route {
+                    issi: ind.issi,
+                    nsapi: ind.nsapi,
+                },
+            );
+        } else if self
+            .routes
+            .get(&ind.ip_addr)
+            .is_some_and(|r| r.issi == ind.issi && r.nsapi == ind.nsapi)
+        {
+            tracing::info!("{} remove route {}", self.label, ind.ip_addr);
+            self.routes.remove(&ind.ip_addr);
+        }
+    }
+
+    /// Packet from an MS, hand it to the host
+    fn handle_data_ind(&mut self, ind: SnDataInd) {
+        if ind.packet.len() > self.iface.mtu() {
+            tracing::warn!(
+                "{} dropping {} byte packet from ISSI {}: exceeds MTU {}",
+                self.label,
+                ind.packet.len(),
+                ind.issi,
+                self.iface.mtu()
+            );
+            return;
+        }
+        tracing::trace!("{} UL {:?} {} bytes", self.label, ipv4_src(&ind.packet), ind.packet.len());
+        if let Err(e) = self.iface.send_packet(&ind.packet) {
+            tracing::error!("{} failed to send packet: {}", self.label, e);
+        }
+    }
+
+    /// Packet from the host, pass it to the SNDCP if it is addressed to one of our MSs
+    fn handle_host_packet(&mut self, packet: Vec<u8>) {
+        let Some(dst) = ipv4_dst(&packet) else {
+            tracing::trace!("{} ignoring non-IPv4 packet of {} bytes", self.label, packet.len());
+            return;
+        };
+        let Some(route) = self.routes.get(&dst) else {
+            tracing::debug!("{} no route to {}, dropping packet", self.label, dst);
+            return;
+        };
+        if packet.len() > self.iface.mtu() {
+            tracing::warn!(
+                "{} dropping {} byte packet for {}: exceeds MTU {}",
+                self.label,
+                packet.len(),
+                dst,
+                self.iface.mtu()
+            );
+            return;
+        }
+
+        tracing::trace!("{} DL {} via ISSI {} {} bytes", self.label, dst, route.issi, packet.len());
+        let msg = SapMsg {
+            sap: self.sap,
+            src: self.entity_self,
+            dest: self.entity_dest,
+            dltime: TdmaTime::default(),
+            msg: SapMsgInner::SnDataReq(SnDataReq { packet }),
+        };
+        if let Err(e) = self.w2e_sender.send(msg) {
+            tracing::error!("{} failed to send SnDataReq to main thread: {:?}", self.label, e);
+        }
+    }
+}
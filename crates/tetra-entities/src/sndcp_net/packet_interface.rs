@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, unbounded};
+
+use crate::network::transports::NetworkError;
+
+/// A host network interface exchanging raw IP packets with the packet data gateway
+pub trait PacketInterface: Send {
+    /// Maximum IP packet size the interface carries, in bytes
+    fn mtu(&self) -> usize;
+
+    /// Write a single IP packet to the host
+    fn send_packet(&mut self, packet: &[u8]) -> Result<(), NetworkError>;
+
+    /// Wait for a single IP packet from the host (blocking with timeout)
+    fn recv_packet(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError>;
+}
+
+/// User-space stand-in for a TUN interface, with the host side exposed through a ChannelInterfaceHost
+pub struct ChannelInterface {
+    mtu: usize,
+    to_host: Sender<Vec<u8>>,
+    from_host: Receiver<Vec<u8>>,
+}
+
+/// Host end of a ChannelInterface
+pub struct ChannelInterfaceHost {
+    /// Packets the host sends towards the MSs
+    pub sender: Sender<Vec<u8>>,
+    /// Packets sent by the MSs
+    pub receiver: Receiver<Vec<u8>>,
+}
+
+impl ChannelInterface {
+    pub fn new(mtu: usize) -> (Self, ChannelInterfaceHost) {
+        let (to_host, host_rx) = unbounded();
+        let (host_tx, from_host) = unbounded();
+        let iface = Self { mtu, to_host, from_host };
+        let host = ChannelInterfaceHost {
+            sender: host_tx,
+            receiver: host_rx,
+        };
+        (iface, host)
+    }
+}
+
+impl PacketInterface for ChannelInterface {
+    fn mtu(&self) -> usize {
+        self.mtu
+    }
+
+    fn send_packet(&mut self, packet: &[u8]) -> Result<(), NetworkError> {
+        self.to_host
+            .send(packet.to_vec())
+            .map_err(|_| NetworkError::SendFailed("host end dropped".to_string()))
+    }
+
+    fn recv_packet(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        match self.from_host.recv_timeout(timeout) {
+            Ok(packet) => Ok(Some(packet)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(NetworkError::ReceiveFailed("host end dropped".to_string())),
+        }
+    }
+}
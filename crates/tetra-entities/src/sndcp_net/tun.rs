@@ -0,0 +1,123 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::time::Duration;
+
+use crate::network::transports::NetworkError;
+use crate::sndcp_net::packet_interface::PacketInterface;
+
+/// Linux TUN interface, carrying raw IP packets without packet information header
+pub struct TunInterface {
+    file: File,
+    name: String,
+    mtu: usize,
+    buf: Vec<u8>,
+}
+
+impl TunInterface {
+    /// Attaches to the TUN interface with the given name, creating it if needed.
+    /// Also sets the interface MTU and brings it up; if that is not permitted, it has to be done by hand.
+    pub fn open(name: &str, mtu: usize) -> Result<Self, NetworkError> {
+        if name.is_empty() || name.len() >= libc::IFNAMSIZ {
+            return Err(NetworkError::ConnectionFailed(format!("Invalid TUN interface name '{}'", name)));
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/net/tun")
+            .map_err(|e| NetworkError::ConnectionFailed(format!("Failed to open /dev/net/tun: {}", e)))?;
+
+        let mut ifr = ifreq_for(name);
+        ifr.ifr_ifru.ifru_flags = (libc::IFF_TUN | libc::IFF_NO_PI) as libc::c_short;
+        ioctl(file.as_raw_fd(), libc::TUNSETIFF, &mut ifr)
+            .map_err(|e| NetworkError::ConnectionFailed(format!("Failed to attach to TUN interface {}: {}", name, e)))?;
+
+        let iface = Self {
+            file,
+            name: name.to_string(),
+            mtu,
+            buf: vec![0; u16::MAX as usize],
+        };
+        if let Err(e) = iface.configure_link() {
+            tracing::warn!("Could not set MTU {} and bring up {}: {}", mtu, name, e);
+        }
+        Ok(iface)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn configure_link(&self) -> io::Result<()> {
+        // SAFETY: plain socket creation, ownership of the descriptor is taken right away
+        let sock = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+        if sock < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: sock is a freshly created, valid descriptor owned by nobody else
+        let sock = unsafe { OwnedFd::from_raw_fd(sock) };
+
+        let mut ifr = ifreq_for(&self.name);
+        ifr.ifr_ifru.ifru_mtu = self.mtu as libc::c_int;
+        ioctl(sock.as_raw_fd(), libc::SIOCSIFMTU as _, &mut ifr)?;
+
+        let mut ifr = ifreq_for(&self.name);
+        ioctl(sock.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut ifr)?;
+        // SAFETY: SIOCGIFFLAGS filled in the flags member
+        unsafe { ifr.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short };
+        ioctl(sock.as_raw_fd(), libc::SIOCSIFFLAGS as _, &mut ifr)
+    }
+}
+
+impl PacketInterface for TunInterface {
+    fn mtu(&self) -> usize {
+        self.mtu
+    }
+
+    fn send_packet(&mut self, packet: &[u8]) -> Result<(), NetworkError> {
+        self.file
+            .write_all(packet)
+            .map_err(|e| NetworkError::SendFailed(format!("TUN write to {} failed: {}", self.name, e)))
+    }
+
+    fn recv_packet(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        let mut pfd = libc::pollfd {
+            fd: self.file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pfd is a single valid pollfd for the duration of the call
+        let ret = unsafe { libc::poll(&mut pfd, 1, timeout.as_millis() as libc::c_int) };
+        if ret < 0 {
+            let e = io::Error::last_os_error();
+            if e.kind() == io::ErrorKind::Interrupted {
+                return Ok(None);
+            }
+            return Err(NetworkError::ReceiveFailed(format!("TUN poll on {} failed: {}", self.name, e)));
+        }
+        if ret == 0 {
+            return Ok(None);
+        }
+
+        let len = self
+            .file
+            .read(&mut self.buf)
+            .map_err(|e| NetworkError::ReceiveFailed(format!("TUN read from {} failed: {}", self.name, e)))?;
+        Ok(Some(self.buf[..len].to_vec()))
+    }
+}
+
+fn ifreq_for(name: &str) -> libc::ifreq {
+    // SAFETY: ifreq is plain old data, all zeroes is a valid value
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in ifr.ifr_name.iter_mut().zip(name.bytes()) {
+        *dst = src as libc::c_char;
+    }
+    ifr
+}
+
+fn ioctl(fd: RawFd, request: libc::Ioctl, ifr: &mut libc::ifreq) -> io::Result<()> {
+    // SAFETY: all requests issued here take a pointer to an ifreq, which outlives the call
+    let ret = unsafe { libc::ioctl(fd, request, ifr as *mut libc::ifreq) };
+    if ret < 0 { Err(io::Error::last_os_error()) } else { Ok(()) }
+}
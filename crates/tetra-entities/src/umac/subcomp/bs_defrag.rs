@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use tetra_core::{BitBuffer, TdmaTime, TetraAddress, Todo};
+use tetra_core::{BitBuffer, TdmaTime, TetraAddress};
 
+use crate::umac::subcomp::cipher::SduCipher;
 use crate::umac::subcomp::defrag::{DefragBuffer, DefragBufferState};
 
 const DEFRAG_BUF_MAX_LEN: usize = 4096;
@@ -39,7 +40,7 @@ impl BsDefrag {
     }
 
     /// Inserts a first fragment into a fragbuffer.
-    pub fn insert_first(&mut self, bitbuffer: &mut BitBuffer, t: TdmaTime, addr: TetraAddress, aie_info: Option<Todo>) {
+    pub fn insert_first(&mut self, bitbuffer: &mut BitBuffer, t: TdmaTime, addr: TetraAddress, aie_info: Option<SduCipher>) {
         // Check if buffer already exists for this ssi/timeslot
         // Remove and discard, if so.
         let ts = (t.t - 1) as usize;
@@ -136,7 +137,7 @@ impl BsDefrag {
     }
 
     /// Retrieves a read-only reference to the AIE info associated with a DefragBuffer
-    pub fn get_aie_info(&self, ssi: u32, t: TdmaTime) -> Option<&Todo> {
+    pub fn get_aie_info(&self, ssi: u32, t: TdmaTime) -> Option<&SduCipher> {
         let ts = (t.t - 1) as usize;
         let buf = match self.buffers[ts].get(&ssi) {
             Some(b) => b,
@@ -1,9 +1,10 @@
 use std::cmp::min;
 
-use tetra_core::{BitBuffer, TxReporter};
+use tetra_core::{BitBuffer, Direction, TdmaTime, TxReporter};
 
 use tetra_pdus::umac::pdus::{mac_end_dl::MacEndDl, mac_frag_dl::MacFragDl, mac_resource::MacResource};
 
+use crate::umac::subcomp::cipher::SduCipher;
 use crate::umac::subcomp::fillbits;
 
 #[derive(Debug)]
@@ -13,6 +14,8 @@ pub struct BsFragger {
     is_fully_transmitted: bool,
     sdu: BitBuffer,
     tx_reporter: Option<TxReporter>,
+    /// When set, the TM-SDU bits of every chunk are encrypted for the slot they are sent in
+    cipher: Option<SduCipher>,
 }
 
 /// We won't start fragmentation if less than MIN_SLOT_CAP_FOR_FRAG_START bits are free in the slot
@@ -32,6 +35,23 @@ impl BsFragger {
             is_fully_transmitted: false,
             sdu,
             tx_reporter,
+            cipher: None,
+        }
+    }
+
+    pub fn set_cipher(&mut self, cipher: Option<SduCipher>) {
+        self.cipher = cipher;
+    }
+
+    /// Copies num_bits of the TM-SDU into the MAC block, encrypting them if a cipher is set
+    fn write_sdu_bits(&mut self, mac_block: &mut BitBuffer, num_bits: usize, ts: TdmaTime) {
+        let sdu_start = mac_block.get_pos();
+        mac_block.copy_bits(&mut self.sdu, num_bits);
+        if let Some(cipher) = &self.cipher {
+            let sdu_end = mac_block.get_pos();
+            mac_block.seek(sdu_start);
+            cipher.apply(mac_block, num_bits, ts, 0, Direction::Dl);
+            mac_block.seek(sdu_end);
         }
     }
 
@@ -39,7 +59,7 @@ impl BsFragger {
     /// Then, writes as many SDU bits as possible.
     /// Returns true if the entire SDU was consumed, false if the PDU is fragmented
     /// and more chunks are needed.
-    fn get_resource_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+    fn get_resource_chunk(&mut self, mac_block: &mut BitBuffer, ts: TdmaTime) -> bool {
         // Some sanity checks
         assert!(self.sdu.get_pos() == 0, "SDU must be at the start of the buffer");
         assert!(!self.mac_hdr_is_written, "MAC header should not be written yet");
@@ -81,7 +101,7 @@ impl BsFragger {
 
             // Write MAC-RESOURCE header, followed by TM-SDU, to MAC block
             self.resource.to_bitbuf(mac_block);
-            mac_block.copy_bits(&mut self.sdu, sdu_len_bits);
+            self.write_sdu_bits(mac_block, sdu_len_bits, ts);
             fillbits::addition::write(mac_block, Some(num_fill_bits));
 
             // We're done with this packet
@@ -112,7 +132,7 @@ impl BsFragger {
             );
 
             self.resource.to_bitbuf(mac_block);
-            mac_block.copy_bits(&mut self.sdu, sdu_bits);
+            self.write_sdu_bits(mac_block, sdu_bits, ts);
             fillbits::addition::write(mac_block, None);
 
             // More fragments follow
@@ -126,7 +146,7 @@ impl BsFragger {
     /// MAC-END.
     /// Returns true when MAC-END (DL) was created and no further fragments are needed
     /// TODO FIXME: support adding ChanAlloc element in MAC-END
-    fn get_frag_or_end_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+    fn get_frag_or_end_chunk(&mut self, mac_block: &mut BitBuffer, ts: TdmaTime) -> bool {
         // Some sanity checks
         assert!(self.mac_hdr_is_written, "MAC header should be previously written");
 
@@ -158,7 +178,7 @@ impl BsFragger {
 
             // Write MAC-END header followed by TM-SDU
             pdu.to_bitbuf(mac_block);
-            mac_block.copy_bits(&mut self.sdu, sdu_bits);
+            self.write_sdu_bits(mac_block, sdu_bits, ts);
 
             // Write fill bits (if needed)
             if num_fill_bits > 0 {
@@ -190,7 +210,7 @@ impl BsFragger {
             );
 
             pdu.to_bitbuf(mac_block);
-            mac_block.copy_bits(&mut self.sdu, sdu_bits_in_frag);
+            self.write_sdu_bits(mac_block, sdu_bits_in_frag, ts);
 
             if num_fill_bits > 0 {
                 mac_block.write_bit(1);
@@ -205,7 +225,7 @@ impl BsFragger {
     /// First chunk is the provided resource, possibly changed to indicate fragmentation.
     /// Subsequent chunks are MAC-FRAG or MAC-END.
     /// Returns bool is_fully_transmitted
-    pub fn get_next_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+    pub fn get_next_chunk(&mut self, mac_block: &mut BitBuffer, ts: TdmaTime) -> bool {
         assert!(!self.is_fully_transmitted, "all fragments have already been produced");
         assert!(
             mac_block.get_len_written() % 8 == 0 || mac_block.get_len_remaining() == 0,
@@ -214,10 +234,10 @@ impl BsFragger {
 
         self.is_fully_transmitted = if !self.mac_hdr_is_written {
             // First chunk, write MAC-RESOURCE
-            self.get_resource_chunk(mac_block)
+            self.get_resource_chunk(mac_block, ts)
         } else {
             // Subsequent chunks, write MAC-FRAG or MAC-END
-            self.get_frag_or_end_chunk(mac_block)
+            self.get_frag_or_end_chunk(mac_block, ts)
         };
 
         // If we're done now, we'll report the PDUs full transmission.
@@ -281,7 +301,7 @@ mod tests {
         let mut mac_block = BitBuffer::new(SCH_F_CAP);
 
         let mut fragger = BsFragger::new(pdu, sdu, None);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
 
         assert!(done, "Should be done in single chunk");
@@ -298,7 +318,7 @@ mod tests {
         let mut fragger = BsFragger::new(pdu, sdu, None);
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacResource::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -308,7 +328,7 @@ mod tests {
         assert!(!done, "Should take four blocks");
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -318,7 +338,7 @@ mod tests {
         assert!(!done, "Should take four blocks");
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -328,7 +348,7 @@ mod tests {
         assert!(!done, "Should take four blocks");
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacEndDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -356,7 +376,7 @@ mod tests {
         let mut fragger = BsFragger::new(pdu, sdu, Some(reporter.clone()));
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacResource::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -367,7 +387,7 @@ mod tests {
         assert!(!reporter.is_in_final_state() && !reporter.is_transmitted());
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -378,7 +398,7 @@ mod tests {
         assert!(!reporter.is_in_final_state() && !reporter.is_transmitted());
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -389,7 +409,7 @@ mod tests {
         assert!(!reporter.is_in_final_state() && !reporter.is_transmitted());
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacEndDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -1,4 +1,9 @@
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, TetraAddress, Todo, TxReporter, unimplemented_log};
+use std::ops::Range;
+
+use tetra_core::{
+    BitBuffer, Direction, MAX_TRAFFIC_CHANNELS, PhyBlockNum, PhysicalChannel, TdmaTime, TetraAddress, Todo, TxReporter, channel_carrier,
+    traffic_channel, unimplemented_log,
+};
 use tetra_saps::{
     control::call_control::Circuit,
     tmv::{TmvUnitdataReq, TmvUnitdataReqSlot, enums::logical_chans::LogicalChannel},
@@ -25,7 +30,7 @@ use tetra_pdus::{
 
 use crate::{
     lmac::components::scrambler,
-    umac::subcomp::{bs_frag::BsFragger, circuit_mgr::CircuitMgr},
+    umac::subcomp::{bs_frag::BsFragger, cipher::SduCipher, circuit_mgr::CircuitMgr},
 };
 
 /// We submit this many TX timeslots ahead of the current time
@@ -64,8 +69,9 @@ pub struct BsChannelScheduler {
     /// Collect dltx traffic here that can't be sent this slot.
     /// Swapped back into the dltx_queues method at the end of the tick.
     dltx_next_slot_queue: Vec<DlSchedElem>,
-    /// Four queues for scheduled downlink traffic, one per timeslot
-    dltx_queues: [Vec<DlSchedElem>; 4],
+    /// Queues for scheduled downlink traffic, one per traffic channel. Only the main carrier
+    /// channels (the timeslots 1-4) carry signalling other than stealing.
+    dltx_queues: [Vec<DlSchedElem>; MAX_TRAFFIC_CHANNELS],
     ulsched: [[TimeslotSchedule; MACSCHED_NUM_FRAMES]; 4],
 
     circuits: CircuitMgr,
@@ -73,12 +79,15 @@ pub struct BsChannelScheduler {
     /// When true, the given timeslot is in call hangtime: keep circuit allocated but stop
     /// sending traffic-plane TCH blocks. Instead, transmit signalling-plane idle (Null PDUs)
     /// and signal UL usage as AssignedOnly so MS can request the floor.
-    hangtime: [bool; 4],
+    hangtime: [bool; MAX_TRAFFIC_CHANNELS],
 
     /// Per-timeslot set of SSIs whose RandomAccessAck was dropped by dl_drop_all_except_stolen.
     /// The next STCH built for a matching SSI should carry random_access_flag=true to properly
     /// acknowledge the random access per ETSI 21.4.3.1.
-    pending_ra_acks: [Vec<u32>; 4],
+    pending_ra_acks: [Vec<u32>; MAX_TRAFFIC_CHANNELS],
+
+    /// Air-interface encryption applied to DL traffic, per traffic channel, if the cell is class 2 or 3
+    traffic_ciphers: [Option<SduCipher>; MAX_TRAFFIC_CHANNELS],
 }
 
 #[derive(Debug)]
@@ -94,7 +103,8 @@ pub enum DlSchedElem {
     Grant(TetraAddress, BasicSlotgrant),
 
     /// A MAC-RESOURCE PDU. May be split into fragments upon processing, in which case a FragBuf will be inserted after processing the resource.
-    Resource(MacResource, BitBuffer, Option<TxReporter>),
+    /// If a cipher is given, the TM-SDU is encrypted upon transmission.
+    Resource(MacResource, BitBuffer, Option<TxReporter>, Option<SduCipher>),
 
     /// A FragBuf containing remaining non-transmitted information after a MAC-RESOURCE start has been transmitted
     FragBuf(BsFragger),
@@ -102,7 +112,8 @@ pub enum DlSchedElem {
     /// Pre-built STCH block for FACCH/stealing a half-slot from traffic channel.
     /// Contains MAC-U-SIGNAL (3 bits) + TM-SDU = 124 type1 bits.
     /// Delivers time-critical signaling (D-TX CEASED, D-TX GRANTED) per EN 300 392-2, clause 23.5.
-    Stealing(BitBuffer, Option<TxReporter>),
+    /// If a cipher is given, the TM-SDU bits in the given range are encrypted upon transmission.
+    Stealing(BitBuffer, Option<TxReporter>, Option<(SduCipher, Range<usize>)>),
 }
 
 const EMPTY_SCHED_ELEM: TimeslotSchedule = TimeslotSchedule {
@@ -120,17 +131,18 @@ impl BsChannelScheduler {
             scrambling_code,
             precomps,
             dltx_next_slot_queue: Vec::new(),
-            dltx_queues: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            dltx_queues: [const { Vec::new() }; MAX_TRAFFIC_CHANNELS],
             ulsched: EMPTY_SCHED,
             circuits: CircuitMgr::new(),
-            hangtime: [false, false, false, false],
-            pending_ra_acks: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            hangtime: [false; MAX_TRAFFIC_CHANNELS],
+            pending_ra_acks: [const { Vec::new() }; MAX_TRAFFIC_CHANNELS],
+            traffic_ciphers: [const { None }; MAX_TRAFFIC_CHANNELS],
         }
     }
 
-    /// Enter/leave hangtime for a traffic timeslot (2..=4).
+    /// Enter/leave hangtime for a traffic channel (see tetra_core::traffic_channel).
     pub fn set_hangtime(&mut self, ts: u8, active: bool) {
-        if !(1..=4).contains(&ts) {
+        if !(1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
             tracing::warn!("BsChannelScheduler::set_hangtime: invalid ts {}", ts);
             return;
         }
@@ -192,6 +204,11 @@ impl BsChannelScheduler {
     //     unimplemented!("need to refresh some msgs possibly");
     // }
 
+    /// Sets the cipher for the DL traffic of the given traffic channel
+    pub fn set_traffic_cipher(&mut self, chan: u8, cipher: Option<SduCipher>) {
+        self.traffic_ciphers[chan as usize - 1] = cipher;
+    }
+
     /// Update the System Wide Services flag in the broadcast SYSINFO.
     pub fn set_system_wide_services_state(&mut self, enabled: bool) {
         if self.precomps.mle_sysinfo.bs_service_details.system_wide_services != enabled {
@@ -206,7 +223,7 @@ impl BsChannelScheduler {
 
     /// Fully wipe the schedule
     pub fn purge_schedule(&mut self) {
-        self.dltx_queues = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        self.dltx_queues = [const { Vec::new() }; MAX_TRAFFIC_CHANNELS];
         self.ulsched = EMPTY_SCHED;
     }
 
@@ -415,7 +432,7 @@ impl BsChannelScheduler {
         self.dltx_queues[ts as usize - 1].push(elem);
     }
 
-    pub fn dl_enqueue_tma(&mut self, ts: u8, pdu: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>) {
+    pub fn dl_enqueue_tma(&mut self, ts: u8, pdu: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>, cipher: Option<SduCipher>) {
         tracing::debug!(
             "dl_enqueue_tma: ts {} enqueueing {} PDU {:?} SDU {}",
             if tx_reporter.is_some() { "reported" } else { "" },
@@ -423,7 +440,7 @@ impl BsChannelScheduler {
             pdu,
             sdu.dump_bin(),
         );
-        let elem = DlSchedElem::Resource(pdu, sdu, tx_reporter);
+        let elem = DlSchedElem::Resource(pdu, sdu, tx_reporter, cipher);
         self.dltx_queues[ts as usize - 1].push(elem);
     }
 
@@ -442,9 +459,15 @@ impl BsChannelScheduler {
 
     /// Enqueue a pre-built STCH block for FACCH/stealing on a traffic timeslot.
     /// The block must be 124 type1 bits containing MAC-U-SIGNAL header + TM-SDU.
-    pub fn dl_enqueue_stealing(&mut self, ts: u8, block: BitBuffer, tx_reporter: Option<TxReporter>) {
+    pub fn dl_enqueue_stealing(
+        &mut self,
+        ts: u8,
+        block: BitBuffer,
+        tx_reporter: Option<TxReporter>,
+        cipher: Option<(SduCipher, Range<usize>)>,
+    ) {
         tracing::info!("dl_enqueue_stealing: ts {} enqueueing STCH block ({} bits)", ts, block.get_len());
-        self.dltx_queues[ts as usize - 1].push(DlSchedElem::Stealing(block, tx_reporter));
+        self.dltx_queues[ts as usize - 1].push(DlSchedElem::Stealing(block, tx_reporter, cipher));
     }
 
     fn dl_enqueue_tma_frag_next_frame(&mut self, fragger: BsFragger) {
@@ -469,9 +492,14 @@ impl BsChannelScheduler {
         self.circuits.is_active(dir, ts)
     }
 
+    /// Timeslot whose DL carries the UL traffic received on `ul_ts`
+    pub fn circuit_dl_target(&self, ul_ts: u8) -> u8 {
+        self.circuits.get_dl_target(ul_ts)
+    }
+
     pub fn close_circuit(&mut self, dir: Direction, ts: u8) -> Option<Circuit> {
         // Clearing hangtime here is safe: if the circuit is gone, this timeslot is no longer in use.
-        if (1..=4).contains(&ts) {
+        if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
             self.hangtime[ts as usize - 1] = false;
         }
         self.circuits.close_circuit(dir, ts)
@@ -479,7 +507,7 @@ impl BsChannelScheduler {
 
     pub fn create_circuit(&mut self, dir: Direction, circuit: Circuit) {
         // New/updated circuit implies traffic mode.
-        if (1..=4).contains(&circuit.ts) {
+        if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&circuit.ts) {
             self.hangtime[circuit.ts as usize - 1] = false;
         }
         self.circuits.create_circuit(dir, circuit);
@@ -531,13 +559,12 @@ impl BsChannelScheduler {
 
         for index in 0..queue.len() {
             let elem = &mut queue[index];
-            if let DlSchedElem::Resource(pdu, _sdu, _repeat) = elem {
-                if let Some(pdu_ssi) = pdu.addr {
-                    if pdu_ssi.ssi == addr.ssi {
-                        // Found a resource for this address
-                        return queue.get_mut(index);
-                    }
-                }
+            if let DlSchedElem::Resource(pdu, ..) = elem
+                && let Some(pdu_ssi) = pdu.addr
+                && pdu_ssi.ssi == addr.ssi
+            {
+                // Found a resource for this address
+                return queue.get_mut(index);
             }
         }
         // No resource for this address was found
@@ -599,7 +626,7 @@ impl BsChannelScheduler {
                 tracing::warn!("dl_drop_all_except_stolen: discarding scheduled {:?} on ts {}", elem, timeslot);
 
                 match elem {
-                    DlSchedElem::Resource(_, _, tx_reporter) => {
+                    DlSchedElem::Resource(_, _, tx_reporter, _) => {
                         // Report as discarded manually
                         if let Some(tx_reporter) = tx_reporter {
                             tx_reporter.mark_discarded();
@@ -641,7 +668,7 @@ impl BsChannelScheduler {
             };
             let mac_resource = self.dl_get_scheduled_resource_for_ssi(ts, addr);
             match mac_resource {
-                Some(DlSchedElem::Resource(pdu, _sdu, _repeat)) => {
+                Some(DlSchedElem::Resource(pdu, ..)) => {
                     // Integrate grant into the resource
                     match &elem {
                         DlSchedElem::Grant(_, grant) => {
@@ -685,7 +712,7 @@ impl BsChannelScheduler {
                     };
 
                     // Push new resource into the queue. These do not need a tx_reporter
-                    let dlsched_res = DlSchedElem::Resource(pdu, BitBuffer::new(0), None);
+                    let dlsched_res = DlSchedElem::Resource(pdu, BitBuffer::new(0), None, None);
                     self.dltx_queues[ts.t as usize - 1].push(dlsched_res);
                 }
                 _ => panic!(),
@@ -706,12 +733,13 @@ impl BsChannelScheduler {
                             unimplemented_log!("finalize_ts_for_tick: Broadcast scheduling not implemented");
                         }
 
-                        DlSchedElem::Resource(pdu, sdu, tx_reporter) => {
+                        DlSchedElem::Resource(pdu, sdu, tx_reporter, cipher) => {
                             // Allocate bitbuf if not already done
                             let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
                             // Create fragger, either to send the whole PDU or to start fragmentation
                             let mut fragger = BsFragger::new(pdu, sdu, tx_reporter);
-                            if !fragger.get_next_chunk(&mut buf) {
+                            fragger.set_cipher(cipher);
+                            if !fragger.get_next_chunk(&mut buf, ts) {
                                 // Fragmentation was started and we have more chunks to send
                                 // Enqueue fragger with remaining data for retrieval next frame
                                 self.dl_enqueue_tma_frag_next_frame(fragger);
@@ -722,7 +750,7 @@ impl BsChannelScheduler {
                         DlSchedElem::FragBuf(mut fragger) => {
                             // Allocate bitbuf if not already done
                             let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
-                            if !fragger.get_next_chunk(&mut buf) {
+                            if !fragger.get_next_chunk(&mut buf, ts) {
                                 // Fragmentation was continued and we still have more chunks to send
                                 // Re-enqueue fragger with remaining data for retrieval next frame
                                 self.dl_enqueue_tma_frag_next_frame(fragger);
@@ -730,7 +758,7 @@ impl BsChannelScheduler {
                             buf_opt = Some(buf);
                         }
 
-                        DlSchedElem::Stealing(_, tx_reporter) => {
+                        DlSchedElem::Stealing(_, tx_reporter, _) => {
                             // Stealing items should only appear on traffic timeslots; discard if found here
                             tracing::warn!(
                                 "dl_build_block_from_signalling_schedule: Stealing item found on non-traffic ts {}, discarding",
@@ -767,9 +795,9 @@ impl BsChannelScheduler {
     /// - tch_block: speech/silence (274 bits)
     /// - stch_block: STCH signaling (124 bits) for FACCH stealing (EN 300 392-2, clause 23.5)
     /// Also reports transmission, if a TxReporter was attached to the DlSchedElem::Stealing element
-    fn dl_build_traffic_block(&mut self, ts: TdmaTime) -> (BitBuffer, Option<BitBuffer>) {
+    fn dl_build_traffic_block(&mut self, chan: u8, ts: TdmaTime) -> (BitBuffer, Option<BitBuffer>) {
         // Get speech data or silence
-        let tch_buf = if let Some(block) = self.circuits.take_block(ts.t) {
+        let mut tch_buf = if let Some(block) = self.circuits.take_block(chan) {
             let mut buf = BitBuffer::from_vec(block);
             // Raw ACELP speech (274 bits for TCH/S).
             // Clamp to TCH_S_CAP as Vec may be larger (e.g. 280 bits).
@@ -781,12 +809,25 @@ impl BsChannelScheduler {
             BitBuffer::new(TCH_S_CAP)
         };
 
+        // Encrypt the traffic channel, silence included, so the MS always decrypts to what we sent
+        let carrier = channel_carrier(chan);
+        if let Some(cipher) = &self.traffic_ciphers[chan as usize - 1] {
+            cipher.apply(&mut tch_buf, TCH_S_CAP, ts, carrier, Direction::Dl);
+        }
+
         // Check for FACCH/stealing: take a queued Stealing item (highest priority signaling)
         let (stch_opt, tx_reporter_opt) = {
-            let q = &mut self.dltx_queues[ts.t as usize - 1];
+            let q = &mut self.dltx_queues[chan as usize - 1];
             if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Stealing(..))) {
                 match q.remove(i) {
-                    DlSchedElem::Stealing(buf, tx_reporter) => (Some(buf), tx_reporter),
+                    DlSchedElem::Stealing(mut buf, tx_reporter, cipher) => {
+                        if let Some((cipher, range)) = cipher {
+                            buf.seek(range.start);
+                            cipher.apply(&mut buf, range.len(), ts, carrier, Direction::Dl);
+                            buf.seek(0);
+                        }
+                        (Some(buf), tx_reporter)
+                    }
                     _ => unreachable!(),
                 }
             } else {
@@ -795,8 +836,8 @@ impl BsChannelScheduler {
         };
 
         // Warn about other queued signaling that can't be sent via stealing yet
-        if stch_opt.is_none() && !self.dltx_queues[ts.t as usize - 1].is_empty() {
-            tracing::warn!("dl_build_traffic_block: queued signaling on ts {} but no stealing item", ts.t);
+        if stch_opt.is_none() && !self.dltx_queues[chan as usize - 1].is_empty() {
+            tracing::warn!("dl_build_traffic_block: queued signaling on ts {} but no stealing item", chan);
         }
 
         // If desired, report transmission
@@ -832,7 +873,7 @@ impl BsChannelScheduler {
         }
 
         // Return Resources last
-        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Resource(_, _, _))) {
+        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Resource(..))) {
             return Some(q.remove(i));
         }
 
@@ -881,7 +922,7 @@ impl BsChannelScheduler {
         let ul_phy = if ul_is_traffic { PhysicalChannel::Tp } else { PhysicalChannel::Cp };
 
         let mut elem = if dl_is_traffic {
-            let (tch_buf, stch_opt) = self.dl_build_traffic_block(ts);
+            let (tch_buf, stch_opt) = self.dl_build_traffic_block(ts.t, ts);
 
             if let Some(stch_buf) = stch_opt {
                 // FACCH/Stealing: 1st half = STCH signaling, 2nd half = TCH speech.
@@ -894,6 +935,7 @@ impl BsChannelScheduler {
                 );
                 TmvUnitdataReqSlot {
                     ts,
+                    carrier: 0,
                     blk1: Some(TmvUnitdataReq {
                         logical_channel: LogicalChannel::Stch,
                         mac_block: stch_buf,
@@ -911,6 +953,7 @@ impl BsChannelScheduler {
                 // Normal traffic: full-slot TCH
                 TmvUnitdataReqSlot {
                     ts,
+                    carrier: 0,
                     blk1: Some(TmvUnitdataReq {
                         logical_channel: LogicalChannel::TchS,
                         mac_block: tch_buf,
@@ -931,6 +974,7 @@ impl BsChannelScheduler {
             if let Some(buf) = buf {
                 TmvUnitdataReqSlot {
                     ts,
+                    carrier: 0,
                     blk1: Some(TmvUnitdataReq {
                         logical_channel: LogicalChannel::SchF,
                         mac_block: buf,
@@ -946,6 +990,7 @@ impl BsChannelScheduler {
                 if hang_effective && dl_circuit_active {
                     TmvUnitdataReqSlot {
                         ts,
+                        carrier: 0,
                         blk1: Some(TmvUnitdataReq {
                             logical_channel: LogicalChannel::SchF,
                             mac_block: self.generate_hangtime_idle_schf(),
@@ -959,6 +1004,7 @@ impl BsChannelScheduler {
                     // Put default SYNC/SYSINFO frame
                     TmvUnitdataReqSlot {
                         ts,
+                        carrier: 0,
                         blk1: None,
                         blk2: None,
                         bbk: None,
@@ -975,7 +1021,7 @@ impl BsChannelScheduler {
 
         // Construct the BBK block to reflect UL/DL usage
         assert!(elem.bbk.is_none(), "BBK block already set");
-        elem.bbk = Some(self.generate_bbk_block(ts));
+        elem.bbk = Some(self.generate_bbk_block(ts.t, ts));
 
         // tracing::trace!("finalize_ts_for_tick: have {}{}{}",
         //     if elem.bbk.is_some() { "bbk " } else { "" },
@@ -1050,13 +1096,80 @@ impl BsChannelScheduler {
         elem
     }
 
-    fn generate_bbk_block(&self, ts: TdmaTime) -> TmvUnitdataReq {
+    /// Prepares the FUTURE timeslot of a secondary carrier, like finalize_ts_for_tick does for the main carrier.
+    /// Secondary carriers only carry traffic channels: nothing is transmitted in timeslots without a circuit.
+    /// Allocated timeslots carry traffic, or an idle SCH/F during hangtime and in frame 18.
+    pub fn finalize_secondary_ts_for_tick(&mut self, carrier: u8) -> Option<TmvUnitdataReqSlot> {
+        assert!(carrier != 0, "main carrier is finalized by finalize_ts_for_tick");
+        let ts = self.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
+        let chan = traffic_channel(carrier, ts.t);
+
+        let dl_circuit_active = self.circuits.is_active(Direction::Dl, chan);
+        let ul_circuit_active = self.circuits.is_active(Direction::Ul, chan);
+        if !dl_circuit_active && !ul_circuit_active {
+            return None;
+        }
+
+        let hang_effective = self.is_hangtime_effective(chan);
+        let dl_is_traffic = dl_circuit_active && !hang_effective && ts.f != 18;
+        let ul_is_traffic = ul_circuit_active && !hang_effective && ts.f != 18;
+        let ul_phy_chan = if ul_is_traffic { PhysicalChannel::Tp } else { PhysicalChannel::Cp };
+
+        let (blk1, blk2) = if dl_is_traffic {
+            let (tch_buf, stch_opt) = self.dl_build_traffic_block(chan, ts);
+            let tch = TmvUnitdataReq {
+                logical_channel: LogicalChannel::TchS,
+                mac_block: tch_buf,
+                scrambling_code: self.scrambling_code,
+            };
+            match stch_opt {
+                Some(stch_buf) => {
+                    tracing::info!("finalize_secondary_ts_for_tick: FACCH stealing on carrier {} ts {}", carrier, ts.t);
+                    let stch = TmvUnitdataReq {
+                        logical_channel: LogicalChannel::Stch,
+                        mac_block: stch_buf,
+                        scrambling_code: self.scrambling_code,
+                    };
+                    (stch, Some(tch))
+                }
+                None => (tch, None),
+            }
+        } else {
+            let idle = TmvUnitdataReq {
+                logical_channel: LogicalChannel::SchF,
+                mac_block: self.generate_hangtime_idle_schf(),
+                scrambling_code: self.scrambling_code,
+            };
+            (idle, None)
+        };
+
+        let mut elem = TmvUnitdataReqSlot {
+            ts,
+            carrier,
+            ul_phy_chan,
+            blk1: Some(blk1),
+            blk2,
+            bbk: Some(self.generate_bbk_block(chan, ts)),
+        };
+        elem.blk1 = self.try_add_null_pdus(elem.blk1);
+
+        // Move all BitBuffer positions to the start of the window
+        elem.bbk.as_mut().unwrap().mac_block.seek(0);
+        elem.blk1.as_mut().unwrap().mac_block.seek(0);
+        if let Some(blk2) = elem.blk2.as_mut() {
+            blk2.mac_block.seek(0);
+        }
+        Some(elem)
+    }
+
+    /// Generates the AACH for the given traffic channel (see tetra_core::traffic_channel) at time ts
+    fn generate_bbk_block(&self, chan: u8, ts: TdmaTime) -> TmvUnitdataReq {
         let (ul_traffic_usage, dl_traffic_usage) = if ts.f == 18 {
             (None, None)
         } else {
             (
-                self.circuits.get_usage(Direction::Ul, ts.t),
-                self.circuits.get_usage(Direction::Dl, ts.t),
+                self.circuits.get_usage(Direction::Ul, chan),
+                self.circuits.get_usage(Direction::Dl, chan),
             )
         };
 
@@ -1065,7 +1178,7 @@ impl BsChannelScheduler {
         if ts.f != 18 {
             let mut aach = AccessAssign::default();
 
-            match ts.t {
+            match chan {
                 1 => {
                     assert!(dl_traffic_usage.is_none(), "DL ts 1 can't be traffic");
                     assert!(ul_traffic_usage.is_none(), "UL ts 1 can't be traffic (is this allowed?"); // TODO FIXME check spec
@@ -1083,14 +1196,14 @@ impl BsChannelScheduler {
                         base_frame_len: 4,
                     });
                 }
-                2..=4 => {
-                    // Additional channels (TS2..TS4).
+                2.. => {
+                    // Additional channels (TS2..TS4, and all timeslots of secondary carriers).
                     // Normal operation: Traffic(usage) when a circuit is active, else Unallocated.
                     // Hangtime: immediately switch AACH to AssignedControl so radios
                     // detect the end of traffic in the same frame as D-TX CEASED.
                     // The timeslot may still be in traffic mode (for STCH delivery) but
                     // the AACH reflects the new channel state.
-                    let in_hangtime = (2..=4).contains(&ts.t) && self.hangtime[ts.t as usize - 1];
+                    let in_hangtime = self.hangtime[chan as usize - 1];
 
                     if in_hangtime && (dl_traffic_usage.is_some() || ul_traffic_usage.is_some()) {
                         aach.dl_usage = AccessAssignDlUsage::AssignedControl;
@@ -1114,15 +1227,19 @@ impl BsChannelScheduler {
                         };
                     }
                 }
-                _ => panic!("finalize_ts_for_tick: invalid timeslot {}", ts.t),
+                _ => panic!("finalize_ts_for_tick: invalid timeslot {}", chan),
             }
 
             aach.to_bitbuf(&mut aach_bb);
         } else {
-            // Fr18
+            // Fr18. Random access on secondary carriers is only for MSs assigned to them
             assert!(ul_traffic_usage.is_none() && dl_traffic_usage.is_none());
             let aach = AccessAssignFr18 {
-                ul_usage: AccessAssignUlUsage::CommonOnly,
+                ul_usage: if channel_carrier(chan) == 0 {
+                    AccessAssignUlUsage::CommonOnly
+                } else {
+                    AccessAssignUlUsage::AssignedOnly
+                },
                 f1_af1: Some(AccessField {
                     access_code: 0,
                     base_frame_len: 1,
@@ -1484,7 +1601,7 @@ mod tests {
         };
         let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
         let sdu = BitBuffer::new(0);
-        sched.dl_enqueue_tma(ts.t, pdu, sdu, None);
+        sched.dl_enqueue_tma(ts.t, pdu, sdu, None, None);
 
         let grant = BasicSlotgrant {
             capacity_allocation: BasicSlotgrantCapAlloc::FirstSubslotGranted,
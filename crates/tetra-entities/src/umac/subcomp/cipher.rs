@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tetra_config::bluestation::{CfgEncryption, SecurityClass};
+use tetra_core::{BitBuffer, Direction, SsiType, TdmaTime, TetraAddress};
+
+/// Cipher keys (SCK, CCK, DCK) are 80 bits, carried in the low bits of a u128
+pub type CipherKey = u128;
+
+/// Value of the MAC-RESOURCE encryption mode field for an encrypted TM-SDU
+pub const ENCRYPTION_MODE_ENCRYPTED: u8 = 0b01;
+
+/// A TEA-class keystream generator (EN 300 392-7 clause 6.2).
+/// The TEA algorithms are only available under NDA, so they are plugged in through this trait.
+pub trait KeystreamGenerator: Send + Sync {
+    /// Produces num_bits of keystream for cipher key ck and initial value iv, packed MSB first
+    fn keystream(&self, ck: CipherKey, iv: u64, num_bits: usize) -> Vec<u8>;
+}
+
+/// Non-standard stand-in for a TEA algorithm, running MD5 in counter mode.
+/// Test radios or simulators implementing the same generator can talk to us.
+/// Provides no real security and will not interoperate with TEA radios.
+pub struct TestKeystreamGenerator;
+
+impl KeystreamGenerator for TestKeystreamGenerator {
+    fn keystream(&self, ck: CipherKey, iv: u64, num_bits: usize) -> Vec<u8> {
+        let num_bytes = num_bits.div_ceil(8);
+        let mut ks = Vec::with_capacity(num_bytes + 16);
+        let mut counter = 0u32;
+        while ks.len() < num_bytes {
+            let mut input = Vec::with_capacity(22);
+            input.extend_from_slice(&ck.to_be_bytes()[6..]);
+            input.extend_from_slice(&iv.to_be_bytes());
+            input.extend_from_slice(&counter.to_be_bytes());
+            ks.extend_from_slice(&md5::compute(&input).0);
+            counter += 1;
+        }
+        ks.truncate(num_bytes);
+        ks
+    }
+}
+
+/// Class of key used to encrypt a TM-SDU or traffic channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyClass {
+    /// Static cipher key, security class 2
+    Sck,
+    /// Common cipher key, security class 3 group and broadcast traffic
+    Cck,
+    /// Derived cipher key, security class 3 individual traffic
+    Dck,
+}
+
+/// Derives the keystream initial value from the TDMA time of the burst and the carrier it is sent on, so
+/// the same timeslot on different carriers never shares keystream.
+/// Layout MSB first: TDMA time cycle (31 bits), carrier (3), timeslot - 1 (2), frame (5), multiframe (6),
+/// hyperframe (16), direction (1, set for UL). The full hyperframe number and the count of its wraps keep the
+/// initial value from repeating under the same key.
+pub fn derive_iv(t: TdmaTime, cycle: u32, carrier: u8, dir: Direction) -> u64 {
+    let cycle = cycle as u64 & 0x7fff_ffff;
+    let cn = carrier as u64 & 0x7;
+    let tn = t.t.saturating_sub(1) as u64 & 0x3;
+    let fn_ = t.f as u64 & 0x1f;
+    let mn = t.m as u64 & 0x3f;
+    let hn = t.h as u64;
+    let ul = (dir == Direction::Ul) as u64;
+    (cycle << 33) | (cn << 30) | (tn << 28) | (fn_ << 23) | (mn << 17) | (hn << 1) | ul
+}
+
+/// Counts wraps of the 16-bit hyperframe number (TDMA time cycles of about 46 days), shared between the cell
+/// and its ciphers. A burst belongs to the cycle that puts it closest to the last observed time, so bursts
+/// scheduled ahead across the wrap get the next cycle.
+#[derive(Default)]
+pub struct TdmaCycleCounter {
+    /// Hyperframe number of the last observed time, extended with the cycle count in the upper bits
+    extended_hn: AtomicU64,
+}
+
+impl TdmaCycleCounter {
+    /// Advances the counter to the current cell time
+    pub fn observe(&self, t: TdmaTime) {
+        self.extended_hn.store(self.extend(t.h), Ordering::Relaxed);
+    }
+
+    /// Returns the TDMA time cycle a burst with the given hyperframe number belongs to
+    pub fn cycle_of(&self, t: TdmaTime) -> u32 {
+        (self.extend(t.h) >> 16) as u32
+    }
+
+    fn extend(&self, h: u16) -> u64 {
+        let last = self.extended_hn.load(Ordering::Relaxed);
+        let diff = h.wrapping_sub(last as u16) as i16;
+        last.wrapping_add_signed(diff as i64)
+    }
+}
+
+/// A key bound to a keystream generator, ready to encrypt or decrypt bits in place
+#[derive(Clone)]
+pub struct SduCipher {
+    ksg: Arc<dyn KeystreamGenerator>,
+    pub key_class: KeyClass,
+    key: CipherKey,
+    cycles: Arc<TdmaCycleCounter>,
+}
+
+impl fmt::Debug for SduCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SduCipher({:?})", self.key_class)
+    }
+}
+
+impl SduCipher {
+    pub fn new(ksg: Arc<dyn KeystreamGenerator>, key_class: KeyClass, key: CipherKey) -> Self {
+        Self::with_cycle_counter(ksg, key_class, key, Arc::new(TdmaCycleCounter::default()))
+    }
+
+    pub fn with_cycle_counter(
+        ksg: Arc<dyn KeystreamGenerator>,
+        key_class: KeyClass,
+        key: CipherKey,
+        cycles: Arc<TdmaCycleCounter>,
+    ) -> Self {
+        Self {
+            ksg,
+            key_class,
+            key,
+            cycles,
+        }
+    }
+
+    fn iv(&self, t: TdmaTime, carrier: u8, dir: Direction) -> u64 {
+        derive_iv(t, self.cycles.cycle_of(t), carrier, dir)
+    }
+
+    /// Xors keystream over num_bits of buf, starting at pos. Leaves pos unchanged.
+    pub fn apply(&self, buf: &mut BitBuffer, num_bits: usize, t: TdmaTime, carrier: u8, dir: Direction) {
+        if num_bits == 0 {
+            return;
+        }
+        let ks = self.ksg.keystream(self.key, self.iv(t, carrier, dir), num_bits);
+        let pos = buf.get_pos();
+        buf.xor_bytearr(&ks, num_bits).expect("cipher range exceeds buffer");
+        buf.seek(pos);
+    }
+
+    /// Xors keystream over all bits from pos to the end of buf, for signalling on the main carrier.
+    /// Leaves pos unchanged.
+    pub fn apply_to_remaining(&self, buf: &mut BitBuffer, t: TdmaTime, dir: Direction) {
+        let num_bits = buf.get_len_remaining();
+        self.apply(buf, num_bits, t, 0, dir);
+    }
+
+    /// Xors keystream over a one-bit-per-byte array
+    pub fn apply_to_bitarr(&self, bits: &mut [u8], t: TdmaTime, carrier: u8, dir: Direction) {
+        let ks = self.ksg.keystream(self.key, self.iv(t, carrier, dir), bits.len());
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit ^= (ks[i / 8] >> (7 - i % 8)) & 1;
+        }
+    }
+}
+
+/// Air-interface encryption state of the cell: configured class keys and per-MS derived cipher keys
+pub struct AirInterfaceCipher {
+    security_class: SecurityClass,
+    sck: Option<CipherKey>,
+    cck: Option<CipherKey>,
+    /// Derived cipher keys per ISSI, available once an MS has authenticated
+    dcks: HashMap<u32, CipherKey>,
+    ksg: Arc<dyn KeystreamGenerator>,
+    cycles: Arc<TdmaCycleCounter>,
+}
+
+impl AirInterfaceCipher {
+    pub fn new(cfg: Option<&CfgEncryption>) -> Self {
+        Self {
+            security_class: cfg.map_or(SecurityClass::Class1, |c| c.security_class),
+            sck: cfg.and_then(|c| c.sck),
+            cck: cfg.and_then(|c| c.cck),
+            dcks: HashMap::new(),
+            ksg: Arc::new(TestKeystreamGenerator),
+            cycles: Arc::new(TdmaCycleCounter::default()),
+        }
+    }
+
+    pub fn set_keystream_generator(&mut self, ksg: Arc<dyn KeystreamGenerator>) {
+        self.ksg = ksg;
+    }
+
+    pub fn security_class(&self) -> SecurityClass {
+        self.security_class
+    }
+
+    /// Follows the cell time, so initial values keep changing when the hyperframe number wraps
+    pub fn observe_time(&self, t: TdmaTime) {
+        self.cycles.observe(t);
+    }
+
+    /// Stores or removes the derived cipher key of an MS
+    pub fn set_dck(&mut self, issi: u32, dck: Option<CipherKey>) {
+        match dck {
+            Some(dck) => self.dcks.insert(issi, dck),
+            None => self.dcks.remove(&issi),
+        };
+    }
+
+    /// Returns the cipher for signalling to or from the given address, or None if it is sent in the clear.
+    /// In class 3, individual addresses use their DCK, so MSs that have not authenticated yet register and authenticate
+    /// in the clear.
+    pub fn cipher_for(&self, addr: &TetraAddress) -> Option<SduCipher> {
+        match self.security_class {
+            SecurityClass::Class1 => None,
+            SecurityClass::Class2 => self.make(KeyClass::Sck, self.sck),
+            SecurityClass::Class3 => match addr.ssi_type {
+                SsiType::Gssi => self.make(KeyClass::Cck, self.cck),
+                _ => self.make(KeyClass::Dck, self.dcks.get(&addr.ssi).copied()),
+            },
+        }
+    }
+
+    /// Returns true if traffic of the given MS may not be carried in the clear, while no cipher is available for it.
+    /// A class 3 cell only carries individual traffic under the DCK of the MS.
+    pub fn refuses_clear_traffic(&self, individual_issi: Option<u32>) -> bool {
+        self.security_class == SecurityClass::Class3 && individual_issi.is_some_and(|issi| !self.dcks.contains_key(&issi))
+    }
+
+    /// Returns the cipher for a traffic channel. Group traffic uses the cell-wide key. In class 3, individual
+    /// traffic uses the DCK of the given MS; without it, there is no cipher and the traffic is refused.
+    pub fn traffic_cipher(&self, individual_issi: Option<u32>) -> Option<SduCipher> {
+        match (self.security_class, individual_issi) {
+            (SecurityClass::Class1, _) => None,
+            (SecurityClass::Class2, _) => self.make(KeyClass::Sck, self.sck),
+            (SecurityClass::Class3, None) => self.make(KeyClass::Cck, self.cck),
+            (SecurityClass::Class3, Some(issi)) => self.make(KeyClass::Dck, self.dcks.get(&issi).copied()),
+        }
+    }
+
+    fn make(&self, key_class: KeyClass, key: Option<CipherKey>) -> Option<SduCipher> {
+        key.map(|key| SduCipher::with_cycle_counter(self.ksg.clone(), key_class, key, self.cycles.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class3() -> AirInterfaceCipher {
+        AirInterfaceCipher::new(Some(&CfgEncryption {
+            security_class: SecurityClass::Class3,
+            sck: None,
+            sck_number: 1,
+            cck: Some(0x1234),
+        }))
+    }
+
+    #[test]
+    fn test_derive_iv() {
+        let t = TdmaTime {
+            t: 4,
+            f: 18,
+            m: 60,
+            h: 0xffff,
+        };
+        let iv = derive_iv(t, 0, 0, Direction::Ul);
+        assert_eq!(iv >> 28, 3);
+        assert_eq!((iv >> 23) & 0x1f, 18);
+        assert_eq!((iv >> 17) & 0x3f, 60);
+        assert_eq!((iv >> 1) & 0xffff, 0xffff);
+        assert_eq!(iv & 1, 1);
+        assert!(iv < 1 << 30);
+        assert_ne!(iv, derive_iv(t, 0, 0, Direction::Dl));
+
+        // The same timeslot on another carrier gets its own initial value
+        let iv_carrier = derive_iv(t, 0, 2, Direction::Ul);
+        assert_eq!(iv_carrier >> 30, 2);
+        assert_eq!(iv_carrier & ((1 << 30) - 1), iv);
+
+        // Hyperframes 0x7fff apart, or a whole TDMA time cycle apart, do not share keystream
+        let t_low = TdmaTime { h: 0x0001, ..t };
+        let t_high = TdmaTime { h: 0x8000, ..t };
+        assert_ne!(derive_iv(t_low, 0, 0, Direction::Ul), derive_iv(t_high, 0, 0, Direction::Ul));
+        assert_ne!(derive_iv(t, 0, 0, Direction::Ul), derive_iv(t, 1, 0, Direction::Ul));
+    }
+
+    #[test]
+    fn test_tdma_cycle_counter() {
+        let cycles = TdmaCycleCounter::default();
+        let at = |h| TdmaTime { t: 1, f: 1, m: 1, h };
+        cycles.observe(at(0xfffe));
+        assert_eq!(cycles.cycle_of(at(0xfffe)), u32::MAX);
+
+        for h in [0, 0x4000, 0x8000, 0xc000, 0xffff] {
+            cycles.observe(at(h));
+        }
+        // A burst scheduled ahead across the wrap already belongs to the next cycle
+        assert_eq!(cycles.cycle_of(at(0xffff)), 0);
+        assert_eq!(cycles.cycle_of(at(1)), 1);
+        cycles.observe(at(1));
+        assert_eq!(cycles.cycle_of(at(1)), 1);
+        // A late burst from before the wrap still uses the previous cycle
+        assert_eq!(cycles.cycle_of(at(0xfffe)), 0);
+    }
+
+    #[test]
+    fn test_keystream_changes_across_wrap() {
+        let aie = class3();
+        let cipher = aie.traffic_cipher(None).unwrap();
+        let t = TdmaTime { t: 1, f: 3, m: 7, h: 42 };
+        let mut first = BitBuffer::new(64);
+        cipher.apply(&mut first, 64, t, 0, Direction::Dl);
+
+        // One full TDMA time cycle later, the same time gets fresh keystream
+        for h in [0x4000, 0x8000, 0xc000, 0xffff, 0, 42] {
+            aie.observe_time(TdmaTime { h, ..t });
+        }
+        let mut second = BitBuffer::new(64);
+        cipher.apply(&mut second, 64, t, 0, Direction::Dl);
+        assert_ne!(first.to_bitstr(), second.to_bitstr());
+    }
+
+    #[test]
+    fn test_apply_roundtrip() {
+        let cipher = class3().traffic_cipher(None).unwrap();
+        let t = TdmaTime { t: 1, f: 3, m: 7, h: 42 };
+        let plain = "1011001110001111000011111000001111110";
+        let mut buf = BitBuffer::from_bitstr(plain);
+        buf.seek(3);
+        cipher.apply(&mut buf, 30, t, 0, Direction::Dl);
+        assert_eq!(buf.get_pos(), 3);
+        assert_eq!(buf.to_bitstr()[..3], plain[..3]);
+        assert_eq!(buf.to_bitstr()[33..], plain[33..]);
+        assert_ne!(buf.to_bitstr(), plain);
+        cipher.apply(&mut buf, 30, t, 0, Direction::Dl);
+        assert_eq!(buf.to_bitstr(), plain);
+    }
+
+    #[test]
+    fn test_class3_key_selection() {
+        let mut aie = class3();
+        let ms = TetraAddress::new(1000001, SsiType::Issi);
+        let group = TetraAddress::new(91, SsiType::Gssi);
+        assert!(aie.cipher_for(&ms).is_none());
+        assert_eq!(aie.cipher_for(&group).unwrap().key_class, KeyClass::Cck);
+        aie.set_dck(1000001, Some(0xabcd));
+        assert_eq!(aie.cipher_for(&ms).unwrap().key_class, KeyClass::Dck);
+        aie.set_dck(1000001, None);
+        assert!(aie.cipher_for(&ms).is_none());
+    }
+
+    #[test]
+    fn test_class3_traffic_key_selection() {
+        let mut aie = class3();
+        assert_eq!(aie.traffic_cipher(None).unwrap().key_class, KeyClass::Cck);
+        assert!(!aie.refuses_clear_traffic(None));
+        assert!(aie.traffic_cipher(Some(1000001)).is_none());
+        assert!(aie.refuses_clear_traffic(Some(1000001)));
+        aie.set_dck(1000001, Some(0xabcd));
+        assert_eq!(aie.traffic_cipher(Some(1000001)).unwrap().key_class, KeyClass::Dck);
+        assert!(!aie.refuses_clear_traffic(Some(1000001)));
+    }
+}
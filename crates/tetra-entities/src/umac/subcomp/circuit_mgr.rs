@@ -1,22 +1,22 @@
 use std::collections::VecDeque;
 
-use tetra_core::Direction;
+use tetra_core::{Direction, MAX_TRAFFIC_CHANNELS};
 use tetra_saps::control::call_control::Circuit;
 
 pub struct CircuitMgr {
-    pub dl: [Option<Circuit>; 4],
-    pub ul: [Option<Circuit>; 4],
+    pub dl: [Option<Circuit>; MAX_TRAFFIC_CHANNELS],
+    pub ul: [Option<Circuit>; MAX_TRAFFIC_CHANNELS],
 
-    /// Data blocks queued to be transmitted, per timeslot
-    pub tx_data: [VecDeque<Vec<u8>>; 4],
+    /// Data blocks queued to be transmitted, per traffic channel
+    pub tx_data: [VecDeque<Vec<u8>>; MAX_TRAFFIC_CHANNELS],
 }
 
 impl CircuitMgr {
     pub fn new() -> Self {
         Self {
-            dl: [None, None, None, None],
-            ul: [None, None, None, None],
-            tx_data: [VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            dl: [const { None }; MAX_TRAFFIC_CHANNELS],
+            ul: [const { None }; MAX_TRAFFIC_CHANNELS],
+            tx_data: [const { VecDeque::new() }; MAX_TRAFFIC_CHANNELS],
         }
     }
 
@@ -48,6 +48,15 @@ impl CircuitMgr {
         }
     }
 
+    /// Returns the timeslot on whose DL the UL traffic of the given timeslot is to be sent.
+    /// This is the same timeslot, unless the UL circuit is cross-connected to a peer (duplex individual call).
+    pub fn get_dl_target(&self, ul_ts: u8) -> u8 {
+        self.ul[ul_ts as usize - 1]
+            .as_ref()
+            .and_then(|circuit| circuit.peer_ts)
+            .unwrap_or(ul_ts)
+    }
+
     /// Closes an active circuit, and return the Circuit to the caller
     pub fn close_circuit(&mut self, dir: Direction, ts: u8) -> Option<Circuit> {
         match dir {
@@ -1,4 +1,6 @@
-use tetra_core::{BitBuffer, SsiType, TdmaTime, TetraAddress, Todo};
+use tetra_core::{BitBuffer, SsiType, TdmaTime, TetraAddress};
+
+use crate::umac::subcomp::cipher::SduCipher;
 
 const DEFRAG_BUF_INITIAL_LEN: usize = 512;
 
@@ -15,7 +17,8 @@ pub struct DefragBuffer {
     pub t_first: TdmaTime,
     pub t_last: TdmaTime,
     pub num_frags: usize,
-    pub aie_info: Option<Todo>,
+    /// Cipher for the remaining fragments, if the first fragment was encrypted
+    pub aie_info: Option<SduCipher>,
     pub buffer: BitBuffer,
 }
 
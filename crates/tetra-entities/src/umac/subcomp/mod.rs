@@ -1,11 +1,13 @@
 pub mod bs_defrag;
 pub mod bs_frag;
 pub mod bs_sched;
+pub mod cipher;
 pub mod defrag;
 
 pub mod circuit_mgr;
 
 pub mod ms_defrag;
+pub mod ms_ul_sched;
 
 pub mod event_label_store;
 pub mod fillbits;
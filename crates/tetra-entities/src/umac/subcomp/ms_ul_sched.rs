@@ -0,0 +1,525 @@
+use std::collections::VecDeque;
+
+use tetra_core::{BitBuffer, SsiType, TdmaTime, TetraAddress, TxReporter};
+use tetra_pdus::umac::enums::access_assign_ul_usage::AccessAssignUlUsage;
+use tetra_pdus::umac::enums::basic_slotgrant_cap_alloc::BasicSlotgrantCapAlloc;
+use tetra_pdus::umac::enums::basic_slotgrant_granting_delay::BasicSlotgrantGrantingDelay;
+use tetra_pdus::umac::enums::reservation_requirement::ReservationRequirement;
+use tetra_pdus::umac::fields::basic_slotgrant::BasicSlotgrant;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_end_ul::MacEndUl;
+use tetra_pdus::umac::pdus::mac_frag_ul::MacFragUl;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+
+use crate::umac::subcomp::bs_sched::SCH_F_CAP;
+use crate::umac::subcomp::fillbits;
+
+/// Number of type1 bits in an SCH/HU subslot block
+pub const SCH_HU_CAP: usize = 92;
+
+/// Length of a MAC-ACCESS header with SSI address and the optional length/capacity request field
+const MAC_ACCESS_HDR_LEN: usize = 36;
+/// Length of a MAC-FRAG (uplink) header
+const MAC_FRAG_UL_HDR_LEN: usize = 4;
+/// Length of a MAC-END (uplink) header with length indication
+const MAC_END_UL_HDR_LEN: usize = 10;
+
+/// Largest TM-SDU carried in a single MAC-ACCESS. The length indication counts octets, and the
+/// PDU is padded up to the last whole octet of the subslot so no garbage PDU follows it.
+const MAC_ACCESS_MAX_SDU_LEN: usize = (SCH_HU_CAP / 8) * 8 - MAC_ACCESS_HDR_LEN;
+/// Largest TM-SDU fragment carried in a MAC-ACCESS with capacity request, which fills the subslot
+const MAC_ACCESS_MAX_FRAG_LEN: usize = SCH_HU_CAP - MAC_ACCESS_HDR_LEN;
+/// Largest TM-SDU fragment carried in a MAC-FRAG, which fills the full slot
+const MAC_FRAG_UL_MAX_SDU_LEN: usize = SCH_F_CAP - MAC_FRAG_UL_HDR_LEN;
+/// Largest TM-SDU fragment carried in a MAC-END, padded up to the last whole octet of the slot
+const MAC_END_UL_MAX_SDU_LEN: usize = (SCH_F_CAP / 8) * 8 - MAC_END_UL_HDR_LEN;
+
+/// Defaults for the random access parameters, used until the cell broadcasts its own
+const DEFAULT_RA_WAIT_FRAMES: u8 = 5;
+const DEFAULT_RA_MAX_ATTEMPTS: u8 = 5;
+/// Frames within which a new random access attempt is made after a failed one
+const RA_BACKOFF_MAX_FRAMES: i32 = 4;
+
+/// A TM-SDU handed down by the LLC, waiting for uplink capacity
+struct UlSdu {
+    sdu: BitBuffer,
+    tx_reporter: Option<TxReporter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum UlState {
+    /// Waiting for a random access opportunity at or after the given time
+    RandomAccess { attempts: u8, not_before: TdmaTime },
+    /// MAC-ACCESS sent, waiting for the random access acknowledgement
+    AwaitAck { attempts: u8, deadline: TdmaTime },
+    /// Random access acknowledged, waiting for the slot grant covering the remainder
+    AwaitGrant { attempts: u8, deadline: TdmaTime },
+    /// Granted uplink slots in which the remainder is sent
+    Granted { slots: VecDeque<TdmaTime> },
+}
+
+/// The uplink transfer in progress
+struct UlTransfer {
+    sdu: UlSdu,
+    state: UlState,
+}
+
+/// Uplink scheduler of the mobile station. Takes TM-SDUs one at a time through random access on the
+/// MCCH, requests reserved capacity for SDUs that do not fit a single subslot, and sends the remainder
+/// as MAC-FRAG / MAC-END in the slots granted by the BS.
+pub struct MsUlScheduler {
+    /// Own ISSI, used as address on all uplink PDUs
+    issi: u32,
+
+    queue: VecDeque<UlSdu>,
+    cur: Option<UlTransfer>,
+
+    /// Frames to wait for a random access response, from the cell's access definition
+    ra_wait_frames: u8,
+    /// Number of random access transmissions before giving up, from the cell's access definition
+    ra_max_attempts: u8,
+
+    /// Uplink usage as last announced on the AACH, per timeslot
+    ul_usage: [AccessAssignUlUsage; 4],
+}
+
+impl MsUlScheduler {
+    pub fn new(issi: u32) -> Self {
+        Self {
+            issi,
+            queue: VecDeque::new(),
+            cur: None,
+            ra_wait_frames: DEFAULT_RA_WAIT_FRAMES,
+            ra_max_attempts: DEFAULT_RA_MAX_ATTEMPTS,
+            ul_usage: [AccessAssignUlUsage::CommonOnly; 4],
+        }
+    }
+
+    /// Adopt the random access parameters broadcast by the cell. Zero values are reserved and ignored.
+    pub fn set_ra_params(&mut self, wait_frames: u8, max_attempts: u8) {
+        if wait_frames > 0 {
+            self.ra_wait_frames = wait_frames;
+        }
+        if max_attempts > 0 {
+            self.ra_max_attempts = max_attempts;
+        }
+    }
+
+    pub fn set_ul_usage(&mut self, ts: u8, usage: AccessAssignUlUsage) {
+        self.ul_usage[ts as usize - 1] = usage;
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.cur.is_none() && self.queue.is_empty()
+    }
+
+    /// Queue a TM-SDU for uplink transmission
+    pub fn enqueue(&mut self, mut sdu: BitBuffer, tx_reporter: Option<TxReporter>) {
+        sdu.seek(0);
+        tracing::debug!("ms_ul_sched: queued {} bit TM-SDU", sdu.get_len());
+        self.queue.push_back(UlSdu { sdu, tx_reporter });
+    }
+
+    /// Discard everything, for instance when the serving cell is lost
+    pub fn purge(&mut self) {
+        for ul in self.cur.take().map(|t| t.sdu).into_iter().chain(self.queue.drain(..)) {
+            if let Some(tx_reporter) = ul.tx_reporter {
+                tx_reporter.mark_discarded();
+            }
+        }
+    }
+
+    /// Handle a MAC-RESOURCE to our ISSI with the random access flag set, received at dltime
+    pub fn rx_random_access_ack(&mut self, dltime: TdmaTime) {
+        let Some(transfer) = &mut self.cur else {
+            return;
+        };
+        let UlState::AwaitAck { attempts, .. } = transfer.state else {
+            return;
+        };
+
+        if transfer.sdu.sdu.get_len_remaining() == 0 {
+            // Sent as a single MAC-ACCESS, we're done
+            tracing::debug!("ms_ul_sched: random access acknowledged, TM-SDU complete");
+            let transfer = self.cur.take().unwrap(); // Guaranteed
+            if let Some(tx_reporter) = transfer.sdu.tx_reporter {
+                tx_reporter.mark_transmitted();
+            }
+        } else {
+            tracing::debug!("ms_ul_sched: random access acknowledged, awaiting slot grant");
+            transfer.state = UlState::AwaitGrant {
+                attempts,
+                deadline: dltime.add_timeslots(self.ra_wait_frames as i32 * 4),
+            };
+        }
+    }
+
+    /// Handle a slot grant to our ISSI, received at dltime. The first granted opportunity is the
+    /// uplink slot with the same number as the downlink slot carrying the grant.
+    pub fn rx_slot_grant(&mut self, dltime: TdmaTime, grant: &BasicSlotgrant) {
+        let Some(transfer) = &mut self.cur else {
+            tracing::debug!("ms_ul_sched: ignoring grant, nothing to send");
+            return;
+        };
+        if !matches!(transfer.state, UlState::AwaitAck { .. } | UlState::AwaitGrant { .. }) {
+            tracing::debug!("ms_ul_sched: ignoring grant in state {:?}", transfer.state);
+            return;
+        }
+
+        let num_slots = match grant.capacity_allocation {
+            BasicSlotgrantCapAlloc::FirstSubslotGranted | BasicSlotgrantCapAlloc::SecondSubslotGranted => {
+                tracing::warn!("ms_ul_sched: subslot grant {:?} not supported", grant.capacity_allocation);
+                return;
+            }
+            cap_alloc => cap_alloc.to_req_slotcount(),
+        };
+        let skip = match grant.granting_delay {
+            BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity => 0,
+            BasicSlotgrantGrantingDelay::DelayNOpportunities(n) => n as usize,
+            BasicSlotgrantGrantingDelay::AllocStartsAtOpportunityInFr18 | BasicSlotgrantGrantingDelay::WaitForAnotherSlotgrantMessage => {
+                tracing::debug!("ms_ul_sched: grant with delay {:?}, waiting", grant.granting_delay);
+                return;
+            }
+        };
+
+        let slots = Self::granted_slots(dltime, skip, num_slots);
+        tracing::debug!("ms_ul_sched: granted {:?}", slots);
+        transfer.state = UlState::Granted { slots };
+    }
+
+    /// Uplink slots belonging to a grant: every fourth slot starting at first, skipping the
+    /// mandatory CLCH opportunities, after delaying skip opportunities
+    fn granted_slots(first: TdmaTime, skip: usize, num_slots: usize) -> VecDeque<TdmaTime> {
+        let mut slots = VecDeque::with_capacity(num_slots);
+        let mut skipped = 0;
+        let mut candidate = first;
+        while slots.len() < num_slots {
+            if !candidate.is_mandatory_clch() {
+                if skipped < skip {
+                    skipped += 1;
+                } else {
+                    slots.push_back(candidate);
+                }
+            }
+            candidate = candidate.add_timeslots(4);
+        }
+        slots
+    }
+
+    /// Number of full slots needed to send the remaining bits through MAC-FRAG / MAC-END
+    fn slots_needed(mut remaining: usize) -> usize {
+        let mut slots = 1;
+        while remaining > MAC_END_UL_MAX_SDU_LEN {
+            remaining -= MAC_FRAG_UL_MAX_SDU_LEN.min(remaining - 1);
+            slots += 1;
+        }
+        slots
+    }
+
+    /// Advance timers and start the next transfer if idle. Called once per timeslot.
+    fn update(&mut self, now: TdmaTime) {
+        if let Some(transfer) = &mut self.cur {
+            let timed_out = match transfer.state {
+                UlState::AwaitAck { attempts, deadline } | UlState::AwaitGrant { attempts, deadline } if now.diff(deadline) > 0 => {
+                    Some(attempts)
+                }
+                _ => None,
+            };
+            if let Some(attempts) = timed_out {
+                if attempts >= self.ra_max_attempts {
+                    tracing::warn!(
+                        "ms_ul_sched: no response after {} random access attempts, discarding TM-SDU",
+                        attempts
+                    );
+                    let transfer = self.cur.take().unwrap(); // Guaranteed
+                    if let Some(tx_reporter) = transfer.sdu.tx_reporter {
+                        tx_reporter.mark_discarded();
+                    }
+                } else {
+                    let backoff = rand::random_range(1..=RA_BACKOFF_MAX_FRAMES) * 4;
+                    tracing::debug!("ms_ul_sched: no response to random access attempt {}, backing off", attempts);
+                    transfer.sdu.sdu.seek(0);
+                    transfer.state = UlState::RandomAccess {
+                        attempts,
+                        not_before: now.add_timeslots(backoff),
+                    };
+                }
+            }
+        }
+
+        if self.cur.is_none()
+            && let Some(sdu) = self.queue.pop_front()
+        {
+            self.cur = Some(UlTransfer {
+                sdu,
+                state: UlState::RandomAccess {
+                    attempts: 0,
+                    not_before: now,
+                },
+            });
+        }
+    }
+
+    /// Returns whether a random access may be made in the given uplink slot
+    fn is_ra_opportunity(&self, ul_time: TdmaTime) -> bool {
+        ul_time.t == 1
+            && !ul_time.is_mandatory_clch()
+            && matches!(
+                self.ul_usage[0],
+                AccessAssignUlUsage::CommonOnly | AccessAssignUlUsage::CommonAndAssigned
+            )
+    }
+
+    /// Produce the signalling block to transmit in the given uplink slot, if any
+    pub fn build_ul_block(&mut self, ul_time: TdmaTime) -> Option<(LogicalChannel, BitBuffer)> {
+        self.update(ul_time);
+
+        let ra_opportunity = self.is_ra_opportunity(ul_time);
+        let transfer = self.cur.as_mut()?;
+        match &mut transfer.state {
+            UlState::RandomAccess { attempts, not_before } => {
+                if !ra_opportunity || ul_time.diff(*not_before) < 0 {
+                    return None;
+                }
+                let attempts = *attempts + 1;
+                let block = Self::build_mac_access(self.issi, &mut transfer.sdu.sdu);
+                tracing::debug!("ms_ul_sched: random access attempt {} at {}", attempts, ul_time);
+                transfer.state = UlState::AwaitAck {
+                    attempts,
+                    deadline: ul_time.add_timeslots(4 + self.ra_wait_frames as i32 * 4),
+                };
+                Some((LogicalChannel::SchHu, block))
+            }
+            UlState::Granted { slots } => {
+                // Drop granted slots we have missed
+                while let Some(slot) = slots.front()
+                    && ul_time.diff(*slot) > 0
+                {
+                    tracing::warn!("ms_ul_sched: missed granted slot {}", slot);
+                    slots.pop_front();
+                }
+                if slots.front() != Some(&ul_time) {
+                    if slots.is_empty() {
+                        // Grant exhausted without completing, start over
+                        tracing::warn!("ms_ul_sched: grant too small, restarting transfer");
+                        transfer.sdu.sdu.seek(0);
+                        transfer.state = UlState::RandomAccess {
+                            attempts: 0,
+                            not_before: ul_time,
+                        };
+                    }
+                    return None;
+                }
+                slots.pop_front();
+
+                let block = Self::build_mac_frag_or_end(&mut transfer.sdu.sdu);
+                if transfer.sdu.sdu.get_len_remaining() == 0 {
+                    tracing::debug!("ms_ul_sched: TM-SDU complete at {}", ul_time);
+                    let transfer = self.cur.take().unwrap(); // Guaranteed
+                    if let Some(tx_reporter) = transfer.sdu.tx_reporter {
+                        tx_reporter.mark_transmitted();
+                    }
+                }
+                Some((LogicalChannel::SchF, block))
+            }
+            UlState::AwaitAck { .. } | UlState::AwaitGrant { .. } => None,
+        }
+    }
+
+    /// Build a MAC-ACCESS carrying the SDU if it fits, or its first fragment with a capacity request
+    fn build_mac_access(issi: u32, sdu: &mut BitBuffer) -> BitBuffer {
+        let sdu_len = sdu.get_len_remaining();
+        let mut block = BitBuffer::new(SCH_HU_CAP);
+        let addr = Some(TetraAddress {
+            ssi: issi,
+            ssi_type: SsiType::Ssi,
+            encrypted: false,
+        });
+
+        if sdu_len <= MAC_ACCESS_MAX_SDU_LEN {
+            let num_fill_bits = MAC_ACCESS_MAX_SDU_LEN - sdu_len;
+            let pdu = MacAccess {
+                fill_bits: num_fill_bits > 0,
+                encrypted: false,
+                addr,
+                event_label: None,
+                length_ind: Some((SCH_HU_CAP / 8) as u8),
+                frag_flag: None,
+                reservation_req: None,
+            };
+            tracing::debug!("-> {}", pdu);
+            pdu.to_bitbuf(&mut block);
+            block.copy_bits(sdu, sdu_len);
+            fillbits::addition::write(&mut block, Some(num_fill_bits));
+        } else {
+            // Leave at least one bit for the MAC-END
+            let frag_len = MAC_ACCESS_MAX_FRAG_LEN.min(sdu_len - 1);
+            let num_fill_bits = MAC_ACCESS_MAX_FRAG_LEN - frag_len;
+            let slots = Self::slots_needed(sdu_len - frag_len);
+            let pdu = MacAccess {
+                fill_bits: num_fill_bits > 0,
+                encrypted: false,
+                addr,
+                event_label: None,
+                length_ind: None,
+                frag_flag: Some(true),
+                reservation_req: Some(ReservationRequirement::from_req_slotcount(slots)),
+            };
+            tracing::debug!("-> {}", pdu);
+            pdu.to_bitbuf(&mut block);
+            block.copy_bits(sdu, frag_len);
+            fillbits::addition::write(&mut block, Some(num_fill_bits));
+        }
+
+        // Zero whatever remains after the last whole octet
+        block.write_zeroes(block.get_len_remaining());
+        block.seek(0);
+        block
+    }
+
+    /// Build a MAC-END if the rest of the SDU fits, otherwise a MAC-FRAG filling the slot
+    fn build_mac_frag_or_end(sdu: &mut BitBuffer) -> BitBuffer {
+        let sdu_len = sdu.get_len_remaining();
+        let mut block = BitBuffer::new(SCH_F_CAP);
+
+        if sdu_len <= MAC_END_UL_MAX_SDU_LEN {
+            let num_fill_bits = MAC_END_UL_MAX_SDU_LEN - sdu_len;
+            let pdu = MacEndUl {
+                fill_bits: num_fill_bits > 0,
+                length_ind: Some((SCH_F_CAP / 8) as u8),
+                reservation_req: None,
+            };
+            tracing::debug!("-> {}", pdu);
+            pdu.to_bitbuf(&mut block).expect("valid MAC-END length");
+            block.copy_bits(sdu, sdu_len);
+            fillbits::addition::write(&mut block, Some(num_fill_bits));
+            block.write_zeroes(block.get_len_remaining());
+        } else {
+            let frag_len = MAC_FRAG_UL_MAX_SDU_LEN.min(sdu_len - 1);
+            let pdu = MacFragUl {
+                fill_bits: frag_len < MAC_FRAG_UL_MAX_SDU_LEN,
+            };
+            tracing::debug!("-> {}", pdu);
+            pdu.to_bitbuf(&mut block);
+            block.copy_bits(sdu, frag_len);
+            fillbits::addition::write(&mut block, None);
+        }
+
+        block.seek(0);
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tetra_core::debug;
+
+    fn time(f: u8) -> TdmaTime {
+        TdmaTime { t: 1, f, m: 1, h: 0 }
+    }
+
+    fn sdu(len: usize) -> BitBuffer {
+        let mut buf = BitBuffer::new(len);
+        for i in 0..len {
+            buf.write_bit((i % 3 == 0) as u8);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_short_sdu_single_access() {
+        debug::setup_logging_verbose();
+        let mut sched = MsUlScheduler::new(1234);
+        let tx_reporter = TxReporter::new();
+        sched.enqueue(sdu(30), Some(tx_reporter.clone()));
+
+        let (lchan, mut block) = sched.build_ul_block(time(1)).unwrap();
+        assert_eq!(lchan, LogicalChannel::SchHu);
+        assert_eq!(block.get_len(), SCH_HU_CAP);
+
+        let pdu = MacAccess::from_bitbuf(&mut block).unwrap();
+        assert_eq!(pdu.addr.unwrap().ssi, 1234);
+        assert_eq!(pdu.length_ind, Some(11));
+        assert!(pdu.fill_bits);
+        let num_fill_bits = fillbits::removal::get_num_fill_bits(&block, 88, false);
+        assert_eq!(88 - MAC_ACCESS_HDR_LEN - num_fill_bits, 30);
+
+        // Nothing more to send until acked
+        assert!(sched.build_ul_block(time(2)).is_none());
+        assert!(!tx_reporter.is_transmitted());
+        sched.rx_random_access_ack(time(2));
+        assert!(tx_reporter.is_transmitted());
+        assert!(sched.is_idle());
+    }
+
+    #[test]
+    fn test_long_sdu_fragmented() {
+        debug::setup_logging_verbose();
+        let mut sched = MsUlScheduler::new(1234);
+        let tx_reporter = TxReporter::new();
+        sched.enqueue(sdu(400), Some(tx_reporter.clone()));
+
+        let (_, mut block) = sched.build_ul_block(time(1)).unwrap();
+        let pdu = MacAccess::from_bitbuf(&mut block).unwrap();
+        assert!(pdu.is_frag_start());
+        // 400 - 56 = 344 bits remain, needing a MAC-FRAG and a MAC-END
+        assert_eq!(pdu.reservation_req, Some(ReservationRequirement::Req2Slots));
+
+        sched.rx_random_access_ack(time(2));
+        sched.rx_slot_grant(
+            time(2),
+            &BasicSlotgrant {
+                capacity_allocation: BasicSlotgrantCapAlloc::Grant2Slots,
+                granting_delay: BasicSlotgrantGrantingDelay::DelayNOpportunities(1),
+            },
+        );
+
+        // First opportunity skipped
+        assert!(sched.build_ul_block(time(2)).is_none());
+        let (lchan, mut block) = sched.build_ul_block(time(3)).unwrap();
+        assert_eq!(lchan, LogicalChannel::SchF);
+        let frag = MacFragUl::from_bitbuf(&mut block).unwrap();
+        assert!(!frag.fill_bits);
+        assert!(!tx_reporter.is_transmitted());
+
+        let (_, mut block) = sched.build_ul_block(time(4)).unwrap();
+        let end = MacEndUl::from_bitbuf(&mut block).unwrap();
+        let num_fill_bits = fillbits::removal::get_num_fill_bits(&block, 264, false);
+        assert_eq!(264 - MAC_END_UL_HDR_LEN - num_fill_bits, 400 - 56 - 264);
+        assert!(end.fill_bits);
+        assert!(tx_reporter.is_transmitted());
+        assert!(sched.is_idle());
+    }
+
+    #[test]
+    fn test_random_access_gives_up() {
+        debug::setup_logging_verbose();
+        let mut sched = MsUlScheduler::new(1234);
+        sched.set_ra_params(1, 2);
+        let tx_reporter = TxReporter::new();
+        sched.enqueue(sdu(30), Some(tx_reporter.clone()));
+
+        let mut attempts = 0;
+        let mut t = time(1);
+        for _ in 0..200 {
+            if sched.build_ul_block(t).is_some() {
+                attempts += 1;
+            }
+            t = t.add_timeslots(4);
+        }
+        assert_eq!(attempts, 2);
+        assert!(tx_reporter.is_discarded());
+        assert!(sched.is_idle());
+    }
+
+    #[test]
+    fn test_granted_slots_skip_clch() {
+        // Frame 18 of multiframe 2 holds the mandatory CLCH on timeslot 1
+        let first = TdmaTime { t: 1, f: 17, m: 2, h: 0 };
+        assert!(first.add_timeslots(4).is_mandatory_clch());
+        let slots = MsUlScheduler::granted_slots(first, 0, 2);
+        assert_eq!(slots, [first, first.add_timeslots(8)]);
+    }
+}
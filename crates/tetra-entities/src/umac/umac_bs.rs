@@ -1,9 +1,12 @@
 use std::panic;
+use std::sync::Arc;
 
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{AuthPolicy, SecurityClass, SharedConfig};
 use tetra_core::freqs::FreqInfo;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, Todo, unimplemented_log};
+use tetra_core::{
+    BitBuffer, Direction, MAX_TRAFFIC_CHANNELS, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, Todo, channel_carrier, unimplemented_log,
+};
 use tetra_pdus::mle::fields::bs_service_details::BsServiceDetails;
 use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
 use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
@@ -23,6 +26,7 @@ use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
 use tetra_pdus::umac::pdus::mac_u_blck::MacUBlck;
 use tetra_pdus::umac::pdus::mac_u_signal::MacUSignal;
 use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::cipher::MmCipherKeyUpdate;
 use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
@@ -33,6 +37,7 @@ use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::lmac::components::scrambler;
 use crate::umac::subcomp::bs_sched::{BsChannelScheduler, PrecomputedUmacPdus, TCH_S_CAP};
+use crate::umac::subcomp::cipher::{AirInterfaceCipher, ENCRYPTION_MODE_ENCRYPTED, KeystreamGenerator, SduCipher};
 use crate::umac::subcomp::fillbits;
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 
@@ -57,9 +62,15 @@ pub struct UmacBs {
     /// Access to this field is used only by testing code
     pub channel_scheduler: BsChannelScheduler,
     // ulrx_scheduler: UlScheduler,
-    /// Timestamp of last received UL voice frame per timeslot (0-indexed: ts1..ts4).
+    /// Number of carriers of the cell. The main carrier carries the control channel, the others only traffic.
+    num_carriers: u8,
+    /// Timestamp of last received UL voice frame per traffic channel (0-indexed: channel 1 is ts1 of the main carrier).
     /// Used to detect UL inactivity when a radio disappears mid-transmission.
-    last_ul_voice: [Option<TdmaTime>; 4],
+    last_ul_voice: [Option<TdmaTime>; MAX_TRAFFIC_CHANNELS],
+    /// Air-interface encryption keys and keystream generator
+    aie: AirInterfaceCipher,
+    /// (UL sender, DL receiver) ISSIs per traffic channel carrying an individual call, whose DCKs encrypt its traffic
+    individual_parties: [Option<(u32, u32)>; MAX_TRAFFIC_CHANNELS],
 }
 
 struct PendingStch {
@@ -76,7 +87,10 @@ impl UmacBs {
         let scrambling_code = scrambler::tetra_scramb_get_init(c.net.mcc, c.net.mnc, c.cell.colour_code);
         let system_wide_services = Self::get_system_wide_services_state(&config);
         let precomps = Self::generate_precomps(&config);
-        Self {
+        let aie = AirInterfaceCipher::new(c.encryption.as_ref());
+        let num_carriers = c.cell.num_carriers() as u8;
+        let channel_scheduler = BsChannelScheduler::new(scrambling_code, precomps);
+        let mut umac = Self {
             self_component: TetraEntity::Umac,
             config,
             dltime: TdmaTime::default(),
@@ -85,8 +99,57 @@ impl UmacBs {
             defrag: BsDefrag::new(),
             pending_stch: None,
             // event_label_store: EventLabelStore::new(),
-            channel_scheduler: BsChannelScheduler::new(scrambling_code, precomps),
-            last_ul_voice: [None; 4],
+            channel_scheduler,
+            num_carriers,
+            last_ul_voice: [None; MAX_TRAFFIC_CHANNELS],
+            aie,
+            individual_parties: [None; MAX_TRAFFIC_CHANNELS],
+        };
+        umac.refresh_traffic_ciphers();
+        umac
+    }
+
+    /// Replaces the keystream generator, e.g. with a TEA implementation
+    pub fn set_keystream_generator(&mut self, ksg: Arc<dyn KeystreamGenerator>) {
+        self.aie.set_keystream_generator(ksg);
+        self.refresh_traffic_ciphers();
+    }
+
+    /// Returns the MS whose key protects the traffic of a traffic channel in the given direction, if it carries an individual call
+    fn traffic_party(&self, chan: u8, dir: Direction) -> Option<u32> {
+        let parties = self.individual_parties.get(chan as usize - 1).copied().flatten();
+        parties.map(|(ul, dl)| if dir == Direction::Ul { ul } else { dl })
+    }
+
+    /// Returns the cipher for the traffic of a traffic channel in the given direction
+    fn traffic_cipher(&self, chan: u8, dir: Direction) -> Option<SduCipher> {
+        self.aie.traffic_cipher(self.traffic_party(chan, dir))
+    }
+
+    /// Returns true if the traffic of a traffic channel in the given direction may not be carried in the clear
+    fn clear_traffic_refused(&self, chan: u8, dir: Direction) -> bool {
+        self.aie.refuses_clear_traffic(self.traffic_party(chan, dir))
+    }
+
+    /// Hands the current DL traffic cipher of a traffic channel to the scheduler, warning of traffic refused in the clear
+    fn refresh_traffic_cipher(&mut self, chan: u8) {
+        for dir in [Direction::Ul, Direction::Dl] {
+            if self.clear_traffic_refused(chan, dir) {
+                tracing::warn!(
+                    "refusing clear {:?} traffic on ts {}: MS {:?} has no DCK",
+                    dir,
+                    chan,
+                    self.traffic_party(chan, dir)
+                );
+            }
+        }
+        let cipher = self.traffic_cipher(chan, Direction::Dl);
+        self.channel_scheduler.set_traffic_cipher(chan, cipher);
+    }
+
+    fn refresh_traffic_ciphers(&mut self) {
+        for chan in 1..=MAX_TRAFFIC_CHANNELS as u8 {
+            self.refresh_traffic_cipher(chan);
         }
     }
 
@@ -97,16 +160,23 @@ impl UmacBs {
         let c = config.config();
 
         // TODO FIXME make more/all parameters configurable
+        let auth_required = c.auth.as_ref().is_some_and(|auth| auth.policy == AuthPolicy::Required);
+        let security_class = c.encryption.as_ref().map(|enc| enc.security_class);
+        let class3 = security_class == Some(SecurityClass::Class3);
         let ext_services = SysinfoExtendedServices {
-            auth_required: false,
-            class1_supported: true,
-            class2_supported: true,
-            class3_supported: false,
-            sck_n: Some(0),
-            dck_retrieval_during_cell_select: None,
-            dck_retrieval_during_cell_reselect: None,
-            linked_gck_crypto_periods: None,
-            short_gck_vn: None,
+            auth_required,
+            class1_supported: matches!(security_class, None | Some(SecurityClass::Class1)),
+            class2_supported: !class3,
+            class3_supported: class3,
+            sck_n: if class3 {
+                None
+            } else {
+                Some(c.encryption.as_ref().map_or(0, |enc| enc.sck_number - 1))
+            },
+            dck_retrieval_during_cell_select: class3.then_some(false),
+            dck_retrieval_during_cell_reselect: class3.then_some(false),
+            linked_gck_crypto_periods: class3.then_some(false),
+            short_gck_vn: class3.then_some(0),
             sdstl_addressing_method: 2,
             gck_supported: false,
             section: 0,
@@ -174,7 +244,7 @@ impl UmacBs {
                 voice_service: c.cell.voice_service,
                 circuit_mode_data_service: c.cell.circuit_mode_data_service,
                 sndcp_service: c.cell.sndcp_service,
-                aie_service: c.cell.aie_service,
+                aie_service: c.cell.aie_service || matches!(security_class, Some(SecurityClass::Class2 | SecurityClass::Class3)),
                 advanced_link: c.cell.advanced_link,
             },
         };
@@ -508,10 +578,16 @@ impl UmacBs {
         }
 
         // Decrypt if needed
-        if pdu.encrypted {
-            unimplemented_log!("rx_mac_data: Encryption mode > 0");
-            return;
-        }
+        let cipher = if pdu.encrypted {
+            let Some(cipher) = self.aie.cipher_for(&addr) else {
+                tracing::warn!("rx_mac_data: encrypted PDU from {} but no cipher key available, dropping", addr);
+                return;
+            };
+            cipher.apply_to_remaining(&mut prim.pdu, message.dltime, Direction::Ul);
+            Some(cipher)
+        } else {
+            None
+        };
 
         // Handle reservation if present
         // let ul_time = message.dltime.add_timeslots(-2);
@@ -527,9 +603,10 @@ impl UmacBs {
         };
 
         tracing::debug!("rx_mac_data: {}", prim.pdu.dump_bin_full(true));
+        let encrypted = cipher.is_some();
         if is_frag_start {
             // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, cipher);
         } else {
             // Pass directly to LLC
             let sdu = {
@@ -557,7 +634,7 @@ impl UmacBs {
                         endpoint_id: 0,        // TODO FIXME
                         new_endpoint_id: None, // TODO FIXME
                         css_endpoint_id: None, // TODO FIXME
-                        air_interface_encryption: pdu.encrypted as Todo,
+                        air_interface_encryption: encrypted as Todo,
                         chan_change_response_req: false,
                         chan_change_handle: None,
                         chan_info: None,
@@ -659,10 +736,16 @@ impl UmacBs {
         self.channel_scheduler.dl_enqueue_random_access_ack(message.dltime.t, addr);
 
         // Decrypt if needed
-        if pdu.encrypted {
-            unimplemented_log!("rx_mac_access: Encryption mode > 0");
-            return;
-        }
+        let cipher = if pdu.encrypted {
+            let Some(cipher) = self.aie.cipher_for(&addr) else {
+                tracing::warn!("rx_mac_access: encrypted PDU from {} but no cipher key available, dropping", addr);
+                return;
+            };
+            cipher.apply_to_remaining(&mut prim.pdu, message.dltime, Direction::Ul);
+            Some(cipher)
+        } else {
+            None
+        };
 
         // Handle reservation if present
         if let Some(res_req) = &pdu.reservation_req {
@@ -676,9 +759,10 @@ impl UmacBs {
         };
 
         // tracing::debug!("rx_mac_access: {}", prim.pdu.dump_bin_full(true));
+        let encrypted = cipher.is_some();
         if pdu.is_frag_start() {
             // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, cipher);
         } else {
             // Pass directly to LLC
             if prim.pdu.get_len_remaining() == 0 {
@@ -713,7 +797,7 @@ impl UmacBs {
                         endpoint_id: 0,        // TODO FIXME
                         new_endpoint_id: None, // TODO FIXME
                         css_endpoint_id: None, // TODO FIXME
-                        air_interface_encryption: pdu.encrypted as Todo,
+                        air_interface_encryption: encrypted as Todo,
                         chan_change_response_req: false,
                         chan_change_handle: None,
                         chan_info: None,
@@ -773,9 +857,8 @@ impl UmacBs {
             self.channel_scheduler.dump_ul_schedule_full(true);
             return;
         };
-        if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, message.dltime) {
-            unimplemented_log!("rx_mac_frag_ul: Encryption not supported");
-            return;
+        if let Some(cipher) = self.defrag.get_aie_info(slot_owner, message.dltime) {
+            cipher.apply_to_remaining(&mut prim.pdu, message.dltime, Direction::Ul);
         }
 
         // Insert into defragmenter
@@ -839,8 +922,8 @@ impl UmacBs {
             self.channel_scheduler.dump_ul_schedule_full(true);
             return;
         };
-        if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, message.dltime) {
-            unimplemented!("rx_mac_end_ul: Encryption not supported");
+        if let Some(cipher) = self.defrag.get_aie_info(slot_owner, message.dltime) {
+            cipher.apply_to_remaining(&mut prim.pdu, message.dltime, Direction::Ul);
         }
 
         // Insert last fragment and retrieve finalized block
@@ -863,6 +946,7 @@ impl UmacBs {
 
         // Pass completed block to LLC
         tracing::debug!("rx_mac_end_ul: sdu: {:?}", defragbuf.buffer.dump_bin());
+        let encrypted = defragbuf.aie_info.is_some();
 
         let m = SapMsg {
             sap: Sap::TmaSap,
@@ -874,10 +958,10 @@ impl UmacBs {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,              // TODO FIXME
-                new_endpoint_id: None,       // TODO FIXME
-                css_endpoint_id: None,       // TODO FIXME
-                air_interface_encryption: 0, // TODO FIXME implement
+                endpoint_id: 0,        // TODO FIXME
+                new_endpoint_id: None, // TODO FIXME
+                css_endpoint_id: None, // TODO FIXME
+                air_interface_encryption: encrypted as Todo,
                 chan_change_response_req: false,
                 chan_change_handle: None,
                 chan_info: None,
@@ -956,8 +1040,8 @@ impl UmacBs {
             self.channel_scheduler.dump_ul_schedule_full(true);
             return;
         };
-        if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, message.dltime) {
-            unimplemented!("rx_mac_end_hu: Encryption not supported");
+        if let Some(cipher) = self.defrag.get_aie_info(slot_owner, message.dltime) {
+            cipher.apply_to_remaining(&mut prim.pdu, message.dltime, Direction::Ul);
         }
 
         // Insert last fragment and retrieve finalized block
@@ -980,6 +1064,7 @@ impl UmacBs {
 
         // Pass completed block to LLC
         tracing::debug!("rx_mac_end_hu: sdu: {:?}", defragbuf.buffer.dump_bin());
+        let encrypted = defragbuf.aie_info.is_some();
 
         let m = SapMsg {
             sap: Sap::TmaSap,
@@ -991,10 +1076,10 @@ impl UmacBs {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,              // TODO FIXME
-                new_endpoint_id: None,       // TODO FIXME
-                css_endpoint_id: None,       // TODO FIXME
-                air_interface_encryption: 0, // TODO FIXME implement
+                endpoint_id: 0,        // TODO FIXME
+                new_endpoint_id: None, // TODO FIXME
+                css_endpoint_id: None, // TODO FIXME
+                air_interface_encryption: encrypted as Todo,
                 chan_change_response_req: false,
                 chan_change_handle: None,
                 chan_info: None,
@@ -1101,6 +1186,19 @@ impl UmacBs {
         let SapMsgInner::TmaUnitdataReq(prim) = message.msg else { panic!() };
         let mut sdu = prim.pdu;
 
+        // Encrypt with the key for the addressed MS or group, unless clear transmission was requested explicitly
+        let cipher = if prim.air_interface_encryption == Some(0) {
+            None
+        } else {
+            self.aie.cipher_for(&prim.main_address)
+        };
+        let encryption_mode = if cipher.is_some() { ENCRYPTION_MODE_ENCRYPTED } else { 0 };
+        // The address is flagged as encrypted along with the TM-SDU. ESI mapping is not applied, the SSI is sent as-is.
+        let main_address = TetraAddress {
+            encrypted: cipher.is_some(),
+            ..prim.main_address
+        };
+
         // ── FACCH/Stealing path ──────────────────────────────────────────
         // stealing_permission → STCH on traffic channel for time-critical signaling
         // (D-TX CEASED, D-TX GRANTED) per EN 300 392-2, clause 23.5.
@@ -1111,8 +1209,8 @@ impl UmacBs {
             let traffic_ts = prim
                 .chan_alloc
                 .as_ref()
-                .and_then(|ca| ca.timeslots.iter().enumerate().find(|&(_, &set)| set).map(|(i, _)| (i + 1) as u8))
-                .or_else(|| (2..=4u8).find(|&t| self.channel_scheduler.circuit_is_active(Direction::Dl, t)));
+                .and_then(|ca| ca.first_channel())
+                .or_else(|| (2..=4 * self.num_carriers).find(|&t| self.channel_scheduler.circuit_is_active(Direction::Dl, t)));
 
             if let Some(ts) = traffic_ts {
                 // Build MAC-RESOURCE PDU for the STCH half-slot (124 type1 bits).
@@ -1131,10 +1229,10 @@ impl UmacBs {
                 let mut mac_pdu = MacResource {
                     fill_bits: false,
                     pos_of_grant: 0,
-                    encryption_mode: 0,
+                    encryption_mode,
                     random_access_flag: is_random_access_response,
                     length_ind: 0,
-                    addr: Some(prim.main_address),
+                    addr: Some(main_address),
                     event_label: None,
                     usage_marker,
                     power_control_element: None,
@@ -1150,6 +1248,7 @@ impl UmacBs {
                 // Both BL-DATA and BL-UDATA are valid D-LLC-PDU types per the spec.
                 sdu.seek(0);
                 let sdu_len = sdu.get_len();
+                let sdu_start = stch_block.get_pos();
                 stch_block.copy_bits(&mut sdu, sdu_len);
                 // Remaining bits beyond length_ind are ignored by the receiver.
 
@@ -1160,7 +1259,9 @@ impl UmacBs {
                     stch_block.get_len()
                 );
 
-                self.channel_scheduler.dl_enqueue_stealing(ts, stch_block, prim.tx_reporter);
+                // The TM-SDU is encrypted once the frame it is sent in is known
+                let cipher = cipher.map(|cipher| (cipher, sdu_start..sdu_start + sdu_len));
+                self.channel_scheduler.dl_enqueue_stealing(ts, stch_block, prim.tx_reporter, cipher);
 
                 return;
             } else {
@@ -1171,10 +1272,8 @@ impl UmacBs {
 
         // ── Normal signaling path (MCCH / SCH/F) ────────────────────────
         let (usage_marker, mac_chan_alloc) = if let Some(chan_alloc) = prim.chan_alloc {
-            (
-                chan_alloc.usage,
-                Some(Self::cmce_to_mac_chanalloc(&chan_alloc, self.config.config().cell.main_carrier)),
-            )
+            let carrier_num = self.config.config().cell.carrier_number(chan_alloc.carrier.unwrap_or(0));
+            (chan_alloc.usage, Some(Self::cmce_to_mac_chanalloc(&chan_alloc, carrier_num)))
         } else {
             (None, None)
         };
@@ -1187,10 +1286,10 @@ impl UmacBs {
         let mut pdu = MacResource {
             fill_bits: false, // Updated later
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode,
             random_access_flag: is_random_access_response,
             length_ind: 0, // Updated later
-            addr: Some(prim.main_address),
+            addr: Some(main_address),
             event_label: None,
             usage_marker,
             power_control_element: None,
@@ -1205,7 +1304,8 @@ impl UmacBs {
         if message.dltime.t != 1 {
             tracing::warn!("rx_ul_tma_unitdata_req: signaling scheduled for non-MCCH {}", message.dltime.t);
         }
-        self.channel_scheduler.dl_enqueue_tma(message.dltime.t, pdu, sdu, prim.tx_reporter);
+        self.channel_scheduler
+            .dl_enqueue_tma(message.dltime.t, pdu, sdu, prim.tx_reporter, cipher);
 
         // let enqueue_ts = 1;
         // self.channel_scheduler.dl_enqueue_tma(enqueue_ts, pdu, sdu, prim.tx_reporter);
@@ -1236,10 +1336,12 @@ impl UmacBs {
                 let ts = prim.ts;
                 // Refresh UL inactivity timer when DL voice is being fed (network call scenario).
                 // This prevents false timeout when Brew is the speaker and no UL radio is transmitting.
-                if (1..=4).contains(&ts) && self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) && self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
                     self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
                 }
-                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) && self.clear_traffic_refused(ts, Direction::Dl) {
+                    tracing::trace!("rx_tmd_prim: dropping DL voice for MS without DCK ts={}", ts);
+                } else if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
                     self.channel_scheduler.dl_schedule_tmd(ts, prim.data);
                 } else {
                     tracing::warn!(
@@ -1253,34 +1355,53 @@ impl UmacBs {
             // UL voice from LMAC → forward to Brew + optional loopback to DL
             SapMsgInner::TmdCircuitDataInd(prim) => {
                 let ts = prim.ts;
-                let data = prim.data;
+                let mut data = prim.data;
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) && self.clear_traffic_refused(ts, Direction::Ul) {
+                    tracing::trace!("rx_tmd_prim: dropping UL voice from MS without DCK ts={}", ts);
+                    return;
+                }
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts)
+                    && let Some(cipher) = self.traffic_cipher(ts, Direction::Ul)
+                {
+                    decrypt_ul_tch(&cipher, &mut data, dltime, channel_carrier(ts));
+                }
 
                 // Track last UL voice frame time for inactivity detection
-                if (1..=4).contains(&ts) {
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
                     self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
                 }
 
-                // Forward UL voice to Brew (User plane) if loaded
-                if self.config.config().brew.is_some() {
+                // Forward UL voice to Brew and the inter-site backhaul (User plane) if loaded
+                let cfg = self.config.config();
+                let bridges = [
+                    (TetraEntity::Brew, cfg.brew.is_some()),
+                    (TetraEntity::Backhaul, cfg.backhaul.is_some()),
+                ];
+                for (dest, active) in bridges {
+                    if !active {
+                        continue;
+                    }
                     if self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
                         let msg = SapMsg {
                             sap: Sap::TmdSap,
                             src: TetraEntity::Umac,
-                            dest: TetraEntity::Brew,
+                            dest,
                             dltime,
                             msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts, data: data.clone() }),
                         };
                         queue.push_back(msg);
                     } else {
-                        tracing::trace!("rx_tmd_prim: no active UL circuit on ts={}, dropping UL voice to Brew", ts);
+                        tracing::trace!("rx_tmd_prim: no active UL circuit on ts={}, dropping UL voice to {:?}", ts, dest);
                     }
                 }
 
-                // Loopback only if there's an active DL circuit on this timeslot
-                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
-                    tracing::trace!("rx_tmd_prim: loopback UL voice on ts={}", ts);
+                // Loopback only if there's an active DL circuit on the target timeslot. For duplex
+                // individual calls, the UL of one party is cross-connected to the DL of the other.
+                let dl_ts = self.channel_scheduler.circuit_dl_target(ts);
+                if self.channel_scheduler.circuit_is_active(Direction::Dl, dl_ts) {
+                    tracing::trace!("rx_tmd_prim: loopback UL voice on ts={} to DL ts={}", ts, dl_ts);
                     if let Some(packed) = pack_ul_acelp_bits(&data) {
-                        self.channel_scheduler.dl_schedule_tmd(ts, packed);
+                        self.channel_scheduler.dl_schedule_tmd(dl_ts, packed);
                     } else {
                         tracing::warn!(
                             "rx_tmd_prim: unsupported UL voice length {} on ts={}, skipping loopback",
@@ -1289,7 +1410,7 @@ impl UmacBs {
                         );
                     }
                 } else {
-                    tracing::trace!("rx_tmd_prim: no active DL circuit on ts={}, skipping loopback", ts);
+                    tracing::trace!("rx_tmd_prim: no active DL circuit on ts={}, skipping loopback", dl_ts);
                 }
             }
             _ => {
@@ -1397,16 +1518,23 @@ impl UmacBs {
                 circuit_mode: circuit.circuit_mode,
                 speech_service: circuit.speech_service,
                 etee_encrypted: circuit.etee_encrypted,
+                peer_ts: circuit.peer_ts,
+                individual_parties: circuit.individual_parties,
             };
             self.channel_scheduler.create_circuit(d, c);
 
             // Start UL inactivity timer when opening a UL circuit
-            if d == Direction::Ul && (1..=4).contains(&ts) {
+            if d == Direction::Ul && (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
                 self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
             }
 
             tracing::debug!("  rx_control_circuit_open: Setup {:?} circuit for ts {}", d, ts);
         }
+
+        if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
+            self.individual_parties[ts as usize - 1] = circuit.individual_parties;
+            self.refresh_traffic_cipher(ts);
+        }
     }
 
     fn rx_control_circuit_close(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
@@ -1426,7 +1554,7 @@ impl UmacBs {
             match self.channel_scheduler.close_circuit(d, ts) {
                 Some(_) => {
                     // Clear UL inactivity timer when closing a UL circuit
-                    if d == Direction::Ul && (1..=4).contains(&ts) {
+                    if d == Direction::Ul && (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
                         self.last_ul_voice[ts as usize - 1] = None;
                     }
                     tracing::info!("  rx_control_circuit_close: Closed {:?} circuit for ts {}", d, ts);
@@ -1436,6 +1564,14 @@ impl UmacBs {
                 }
             }
         }
+
+        if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts)
+            && !self.channel_scheduler.circuit_is_active(Direction::Dl, ts)
+            && !self.channel_scheduler.circuit_is_active(Direction::Ul, ts)
+        {
+            self.individual_parties[ts as usize - 1] = None;
+            self.refresh_traffic_cipher(ts);
+        }
     }
 
     /// Check for UL inactivity on traffic timeslots. If no voice frames have arrived
@@ -1445,7 +1581,7 @@ impl UmacBs {
         // 3 multiframes ~ 3s. Above T.213 (1s) to tolerate DTX and brief RF fading.
         const UL_INACTIVITY_TIMESLOTS: i32 = 3 * 18 * 4;
 
-        for ts in 1..=4u8 {
+        for ts in 1..=4 * self.num_carriers {
             let idx = ts as usize - 1;
 
             // Only check timeslots with an active UL circuit
@@ -1479,10 +1615,25 @@ impl UmacBs {
         }
     }
 
+    fn rx_cipher_key_update(&mut self, update: MmCipherKeyUpdate) {
+        tracing::debug!(
+            "rx_cipher_key_update: {} DCK for ISSI {}",
+            if update.dck.is_some() { "storing" } else { "removing" },
+            update.issi
+        );
+        self.aie.set_dck(update.issi, update.dck);
+        self.refresh_traffic_ciphers();
+    }
+
     fn rx_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_control");
-        let SapMsgInner::CmceCallControl(prim) = message.msg else {
-            panic!()
+        let prim = match message.msg {
+            SapMsgInner::CmceCallControl(prim) => prim,
+            SapMsgInner::MmCipherKeyUpdate(update) => {
+                self.rx_cipher_key_update(update);
+                return;
+            }
+            _ => panic!(),
         };
 
         match prim {
@@ -1496,20 +1647,31 @@ impl UmacBs {
             CallControl::FloorReleased { ts, .. } => {
                 self.channel_scheduler.set_hangtime(ts, true);
                 // Stop checking UL inactivity during hangtime
-                if (1..=4).contains(&ts) {
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
                     self.last_ul_voice[ts as usize - 1] = None;
                 }
             }
-            CallControl::FloorGranted { ts, .. } => {
+            CallControl::FloorGranted {
+                ts,
+                source_issi,
+                dest_gssi,
+                ..
+            } => {
                 self.channel_scheduler.set_hangtime(ts, false);
                 // Restart UL inactivity timer when new speaker gets floor
-                if (1..=4).contains(&ts) {
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
                     self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+
+                    // On a simplex individual call, the new speaker sends with its DCK and its peer receives with its own
+                    if self.individual_parties[ts as usize - 1].is_some() {
+                        self.individual_parties[ts as usize - 1] = Some((source_issi, dest_gssi));
+                        self.refresh_traffic_cipher(ts);
+                    }
                 }
             }
             CallControl::CallEnded { ts, .. } => {
                 self.channel_scheduler.set_hangtime(ts, false);
-                if (1..=4).contains(&ts) {
+                if (1..=MAX_TRAFFIC_CHANNELS as u8).contains(&ts) {
                     self.last_ul_voice[ts as usize - 1] = None;
                 }
             }
@@ -1518,7 +1680,11 @@ impl UmacBs {
             CallControl::UlInactivityTimeout { .. } => {}
 
             // NetworkCall* are for CMCE ↔ Brew, not UMAC (for now)
-            CallControl::NetworkCallStart { .. } | CallControl::NetworkCallReady { .. } | CallControl::NetworkCallEnd { .. } => {
+            CallControl::NetworkCallStart { .. }
+            | CallControl::NetworkCallReady { .. }
+            | CallControl::NetworkCallEnd { .. }
+            | CallControl::NetworkIndividualCall { .. }
+            | CallControl::CallDtmf { .. } => {
                 tracing::trace!("rx_control: ignoring CMCE-Brew notification (not for UMAC)");
             }
         }
@@ -1565,6 +1731,7 @@ impl TetraEntityTrait for UmacBs {
 
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
         self.dltime = ts;
+        self.aie.observe_time(ts);
         self.refresh_system_wide_services();
 
         if self.channel_scheduler.cur_dltime != ts && self.channel_scheduler.cur_dltime == (TdmaTime { t: 0, f: 0, m: 0, h: 0 }) {
@@ -1580,6 +1747,18 @@ impl TetraEntityTrait for UmacBs {
 
         // Collect/construct traffic that should be sent down to the LMAC
         // This is basically the _previous_ timeslot
+        // Secondary carriers go first: the PHY transmits all carriers once the main carrier slot arrives
+        for carrier in 1..self.num_carriers {
+            if let Some(elem) = self.channel_scheduler.finalize_secondary_ts_for_tick(carrier) {
+                queue.push_back(SapMsg {
+                    sap: Sap::TmvSap,
+                    src: self.self_component,
+                    dest: TetraEntity::Lmac,
+                    dltime: ts.add_timeslots(-1),
+                    msg: SapMsgInner::TmvUnitdataReq(elem),
+                });
+            }
+        }
         let elem = self.channel_scheduler.finalize_ts_for_tick();
         let s = SapMsg {
             sap: Sap::TmvSap,
@@ -1593,9 +1772,22 @@ impl TetraEntityTrait for UmacBs {
     }
 }
 
+/// Decrypt a UL TCH/S block in place. Handles the same formats as pack_ul_acelp_bits.
+fn decrypt_ul_tch(cipher: &SduCipher, data: &mut Vec<u8>, t: TdmaTime, carrier: u8) {
+    const PACKED_TCH_S_BYTES: usize = TCH_S_CAP.div_ceil(8);
+
+    if data.len() == PACKED_TCH_S_BYTES {
+        let mut buf = BitBuffer::from_vec(std::mem::take(data));
+        cipher.apply(&mut buf, TCH_S_CAP, t, carrier, Direction::Ul);
+        *data = buf.into_bytes();
+    } else if data.len() >= TCH_S_CAP {
+        cipher.apply_to_bitarr(&mut data[..TCH_S_CAP], t, carrier, Direction::Ul);
+    }
+}
+
 /// Pack UL ACELP voice bits (274 bits, one-bit-per-byte) into packed byte array for DL transmission.
 /// Handles both already-packed (35 bytes) and unpacked (274 bytes) formats.
-fn pack_ul_acelp_bits(bits: &[u8]) -> Option<Vec<u8>> {
+pub(crate) fn pack_ul_acelp_bits(bits: &[u8]) -> Option<Vec<u8>> {
     const PACKED_TCH_S_BYTES: usize = (TCH_S_CAP + 7) / 8;
 
     // Already packed format — pass through
@@ -0,0 +1,363 @@
+use std::collections::VecDeque;
+
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, Sap, TdmaTime, TetraAddress};
+use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
+use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_data::MacData;
+use tetra_pdus::umac::pdus::mac_end_hu::MacEndHu;
+use tetra_pdus::umac::pdus::mac_end_ul::MacEndUl;
+use tetra_pdus::umac::pdus::mac_frag_ul::MacFragUl;
+use tetra_saps::tlmc::{TlmcConfigureReq, TlmcValidAddress};
+use tetra_saps::tmv::TmvUnitdataInd;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::monitor::decoder;
+use crate::monitor::{EventLog, MonitorEvent};
+use crate::umac::subcomp::bs_defrag::BsDefrag;
+use crate::umac::subcomp::fillbits;
+use crate::umac::umac_ms::UmacMs;
+use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
+
+/// Passive monitor UMAC
+///
+/// Downlink blocks are handled by an MS Umac that accepts every address and never transmits. What
+/// it would pass up to the LLC and MLE is decoded here instead, as there are no upper layers in a
+/// monitor stack. Uplink blocks are parsed here as well, as the BS Umac relies on its own uplink
+/// schedule to do so. All decoded signalling ends up in the event log.
+pub struct UmacMon {
+    config: SharedConfig,
+
+    /// Follows the downlink of the monitored cell
+    dl: UmacMs,
+
+    /// Fragmented uplink TM-SDUs being reassembled
+    ul_defrag: BsDefrag,
+    /// MAC-FRAG and MAC-END carry no address; the BS knows from its grants who sends them.
+    /// We assume the continuation belongs to whoever last started a fragmented transfer on the timeslot.
+    ul_frag_owner: [Option<u32>; 4],
+
+    /// MCC and MNC of the cell we are synchronized to
+    cell: Option<(u16, u16)>,
+
+    event_log: EventLog,
+}
+
+impl UmacMon {
+    pub fn new(config: SharedConfig, event_log: EventLog) -> Self {
+        Self {
+            dl: UmacMs::new_monitor(config.clone()),
+            config,
+            ul_defrag: BsDefrag::new(),
+            ul_frag_owner: [None; 4],
+            cell: None,
+            event_log,
+        }
+    }
+
+    fn log_event(&mut self, dltime: TdmaTime, direction: Direction, event: MonitorEvent) {
+        self.event_log.log(dltime, direction, &event);
+    }
+
+    fn rx_tm_sdu(&mut self, dltime: TdmaTime, direction: Direction, addr: TetraAddress, sdu: BitBuffer) {
+        if let Some(event) = decoder::decode_tm_sdu(sdu, addr, direction) {
+            self.log_event(dltime, direction, event);
+        }
+    }
+
+    /// Pass a downlink block through the MS Umac, and take over whatever it sends to the upper layers
+    fn rx_dl_block(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let mut pending = VecDeque::from([message]);
+        let mut to_lmac = Vec::new();
+
+        while let Some(message) = pending.pop_front() {
+            let mut dl_queue = MessageQueue::new();
+            self.dl.rx_prim(&mut dl_queue, message);
+
+            while let Some(out) = dl_queue.pop_front() {
+                match out.msg {
+                    SapMsgInner::TmvConfigureReq(_) => to_lmac.push(out),
+                    SapMsgInner::TlmbSyncInd(mut prim) => {
+                        if let Some(m) = self.rx_sync(out.dltime, &mut prim.tl_sdu) {
+                            pending.push_back(m);
+                        }
+                    }
+                    SapMsgInner::TmaUnitdataInd(prim) => {
+                        if let Some(sdu) = prim.pdu {
+                            self.rx_tm_sdu(out.dltime, Direction::Dl, prim.main_address, sdu);
+                        }
+                    }
+                    other => {
+                        tracing::trace!("rx_dl_block: ignoring {:?}", other);
+                    }
+                }
+            }
+        }
+
+        // The LMAC needs these before the remaining blocks of the burst, keep their order
+        for m in to_lmac.into_iter().rev() {
+            queue.push_prio(m, MessagePrio::Immediate);
+        }
+    }
+
+    /// Stands in for the MLE: the MS Umac needs the MCC and MNC from the D-MLE-SYNC for the scrambling code
+    fn rx_sync(&mut self, dltime: TdmaTime, tl_sdu: &mut BitBuffer) -> Option<SapMsg> {
+        let pdu = match DMleSync::from_bitbuf(tl_sdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DMleSync: {:?} {}", e, tl_sdu.dump_bin());
+                return None;
+            }
+        };
+        if self.cell == Some((pdu.mcc, pdu.mnc)) {
+            return None;
+        }
+        self.cell = Some((pdu.mcc, pdu.mnc));
+        self.log_event(
+            dltime,
+            Direction::Dl,
+            MonitorEvent::CellSync {
+                mcc: pdu.mcc,
+                mnc: pdu.mnc,
+            },
+        );
+
+        Some(SapMsg {
+            sap: Sap::TlmcSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Umac,
+            dltime,
+            msg: SapMsgInner::TlmcConfigureReq(TlmcConfigureReq {
+                valid_addresses: Some(TlmcValidAddress {
+                    mcc: pdu.mcc,
+                    mnc: pdu.mnc,
+                }),
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Parse all MAC PDUs in an uplink block (Clause 21.4.1)
+    fn rx_ul_block(&mut self, dltime: TdmaTime, mut prim: TmvUnitdataInd) {
+        loop {
+            let Some(bits) = prim.pdu.peek_bits(3) else {
+                return;
+            };
+            let orig_start = prim.pdu.get_raw_start();
+
+            match prim.logical_channel {
+                LogicalChannel::SchHu => {
+                    if (bits >> 2) & 1 == 0 {
+                        self.rx_mac_access(dltime, &mut prim.pdu);
+                    } else {
+                        self.rx_mac_end_hu(dltime, &mut prim.pdu);
+                    }
+                }
+                LogicalChannel::SchF | LogicalChannel::Stch => match MacPduType::try_from(bits >> 1) {
+                    Ok(MacPduType::MacResourceMacData) => self.rx_mac_data(dltime, &mut prim.pdu),
+                    Ok(MacPduType::MacFragMacEnd) if bits & 1 == 0 => self.rx_mac_frag_ul(dltime, &mut prim.pdu),
+                    Ok(MacPduType::MacFragMacEnd) => self.rx_mac_end_ul(dltime, &mut prim.pdu),
+                    other => {
+                        tracing::trace!("rx_ul_block: ignoring {:?}", other);
+                        return;
+                    }
+                },
+                other => {
+                    tracing::warn!("rx_ul_block: unexpected logical channel {:?}", other);
+                    return;
+                }
+            }
+
+            // Continue with the next MAC PDU if any, a null PDU needs at least 16 bits
+            if prim.pdu.get_raw_start() == orig_start || prim.pdu.get_len() < 16 {
+                return;
+            }
+        }
+    }
+
+    /// Restrict the block to the current MAC PDU without fill bits. Returns the PDU length,
+    /// the number of fill bits and the original end of the block.
+    fn delimit_pdu(pdu: &mut BitBuffer, len_bits: usize, fill_bits: bool, is_null_pdu: bool) -> (usize, usize, usize) {
+        let len_bits = len_bits.min(pdu.get_len());
+        let num_fill_bits = if fill_bits {
+            fillbits::removal::get_num_fill_bits(pdu, len_bits, is_null_pdu)
+        } else {
+            0
+        };
+        let orig_end = pdu.get_raw_end();
+        pdu.set_raw_end(pdu.get_raw_start() + len_bits - num_fill_bits);
+        (len_bits - num_fill_bits, num_fill_bits, orig_end)
+    }
+
+    /// Move on to the MAC PDU following the current one
+    fn next_pdu(pdu: &mut BitBuffer, (len_bits, num_fill_bits, orig_end): (usize, usize, usize)) {
+        pdu.set_raw_end(orig_end);
+        pdu.set_raw_pos(pdu.get_raw_start() + len_bits + num_fill_bits);
+        pdu.set_raw_start(pdu.get_raw_pos());
+    }
+
+    /// Deliver or start reassembling the TM-SDU following a MAC-ACCESS or MAC-DATA header
+    fn rx_ul_sdu(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer, addr: TetraAddress, encrypted: bool, frag_start: bool) {
+        let ts = (dltime.t - 1) as usize;
+        if encrypted {
+            tracing::debug!("rx_ul_sdu: skipping encrypted TM-SDU from {}", addr);
+            self.ul_frag_owner[ts] = None;
+        } else if frag_start {
+            self.ul_defrag.insert_first(pdu, dltime, addr, None);
+            self.ul_frag_owner[ts] = Some(addr.ssi);
+        } else if pdu.get_len_remaining() > 0 {
+            self.rx_tm_sdu(dltime, Direction::Ul, addr, BitBuffer::from_bitbuffer_pos(pdu));
+        }
+    }
+
+    /// Add the last fragment of a transfer, and decode the reassembled TM-SDU
+    fn rx_ul_last_frag(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer) {
+        let ts = (dltime.t - 1) as usize;
+        let Some(owner) = self.ul_frag_owner[ts].take() else {
+            tracing::debug!("rx_ul_last_frag: no fragmented transfer on ts {}", dltime.t);
+            return;
+        };
+        if let Some(defragbuf) = self.ul_defrag.insert_last(pdu, owner, dltime) {
+            self.rx_tm_sdu(dltime, Direction::Ul, defragbuf.addr, defragbuf.buffer);
+        }
+    }
+
+    fn rx_mac_access(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer) {
+        let hdr = match MacAccess::from_bitbuf(pdu) {
+            Ok(hdr) => hdr,
+            Err(e) => {
+                tracing::debug!("Failed parsing MacAccess: {:?} {}", e, pdu.dump_bin());
+                return;
+            }
+        };
+        let Some(addr) = hdr.addr else {
+            tracing::debug!("rx_mac_access: event labels not supported");
+            return;
+        };
+        let len_bits = match hdr.length_ind {
+            Some(0) => 36,
+            Some(length_ind) => length_ind as usize * 8,
+            None => pdu.get_len(),
+        };
+        let bounds = Self::delimit_pdu(pdu, len_bits, hdr.fill_bits, hdr.is_null_pdu());
+        if hdr.is_null_pdu() {
+            return;
+        }
+        self.rx_ul_sdu(dltime, pdu, addr, hdr.encrypted, hdr.is_frag_start());
+        Self::next_pdu(pdu, bounds);
+    }
+
+    fn rx_mac_data(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer) {
+        let hdr = match MacData::from_bitbuf(pdu) {
+            Ok(hdr) => hdr,
+            Err(e) => {
+                tracing::debug!("Failed parsing MacData: {:?} {}", e, pdu.dump_bin());
+                return;
+            }
+        };
+        let Some(addr) = hdr.addr else {
+            tracing::debug!("rx_mac_data: event labels not supported");
+            return;
+        };
+        let (len_bits, frag_start) = match hdr.length_ind {
+            Some(0) => return, // Null PDU
+            Some(0b111110) => (pdu.get_len(), false),
+            Some(0b111111) => (pdu.get_len(), true),
+            Some(length_ind) => (length_ind as usize * 8, false),
+            None => (pdu.get_len(), hdr.frag_flag.unwrap_or(false)),
+        };
+        let bounds = Self::delimit_pdu(pdu, len_bits, hdr.fill_bits, false);
+        self.rx_ul_sdu(dltime, pdu, addr, hdr.encrypted, frag_start);
+        Self::next_pdu(pdu, bounds);
+    }
+
+    fn rx_mac_frag_ul(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer) {
+        let hdr = match MacFragUl::from_bitbuf(pdu) {
+            Ok(hdr) => hdr,
+            Err(e) => {
+                tracing::debug!("Failed parsing MacFragUl: {:?} {}", e, pdu.dump_bin());
+                return;
+            }
+        };
+        // A MAC-FRAG always fills the rest of the block
+        Self::delimit_pdu(pdu, pdu.get_len(), hdr.fill_bits, false);
+        let ts = (dltime.t - 1) as usize;
+        match self.ul_frag_owner[ts] {
+            Some(owner) => self.ul_defrag.insert_next(pdu, owner, dltime),
+            None => tracing::debug!("rx_mac_frag_ul: no fragmented transfer on ts {}", dltime.t),
+        }
+    }
+
+    fn rx_mac_end_ul(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer) {
+        let hdr = match MacEndUl::from_bitbuf(pdu) {
+            Ok(hdr) => hdr,
+            Err(e) => {
+                tracing::debug!("Failed parsing MacEndUl: {:?} {}", e, pdu.dump_bin());
+                return;
+            }
+        };
+        let len_bits = hdr.length_ind.map_or(pdu.get_len(), |l| l as usize * 8);
+        let bounds = Self::delimit_pdu(pdu, len_bits, hdr.fill_bits, false);
+        self.rx_ul_last_frag(dltime, pdu);
+        Self::next_pdu(pdu, bounds);
+    }
+
+    fn rx_mac_end_hu(&mut self, dltime: TdmaTime, pdu: &mut BitBuffer) {
+        let hdr = match MacEndHu::from_bitbuf(pdu) {
+            Ok(hdr) => hdr,
+            Err(e) => {
+                tracing::debug!("Failed parsing MacEndHu: {:?} {}", e, pdu.dump_bin());
+                return;
+            }
+        };
+        // Table 21.44: length indication 0 is reserved
+        let len_bits = match hdr.length_ind {
+            Some(0) => return,
+            Some(length_ind) => length_ind as usize * 8,
+            None => pdu.get_len(),
+        };
+        let bounds = Self::delimit_pdu(pdu, len_bits, hdr.fill_bits, false);
+        self.rx_ul_last_frag(dltime, pdu);
+        Self::next_pdu(pdu, bounds);
+    }
+}
+
+impl TetraEntityTrait for UmacMon {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Umac
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.dl.set_config(config.clone());
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match message.msg {
+            SapMsgInner::TmvUnitdataInd(prim) if prim.direction == Direction::Ul => {
+                self.rx_ul_block(message.dltime, prim);
+            }
+            SapMsgInner::TmvUnitdataInd(_) => {
+                self.rx_dl_block(queue, message);
+            }
+            SapMsgInner::TmdCircuitDataInd(_) => {
+                // Speech is not monitored
+            }
+            other => {
+                tracing::warn!("rx_prim: unexpected {:?} on {:?}", other, message.sap);
+            }
+        }
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dl.tick_start(queue, ts);
+        if let Some(dltime) = self.dl.dltime() {
+            self.ul_defrag.age_buffers(dltime);
+        }
+    }
+}
@@ -1,16 +1,21 @@
+use std::collections::VecDeque;
 use std::panic;
 
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, PhyBlockNum, Sap, TdmaTime, Todo, unimplemented_log};
-use tetra_saps::tlmb::TlmbSysinfoInd;
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, Todo, unimplemented_log};
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::tlmb::{TlmbSyncInd, TlmbSysinfoInd};
 use tetra_saps::tma::TmaUnitdataInd;
-use tetra_saps::tmv::TmvConfigureReq;
+use tetra_saps::tmd::TmdCircuitDataInd;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::tmv::{TmvConfigureReq, TmvUnitdataReq, TmvUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use tetra_pdus::umac::enums::broadcast_type::BroadcastType;
 use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
+use tetra_pdus::umac::fields::channel_allocation::ChanAllocElement;
 use tetra_pdus::umac::pdus::access_assign::AccessAssign;
 use tetra_pdus::umac::pdus::access_assign_fr18::AccessAssignFr18;
 use tetra_pdus::umac::pdus::mac_end_dl::MacEndDl;
@@ -19,10 +24,16 @@ use tetra_pdus::umac::pdus::mac_resource::MacResource;
 use tetra_pdus::umac::pdus::mac_sync::MacSync;
 use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
 
+use crate::umac::subcomp::bs_sched::TCH_S_CAP;
+use crate::umac::subcomp::circuit_mgr::CircuitMgr;
 use crate::umac::subcomp::fillbits;
 use crate::umac::subcomp::ms_defrag::MsDefrag;
+use crate::umac::subcomp::ms_ul_sched::MsUlScheduler;
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 
+/// Broadcast SSI, addressed to all MSs in the cell
+const SSI_ALL: u32 = 0xFFFFFF;
+
 pub struct UmacMs {
     // config: Option<SharedConfig>,
     self_component: TetraEntity,
@@ -37,10 +48,28 @@ pub struct UmacMs {
     cc: Option<u8>,
     /// Derived from mcc/mnc, and passed to lmac
     scrambling_code: Option<u32>,
+
+    /// Downlink time of the serving cell, recovered from the SYNC and advanced every tick.
+    /// None until the first SYNC has been received.
+    dltime: Option<TdmaTime>,
+
+    /// Own ISSI, used for address filtering and as address on all uplink PDUs
+    issi: u32,
+    /// Takes TM-SDUs from the LLC through random access and granted slots
+    ul_sched: MsUlScheduler,
+
+    /// Circuits set up by the CMCE for the group call we take part in
+    circuits: CircuitMgr,
+    /// Speech frames from the user, to be sent on the uplink circuit
+    ul_traffic: VecDeque<Vec<u8>>,
+
+    /// Passive monitor: deliver PDUs for all addresses and never transmit
+    monitor: bool,
 }
 
 impl UmacMs {
     pub fn new(config: SharedConfig) -> Self {
+        let issi = config.config().ms.as_ref().expect("MS stack requires ms config").issi;
         Self {
             self_component: TetraEntity::Umac,
             config,
@@ -50,6 +79,55 @@ impl UmacMs {
             mnc: None,
             cc: None,
             scrambling_code: None,
+
+            dltime: None,
+            issi,
+            ul_sched: MsUlScheduler::new(issi),
+            circuits: CircuitMgr::new(),
+            ul_traffic: VecDeque::new(),
+            monitor: false,
+        }
+    }
+
+    /// Create a receive-only instance for monitor mode, which has no identity of its own
+    pub fn new_monitor(config: SharedConfig) -> Self {
+        Self {
+            self_component: TetraEntity::Umac,
+            config,
+            defrag: MsDefrag::new(),
+
+            mcc: None,
+            mnc: None,
+            cc: None,
+            scrambling_code: None,
+
+            dltime: None,
+            issi: 0,
+            ul_sched: MsUlScheduler::new(0),
+            circuits: CircuitMgr::new(),
+            ul_traffic: VecDeque::new(),
+            monitor: true,
+        }
+    }
+
+    /// Downlink time of the serving cell, None until synchronized
+    pub fn dltime(&self) -> Option<TdmaTime> {
+        self.dltime
+    }
+
+    /// Returns true if a downlink PDU to the given SSI is meant for us: our ISSI, one of our groups, or all MSs
+    fn is_own_address(&self, ssi: u32) -> bool {
+        self.monitor || ssi == self.issi || ssi == SSI_ALL || self.config.config().ms.as_ref().is_some_and(|ms| ms.groups.contains(&ssi))
+    }
+
+    /// Converts a received channel allocation element to the form the CMCE works with
+    fn mac_to_cmce_chanalloc(chan_alloc: &ChanAllocElement, usage: Option<u8>) -> CmceChanAllocReq {
+        CmceChanAllocReq {
+            usage,
+            carrier: None,
+            timeslots: chan_alloc.ts_assigned,
+            alloc_type: chan_alloc.alloc_type,
+            ul_dl_assigned: chan_alloc.ul_dl_assigned,
         }
     }
 
@@ -171,7 +249,7 @@ impl UmacMs {
 
     // message pos: start of broadcast frame
     // Will NOT advance pos but pass to underlying function
-    fn rx_broadcast(&self, queue: &mut MessageQueue, message: &mut SapMsg) {
+    fn rx_broadcast(&mut self, queue: &mut MessageQueue, message: &mut SapMsg) {
         tracing::trace!("rx_broadcast");
 
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
@@ -187,13 +265,13 @@ impl UmacMs {
                 self.rx_broadcast_sysinfo(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_broadcast: {:?}", bcast_type);
             }
         }
     }
 
     // Parses the sysinfo pdu
-    fn rx_broadcast_sysinfo(&self, queue: &mut MessageQueue, message: &mut SapMsg) {
+    fn rx_broadcast_sysinfo(&mut self, queue: &mut MessageQueue, message: &mut SapMsg) {
         tracing::trace!("rx_broadcast_sysinfo");
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
@@ -213,14 +291,17 @@ impl UmacMs {
 
         // TODO FIXME adopt sysinfo info into global state
 
-        if pdu.hyperframe_number.is_some() && pdu.hyperframe_number.unwrap() != message.dltime.h {
+        if let Some(access_def) = &pdu.default_access_code {
+            self.ul_sched.set_ra_params(access_def.wt, access_def.nu);
+        }
+
+        if let Some(dltime) = self.dltime
+            && let Some(h) = pdu.hyperframe_number
+            && h != dltime.h
+        {
             // Send message to Phy about new hyperframe number
-            let t = TdmaTime {
-                t: message.dltime.t,
-                f: message.dltime.f,
-                m: message.dltime.m,
-                h: pdu.hyperframe_number.unwrap(),
-            };
+            let t = TdmaTime { h, ..dltime };
+            self.dltime = Some(t);
             let m = SapMsg {
                 sap: Sap::TmvSap,
                 src: self.self_component,
@@ -231,8 +312,8 @@ impl UmacMs {
                     ..Default::default()
                 }),
             };
-            tracing::info!("rx_broadcast_sysinfo: Updated TdmaTime: {:?} -> {:?}", message.dltime, t);
-            queue.push_back(m);
+            tracing::info!("rx_broadcast_sysinfo: Updated TdmaTime: {:?} -> {:?}", dltime, t);
+            queue.push_prio(m, MessagePrio::Immediate);
         }
 
         let tlsdu = BitBuffer::from_bitbuffer_pos(&prim.pdu);
@@ -270,10 +351,6 @@ impl UmacMs {
             }
         };
 
-        if pdu.encryption_mode > 0 {
-            unimplemented_log!("rx_mac_resource: Encryption mode > 0, not implemented");
-        }
-
         // Compute len
         let mut pdu_len_bits = {
             match pdu.length_ind {
@@ -283,7 +360,6 @@ impl UmacMs {
                 }
                 0b111110 => {
                     // Second half slot stolen in STCH
-                    unimplemented_log!("rx_mac_resource: SECOND HALF SLOT STOLEN IN STCH but signal not implemented");
                     prim.pdu.get_len()
                 }
                 0b111111 => {
@@ -328,31 +404,48 @@ impl UmacMs {
             prim.pdu.dump_bin_full(true)
         );
 
-        if pdu.addr.is_none() {
+        if pdu.length_ind == 0b111110 {
+            // The LMAC needs to know before it decodes the second half, whoever this PDU is addressed to
+            tracing::debug!("rx_mac_resource: STCH 2nd half stolen");
+            self.signal_lmac_second_half_stolen(queue);
+        }
+
+        let Some(addr) = pdu.addr else {
             // TODO not sure if there is scenarios in which we want to pass a null pdu to the LLC
             // tracing::warn!("rx_mac_resource: Null PDU not passed to LLC");
             return;
+        };
+
+        // Random access acknowledgement and slot grants for our uplink transfer
+        if !self.monitor
+            && addr.ssi == self.issi
+            && let Some(dltime) = self.dltime
+        {
+            if pdu.random_access_flag {
+                self.ul_sched.rx_random_access_ack(dltime);
+            }
+            if let Some(grant) = &pdu.slot_granting_element {
+                self.ul_sched.rx_slot_grant(dltime, grant);
+            }
         }
 
-        // Decrypt if needed
-        if pdu.encryption_mode > 0 {
+        if !self.is_own_address(addr.ssi) {
+            tracing::trace!("rx_mac_resource: not addressed to us: {}", addr);
+        } else if pdu.encryption_mode > 0 {
+            // Decrypt if needed
             unimplemented_log!("rx_mac_resource: Encryption mode > 0");
-            return;
             // TODO:
             // Check if key available
             // generate keystream
             // apply keystream to data
             // re-decode chanalloc
             // continue
-        }
-
-        tracing::debug!("rx_mac_resource: {}", prim.pdu.dump_bin_full(true));
-        if pdu.length_ind == 0b111111 {
+        } else if pdu.length_ind == 0b111111 {
+            tracing::debug!("rx_mac_resource: {}", prim.pdu.dump_bin_full(true));
             // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, message.dltime, pdu.addr.unwrap(), None);
-        } else if pdu.length_ind == 0b111110 {
-            tracing::warn!("rx_mac_resource: SECOND HALF SLOT STOLEN IN STCH but not implemented");
+            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
         } else {
+            tracing::debug!("rx_mac_resource: {}", prim.pdu.dump_bin_full(true));
             // Pass directly to LLC
             let sdu = {
                 if pdu.length_ind == 0 {
@@ -377,7 +470,7 @@ impl UmacMs {
 
                     msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                         pdu: sdu,
-                        main_address: pdu.addr.unwrap(),
+                        main_address: addr,
                         scrambling_code: prim.scrambling_code,
                         endpoint_id: 0,        // TODO FIXME
                         new_endpoint_id: None, // TODO FIXME
@@ -385,14 +478,17 @@ impl UmacMs {
                         air_interface_encryption: pdu.encryption_mode as Todo,
                         chan_change_response_req: false,
                         chan_change_handle: None,
-                        chan_info: None,
+                        chan_info: pdu
+                            .chan_alloc_element
+                            .as_ref()
+                            .map(|c| Self::mac_to_cmce_chanalloc(c, pdu.usage_marker)),
                     }),
                 };
                 queue.push_back(m);
             } else {
                 // Either this is a null pdu or we are at the end of the block
                 // For now, we don't deliver this. However, important data may need to be signalled upwards
-                tracing::info!("rx_mac_resource: empty PDU not passed to LLC");
+                tracing::debug!("rx_mac_resource: empty PDU not passed to LLC");
             }
         }
 
@@ -436,7 +532,7 @@ impl UmacMs {
         tracing::debug!("rx_mac_frag: pdu_len_bits: {} fill_bits: {}", pdu_len_bits, num_fill_bits);
 
         // Decrypt if needed
-        if let Some(_aie_info) = self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info {
+        if self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info.is_some() {
             // TODO FIXME implement
             unimplemented_log!("rx_mac_frag: Encryption not supported");
             return;
@@ -482,13 +578,6 @@ impl UmacMs {
         prim.pdu.set_raw_end(prim.pdu.get_raw_start() + pdu_len_bits);
         tracing::debug!("rx_mac_end: pdu_len_bits: {} fill_bits: {}", pdu_len_bits, num_fill_bits);
 
-        // Decrypt if needed
-        if let Some(_aie_info) = self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info {
-            // TODO FIXME implement
-            unimplemented!("rx_mac_end: Encryption not supported");
-            // TODO FIXME Also re-parse chanalloc
-        }
-
         // Insert into defragmenter
         self.defrag.insert_last(&mut prim.pdu, message.dltime);
 
@@ -499,6 +588,13 @@ impl UmacMs {
             return;
         };
 
+        // Decrypt if needed
+        if defragbuf.aie_info.is_some() {
+            // TODO FIXME implement, also re-parse chanalloc
+            unimplemented_log!("rx_mac_end: Encryption not supported");
+            return;
+        }
+
         // Pass block directly to LLC
         tracing::debug!("rx_mac_end: sdu: {:?}", defragbuf.buffer.dump_bin());
 
@@ -518,7 +614,7 @@ impl UmacMs {
                 air_interface_encryption: 0, // TODO FIXME implement
                 chan_change_response_req: false,
                 chan_change_handle: None,
-                chan_info: None,
+                chan_info: pdu.chan_alloc_element.as_ref().map(|c| Self::mac_to_cmce_chanalloc(c, None)),
             }),
         };
         queue.push_back(m);
@@ -535,7 +631,7 @@ impl UmacMs {
         let SapMsgInner::TmvUnitdataInd(_prim) = &mut message.msg else {
             panic!()
         };
-        unimplemented!("rx_usignal");
+        unimplemented_log!("rx_usignal");
     }
 
     fn rx_supp(&self, _queue: &mut MessageQueue, message: &mut SapMsg) {
@@ -546,10 +642,10 @@ impl UmacMs {
         };
         // Check we're indeed on the right channel (Clause 21.4.1 Table 21.48)
         assert!(prim.logical_channel != LogicalChannel::Stch && prim.logical_channel != LogicalChannel::SchHd);
-        unimplemented!("rx_supp");
+        unimplemented_log!("rx_supp");
     }
 
-    pub fn rx_tmv_aach(&self, queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tmv_aach(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tmv_aach");
 
         // TODO FIXME, more extensively store and process AACH state in both LMAC and UMAC
@@ -571,9 +667,10 @@ impl UmacMs {
                 }
             };
 
+            self.ul_sched.set_ul_usage(message.dltime.t, pdu.ul_usage);
             pdu.dl_usage.is_traffic()
         } else {
-            let _pdu = match AccessAssignFr18::from_bitbuf(&mut prim.pdu) {
+            let pdu = match AccessAssignFr18::from_bitbuf(&mut prim.pdu) {
                 Ok(pdu) => {
                     tracing::debug!("<- {:?}", pdu);
                     pdu
@@ -584,6 +681,7 @@ impl UmacMs {
                 }
             };
 
+            self.ul_sched.set_ul_usage(message.dltime.t, pdu.ul_usage);
             false
         };
 
@@ -601,14 +699,14 @@ impl UmacMs {
         queue.push_prio(m, MessagePrio::Immediate);
     }
 
-    pub fn rx_tmv_bsch(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tmv_bsch(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tmv_bsch");
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
 
         // Unpack and validate with expected state
-        let _pdu = match MacSync::from_bitbuf(&mut prim.pdu) {
+        let pdu = match MacSync::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -619,100 +717,218 @@ impl UmacMs {
             }
         };
 
-        unimplemented_log!("can't update global state");
-
-        // let netinfo_changed = {
-        //     let config_r = self.config.read();
-        //         mac_sync.system_code != config_r.la_info.system_code
-        //             || mac_sync.sharing_mode != config_r.la_info.sharing_mode
-        //             || mac_sync.ts_reserved_frames != config_r.la_info.ts_reserved_frames
-        //             || mac_sync.u_plane_dtx != config_r.la_info.u_plane_dtx
-        //             || mac_sync.frame_18_ext != config_r.la_info.frame_18_ext
-        // };
-        // // tracing::trace!("rx_tmv_bsch: netinfo_changed: {}, cc_changed: {}, tdma_time_changed: {}", netinfo_changed, cc_changed, tdma_time_changed);
-
-        // // Update global state if needed
-        // if netinfo_changed  {
-        //     let mut config_w = self.config.write();
-        //     config_w.la_info.system_code = mac_sync.system_code;
-        //     // config_w.netinfo.colour_code = mac_sync.colour_code;
-        //     config_w.la_info.sharing_mode = mac_sync.sharing_mode;
-        //     config_w.la_info.ts_reserved_frames = mac_sync.ts_reserved_frames;
-        //     config_w.la_info.u_plane_dtx = mac_sync.u_plane_dtx;
-        //     config_w.la_info.frame_18_ext = mac_sync.frame_18_ext;
-        //     tracing::info!("rx_tmv_bsch: Updated TetraGlobalState: {:?}", mac_sync);
-        // }
-
-        // if mac_sync.time.t != message.t_submit.t || mac_sync.time.f != message.t_submit.f || mac_sync.time.m != message.t_submit.m {
-        //     // TODO warn/bail when really not in line with expected time
-        //     let t = TdmaTime{
-        //         t: mac_sync.time.t,
-        //         f: mac_sync.time.f,
-        //         m: mac_sync.time.m,
-        //         h: message.t_submit.h,
-        //     };
-        //     let m = SapMsg {
-        //         sap: Sap::TmvSap,
-        //         src: self.self_component,
-        //         dest: TetraComponent::Lmac,
-        //         t_submit: message.t_submit,
-        //         msg: SapMsgInner::TmvConfigureReq(
-        //             TmvConfigureReq{
-        //                 time: Some(t),
-        //                 .. Default::default()
-        //             }
-        //         )
-        //     };
-        //     tracing::info!("rx_tmv_bsch: Updated TdmaTime: {:?} -> {:?}", message.t_submit, t);
-        //     queue.push_back(m);
-        // }
-
-        // if Some(mac_sync.colour_code) != self.cc {
-        //     // Update scrambling code
-        //     tracing::info!("rx_tmv_bsch: Updated colour code: {:?} -> {:?}", self.cc, mac_sync.colour_code);
-        //     self.cc = Some(mac_sync.colour_code);
-        //     self.update_scrambing_and_submit_to_lmac(queue, &message);
-
-        // } else {
-        //     tracing::trace!("rx_tmv_bsch: Colour code unchanged: {:?}", self.cc);
-        // }
-
-        // // Take ownership of prim and sdu
-        // let prim = if let SapMsgInner::TmvUnitdataInd(inner) = message.msg {
-        //     inner
-        // } else {
-        //     panic!();
-        // };
-        // let tlsdu = prim.pdu;
-
-        // let m = SapMsg {
-        //     sap: Sap::TlmbSap,
-        //     src: TetraComponent::Umac,
-        //     dest: TetraComponent::Mle,
-        //     t_submit: message.t_submit,
-
-        //     msg: SapMsgInner::TlmbSyncInd(
-        //         TlmbSyncInd {
-        //             endpoint_id: 0,
-        //             tl_sdu: tlsdu
-        //         }
-        //     )
-        // };
-        // tracing::info!("rx_tmv_bsch: {:?}", m.msg);
-        // queue.push_back(m);
+        // Adopt the cell's time. The hyperframe number is not in the SYNC, it follows from the SYSINFO.
+        let t = TdmaTime {
+            h: self.dltime.map(|t| t.h).unwrap_or_default(),
+            ..pdu.time
+        };
+        if self.dltime != Some(t) {
+            tracing::info!("rx_tmv_bsch: Updated TdmaTime: {:?} -> {:?}", self.dltime, t);
+            self.dltime = Some(t);
+            let m = SapMsg {
+                sap: Sap::TmvSap,
+                src: self.self_component,
+                dest: TetraEntity::Lmac,
+                dltime: t,
+                msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+                    time: Some(t),
+                    ..Default::default()
+                }),
+            };
+            queue.push_prio(m, MessagePrio::Immediate);
+        }
+
+        if Some(pdu.colour_code) != self.cc {
+            // Update scrambling code
+            tracing::info!("rx_tmv_bsch: Updated colour code: {:?} -> {:?}", self.cc, pdu.colour_code);
+            self.cc = Some(pdu.colour_code);
+            self.update_scrambing_and_submit_to_lmac(queue, t);
+        }
+
+        // Pass the remainder, the D-MLE-SYNC, to the MLE. It provides us with the MCC and MNC
+        // through the TLMC-CONFIGURE, which we need before the rest of this burst can be descrambled.
+        let tl_sdu = BitBuffer::from_bitbuffer_pos(&prim.pdu);
+        let m = SapMsg {
+            sap: Sap::TlmbSap,
+            src: TetraEntity::Umac,
+            dest: TetraEntity::Mle,
+            dltime: t,
+            msg: SapMsgInner::TlmbSyncInd(TlmbSyncInd { endpoint_id: 0, tl_sdu }),
+        };
+        queue.push_prio(m, MessagePrio::Immediate);
     }
 
-    fn rx_tma_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tma_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tma_prim");
-        unimplemented!();
+        match message.msg {
+            SapMsgInner::TmaUnitdataReq(prim) => {
+                if self.dltime.is_none() {
+                    tracing::warn!("rx_tma_prim: not synchronized to a cell, discarding TM-SDU");
+                    if let Some(tx_reporter) = prim.tx_reporter {
+                        tx_reporter.mark_discarded();
+                    }
+                    return;
+                }
+                self.ul_sched.enqueue(prim.pdu, prim.tx_reporter);
+            }
+            _ => {
+                unimplemented_log!("rx_tma_prim: {:?}", message.msg);
+            }
+        }
     }
 
-    fn rx_tlmb_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlmb_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlmb_prim");
-        unimplemented!();
+        unimplemented_log!("rx_tlmb_prim: {:?}", message.msg);
+    }
+
+    fn rx_tmd_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tmd_prim");
+        let dltime = message.dltime;
+        match message.msg {
+            // Speech from the user, sent on our uplink circuit
+            SapMsgInner::TmdCircuitDataReq(prim) => {
+                if self.circuits.is_active(Direction::Ul, prim.ts) {
+                    self.ul_traffic.push_back(prim.data);
+                } else {
+                    tracing::trace!("rx_tmd_prim: dropping UL voice on inactive circuit ts={}", prim.ts);
+                }
+            }
+            // Received speech, passed to the user
+            SapMsgInner::TmdCircuitDataInd(prim) => {
+                if self.circuits.is_active(Direction::Dl, prim.ts) {
+                    let m = SapMsg {
+                        sap: Sap::TmdSap,
+                        src: TetraEntity::Umac,
+                        dest: TetraEntity::User,
+                        dltime,
+                        msg: SapMsgInner::TmdCircuitDataInd(TmdCircuitDataInd {
+                            ts: prim.ts,
+                            data: prim.data,
+                        }),
+                    };
+                    queue.push_back(m);
+                } else {
+                    tracing::trace!("rx_tmd_prim: no active DL circuit on ts={}, dropping voice", prim.ts);
+                }
+            }
+            _ => panic!(),
+        }
+    }
+
+    fn rx_control(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_control");
+        let SapMsgInner::CmceCallControl(prim) = message.msg else {
+            panic!()
+        };
+
+        match prim {
+            CallControl::Open(circuit) => {
+                let dirs = match circuit.direction {
+                    Direction::Both => vec![Direction::Dl, Direction::Ul],
+                    d @ (Direction::Dl | Direction::Ul) => vec![d],
+                    Direction::None => vec![],
+                };
+                for d in dirs {
+                    tracing::debug!("rx_control: open {:?} circuit on ts {}", d, circuit.ts);
+                    if d == Direction::Ul {
+                        self.ul_traffic.clear();
+                    }
+                    self.circuits.create_circuit(
+                        d,
+                        Circuit {
+                            direction: d,
+                            ..circuit.clone()
+                        },
+                    );
+                }
+            }
+            CallControl::Close(dir, ts) => {
+                let dirs = match dir {
+                    Direction::Both => vec![Direction::Dl, Direction::Ul],
+                    d @ (Direction::Dl | Direction::Ul) => vec![d],
+                    Direction::None => vec![],
+                };
+                for d in dirs {
+                    tracing::debug!("rx_control: close {:?} circuit on ts {}", d, ts);
+                    if d == Direction::Ul {
+                        self.ul_traffic.clear();
+                    }
+                    self.circuits.close_circuit(d, ts);
+                }
+            }
+            _ => {
+                tracing::trace!("rx_control: ignoring {:?}", prim);
+            }
+        }
+    }
+
+    /// Build the uplink slot with the same number as the current downlink slot, and pass it to the LMAC
+    fn submit_ul_slot(&mut self, queue: &mut MessageQueue, ul_time: TdmaTime) {
+        let Some(scrambling_code) = self.scrambling_code else {
+            return;
+        };
+
+        let (ul_phy_chan, blk1, blk2) = if self.circuits.is_active(Direction::Ul, ul_time.t) && ul_time.f != 18 {
+            // Our traffic channel, frame 18 is reserved for control
+            let mac_block = match self.ul_traffic.pop_front() {
+                Some(block) => {
+                    let mut buf = BitBuffer::from_vec(block);
+                    buf.set_raw_end(buf.get_raw_start() + TCH_S_CAP);
+                    buf
+                }
+                None => BitBuffer::new(TCH_S_CAP),
+            };
+            let blk = TmvUnitdataReq {
+                mac_block,
+                logical_channel: LogicalChannel::TchS,
+                scrambling_code,
+            };
+            (PhysicalChannel::Tp, Some(blk), None)
+        } else if let Some((logical_channel, mac_block)) = self.ul_sched.build_ul_block(ul_time) {
+            let blk = TmvUnitdataReq {
+                mac_block,
+                logical_channel,
+                scrambling_code,
+            };
+            (PhysicalChannel::Cp, Some(blk), None)
+        } else {
+            return;
+        };
+
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Umac,
+            dest: TetraEntity::Lmac,
+            dltime: ul_time,
+            msg: SapMsgInner::TmvUnitdataReq(TmvUnitdataReqSlot {
+                ts: ul_time,
+                carrier: 0,
+                ul_phy_chan,
+                blk1,
+                blk2,
+                bbk: None,
+            }),
+        };
+        queue.push_back(m);
     }
 
-    fn update_scrambing_and_submit_to_lmac(&mut self, queue: &mut MessageQueue, message: &SapMsg) {
+    fn signal_lmac_second_half_stolen(&mut self, queue: &mut MessageQueue) {
+        // Must be Immediate priority so the LMAC sees it before processing Block2
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: self.self_component,
+            dest: TetraEntity::Lmac,
+            dltime: self.dltime.unwrap_or_default(), // Control message so don't care
+            msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+                blk2_stolen: Some(true),
+                ..Default::default()
+            }),
+        };
+        queue.push_prio(m, MessagePrio::Immediate);
+    }
+
+    fn update_scrambing_and_submit_to_lmac(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
         if let (Some(mcc), Some(mnc), Some(cc)) = (self.mcc, self.mnc, self.cc) {
             self.scrambling_code = Some((((cc as u32) | ((mnc as u32) << 6) | ((mcc as u32) << 20)) << 2) | 3);
 
@@ -728,13 +944,14 @@ impl UmacMs {
                 sap: Sap::TmvSap,
                 src: self.self_component,
                 dest: TetraEntity::Lmac,
-                dltime: message.dltime,
+                dltime,
                 msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
                     scrambling_code: self.scrambling_code,
                     ..Default::default()
                 }),
             };
-            queue.push_back(m);
+            // The remaining blocks of the burst being processed need it
+            queue.push_prio(m, MessagePrio::Immediate);
         }
     }
 
@@ -751,7 +968,7 @@ impl UmacMs {
             self.mnc = Some(valid_addresses.mnc);
 
             // Attempt to update scrambling code (if cc is also known)
-            self.update_scrambing_and_submit_to_lmac(queue, &message);
+            self.update_scrambing_and_submit_to_lmac(queue, message.dltime);
         } else {
             tracing::warn!("rx_tlmc_configure_req: No valid addresses provided");
         }
@@ -764,7 +981,7 @@ impl UmacMs {
                 self.rx_tlmc_configure_req(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_tlmc_prim: {:?}", message.msg);
             }
         }
     }
@@ -775,6 +992,10 @@ impl TetraEntityTrait for UmacMs {
         TetraEntity::Umac
     }
 
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
@@ -796,9 +1017,36 @@ impl TetraEntityTrait for UmacMs {
                 self.rx_tlmc_prim(queue, message);
             }
 
+            Sap::TmdSap => {
+                self.rx_tmd_prim(queue, message);
+            }
+
+            Sap::Control => {
+                self.rx_control(queue, message);
+            }
+
             _ => {
                 panic!()
             }
         }
     }
+
+    fn tick_start(&mut self, _queue: &mut MessageQueue, _ts: TdmaTime) {
+        // Our time follows the serving cell, independent of the router's tick counter
+        if let Some(dltime) = self.dltime {
+            let dltime = dltime.add_timeslots(1);
+            self.dltime = Some(dltime);
+            self.defrag.age_buffers(dltime);
+        }
+    }
+
+    fn tick_end(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
+        // All downlink blocks of this slot have been processed, including any grants
+        if !self.monitor
+            && let Some(dltime) = self.dltime
+        {
+            self.submit_ul_slot(queue, dltime);
+        }
+        false
+    }
 }
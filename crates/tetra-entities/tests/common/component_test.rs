@@ -16,8 +16,15 @@ use tetra_entities::umac::umac_bs::UmacBs;
 
 // MS imports
 use tetra_entities::lmac::lmac_ms::LmacMs;
+use tetra_entities::mle::mle_ms::MleMs;
+use tetra_entities::mm::mm_ms::MmMs;
 use tetra_entities::umac::umac_ms::UmacMs;
 
+// Monitor imports
+use tetra_entities::lmac::lmac_mon::LmacMon;
+use tetra_entities::monitor::EventLog;
+use tetra_entities::umac::umac_mon::UmacMon;
+
 use crate::common::default_stack;
 
 use super::sink::Sink;
@@ -40,7 +47,7 @@ impl ComponentTest {
         match stack_mode {
             StackMode::Bs => default_stack::default_test_config_bs(),
             StackMode::Ms => default_stack::default_test_config_ms(),
-            _ => panic!("Unsupported stack mode"),
+            StackMode::Mon => default_stack::default_test_config_mon(),
         }
     }
 
@@ -83,8 +90,8 @@ impl ComponentTest {
             StackMode::Ms => {
                 self.create_components_ms(components);
             }
-            _ => {
-                panic!("Only BS stack mode is supported in ComponentTest");
+            StackMode::Mon => {
+                self.create_components_mon(components);
             }
         }
 
@@ -149,9 +156,13 @@ impl ComponentTest {
                     self.router.register_entity(Box::new(llc));
                 }
                 TetraEntity::Mle => {
-                    let mle = MleBs::new(self.config.clone());
+                    let mle = MleMs::new(self.config.clone());
                     self.router.register_entity(Box::new(mle));
                 }
+                TetraEntity::Mm => {
+                    let mm = MmMs::new(self.config.clone());
+                    self.router.register_entity(Box::new(mm));
+                }
                 TetraEntity::Cmce => {
                     let cmce = CmceMs::new(self.config.clone());
                     self.router.register_entity(Box::new(cmce));
@@ -163,6 +174,24 @@ impl ComponentTest {
         }
     }
 
+    fn create_components_mon(&mut self, components: Vec<TetraEntity>) {
+        for component in components.iter() {
+            match component {
+                TetraEntity::Lmac => {
+                    let lmac = LmacMon::new(self.config.clone());
+                    self.router.register_entity(Box::new(lmac));
+                }
+                TetraEntity::Umac => {
+                    let umac = UmacMon::new(self.config.clone(), EventLog::stdout());
+                    self.router.register_entity(Box::new(umac));
+                }
+                _ => {
+                    panic!("Component not implemented: {:?}", component);
+                }
+            }
+        }
+    }
+
     fn create_sinks(&mut self, sinks: Vec<TetraEntity>) {
         // Setup any sinks
         for sink in sinks.iter() {
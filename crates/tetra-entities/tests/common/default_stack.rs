@@ -1,4 +1,4 @@
-use tetra_config::bluestation::{CfgCellInfo, CfgNetInfo, CfgPhyIo, PhyBackend, StackConfig, StackMode};
+use tetra_config::bluestation::{CfgCellInfo, CfgMonitor, CfgMs, CfgNetInfo, CfgPhyIo, PhyBackend, StackConfig, StackMode};
 use tetra_core::{freqs::FreqInfo, ranges::SortedDisjointSsiRanges};
 
 /// Creates a default config for testing. It can still be modified as needed
@@ -13,10 +13,21 @@ pub fn default_test_config_bs() -> StackConfig {
     StackConfig {
         stack_mode: StackMode::Bs,
         debug_log: None,
+        alarm_log: None,
+        subscriber_db: None,
         phy_io,
         net: net_info,
         cell: cell_info,
         brew: None,
+        auth: None,
+        encryption: None,
+        sndcp: None,
+        ms: None,
+        monitor: None,
+        neighbour_cells: vec![],
+        backhaul: None,
+        registration_store: None,
+        sds_store: None,
     }
 }
 
@@ -28,6 +39,7 @@ pub fn default_phy_io() -> CfgPhyIo {
         ul_input_file: None,
         dl_input_file: None,
         soapysdr: None,
+        file: None,
     }
 }
 
@@ -45,6 +57,7 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
         duplex_spacing_id: freq_info.duplex_spacing_id,
         custom_duplex_spacing: None,
         reverse_operation: freq_info.reverse_operation,
+        secondary_carriers: vec![],
         neighbor_cell_broadcast: 0,
         late_entry_supported: false,
         subscriber_class: 65535, // All subscriber classes allowed
@@ -65,6 +78,10 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
         u_plane_dtx: false,
         frame_18_ext: false,
         local_ssi_ranges: SortedDisjointSsiRanges::from_vec_ssirange(vec![]),
+        slow_reselect_threshold: 10,
+        slow_reselect_hysteresis: 10,
+        fast_reselect_threshold: 6,
+        fast_reselect_hysteresis: 6,
         timezone: None,
     }
 }
@@ -72,5 +89,20 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
 pub fn default_test_config_ms() -> StackConfig {
     let mut config = default_test_config_bs();
     config.stack_mode = StackMode::Ms;
+    config.ms = Some(CfgMs {
+        issi: 7015011,
+        groups: vec![91],
+        console: false,
+    });
+    config
+}
+
+pub fn default_test_config_mon() -> StackConfig {
+    let mut config = default_test_config_bs();
+    config.stack_mode = StackMode::Mon;
+    config.monitor = Some(CfgMonitor {
+        uplink: true,
+        event_log: None,
+    });
     config
 }
@@ -2,6 +2,7 @@
 
 pub mod component_test;
 pub mod default_stack;
+pub mod shared_log;
 pub mod sink;
 
 pub use component_test::ComponentTest;
@@ -0,0 +1,360 @@
+mod common;
+
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use tetra_config::bluestation::{CfgBackhaul, CfgBackhaulPeer, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::backhaul::entity::BackhaulEntity;
+use tetra_entities::backhaul::protocol::SiteMessage;
+use tetra_entities::backhaul::worker::{BackhaulCommand, BackhaulEvent};
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmd::TmdCircuitDataInd;
+
+use crate::common::ComponentTest;
+
+const SITE_A: u16 = 1;
+const SITE_B: u16 = 2;
+
+/// One BlueStation site, with CMCE and the backhaul entity. Its backhaul link is the pair of channels
+/// the worker thread would otherwise own.
+struct Site {
+    site_id: u16,
+    dltime: TdmaTime,
+    test: ComponentTest,
+    event_sender: Sender<BackhaulEvent>,
+    command_receiver: Receiver<BackhaulCommand>,
+}
+
+impl Site {
+    fn new(site_id: u16, peer_id: u16, dltime: TdmaTime) -> Self {
+        let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+        config.backhaul = Some(CfgBackhaul {
+            site_id,
+            listen: "127.0.0.1:0".parse().unwrap(),
+            peers: vec![CfgBackhaulPeer {
+                site_id: peer_id,
+                address: "127.0.0.1:7700".parse().unwrap(),
+            }],
+            reconnect_delay: Duration::from_secs(1),
+            shared_secret: "test".to_string(),
+        });
+        let mut test = ComponentTest::from_config(config, Some(dltime));
+        test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Mm]);
+
+        let (event_sender, event_receiver) = unbounded();
+        let (command_sender, command_receiver) = unbounded();
+        let entity = BackhaulEntity::with_link(test.get_shared_config(), event_receiver, command_sender);
+        test.register_entity(entity);
+
+        // Our link to the peer is up
+        event_sender.send(BackhaulEvent::SiteConnected { site_id: peer_id }).unwrap();
+
+        Self {
+            site_id,
+            dltime,
+            test,
+            event_sender,
+            command_receiver,
+        }
+    }
+
+    /// Register a local MS attached to a group, as MM would
+    fn attach_local(&mut self, issi: u32, gssi: u32) {
+        {
+            let mut state = self.test.config.state_write();
+            state.subscribers.register(issi);
+            state.subscribers.affiliate(issi, gssi);
+        }
+        self.test.submit_message(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Cmce,
+            dltime: self.dltime,
+            msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+                issi,
+                groups: vec![gssi],
+                action: BrewSubscriberAction::Affiliate,
+            }),
+        });
+    }
+
+    fn submit_to_backhaul(&mut self, src: TetraEntity, sap: Sap, msg: SapMsgInner) {
+        self.test.submit_message(SapMsg {
+            sap,
+            src,
+            dest: TetraEntity::Backhaul,
+            dltime: self.dltime,
+            msg,
+        });
+    }
+}
+
+/// Deliver everything `from` sent over the backhaul to `to`
+fn shuttle(from: &Site, to: &Site) -> usize {
+    let mut count = 0;
+    while let Ok(command) = from.command_receiver.try_recv() {
+        if let BackhaulCommand::Send { site_id, msg } = command {
+            assert_eq!(site_id, to.site_id);
+            to.event_sender
+                .send(BackhaulEvent::Received {
+                    site_id: from.site_id,
+                    msg,
+                })
+                .unwrap();
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Run both sites for a tick and exchange their backhaul traffic
+fn run_sites(a: &mut Site, b: &mut Site, ticks: usize) {
+    for _ in 0..ticks {
+        a.test.run_stack(Some(1));
+        b.test.run_stack(Some(1));
+        shuttle(a, b);
+        shuttle(b, a);
+    }
+}
+
+fn build_u_sds_data_msg(dltime: TdmaTime, source_issi: u32, dest_ssi: u32, payload: u16) -> SapMsg {
+    let u_sds = USdsData {
+        area_selection: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_short_number_address: None,
+        called_party_ssi: Some(dest_ssi as u64),
+        called_party_extension: None,
+        user_defined_data: SdsUserData::Type1(payload),
+        external_subscriber_number: None,
+        dm_ms_address: None,
+    };
+
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_sds.to_bitbuf(&mut sdu).expect("Failed to serialize U-SDS-DATA");
+    sdu.seek(0);
+
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(source_issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_alloc: None,
+        }),
+    }
+}
+
+/// Downlink signalling to a group (D-SETUP, D-SDS-DATA, ...)
+fn count_group_pdus(msgs: &[SapMsg], gssi: u32) -> usize {
+    msgs.iter()
+        .filter(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) => prim.main_address.ssi == gssi && prim.main_address.ssi_type == SsiType::Gssi,
+            _ => false,
+        })
+        .count()
+}
+
+#[test]
+fn test_backhaul_group_call_to_site_with_members() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut a = Site::new(SITE_A, SITE_B, dltime);
+    let mut b = Site::new(SITE_B, SITE_A, dltime);
+
+    // An MS at site B listens to group 91, site A learns this from the Hello
+    b.attach_local(2002, 91);
+    run_sites(&mut a, &mut b, 2);
+    assert_eq!(a.test.config.state_read().subscribers.remote_sites_with_group(91), vec![SITE_B]);
+    a.test.dump_sinks();
+    b.test.dump_sinks();
+
+    // A local MS at site A takes the floor on ts 2
+    a.submit_to_backhaul(
+        TetraEntity::Cmce,
+        Sap::Control,
+        SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+            call_id: 1,
+            source_issi: 1001,
+            dest_gssi: 91,
+            ts: 2,
+            priority: 0,
+        }),
+    );
+    run_sites(&mut a, &mut b, 2);
+
+    // Site B sets up the call for its members
+    let msgs = b.test.dump_sinks();
+    assert!(count_group_pdus(&msgs, 91) > 0, "Expected D-SETUP to GSSI 91 at site B");
+
+    // Voice from site A is played out at site B
+    for _ in 0..8 {
+        a.submit_to_backhaul(
+            TetraEntity::Umac,
+            Sap::TmdSap,
+            SapMsgInner::TmdCircuitDataInd(TmdCircuitDataInd {
+                ts: 2,
+                data: vec![1u8; 274],
+            }),
+        );
+        run_sites(&mut a, &mut b, 4);
+    }
+    let msgs = b.test.dump_sinks();
+    let frames: Vec<&SapMsg> = msgs
+        .iter()
+        .filter(|m| m.dest == TetraEntity::Umac && matches!(m.msg, SapMsgInner::TmdCircuitDataReq(_)))
+        .collect();
+    assert!(!frames.is_empty(), "Expected voice frames played out at site B");
+    for frame in frames {
+        let SapMsgInner::TmdCircuitDataReq(prim) = &frame.msg else {
+            unreachable!()
+        };
+        assert_eq!(prim.data.len(), 35);
+    }
+
+    // Floor released at site A ends the call at site B
+    a.submit_to_backhaul(
+        TetraEntity::Cmce,
+        Sap::Control,
+        SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id: 1, ts: 2 }),
+    );
+    run_sites(&mut a, &mut b, 2);
+    let msgs = b.test.dump_sinks();
+    assert!(
+        msgs.iter()
+            .any(|m| m.dest == TetraEntity::Umac && matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::FloorReleased { .. }))),
+        "Expected site B to enter hangtime"
+    );
+}
+
+#[test]
+fn test_backhaul_group_call_not_forwarded_without_members() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut a = Site::new(SITE_A, SITE_B, dltime);
+    let mut b = Site::new(SITE_B, SITE_A, dltime);
+    run_sites(&mut a, &mut b, 2);
+
+    a.submit_to_backhaul(
+        TetraEntity::Cmce,
+        Sap::Control,
+        SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+            call_id: 1,
+            source_issi: 1001,
+            dest_gssi: 91,
+            ts: 2,
+            priority: 0,
+        }),
+    );
+    a.test.run_stack(Some(1));
+    assert_eq!(shuttle(&a, &b), 0, "No site has members of group 91");
+}
+
+#[test]
+fn test_backhaul_sds_to_other_site() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut a = Site::new(SITE_A, SITE_B, dltime);
+    let mut b = Site::new(SITE_B, SITE_A, dltime);
+
+    b.test.config.state_write().subscribers.register(2002);
+    run_sites(&mut a, &mut b, 2);
+    a.test.dump_sinks();
+    b.test.dump_sinks();
+
+    // U-SDS-DATA at site A for the MS at site B
+    let msg = build_u_sds_data_msg(dltime, 1001, 2002, 0xBEEF);
+    a.test.submit_message(msg);
+    run_sites(&mut a, &mut b, 8);
+
+    let a_msgs = a.test.dump_sinks();
+    assert!(
+        !a_msgs.iter().any(|m| m.dest == TetraEntity::Mle),
+        "SDS for a remote MS should not be delivered locally"
+    );
+    let b_msgs = b.test.dump_sinks();
+    let delivered = b_msgs.iter().any(|m| match &m.msg {
+        SapMsgInner::LcmcMleUnitdataReq(prim) => prim.main_address.ssi == 2002 && prim.main_address.ssi_type == SsiType::Issi,
+        _ => false,
+    });
+    assert!(delivered, "Expected D-SDS-DATA to ISSI 2002 at site B");
+}
+
+#[test]
+fn test_backhaul_registration_migrates() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut a = Site::new(SITE_A, SITE_B, dltime);
+    let mut b = Site::new(SITE_B, SITE_A, dltime);
+
+    // The MS is registered at site A
+    a.test.config.state_write().subscribers.register(3003);
+    run_sites(&mut a, &mut b, 2);
+    assert_eq!(b.test.config.state_read().subscribers.remote_site(3003), Some(SITE_A));
+    a.test.dump_sinks();
+
+    // It roams to site B and registers there
+    b.test.config.state_write().subscribers.register(3003);
+    b.submit_to_backhaul(
+        TetraEntity::Mm,
+        Sap::Control,
+        SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: 3003,
+            groups: Vec::new(),
+            action: BrewSubscriberAction::Register,
+        }),
+    );
+    run_sites(&mut a, &mut b, 2);
+
+    assert_eq!(b.test.config.state_read().subscribers.remote_site(3003), None);
+    assert_eq!(a.test.config.state_read().subscribers.remote_site(3003), Some(SITE_B));
+
+    // Site A is told to drop its stale registration
+    let msgs = a.test.dump_sinks();
+    let dropped = msgs.iter().any(|m| {
+        m.dest == TetraEntity::Mm
+            && matches!(
+                &m.msg,
+                SapMsgInner::MmSubscriberUpdate(update)
+                    if update.issi == 3003 && update.action == BrewSubscriberAction::Deregister
+            )
+    });
+    assert!(dropped, "Expected Deregister to MM at site A");
+}
+
+#[test]
+fn test_backhaul_ignores_unknown_site() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut b = Site::new(SITE_B, SITE_A, dltime);
+
+    // A site that is not one of our peers claims a registration
+    b.event_sender
+        .send(BackhaulEvent::Received {
+            site_id: 9,
+            msg: SiteMessage::Register { issi: 3003 },
+        })
+        .unwrap();
+    b.test.run_stack(Some(2));
+
+    assert_eq!(b.test.config.state_read().subscribers.remote_site(3003), None);
+}
@@ -1,12 +1,30 @@
 mod common;
 
-use tetra_config::bluestation::StackMode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tetra_config::bluestation::{CfgBrew, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TxState, debug};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TimeslotOwner, TxState, debug};
+use tetra_pdus::cmce::enums::call_status::CallStatus;
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
 use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::enums::transmission_grant::TransmissionGrant;
 use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::fields::dtmf::Dtmf;
+use tetra_pdus::cmce::pdus::d_call_proceeding::DCallProceeding;
+use tetra_pdus::cmce::pdus::d_info::DInfo;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_alert::UAlert;
+use tetra_pdus::cmce::pdus::u_connect::UConnect;
+use tetra_pdus::cmce::pdus::u_disconnect::UDisconnect;
+use tetra_pdus::cmce::pdus::u_info::UInfo;
 use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::cmce::pdus::u_tx_ceased::UTxCeased;
+use tetra_pdus::cmce::pdus::u_tx_demand::UTxDemand;
 use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::{CallControl, IndividualCallSignal};
 use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
 use tetra_saps::control::enums::communication_type::CommunicationType;
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
@@ -16,6 +34,7 @@ use crate::common::ComponentTest;
 
 const TEST_GSSI: u32 = 91;
 const TEST_ISSI: u32 = 1000001;
+const TEST_ISSI_CALLED: u32 = 1000002;
 
 /// Helper: register a subscriber on a GSSI so CMCE accepts calls for that group.
 fn register_subscriber(test: &mut ComponentTest, dltime: TdmaTime, issi: u32, gssi: u32) {
@@ -51,22 +70,36 @@ fn register_subscriber(test: &mut ComponentTest, dltime: TdmaTime, issi: u32, gs
 
 /// Helper: build a U-SETUP SAP message for a group call.
 fn build_u_setup_msg(dltime: TdmaTime, calling_issi: u32, dest_gssi: u32) -> SapMsg {
+    build_u_setup_msg_with(dltime, calling_issi, dest_gssi, CommunicationType::P2Mp, false, false, 0)
+}
+
+/// Helper: build a U-SETUP SAP message with the given communication type, hook method, simplex/duplex selection
+/// and call priority.
+fn build_u_setup_msg_with(
+    dltime: TdmaTime,
+    calling_issi: u32,
+    called_ssi: u32,
+    communication_type: CommunicationType,
+    hook_method_selection: bool,
+    simplex_duplex_selection: bool,
+    call_priority: u8,
+) -> SapMsg {
     let u_setup = USetup {
         area_selection: 0,
-        hook_method_selection: false,
-        simplex_duplex_selection: false,
+        hook_method_selection,
+        simplex_duplex_selection,
         basic_service_information: BasicServiceInformation {
             circuit_mode_type: CircuitModeType::TchS,
             encryption_flag: false,
-            communication_type: CommunicationType::P2Mp,
+            communication_type,
             slots_per_frame: None,
             speech_service: Some(0),
         },
         request_to_transmit_send_data: false,
-        call_priority: 0,
+        call_priority,
         clir_control: 0,
         called_party_type_identifier: PartyTypeIdentifier::Ssi,
-        called_party_ssi: Some(dest_gssi as u64),
+        called_party_ssi: Some(called_ssi as u64),
         called_party_short_number_address: None,
         called_party_extension: None,
         external_subscriber_number: None,
@@ -78,7 +111,11 @@ fn build_u_setup_msg(dltime: TdmaTime, calling_issi: u32, dest_gssi: u32) -> Sap
     let mut sdu = BitBuffer::new_autoexpand(80);
     u_setup.to_bitbuf(&mut sdu).expect("Failed to serialize USetup");
     sdu.seek(0);
+    build_ul_msg(dltime, calling_issi, sdu)
+}
 
+/// Helper: wrap a serialized uplink CMCE PDU from the given ISSI into an LCMC indication.
+fn build_ul_msg(dltime: TdmaTime, issi: u32, sdu: BitBuffer) -> SapMsg {
     SapMsg {
         sap: Sap::LcmcSap,
         src: TetraEntity::Mle,
@@ -89,13 +126,39 @@ fn build_u_setup_msg(dltime: TdmaTime, calling_issi: u32, dest_gssi: u32) -> Sap
             handle: 1,
             endpoint_id: 1,
             link_id: 1,
-            received_tetra_address: TetraAddress::new(calling_issi, SsiType::Issi),
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
             chan_change_resp_req: false,
             chan_change_handle: None,
+            chan_alloc: None,
         }),
     }
 }
 
+/// List (PDU type, destination SSI, has channel allocation) for all downlink CMCE PDUs in the sink output.
+fn dl_pdus(msgs: &[SapMsg]) -> Vec<(CmcePduTypeDl, u32, bool)> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if msg.dest == TetraEntity::Mle => {
+                let pdu_type = CmcePduTypeDl::try_from(prim.sdu.peek_bits(5)?).ok()?;
+                Some((pdu_type, prim.main_address.ssi, prim.chan_alloc.is_some()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Extract the D-SETUP sent to the given SSI from the sink output.
+fn find_d_setup(msgs: &[SapMsg], ssi: u32) -> Option<DSetup> {
+    msgs.iter().find_map(|msg| match &msg.msg {
+        SapMsgInner::LcmcMleUnitdataReq(prim)
+            if prim.main_address.ssi == ssi && prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DSetup.into_raw()) =>
+        {
+            DSetup::from_bitbuf(&mut prim.sdu.clone()).ok()
+        }
+        _ => None,
+    })
+}
+
 /// Extract tx_reporters from D-SETUP messages in the sink output.
 /// D-SETUPs are identified as LcmcMleUnitdataReq with a chan_alloc that has a usage field.
 fn extract_d_setup_reporters(msgs: &mut Vec<SapMsg>) -> Vec<tetra_core::TxReporter> {
@@ -196,3 +259,1311 @@ fn test_dsetup_late_entry_throttle() {
         "Each re-sent D-SETUP should carry a fresh tx_reporter"
     );
 }
+
+/// An MS that attaches to a group with a call in progress gets the call's D-SETUP right away,
+/// rather than at the next periodic late-entry re-send.
+#[test]
+fn test_late_entry_dsetup_on_affiliation() {
+    debug::setup_logging_verbose();
+
+    const OTHER_GSSI: u32 = 92;
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    let call_id = find_d_setup(&test.dump_sinks(), TEST_GSSI).unwrap().call_identifier;
+
+    // Get past the initial D-SETUP repeats, well before the next late-entry re-send
+    test.run_stack(Some(8));
+    test.dump_sinks();
+
+    // Attaching to a group without a call sends nothing
+    register_subscriber(&mut test, dltime, TEST_ISSI_CALLED, OTHER_GSSI);
+    let affiliate = |gssi: u32| SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: TEST_ISSI_CALLED,
+            groups: vec![gssi],
+            action: BrewSubscriberAction::Affiliate,
+        }),
+    };
+    test.submit_message(affiliate(OTHER_GSSI + 1));
+    test.run_stack(Some(1));
+    assert_eq!(count_d_setups(&test.dump_sinks()), 0);
+
+    // Attaching to the group in the call triggers an immediate D-SETUP for the ongoing call
+    test.submit_message(affiliate(TEST_GSSI));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(count_d_setups(&msgs), 1);
+    let d_setup = find_d_setup(&msgs, TEST_GSSI).expect("Expected late entry D-SETUP");
+    assert_eq!(d_setup.call_identifier, call_id);
+    assert_eq!(d_setup.transmission_grant, TransmissionGrant::GrantedToOtherUser);
+    assert_eq!(d_setup.calling_party_address_ssi, Some(TEST_ISSI));
+}
+
+/// Set up an individual call between two registered ISSIs and return its call identifier
+/// together with the sink output after the D-SETUP page.
+fn setup_individual_call(test: &mut ComponentTest, dltime: TdmaTime, hook_method: bool, duplex: bool) -> (u16, Vec<SapMsg>) {
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    test.config.state_write().subscribers.register(TEST_ISSI_CALLED);
+
+    let u_setup_msg = build_u_setup_msg_with(dltime, TEST_ISSI, TEST_ISSI_CALLED, CommunicationType::P2p, hook_method, duplex, 0);
+    test.submit_message(u_setup_msg);
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    let d_setup = find_d_setup(&msgs, TEST_ISSI_CALLED).expect("Expected D-SETUP paging the called ISSI");
+    assert_eq!(d_setup.calling_party_address_ssi, Some(TEST_ISSI));
+    assert_eq!(d_setup.hook_method_selection, hook_method);
+    assert_eq!(d_setup.simplex_duplex_selection, duplex);
+    (d_setup.call_identifier, msgs)
+}
+
+/// Individual call with hook signalling: U-SETUP -> D-CALL-PROCEEDING + D-SETUP, U-ALERT -> D-ALERT,
+/// U-CONNECT -> D-CONNECT + D-CONNECT ACKNOWLEDGE with channel allocation, U-DISCONNECT -> D-RELEASE to both.
+#[test]
+fn test_individual_call_hook_signalling() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+
+    let (call_id, msgs) = setup_individual_call(&mut test, dltime, true, false);
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DCallProceeding, TEST_ISSI, false)));
+    assert!(pdus.contains(&(CmcePduTypeDl::DSetup, TEST_ISSI_CALLED, false)));
+    assert!(
+        !msgs
+            .iter()
+            .any(|m| matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::Open(_)))),
+        "No traffic channel should be opened before the called party answers"
+    );
+
+    // Called MS alerts its user
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UAlert {
+        call_identifier: call_id,
+        reserved: true,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    let pdus = dl_pdus(&test.dump_sinks());
+    assert_eq!(pdus, vec![(CmcePduTypeDl::DAlert, TEST_ISSI, false)]);
+
+    // Called user answers
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UConnect {
+        call_identifier: call_id,
+        hook_method_selection: true,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DConnect, TEST_ISSI, true)));
+    assert!(pdus.contains(&(CmcePduTypeDl::DConnectAcknowledge, TEST_ISSI_CALLED, true)));
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::Open(_)))),
+        "Traffic channel should be opened once the called party connects"
+    );
+
+    // No late-entry D-SETUPs for individual calls
+    test.run_stack(Some(720));
+    let pdus = dl_pdus(&test.dump_sinks());
+    assert!(!pdus.iter().any(|(t, _, _)| *t == CmcePduTypeDl::DSetup));
+
+    // Calling party hangs up
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UDisconnect {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DRelease, TEST_ISSI, false)));
+    assert!(pdus.contains(&(CmcePduTypeDl::DRelease, TEST_ISSI_CALLED, false)));
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::Close(_, _))))
+    );
+}
+
+/// Individual call to an ISSI that is not registered is released towards the caller right away.
+#[test]
+fn test_individual_call_called_party_not_reachable() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test.config.state_write().subscribers.register(TEST_ISSI);
+
+    let u_setup_msg = build_u_setup_msg_with(dltime, TEST_ISSI, TEST_ISSI_CALLED, CommunicationType::P2p, false, false, 0);
+    test.submit_message(u_setup_msg);
+    test.run_stack(Some(1));
+
+    let pdus = dl_pdus(&test.dump_sinks());
+    assert_eq!(pdus, vec![(CmcePduTypeDl::DRelease, TEST_ISSI, false)]);
+}
+
+/// Duplex individual call: each party gets its own traffic circuit, cross-connected in UMAC,
+/// and both circuits are released when the call ends.
+#[test]
+fn test_individual_call_duplex() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+
+    let (call_id, _) = setup_individual_call(&mut test, dltime, true, true);
+
+    // Called user answers, keeping duplex
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UConnect {
+        call_identifier: call_id,
+        hook_method_selection: true,
+        simplex_duplex_selection: true,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DConnect, TEST_ISSI, true)));
+    assert!(pdus.contains(&(CmcePduTypeDl::DConnectAcknowledge, TEST_ISSI_CALLED, true)));
+
+    let opened: Vec<_> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) => Some((circuit.ts, circuit.peer_ts)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(opened.len(), 2, "Expected one circuit per party");
+    let (ts_a, peer_a) = opened[0];
+    let (ts_b, peer_b) = opened[1];
+    assert_ne!(ts_a, ts_b);
+    assert_eq!(peer_a, Some(ts_b));
+    assert_eq!(peer_b, Some(ts_a));
+
+    // Floor control does not apply to duplex calls
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UTxDemand {
+        call_identifier: call_id,
+        tx_demand_priority: 0,
+        encryption_control: false,
+        reserved: false,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    assert!(dl_pdus(&test.dump_sinks()).is_empty());
+
+    // Called party hangs up, both circuits are closed
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UDisconnect {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let closed = msgs
+        .iter()
+        .filter(|m| matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::Close(_, _))))
+        .count();
+    assert_eq!(closed, 2);
+    assert!(dl_pdus(&msgs).contains(&(CmcePduTypeDl::DRelease, TEST_ISSI, false)));
+}
+
+/// With the main carrier full, a group call is placed on the first timeslot of the secondary carrier.
+/// The D-SETUP channel allocation then names that carrier, and the circuit is opened on channel 5.
+#[test]
+fn test_group_call_on_secondary_carrier() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.secondary_carriers = vec![1525];
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    {
+        let shared = test.get_shared_config();
+        let mut state = shared.state_write();
+        for ts in 2..=4 {
+            state.timeslot_alloc.reserve(TimeslotOwner::Brew, ts).unwrap();
+        }
+    }
+
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    let opened: Vec<u8> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) => Some(circuit.ts),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(opened, vec![5]);
+
+    let chan_alloc = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DSetup.into_raw()) => {
+                prim.chan_alloc.clone()
+            }
+            _ => None,
+        })
+        .expect("Expected D-SETUP with channel allocation");
+    assert_eq!(chan_alloc.carrier, Some(1));
+    assert_eq!(chan_alloc.timeslots, [true, false, false, false]);
+    assert_eq!(chan_alloc.first_channel(), Some(5));
+}
+
+/// Reserve the given traffic timeslots for another owner, so CMCE cannot allocate them.
+fn occupy_timeslots(test: &ComponentTest, timeslots: &[u8]) {
+    let shared = test.get_shared_config();
+    let mut state = shared.state_write();
+    for &ts in timeslots {
+        state.timeslot_alloc.reserve(TimeslotOwner::Brew, ts).unwrap();
+    }
+}
+
+/// Timeslots of all circuits opened in the sink output
+fn opened_timeslots(msgs: &[SapMsg]) -> Vec<u8> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) => Some(circuit.ts),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A group call for which no timeslot is free is queued with D-CALL-PROCEEDING (call queued),
+/// and set up as soon as a timeslot is released.
+#[test]
+fn test_group_call_queued_until_timeslot_free() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    occupy_timeslots(&test, &[2, 3, 4]);
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(opened_timeslots(&msgs).is_empty());
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DCallProceeding, TEST_ISSI, false)]);
+    let proceeding = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) => DCallProceeding::from_bitbuf(&mut prim.sdu.clone()).ok(),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(proceeding.call_status, Some(CallStatus::Callqueued));
+
+    // Still queued while no timeslot frees up
+    test.run_stack(Some(8));
+    assert!(opened_timeslots(&test.dump_sinks()).is_empty());
+
+    test.get_shared_config()
+        .state_write()
+        .timeslot_alloc
+        .release(TimeslotOwner::Brew, 3)
+        .unwrap();
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(opened_timeslots(&msgs), vec![3]);
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DConnect, TEST_ISSI, true)));
+    assert!(pdus.contains(&(CmcePduTypeDl::DSetup, TEST_GSSI, true)));
+    assert!(!pdus.iter().any(|(pdu_type, _, _)| *pdu_type == CmcePduTypeDl::DCallProceeding));
+    let d_setup = find_d_setup(&msgs, TEST_GSSI).unwrap();
+    assert_eq!(d_setup.call_identifier, proceeding.call_identifier);
+}
+
+/// A queued call is released when its group loses all listeners before a timeslot frees up.
+#[test]
+fn test_queued_group_call_released_without_listeners() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    occupy_timeslots(&test, &[2, 3, 4]);
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    assert_eq!(
+        dl_pdus(&test.dump_sinks()),
+        vec![(CmcePduTypeDl::DCallProceeding, TEST_ISSI, false)]
+    );
+
+    // The only member detaches from the group while the call waits
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: TEST_ISSI,
+            groups: vec![TEST_GSSI],
+            action: BrewSubscriberAction::Deaffiliate,
+        }),
+    });
+    test.run_stack(Some(2));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DRelease, TEST_ISSI, false)]);
+    let release = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) => DRelease::from_bitbuf(&mut prim.sdu.clone()).ok(),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(release.disconnect_cause, DisconnectCause::CalledPartyNotReachable);
+
+    // Nothing is set up once a timeslot frees up
+    test.get_shared_config()
+        .state_write()
+        .timeslot_alloc
+        .release(TimeslotOwner::Brew, 3)
+        .unwrap();
+    test.run_stack(Some(1));
+    assert!(opened_timeslots(&test.dump_sinks()).is_empty());
+}
+
+/// A queued call is dropped when its calling MS disconnects before a timeslot frees up.
+#[test]
+fn test_queued_group_call_cancelled() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    occupy_timeslots(&test, &[2, 3, 4]);
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let call_id = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) => DCallProceeding::from_bitbuf(&mut prim.sdu.clone()).ok(),
+            _ => None,
+        })
+        .unwrap()
+        .call_identifier;
+
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UDisconnect {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+    assert_eq!(dl_pdus(&test.dump_sinks()), vec![(CmcePduTypeDl::DRelease, TEST_ISSI, false)]);
+
+    test.get_shared_config()
+        .state_write()
+        .timeslot_alloc
+        .release(TimeslotOwner::Brew, 2)
+        .unwrap();
+    test.run_stack(Some(4));
+    assert!(opened_timeslots(&test.dump_sinks()).is_empty());
+}
+
+/// An emergency call takes over the timeslot of a normal priority call when the cell is full:
+/// the pre-empted group gets D-TX INTERRUPT on its traffic channel and D-RELEASE, and the
+/// emergency call is set up on the freed timeslot. A non pre-emptive call in the same situation is queued.
+#[test]
+fn test_emergency_call_preempts_lower_priority() {
+    debug::setup_logging_verbose();
+
+    const OTHER_GSSI: u32 = 92;
+    const EMERGENCY_PRIORITY: u8 = 15;
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    occupy_timeslots(&test, &[3, 4]);
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+    register_subscriber(&mut test, dltime, TEST_ISSI_CALLED, OTHER_GSSI);
+
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(opened_timeslots(&msgs), vec![2]);
+
+    // A second normal priority call has to wait
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI_CALLED,
+        OTHER_GSSI,
+        CommunicationType::P2Mp,
+        false,
+        false,
+        5,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(opened_timeslots(&msgs).is_empty());
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DCallProceeding, TEST_ISSI_CALLED, false)]);
+
+    // The emergency call pre-empts the first call
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI_CALLED,
+        OTHER_GSSI,
+        CommunicationType::P2Mp,
+        false,
+        false,
+        EMERGENCY_PRIORITY,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DTxInterrupt, TEST_GSSI, true)));
+    assert!(pdus.contains(&(CmcePduTypeDl::DRelease, TEST_GSSI, false)));
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::Close(_, 2))))
+    );
+    assert_eq!(opened_timeslots(&msgs), vec![2]);
+    let d_setup = find_d_setup(&msgs, OTHER_GSSI).unwrap();
+    assert_eq!(d_setup.call_priority, EMERGENCY_PRIORITY);
+
+    // Interrupt comes before the release, which comes before the new call
+    let pos = |wanted: CmcePduTypeDl, ssi: u32| pdus.iter().position(|&(t, s, _)| t == wanted && s == ssi).unwrap();
+    assert!(pos(CmcePduTypeDl::DTxInterrupt, TEST_GSSI) < pos(CmcePduTypeDl::DRelease, TEST_GSSI));
+    assert!(pos(CmcePduTypeDl::DRelease, TEST_GSSI) < pos(CmcePduTypeDl::DSetup, OTHER_GSSI));
+
+    // Another emergency call cannot pre-empt a call of equal priority and is queued instead
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        TEST_GSSI,
+        CommunicationType::P2Mp,
+        false,
+        false,
+        EMERGENCY_PRIORITY,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(opened_timeslots(&msgs).is_empty());
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DCallProceeding, TEST_ISSI, false)]);
+}
+
+/// Brew FloorGranted notifications (dest gssi, priority) among the given messages
+fn brew_floor_grants(msgs: &[SapMsg]) -> Vec<(u32, u8)> {
+    msgs.iter()
+        .filter(|m| m.dest == TetraEntity::Brew)
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::FloorGranted { dest_gssi, priority, .. }) => Some((*dest_gssi, *priority)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// An emergency group call is set up even without local group members, reaches Brew although the group
+/// is not whitelisted, and is written to the alarm log. A normal call to the same group stays local.
+#[test]
+fn test_emergency_call_bypasses_brew_whitelist_and_raises_alarm() {
+    debug::setup_logging_verbose();
+
+    const OTHER_GSSI: u32 = 92;
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let alarm_path = std::env::temp_dir().join(format!("test_cmce_bs_alarms_{}.jsonl", nanos));
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.alarm_log = Some(alarm_path.to_string_lossy().into_owned());
+    config.brew = Some(CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: false,
+        feature_packet_data_enabled: false,
+        whitelisted_ssis: Some(vec![TEST_GSSI]),
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+    test.config.state_write().network_connected = true;
+
+    // Nobody is affiliated to the called group, the emergency call still goes ahead over Brew
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        OTHER_GSSI,
+        CommunicationType::P2Mp,
+        false,
+        false,
+        15,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let d_setup = find_d_setup(&msgs, OTHER_GSSI).expect("Emergency call must be set up");
+    assert_eq!(d_setup.call_priority, 15);
+    assert_eq!(brew_floor_grants(&msgs), vec![(OTHER_GSSI, 15)]);
+
+    let alarms = std::fs::read_to_string(&alarm_path).unwrap();
+    let _ = std::fs::remove_file(&alarm_path);
+    let lines: Vec<&str> = alarms.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let alarm: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(alarm["alarm"], "emergency_call");
+    assert_eq!(alarm["calling_issi"], TEST_ISSI);
+    assert_eq!(alarm["called_ssi"], OTHER_GSSI);
+    assert_eq!(alarm["individual"], false);
+
+    // A normal priority call on a non-whitelisted group is not bridged to Brew
+    register_subscriber(&mut test, dltime, TEST_ISSI_CALLED, OTHER_GSSI + 1);
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI_CALLED, OTHER_GSSI + 1));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(find_d_setup(&msgs, OTHER_GSSI + 1).is_some());
+    assert!(brew_floor_grants(&msgs).is_empty());
+}
+
+/// An emergency group call without local listeners is rejected when no network bridge could carry it
+#[test]
+fn test_emergency_call_without_listeners_or_bridge() {
+    debug::setup_logging_verbose();
+
+    const OTHER_GSSI: u32 = 92;
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        OTHER_GSSI,
+        CommunicationType::P2Mp,
+        false,
+        false,
+        15,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(find_d_setup(&msgs, OTHER_GSSI).is_none());
+    assert!(opened_timeslots(&msgs).is_empty());
+}
+
+/// An emergency individual call takes over the call the called party is in, and reaches Brew subscribers
+/// whose ISSI is not otherwise routed there
+#[test]
+fn test_emergency_individual_call_bypasses_restrictions() {
+    debug::setup_logging_verbose();
+
+    const THIRD_ISSI: u32 = 1000003;
+    /// Not a 7 digit ISSI, so not routed to TetraPack
+    const DISPATCHER_ISSI: u32 = 262993;
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(CfgBrew {
+        host: "core.tetrapack.online".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: false,
+        feature_packet_data_enabled: false,
+        whitelisted_ssis: None,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test.config.state_write().network_connected = true;
+    let (call_id, _) = setup_individual_call(&mut test, dltime, false, false);
+    test.config.state_write().subscribers.register(THIRD_ISSI);
+
+    // A normal priority call to the busy party is rejected
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        THIRD_ISSI,
+        TEST_ISSI_CALLED,
+        CommunicationType::P2p,
+        false,
+        false,
+        0,
+    ));
+    test.run_stack(Some(1));
+    assert_eq!(dl_pdus(&test.dump_sinks()), vec![(CmcePduTypeDl::DRelease, THIRD_ISSI, false)]);
+
+    // An emergency call releases the ongoing call and pages the called party
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        THIRD_ISSI,
+        TEST_ISSI_CALLED,
+        CommunicationType::P2p,
+        false,
+        false,
+        15,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let pdus = dl_pdus(&msgs);
+    assert!(pdus.contains(&(CmcePduTypeDl::DRelease, TEST_ISSI, false)));
+    let d_setup = find_d_setup(&msgs, TEST_ISSI_CALLED).expect("Emergency call must page the called party");
+    assert_ne!(d_setup.call_identifier, call_id);
+    assert_eq!(d_setup.calling_party_address_ssi, Some(THIRD_ISSI));
+
+    // A normal priority call to an ISSI TetraPack does not route is not reachable, an emergency call is bridged
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        DISPATCHER_ISSI,
+        CommunicationType::P2p,
+        false,
+        false,
+        0,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DRelease, TEST_ISSI, false)]);
+    assert!(brew_individual_signals(&msgs).is_empty());
+
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        DISPATCHER_ISSI,
+        CommunicationType::P2p,
+        false,
+        false,
+        15,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(matches!(
+        brew_individual_signals(&msgs).as_slice(),
+        [IndividualCallSignal::Setup {
+            called_issi: DISPATCHER_ISSI,
+            priority: 15,
+            ..
+        }]
+    ));
+}
+
+/// A rejected emergency set-up does not raise the alarm, only one that is accepted does
+#[test]
+fn test_emergency_alarm_only_for_accepted_call() {
+    debug::setup_logging_verbose();
+
+    const UNKNOWN_ISSI: u32 = 1234;
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let alarm_path = std::env::temp_dir().join(format!("test_cmce_bs_alarms_rejected_{}.jsonl", nanos));
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.alarm_log = Some(alarm_path.to_string_lossy().into_owned());
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    test.config.state_write().subscribers.register(TEST_ISSI_CALLED);
+
+    // The called ISSI is not registered, so the call is rejected
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        UNKNOWN_ISSI,
+        CommunicationType::P2p,
+        false,
+        false,
+        15,
+    ));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+    let alarms = std::fs::read_to_string(&alarm_path).unwrap_or_default();
+    assert!(alarms.is_empty(), "rejected call raised an alarm: {}", alarms);
+
+    // The same call to a registered ISSI is accepted
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        TEST_ISSI_CALLED,
+        CommunicationType::P2p,
+        false,
+        false,
+        15,
+    ));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+    let alarms = std::fs::read_to_string(&alarm_path).unwrap();
+    let _ = std::fs::remove_file(&alarm_path);
+    let lines: Vec<&str> = alarms.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let alarm: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(alarm["called_ssi"], TEST_ISSI_CALLED);
+    assert_eq!(alarm["individual"], true);
+}
+
+/// Remote ISSI of a network subscriber reached over Brew
+const TEST_ISSI_NETWORK: u32 = 2620001;
+
+/// Stack connected to a Brew server, so individual calls to unknown ISSIs are bridged
+fn brew_bridge_test_stack(dltime: TdmaTime) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: false,
+        feature_packet_data_enabled: false,
+        whitelisted_ssis: None,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test.config.state_write().network_connected = true;
+    test
+}
+
+/// Individual call signalling sent to Brew among the given messages
+fn brew_individual_signals(msgs: &[SapMsg]) -> Vec<IndividualCallSignal> {
+    msgs.iter()
+        .filter(|m| m.dest == TetraEntity::Brew)
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall { signal, .. }) => Some(signal.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Individual call signalling from the network party, as relayed by Brew
+fn build_network_signal(dltime: TdmaTime, brew_uuid: uuid::Uuid, signal: IndividualCallSignal) -> SapMsg {
+    SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Brew,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall { brew_uuid, signal }),
+    }
+}
+
+fn build_u_tx_demand(call_id: u16) -> BitBuffer {
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UTxDemand {
+        call_identifier: call_id,
+        tx_demand_priority: 0,
+        encryption_control: false,
+        reserved: false,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+/// A network subscriber calls a local MS over Brew: the local MS is paged, alerting and answering are relayed
+/// to the network, the floor changes hands between both sides and the network releases the call.
+#[test]
+fn test_individual_call_from_network() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = brew_bridge_test_stack(dltime);
+    test.config.state_write().subscribers.register(TEST_ISSI_CALLED);
+    let brew_uuid = uuid::Uuid::new_v4();
+
+    // Calls to ISSIs that are not registered here are rejected towards the network
+    let setup = |called_issi| IndividualCallSignal::Setup {
+        calling_issi: TEST_ISSI_NETWORK,
+        called_issi,
+        priority: 0,
+        hook_method: true,
+    };
+    test.submit_message(build_network_signal(dltime, uuid::Uuid::new_v4(), setup(TEST_ISSI)));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(dl_pdus(&msgs).is_empty());
+    assert_eq!(
+        brew_individual_signals(&msgs),
+        vec![IndividualCallSignal::Release {
+            cause: DisconnectCause::CalledPartyNotReachable.into_raw() as u8
+        }]
+    );
+
+    test.submit_message(build_network_signal(dltime, brew_uuid, setup(TEST_ISSI_CALLED)));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let d_setup = find_d_setup(&msgs, TEST_ISSI_CALLED).expect("Expected D-SETUP paging the called ISSI");
+    assert_eq!(d_setup.calling_party_address_ssi, Some(TEST_ISSI_NETWORK));
+    assert!(!d_setup.simplex_duplex_selection);
+    assert_eq!(brew_individual_signals(&msgs), vec![IndividualCallSignal::Accept]);
+    let call_id = d_setup.call_identifier;
+
+    // Alerting is relayed to the network instead of a D-ALERT
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UAlert {
+        call_identifier: call_id,
+        reserved: true,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(dl_pdus(&msgs).is_empty());
+    assert_eq!(brew_individual_signals(&msgs), vec![IndividualCallSignal::Alert]);
+
+    // The local MS answers: it is through-connected, Brew is given the traffic channel
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UConnect {
+        call_identifier: call_id,
+        hook_method_selection: true,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DConnectAcknowledge, TEST_ISSI_CALLED, true)]);
+    assert_eq!(brew_individual_signals(&msgs), vec![IndividualCallSignal::Connect]);
+    let opened_ts = opened_timeslots(&msgs);
+    assert_eq!(opened_ts.len(), 1);
+    assert!(msgs.iter().any(|m| matches!(
+        m.msg,
+        SapMsgInner::CmceCallControl(CallControl::NetworkCallReady { brew_uuid: uuid, call_id: id, ts, .. })
+            if uuid == brew_uuid && id == call_id && ts == opened_ts[0]
+    )));
+
+    // The network caller holds the floor, the local MS can't take it over
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, build_u_tx_demand(call_id)));
+    test.run_stack(Some(1));
+    assert!(dl_pdus(&test.dump_sinks()).is_empty());
+
+    // Once the network caller stops talking, the local MS gets the floor and the network is told.
+    // Floor control PDUs are stolen from the traffic channel, so they carry its channel allocation.
+    test.submit_message(build_network_signal(dltime, brew_uuid, IndividualCallSignal::TxCeased));
+    test.run_stack(Some(1));
+    assert_eq!(
+        dl_pdus(&test.dump_sinks()),
+        vec![(CmcePduTypeDl::DTxCeased, TEST_ISSI_CALLED, true)]
+    );
+
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, build_u_tx_demand(call_id)));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DTxGranted, TEST_ISSI_CALLED, true)]);
+    assert_eq!(
+        brew_individual_signals(&msgs),
+        vec![IndividualCallSignal::TxGranted { issi: TEST_ISSI_CALLED }]
+    );
+
+    // A network floor grant while the local MS transmits is ignored
+    let grant = IndividualCallSignal::TxGranted { issi: TEST_ISSI_NETWORK };
+    test.submit_message(build_network_signal(dltime, brew_uuid, grant));
+    test.run_stack(Some(1));
+    assert!(dl_pdus(&test.dump_sinks()).is_empty());
+
+    // The network releases the call, the local MS is released with the network's cause
+    let release = IndividualCallSignal::Release {
+        cause: DisconnectCause::UserRequestedDisconnection.into_raw() as u8,
+    };
+    test.submit_message(build_network_signal(dltime, brew_uuid, release));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DRelease, TEST_ISSI_CALLED, false)]);
+    assert!(brew_individual_signals(&msgs).is_empty());
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(m.msg, SapMsgInner::CmceCallControl(CallControl::Close(_, _))))
+    );
+}
+
+/// A local MS calls a network subscriber: the call is set up over Brew as a simplex call, the caller is
+/// alerted and through-connected when the network answers, and a local disconnect releases the network side.
+#[test]
+fn test_individual_call_to_network() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = brew_bridge_test_stack(dltime);
+    test.config.state_write().subscribers.register(TEST_ISSI);
+
+    // Without a Brew connection, the called party is not reachable
+    test.config.state_write().network_connected = false;
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        TEST_ISSI_NETWORK,
+        CommunicationType::P2p,
+        true,
+        true,
+        0,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DRelease, TEST_ISSI, false)]);
+    assert!(brew_individual_signals(&msgs).is_empty());
+
+    // Connected: the duplex request is downgraded to simplex and set up over Brew
+    test.config.state_write().network_connected = true;
+    test.submit_message(build_u_setup_msg_with(
+        dltime,
+        TEST_ISSI,
+        TEST_ISSI_NETWORK,
+        CommunicationType::P2p,
+        true,
+        true,
+        0,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DCallProceeding, TEST_ISSI, false)]);
+    assert_eq!(
+        brew_individual_signals(&msgs),
+        vec![IndividualCallSignal::Setup {
+            calling_issi: TEST_ISSI,
+            called_issi: TEST_ISSI_NETWORK,
+            priority: 0,
+            hook_method: true,
+        }]
+    );
+    let brew_uuid = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::NetworkIndividualCall { brew_uuid, .. }) => Some(*brew_uuid),
+            _ => None,
+        })
+        .unwrap();
+    let call_id = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DCallProceeding.into_raw()) => {
+                DCallProceeding::from_bitbuf(&mut prim.sdu.clone()).ok()
+            }
+            _ => None,
+        })
+        .map(|pdu| {
+            assert!(!pdu.simplex_duplex_selection);
+            pdu.call_identifier
+        })
+        .unwrap();
+
+    // Network side alerts, the caller gets D-ALERT
+    test.submit_message(build_network_signal(dltime, brew_uuid, IndividualCallSignal::Accept));
+    test.submit_message(build_network_signal(dltime, brew_uuid, IndividualCallSignal::Alert));
+    test.run_stack(Some(1));
+    assert_eq!(dl_pdus(&test.dump_sinks()), vec![(CmcePduTypeDl::DAlert, TEST_ISSI, false)]);
+
+    // Network side answers: the caller is through-connected with the floor
+    test.submit_message(build_network_signal(dltime, brew_uuid, IndividualCallSignal::Connect));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DConnect, TEST_ISSI, true)]);
+    assert_eq!(
+        brew_individual_signals(&msgs),
+        vec![
+            IndividualCallSignal::ConnectConfirm,
+            IndividualCallSignal::TxGranted { issi: TEST_ISSI }
+        ]
+    );
+    assert_eq!(opened_timeslots(&msgs).len(), 1);
+
+    // Caller stops talking, the network is told. D-TX CEASED goes out on the traffic channel.
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UTxCeased {
+        call_identifier: call_id,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DTxCeased, TEST_ISSI, true)]);
+    assert_eq!(brew_individual_signals(&msgs), vec![IndividualCallSignal::TxCeased]);
+
+    // Network subscriber takes the floor
+    let grant = IndividualCallSignal::TxGranted { issi: TEST_ISSI_NETWORK };
+    test.submit_message(build_network_signal(dltime, brew_uuid, grant));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DTxGranted, TEST_ISSI, true)]);
+    assert!(brew_individual_signals(&msgs).is_empty());
+
+    // Caller hangs up, the network side is released
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UDisconnect {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DRelease, TEST_ISSI, false)]);
+    assert_eq!(
+        brew_individual_signals(&msgs),
+        vec![IndividualCallSignal::Release {
+            cause: DisconnectCause::UserRequestedDisconnection.into_raw() as u8
+        }]
+    );
+}
+
+fn build_u_info_dtmf(call_id: u16, digits: &str) -> BitBuffer {
+    let mut sdu = BitBuffer::new_autoexpand(48);
+    UInfo {
+        call_identifier: call_id,
+        poll_response: false,
+        modify: None,
+        dtmf: Some(Dtmf::tone_start(digits).unwrap().to_type3()),
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+/// DTMF digits of the D-INFO PDUs sent to the given SSI
+fn d_info_digits(msgs: &[SapMsg], ssi: u32) -> Vec<String> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim)
+                if prim.main_address.ssi == ssi && prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DInfo.into_raw()) =>
+            {
+                let pdu = DInfo::from_bitbuf(&mut prim.sdu.clone()).ok()?;
+                Some(Dtmf::from_type3(pdu.dtmf.as_ref()?).ok()?.digits)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Brew DTMF notifications (call id, digits) among the given messages
+fn brew_dtmf(msgs: &[SapMsg]) -> Vec<(u16, String)> {
+    msgs.iter()
+        .filter(|m| m.dest == TetraEntity::Brew)
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::CallDtmf { call_id, digits }) => Some((*call_id, digits.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// DTMF dialled by the speaker of a group call is sent to the group with D-INFO
+#[test]
+fn test_group_call_dtmf() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    register_subscriber(&mut test, dltime, TEST_ISSI, TEST_GSSI);
+    register_subscriber(&mut test, dltime, TEST_ISSI_CALLED, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(dltime, TEST_ISSI, TEST_GSSI));
+    test.run_stack(Some(1));
+    let call_id = find_d_setup(&test.dump_sinks(), TEST_GSSI).unwrap().call_identifier;
+
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, build_u_info_dtmf(call_id, "42")));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DInfo, TEST_GSSI, true)]);
+    assert_eq!(d_info_digits(&msgs, TEST_GSSI), vec!["42".to_string()]);
+    assert!(brew_dtmf(&msgs).is_empty());
+
+    // Only the speaker dials into the call
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, build_u_info_dtmf(call_id, "1")));
+    test.run_stack(Some(1));
+    assert!(dl_pdus(&test.dump_sinks()).is_empty());
+}
+
+/// DTMF in an individual call bridged to a network subscriber is relayed in both directions
+#[test]
+fn test_individual_call_dtmf_with_network() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = brew_bridge_test_stack(dltime);
+    test.config.state_write().subscribers.register(TEST_ISSI_CALLED);
+    let brew_uuid = uuid::Uuid::new_v4();
+
+    let setup = IndividualCallSignal::Setup {
+        calling_issi: TEST_ISSI_NETWORK,
+        called_issi: TEST_ISSI_CALLED,
+        priority: 0,
+        hook_method: false,
+    };
+    test.submit_message(build_network_signal(dltime, brew_uuid, setup));
+    test.run_stack(Some(1));
+    let call_id = find_d_setup(&test.dump_sinks(), TEST_ISSI_CALLED).unwrap().call_identifier;
+
+    // Digits dialled before the call is connected are not relayed
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, build_u_info_dtmf(call_id, "0")));
+    test.run_stack(Some(1));
+    assert!(brew_dtmf(&test.dump_sinks()).is_empty());
+
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UConnect {
+        call_identifier: call_id,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, sdu));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // Local digits go to the network party
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI_CALLED, build_u_info_dtmf(call_id, "123*")));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(dl_pdus(&msgs).is_empty());
+    assert_eq!(brew_dtmf(&msgs), vec![(call_id, "123*".to_string())]);
+
+    // Network digits are delivered to the local MS on its traffic channel
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Brew,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::CmceCallControl(CallControl::CallDtmf {
+            call_id,
+            digits: "#9".to_string(),
+        }),
+    });
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(dl_pdus(&msgs), vec![(CmcePduTypeDl::DInfo, TEST_ISSI_CALLED, true)]);
+    assert_eq!(d_info_digits(&msgs, TEST_ISSI_CALLED), vec!["#9".to_string()]);
+}
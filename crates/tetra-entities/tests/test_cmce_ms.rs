@@ -0,0 +1,387 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::call_timeout::CallTimeout;
+use tetra_pdus::cmce::enums::call_timeout_setup_phase::CallTimeoutSetupPhase;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::enums::transmission_grant::TransmissionGrant;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_call_proceeding::DCallProceeding;
+use tetra_pdus::cmce::pdus::d_connect::DConnect;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::d_tx_granted::DTxGranted;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::cmce::pdus::u_tx_ceased::UTxCeased;
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
+use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
+use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tncc::{TnccSetupReq, TnccTxReq, TnccTxState};
+use tetra_saps::tnsds::TnsdsUnitdataReq;
+
+use crate::common::ComponentTest;
+
+const MS_ISSI: u32 = 7015011;
+const MS_GSSI: u32 = 91;
+const OTHER_ISSI: u32 = 7015012;
+const CALL_ID: u16 = 5;
+
+fn ms_test_stack() -> ComponentTest {
+    let mut test = ComponentTest::new(StackMode::Ms, Some(TdmaTime::default()));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::User],
+    );
+    test
+}
+
+fn chan_alloc(ts: u8, usage: u8) -> CmceChanAllocReq {
+    let mut timeslots = [false; 4];
+    timeslots[ts as usize - 1] = true;
+    CmceChanAllocReq {
+        usage: Some(usage),
+        alloc_type: ChanAllocType::Replace,
+        carrier: None,
+        timeslots,
+        ul_dl_assigned: UlDlAssignment::Both,
+    }
+}
+
+/// Wraps a serialized downlink CMCE PDU as if it came up from the MLE
+fn build_dl_msg(sdu: BitBuffer, addr: TetraAddress, chan_alloc: Option<CmceChanAllocReq>) -> SapMsg {
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 0,
+            endpoint_id: 0,
+            link_id: 0,
+            received_tetra_address: addr,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_alloc,
+        }),
+    }
+}
+
+fn build_user_msg(sap: Sap, msg: SapMsgInner) -> SapMsg {
+    SapMsg {
+        sap,
+        src: TetraEntity::User,
+        dest: TetraEntity::Cmce,
+        dltime: TdmaTime::default(),
+        msg,
+    }
+}
+
+fn group_bsi() -> BasicServiceInformation {
+    BasicServiceInformation {
+        circuit_mode_type: CircuitModeType::TchS,
+        encryption_flag: false,
+        communication_type: CommunicationType::P2Mp,
+        slots_per_frame: None,
+        speech_service: Some(0),
+    }
+}
+
+fn d_setup_sdu(calling_ssi: u32) -> BitBuffer {
+    let pdu = DSetup {
+        call_identifier: CALL_ID,
+        call_time_out: CallTimeout::T5m,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: group_bsi(),
+        transmission_grant: TransmissionGrant::GrantedToOtherUser,
+        transmission_request_permission: false,
+        call_priority: 0,
+        notification_indicator: None,
+        temporary_address: None,
+        calling_party_address_ssi: Some(calling_ssi),
+        calling_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+/// Returns the uplink CMCE PDUs sent to the MLE
+fn ul_sdus(msgs: &[SapMsg]) -> Vec<BitBuffer> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) => {
+                assert_eq!(prim.main_address.ssi, MS_ISSI);
+                Some(prim.sdu.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the circuit commands sent to the UMAC as (open, direction, ts)
+fn circuit_cmds(msgs: &[SapMsg]) -> Vec<(bool, Direction, u8)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(c)) => Some((true, c.direction, c.ts)),
+            SapMsgInner::CmceCallControl(CallControl::Close(dir, ts)) => Some((false, *dir, *ts)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn tx_states(msgs: &[SapMsg]) -> Vec<TnccTxState> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TnccTxInd(ind) => Some(ind.state),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_ms_group_call_setup() {
+    debug::setup_logging_verbose();
+    let mut test = ms_test_stack();
+    let own_addr = TetraAddress::new(MS_ISSI, SsiType::Issi);
+
+    // User starts a group call, the CMCE sends U-SETUP
+    test.submit_message(build_user_msg(
+        Sap::TnccSap,
+        SapMsgInner::TnccSetupReq(TnccSetupReq { called_ssi: MS_GSSI }),
+    ));
+    test.deliver_all_messages();
+    let sdus = ul_sdus(&test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let u_setup = USetup::from_bitbuf(&mut sdus[0].clone()).unwrap();
+    assert_eq!(u_setup.called_party_type_identifier, PartyTypeIdentifier::Ssi);
+    assert_eq!(u_setup.called_party_ssi, Some(MS_GSSI as u64));
+    assert_eq!(u_setup.basic_service_information.communication_type, CommunicationType::P2Mp);
+
+    // SwMI answers with D-CALL PROCEEDING and D-CONNECT with the floor granted on ts 2
+    let proceeding = DCallProceeding {
+        call_identifier: CALL_ID,
+        call_time_out_set_up_phase: CallTimeoutSetupPhase::T10s,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        call_status: None,
+        notification_indicator: None,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    proceeding.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_dl_msg(sdu, own_addr, None));
+
+    let connect = DConnect {
+        call_identifier: CALL_ID,
+        call_time_out: CallTimeout::T5m,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        transmission_grant: TransmissionGrant::Granted,
+        transmission_request_permission: false,
+        call_ownership: true,
+        call_priority: None,
+        basic_service_information: None,
+        temporary_address: None,
+        notification_indicator: None,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    connect.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_dl_msg(sdu, own_addr, Some(chan_alloc(2, 4))));
+    test.deliver_all_messages();
+
+    let msgs = test.dump_sinks();
+    assert_eq!(circuit_cmds(&msgs), vec![(true, Direction::Dl, 2), (true, Direction::Ul, 2)]);
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(&m.msg, SapMsgInner::TnccSetupCon(c) if c.call_id == CALL_ID))
+    );
+    assert_eq!(tx_states(&msgs), vec![TnccTxState::Granted]);
+
+    // Our own D-SETUP to the group is not a new call
+    test.submit_message(build_dl_msg(
+        d_setup_sdu(MS_ISSI),
+        TetraAddress::new(MS_GSSI, SsiType::Gssi),
+        Some(chan_alloc(2, 4)),
+    ));
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty());
+
+    // PTT released: uplink closes before U-TX CEASED goes out
+    test.submit_message(build_user_msg(
+        Sap::TnccSap,
+        SapMsgInner::TnccTxReq(TnccTxReq {
+            call_id: CALL_ID,
+            demand: false,
+        }),
+    ));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(circuit_cmds(&msgs), vec![(false, Direction::Ul, 2)]);
+    let sdus = ul_sdus(&msgs);
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(UTxCeased::from_bitbuf(&mut sdus[0].clone()).unwrap().call_identifier, CALL_ID);
+
+    // Call released by the SwMI
+    let release = DRelease {
+        call_identifier: CALL_ID,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        notification_indicator: None,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    release.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_dl_msg(sdu, TetraAddress::new(MS_GSSI, SsiType::Gssi), None));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(circuit_cmds(&msgs), vec![(false, Direction::Dl, 2)]);
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(&m.msg, SapMsgInner::TnccReleaseInd(r) if r.call_id == Some(CALL_ID)))
+    );
+}
+
+#[test]
+fn test_ms_group_call_join_and_grant() {
+    debug::setup_logging_verbose();
+    let mut test = ms_test_stack();
+    let group_addr = TetraAddress::new(MS_GSSI, SsiType::Gssi);
+
+    // Another member calls our group: we join and listen
+    test.submit_message(build_dl_msg(d_setup_sdu(OTHER_ISSI), group_addr, Some(chan_alloc(3, 5))));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(circuit_cmds(&msgs), vec![(true, Direction::Dl, 3)]);
+    assert!(msgs.iter().any(|m| matches!(&m.msg,
+        SapMsgInner::TnccSetupInd(s) if s.call_id == CALL_ID && s.calling_ssi == OTHER_ISSI && s.called_ssi == MS_GSSI)));
+    assert_eq!(tx_states(&msgs), vec![TnccTxState::OtherParty]);
+
+    // Late-entry repetition is ignored
+    test.submit_message(build_dl_msg(d_setup_sdu(OTHER_ISSI), group_addr, Some(chan_alloc(3, 5))));
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty());
+
+    // We get the floor
+    let granted = DTxGranted {
+        call_identifier: CALL_ID,
+        transmission_grant: TransmissionGrant::Granted as u8,
+        transmission_request_permission: false,
+        encryption_control: false,
+        reserved: false,
+        notification_indicator: None,
+        transmitting_party_type_identifier: Some(1),
+        transmitting_party_address_ssi: Some(MS_ISSI as u64),
+        transmitting_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    granted.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_dl_msg(sdu, TetraAddress::new(MS_ISSI, SsiType::Issi), None));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(circuit_cmds(&msgs), vec![(true, Direction::Ul, 3)]);
+    assert_eq!(tx_states(&msgs), vec![TnccTxState::Granted]);
+
+    // The group copy of our own grant changes nothing
+    let group_granted = DTxGranted {
+        transmission_grant: TransmissionGrant::GrantedToOtherUser as u8,
+        ..granted
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    group_granted.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_dl_msg(sdu, group_addr, None));
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty());
+}
+
+#[test]
+fn test_ms_call_setup_timeout() {
+    debug::setup_logging_verbose();
+    let mut test = ms_test_stack();
+
+    test.submit_message(build_user_msg(
+        Sap::TnccSap,
+        SapMsgInner::TnccSetupReq(TnccSetupReq { called_ssi: MS_GSSI }),
+    ));
+    test.run_stack(Some(10 * 18 * 4 + 2));
+    let msgs = test.dump_sinks();
+    assert!(
+        msgs.iter()
+            .any(|m| matches!(&m.msg, SapMsgInner::TnccReleaseInd(r) if r.call_id.is_none()))
+    );
+}
+
+#[test]
+fn test_ms_sds() {
+    debug::setup_logging_verbose();
+    let mut test = ms_test_stack();
+
+    // Send
+    let user_data = SdsUserData::Type4(32, vec![0x02, 0x01, b'h', b'i']);
+    test.submit_message(build_user_msg(
+        Sap::TnsdsSap,
+        SapMsgInner::TnsdsUnitdataReq(TnsdsUnitdataReq {
+            called_ssi: OTHER_ISSI,
+            user_data: user_data.clone(),
+        }),
+    ));
+    test.deliver_all_messages();
+    let sdus = ul_sdus(&test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let u_sds = USdsData::from_bitbuf(&mut sdus[0].clone()).unwrap();
+    assert_eq!(u_sds.called_party_ssi, Some(OTHER_ISSI as u64));
+    assert_eq!(u_sds.user_defined_data, user_data);
+
+    // Receive
+    let pdu = DSdsData {
+        calling_party_type_identifier: PartyTypeIdentifier::Ssi,
+        calling_party_address_ssi: Some(OTHER_ISSI as u64),
+        calling_party_extension: None,
+        user_defined_data: SdsUserData::Type1(0x8002),
+        external_subscriber_number: None,
+        dm_ms_address: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_dl_msg(sdu, TetraAddress::new(MS_ISSI, SsiType::Issi), None));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    let SapMsgInner::TnsdsUnitdataInd(ind) = &msgs[0].msg else {
+        panic!("expected TnsdsUnitdataInd, got {:?}", msgs[0].msg);
+    };
+    assert_eq!(ind.calling_ssi, OTHER_ISSI);
+    assert_eq!(ind.called_ssi, MS_ISSI);
+    assert_eq!(ind.user_data, SdsUserData::Type1(0x8002));
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use common::ComponentTest;
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, TxState, debug};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tla::{AlReport, TlaTlConnectReq, TlaTlDataReqAl, TlaTlDisconnectReq, TlaTlUnitdataReqAl};
+use tetra_saps::tma::TmaUnitdataInd;
+
+const MS_ISSI: u32 = 7015011;
+
+fn ms_address() -> TetraAddress {
+    TetraAddress {
+        ssi: MS_ISSI,
+        ssi_type: SsiType::Issi,
+        encrypted: false,
+    }
+}
+
+/// TL-SDU of the given length with a recognizable bit pattern
+fn make_tl_sdu(len: usize) -> BitBuffer {
+    let bits: String = (0..len).map(|i| if (i * 7 + i / 3) % 5 < 2 { '1' } else { '0' }).collect();
+    BitBuffer::from_bitstr(&bits)
+}
+
+/// A BS and an MS LLC, with the PDUs each hands to its MAC delivered to the other one
+struct AlPair {
+    bs: ComponentTest,
+    ms: ComponentTest,
+    /// Messages the BS and MS LLC passed up to their MLE
+    bs_mle: Vec<SapMsg>,
+    ms_mle: Vec<SapMsg>,
+    /// Number of PDUs sent by the BS so far, and which of them get lost on the way
+    bs_pdus_sent: usize,
+    bs_pdus_lost: Vec<usize>,
+}
+
+impl AlPair {
+    fn new() -> Self {
+        let mut bs = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default()));
+        bs.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+        let mut ms = ComponentTest::new(StackMode::Ms, Some(TdmaTime::default()));
+        ms.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+        Self {
+            bs,
+            ms,
+            bs_mle: vec![],
+            ms_mle: vec![],
+            bs_pdus_sent: 0,
+            bs_pdus_lost: vec![],
+        }
+    }
+
+    fn submit_bs(&mut self, msg: SapMsgInner) {
+        self.bs.submit_message(SapMsg {
+            sap: Sap::TlaSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Llc,
+            dltime: TdmaTime::default(),
+            msg,
+        });
+    }
+
+    /// Runs both stacks for one timeslot and passes the PDUs over the air
+    fn step(&mut self) {
+        self.bs.run_stack(Some(1));
+        self.ms.run_stack(Some(1));
+
+        for msg in self.bs.dump_sinks() {
+            if msg.dest == TetraEntity::Mle {
+                self.bs_mle.push(msg);
+                continue;
+            }
+            let lost = self.bs_pdus_lost.contains(&self.bs_pdus_sent);
+            self.bs_pdus_sent += 1;
+            if let Some(msg) = over_the_air(msg)
+                && !lost
+            {
+                self.ms.submit_message(msg);
+            }
+        }
+        for msg in self.ms.dump_sinks() {
+            if msg.dest == TetraEntity::Mle {
+                self.ms_mle.push(msg);
+            } else if let Some(msg) = over_the_air(msg) {
+                self.bs.submit_message(msg);
+            }
+        }
+    }
+
+    fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+}
+
+/// Turns a TMA-UNITDATA request into the indication the peer LLC receives
+fn over_the_air(msg: SapMsg) -> Option<SapMsg> {
+    let SapMsgInner::TmaUnitdataReq(req) = msg.msg else {
+        return None;
+    };
+    if let Some(tx_reporter) = &req.tx_reporter {
+        tx_reporter.mark_transmitted();
+    }
+    Some(SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Umac,
+        dest: TetraEntity::Llc,
+        dltime: msg.dltime.add_timeslots(2),
+        msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+            pdu: Some(req.pdu),
+            main_address: req.main_address,
+            scrambling_code: 0,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        }),
+    })
+}
+
+/// Sets up an advanced link from the BS and queues a TL-SDU on it
+fn connect_and_send(pair: &mut AlPair, tl_sdu: BitBuffer) -> TxReporter {
+    let tx_reporter = TxReporter::new();
+    pair.submit_bs(SapMsgInner::TlaTlConnectReq(TlaTlConnectReq {
+        main_address: ms_address(),
+        link_id: 0,
+        endpoint_id: 0,
+        unacknowledged: false,
+        req_handle: 0,
+    }));
+    pair.submit_bs(SapMsgInner::TlaTlDataReqAl(TlaTlDataReqAl {
+        main_address: ms_address(),
+        link_id: 0,
+        endpoint_id: 0,
+        tl_sdu,
+        req_handle: 0,
+        tx_reporter: Some(tx_reporter.clone()),
+    }));
+    tx_reporter
+}
+
+fn received_tl_sdus(msgs: &[SapMsg]) -> Vec<String> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::TlaTlDataIndAl(prim) => prim.tl_sdu.as_ref().map(|sdu| sdu.to_bitstr()),
+            SapMsgInner::TlaTlUnitdataIndAl(prim) => prim.tl_sdu.as_ref().map(|sdu| sdu.to_bitstr()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_al_segmented_transfer() {
+    debug::setup_logging_verbose();
+    let mut pair = AlPair::new();
+    let tl_sdu = make_tl_sdu(3000); // Beyond N.251, 16 segments once the FCS is added
+    let tx_reporter = connect_and_send(&mut pair, tl_sdu.clone());
+    pair.run(40);
+
+    assert!(
+        pair.bs_mle
+            .iter()
+            .any(|m| matches!(&m.msg, SapMsgInner::TlaTlConnectConf(c) if c.report == AlReport::Success))
+    );
+    assert!(pair.ms_mle.iter().any(|m| matches!(m.msg, SapMsgInner::TlaTlConnectInd(_))));
+    assert_eq!(received_tl_sdus(&pair.ms_mle), vec![tl_sdu.to_bitstr()]);
+    assert_eq!(tx_reporter.get_state(), TxState::Acknowledged);
+
+    // Release the link
+    pair.submit_bs(SapMsgInner::TlaTlDisconnectReq(TlaTlDisconnectReq {
+        main_address: ms_address(),
+        link_id: 0,
+        endpoint_id: 0,
+    }));
+    pair.run(4);
+    assert!(
+        pair.bs_mle
+            .iter()
+            .any(|m| matches!(&m.msg, SapMsgInner::TlaTlDisconnectConf(c) if c.report == AlReport::Success))
+    );
+    assert!(pair.ms_mle.iter().any(|m| matches!(m.msg, SapMsgInner::TlaTlDisconnectInd(_))));
+}
+
+#[test]
+fn test_al_selective_retransmission() {
+    debug::setup_logging_verbose();
+    let mut pair = AlPair::new();
+    // AL-SETUP goes first, then lose two of the AL-DATA PDUs
+    pair.bs_pdus_lost = vec![2, 4];
+    let tl_sdu = make_tl_sdu(1500);
+    let tx_reporter = connect_and_send(&mut pair, tl_sdu.clone());
+    pair.run(200);
+
+    assert_eq!(received_tl_sdus(&pair.ms_mle), vec![tl_sdu.to_bitstr()]);
+    assert_eq!(tx_reporter.get_state(), TxState::Acknowledged);
+}
+
+#[test]
+fn test_al_unitdata() {
+    debug::setup_logging_verbose();
+    let mut pair = AlPair::new();
+    let tl_sdu = make_tl_sdu(1000);
+    pair.submit_bs(SapMsgInner::TlaTlUnitdataReqAl(TlaTlUnitdataReqAl {
+        main_address: ms_address(),
+        link_id: 0,
+        endpoint_id: 0,
+        tl_sdu: tl_sdu.clone(),
+        req_handle: 0,
+        tx_reporter: None,
+    }));
+    pair.run(20);
+
+    // Repetitions are delivered only once
+    assert_eq!(received_tl_sdus(&pair.ms_mle), vec![tl_sdu.to_bitstr()]);
+}
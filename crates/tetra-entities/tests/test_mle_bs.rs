@@ -0,0 +1,313 @@
+mod common;
+
+use common::ComponentTest;
+use tetra_config::bluestation::{CfgNeighbourCell, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_call_restore::DCallRestore;
+use tetra_pdus::cmce::pdus::u_call_restore::UCallRestore;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::mle::enums::mle_fail_cause::MleFailCause;
+use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mle::pdus::d_new_cell::DNewCell;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+use tetra_pdus::mle::pdus::d_prepare_fail::DPrepareFail;
+use tetra_pdus::mle::pdus::d_restore_ack::DRestoreAck;
+use tetra_pdus::mle::pdus::d_restore_fail::DRestoreFail;
+use tetra_pdus::mle::pdus::u_prepare::UPrepare;
+use tetra_pdus::mle::pdus::u_restore::URestore;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tla::TlaTlDataIndBl;
+
+const TEST_ISSI: u32 = 1000001;
+const TEST_ISSI_ROAMING: u32 = 1000002;
+const TEST_GSSI: u32 = 91;
+
+fn neighbour_cell(cell_id: u8, main_carrier: u16, location_area: u16) -> CfgNeighbourCell {
+    CfgNeighbourCell {
+        cell_id,
+        main_carrier,
+        freq_band: None,
+        freq_offset_hz: None,
+        duplex_spacing_id: None,
+        reverse_operation: None,
+        location_area,
+        mcc: None,
+        mnc: None,
+        reselection_types_supported: 1,
+        synchronized: false,
+        service_level: 0,
+        max_ms_tx_power: None,
+        min_rx_access_level: None,
+    }
+}
+
+#[test]
+fn test_d_nwrk_broadcast_neighbour_cells() {
+    debug::setup_logging_verbose();
+
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.neighbour_cells = vec![neighbour_cell(1, 1530, 3), neighbour_cell(2, 1540, 4)];
+    config.neighbour_cells[1].freq_offset_hz = Some(6250);
+
+    // Start at the timeslot the broadcast is sent on
+    let dltime = TdmaTime { h: 0, m: 20, f: 1, t: 1 };
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc]);
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::TlaTlUnitdataReqBl(prim) = &sink_msgs[0].msg else {
+        panic!("expected TL-UNITDATA request");
+    };
+    let mut sdu = prim.tl_sdu.clone();
+    sdu.seek(3); // MLE protocol discriminator
+    let pdu = DNwrkBroadcast::from_bitbuf(&mut sdu).unwrap();
+
+    assert_eq!(pdu.cell_re_select_parameters.slow_reselect_threshold, 5); // 10 dB
+    assert_eq!(pdu.tetra_network_time, None);
+    assert_eq!(pdu.number_of_ca_neighbour_cells, Some(2));
+    let cells = &pdu.neighbour_cell_information_for_ca;
+    assert_eq!(cells[0].cell_identifier_ca, 1);
+    assert_eq!(cells[0].main_carrier_number, 1530);
+    assert_eq!(cells[0].location_area, Some(3));
+    assert_eq!(cells[0].main_carrier_number_extension, None);
+    // Band 4, offset +6.25 kHz, duplex spacing 4, no reverse operation, as the serving cell but for the offset
+    assert_eq!(cells[1].main_carrier_number_extension, Some((4 << 6) | (1 << 4) | (4 << 1)));
+    assert_eq!(sdu.get_len_remaining(), 0);
+}
+
+/// TL-DATA indication from the LLC carrying a PDU of the given protocol from the given ISSI
+fn build_ul_msg(dltime: TdmaTime, issi: u32, protocol: MleProtocolDiscriminator, write_pdu: impl FnOnce(&mut BitBuffer)) -> SapMsg {
+    let mut tl_sdu = BitBuffer::new_autoexpand(64);
+    tl_sdu.write_bits(protocol.into_raw(), 3);
+    write_pdu(&mut tl_sdu);
+    tl_sdu.seek(0);
+    SapMsg {
+        sap: Sap::TlaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Mle,
+        dltime,
+        msg: SapMsgInner::TlaTlDataIndBl(TlaTlDataIndBl {
+            main_address: TetraAddress::new(issi, SsiType::Issi),
+            link_id: 0,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: Some(tl_sdu),
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            req_handle: 0,
+        }),
+    }
+}
+
+/// MLE PDUs sent down to the LLC, positioned after the protocol discriminator, with whether a channel allocation is attached
+fn mle_dl_pdus(msgs: Vec<SapMsg>) -> Vec<(MlePduTypeDl, BitBuffer, bool)> {
+    msgs.into_iter()
+        .filter_map(|msg| {
+            let (mut tl_sdu, chan_alloc) = match msg.msg {
+                SapMsgInner::TlaTlDataReqBl(prim) => (prim.tl_sdu, prim.chan_alloc.is_some()),
+                SapMsgInner::TlaTlUnitdataReqBl(prim) => (prim.tl_sdu, prim.chan_alloc.is_some()),
+                _ => return None,
+            };
+            if tl_sdu.read_bits(3)? != MleProtocolDiscriminator::Mle.into_raw() {
+                return None;
+            }
+            let pdu_type = MlePduTypeDl::try_from(tl_sdu.peek_bits(3)?).ok()?;
+            Some((pdu_type, tl_sdu, chan_alloc))
+        })
+        .collect()
+}
+
+#[test]
+fn test_u_prepare_forward_registration() {
+    debug::setup_logging_verbose();
+
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.neighbour_cells = vec![neighbour_cell(1, 1530, 3)];
+    let dltime = TdmaTime::default();
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mle, TetraEntity::Mm], vec![TetraEntity::Llc, TetraEntity::Cmce]);
+
+    // Announced type 1 reselection to cell 1, forward registering through this cell
+    let demand = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut mm_sdu = BitBuffer::new_autoexpand(32);
+    demand.to_bitbuf(&mut mm_sdu).unwrap();
+    mm_sdu.seek(0);
+    let prepare = UPrepare {
+        cell_identifier_ca: Some(1),
+        sdu: Some(mm_sdu),
+    };
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, MleProtocolDiscriminator::Mle, |buf| {
+        prepare.to_bitbuf(buf).unwrap()
+    }));
+    test.run_stack(Some(1));
+
+    let pdus = mle_dl_pdus(test.dump_sinks());
+    assert_eq!(pdus.len(), 1);
+    let (pdu_type, mut buf, _) = pdus.into_iter().next().unwrap();
+    assert_eq!(pdu_type, MlePduTypeDl::DNewCell);
+    let d_new_cell = DNewCell::from_bitbuf(&mut buf).unwrap();
+    let mm_pdu = d_new_cell.sdu.expect("D-NEW-CELL without the registration response");
+    assert_eq!(mm_pdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+
+    // A cell that isn't one of our neighbours
+    let prepare = UPrepare {
+        cell_identifier_ca: Some(5),
+        sdu: None,
+    };
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, MleProtocolDiscriminator::Mle, |buf| {
+        prepare.to_bitbuf(buf).unwrap()
+    }));
+    test.run_stack(Some(1));
+
+    let pdus = mle_dl_pdus(test.dump_sinks());
+    assert_eq!(pdus.len(), 1);
+    let (pdu_type, mut buf, _) = pdus.into_iter().next().unwrap();
+    assert_eq!(pdu_type, MlePduTypeDl::DPrepareFail);
+    let d_prepare_fail = DPrepareFail::from_bitbuf(&mut buf).unwrap();
+    assert_eq!(
+        d_prepare_fail.fail_cause as u64,
+        MleFailCause::CellReselectionTypeNotSupported.into_raw()
+    );
+}
+
+/// U-RESTORE carrying a U-CALL RESTORE for the given group
+fn build_u_restore(dltime: TdmaTime, issi: u32, call_id: u16, gssi: u32) -> SapMsg {
+    let call_restore = UCallRestore {
+        call_identifier: call_id,
+        request_to_transmit_send_data: true,
+        other_party_type_identifier: 1,
+        other_party_short_number_address: None,
+        other_party_ssi: Some(gssi as u64),
+        other_party_extension: None,
+        basic_service_information: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut cmce_sdu = BitBuffer::new_autoexpand(48);
+    call_restore.to_bitbuf(&mut cmce_sdu).unwrap();
+    cmce_sdu.seek(0);
+    let restore = URestore {
+        mcc: None,
+        mnc: None,
+        la: Some(3),
+        sdu: Some(cmce_sdu),
+    };
+    build_ul_msg(dltime, issi, MleProtocolDiscriminator::Mle, |buf| restore.to_bitbuf(buf).unwrap())
+}
+
+#[test]
+fn test_u_restore_group_call() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Mle, TetraEntity::Cmce],
+        vec![TetraEntity::Llc, TetraEntity::Umac, TetraEntity::Brew],
+    );
+
+    // A group call in progress on this cell
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: TEST_ISSI,
+            groups: vec![TEST_GSSI],
+            action: BrewSubscriberAction::Affiliate,
+        }),
+    });
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(TEST_GSSI as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, MleProtocolDiscriminator::Cmce, |buf| {
+        u_setup.to_bitbuf(buf).unwrap()
+    }));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // An MS walking in from the neighbour cell restores its part in the call
+    test.submit_message(build_u_restore(dltime, TEST_ISSI_ROAMING, 1234, TEST_GSSI));
+    test.run_stack(Some(1));
+
+    let pdus = mle_dl_pdus(test.dump_sinks());
+    assert_eq!(pdus.len(), 1);
+    let (pdu_type, mut buf, chan_alloc) = pdus.into_iter().next().unwrap();
+    assert_eq!(pdu_type, MlePduTypeDl::DRestoreAck);
+    assert!(chan_alloc, "D-RESTORE-ACK should move the MS to the traffic channel");
+    let d_restore_ack = DRestoreAck::from_bitbuf(&mut buf).unwrap();
+    let mut cmce_pdu = d_restore_ack.sdu.expect("D-RESTORE-ACK without D-CALL RESTORE");
+    assert_eq!(cmce_pdu.peek_bits(5), Some(CmcePduTypeDl::DCallRestore.into_raw()));
+    let d_call_restore = DCallRestore::from_bitbuf(&mut cmce_pdu).unwrap();
+    assert_eq!(d_call_restore.call_identifier, 1234);
+    assert!(d_call_restore.new_call_identifier.is_some());
+
+    // No call for this group here
+    test.submit_message(build_u_restore(dltime, TEST_ISSI_ROAMING, 1235, TEST_GSSI + 1));
+    test.run_stack(Some(1));
+
+    let pdus = mle_dl_pdus(test.dump_sinks());
+    assert_eq!(pdus.len(), 1);
+    let (pdu_type, mut buf, _) = pdus.into_iter().next().unwrap();
+    assert_eq!(pdu_type, MlePduTypeDl::DRestoreFail);
+    let d_restore_fail = DRestoreFail::from_bitbuf(&mut buf).unwrap();
+    assert_eq!(
+        d_restore_fail.fail_cause as u64,
+        MleFailCause::RestorationCannotBeDoneOnCell.into_raw()
+    );
+}
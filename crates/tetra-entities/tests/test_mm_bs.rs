@@ -1,8 +1,34 @@
 mod common;
 
-use tetra_config::bluestation::StackMode;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::unbounded;
+use tetra_config::bluestation::{AuthPolicy, CfgAuth, CfgBrew, CfgEncryption, CfgRegistrationStore, SecurityClass, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::brew::entity::BrewEntity;
+use tetra_entities::brew::worker::{BrewCommand, BrewEvent};
+use tetra_entities::mm::components::authentication::{AuthAlgorithm, TestAuthAlgorithm};
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::fields::authentication_uplink::AuthenticationUplink;
+use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_authentication_demand::DAuthenticationDemand;
+use tetra_pdus::mm::pdus::d_authentication_response::DAuthenticationResponse;
+use tetra_pdus::mm::pdus::d_authentication_result::DAuthenticationResult;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_command::DLocationUpdateCommand;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_authentication_demand::UAuthenticationDemand;
+use tetra_pdus::mm::pdus::u_authentication_response::UAuthenticationResponse;
+use tetra_pdus::mm::pdus::u_authentication_result::UAuthenticationResult;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::brew::BrewSubscriberAction;
+use tetra_saps::control::cipher::MmCipherKeyUpdate;
 use tetra_saps::lmm::LmmMleUnitdataInd;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
 
@@ -47,3 +73,642 @@ fn test_unsupported_u_mm_status() {
     assert_eq!(sink_msgs.len(), 1);
     tracing::info!("We have the expected MM message, but full validation of result not implemented");
 }
+
+const AUTH_TEST_ISSI: u32 = 1000001;
+const AUTH_TEST_KEY: [u8; 16] = [0x42; 16];
+
+/// Build a test stack running MM only, with the given authentication policy and a key for AUTH_TEST_ISSI
+fn auth_test_stack(policy: AuthPolicy, dltime: TdmaTime) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.auth = Some(CfgAuth {
+        policy,
+        keys: HashMap::from([(AUTH_TEST_ISSI, AUTH_TEST_KEY)]),
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+fn build_mm_ul_msg(dltime: TdmaTime, issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress {
+                encrypted: false,
+                ssi_type: SsiType::Issi,
+                ssi: issi,
+            },
+        }),
+    }
+}
+
+fn build_itsi_attach(dltime: TdmaTime, issi: u32) -> SapMsg {
+    build_itsi_attach_with(dltime, issi, None)
+}
+
+fn build_itsi_attach_with(dltime: TdmaTime, issi: u32, authentication_uplink: Option<AuthenticationUplink>) -> SapMsg {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_mm_ul_msg(dltime, issi, sdu)
+}
+
+/// Returns the MM PDUs sent down to MLE, in order
+fn mm_dl_sdus(msgs: Vec<SapMsg>) -> Vec<BitBuffer> {
+    msgs.into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(prim.sdu),
+            _ => None,
+        })
+        .collect()
+}
+
+fn mm_pdu_type(sdu: &BitBuffer) -> MmPduTypeDl {
+    MmPduTypeDl::try_from(sdu.peek_bits(4).unwrap()).unwrap()
+}
+
+/// Challenge MS, return the D-AUTHENTICATION DEMAND
+fn start_auth(test: &mut ComponentTest, dltime: TdmaTime) -> DAuthenticationDemand {
+    test.submit_message(build_itsi_attach(dltime, AUTH_TEST_ISSI));
+    test.run_stack(Some(1));
+    let mut sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DAuthentication);
+    assert!(!test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI));
+    DAuthenticationDemand::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationDemand")
+}
+
+fn send_auth_response(test: &mut ComponentTest, dltime: TdmaTime, res1: u32, rand2: Option<u128>) -> Vec<BitBuffer> {
+    let pdu = UAuthenticationResponse {
+        response_value: res1,
+        mutual_authentication_flag: rand2.is_some(),
+        random_challenge: rand2,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(128);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_mm_ul_msg(dltime, AUTH_TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+    mm_dl_sdus(test.dump_sinks())
+}
+
+#[test]
+fn test_authentication_success_with_mutual() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = auth_test_stack(AuthPolicy::Required, dltime);
+
+    let demand = start_auth(&mut test, dltime);
+
+    // Compute the response as the MS would, and challenge the infrastructure in return
+    let alg = TestAuthAlgorithm;
+    let ks = alg.ta11(&AUTH_TEST_KEY, demand.random_seed);
+    let (res1, _dck1) = alg.ta12(&ks, demand.random_challenge);
+    let rand2 = 0x1234_5678_9abc_def0_1234;
+    let mut sdus = send_auth_response(&mut test, dltime, res1, Some(rand2));
+
+    assert_eq!(sdus.len(), 2);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DAuthentication);
+    assert_eq!(mm_pdu_type(&sdus[1]), MmPduTypeDl::DLocationUpdateAccept);
+    let result = DAuthenticationResult::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationResult");
+    assert!(result.authentication_result);
+    let ks_ = alg.ta21(&AUTH_TEST_KEY, demand.random_seed);
+    assert_eq!(result.response_value, Some(alg.ta22(&ks_, rand2)));
+    assert!(test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI));
+}
+
+#[test]
+fn test_authentication_wrong_response_rejected() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = auth_test_stack(AuthPolicy::Required, dltime);
+
+    let demand = start_auth(&mut test, dltime);
+    let alg = TestAuthAlgorithm;
+    let ks = alg.ta11(&AUTH_TEST_KEY, demand.random_seed);
+    let (res1, _) = alg.ta12(&ks, demand.random_challenge);
+    let mut sdus = send_auth_response(&mut test, dltime, !res1, None);
+
+    assert_eq!(sdus.len(), 2);
+    let result = DAuthenticationResult::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationResult");
+    assert!(!result.authentication_result);
+    assert_eq!(mm_pdu_type(&sdus[1]), MmPduTypeDl::DLocationUpdateReject);
+    assert!(!test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI));
+}
+
+#[test]
+fn test_authentication_policy_unknown_ms() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+
+    // Required: an MS without key is refused outright
+    let mut test = auth_test_stack(AuthPolicy::Required, dltime);
+    test.submit_message(build_itsi_attach(dltime, AUTH_TEST_ISSI + 1));
+    test.run_stack(Some(1));
+    let sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DLocationUpdateReject);
+    assert!(!test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI + 1));
+
+    // Optional: an MS without key registers without authentication
+    let mut test = auth_test_stack(AuthPolicy::Optional, dltime);
+    test.submit_message(build_itsi_attach(dltime, AUTH_TEST_ISSI + 1));
+    test.run_stack(Some(1));
+    let sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DLocationUpdateAccept);
+    assert!(test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI + 1));
+}
+
+/// A class 3 cell only serves authenticated MSs, whatever the authentication policy
+#[test]
+fn test_class3_requires_authentication() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.auth = Some(CfgAuth {
+        policy: AuthPolicy::Disabled,
+        keys: HashMap::from([(AUTH_TEST_ISSI, AUTH_TEST_KEY)]),
+    });
+    config.encryption = Some(CfgEncryption {
+        security_class: SecurityClass::Class3,
+        sck: None,
+        sck_number: 1,
+        cck: Some(0x1234_5678_9abc_def0_1234),
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce, TetraEntity::Umac]);
+
+    // An MS with a key is challenged, one without is refused
+    start_auth(&mut test, dltime);
+    test.submit_message(build_itsi_attach(dltime, AUTH_TEST_ISSI + 1));
+    test.run_stack(Some(1));
+    let sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DLocationUpdateReject);
+    assert!(!test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI + 1));
+}
+
+/// An MS challenging the infrastructure while registering gets RES2 in the D-AUTHENTICATION RESULT
+/// of the exchange, or in the D-LOCATION UPDATE ACCEPT if it is not challenged itself
+#[test]
+fn test_authentication_uplink_at_registration() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let alg = TestAuthAlgorithm;
+    let rand2 = 0x1234_5678_9abc_def0_1234;
+    let auth_ul = Some(AuthenticationUplink { random_challenge: rand2 });
+
+    // The MS is challenged first, its RAND2 is answered with the authentication result
+    let mut test = auth_test_stack(AuthPolicy::Required, dltime);
+    test.submit_message(build_itsi_attach_with(dltime, AUTH_TEST_ISSI, auth_ul.clone()));
+    test.run_stack(Some(1));
+    let mut sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let demand = DAuthenticationDemand::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationDemand");
+    let ks = alg.ta11(&AUTH_TEST_KEY, demand.random_seed);
+    let (res1, _) = alg.ta12(&ks, demand.random_challenge);
+    let mut sdus = send_auth_response(&mut test, dltime, res1, None);
+
+    assert_eq!(sdus.len(), 2);
+    let result = DAuthenticationResult::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationResult");
+    assert!(result.authentication_result);
+    let ks_ = alg.ta21(&AUTH_TEST_KEY, demand.random_seed);
+    assert_eq!(result.response_value, Some(alg.ta22(&ks_, rand2)));
+    let accept = DLocationUpdateAccept::from_bitbuf(&mut sdus[1]).expect("Failed parsing DLocationUpdateAccept");
+    assert!(accept.authentication_downlink.is_none());
+
+    // Without a challenge of its own, the MS gets its answer with the registration
+    let mut test = auth_test_stack(AuthPolicy::Disabled, dltime);
+    test.submit_message(build_itsi_attach_with(dltime, AUTH_TEST_ISSI, auth_ul));
+    test.run_stack(Some(1));
+    let mut sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let accept = DLocationUpdateAccept::from_bitbuf(&mut sdus[0]).expect("Failed parsing DLocationUpdateAccept");
+    let auth_dl = accept.authentication_downlink.expect("Expected authentication downlink");
+    let ks_ = alg.ta21(&AUTH_TEST_KEY, auth_dl.random_seed);
+    assert_eq!(auth_dl.response_value, alg.ta22(&ks_, rand2));
+    assert!(test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI));
+}
+
+/// A U-AUTHENTICATION DEMAND is answered with RES2, or rejected if no key is known for the MS
+#[test]
+fn test_u_authentication_demand() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = auth_test_stack(AuthPolicy::Optional, dltime);
+    let rand2 = 0x0fed_cba9_8765_4321_0f0f;
+
+    let send_demand = |test: &mut ComponentTest, issi: u32| {
+        let pdu = UAuthenticationDemand {
+            random_challenge: rand2,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(90);
+        pdu.to_bitbuf(&mut sdu).unwrap();
+        sdu.seek(0);
+        test.submit_message(build_mm_ul_msg(dltime, issi, sdu));
+        test.run_stack(Some(1));
+        mm_dl_sdus(test.dump_sinks())
+    };
+
+    let mut sdus = send_demand(&mut test, AUTH_TEST_ISSI);
+    assert_eq!(sdus.len(), 1);
+    let response = DAuthenticationResponse::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationResponse");
+    let alg = TestAuthAlgorithm;
+    let ks_ = alg.ta21(&AUTH_TEST_KEY, response.random_seed);
+    assert_eq!(response.response_value, alg.ta22(&ks_, rand2));
+    assert!(!response.mutual_authentication_flag);
+
+    // No key known, the demand is rejected (sub type 3)
+    let sdus = send_demand(&mut test, AUTH_TEST_ISSI + 1);
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DAuthentication);
+    assert_eq!(sdus[0].peek_bits_posoffset(4, 2), Some(3));
+}
+
+/// A wrong response is refused under the handle of the registration that triggered the challenge
+#[test]
+fn test_authentication_reject_uses_demand_handle() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = auth_test_stack(AuthPolicy::Required, dltime);
+
+    let mut attach = build_itsi_attach(dltime, AUTH_TEST_ISSI);
+    let SapMsgInner::LmmMleUnitdataInd(prim) = &mut attach.msg else {
+        unreachable!()
+    };
+    prim.handle = 5;
+    test.submit_message(attach);
+    test.run_stack(Some(1));
+    let mut sdus = mm_dl_sdus(test.dump_sinks());
+    let demand = DAuthenticationDemand::from_bitbuf(&mut sdus[0]).expect("Failed parsing DAuthenticationDemand");
+
+    let alg = TestAuthAlgorithm;
+    let ks = alg.ta11(&AUTH_TEST_KEY, demand.random_seed);
+    let (res1, _) = alg.ta12(&ks, demand.random_challenge);
+    let pdu = UAuthenticationResponse {
+        response_value: !res1,
+        mutual_authentication_flag: false,
+        random_challenge: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_mm_ul_msg(dltime, AUTH_TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+
+    let reject = test
+        .dump_sinks()
+        .into_iter()
+        .find_map(|m| match m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) if mm_pdu_type(&prim.sdu) == MmPduTypeDl::DLocationUpdateReject => Some(prim),
+            _ => None,
+        })
+        .expect("Expected DLocationUpdateReject");
+    assert_eq!(reject.handle, 5);
+}
+
+/// An MS reporting that the infrastructure failed its challenge loses its registration and DCK
+#[test]
+fn test_authentication_result_failure_drops_registration() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.auth = Some(CfgAuth {
+        policy: AuthPolicy::Required,
+        keys: HashMap::from([(AUTH_TEST_ISSI, AUTH_TEST_KEY)]),
+    });
+    config.encryption = Some(CfgEncryption {
+        security_class: SecurityClass::Class3,
+        sck: None,
+        sck_number: 1,
+        cck: Some(0x1234_5678_9abc_def0_1234),
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce, TetraEntity::Umac]);
+
+    let demand = start_auth(&mut test, dltime);
+    let alg = TestAuthAlgorithm;
+    let ks = alg.ta11(&AUTH_TEST_KEY, demand.random_seed);
+    let (res1, _) = alg.ta12(&ks, demand.random_challenge);
+    send_auth_response(&mut test, dltime, res1, Some(0x1234_5678_9abc_def0_1234));
+    assert!(test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI));
+
+    let pdu = UAuthenticationResult {
+        authentication_result: false,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_mm_ul_msg(dltime, AUTH_TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+
+    let key_updates: Vec<MmCipherKeyUpdate> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::MmCipherKeyUpdate(update) => Some(update),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(key_updates.len(), 1);
+    assert_eq!(key_updates[0].issi, AUTH_TEST_ISSI);
+    assert!(key_updates[0].dck.is_none());
+    assert!(!test.config.state_read().subscribers.is_registered(AUTH_TEST_ISSI));
+}
+
+const DB_TEST_ISSI: u32 = 1000001;
+const DB_TEST_GSSI: u32 = 91;
+
+fn temp_subscriber_db(name: &str, contents: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let path = std::env::temp_dir().join(format!("test_mm_bs_{}_{}.toml", name, nanos));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Build a test stack running MM only, admitting the subscribers in the given database file.
+/// The cell admits subscriber classes 1 to 8.
+fn subscriber_db_test_stack(db_path: &Path, dltime: TdmaTime) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.subscriber_db = Some(db_path.to_string_lossy().into_owned());
+    config.cell.subscriber_class = 0x00ff;
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+/// Send an ITSI attach and return the single MM PDU sent in response
+fn itsi_attach(test: &mut ComponentTest, dltime: TdmaTime, issi: u32) -> BitBuffer {
+    test.submit_message(build_itsi_attach(dltime, issi));
+    test.run_stack(Some(1));
+    let mut sdus = mm_dl_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    sdus.remove(0)
+}
+
+/// Build a U-ATTACH/DETACH GROUP IDENTITY attaching to the given groups
+fn build_group_attach(dltime: TdmaTime, issi: u32, gssis: &[u32]) -> SapMsg {
+    let attach = |gssi: u32| GroupIdentityUplink {
+        class_of_usage: Some(4),
+        group_identity_detachment_uplink: None,
+        gssi: Some(gssi),
+        address_extension: None,
+        vgssi: None,
+    };
+    let pdu = UAttachDetachGroupIdentity {
+        group_identity_report: false,
+        group_identity_attach_detach_mode: false,
+        group_report_response: None,
+        group_identity_uplink: Some(gssis.iter().copied().map(attach).collect()),
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_mm_ul_msg(dltime, issi, sdu)
+}
+
+fn expect_reject_cause(mut sdu: BitBuffer) -> u8 {
+    assert_eq!(mm_pdu_type(&sdu), MmPduTypeDl::DLocationUpdateReject);
+    DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap().reject_cause
+}
+
+#[test]
+fn test_subscriber_db_location_update() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let db_path = temp_subscriber_db(
+        "location_update",
+        r#"
+        groups = [91, 92]
+
+        [subscribers.1000001]
+        class = 0x0003
+
+        [subscribers.1000002]
+        barred = true
+
+        [subscribers.1000003]
+        class = 0x0100
+        "#,
+    );
+    let mut test = subscriber_db_test_stack(&db_path, dltime);
+    let _ = std::fs::remove_file(&db_path);
+
+    // Listed subscriber is accepted and told its subscriber class
+    let mut sdu = itsi_attach(&mut test, dltime, DB_TEST_ISSI);
+    assert_eq!(mm_pdu_type(&sdu), MmPduTypeDl::DLocationUpdateAccept);
+    let accept = DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(accept.subscriber_class, Some(0x0003));
+    assert!(test.config.state_read().subscribers.is_registered(DB_TEST_ISSI));
+
+    // Barred: illegal MS
+    assert_eq!(expect_reject_cause(itsi_attach(&mut test, dltime, DB_TEST_ISSI + 1)), 2);
+    // No subscriber class in common with the cell: LA not allowed
+    assert_eq!(expect_reject_cause(itsi_attach(&mut test, dltime, DB_TEST_ISSI + 2)), 3);
+    // Not listed: ITSI unknown
+    assert_eq!(expect_reject_cause(itsi_attach(&mut test, dltime, DB_TEST_ISSI + 3)), 1);
+    for issi in DB_TEST_ISSI + 1..=DB_TEST_ISSI + 3 {
+        assert!(!test.config.state_read().subscribers.is_registered(issi));
+    }
+}
+
+#[test]
+fn test_subscriber_db_group_attach() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let db_path = temp_subscriber_db(
+        "group_attach",
+        r#"
+        groups = [91, 92]
+
+        [subscribers.1000001]
+        groups = [91]
+        "#,
+    );
+    let mut test = subscriber_db_test_stack(&db_path, dltime);
+    let _ = std::fs::remove_file(&db_path);
+    itsi_attach(&mut test, dltime, DB_TEST_ISSI);
+
+    // Attach to a permitted group, a group the subscriber may not use and a group that does not exist
+    test.submit_message(build_group_attach(
+        dltime,
+        DB_TEST_ISSI,
+        &[DB_TEST_GSSI, DB_TEST_GSSI + 1, DB_TEST_GSSI + 2],
+    ));
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    let affiliated: Vec<Vec<u32>> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::MmSubscriberUpdate(update) if update.action == BrewSubscriberAction::Affiliate => Some(update.groups.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(affiliated, vec![vec![DB_TEST_GSSI]]);
+
+    let mut sdus = mm_dl_sdus(msgs);
+    assert_eq!(sdus.len(), 1);
+    let ack = DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut sdus[0]).unwrap();
+    assert_eq!(ack.group_identity_accept_reject, 1);
+    let accepted: Vec<Option<u32>> = ack.group_identity_downlink.unwrap().iter().map(|gid| gid.gssi).collect();
+    assert_eq!(accepted, vec![Some(DB_TEST_GSSI)]);
+}
+
+#[test]
+fn test_subscriber_db_reload() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let db_path = temp_subscriber_db("reload", "groups = [91]\n");
+    let mut test = subscriber_db_test_stack(&db_path, dltime);
+
+    assert_eq!(expect_reject_cause(itsi_attach(&mut test, dltime, DB_TEST_ISSI)), 1);
+
+    // The changed file is picked up within a multiframe
+    std::fs::write(&db_path, "groups = [91]\n\n[subscribers.1000001]\n").unwrap();
+    test.run_stack(Some(72));
+    test.dump_sinks();
+    let sdu = itsi_attach(&mut test, dltime, DB_TEST_ISSI);
+    assert_eq!(mm_pdu_type(&sdu), MmPduTypeDl::DLocationUpdateAccept);
+
+    // A broken file keeps the previous database in place
+    std::fs::write(&db_path, "groups = [91\n").unwrap();
+    test.run_stack(Some(72));
+    test.dump_sinks();
+    let sdu = itsi_attach(&mut test, dltime, DB_TEST_ISSI);
+    assert_eq!(mm_pdu_type(&sdu), MmPduTypeDl::DLocationUpdateAccept);
+
+    // Barring the registered subscriber removes its registration on its next location update
+    std::fs::write(&db_path, "groups = [91]\n\n[subscribers.1000001]\nbarred = true\n").unwrap();
+    test.run_stack(Some(72));
+    test.dump_sinks();
+    assert_eq!(expect_reject_cause(itsi_attach(&mut test, dltime, DB_TEST_ISSI)), 2);
+    assert!(!test.config.state_read().subscribers.is_registered(DB_TEST_ISSI));
+    let _ = std::fs::remove_file(&db_path);
+}
+
+/// Registrations and group attachments survive a restart: the new stack re-announces them to CMCE and
+/// asks the MS to register again
+#[test]
+fn test_registrations_restored_after_restart() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let state_path = std::env::temp_dir().join(format!("test_mm_bs_registrations_{}.json", nanos));
+
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.registration_store = Some(CfgRegistrationStore {
+        file: state_path.to_string_lossy().into_owned(),
+        request_reregistration: true,
+    });
+
+    let mut test = ComponentTest::from_config(config.clone(), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    itsi_attach(&mut test, dltime, DB_TEST_ISSI);
+    test.submit_message(build_group_attach(dltime, DB_TEST_ISSI, &[DB_TEST_GSSI]));
+    test.run_stack(Some(72));
+    test.dump_sinks();
+    assert!(state_path.exists(), "Registrations should be saved within a multiframe");
+
+    // Restart, with a Brew link that only comes up after the registrations are restored
+    config.brew = Some(CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: false,
+        feature_packet_data_enabled: false,
+        whitelisted_ssis: None,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    let (brew_event_sender, brew_event_receiver) = unbounded();
+    let (brew_command_sender, brew_command_receiver) = unbounded();
+    test.register_entity(BrewEntity::with_link(
+        test.get_shared_config(),
+        brew_event_receiver,
+        brew_command_sender,
+    ));
+    test.run_stack(Some(1));
+    let _ = std::fs::remove_file(&state_path);
+
+    {
+        let state = test.config.state_read();
+        assert!(state.subscribers.is_registered(DB_TEST_ISSI));
+        assert!(state.subscribers.has_group_members(DB_TEST_GSSI));
+    }
+    let msgs = test.dump_sinks();
+    let updates: Vec<(BrewSubscriberAction, Vec<u32>)> = msgs
+        .iter()
+        .filter(|m| m.dest == TetraEntity::Cmce)
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::MmSubscriberUpdate(update) if update.issi == DB_TEST_ISSI => Some((update.action, update.groups.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        updates,
+        vec![
+            (BrewSubscriberAction::Register, vec![]),
+            (BrewSubscriberAction::Affiliate, vec![DB_TEST_GSSI])
+        ]
+    );
+    let mut sdus = mm_dl_sdus(msgs);
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(mm_pdu_type(&sdus[0]), MmPduTypeDl::DLocationUpdateCommand);
+    let command = DLocationUpdateCommand::from_bitbuf(&mut sdus[0]).expect("Failed parsing");
+    assert!(command.group_identity_report);
+    assert_eq!(sdus[0].get_len_remaining(), 0, "Buffer not fully consumed");
+
+    // Once the Brew worker connects, the restored registration and affiliation are sent to the server
+    while brew_command_receiver.try_recv().is_ok() {}
+    brew_event_sender.send(BrewEvent::Connected).unwrap();
+    test.run_stack(Some(1));
+    let commands: Vec<BrewCommand> = brew_command_receiver.try_iter().collect();
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, BrewCommand::RegisterSubscriber { issi } if *issi == DB_TEST_ISSI)),
+        "Expected REGISTER of ISSI {} to Brew, got {:?}",
+        DB_TEST_ISSI,
+        commands
+    );
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, BrewCommand::AffiliateGroups { issi, groups } if *issi == DB_TEST_ISSI && *groups == vec![DB_TEST_GSSI])),
+        "Expected AFFILIATE of ISSI {} to Brew, got {:?}",
+        DB_TEST_ISSI,
+        commands
+    );
+}
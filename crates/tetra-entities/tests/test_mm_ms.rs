@@ -0,0 +1,201 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::fields::group_identity_attachment::GroupIdentityAttachment;
+use tetra_pdus::mm::fields::group_identity_downlink::GroupIdentityDownlink;
+use tetra_pdus::mm::fields::group_identity_location_accept::GroupIdentityLocationAccept;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_command::DLocationUpdateCommand;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::lmm::{LmmMleActivateConf, LmmMleUnitdataInd};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const MS_ISSI: u32 = 7015011;
+const MS_GSSI: u32 = 91;
+
+fn ms_test_stack(dltime: TdmaTime) -> ComponentTest {
+    let mut test = ComponentTest::new(StackMode::Ms, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::User]);
+    test
+}
+
+fn build_activate_conf(dltime: TdmaTime, registration_required: bool) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleActivateConf(LmmMleActivateConf {
+            registration_required,
+            la: 2,
+            cell_type: 0,
+        }),
+    }
+}
+
+fn build_accept(dltime: TdmaTime) -> SapMsg {
+    let pdu = DLocationUpdateAccept {
+        location_update_accept_type: LocationUpdateType::ItsiAttach,
+        ssi: None,
+        address_extension: None,
+        subscriber_class: None,
+        energy_saving_information: None,
+        scch_information_and_distribution_on_18th_frame: None,
+        new_registered_area: None,
+        security_downlink: None,
+        group_identity_location_accept: Some(GroupIdentityLocationAccept {
+            group_identity_accept_reject: 0,
+            group_identity_downlink: Some(vec![GroupIdentityDownlink {
+                group_identity_attachment: Some(GroupIdentityAttachment {
+                    group_identity_attachment_lifetime: 3,
+                    class_of_usage: 4,
+                }),
+                group_identity_detachment_uplink: None,
+                gssi: Some(MS_GSSI),
+                address_extension: None,
+                vgssi: None,
+            }]),
+        }),
+        default_group_attachment_lifetime: None,
+        authentication_downlink: None,
+        group_identity_security_related_information: None,
+        cell_type_control: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_unitdata_ind(dltime, sdu)
+}
+
+fn build_location_update_command(dltime: TdmaTime) -> SapMsg {
+    let pdu = DLocationUpdateCommand {
+        group_identity_report: true,
+        cipher_control: false,
+        ciphering_parameters: None,
+        address_extension: None,
+        cell_type_control: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_unitdata_ind(dltime, sdu)
+}
+
+fn build_unitdata_ind(dltime: TdmaTime, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(MS_ISSI, SsiType::Issi),
+        }),
+    }
+}
+
+/// Returns the U-LOCATION UPDATE DEMANDs sent down to MLE, in order
+fn location_update_demands(msgs: &[SapMsg]) -> Vec<ULocationUpdateDemand> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => {
+                assert_eq!(prim.address.ssi, MS_ISSI);
+                let mut sdu = prim.sdu.clone();
+                ULocationUpdateDemand::from_bitbuf(&mut sdu).ok()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_ms_itsi_attach() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default();
+    let mut test = ms_test_stack(dltime);
+
+    // Cell selected, registration required: ITSI attach including our group
+    test.submit_message(build_activate_conf(dltime, true));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    let demands = location_update_demands(&msgs);
+    assert_eq!(demands.len(), 1);
+    assert_eq!(demands[0].location_update_type, LocationUpdateType::ItsiAttach);
+    let gild = demands[0].group_identity_location_demand.as_ref().expect("group demand missing");
+    let groups = gild.group_identity_uplink.as_ref().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].gssi, Some(MS_GSSI));
+
+    // Accept with the group attached
+    test.submit_message(build_accept(dltime));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    let ind = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::TnmmRegistrationInd(ind) => Some(ind.clone()),
+            _ => None,
+        })
+        .expect("no registration indication");
+    assert!(ind.registered);
+    assert_eq!(ind.issi, MS_ISSI);
+    assert_eq!(ind.groups, vec![MS_GSSI]);
+
+    // Nothing is retried once accepted
+    test.run_stack(Some(5 * 18 * 4 + 1));
+    assert!(location_update_demands(&test.dump_sinks()).is_empty());
+}
+
+#[test]
+fn test_ms_itsi_attach_retry() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default();
+    let mut test = ms_test_stack(dltime);
+
+    test.submit_message(build_activate_conf(dltime, true));
+    test.deliver_all_messages();
+    assert_eq!(location_update_demands(&test.dump_sinks()).len(), 1);
+
+    // No answer: the demand is repeated after the response timeout, then we give up
+    test.run_stack(Some(3 * 5 * 18 * 4 + 1));
+    let msgs = test.dump_sinks();
+    assert_eq!(location_update_demands(&msgs).len(), 2);
+    let ind = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::TnmmRegistrationInd(ind) => Some(ind.clone()),
+            _ => None,
+        })
+        .expect("no registration indication");
+    assert!(!ind.registered);
+}
+
+#[test]
+fn test_ms_location_update_command() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default();
+    let mut test = ms_test_stack(dltime);
+
+    test.submit_message(build_activate_conf(dltime, true));
+    test.deliver_all_messages();
+    test.submit_message(build_accept(dltime));
+    test.deliver_all_messages();
+    test.dump_sinks();
+
+    // The SwMI does not know us (e.g. after a restart) and demands a location update
+    test.submit_message(build_location_update_command(dltime));
+    test.deliver_all_messages();
+    let demands = location_update_demands(&test.dump_sinks());
+    assert_eq!(demands.len(), 1);
+    assert_eq!(demands[0].location_update_type, LocationUpdateType::DemandLocationUpdating);
+    let gild = demands[0].group_identity_location_demand.as_ref().expect("group demand missing");
+    assert_eq!(gild.group_identity_uplink.as_ref().unwrap()[0].gssi, Some(MS_GSSI));
+}
@@ -0,0 +1,438 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::monitor::EventLog;
+use tetra_entities::umac::subcomp::fillbits;
+use tetra_entities::umac::umac_mon::UmacMon;
+use tetra_pdus::cmce::enums::call_timeout::CallTimeout;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::enums::transmission_grant::TransmissionGrant;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_pdus::llc::pdus::bl_udata::BlUdata;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::fields::group_identity_attachment::GroupIdentityAttachment;
+use tetra_pdus::mm::fields::group_identity_downlink::GroupIdentityDownlink;
+use tetra_pdus::mm::fields::group_identity_location_accept::GroupIdentityLocationAccept;
+use tetra_pdus::mm::fields::group_identity_location_demand::GroupIdentityLocationDemand;
+use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::u_itsi_detach::UItsiDetach;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_pdus::umac::enums::reservation_requirement::ReservationRequirement;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_data::MacData;
+use tetra_pdus::umac::pdus::mac_end_hu::MacEndHu;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+
+use crate::common::{ComponentTest, shared_log::SharedLog};
+
+const MS_ISSI: u32 = 7015011;
+const GSSI: u32 = 91;
+
+fn mon_test_stack(dltime: TdmaTime) -> (ComponentTest, SharedLog) {
+    let mut test = ComponentTest::new(StackMode::Mon, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Lmac], vec![]);
+    let log = SharedLog::default();
+    let umac = UmacMon::new(test.config.clone(), EventLog::new(Box::new(log.clone())));
+    test.register_entity(umac);
+    (test, log)
+}
+
+fn block_msg(dltime: TdmaTime, pdu: BitBuffer, logical_channel: LogicalChannel, direction: Direction) -> SapMsg {
+    let block_num = match logical_channel {
+        LogicalChannel::SchF => PhyBlockNum::Both,
+        _ => PhyBlockNum::Block1,
+    };
+    SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu,
+            block_num,
+            logical_channel,
+            crc_pass: true,
+            scrambling_code: 0,
+            direction,
+        }),
+    }
+}
+
+/// Wraps an MM or CMCE PDU in a BL-UDATA and MLE header
+fn tm_sdu(disc: MleProtocolDiscriminator, mut pdu: BitBuffer) -> BitBuffer {
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    BlUdata { has_fcs: false }.to_bitbuf(&mut sdu);
+    sdu.write_bits(disc as u64, 3);
+    let pdu_len = pdu.get_len();
+    sdu.copy_bits(&mut pdu, pdu_len);
+    sdu.seek(0);
+    sdu
+}
+
+fn ssi(ssi: u32) -> TetraAddress {
+    TetraAddress::new(ssi, SsiType::Ssi)
+}
+
+/// A SCH/F downlink block with a MAC-RESOURCE carrying the SDU, followed by a null PDU
+fn dl_resource_block(addr: TetraAddress, mut sdu: BitBuffer) -> BitBuffer {
+    let mut pdu = MacResource {
+        addr: Some(addr),
+        ..Default::default()
+    };
+    let num_fill_bits = pdu.update_len_and_fill_ind(sdu.get_len());
+    let mut block = BitBuffer::new(268);
+    pdu.to_bitbuf(&mut block);
+    let sdu_len = sdu.get_len();
+    block.copy_bits(&mut sdu, sdu_len);
+    fillbits::addition::write(&mut block, Some(num_fill_bits));
+    MacResource::null_pdu().to_bitbuf(&mut block);
+    block.seek(0);
+    block
+}
+
+/// A SCH/HU uplink block with a MAC-ACCESS carrying the SDU, followed by a null PDU
+fn ul_access_block(addr: TetraAddress, mut sdu: BitBuffer) -> BitBuffer {
+    let len_bits = 36 + sdu.get_len();
+    let num_fill_bits = (8 - len_bits % 8) % 8;
+    let pdu = MacAccess {
+        fill_bits: num_fill_bits > 0,
+        encrypted: false,
+        addr: Some(addr),
+        event_label: None,
+        length_ind: Some(len_bits.div_ceil(8) as u8),
+        frag_flag: None,
+        reservation_req: None,
+    };
+    let mut block = BitBuffer::new(92);
+    pdu.to_bitbuf(&mut block);
+    let sdu_len = sdu.get_len();
+    block.copy_bits(&mut sdu, sdu_len);
+    fillbits::addition::write(&mut block, Some(num_fill_bits));
+    if block.get_len_remaining() >= 36 {
+        MacAccess {
+            fill_bits: false,
+            length_ind: Some(0),
+            ..pdu
+        }
+        .to_bitbuf(&mut block);
+    }
+    block.seek(0);
+    block
+}
+
+/// A SCH/F uplink block with a MAC-DATA carrying the SDU, padded to the end of the block
+fn ul_data_block(addr: TetraAddress, mut sdu: BitBuffer) -> BitBuffer {
+    let len_bits = 37 + sdu.get_len();
+    let num_fill_bits = (8 - len_bits % 8) % 8;
+    let pdu = MacData {
+        fill_bits: num_fill_bits > 0,
+        encrypted: false,
+        addr: Some(addr),
+        event_label: None,
+        length_ind: Some(len_bits.div_ceil(8) as u8),
+        frag_flag: None,
+        reservation_req: None,
+    };
+    let mut block = BitBuffer::new(268);
+    pdu.to_bitbuf(&mut block);
+    let sdu_len = sdu.get_len();
+    block.copy_bits(&mut sdu, sdu_len);
+    fillbits::addition::write(&mut block, Some(num_fill_bits));
+    block.seek(0);
+    block
+}
+
+fn d_location_update_accept() -> BitBuffer {
+    let pdu = DLocationUpdateAccept {
+        location_update_accept_type: LocationUpdateType::ItsiAttach,
+        ssi: None,
+        address_extension: None,
+        subscriber_class: None,
+        energy_saving_information: None,
+        scch_information_and_distribution_on_18th_frame: None,
+        new_registered_area: None,
+        security_downlink: None,
+        group_identity_location_accept: Some(GroupIdentityLocationAccept {
+            group_identity_accept_reject: 0,
+            group_identity_downlink: Some(vec![GroupIdentityDownlink {
+                group_identity_attachment: Some(GroupIdentityAttachment {
+                    group_identity_attachment_lifetime: 3,
+                    class_of_usage: 4,
+                }),
+                group_identity_detachment_uplink: None,
+                gssi: Some(GSSI),
+                address_extension: None,
+                vgssi: None,
+            }]),
+        }),
+        default_group_attachment_lifetime: None,
+        authentication_downlink: None,
+        group_identity_security_related_information: None,
+        cell_type_control: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+fn d_setup(call_identifier: u16, calling_ssi: u32) -> BitBuffer {
+    let pdu = DSetup {
+        call_identifier,
+        call_time_out: CallTimeout::T5m,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        transmission_grant: TransmissionGrant::GrantedToOtherUser,
+        transmission_request_permission: false,
+        call_priority: 0,
+        notification_indicator: None,
+        temporary_address: None,
+        calling_party_address_ssi: Some(calling_ssi),
+        calling_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+fn u_location_update_demand() -> BitBuffer {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: Some(0x4a5a5a),
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: Some(GroupIdentityLocationDemand {
+            group_identity_attach_detach_mode: 1,
+            group_identity_uplink: Some(vec![GroupIdentityUplink {
+                class_of_usage: Some(4),
+                group_identity_detachment_uplink: None,
+                gssi: Some(GSSI),
+                address_extension: None,
+                vgssi: None,
+            }]),
+        }),
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+fn u_itsi_detach() -> BitBuffer {
+    let pdu = UItsiDetach {
+        address_extension: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+fn u_sds_data(dest_ssi: u32, payload: u16) -> BitBuffer {
+    let pdu = USdsData {
+        area_selection: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_short_number_address: None,
+        called_party_ssi: Some(dest_ssi as u64),
+        called_party_extension: None,
+        user_defined_data: SdsUserData::Type1(payload),
+        external_subscriber_number: None,
+        dm_ms_address: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    sdu
+}
+
+#[test]
+/// The SYNC is logged once per cell, and configures the LMAC for the cell's scrambling code
+fn test_monitor_cell_sync() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default();
+    let mut test = ComponentTest::new(StackMode::Mon, Some(dltime));
+    test.populate_entities(vec![], vec![TetraEntity::Lmac]);
+    let log = SharedLog::default();
+    test.register_entity(UmacMon::new(test.config.clone(), EventLog::new(Box::new(log.clone()))));
+
+    // SYNC CC 1 TN 4 FN 11 MN 9 MCC 420 MNC 555
+    let sync = "000100000111010110010010000000001101001000000100010101110011";
+    test.submit_message(block_msg(dltime, BitBuffer::from_bitstr(sync), LogicalChannel::Bsch, Direction::Dl));
+    test.deliver_all_messages();
+
+    let events = log.take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["event"], "cell_sync");
+    assert_eq!(events[0]["link"], "dl");
+    assert_eq!(events[0]["mcc"], 420);
+    assert_eq!(events[0]["mnc"], 555);
+
+    let msgs = test.dump_sinks();
+    let configs: Vec<_> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TmvConfigureReq(prim) => Some(prim),
+            _ => None,
+        })
+        .collect();
+    assert!(configs.iter().any(|c| c.time.is_some()), "time not configured");
+    assert!(
+        configs.iter().any(|c| c.scrambling_code.is_some()),
+        "scrambling code not configured"
+    );
+
+    // The same cell again is not a new event
+    test.submit_message(block_msg(dltime, BitBuffer::from_bitstr(sync), LogicalChannel::Bsch, Direction::Dl));
+    test.deliver_all_messages();
+    assert!(log.take_events().is_empty());
+}
+
+#[test]
+/// Downlink signalling to any address is decoded and logged
+fn test_monitor_dl_signalling() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default();
+    let (mut test, log) = mon_test_stack(dltime);
+
+    let sdu = tm_sdu(MleProtocolDiscriminator::Mm, d_location_update_accept());
+    let block = dl_resource_block(ssi(MS_ISSI), sdu);
+    test.submit_message(block_msg(dltime, block, LogicalChannel::SchF, Direction::Dl));
+    test.deliver_all_messages();
+
+    let sdu = tm_sdu(MleProtocolDiscriminator::Cmce, d_setup(7, 1000));
+    let block = dl_resource_block(ssi(GSSI), sdu);
+    test.submit_message(block_msg(dltime.add_timeslots(4), block, LogicalChannel::SchF, Direction::Dl));
+    test.deliver_all_messages();
+
+    let events = log.take_events();
+    assert_eq!(events.len(), 2, "{:?}", events);
+    assert_eq!(events[0]["event"], "registration_accept");
+    assert_eq!(events[0]["issi"], MS_ISSI);
+    assert_eq!(events[0]["accept_type"], "ItsiAttach");
+    assert_eq!(events[0]["groups"], serde_json::json!([GSSI]));
+
+    assert_eq!(events[1]["event"], "call_setup");
+    assert_eq!(events[1]["link"], "dl");
+    assert_eq!(events[1]["call_id"], 7);
+    assert_eq!(events[1]["called"], GSSI);
+    assert_eq!(events[1]["calling"], 1000);
+}
+
+#[test]
+/// Uplink MAC-ACCESS and MAC-DATA PDUs are decoded and logged with the sender's address
+fn test_monitor_ul_signalling() {
+    debug::setup_logging_verbose();
+    let ultime = TdmaTime::default();
+    let (mut test, log) = mon_test_stack(ultime.add_timeslots(2));
+
+    let sdu = tm_sdu(MleProtocolDiscriminator::Mm, u_itsi_detach());
+    let block = ul_access_block(ssi(MS_ISSI), sdu);
+    test.submit_message(block_msg(ultime, block, LogicalChannel::SchHu, Direction::Ul));
+    test.deliver_all_messages();
+
+    let sdu = tm_sdu(MleProtocolDiscriminator::Cmce, u_sds_data(1000, 0xCAFE));
+    let block = ul_data_block(ssi(MS_ISSI), sdu);
+    test.submit_message(block_msg(ultime.add_timeslots(4), block, LogicalChannel::SchF, Direction::Ul));
+    test.deliver_all_messages();
+
+    let events = log.take_events();
+    assert_eq!(events.len(), 2, "{:?}", events);
+    assert_eq!(events[0]["event"], "detach");
+    assert_eq!(events[0]["link"], "ul");
+    assert_eq!(events[0]["issi"], MS_ISSI);
+
+    assert_eq!(events[1]["event"], "sds");
+    assert_eq!(events[1]["link"], "ul");
+    assert_eq!(events[1]["calling"], MS_ISSI);
+    assert_eq!(events[1]["called"], 1000);
+    assert_eq!(events[1]["sds_type"], 1);
+    assert_eq!(events[1]["data"], "cafe");
+}
+
+#[test]
+/// A registration fragmented over a MAC-ACCESS and a MAC-END-HU is reassembled before decoding
+fn test_monitor_ul_fragmented() {
+    debug::setup_logging_verbose();
+    let ultime = TdmaTime::default();
+    let (mut test, log) = mon_test_stack(ultime.add_timeslots(2));
+
+    let mut sdu = tm_sdu(MleProtocolDiscriminator::Mm, u_location_update_demand());
+    let first_len = 92 - 36;
+    assert!(sdu.get_len() > first_len && sdu.get_len() - first_len <= 92 - 7);
+
+    // MAC-ACCESS fragmentation start, the SDU fills the rest of the subslot
+    let mut block = BitBuffer::new(92);
+    MacAccess {
+        fill_bits: false,
+        encrypted: false,
+        addr: Some(ssi(MS_ISSI)),
+        event_label: None,
+        length_ind: None,
+        frag_flag: Some(true),
+        reservation_req: Some(ReservationRequirement::Req1Subslot),
+    }
+    .to_bitbuf(&mut block);
+    block.copy_bits(&mut sdu, first_len);
+    block.seek(0);
+    test.submit_message(block_msg(ultime, block, LogicalChannel::SchHu, Direction::Ul));
+    test.deliver_all_messages();
+    assert!(log.take_events().is_empty());
+
+    // MAC-END-HU with the remainder in the granted subslot of the next frame
+    let rest_len = sdu.get_len_remaining();
+    let len_bits = 7 + rest_len;
+    let num_fill_bits = (8 - len_bits % 8) % 8;
+    let mut block = BitBuffer::new(92);
+    MacEndHu {
+        fill_bits: num_fill_bits > 0,
+        length_ind: Some(len_bits.div_ceil(8) as u8),
+        reservation_req: None,
+    }
+    .to_bitbuf(&mut block);
+    block.copy_bits(&mut sdu, rest_len);
+    fillbits::addition::write(&mut block, Some(num_fill_bits));
+    block.seek(0);
+    test.submit_message(block_msg(ultime.add_timeslots(4), block, LogicalChannel::SchHu, Direction::Ul));
+    test.deliver_all_messages();
+
+    let events = log.take_events();
+    assert_eq!(events.len(), 1, "{:?}", events);
+    assert_eq!(events[0]["event"], "registration_demand");
+    assert_eq!(events[0]["issi"], MS_ISSI);
+    assert_eq!(events[0]["update_type"], "ItsiAttach");
+    assert_eq!(events[0]["groups"], serde_json::json!([GSSI]));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tetra_config::bluestation::{CfgFileIo, CfgMonitor, IqFormat, PhyBackend, StackConfig, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_entities::monitor::EventLog;
+use tetra_entities::phy::components::file_dev::RxTxDevFile;
+use tetra_entities::phy::phy_bs::PhyBs;
+use tetra_entities::phy::phy_mon::PhyMon;
+use tetra_entities::umac::umac_mon::UmacMon;
+
+use crate::common::{ComponentTest, default_stack::default_test_config_bs, shared_log::SharedLog};
+
+fn temp_path(name: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    std::env::temp_dir().join(format!("test_phy_file_{}_{}.cf32", name, nanos))
+}
+
+fn file_config(mode: StackMode, rx_file: Option<&PathBuf>, tx_file: Option<&PathBuf>) -> StackConfig {
+    let mut config = default_test_config_bs();
+    config.stack_mode = mode;
+    config.phy_io.backend = PhyBackend::File;
+    config.phy_io.file = Some(CfgFileIo {
+        rx_file: rx_file.map(|p| p.to_string_lossy().into_owned()),
+        tx_file: tx_file.map(|p| p.to_string_lossy().into_owned()),
+        format: IqFormat::Cf32,
+        sample_rate: 256e3,
+        rx_center_freq: None,
+        tx_center_freq: None,
+        repeat: false,
+    });
+    config
+}
+
+#[test]
+fn test_file_bs_to_monitor() {
+    // Two multiframes of BS downlink, written to a file
+    let dl_file = temp_path("dl");
+    {
+        let config = file_config(StackMode::Bs, None, Some(&dl_file));
+        let mut test = ComponentTest::from_config(config, None);
+        test.populate_entities(
+            vec![
+                TetraEntity::Lmac,
+                TetraEntity::Umac,
+                TetraEntity::Llc,
+                TetraEntity::Mle,
+                TetraEntity::Mm,
+                TetraEntity::Cmce,
+            ],
+            vec![],
+        );
+        let cfg = test.get_shared_config();
+        let dev = RxTxDevFile::new(&cfg, None).expect("failed to open BS TX file");
+        test.register_entity(PhyBs::new(cfg, dev));
+        test.run_stack(Some(4 * 18 * 2));
+        // Dropping the stack flushes the TX file
+    }
+    assert!(std::fs::metadata(&dl_file).unwrap().len() > 0, "BS did not write any samples");
+
+    // Replay it on a downlink-only monitor, which has to synchronize to the cell
+    let mut config = file_config(StackMode::Mon, Some(&dl_file), None);
+    config.monitor = Some(CfgMonitor {
+        uplink: false,
+        event_log: None,
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Lmac], vec![]);
+    let log = SharedLog::default();
+    let cfg = test.get_shared_config();
+    test.register_entity(UmacMon::new(cfg.clone(), EventLog::new(Box::new(log.clone()))));
+    let dev = RxTxDevFile::new(&cfg, None).expect("failed to open monitor RX file");
+    test.register_entity(PhyMon::new(cfg, dev));
+    test.run_stack(Some(4 * 18 * 2));
+
+    let events = log.take_events();
+    std::fs::remove_file(&dl_file).ok();
+    let sync = events
+        .iter()
+        .find(|e| e["event"] == "cell_sync")
+        .expect("monitor did not synchronize to the replayed cell");
+    assert_eq!(sync["mcc"], 204);
+    assert_eq!(sync["mnc"], 1337);
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use std::thread;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, TdmaTime, TrainingSequence};
+use tetra_entities::lmac::components::errorcontrol;
+use tetra_entities::phy::components::burst_consts::*;
+use tetra_entities::phy::components::channel_sim::ChannelParams;
+use tetra_entities::phy::components::loopback_dev::{LinkEnd, LoopbackChannel, RxTxDevLoopback};
+use tetra_entities::phy::components::slotter;
+use tetra_entities::phy::phy_bs::PhyBs;
+use tetra_entities::phy::phy_ms::PhyMs;
+use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, RxTxDevError, TxSlotBits};
+use tetra_saps::sapmsg::SapMsgInner;
+use tetra_saps::tmv::TmvUnitdataReq;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::tp::TpUnitdataInd;
+
+use crate::common::ComponentTest;
+
+const SAMPLE_RATE: f64 = 256e3;
+
+/// Runs a full BS and MS stack over the link for the given number of slots,
+/// returning what reached the MS user
+fn run_bs_ms(channel: LoopbackChannel, num_ticks: usize) -> Vec<SapMsgInner> {
+    let bs_io = channel.end(LinkEnd::Bs);
+    let ms_io = channel.end(LinkEnd::Ms);
+
+    let bs = thread::spawn(move || {
+        let mut test = ComponentTest::new(StackMode::Bs, None);
+        test.populate_entities(
+            vec![
+                TetraEntity::Lmac,
+                TetraEntity::Umac,
+                TetraEntity::Llc,
+                TetraEntity::Mle,
+                TetraEntity::Mm,
+                TetraEntity::Cmce,
+            ],
+            vec![],
+        );
+        let cfg = test.get_shared_config();
+        test.register_entity(PhyBs::new(cfg.clone(), RxTxDevLoopback::new(&cfg, bs_io)));
+        test.run_stack(Some(num_ticks));
+    });
+
+    let ms = thread::spawn(move || {
+        let mut test = ComponentTest::new(StackMode::Ms, None);
+        test.populate_entities(
+            vec![
+                TetraEntity::Lmac,
+                TetraEntity::Umac,
+                TetraEntity::Llc,
+                TetraEntity::Mle,
+                TetraEntity::Mm,
+                TetraEntity::Cmce,
+            ],
+            vec![TetraEntity::User],
+        );
+        let cfg = test.get_shared_config();
+        test.register_entity(PhyMs::new(cfg.clone(), RxTxDevLoopback::new(&cfg, ms_io)));
+        test.run_stack(Some(num_ticks));
+        test.dump_sinks().into_iter().map(|m| m.msg).collect::<Vec<_>>()
+        // Dropping the stack closes the link, so the BS does not wait for the MS anymore
+    });
+
+    bs.join().expect("BS stack panicked");
+    ms.join().expect("MS stack panicked")
+}
+
+fn assert_registered(user_msgs: &[SapMsgInner]) {
+    let registered = user_msgs.iter().any(|msg| match msg {
+        SapMsgInner::TnmmRegistrationInd(ind) => ind.registered && ind.groups.contains(&91),
+        _ => false,
+    });
+    assert!(registered, "MS did not register, got {:?}", user_msgs);
+}
+
+#[test]
+fn test_loopback_ms_registers() {
+    let channel = LoopbackChannel::new(SAMPLE_RATE, &ChannelParams::default(), &ChannelParams::default());
+    assert_registered(&run_bs_ms(channel, 4 * 18 * 4));
+}
+
+#[test]
+fn test_loopback_ms_registers_impaired() {
+    // Noisy link with a 1 ppm frequency error at 400 MHz and a propagation delay of about 4 km
+    let dl = ChannelParams {
+        es_n0_db: Some(20.0),
+        freq_offset_hz: 400.0,
+        delay_samples: 3,
+        seed: 1,
+        ..Default::default()
+    };
+    let ul = ChannelParams {
+        freq_offset_hz: -400.0,
+        seed: 2,
+        ..dl.clone()
+    };
+    let channel = LoopbackChannel::new(SAMPLE_RATE, &dl, &ul);
+    assert_registered(&run_bs_ms(channel, 4 * 18 * 4));
+}
+
+/// Scrambling code of the SCH/HD blocks sent in error rate measurements
+const MEASURE_SCRAMBLING_CODE: u32 = 0x1234567;
+
+/// Demodulated slots ignored at the start of a measurement, while the MS synchronizes
+const MEASURE_WARMUP_SLOTS: usize = 8;
+
+#[derive(Debug, Default)]
+struct ErrorRates {
+    bits: usize,
+    bit_errors: usize,
+    frames: usize,
+    frame_errors: usize,
+}
+
+impl ErrorRates {
+    /// NaN if no burst was detected
+    fn ber(&self) -> f64 {
+        self.bit_errors as f64 / self.bits as f64
+    }
+
+    /// 1 if the MS did not even synchronize
+    fn fer(&self) -> f64 {
+        if self.frames == 0 {
+            1.0
+        } else {
+            self.frame_errors as f64 / self.frames as f64
+        }
+    }
+}
+
+fn random_bits(len: usize) -> Vec<u8> {
+    (0..len).map(|_| rand::random_range(0..2)).collect()
+}
+
+/// Measures error rates on the downlink through the modem and the channel, without the upper stack.
+///
+/// The BS sends synchronization bursts carrying the same random SCH/HD block in every slot.
+/// The bit error rate is that of the demodulated SCH/HD type-5 bits, the frame error rate counts
+/// the demodulated slots in which the block could not be decoded, including slots without a detected burst.
+fn measure_dl_error_rates(params: &ChannelParams, num_slots: usize) -> ErrorRates {
+    let channel = LoopbackChannel::new(SAMPLE_RATE, params, &ChannelParams::default());
+    let bs_io = channel.end(LinkEnd::Bs);
+    let ms_io = channel.end(LinkEnd::Ms);
+
+    let type1 = random_bits(124);
+    let type5 = errorcontrol::encode_cp(TmvUnitdataReq {
+        mac_block: BitBuffer::from_bitarr(&type1),
+        logical_channel: LogicalChannel::SchHd,
+        scrambling_code: MEASURE_SCRAMBLING_CODE,
+    });
+    let mut blk2 = [0u8; SB_BLK2_BITS];
+    type5.clone().to_bitarr(&mut blk2);
+    let blk1: [u8; SB_BLK1_BITS] = random_bits(SB_BLK1_BITS).try_into().unwrap();
+    let bbk: [u8; SB_BBK_BITS] = random_bits(SB_BBK_BITS).try_into().unwrap();
+    let burst = slotter::build_sdb(&blk1, &bbk, &blk2);
+
+    let bs = thread::spawn(move || {
+        let cfg = ComponentTest::new(StackMode::Bs, None).get_shared_config();
+        let mut dev = RxTxDevLoopback::new(&cfg, bs_io);
+        for slot in 0..num_slots {
+            let tx_slot = [TxSlotBits {
+                time: TdmaTime::default().add_timeslots(slot as i32 + 1),
+                slot: Some(&burst),
+                ..Default::default()
+            }];
+            dev.rxtx_timeslot(&tx_slot).unwrap();
+        }
+    });
+
+    let cfg = ComponentTest::new(StackMode::Ms, None).get_shared_config();
+    let mut dev = RxTxDevLoopback::new(&cfg, ms_io);
+    let mut rates = ErrorRates::default();
+    let mut slots = 0;
+    loop {
+        let rx = match dev.rxtx_timeslot(&[]) {
+            Ok(rx) => rx,
+            Err(RxTxDevError::RxEndOfData) => break,
+            Err(e) => panic!("MS receive failed: {:?}", e),
+        };
+        let Some(Some(rx_slot)) = rx.into_iter().next() else {
+            continue;
+        };
+        slots += 1;
+        if slots <= MEASURE_WARMUP_SLOTS {
+            continue;
+        }
+
+        rates.frames += 1;
+        if rx_slot.slot.train_type != TrainingSequence::SyncTrainSeq {
+            rates.frame_errors += 1;
+            continue;
+        }
+        let rx_blk2 = &rx_slot.slot.bits[SB_BLK2_OFFSET..SB_BLK2_OFFSET + SB_BLK2_BITS];
+        rates.bits += SB_BLK2_BITS;
+        rates.bit_errors += rx_blk2.iter().zip(blk2.iter()).filter(|(a, b)| a != b).count();
+
+        let (decoded, crc_ok) = errorcontrol::decode_cp(
+            LogicalChannel::SchHd,
+            TpUnitdataInd {
+                train_type: TrainingSequence::SyncTrainSeq,
+                burst_type: BurstType::SDB,
+                block_type: PhyBlockType::SB2,
+                block_num: PhyBlockNum::Block2,
+                block: BitBuffer::from_bitarr(rx_blk2),
+                carrier: 0,
+            },
+            Some(MEASURE_SCRAMBLING_CODE),
+        );
+        let correct = decoded.is_some_and(|mut decoded| {
+            let mut bits = vec![0u8; type1.len()];
+            decoded.seek(0);
+            decoded.to_bitarr(&mut bits);
+            bits == type1
+        });
+        if !(crc_ok && correct) {
+            rates.frame_errors += 1;
+        }
+    }
+    bs.join().expect("BS modem panicked");
+    rates
+}
+
+#[test]
+fn test_loopback_dl_error_rates() {
+    let clean = measure_dl_error_rates(&ChannelParams::default(), 100);
+    assert!(clean.frames > 80, "only {} slots demodulated", clean.frames);
+    assert_eq!((clean.bit_errors, clean.frame_errors), (0, 0), "{:?}", clean);
+
+    let noisy = measure_dl_error_rates(
+        &ChannelParams {
+            es_n0_db: Some(10.0),
+            seed: 3,
+            ..Default::default()
+        },
+        100,
+    );
+    // Raw bit errors, mostly corrected by the channel coding
+    assert!(noisy.ber() > 1e-3, "{:?}", noisy);
+    assert!(noisy.fer() < 0.5, "{:?}", noisy);
+}
+
+/// Prints BER and FER curves over Es/N0, for static and fading channels.
+/// Run with `cargo test --release --test test_phy_loopback -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn loopback_dl_error_rate_curves() {
+    for doppler in [None, Some(50.0)] {
+        println!("Rayleigh fading Doppler {:?} Hz", doppler);
+        println!("Es/N0 dB       BER       FER");
+        for es_n0_db in (0..=20).step_by(2) {
+            let rates = measure_dl_error_rates(
+                &ChannelParams {
+                    es_n0_db: Some(es_n0_db as f64),
+                    rayleigh_doppler_hz: doppler,
+                    seed: es_n0_db as u64,
+                    ..Default::default()
+                },
+                1000,
+            );
+            println!("{:8} {:9.2e} {:9.2e}", es_n0_db, rates.ber(), rates.fer());
+        }
+    }
+}
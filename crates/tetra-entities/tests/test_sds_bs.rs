@@ -2,13 +2,22 @@ mod common;
 
 use std::time::Duration;
 
-use tetra_config::bluestation::{CfgBrew, StackMode};
+use tetra_config::bluestation::{CfgBrew, CfgSdsStore, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, debug};
+use tetra_pdus::cmce::enums::delivery_report_request::DeliveryReportRequest;
+use tetra_pdus::cmce::enums::delivery_status::DeliveryStatus;
 use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
 use tetra_pdus::cmce::enums::pre_coded_status::PreCodedStatus;
+use tetra_pdus::cmce::enums::sds_protocol_id::SdsProtocolId;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
 use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
 use tetra_pdus::cmce::pdus::u_status::UStatus;
+use tetra_pdus::cmce::sds_tl::SdsTlPdu;
+use tetra_pdus::cmce::sds_tl::sds_report::SdsReport;
+use tetra_pdus::cmce::sds_tl::sds_transfer::SdsTransfer;
+use tetra_pdus::cmce::sds_tl::user_data_header::{Concatenation, UserDataHeader};
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
 use tetra_saps::control::enums::sds_user_data::SdsUserData;
 use tetra_saps::control::sds::CmceSdsData;
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
@@ -56,6 +65,7 @@ fn build_u_sds_data_msg(dltime: TdmaTime, source_issi: u32, dest_ssi: u32, paylo
             received_tetra_address: TetraAddress::new(source_issi, SsiType::Issi),
             chan_change_resp_req: false,
             chan_change_handle: None,
+            chan_alloc: None,
         }),
     }
 }
@@ -123,6 +133,7 @@ fn test_sds_brew_forward() {
         reconnect_delay: Duration::from_secs(1),
         jitter_initial_latency_frames: 0,
         feature_sds_enabled: true,
+        feature_packet_data_enabled: false,
         whitelisted_ssis: None,
     });
     let mut test = ComponentTest::from_config(config, Some(dltime));
@@ -292,6 +303,7 @@ fn test_u_status_forwarded_as_d_status() {
             received_tetra_address: TetraAddress::new(1000001, SsiType::Issi),
             chan_change_resp_req: false,
             chan_change_handle: None,
+            chan_alloc: None,
         }),
     };
     test.submit_message(msg);
@@ -328,6 +340,7 @@ fn test_u_status_brew_forward() {
         reconnect_delay: Duration::from_secs(1),
         jitter_initial_latency_frames: 0,
         feature_sds_enabled: true,
+        feature_packet_data_enabled: false,
         whitelisted_ssis: None,
     });
     let mut test = ComponentTest::from_config(config, Some(dltime));
@@ -367,6 +380,7 @@ fn test_u_status_brew_forward() {
             received_tetra_address: TetraAddress::new(1000001, SsiType::Issi),
             chan_change_resp_req: false,
             chan_change_handle: None,
+            chan_alloc: None,
         }),
     };
     test.submit_message(msg);
@@ -435,6 +449,7 @@ fn test_u_status_unregistered_dest_dropped() {
             received_tetra_address: TetraAddress::new(1000001, SsiType::Issi),
             chan_change_resp_req: false,
             chan_change_handle: None,
+            chan_alloc: None,
         }),
     };
     test.submit_message(msg);
@@ -444,3 +459,372 @@ fn test_u_status_unregistered_dest_dropped() {
     let d_status_count = count_d_sds_data(&sink_msgs);
     assert_eq!(d_status_count, 0, "Should not deliver D-STATUS when dest is not registered");
 }
+
+/// Helper: test stack with SDS store-and-forward enabled, with the given validity period
+fn sds_store_test_stack(dltime: TdmaTime, validity_period_secs: u64) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.sds_store = Some(CfgSdsStore {
+        validity_period: Duration::from_secs(validity_period_secs),
+        max_messages_per_issi: 4,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Brew]);
+    test
+}
+
+/// Helper: build a U-SDS-DATA carrying the given type 4 user data
+fn build_u_sds_data_type4_msg(dltime: TdmaTime, source_issi: u32, dest_ssi: u32, data: Vec<u8>) -> SapMsg {
+    let msg = build_u_sds_data_msg(dltime, source_issi, dest_ssi, 0);
+    let SapMsgInner::LcmcMleUnitdataInd(mut prim) = msg.msg else {
+        unreachable!()
+    };
+    let mut pdu = USdsData::from_bitbuf(&mut prim.sdu).unwrap();
+    pdu.user_defined_data = SdsUserData::Type4(data.len() as u16 * 8, data);
+    let mut sdu = BitBuffer::new_autoexpand(120);
+    pdu.to_bitbuf(&mut sdu).expect("Failed to serialize U-SDS-DATA");
+    sdu.seek(0);
+    prim.sdu = sdu;
+    SapMsg {
+        msg: SapMsgInner::LcmcMleUnitdataInd(prim),
+        ..msg
+    }
+}
+
+/// Helper: build a U-SDS-DATA carrying an SDS-TL SDS-TRANSFER text message requesting a
+/// received and consumed report
+fn build_sds_tl_transfer_msg(dltime: TdmaTime, source_issi: u32, dest_ssi: u32, message_reference: u8) -> SapMsg {
+    build_u_sds_data_type4_msg(dltime, source_issi, dest_ssi, vec![0x82, 0x0C, message_reference, 0x01, b'h', b'i'])
+}
+
+/// Helper: build a U-STATUS from a source ISSI to a dest SSI
+fn build_u_status_msg(dltime: TdmaTime, source_issi: u32, dest_ssi: u32, pre_coded_status: u16) -> SapMsg {
+    let u_status = UStatus {
+        area_selection: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_short_number_address: None,
+        called_party_ssi: Some(dest_ssi as u64),
+        called_party_extension: None,
+        pre_coded_status: PreCodedStatus::from(pre_coded_status),
+        external_subscriber_number: None,
+        dm_ms_address: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_status.to_bitbuf(&mut sdu).expect("Failed to serialize U-STATUS");
+    sdu.seek(0);
+
+    let msg = build_u_sds_data_msg(dltime, source_issi, dest_ssi, 0);
+    let SapMsgInner::LcmcMleUnitdataInd(mut prim) = msg.msg else {
+        unreachable!()
+    };
+    prim.sdu = sdu;
+    SapMsg {
+        msg: SapMsgInner::LcmcMleUnitdataInd(prim),
+        ..msg
+    }
+}
+
+/// Helper: (calling ISSI, user data) of the D-SDS-DATA PDUs sent to the given ISSI, with their tx reporters
+fn d_sds_data_to(msgs: &mut [SapMsg], issi: u32) -> Vec<(u32, SdsUserData, Option<TxReporter>)> {
+    msgs.iter_mut()
+        .filter_map(|m| match &mut m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.main_address.ssi == issi => {
+                let pdu = DSdsData::from_bitbuf(&mut prim.sdu.clone()).ok()?;
+                Some((
+                    pdu.calling_party_address_ssi? as u32,
+                    pdu.user_defined_data,
+                    prim.tx_reporter.take(),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Helper: register an ISSI the way MM does, updating the registry and notifying CMCE
+fn register_with_cmce(test: &mut ComponentTest, dltime: TdmaTime, issi: u32) {
+    register_subscriber(test, issi);
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi,
+            groups: vec![],
+            action: BrewSubscriberAction::Register,
+        }),
+    });
+}
+
+/// An SDS for an unregistered ISSI is stored, the sender is told so in an SDS-REPORT, and the
+/// message is delivered once the destination registers.
+#[test]
+fn test_sds_store_and_forward_on_registration() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = sds_store_test_stack(dltime, 3600);
+    register_subscriber(&mut test, 1000001);
+
+    test.submit_message(build_sds_tl_transfer_msg(dltime, 1000001, 2000001, 7));
+    test.run_stack(Some(1));
+    let mut msgs = test.dump_sinks();
+    assert!(d_sds_data_to(&mut msgs, 2000001).is_empty());
+    let reports = d_sds_data_to(&mut msgs, 1000001);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].0, 2000001, "Report is sent on behalf of the destination");
+    assert_eq!(reports[0].1, SdsUserData::Type4(32, vec![0x82, 0x10, 0x22, 7]));
+
+    // Nothing is delivered while the destination stays away
+    test.run_stack(Some(200));
+    assert!(d_sds_data_to(&mut test.dump_sinks(), 2000001).is_empty());
+
+    register_with_cmce(&mut test, dltime, 2000001);
+    test.run_stack(Some(100));
+    let delivered = d_sds_data_to(&mut test.dump_sinks(), 2000001);
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].0, 1000001);
+    assert_eq!(delivered[0].1, SdsUserData::Type4(48, vec![0x82, 0x0C, 7, 0x01, b'h', b'i']));
+}
+
+/// A stored message that is not delivered within the validity period expires, and the sender
+/// receives an SDS-REPORT saying so.
+#[test]
+fn test_sds_store_expiry() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = sds_store_test_stack(dltime, 1);
+    register_subscriber(&mut test, 1000001);
+
+    test.submit_message(build_sds_tl_transfer_msg(dltime, 1000001, 2000001, 9));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // One second is about 71 timeslots
+    test.run_stack(Some(100));
+    let reports = d_sds_data_to(&mut test.dump_sinks(), 1000001);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].1, SdsUserData::Type4(32, vec![0x82, 0x10, 0x48, 9]));
+
+    register_with_cmce(&mut test, dltime, 2000001);
+    test.run_stack(Some(100));
+    assert!(d_sds_data_to(&mut test.dump_sinks(), 2000001).is_empty());
+}
+
+/// A message a registered MS does not acknowledge is stored, and delivered again when the MS
+/// registers anew.
+#[test]
+fn test_sds_store_after_failed_delivery() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = sds_store_test_stack(dltime, 3600);
+    register_subscriber(&mut test, 1000001);
+    register_subscriber(&mut test, 2000001);
+
+    test.submit_message(build_u_sds_data_msg(dltime, 1000001, 2000001, 0x4242));
+    test.run_stack(Some(1));
+    let mut delivered = d_sds_data_to(&mut test.dump_sinks(), 2000001);
+    assert_eq!(delivered.len(), 1);
+    let reporter = delivered[0].2.take().expect("Delivery should be tracked");
+
+    // The radio went out of coverage
+    reporter.mark_transmitted();
+    reporter.mark_lost();
+    test.run_stack(Some(1));
+
+    register_with_cmce(&mut test, dltime, 2000001);
+    test.run_stack(Some(100));
+    let delivered = d_sds_data_to(&mut test.dump_sinks(), 2000001);
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].1, SdsUserData::Type1(0x4242));
+}
+
+/// A short report to a subscriber that left is stored as the equivalent SDS-REPORT.
+#[test]
+fn test_sds_store_short_report() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = sds_store_test_stack(dltime, 3600);
+    register_subscriber(&mut test, 2000001);
+
+    // SDS-SHORT REPORT: message received, MR 7
+    test.submit_message(build_u_status_msg(dltime, 2000001, 1000001, 0x7E07));
+    test.run_stack(Some(1));
+    assert!(d_sds_data_to(&mut test.dump_sinks(), 1000001).is_empty());
+
+    register_with_cmce(&mut test, dltime, 1000001);
+    test.run_stack(Some(100));
+    let delivered = d_sds_data_to(&mut test.dump_sinks(), 1000001);
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].0, 2000001);
+    assert_eq!(delivered[0].1, SdsUserData::Type4(32, vec![0x82, 0x10, 0x00, 7]));
+}
+
+/// Helper: Brew configuration with SDS enabled, connecting to the given host
+fn brew_sds_config(host: &str) -> CfgBrew {
+    CfgBrew {
+        host: host.into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: true,
+        feature_packet_data_enabled: false,
+        whitelisted_ssis: None,
+    }
+}
+
+/// Helper: the SDS sent to Brew, as (source ISSI, dest ISSI, user data)
+fn brew_sds(msgs: &[SapMsg]) -> Vec<(u32, u32, SdsUserData)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceSdsData(sds) if m.dest == TetraEntity::Brew => {
+                Some((sds.source_issi, sds.dest_issi, sds.user_defined_data.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Helper: an SDS-TL text message with user data header, requesting a received report
+fn sds_tl_udh_transfer(message_reference: u8, header: &UserDataHeader, text: &[u8]) -> SdsTransfer {
+    let mut user_data = header.to_bytes();
+    user_data.extend_from_slice(text);
+    SdsTransfer {
+        protocol_identifier: SdsProtocolId::MessageWithUserDataHeader.into_raw() as u8,
+        delivery_report_request: DeliveryReportRequest::MessageReceivedReportRequested,
+        short_form_report: false,
+        message_reference,
+        validity_period: None,
+        forward_address: None,
+        user_data,
+    }
+}
+
+/// The SwMI reports on a group SDS requesting a delivery report, as group members don't
+#[test]
+fn test_sds_tl_group_report() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Brew]);
+    register_subscriber(&mut test, 1000001);
+    affiliate_subscriber(&mut test, 2000001, 91);
+
+    test.submit_message(build_sds_tl_transfer_msg(dltime, 1000001, 91, 3));
+    test.run_stack(Some(1));
+    let mut msgs = test.dump_sinks();
+
+    assert_eq!(d_sds_data_to(&mut msgs, 91).len(), 1, "Expected group delivery");
+    let reports = d_sds_data_to(&mut msgs, 1000001);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].0, 91);
+    let report = SdsTlPdu::from_user_data(&reports[0].1).unwrap();
+    assert_eq!(
+        report,
+        SdsTlPdu::Report(SdsReport::new(0x82, DeliveryStatus::SentToGroupAcknowledgementsPrevented, 3))
+    );
+}
+
+/// A short report on a message from Brew is forwarded as an SDS-REPORT with the protocol of that message
+#[test]
+fn test_sds_tl_short_report_protocol() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(brew_sds_config("test.local"));
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Brew]);
+    register_subscriber(&mut test, 2000001);
+
+    // Immediate text message from the network, requesting a received report
+    let transfer = SdsTransfer {
+        protocol_identifier: SdsProtocolId::ImmediateTextMessagingSdsTl.into_raw() as u8,
+        delivery_report_request: DeliveryReportRequest::MessageReceivedReportRequested,
+        short_form_report: true,
+        message_reference: 0x21,
+        validity_period: None,
+        forward_address: None,
+        user_data: vec![0x01, b'h', b'i'],
+    };
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Brew,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::CmceSdsData(CmceSdsData {
+            source_issi: 5000001,
+            dest_issi: 2000001,
+            user_defined_data: SdsTlPdu::Transfer(transfer).to_user_data(),
+        }),
+    });
+    test.run_stack(Some(4));
+    assert_eq!(d_sds_data_to(&mut test.dump_sinks(), 2000001).len(), 1);
+
+    // The MS answers with a short report: message received, MR 0x21
+    test.submit_message(build_u_status_msg(dltime, 2000001, 5000001, 0x7E21));
+    test.run_stack(Some(1));
+    let forwarded = brew_sds(&test.dump_sinks());
+    assert_eq!(forwarded.len(), 1);
+    assert_eq!(
+        SdsTlPdu::from_user_data(&forwarded[0].2).unwrap(),
+        SdsTlPdu::Report(SdsReport::new(0x89, DeliveryStatus::ReceiptAcknowledgedByDestination, 0x21))
+    );
+}
+
+/// A concatenated message to a TetraPack service is reassembled, and handed to the service whole
+#[test]
+fn test_sds_tl_concatenated_to_service() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(brew_sds_config("core.tetrapack.online"));
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Brew]);
+    register_subscriber(&mut test, 1000001);
+
+    let part = |part_number: u8, text: &[u8]| {
+        let mut header = UserDataHeader::default();
+        header.set_concatenation(Concatenation {
+            reference: 0x42,
+            total_parts: 2,
+            part_number,
+        });
+        sds_tl_udh_transfer(10 + part_number, &header, text)
+    };
+
+    // The second part arrives first. The BS holds it and reports on it itself.
+    let data = SdsTlPdu::Transfer(part(2, b" world")).to_user_data().to_arr();
+    test.submit_message(build_u_sds_data_type4_msg(dltime, 1000001, 200999, data));
+    test.run_stack(Some(1));
+    let mut msgs = test.dump_sinks();
+    assert!(brew_sds(&msgs).is_empty(), "Parts should not reach the service");
+    let reports = d_sds_data_to(&mut msgs, 1000001);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(
+        SdsTlPdu::from_user_data(&reports[0].1).unwrap(),
+        SdsTlPdu::Report(SdsReport::new(0x8A, DeliveryStatus::ConcatenationPartReceived, 12))
+    );
+
+    let data = SdsTlPdu::Transfer(part(1, b"hello")).to_user_data().to_arr();
+    test.submit_message(build_u_sds_data_type4_msg(dltime, 1000001, 200999, data));
+    test.run_stack(Some(1));
+    let mut msgs = test.dump_sinks();
+    assert!(
+        d_sds_data_to(&mut msgs, 1000001).is_empty(),
+        "The service reports on the whole message"
+    );
+    let forwarded = brew_sds(&msgs);
+    assert_eq!(forwarded.len(), 1);
+    assert_eq!((forwarded[0].0, forwarded[0].1), (1000001, 200999));
+    let whole = sds_tl_udh_transfer(11, &UserDataHeader::default(), b"hello world");
+    assert_eq!(SdsTlPdu::from_user_data(&forwarded[0].2).unwrap(), SdsTlPdu::Transfer(whole));
+}
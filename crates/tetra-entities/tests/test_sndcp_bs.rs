@@ -0,0 +1,399 @@
+mod common;
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use tetra_config::bluestation::{CfgBrew, CfgSndcp, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::network::netentity::NetEntity;
+use tetra_entities::sndcp_net::SndcpWorkerChannel;
+use tetra_entities::sndcp_net::packet_interface::ChannelInterface;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::sndcp::enums::activation_reject_cause::ActivationRejectCause;
+use tetra_pdus::sndcp::enums::address_type::SndcpAddressType;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_accept::SnActivatePdpContextAccept;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_demand::SnActivatePdpContextDemand;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_reject::SnActivatePdpContextReject;
+use tetra_pdus::sndcp::pdus::sn_unitdata::SnUnitdata;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::sn::SnDataReq;
+use tetra_saps::tla::{TlaTlDataIndBl, TlaTlUnitdataIndBl};
+
+use crate::common::ComponentTest;
+
+const MS_ISSI: u32 = 1000001;
+
+fn sndcp_test(dltime: TdmaTime, pool_end: Ipv4Addr) -> ComponentTest {
+    sndcp_test_with_sinks(dltime, pool_end, vec![TetraEntity::Llc, TetraEntity::PacketGateway])
+}
+
+fn sndcp_test_with_sinks(dltime: TdmaTime, pool_end: Ipv4Addr, sinks: Vec<TetraEntity>) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.sndcp = Some(CfgSndcp {
+        ip_pool_start: Ipv4Addr::new(10, 20, 0, 10),
+        ip_pool_end: pool_end,
+        mtu: 1500,
+        tun_device: None,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mle, TetraEntity::Sndcp], sinks);
+    test
+}
+
+/// Prepend the SNDCP protocol discriminator to an SN-PDU, as the MS MLE would
+fn mle_sdu(sn_pdu: &BitBuffer) -> BitBuffer {
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    sdu.write_bits(MleProtocolDiscriminator::Sndcp.into_raw(), 3);
+    sdu.copy_bits(&mut BitBuffer::from_bitbuffer(sn_pdu), sn_pdu.get_len());
+    sdu.seek(0);
+    sdu
+}
+
+fn build_tl_data_ind(dltime: TdmaTime, issi: u32, sn_pdu: &BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::TlaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Mle,
+        dltime,
+        msg: SapMsgInner::TlaTlDataIndBl(TlaTlDataIndBl {
+            main_address: TetraAddress::new(issi, SsiType::Ssi),
+            link_id: 0,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: Some(mle_sdu(sn_pdu)),
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            req_handle: 0,
+        }),
+    }
+}
+
+fn build_tl_unitdata_ind(dltime: TdmaTime, issi: u32, sn_pdu: &BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::TlaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Mle,
+        dltime,
+        msg: SapMsgInner::TlaTlUnitdataIndBl(TlaTlUnitdataIndBl {
+            main_address: TetraAddress::new(issi, SsiType::Ssi),
+            link_id: 0,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: Some(mle_sdu(sn_pdu)),
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            report: None,
+        }),
+    }
+}
+
+fn build_unitdata(nsapi: u8, packet: Vec<u8>) -> BitBuffer {
+    let pdu = SnUnitdata {
+        nsapi,
+        dcomp: 0,
+        pcomp: 0,
+        n_pdu: packet,
+    };
+    let mut buf = BitBuffer::new_autoexpand(256);
+    pdu.to_bitbuf(&mut buf).unwrap();
+    buf.seek(0);
+    buf
+}
+
+fn build_demand(nsapi: u8, static_addr: Option<Ipv4Addr>) -> BitBuffer {
+    let address_type = if static_addr.is_some() {
+        SndcpAddressType::Ipv4Static
+    } else {
+        SndcpAddressType::Ipv4Dynamic
+    };
+    let pdu = SnActivatePdpContextDemand {
+        sndcp_version: 1,
+        nsapi,
+        address_type: address_type.into_raw() as u8,
+        ip_address: static_addr.map(u32::from),
+        packet_data_ms_type: 0,
+        pcomp_negotiation: 0,
+    };
+    let mut buf = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut buf).unwrap();
+    buf.seek(0);
+    buf
+}
+
+/// Minimal IPv4 header followed by a payload byte
+fn ipv4_packet(src: Ipv4Addr, dst: Ipv4Addr) -> Vec<u8> {
+    let mut packet = vec![0x45, 0, 0, 21, 0, 0, 0, 0, 64, 17, 0, 0];
+    packet.extend_from_slice(&src.octets());
+    packet.extend_from_slice(&dst.octets());
+    packet.push(0xaa);
+    packet
+}
+
+/// Returns the SN-PDUs sent down to the LLC, with the MLE protocol discriminator stripped
+fn sn_pdus_to_llc(msgs: &[SapMsg]) -> Vec<(u32, BitBuffer)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TlaTlDataReqBl(prim) => {
+                let mut sdu = BitBuffer::from_bitbuffer(&prim.tl_sdu);
+                assert_eq!(sdu.read_bits(3), Some(MleProtocolDiscriminator::Sndcp.into_raw()));
+                Some((prim.main_address.ssi, BitBuffer::from_bitbuffer_pos(&sdu)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_pdp_context_activation_and_data() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = sndcp_test(dltime, Ipv4Addr::new(10, 20, 0, 19));
+
+    // Activate a PDP context with a dynamic address
+    test.submit_message(build_tl_data_ind(dltime, MS_ISSI, &build_demand(5, None)));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+
+    let pdus = sn_pdus_to_llc(&msgs);
+    assert_eq!(pdus.len(), 1);
+    let (ssi, mut sdu) = pdus.into_iter().next().unwrap();
+    assert_eq!(ssi, MS_ISSI);
+    let accept = SnActivatePdpContextAccept::from_bitbuf(&mut sdu).expect("expected SN-ACTIVATE PDP CONTEXT ACCEPT");
+    assert_eq!(accept.nsapi, 5);
+    assert_eq!(accept.ip_address, Some(u32::from(Ipv4Addr::new(10, 20, 0, 10))));
+    assert_eq!(accept.mtu, Some(1)); // 296 bytes, limited by the basic link TL-SDU size
+
+    let ind = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::SnPdpContextInd(ind) => Some(ind),
+            _ => None,
+        })
+        .expect("gateway not informed of new context");
+    assert!(ind.active);
+    assert_eq!(ind.ip_addr, Ipv4Addr::new(10, 20, 0, 10));
+
+    // Uplink IP packet in SN-UNITDATA reaches the gateway
+    let ms_addr = Ipv4Addr::new(10, 20, 0, 10);
+    let host = Ipv4Addr::new(192, 168, 1, 1);
+    let ul_packet = ipv4_packet(ms_addr, host);
+    test.submit_message(build_tl_unitdata_ind(dltime, MS_ISSI, &build_unitdata(5, ul_packet.clone())));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    let ind = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::SnDataInd(ind) => Some(ind),
+            _ => None,
+        })
+        .expect("uplink packet not passed to gateway");
+    assert_eq!((ind.issi, ind.nsapi), (MS_ISSI, 5));
+    assert_eq!(ind.packet, ul_packet);
+
+    // Downlink IP packet is routed to the MS owning the destination address
+    let dl_packet = ipv4_packet(host, ms_addr);
+    test.submit_message(SapMsg {
+        sap: Sap::SnSap,
+        src: TetraEntity::PacketGateway,
+        dest: TetraEntity::Sndcp,
+        dltime,
+        msg: SapMsgInner::SnDataReq(SnDataReq { packet: dl_packet.clone() }),
+    });
+    test.deliver_all_messages();
+    let pdus = sn_pdus_to_llc(&test.dump_sinks());
+    assert_eq!(pdus.len(), 1);
+    let (ssi, mut sdu) = pdus.into_iter().next().unwrap();
+    assert_eq!(ssi, MS_ISSI);
+    let pdu = SnUnitdata::from_bitbuf(&mut sdu).expect("expected SN-UNITDATA");
+    assert_eq!(pdu.nsapi, 5);
+    assert_eq!(pdu.n_pdu, dl_packet);
+
+    // Packets for unassigned addresses are dropped
+    test.submit_message(SapMsg {
+        sap: Sap::SnSap,
+        src: TetraEntity::PacketGateway,
+        dest: TetraEntity::Sndcp,
+        dltime,
+        msg: SapMsgInner::SnDataReq(SnDataReq {
+            packet: ipv4_packet(host, Ipv4Addr::new(10, 20, 0, 11)),
+        }),
+    });
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty());
+}
+
+/// With packet data over Brew enabled, Brew takes the place of the packet data gateway
+#[test]
+fn test_packet_data_over_brew() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.sndcp = Some(CfgSndcp {
+        ip_pool_start: Ipv4Addr::new(10, 20, 0, 10),
+        ip_pool_end: Ipv4Addr::new(10, 20, 0, 19),
+        mtu: 1500,
+        tun_device: None,
+    });
+    config.brew = Some(CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: false,
+        feature_packet_data_enabled: true,
+        whitelisted_ssis: None,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Mle, TetraEntity::Sndcp],
+        vec![TetraEntity::Llc, TetraEntity::PacketGateway, TetraEntity::Brew],
+    );
+
+    test.submit_message(build_tl_data_ind(dltime, MS_ISSI, &build_demand(5, None)));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert!(
+        msgs.iter()
+            .any(|m| m.dest == TetraEntity::Brew && matches!(m.msg, SapMsgInner::SnPdpContextInd(_)))
+    );
+
+    // Uplink packets go to Brew
+    let ms_addr = Ipv4Addr::new(10, 20, 0, 10);
+    let ul_packet = ipv4_packet(ms_addr, Ipv4Addr::new(192, 168, 1, 1));
+    test.submit_message(build_tl_unitdata_ind(dltime, MS_ISSI, &build_unitdata(5, ul_packet.clone())));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert!(msgs.iter().all(|m| m.dest != TetraEntity::PacketGateway));
+    let ind = msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::SnDataInd(ind) if m.dest == TetraEntity::Brew => Some(ind),
+            _ => None,
+        })
+        .expect("uplink packet not passed to Brew");
+    assert_eq!(ind.packet, ul_packet);
+
+    // Packets from Brew are delivered like those of the gateway
+    let dl_packet = ipv4_packet(Ipv4Addr::new(192, 168, 1, 1), ms_addr);
+    test.submit_message(SapMsg {
+        sap: Sap::SnSap,
+        src: TetraEntity::Brew,
+        dest: TetraEntity::Sndcp,
+        dltime,
+        msg: SapMsgInner::SnDataReq(SnDataReq { packet: dl_packet.clone() }),
+    });
+    test.deliver_all_messages();
+    let pdus = sn_pdus_to_llc(&test.dump_sinks());
+    assert_eq!(pdus.len(), 1);
+    let (ssi, mut sdu) = pdus.into_iter().next().unwrap();
+    assert_eq!(ssi, MS_ISSI);
+    assert_eq!(SnUnitdata::from_bitbuf(&mut sdu).unwrap().n_pdu, dl_packet);
+}
+
+#[test]
+fn test_pdp_context_activation_rejects() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    // Single-address pool
+    let mut test = sndcp_test(dltime, Ipv4Addr::new(10, 20, 0, 10));
+
+    let reject_cause = |test: &mut ComponentTest| {
+        let pdus = sn_pdus_to_llc(&test.dump_sinks());
+        assert_eq!(pdus.len(), 1);
+        let (_, mut sdu) = pdus.into_iter().next().unwrap();
+        SnActivatePdpContextReject::from_bitbuf(&mut sdu)
+            .expect("expected SN-ACTIVATE PDP CONTEXT REJECT")
+            .reject_cause
+    };
+
+    // Static address outside of the pool
+    test.submit_message(build_tl_data_ind(
+        dltime,
+        MS_ISSI,
+        &build_demand(1, Some(Ipv4Addr::new(10, 0, 0, 1))),
+    ));
+    test.deliver_all_messages();
+    assert_eq!(
+        reject_cause(&mut test) as u64,
+        ActivationRejectCause::Ipv4StaticAddressNotCorrect.into_raw()
+    );
+
+    // First MS takes the only address, the second one is turned away
+    test.submit_message(build_tl_data_ind(dltime, MS_ISSI, &build_demand(1, None)));
+    test.deliver_all_messages();
+    test.dump_sinks();
+    test.submit_message(build_tl_data_ind(dltime, MS_ISSI + 1, &build_demand(1, None)));
+    test.deliver_all_messages();
+    assert_eq!(
+        reject_cause(&mut test) as u64,
+        ActivationRejectCause::InsufficientResources.into_raw()
+    );
+}
+
+#[test]
+fn test_packet_gateway_forwarding() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = sndcp_test_with_sinks(dltime, Ipv4Addr::new(10, 20, 0, 19), vec![TetraEntity::Llc]);
+    let (iface, host) = ChannelInterface::new(296);
+    let gateway = NetEntity::<SndcpWorkerChannel>::new(
+        test.get_shared_config(),
+        TetraEntity::PacketGateway,
+        TetraEntity::Sndcp,
+        Sap::SnSap,
+        iface,
+    )
+    .expect("Failed to create packet gateway");
+    test.register_entity(gateway);
+
+    test.submit_message(build_tl_data_ind(dltime, MS_ISSI, &build_demand(1, None)));
+    test.run_stack(Some(1));
+    assert_eq!(sn_pdus_to_llc(&test.dump_sinks()).len(), 1);
+
+    // Uplink packet comes out on the host side of the interface
+    let ms_addr = Ipv4Addr::new(10, 20, 0, 10);
+    let host_addr = Ipv4Addr::new(192, 168, 1, 1);
+    let ul_packet = ipv4_packet(ms_addr, host_addr);
+    test.submit_message(build_tl_unitdata_ind(dltime, MS_ISSI, &build_unitdata(1, ul_packet.clone())));
+    test.run_stack(Some(1));
+    let received = host.receiver.recv_timeout(Duration::from_secs(1)).expect("no packet on host side");
+    assert_eq!(received, ul_packet);
+
+    // Downlink packets are only forwarded to addresses with a PDP context, and only up to the MTU
+    let dl_packet = ipv4_packet(host_addr, ms_addr);
+    let mut oversized = ipv4_packet(host_addr, ms_addr);
+    oversized.resize(297, 0);
+    host.sender.send(ipv4_packet(host_addr, Ipv4Addr::new(10, 20, 0, 11))).unwrap();
+    host.sender.send(oversized).unwrap();
+    host.sender.send(dl_packet.clone()).unwrap();
+
+    let mut pdus = vec![];
+    for _ in 0..200 {
+        std::thread::sleep(Duration::from_millis(5));
+        test.run_stack(Some(1));
+        pdus.extend(sn_pdus_to_llc(&test.dump_sinks()));
+        if !pdus.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(pdus.len(), 1);
+    let (ssi, mut sdu) = pdus.into_iter().next().unwrap();
+    assert_eq!(ssi, MS_ISSI);
+    let pdu = SnUnitdata::from_bitbuf(&mut sdu).expect("expected SN-UNITDATA");
+    assert_eq!(pdu.nsapi, 1);
+    assert_eq!(pdu.n_pdu, dl_packet);
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use tetra_config::bluestation::{ForwardingCondition, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::enums::ss_pdu_type::SsPduType;
+use tetra_pdus::cmce::enums::ss_type::SsType;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::fields::ss_facility::SsFacility;
+use tetra_pdus::cmce::pdus::cmce_function_not_supported::CmceFunctionNotSupported;
+use tetra_pdus::cmce::pdus::d_facility::DFacility;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_facility::UFacility;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const TEST_ISSI: u32 = 1000001;
+const TEST_ISSI_CALLED: u32 = 1000002;
+const TEST_ISSI_FORWARDED: u32 = 1000003;
+
+fn ss_test_stack(dltime: TdmaTime) -> ComponentTest {
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test
+}
+
+/// Helper: wrap a serialized uplink CMCE PDU from the given ISSI into an LCMC indication.
+fn build_ul_msg(dltime: TdmaTime, issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_alloc: None,
+        }),
+    }
+}
+
+/// Helper: build a U-FACILITY from the given ISSI carrying the given SS PDU.
+fn build_u_facility(dltime: TdmaTime, issi: u32, ss_type: u8, pdu_type: SsPduType, ss_data: BitBuffer) -> SapMsg {
+    let pdu = UFacility {
+        facility: SsFacility::new(ss_type, pdu_type.into_raw() as u8, ss_data),
+    };
+    let mut sdu = BitBuffer::new_autoexpand(48);
+    pdu.to_bitbuf(&mut sdu).expect("Failed to serialize UFacility");
+    sdu.seek(0);
+    build_ul_msg(dltime, issi, sdu)
+}
+
+/// Helper: SS-specific information holding an ISSI.
+fn issi_data(issi: u32) -> BitBuffer {
+    let mut data = BitBuffer::new_autoexpand(24);
+    data.write_bits(issi as u64, 24);
+    data
+}
+
+/// Extract the D-FACILITY PDUs sent to the given ISSI from the sink output.
+fn d_facilities(msgs: &[SapMsg], issi: u32) -> Vec<SsFacility> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim)
+                if prim.main_address.ssi == issi && prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DFacility.into_raw()) =>
+            {
+                DFacility::from_bitbuf(&mut prim.sdu.clone()).ok().map(|pdu| pdu.facility)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Extract the CMCE FUNCTION NOT SUPPORTED PDUs sent to the given ISSI from the sink output.
+fn function_not_supported(msgs: &[SapMsg], issi: u32) -> Vec<CmceFunctionNotSupported> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim)
+                if prim.main_address.ssi == issi && prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::CmceFunctionNotSupported.into_raw()) =>
+            {
+                CmceFunctionNotSupported::from_bitbuf(&mut prim.sdu.clone()).ok()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Call forwarding activated through U-FACILITY is acknowledged in D-FACILITY, recorded in the
+/// stack state, reported on interrogation, and removed again on deactivation.
+#[test]
+fn test_call_forwarding_activation() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ss_test_stack(dltime);
+    let cfu = SsType::Cfu.into_raw() as u8;
+
+    test.submit_message(build_u_facility(
+        dltime,
+        TEST_ISSI,
+        cfu,
+        SsPduType::Activate,
+        issi_data(TEST_ISSI_FORWARDED),
+    ));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities.len(), 1);
+    assert_eq!(facilities[0].ss_type, cfu);
+    assert_eq!(facilities[0].ss_pdu_type, SsPduType::Result.into_raw() as u8);
+    assert_eq!(facilities[0].ss_data.to_bitstr(), format!("1{:024b}", TEST_ISSI_FORWARDED));
+    assert_eq!(
+        test.config
+            .state_read()
+            .call_forwarding
+            .forwarded_to(TEST_ISSI, ForwardingCondition::Unconditional),
+        Some(TEST_ISSI_FORWARDED)
+    );
+
+    test.submit_message(build_u_facility(dltime, TEST_ISSI, cfu, SsPduType::Interrogate, BitBuffer::new(0)));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities[0].ss_data.to_bitstr(), format!("1{:024b}", TEST_ISSI_FORWARDED));
+
+    test.submit_message(build_u_facility(dltime, TEST_ISSI, cfu, SsPduType::Deactivate, BitBuffer::new(0)));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities[0].ss_pdu_type, SsPduType::Result.into_raw() as u8);
+    assert_eq!(facilities[0].ss_data.to_bitstr(), "0");
+    assert_eq!(
+        test.config
+            .state_read()
+            .call_forwarding
+            .forwarded_to(TEST_ISSI, ForwardingCondition::Unconditional),
+        None
+    );
+
+    // Forwarding to oneself is refused
+    test.submit_message(build_u_facility(dltime, TEST_ISSI, cfu, SsPduType::Activate, issi_data(TEST_ISSI)));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities[0].ss_pdu_type, SsPduType::Reject.into_raw() as u8);
+}
+
+/// An individual call to a subscriber that is not reachable is forwarded to the ISSI it set up
+/// call forwarding on not reachable for.
+#[test]
+fn test_call_forwarding_on_not_reachable() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ss_test_stack(dltime);
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    test.config.state_write().subscribers.register(TEST_ISSI_CALLED);
+    test.config.state_write().subscribers.register(TEST_ISSI_FORWARDED);
+
+    let cfnrc = SsType::Cfnrc.into_raw() as u8;
+    test.submit_message(build_u_facility(
+        dltime,
+        TEST_ISSI_CALLED,
+        cfnrc,
+        SsPduType::Activate,
+        issi_data(TEST_ISSI_FORWARDED),
+    ));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // The called subscriber leaves the cell
+    test.config.state_write().subscribers.deregister(TEST_ISSI_CALLED);
+
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2p,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(TEST_ISSI_CALLED as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).expect("Failed to serialize USetup");
+    sdu.seek(0);
+    test.submit_message(build_ul_msg(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    let d_setup = msgs
+        .iter()
+        .find_map(|msg| match &msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim)
+                if prim.main_address.ssi == TEST_ISSI_FORWARDED && prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DSetup.into_raw()) =>
+            {
+                DSetup::from_bitbuf(&mut prim.sdu.clone()).ok()
+            }
+            _ => None,
+        })
+        .expect("Expected D-SETUP paging the forwarded-to ISSI");
+    assert_eq!(d_setup.calling_party_address_ssi, Some(TEST_ISSI));
+}
+
+/// Talking party identification cannot be withdrawn, and area selections are reported back.
+#[test]
+fn test_tpi_and_area_selection() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ss_test_stack(dltime);
+    let tpi = SsType::Tpi.into_raw() as u8;
+    let area_selection = SsType::AreaSelection.into_raw() as u8;
+
+    test.submit_message(build_u_facility(dltime, TEST_ISSI, tpi, SsPduType::Interrogate, BitBuffer::new(0)));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities[0].ss_pdu_type, SsPduType::Result.into_raw() as u8);
+    assert_eq!(facilities[0].ss_data.to_bitstr(), "1");
+
+    test.submit_message(build_u_facility(dltime, TEST_ISSI, tpi, SsPduType::Deactivate, BitBuffer::new(0)));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities[0].ss_pdu_type, SsPduType::Reject.into_raw() as u8);
+
+    let mut area = BitBuffer::new_autoexpand(4);
+    area.write_bits(3, 4);
+    test.submit_message(build_u_facility(dltime, TEST_ISSI, area_selection, SsPduType::Activate, area));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+    test.submit_message(build_u_facility(
+        dltime,
+        TEST_ISSI,
+        area_selection,
+        SsPduType::Interrogate,
+        BitBuffer::new(0),
+    ));
+    test.run_stack(Some(1));
+    let facilities = d_facilities(&test.dump_sinks(), TEST_ISSI);
+    assert_eq!(facilities[0].ss_type, area_selection);
+    assert_eq!(facilities[0].ss_data.to_bitstr(), "10011");
+}
+
+/// Requests for services without a handler, and requests a handler does not support, are refused
+/// with CMCE FUNCTION NOT SUPPORTED.
+#[test]
+fn test_unsupported_ss_request() {
+    debug::setup_logging_verbose();
+
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ss_test_stack(dltime);
+
+    let requests = [
+        // Known SS-type without a handler
+        (SsType::Cfnry.into_raw() as u8, SsPduType::Interrogate),
+        // Unknown SS-type
+        (40, SsPduType::Interrogate),
+        // SwMI-only SS PDU type
+        (SsType::Cfu.into_raw() as u8, SsPduType::Result),
+    ];
+    for (ss_type, pdu_type) in requests {
+        test.submit_message(build_u_facility(dltime, TEST_ISSI, ss_type, pdu_type, BitBuffer::new(0)));
+        test.run_stack(Some(1));
+        let msgs = test.dump_sinks();
+        assert!(d_facilities(&msgs, TEST_ISSI).is_empty());
+        let refusals = function_not_supported(&msgs, TEST_ISSI);
+        assert_eq!(refusals.len(), 1, "Expected CMCE FUNCTION NOT SUPPORTED for ss_type={}", ss_type);
+        assert_eq!(refusals[0].not_supported_pdu_type as u64, CmcePduTypeUl::UFacility.into_raw());
+        assert_eq!(refusals[0].function_not_supported_pointer, 0);
+    }
+}
@@ -1,11 +1,22 @@
 mod common;
 
-use tetra_config::bluestation::StackMode;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tetra_config::bluestation::{AuthPolicy, CfgAuth, CfgEncryption, SecurityClass, StackConfig, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::{BitBuffer, Direction, Layer2Service, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::umac::subcomp::cipher::{KeyClass, SduCipher, TestKeystreamGenerator};
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::cipher::MmCipherKeyUpdate;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
-use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+use tetra_saps::tma::TmaUnitdataReq;
+use tetra_saps::tmd::TmdCircuitDataReq;
+use tetra_saps::tmv::{TmvUnitdataInd, TmvUnitdataReqSlot, enums::logical_chans::LogicalChannel};
 
 use crate::common::ComponentTest;
 
@@ -24,6 +35,7 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
     };
     let test_sapmsg1 = SapMsg {
         sap: Sap::TmvSap,
@@ -38,6 +50,7 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         logical_channel: LogicalChannel::SchF,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
     };
     let test_sapmsg2 = SapMsg {
         sap: Sap::TmvSap,
@@ -84,6 +97,7 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
     };
     let test_sapmsg1 = SapMsg {
         sap: Sap::TmvSap,
@@ -98,6 +112,7 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
     };
     let test_sapmsg2 = SapMsg {
         sap: Sap::TmvSap,
@@ -172,3 +187,399 @@ fn test_out_fragmented_resource() {
 
     tracing::info!("Validation of result not implemented");
 }
+
+const TEST_SCK: u128 = 0x00112233445566778899;
+
+fn class2_test(dltime: TdmaTime) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.encryption = Some(CfgEncryption {
+        security_class: SecurityClass::Class2,
+        sck: Some(TEST_SCK),
+        sck_number: 1,
+        cck: None,
+    });
+    ComponentTest::from_config(config, Some(dltime))
+}
+
+fn test_sck_cipher() -> SduCipher {
+    SduCipher::new(Arc::new(TestKeystreamGenerator), KeyClass::Sck, TEST_SCK)
+}
+
+#[test]
+fn test_in_encrypted_mac_access() {
+    // Receive SCH/HU containing an SCK-encrypted MAC-ACCESS, which should be decrypted before delivery to the LLC
+    debug::setup_logging_verbose();
+    let sdu_plain = "1010001111000011110101100110011100001111101010010110";
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let ultime = dltime.add_timeslots(-2);
+
+    let addr = TetraAddress {
+        ssi: 1000001,
+        ssi_type: SsiType::Ssi,
+        encrypted: true,
+    };
+    let pdu = MacAccess {
+        fill_bits: false,
+        encrypted: true,
+        addr: Some(addr),
+        event_label: None,
+        length_ind: Some(11), // 36 bit header + 52 bit SDU
+        frag_flag: None,
+        reservation_req: None,
+    };
+    let mut block = BitBuffer::new(92);
+    pdu.to_bitbuf(&mut block);
+    let sdu_start = block.get_pos();
+    block.copy_bits(&mut BitBuffer::from_bitstr(sdu_plain), sdu_plain.len());
+    block.seek(sdu_start);
+    test_sck_cipher().apply(&mut block, sdu_plain.len(), ultime, 0, Direction::Ul);
+    block.seek(0);
+
+    let test_sapmsg = SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: ultime,
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: block,
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::SchHu,
+            crc_pass: true,
+            scrambling_code: 864282631,
+            direction: Direction::Ul,
+        }),
+    };
+
+    let mut test = class2_test(dltime);
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Llc]);
+    test.submit_message(test_sapmsg);
+    test.run_stack(Some(1));
+
+    let sink_msgs = test.dump_sinks();
+    let ind = sink_msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::TmaUnitdataInd(ind) => Some(ind),
+            _ => None,
+        })
+        .expect("no TMA-UNITDATA indication delivered");
+    assert_eq!(ind.main_address.ssi, 1000001);
+    assert_eq!(ind.air_interface_encryption, 1);
+    assert_eq!(ind.pdu.as_ref().unwrap().to_bitstr(), sdu_plain);
+}
+
+#[test]
+fn test_out_encrypted_resource() {
+    // Signalling to a group in a class 2 cell goes out in an encrypted MAC-RESOURCE,
+    // keyed on the TDMA time of the slot it is transmitted in
+    debug::setup_logging_verbose();
+    let sdu_plain = "110010101111000010100101100111000011";
+    let dltime = TdmaTime::default().add_timeslots(4); // Timeslot 1 (MCCH)
+    let group = TetraAddress::new(91, SsiType::Gssi);
+    let test_sapmsg = SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+            req_handle: 0,
+            pdu: BitBuffer::from_bitstr(sdu_plain),
+            main_address: group,
+            endpoint_id: 0,
+            stealing_permission: false,
+            subscriber_class: 0,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_category: None,
+            chan_alloc: None,
+            tx_reporter: None,
+        }),
+    };
+
+    let mut test = class2_test(dltime);
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+    test.submit_message(test_sapmsg);
+    test.run_stack(Some(8));
+
+    let mut found = false;
+    for msg in test.dump_sinks() {
+        let SapMsgInner::TmvUnitdataReq(slot) = msg.msg else {
+            continue;
+        };
+        let Some(blk) = slot.blk1 else {
+            continue;
+        };
+        if blk.logical_channel != LogicalChannel::SchF {
+            continue;
+        }
+        let mut block = blk.mac_block;
+        block.seek(0);
+        let Ok(pdu) = MacResource::from_bitbuf(&mut block) else {
+            continue;
+        };
+        if pdu.addr.map(|a| a.ssi) != Some(91) {
+            continue;
+        }
+        assert_ne!(pdu.encryption_mode, 0);
+        let sdu_start = block.get_pos();
+        assert_ne!(block.to_bitstr()[sdu_start..sdu_start + sdu_plain.len()], *sdu_plain);
+        test_sck_cipher().apply(&mut block, sdu_plain.len(), slot.ts, 0, Direction::Dl);
+        assert_eq!(block.to_bitstr()[sdu_start..sdu_start + sdu_plain.len()], *sdu_plain);
+        found = true;
+    }
+    assert!(found, "no MAC-RESOURCE for the group was transmitted");
+}
+
+/// A circuit on channel 5 is carried by TS1 of the first secondary carrier. Its slots are handed to the
+/// LMAC right before the main carrier slot of the same timeslot, and only when a circuit is active.
+#[test]
+fn test_secondary_carrier_traffic() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.secondary_carriers = vec![1525];
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Cmce,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg: SapMsgInner::CmceCallControl(CallControl::Open(Circuit {
+            direction: Direction::Dl,
+            ts: 5,
+            usage: 4,
+            circuit_mode: CircuitModeType::TchS,
+            speech_service: Some(0),
+            etee_encrypted: false,
+            peer_ts: None,
+            individual_parties: None,
+        })),
+    });
+    test.run_stack(Some(8));
+    let slots: Vec<_> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::TmvUnitdataReq(slot) => Some(slot),
+            _ => None,
+        })
+        .collect();
+
+    let secondary: Vec<_> = slots.iter().enumerate().filter(|(_, s)| s.carrier != 0).collect();
+    assert_eq!(secondary.len(), 2, "Expected one secondary carrier slot per frame");
+    for (idx, slot) in secondary {
+        assert_eq!(slot.carrier, 1);
+        assert_eq!(slot.ts.t, 1);
+        let blk1 = slot.blk1.as_ref().expect("Secondary carrier slot without blk1");
+        assert_eq!(blk1.logical_channel, LogicalChannel::TchS);
+        assert!(slot.bbk.is_some());
+
+        let main = &slots[idx + 1];
+        assert_eq!(main.carrier, 0);
+        assert_eq!(main.ts, slot.ts);
+    }
+    assert_eq!(slots.iter().filter(|s| s.carrier == 0).count(), 8);
+}
+
+const TEST_CCK: u128 = 0x0123456789abcdef0123;
+const TEST_DCK_A: u128 = 0x0a0a0a0a0a0a0a0a0a0a;
+const TEST_DCK_B: u128 = 0x0b0b0b0b0b0b0b0b0b0b;
+const TEST_ISSI_A: u32 = 1000001;
+const TEST_ISSI_B: u32 = 1000002;
+
+fn open_dl_circuit(ts: u8, individual_parties: Option<(u32, u32)>) -> CallControl {
+    CallControl::Open(Circuit {
+        direction: Direction::Dl,
+        ts,
+        usage: 4 + ts,
+        circuit_mode: CircuitModeType::TchS,
+        speech_service: Some(0),
+        etee_encrypted: false,
+        peer_ts: None,
+        individual_parties,
+    })
+}
+
+/// Config of a security class 3 cell. MSs have to authenticate to obtain their DCK.
+fn class3_test_config() -> StackConfig {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.auth = Some(CfgAuth {
+        policy: AuthPolicy::Required,
+        keys: HashMap::new(),
+    });
+    config.encryption = Some(CfgEncryption {
+        security_class: SecurityClass::Class3,
+        sck: None,
+        sck_number: 1,
+        cck: Some(TEST_CCK),
+    });
+    config
+}
+
+/// Decrypts the DL traffic blocks of a traffic channel in the given slots. Returns true if there are any,
+/// and all of them decrypt to silence.
+fn dl_traffic_is_silence(slots: &[TmvUnitdataReqSlot], chan: u8, cipher: &SduCipher) -> bool {
+    let (carrier, ts) = ((chan - 1) / 4, (chan - 1) % 4 + 1);
+    let blocks: Vec<_> = slots
+        .iter()
+        .filter(|slot| slot.carrier == carrier && slot.ts.t == ts)
+        .filter_map(|slot| Some((slot.ts, slot.blk1.as_ref()?)))
+        .filter(|(_, blk)| blk.logical_channel == LogicalChannel::TchS)
+        .map(|(t, blk)| {
+            let mut block = blk.mac_block.clone();
+            block.seek(0);
+            let len = block.get_len();
+            cipher.apply(&mut block, len, t, carrier, Direction::Dl);
+            block
+        })
+        .collect();
+    !blocks.is_empty() && blocks.iter().all(|block| !block.to_bitstr().contains('1'))
+}
+
+/// In class 3, group traffic uses the CCK and individual traffic the DCK of the receiving MS, which
+/// follows the floor. The same timeslot on another carrier gets a keystream of its own.
+#[test]
+fn test_class3_traffic_keys() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = class3_test_config();
+    config.cell.secondary_carriers = vec![1525];
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac, TetraEntity::Cmce]);
+
+    let control = |msg| SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Cmce,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg,
+    };
+    for (issi, dck) in [(TEST_ISSI_A, TEST_DCK_A), (TEST_ISSI_B, TEST_DCK_B)] {
+        let mut msg = control(SapMsgInner::MmCipherKeyUpdate(MmCipherKeyUpdate { issi, dck: Some(dck) }));
+        msg.src = TetraEntity::Mm;
+        test.submit_message(msg);
+    }
+    // Simplex individual call from A to B on channel 2, group calls on channel 6 (TS2 of the secondary carrier)
+    test.submit_message(control(SapMsgInner::CmceCallControl(open_dl_circuit(
+        2,
+        Some((TEST_ISSI_A, TEST_ISSI_B)),
+    ))));
+    test.submit_message(control(SapMsgInner::CmceCallControl(open_dl_circuit(6, None))));
+    test.run_stack(Some(8));
+    let slots: Vec<_> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::TmvUnitdataReq(slot) => Some(slot),
+            _ => None,
+        })
+        .collect();
+
+    let cipher = |key_class, key| SduCipher::new(Arc::new(TestKeystreamGenerator), key_class, key);
+    assert!(dl_traffic_is_silence(&slots, 2, &cipher(KeyClass::Dck, TEST_DCK_B)));
+    assert!(dl_traffic_is_silence(&slots, 6, &cipher(KeyClass::Cck, TEST_CCK)));
+
+    // The same timeslot of the main carrier would not have decrypted with the secondary carrier keystream
+    let secondary = slots.iter().find(|s| s.carrier == 1).unwrap();
+    let mut block = secondary.blk1.as_ref().unwrap().mac_block.clone();
+    block.seek(0);
+    let len = block.get_len();
+    cipher(KeyClass::Cck, TEST_CCK).apply(&mut block, len, secondary.ts, 0, Direction::Dl);
+    assert!(block.to_bitstr().contains('1'));
+
+    // B takes the floor, A is now receiving
+    test.submit_message(control(SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+        call_id: 1,
+        source_issi: TEST_ISSI_B,
+        dest_gssi: TEST_ISSI_A,
+        ts: 2,
+        priority: 0,
+    })));
+    // Slots already prepared before the floor change are flushed first
+    test.run_stack(Some(4));
+    test.dump_sinks();
+    test.run_stack(Some(4));
+    let slots: Vec<_> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::TmvUnitdataReq(slot) => Some(slot),
+            _ => None,
+        })
+        .collect();
+    assert!(dl_traffic_is_silence(&slots, 2, &cipher(KeyClass::Dck, TEST_DCK_A)));
+}
+
+/// In class 3, individual traffic is never carried in the clear: voice for an MS without DCK is dropped
+#[test]
+fn test_class3_refuses_clear_individual_traffic() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::from_config(class3_test_config(), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac, TetraEntity::Cmce]);
+
+    let control = |msg| SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Cmce,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg,
+    };
+    let voice = || SapMsg {
+        sap: Sap::TmdSap,
+        src: TetraEntity::Brew,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg: SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq {
+            ts: 2,
+            data: vec![0xff; 35],
+        }),
+    };
+    let dl_traffic_blocks = |test: &mut ComponentTest| -> Vec<TmvUnitdataReqSlot> {
+        test.dump_sinks()
+            .into_iter()
+            .filter_map(|m| match m.msg {
+                SapMsgInner::TmvUnitdataReq(slot) => Some(slot),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // B has not authenticated, its voice is not sent in the clear
+    test.submit_message(control(SapMsgInner::CmceCallControl(open_dl_circuit(
+        2,
+        Some((TEST_ISSI_A, TEST_ISSI_B)),
+    ))));
+    test.run_stack(Some(4));
+    test.dump_sinks();
+    test.submit_message(voice());
+    test.run_stack(Some(8));
+    let slots = dl_traffic_blocks(&mut test);
+    let clear = |slots: &[TmvUnitdataReqSlot]| {
+        slots
+            .iter()
+            .filter(|slot| slot.ts.t == 2)
+            .filter_map(|slot| slot.blk1.as_ref())
+            .filter(|blk| blk.logical_channel == LogicalChannel::TchS)
+            .all(|blk| !blk.mac_block.to_bitstr().contains('1'))
+    };
+    assert!(clear(&slots));
+
+    // Once B holds a DCK, its voice goes out encrypted
+    let mut msg = control(SapMsgInner::MmCipherKeyUpdate(MmCipherKeyUpdate {
+        issi: TEST_ISSI_B,
+        dck: Some(TEST_DCK_B),
+    }));
+    msg.src = TetraEntity::Mm;
+    test.submit_message(msg);
+    test.run_stack(Some(4));
+    test.dump_sinks();
+    test.submit_message(voice());
+    test.run_stack(Some(8));
+    let slots = dl_traffic_blocks(&mut test);
+    let cipher = SduCipher::new(Arc::new(TestKeystreamGenerator), KeyClass::Dck, TEST_DCK_B);
+    assert!(!clear(&slots));
+    assert!(!dl_traffic_is_silence(&slots, 2, &cipher));
+}
@@ -2,7 +2,7 @@ mod common;
 
 use tetra_config::bluestation::StackMode;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, PhyBlockNum, Sap, TdmaTime, debug};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, TdmaTime, debug};
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
 use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
 
@@ -30,6 +30,7 @@ fn test_umac_ms() {
             logical_channel: LogicalChannel::SchHd,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
         }),
     };
 
@@ -69,6 +70,7 @@ fn test_umac_frag() {
             logical_channel: LogicalChannel::SchHd,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
         }),
     };
     test.submit_message(m);
@@ -87,6 +89,7 @@ fn test_umac_frag() {
             logical_channel: LogicalChannel::SchHd,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
         }),
     };
 
@@ -127,6 +130,7 @@ fn test_sysinfo() {
             logical_channel: LogicalChannel::Bnch,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
         }),
     };
     test.submit_message(m);
@@ -162,6 +166,7 @@ fn test_sync() {
             logical_channel: LogicalChannel::Bsch,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
         }),
     };
     test.submit_message(m);
@@ -196,6 +201,7 @@ fn test_resource() {
             logical_channel: LogicalChannel::SchF,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
         }),
     };
     test.submit_message(m);
@@ -207,3 +213,46 @@ fn test_resource() {
 
     tracing::warn!("Validation of result not implemented");
 }
+
+#[test]
+/// The SYNC sets the MS time, and together with the D-MLE-SYNC the scrambling code used by the LMAC
+fn test_sync_configures_lmac() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    let components = vec![TetraEntity::Umac, TetraEntity::Llc, TetraEntity::Mle];
+    let sinks = vec![TetraEntity::Lmac];
+    test.populate_entities(components, sinks);
+
+    // SYNC CC 1 TN 4 FN 11 MN 9 MCC 420 MNC 555
+    let m = SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: BitBuffer::from_bitstr("000100000111010110010010000000001101001000000100010101110011"),
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::Bsch,
+            crc_pass: true,
+            scrambling_code: 0,
+            direction: Direction::Dl,
+        }),
+    };
+    test.submit_message(m);
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+
+    let configs: Vec<_> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TmvConfigureReq(prim) => Some(prim),
+            _ => None,
+        })
+        .collect();
+    let time = configs.iter().find_map(|c| c.time).expect("time not configured");
+    assert_eq!((time.m, time.f, time.t), (9, 11, 4));
+    assert!(
+        configs.iter().any(|c| c.scrambling_code.is_some()),
+        "scrambling code not configured"
+    );
+}
@@ -0,0 +1,69 @@
+/// Clause 29.4.3.3 Delivery report request
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DeliveryReportRequest {
+    NoReportRequested = 0,
+    MessageReceivedReportRequested = 1,
+    MessageConsumedReportRequested = 2,
+    MessageReceivedAndConsumedReportRequested = 3,
+}
+
+impl std::convert::TryFrom<u64> for DeliveryReportRequest {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(DeliveryReportRequest::NoReportRequested),
+            1 => Ok(DeliveryReportRequest::MessageReceivedReportRequested),
+            2 => Ok(DeliveryReportRequest::MessageConsumedReportRequested),
+            3 => Ok(DeliveryReportRequest::MessageReceivedAndConsumedReportRequested),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DeliveryReportRequest {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            DeliveryReportRequest::NoReportRequested => 0,
+            DeliveryReportRequest::MessageReceivedReportRequested => 1,
+            DeliveryReportRequest::MessageConsumedReportRequested => 2,
+            DeliveryReportRequest::MessageReceivedAndConsumedReportRequested => 3,
+        }
+    }
+
+    /// Whether any delivery report is requested
+    pub fn any(self) -> bool {
+        self != DeliveryReportRequest::NoReportRequested
+    }
+
+    /// Whether a report on the reception of the message is requested
+    pub fn received(self) -> bool {
+        self.into_raw() & 0b01 != 0
+    }
+
+    /// Whether a report on the consumption of the message is requested
+    pub fn consumed(self) -> bool {
+        self.into_raw() & 0b10 != 0
+    }
+}
+
+impl From<DeliveryReportRequest> for u64 {
+    fn from(e: DeliveryReportRequest) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for DeliveryReportRequest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeliveryReportRequest::NoReportRequested => write!(f, "NoReportRequested"),
+            DeliveryReportRequest::MessageReceivedReportRequested => write!(f, "MessageReceivedReportRequested"),
+            DeliveryReportRequest::MessageConsumedReportRequested => write!(f, "MessageConsumedReportRequested"),
+            DeliveryReportRequest::MessageReceivedAndConsumedReportRequested => {
+                write!(f, "MessageReceivedAndConsumedReportRequested")
+            }
+        }
+    }
+}
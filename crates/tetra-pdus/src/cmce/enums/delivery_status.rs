@@ -0,0 +1,226 @@
+use crate::cmce::enums::short_report_type::ShortReportType;
+
+/// Clause 29.4.3.2 Delivery status. Values 0x00 to 0x1F report success, 0x20 to 0x3F temporary errors,
+/// 0x40 to 0x7F data transfer failures and 0x80 to 0x9F flow control. Values undefined here are reserved
+/// Bits: 8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DeliveryStatus {
+    /// SDS receipt acknowledged by destination
+    ReceiptAcknowledgedByDestination = 0x00,
+    /// SDS receipt report acknowledgement
+    ReceiptReportAcknowledgement = 0x01,
+    /// SDS consumed by destination
+    ConsumedByDestination = 0x02,
+    /// SDS consumed report acknowledgement
+    ConsumedReportAcknowledgement = 0x03,
+    /// SDS message forwarded to external network
+    ForwardedToExternalNetwork = 0x04,
+    /// SDS sent to group, acknowledgements prevented
+    SentToGroupAcknowledgementsPrevented = 0x05,
+    /// Concatenation part receipt acknowledged by destination
+    ConcatenationPartReceived = 0x06,
+    /// Congestion, message stored by SwMI
+    CongestionMessageStored = 0x20,
+    /// Message stored by SwMI
+    MessageStored = 0x21,
+    /// Destination not reachable, message stored by SwMI
+    DestinationNotReachableMessageStored = 0x22,
+    /// Network overload
+    NetworkOverload = 0x40,
+    /// Service permanently not available on BS
+    ServicePermanentlyNotAvailable = 0x41,
+    /// Service temporarily not available on BS
+    ServiceTemporarilyNotAvailable = 0x42,
+    /// Source is not authorised for SDS
+    SourceNotAuthorised = 0x43,
+    /// Destination is not authorised for SDS
+    DestinationNotAuthorised = 0x44,
+    /// Unknown destination, gateway or service centre address
+    UnknownDestination = 0x45,
+    /// Unknown forward address
+    UnknownForwardAddress = 0x46,
+    /// Group address with individual service
+    GroupAddressWithIndividualService = 0x47,
+    /// Validity period expired, message not received by far end
+    ExpiredNotReceived = 0x48,
+    /// Validity period expired, message not consumed by far end
+    ExpiredNotConsumed = 0x49,
+    /// Delivery failed
+    DeliveryFailed = 0x4A,
+    /// Destination not registered on system
+    DestinationNotRegistered = 0x4B,
+    /// Destination queue full
+    DestinationQueueFull = 0x4C,
+    /// Message too long for destination or gateway
+    MessageTooLong = 0x4D,
+    /// Destination does not support SDS-TL data transfer PDUs
+    DestinationDoesNotSupportSdsTl = 0x4E,
+    /// Destination host not connected
+    DestinationHostNotConnected = 0x4F,
+    /// Protocol not supported
+    ProtocolNotSupported = 0x50,
+    /// Data coding scheme not supported
+    DataCodingSchemeNotSupported = 0x51,
+    /// Destination memory full, message discarded
+    DestinationMemoryFullMessageDiscarded = 0x52,
+    /// Destination memory full
+    DestinationMemoryFull = 0x60,
+    /// Destination memory available
+    DestinationMemoryAvailable = 0x61,
+    /// Start pending messages
+    StartPendingMessages = 0x62,
+    /// No pending messages
+    NoPendingMessages = 0x63,
+    /// Stop sending
+    StopSending = 0x80,
+    /// Start sending
+    StartSending = 0x81,
+}
+
+impl std::convert::TryFrom<u64> for DeliveryStatus {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0x00 => Ok(DeliveryStatus::ReceiptAcknowledgedByDestination),
+            0x01 => Ok(DeliveryStatus::ReceiptReportAcknowledgement),
+            0x02 => Ok(DeliveryStatus::ConsumedByDestination),
+            0x03 => Ok(DeliveryStatus::ConsumedReportAcknowledgement),
+            0x04 => Ok(DeliveryStatus::ForwardedToExternalNetwork),
+            0x05 => Ok(DeliveryStatus::SentToGroupAcknowledgementsPrevented),
+            0x06 => Ok(DeliveryStatus::ConcatenationPartReceived),
+            0x20 => Ok(DeliveryStatus::CongestionMessageStored),
+            0x21 => Ok(DeliveryStatus::MessageStored),
+            0x22 => Ok(DeliveryStatus::DestinationNotReachableMessageStored),
+            0x40 => Ok(DeliveryStatus::NetworkOverload),
+            0x41 => Ok(DeliveryStatus::ServicePermanentlyNotAvailable),
+            0x42 => Ok(DeliveryStatus::ServiceTemporarilyNotAvailable),
+            0x43 => Ok(DeliveryStatus::SourceNotAuthorised),
+            0x44 => Ok(DeliveryStatus::DestinationNotAuthorised),
+            0x45 => Ok(DeliveryStatus::UnknownDestination),
+            0x46 => Ok(DeliveryStatus::UnknownForwardAddress),
+            0x47 => Ok(DeliveryStatus::GroupAddressWithIndividualService),
+            0x48 => Ok(DeliveryStatus::ExpiredNotReceived),
+            0x49 => Ok(DeliveryStatus::ExpiredNotConsumed),
+            0x4A => Ok(DeliveryStatus::DeliveryFailed),
+            0x4B => Ok(DeliveryStatus::DestinationNotRegistered),
+            0x4C => Ok(DeliveryStatus::DestinationQueueFull),
+            0x4D => Ok(DeliveryStatus::MessageTooLong),
+            0x4E => Ok(DeliveryStatus::DestinationDoesNotSupportSdsTl),
+            0x4F => Ok(DeliveryStatus::DestinationHostNotConnected),
+            0x50 => Ok(DeliveryStatus::ProtocolNotSupported),
+            0x51 => Ok(DeliveryStatus::DataCodingSchemeNotSupported),
+            0x52 => Ok(DeliveryStatus::DestinationMemoryFullMessageDiscarded),
+            0x60 => Ok(DeliveryStatus::DestinationMemoryFull),
+            0x61 => Ok(DeliveryStatus::DestinationMemoryAvailable),
+            0x62 => Ok(DeliveryStatus::StartPendingMessages),
+            0x63 => Ok(DeliveryStatus::NoPendingMessages),
+            0x80 => Ok(DeliveryStatus::StopSending),
+            0x81 => Ok(DeliveryStatus::StartSending),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DeliveryStatus {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            DeliveryStatus::ReceiptAcknowledgedByDestination => 0x00,
+            DeliveryStatus::ReceiptReportAcknowledgement => 0x01,
+            DeliveryStatus::ConsumedByDestination => 0x02,
+            DeliveryStatus::ConsumedReportAcknowledgement => 0x03,
+            DeliveryStatus::ForwardedToExternalNetwork => 0x04,
+            DeliveryStatus::SentToGroupAcknowledgementsPrevented => 0x05,
+            DeliveryStatus::ConcatenationPartReceived => 0x06,
+            DeliveryStatus::CongestionMessageStored => 0x20,
+            DeliveryStatus::MessageStored => 0x21,
+            DeliveryStatus::DestinationNotReachableMessageStored => 0x22,
+            DeliveryStatus::NetworkOverload => 0x40,
+            DeliveryStatus::ServicePermanentlyNotAvailable => 0x41,
+            DeliveryStatus::ServiceTemporarilyNotAvailable => 0x42,
+            DeliveryStatus::SourceNotAuthorised => 0x43,
+            DeliveryStatus::DestinationNotAuthorised => 0x44,
+            DeliveryStatus::UnknownDestination => 0x45,
+            DeliveryStatus::UnknownForwardAddress => 0x46,
+            DeliveryStatus::GroupAddressWithIndividualService => 0x47,
+            DeliveryStatus::ExpiredNotReceived => 0x48,
+            DeliveryStatus::ExpiredNotConsumed => 0x49,
+            DeliveryStatus::DeliveryFailed => 0x4A,
+            DeliveryStatus::DestinationNotRegistered => 0x4B,
+            DeliveryStatus::DestinationQueueFull => 0x4C,
+            DeliveryStatus::MessageTooLong => 0x4D,
+            DeliveryStatus::DestinationDoesNotSupportSdsTl => 0x4E,
+            DeliveryStatus::DestinationHostNotConnected => 0x4F,
+            DeliveryStatus::ProtocolNotSupported => 0x50,
+            DeliveryStatus::DataCodingSchemeNotSupported => 0x51,
+            DeliveryStatus::DestinationMemoryFullMessageDiscarded => 0x52,
+            DeliveryStatus::DestinationMemoryFull => 0x60,
+            DeliveryStatus::DestinationMemoryAvailable => 0x61,
+            DeliveryStatus::StartPendingMessages => 0x62,
+            DeliveryStatus::NoPendingMessages => 0x63,
+            DeliveryStatus::StopSending => 0x80,
+            DeliveryStatus::StartSending => 0x81,
+        }
+    }
+}
+
+impl From<DeliveryStatus> for u64 {
+    fn from(e: DeliveryStatus) -> Self {
+        e.into_raw()
+    }
+}
+
+/// The delivery status of the standard report equivalent to an SDS-SHORT REPORT (clause 29.3.3.4.4)
+impl From<ShortReportType> for DeliveryStatus {
+    fn from(e: ShortReportType) -> Self {
+        match e {
+            ShortReportType::ProtOrEncodingNotSupported => DeliveryStatus::ProtocolNotSupported,
+            ShortReportType::DestMemFull => DeliveryStatus::DestinationMemoryFull,
+            ShortReportType::MessageReceived => DeliveryStatus::ReceiptAcknowledgedByDestination,
+            ShortReportType::MessageConsumed => DeliveryStatus::ConsumedByDestination,
+        }
+    }
+}
+
+impl core::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeliveryStatus::ReceiptAcknowledgedByDestination => write!(f, "ReceiptAcknowledgedByDestination"),
+            DeliveryStatus::ReceiptReportAcknowledgement => write!(f, "ReceiptReportAcknowledgement"),
+            DeliveryStatus::ConsumedByDestination => write!(f, "ConsumedByDestination"),
+            DeliveryStatus::ConsumedReportAcknowledgement => write!(f, "ConsumedReportAcknowledgement"),
+            DeliveryStatus::ForwardedToExternalNetwork => write!(f, "ForwardedToExternalNetwork"),
+            DeliveryStatus::SentToGroupAcknowledgementsPrevented => write!(f, "SentToGroupAcknowledgementsPrevented"),
+            DeliveryStatus::ConcatenationPartReceived => write!(f, "ConcatenationPartReceived"),
+            DeliveryStatus::CongestionMessageStored => write!(f, "CongestionMessageStored"),
+            DeliveryStatus::MessageStored => write!(f, "MessageStored"),
+            DeliveryStatus::DestinationNotReachableMessageStored => write!(f, "DestinationNotReachableMessageStored"),
+            DeliveryStatus::NetworkOverload => write!(f, "NetworkOverload"),
+            DeliveryStatus::ServicePermanentlyNotAvailable => write!(f, "ServicePermanentlyNotAvailable"),
+            DeliveryStatus::ServiceTemporarilyNotAvailable => write!(f, "ServiceTemporarilyNotAvailable"),
+            DeliveryStatus::SourceNotAuthorised => write!(f, "SourceNotAuthorised"),
+            DeliveryStatus::DestinationNotAuthorised => write!(f, "DestinationNotAuthorised"),
+            DeliveryStatus::UnknownDestination => write!(f, "UnknownDestination"),
+            DeliveryStatus::UnknownForwardAddress => write!(f, "UnknownForwardAddress"),
+            DeliveryStatus::GroupAddressWithIndividualService => write!(f, "GroupAddressWithIndividualService"),
+            DeliveryStatus::ExpiredNotReceived => write!(f, "ExpiredNotReceived"),
+            DeliveryStatus::ExpiredNotConsumed => write!(f, "ExpiredNotConsumed"),
+            DeliveryStatus::DeliveryFailed => write!(f, "DeliveryFailed"),
+            DeliveryStatus::DestinationNotRegistered => write!(f, "DestinationNotRegistered"),
+            DeliveryStatus::DestinationQueueFull => write!(f, "DestinationQueueFull"),
+            DeliveryStatus::MessageTooLong => write!(f, "MessageTooLong"),
+            DeliveryStatus::DestinationDoesNotSupportSdsTl => write!(f, "DestinationDoesNotSupportSdsTl"),
+            DeliveryStatus::DestinationHostNotConnected => write!(f, "DestinationHostNotConnected"),
+            DeliveryStatus::ProtocolNotSupported => write!(f, "ProtocolNotSupported"),
+            DeliveryStatus::DataCodingSchemeNotSupported => write!(f, "DataCodingSchemeNotSupported"),
+            DeliveryStatus::DestinationMemoryFullMessageDiscarded => write!(f, "DestinationMemoryFullMessageDiscarded"),
+            DeliveryStatus::DestinationMemoryFull => write!(f, "DestinationMemoryFull"),
+            DeliveryStatus::DestinationMemoryAvailable => write!(f, "DestinationMemoryAvailable"),
+            DeliveryStatus::StartPendingMessages => write!(f, "StartPendingMessages"),
+            DeliveryStatus::NoPendingMessages => write!(f, "NoPendingMessages"),
+            DeliveryStatus::StopSending => write!(f, "StopSending"),
+            DeliveryStatus::StartSending => write!(f, "StartSending"),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+/// Clause 14.8.17 DTMF type
+/// The DTMF type information element shall indicate the meaning of the DTMF element.
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DtmfType {
+    ToneStart = 0,
+    ToneEnd = 1,
+    NotSupported = 2,
+    NotSubscribed = 3,
+}
+
+impl std::convert::TryFrom<u64> for DtmfType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(DtmfType::ToneStart),
+            1 => Ok(DtmfType::ToneEnd),
+            2 => Ok(DtmfType::NotSupported),
+            3 => Ok(DtmfType::NotSubscribed),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DtmfType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            DtmfType::ToneStart => 0,
+            DtmfType::ToneEnd => 1,
+            DtmfType::NotSupported => 2,
+            DtmfType::NotSubscribed => 3,
+        }
+    }
+}
+
+impl From<DtmfType> for u64 {
+    fn from(e: DtmfType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for DtmfType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DtmfType::ToneStart => write!(f, "ToneStart"),
+            DtmfType::ToneEnd => write!(f, "ToneEnd"),
+            DtmfType::NotSupported => write!(f, "NotSupported"),
+            DtmfType::NotSubscribed => write!(f, "NotSubscribed"),
+        }
+    }
+}
@@ -3,10 +3,16 @@ pub mod call_timeout;
 pub mod call_timeout_setup_phase;
 pub mod cmce_pdu_type_dl;
 pub mod cmce_pdu_type_ul;
+pub mod delivery_report_request;
+pub mod delivery_status;
 pub mod disconnect_cause;
+pub mod dtmf_type;
 pub mod party_type_identifier;
 pub mod pre_coded_status;
 pub mod sds_protocol_id;
+pub mod sds_tl_message_type;
 pub mod short_report_type;
+pub mod ss_pdu_type;
+pub mod ss_type;
 pub mod transmission_grant;
 pub mod type3_elem_id;
@@ -0,0 +1,49 @@
+/// Clause 29.4.3.8 Message type of the SDS-TL PDUs. Values 3 to 7 are defined per protocol,
+/// values 8 to 15 are available for user application definition
+/// Bits: 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SdsTlMessageType {
+    SdsTransfer = 0,
+    SdsReport = 1,
+    SdsAck = 2,
+}
+
+impl std::convert::TryFrom<u64> for SdsTlMessageType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(SdsTlMessageType::SdsTransfer),
+            1 => Ok(SdsTlMessageType::SdsReport),
+            2 => Ok(SdsTlMessageType::SdsAck),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SdsTlMessageType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SdsTlMessageType::SdsTransfer => 0,
+            SdsTlMessageType::SdsReport => 1,
+            SdsTlMessageType::SdsAck => 2,
+        }
+    }
+}
+
+impl From<SdsTlMessageType> for u64 {
+    fn from(e: SdsTlMessageType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SdsTlMessageType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SdsTlMessageType::SdsTransfer => write!(f, "SdsTransfer"),
+            SdsTlMessageType::SdsReport => write!(f, "SdsReport"),
+            SdsTlMessageType::SdsAck => write!(f, "SdsAck"),
+        }
+    }
+}
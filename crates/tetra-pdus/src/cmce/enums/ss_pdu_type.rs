@@ -0,0 +1,62 @@
+/// SS-PDU type information element (ETSI EN 300 392-9)
+/// The operation of an SS PDU. The SS-specific information following it depends on the SS-type.
+/// Bits: 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SsPduType {
+    /// MS requests activation of the service, with the SS-specific settings
+    Activate = 0,
+    /// MS requests deactivation of the service
+    Deactivate = 1,
+    /// MS requests the current state of the service
+    Interrogate = 2,
+    /// SwMI reports the state of the service after a request
+    Result = 3,
+    /// SwMI rejects the request
+    Reject = 4,
+}
+
+impl std::convert::TryFrom<u64> for SsPduType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(SsPduType::Activate),
+            1 => Ok(SsPduType::Deactivate),
+            2 => Ok(SsPduType::Interrogate),
+            3 => Ok(SsPduType::Result),
+            4 => Ok(SsPduType::Reject),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SsPduType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SsPduType::Activate => 0,
+            SsPduType::Deactivate => 1,
+            SsPduType::Interrogate => 2,
+            SsPduType::Result => 3,
+            SsPduType::Reject => 4,
+        }
+    }
+}
+
+impl From<SsPduType> for u64 {
+    fn from(e: SsPduType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SsPduType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SsPduType::Activate => write!(f, "Activate"),
+            SsPduType::Deactivate => write!(f, "Deactivate"),
+            SsPduType::Interrogate => write!(f, "Interrogate"),
+            SsPduType::Result => write!(f, "Result"),
+            SsPduType::Reject => write!(f, "Reject"),
+        }
+    }
+}
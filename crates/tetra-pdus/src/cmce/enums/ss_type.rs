@@ -0,0 +1,82 @@
+/// SS-type information element (ETSI EN 300 392-9)
+/// Identifies the supplementary service an SS PDU in U-FACILITY or D-FACILITY belongs to.
+/// Bits: 6
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SsType {
+    /// Calling line identification presentation
+    Clip = 1,
+    /// Connected line identification presentation
+    Colp = 2,
+    /// Calling/connected line identification restriction
+    Clir = 3,
+    /// Talking party identification
+    Tpi = 5,
+    /// Call forwarding unconditional
+    Cfu = 6,
+    /// Call forwarding on busy
+    Cfb = 7,
+    /// Call forwarding on no reply
+    Cfnry = 8,
+    /// Call forwarding on not reachable
+    Cfnrc = 9,
+    /// Area selection
+    AreaSelection = 10,
+}
+
+impl std::convert::TryFrom<u64> for SsType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            1 => Ok(SsType::Clip),
+            2 => Ok(SsType::Colp),
+            3 => Ok(SsType::Clir),
+            5 => Ok(SsType::Tpi),
+            6 => Ok(SsType::Cfu),
+            7 => Ok(SsType::Cfb),
+            8 => Ok(SsType::Cfnry),
+            9 => Ok(SsType::Cfnrc),
+            10 => Ok(SsType::AreaSelection),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SsType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SsType::Clip => 1,
+            SsType::Colp => 2,
+            SsType::Clir => 3,
+            SsType::Tpi => 5,
+            SsType::Cfu => 6,
+            SsType::Cfb => 7,
+            SsType::Cfnry => 8,
+            SsType::Cfnrc => 9,
+            SsType::AreaSelection => 10,
+        }
+    }
+}
+
+impl From<SsType> for u64 {
+    fn from(e: SsType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SsType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SsType::Clip => write!(f, "Clip"),
+            SsType::Colp => write!(f, "Colp"),
+            SsType::Clir => write!(f, "Clir"),
+            SsType::Tpi => write!(f, "Tpi"),
+            SsType::Cfu => write!(f, "Cfu"),
+            SsType::Cfb => write!(f, "Cfb"),
+            SsType::Cfnry => write!(f, "Cfnry"),
+            SsType::Cfnrc => write!(f, "Cfnrc"),
+            SsType::AreaSelection => write!(f, "AreaSelection"),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use core::fmt;
+
+use tetra_core::{PduParseErr, typed_pdu_fields::Type3FieldGeneric};
+
+use crate::cmce::enums::{dtmf_type::DtmfType, type3_elem_id::CmceType3ElemId};
+
+/// Digits in the order of their 4-bit DTMF digit code
+const DTMF_DIGITS: [char; 16] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '*', '#', 'A', 'B', 'C', 'D'];
+
+/// Clause 14.8.17 DTMF
+/// Carries DTMF digits dialled during a call, in U-INFO and D-INFO
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dtmf {
+    /// 3 bits
+    pub dtmf_type: DtmfType,
+    /// 4 bits per digit, only present for DtmfType::ToneStart. Digits as characters 0-9, *, # and A-D
+    pub digits: String,
+}
+
+impl Dtmf {
+    /// Most digits that fit in a Type3FieldGeneric, which holds up to 64 bits
+    pub const MAX_DIGITS: usize = (64 - 3) / 4;
+
+    /// Builds a tone start element for the given digits. Returns None if the digits are empty, too many
+    /// or contain a character that is not a DTMF digit.
+    pub fn tone_start(digits: &str) -> Option<Self> {
+        if digits.is_empty() || digits.chars().count() > Self::MAX_DIGITS || !digits.chars().all(Self::is_digit) {
+            return None;
+        }
+        Some(Dtmf {
+            dtmf_type: DtmfType::ToneStart,
+            digits: digits.to_ascii_uppercase(),
+        })
+    }
+
+    /// Returns true if the character is a DTMF digit, accepting a-d in lower case as well
+    pub fn is_digit(c: char) -> bool {
+        DTMF_DIGITS.contains(&c.to_ascii_uppercase())
+    }
+
+    pub fn from_type3(field: &Type3FieldGeneric) -> Result<Self, PduParseErr> {
+        if field.len < 3 || field.len > 64 {
+            return Err(PduParseErr::InconsistentLength {
+                expected: 3,
+                found: field.len,
+            });
+        }
+        let raw_type = field.data >> (field.len - 3);
+        let dtmf_type = DtmfType::try_from(raw_type).map_err(|_| PduParseErr::InvalidValue {
+            field: "dtmf_type",
+            value: raw_type,
+        })?;
+
+        // Digits follow the type, any bits left over that don't make up a full digit are ignored
+        let num_digits = if dtmf_type == DtmfType::ToneStart { (field.len - 3) / 4 } else { 0 };
+        let digits = (0..num_digits)
+            .map(|i| {
+                let shift = field.len - 3 - 4 * (i + 1);
+                DTMF_DIGITS[((field.data >> shift) & 0xF) as usize]
+            })
+            .collect();
+
+        Ok(Dtmf { dtmf_type, digits })
+    }
+
+    pub fn to_type3(&self) -> Type3FieldGeneric {
+        assert!(self.digits.len() <= Self::MAX_DIGITS, "too many DTMF digits");
+        let mut data = self.dtmf_type.into_raw();
+        let mut len = 3;
+        if self.dtmf_type == DtmfType::ToneStart {
+            for c in self.digits.chars() {
+                let code = DTMF_DIGITS
+                    .iter()
+                    .position(|d| *d == c.to_ascii_uppercase())
+                    .expect("invalid DTMF digit");
+                data = (data << 4) | code as u64;
+                len += 4;
+            }
+        }
+        Type3FieldGeneric {
+            field_id: CmceType3ElemId::Dtmf.into(),
+            len,
+            data,
+        }
+    }
+}
+
+impl fmt::Display for Dtmf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dtmf {{ dtmf_type: {}, digits: {:?} }}", self.dtmf_type, self.digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dtmf_roundtrip() {
+        let dtmf = Dtmf::tone_start("1*#d").unwrap();
+        assert_eq!(dtmf.digits, "1*#D");
+
+        let field = dtmf.to_type3();
+        assert_eq!(field.len, 3 + 4 * 4);
+        assert_eq!(field.data, 0b000_0001_1010_1011_1111);
+        assert_eq!(Dtmf::from_type3(&field).unwrap(), dtmf);
+    }
+
+    #[test]
+    fn test_dtmf_tone_end_has_no_digits() {
+        let field = Type3FieldGeneric {
+            field_id: CmceType3ElemId::Dtmf.into(),
+            len: 3,
+            data: 0b001,
+        };
+        let dtmf = Dtmf::from_type3(&field).unwrap();
+        assert_eq!(dtmf.dtmf_type, DtmfType::ToneEnd);
+        assert!(dtmf.digits.is_empty());
+        assert_eq!(dtmf.to_type3(), field);
+    }
+
+    #[test]
+    fn test_dtmf_rejects_invalid_digits() {
+        assert!(Dtmf::tone_start("").is_none());
+        assert!(Dtmf::tone_start("12E").is_none());
+        assert!(Dtmf::tone_start("0123456789012345").is_none());
+        assert!(Dtmf::tone_start("012345678901234").is_some());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod basic_service_information;
+pub mod dtmf;
 pub mod sds_short_report;
+pub mod ss_facility;
@@ -0,0 +1,88 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, PduParseErr};
+
+/// SS PDU carried in U-FACILITY and D-FACILITY (ETSI EN 300 392-9)
+/// The SS-type and SS-PDU type are common to all supplementary services; the SS-specific
+/// information that follows is kept as raw bits, to be interpreted by the handler of the service.
+#[derive(Debug, Clone)]
+pub struct SsFacility {
+    /// 6 bits, see SsType. Kept raw so that facilities of unknown services can still be parsed and refused.
+    pub ss_type: u8,
+    /// 5 bits, see SsPduType
+    pub ss_pdu_type: u8,
+    /// SS-specific information, all remaining bits of the PDU
+    pub ss_data: BitBuffer,
+}
+
+impl SsFacility {
+    pub fn new(ss_type: u8, ss_pdu_type: u8, ss_data: BitBuffer) -> Self {
+        SsFacility {
+            ss_type,
+            ss_pdu_type,
+            ss_data,
+        }
+    }
+
+    /// Reads the SS PDU, taking all remaining bits of the buffer as SS-specific information
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let ss_type = buffer.read_field(6, "ss_type")? as u8;
+        let ss_pdu_type = buffer.read_field(5, "ss_pdu_type")? as u8;
+        let ss_data = BitBuffer::from_bitbuffer_pos(buffer);
+        buffer.seek(buffer.get_len());
+        Ok(SsFacility {
+            ss_type,
+            ss_pdu_type,
+            ss_data,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.ss_type as u64, 6);
+        buffer.write_bits(self.ss_pdu_type as u64, 5);
+        let mut data = BitBuffer::from_bitbuffer(&self.ss_data);
+        let len = data.get_len();
+        buffer.copy_bits(&mut data, len);
+    }
+}
+
+impl fmt::Display for SsFacility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SsFacility {{ ss_type: {} ss_pdu_type: {} ss_data: {} }}",
+            self.ss_type,
+            self.ss_pdu_type,
+            self.ss_data.dump_bin()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ss_facility_roundtrip() {
+        let bitstr = "00011000000101";
+        let mut buffer = BitBuffer::from_bitstr(bitstr);
+        let facility = SsFacility::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(facility.ss_type, 6);
+        assert_eq!(facility.ss_pdu_type, 0);
+        assert_eq!(facility.ss_data.to_bitstr(), "101");
+        assert_eq!(buffer.get_len_remaining(), 0);
+
+        let mut buffer_out = BitBuffer::new_autoexpand(16);
+        facility.to_bitbuf(&mut buffer_out);
+        assert_eq!(buffer_out.to_bitstr(), bitstr);
+    }
+
+    #[test]
+    fn test_ss_facility_without_data() {
+        let mut buffer = BitBuffer::from_bitstr("00010100010");
+        let facility = SsFacility::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(facility.ss_type, 5);
+        assert_eq!(facility.ss_pdu_type, 2);
+        assert_eq!(facility.ss_data.get_len(), 0);
+    }
+}
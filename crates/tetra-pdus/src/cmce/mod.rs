@@ -1,4 +1,5 @@
 pub mod enums;
 pub mod fields;
 pub mod pdus;
+pub mod sds_tl;
 pub mod structs;
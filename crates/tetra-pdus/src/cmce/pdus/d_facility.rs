@@ -1,7 +1,7 @@
 use core::fmt;
 
 use crate::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
-use tetra_core::typed_pdu_fields::*;
+use crate::cmce::fields::ss_facility::SsFacility;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 /// Representation of the D-FACILITY PDU (Clause 14.7.1.7).
@@ -11,39 +11,57 @@ use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 // note 1: Contents of this PDU shall be defined by SS protocols.
 #[derive(Debug)]
-pub struct DFacility {}
+pub struct DFacility {
+    /// The SS PDU, taking up the remainder of the PDU. See note 1.
+    pub facility: SsFacility,
+}
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DFacility {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(5, "pdu_type")?;
         expect_pdu_type!(pdu_type, CmcePduTypeDl::DFacility)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // The SS PDU follows directly, there are no CMCE type2, type3 or type4 elements
+        let facility = SsFacility::from_bitbuf(buffer)?;
 
-        Ok(DFacility {})
+        Ok(DFacility { facility })
     }
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DFacility.into_raw(), 5);
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
+        self.facility.to_bitbuf(buffer);
         Ok(())
     }
 }
 
 impl fmt::Display for DFacility {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "DFacility {{ }}",)
+        write!(f, "DFacility {{ facility: {} }}", self.facility)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetra_core::debug;
+
+    #[test]
+    fn test_parse_d_facility() {
+        debug::setup_logging_verbose();
+        let bitstr = "10000000101000111";
+        let mut buffer = BitBuffer::from_bitstr(bitstr);
+        let result = DFacility::from_bitbuf(&mut buffer).unwrap();
+        tracing::info!("Parsed DFacility: {:?}", result);
+        assert_eq!(result.facility.ss_type, 5);
+        assert_eq!(result.facility.ss_pdu_type, 3);
+        assert_eq!(result.facility.ss_data.to_bitstr(), "1");
+        assert!(buffer.get_len_remaining() == 0);
+
+        let mut buffer_out = BitBuffer::new_autoexpand(32);
+        result.to_bitbuf(&mut buffer_out).unwrap();
+        assert_eq!(bitstr, buffer_out.to_bitstr());
     }
 }
@@ -1,7 +1,7 @@
 use core::fmt;
 
 use crate::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
-use tetra_core::typed_pdu_fields::*;
+use crate::cmce::fields::ss_facility::SsFacility;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 /// Representation of the U-FACILITY PDU (Clause 14.7.2.5).
@@ -11,39 +11,57 @@ use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 // note 1: Contents of this PDU shall be defined by SS protocols.
 #[derive(Debug)]
-pub struct UFacility {}
+pub struct UFacility {
+    /// The SS PDU, taking up the remainder of the PDU. See note 1.
+    pub facility: SsFacility,
+}
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl UFacility {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(5, "pdu_type")?;
         expect_pdu_type!(pdu_type, CmcePduTypeUl::UFacility)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // The SS PDU follows directly, there are no CMCE type2, type3 or type4 elements
+        let facility = SsFacility::from_bitbuf(buffer)?;
 
-        Ok(UFacility {})
+        Ok(UFacility { facility })
     }
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UFacility.into_raw(), 5);
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
+        self.facility.to_bitbuf(buffer);
         Ok(())
     }
 }
 
 impl fmt::Display for UFacility {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "UFacility {{ }}",)
+        write!(f, "UFacility {{ facility: {} }}", self.facility)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetra_core::debug;
+
+    #[test]
+    fn test_parse_u_facility() {
+        debug::setup_logging_verbose();
+        let bitstr = "1000000011000000000000000000001111101010";
+        let mut buffer = BitBuffer::from_bitstr(bitstr);
+        let result = UFacility::from_bitbuf(&mut buffer).unwrap();
+        tracing::info!("Parsed UFacility: {:?}", result);
+        assert_eq!(result.facility.ss_type, 6);
+        assert_eq!(result.facility.ss_pdu_type, 0);
+        assert_eq!(result.facility.ss_data.clone().read_bits(24), Some(1002));
+        assert!(buffer.get_len_remaining() == 0);
+
+        let mut buffer_out = BitBuffer::new_autoexpand(32);
+        result.to_bitbuf(&mut buffer_out).unwrap();
+        assert_eq!(bitstr, buffer_out.to_bitstr());
     }
 }
@@ -0,0 +1,108 @@
+use tetra_core::{BitBuffer, PduParseErr};
+
+/// Forward address type value indicating that no forward address is present
+const FORWARD_ADDRESS_NONE: u64 = 0b111;
+
+/// Clause 29.4.3.5 Forward address type, with the forward address it announces.
+/// Present in SDS-TRANSFER and SDS-REPORT when storage/forward control is available.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForwardAddress {
+    /// Type 0, 8 bits short number address
+    Sna(u8),
+    /// Type 1, 24 bits
+    Ssi(u32),
+    /// Type 2, 24 bits SSI followed by 24 bits address extension
+    Tsi { ssi: u32, extension: u32 },
+    /// Type 3, 8 bits number of digits followed by 4 bits per digit, with a dummy digit
+    /// padding an odd number of digits to whole octets
+    ExternalSubscriberNumber(Vec<u8>),
+}
+
+impl ForwardAddress {
+    /// Reads the 3-bit forward address type and the address it announces, if any
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Option<Self>, PduParseErr> {
+        let address_type = buffer.read_field(3, "forward_address_type")?;
+        let address = match address_type {
+            0 => ForwardAddress::Sna(buffer.read_field(8, "forward_address_sna")? as u8),
+            1 => ForwardAddress::Ssi(buffer.read_field(24, "forward_address_ssi")? as u32),
+            2 => ForwardAddress::Tsi {
+                ssi: buffer.read_field(24, "forward_address_ssi")? as u32,
+                extension: buffer.read_field(24, "forward_address_extension")? as u32,
+            },
+            3 => {
+                let num_digits = buffer.read_field(8, "number_of_external_subscriber_number_digits")? as usize;
+                let mut digits = Vec::with_capacity(num_digits);
+                for _ in 0..num_digits {
+                    digits.push(buffer.read_field(4, "external_subscriber_number_digit")? as u8);
+                }
+                if num_digits % 2 == 1 {
+                    buffer.read_field(4, "dummy_digit")?;
+                }
+                ForwardAddress::ExternalSubscriberNumber(digits)
+            }
+            FORWARD_ADDRESS_NONE => return Ok(None),
+            _ => {
+                return Err(PduParseErr::InvalidValue {
+                    field: "forward_address_type",
+                    value: address_type,
+                });
+            }
+        };
+        Ok(Some(address))
+    }
+
+    /// Writes the 3-bit forward address type and the address, or the type for no forward address
+    pub fn to_bitbuf(address: Option<&Self>, buffer: &mut BitBuffer) {
+        match address {
+            Some(ForwardAddress::Sna(sna)) => {
+                buffer.write_bits(0, 3);
+                buffer.write_bits(*sna as u64, 8);
+            }
+            Some(ForwardAddress::Ssi(ssi)) => {
+                buffer.write_bits(1, 3);
+                buffer.write_bits(*ssi as u64, 24);
+            }
+            Some(ForwardAddress::Tsi { ssi, extension }) => {
+                buffer.write_bits(2, 3);
+                buffer.write_bits(*ssi as u64, 24);
+                buffer.write_bits(*extension as u64, 24);
+            }
+            Some(ForwardAddress::ExternalSubscriberNumber(digits)) => {
+                buffer.write_bits(3, 3);
+                buffer.write_bits(digits.len() as u64, 8);
+                for digit in digits {
+                    buffer.write_bits(*digit as u64, 4);
+                }
+                if digits.len() % 2 == 1 {
+                    buffer.write_bits(0, 4);
+                }
+            }
+            None => buffer.write_bits(FORWARD_ADDRESS_NONE, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_address_roundtrip() {
+        let addresses = [
+            None,
+            Some(ForwardAddress::Ssi(2000001)),
+            Some(ForwardAddress::Tsi {
+                ssi: 2000001,
+                extension: 0x123456,
+            }),
+            Some(ForwardAddress::ExternalSubscriberNumber(vec![1, 1, 2])),
+        ];
+        for address in addresses {
+            let mut buffer = BitBuffer::new_autoexpand(64);
+            ForwardAddress::to_bitbuf(address.as_ref(), &mut buffer);
+            buffer.seek(0);
+            assert_eq!(ForwardAddress::from_bitbuf(&mut buffer).unwrap(), address);
+            assert_eq!(buffer.get_len_remaining(), 0);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Clause 29 SDS-TL, the transport layer carried in the type 4 user data of SDS for protocol identifiers
+//! 128 and up. The SDS-SHORT REPORT travels in a pre-coded status instead, see `fields::sds_short_report`.
+
+pub mod forward_address;
+pub mod sds_ack;
+pub mod sds_report;
+pub mod sds_transfer;
+pub mod user_data_header;
+
+use tetra_core::{BitBuffer, PduParseErr};
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+
+use crate::cmce::enums::sds_tl_message_type::SdsTlMessageType;
+use crate::cmce::sds_tl::{sds_ack::SdsAck, sds_report::SdsReport, sds_transfer::SdsTransfer};
+
+/// Protocol identifiers from 128 up use SDS-TL (clause 29.4.3.9)
+pub fn is_sds_tl_protocol(protocol_identifier: u8) -> bool {
+    protocol_identifier >= 128
+}
+
+/// An SDS-TL PDU of one of the message types common to all SDS-TL protocols
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SdsTlPdu {
+    Transfer(SdsTransfer),
+    Report(SdsReport),
+    Ack(SdsAck),
+}
+
+impl SdsTlPdu {
+    /// Parse from BitBuffer, taking all remaining bits of the buffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let protocol_identifier = buffer.peek_bits(8).ok_or(PduParseErr::BufferEnded {
+            field: Some("protocol_identifier"),
+        })?;
+        if !is_sds_tl_protocol(protocol_identifier as u8) {
+            return Err(PduParseErr::InvalidValue {
+                field: "protocol_identifier",
+                value: protocol_identifier,
+            });
+        }
+        let message_type = buffer.peek_bits_posoffset(8, 4).ok_or(PduParseErr::BufferEnded {
+            field: Some("message_type"),
+        })?;
+        match SdsTlMessageType::try_from(message_type) {
+            Ok(SdsTlMessageType::SdsTransfer) => Ok(SdsTlPdu::Transfer(SdsTransfer::from_bitbuf(buffer)?)),
+            Ok(SdsTlMessageType::SdsReport) => Ok(SdsTlPdu::Report(SdsReport::from_bitbuf(buffer)?)),
+            Ok(SdsTlMessageType::SdsAck) => Ok(SdsTlPdu::Ack(SdsAck::from_bitbuf(buffer)?)),
+            Err(_) => Err(PduParseErr::InvalidValue {
+                field: "message_type",
+                value: message_type,
+            }),
+        }
+    }
+
+    /// Serialize this PDU into the given BitBuffer
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        match self {
+            SdsTlPdu::Transfer(pdu) => pdu.to_bitbuf(buffer),
+            SdsTlPdu::Report(pdu) => pdu.to_bitbuf(buffer),
+            SdsTlPdu::Ack(pdu) => pdu.to_bitbuf(buffer),
+        }
+    }
+
+    /// Parse the SDS-TL PDU in SDS user data. Fails for user data other than type 4, and for protocols not using SDS-TL
+    pub fn from_user_data(user_data: &SdsUserData) -> Result<Self, PduParseErr> {
+        let SdsUserData::Type4(len_bits, data) = user_data else {
+            return Err(PduParseErr::InvalidValue {
+                field: "short_data_type_identifier",
+                value: user_data.type_identifier() as u64,
+            });
+        };
+        if (*len_bits as usize) > data.len() * 8 {
+            return Err(PduParseErr::InconsistentLength {
+                expected: *len_bits as usize,
+                found: data.len() * 8,
+            });
+        }
+        let mut buffer = BitBuffer::from_bytes(data);
+        buffer.set_raw_end(*len_bits as usize);
+        Self::from_bitbuf(&mut buffer)
+    }
+
+    /// Serialize this PDU as type 4 SDS user data
+    pub fn to_user_data(&self) -> SdsUserData {
+        let mut buffer = BitBuffer::new_autoexpand(64);
+        self.to_bitbuf(&mut buffer);
+        let len_bits = buffer.get_len();
+        let mut data = buffer.into_bytes();
+        data.truncate(len_bits.div_ceil(8));
+        SdsUserData::Type4(len_bits as u16, data)
+    }
+
+    pub fn protocol_identifier(&self) -> u8 {
+        match self {
+            SdsTlPdu::Transfer(pdu) => pdu.protocol_identifier,
+            SdsTlPdu::Report(pdu) => pdu.protocol_identifier,
+            SdsTlPdu::Ack(pdu) => pdu.protocol_identifier,
+        }
+    }
+
+    pub fn message_reference(&self) -> u8 {
+        match self {
+            SdsTlPdu::Transfer(pdu) => pdu.message_reference,
+            SdsTlPdu::Report(pdu) => pdu.message_reference,
+            SdsTlPdu::Ack(pdu) => pdu.message_reference,
+        }
+    }
+}
+
+/// Reads the user data that ends an SDS-TL PDU: all remaining bits of the buffer, in whole octets
+pub(crate) fn read_user_data(buffer: &mut BitBuffer) -> Result<Vec<u8>, PduParseErr> {
+    let num_bits = buffer.get_len_remaining();
+    let mut data = vec![0u8; num_bits.div_ceil(8)];
+    buffer
+        .read_bits_into_slice(num_bits, &mut data)
+        .ok_or(PduParseErr::BufferEnded { field: Some("user_data") })?;
+    Ok(data)
+}
+
+pub(crate) fn write_user_data(buffer: &mut BitBuffer, data: &[u8]) {
+    for byte in data {
+        buffer.write_bits(*byte as u64, 8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmce::enums::delivery_status::DeliveryStatus;
+
+    #[test]
+    fn test_sds_tl_user_data_roundtrip() {
+        // Text message "hi", message received report requested, MR 7
+        let user_data = SdsUserData::Type4(48, vec![0x82, 0x04, 0x07, 0x01, b'h', b'i']);
+        let pdu = SdsTlPdu::from_user_data(&user_data).unwrap();
+        let SdsTlPdu::Transfer(transfer) = &pdu else {
+            panic!("expected SDS-TRANSFER, got {:?}", pdu);
+        };
+        assert_eq!(transfer.protocol_identifier, 0x82);
+        assert_eq!(transfer.message_reference, 7);
+        assert_eq!(transfer.user_data, vec![0x01, b'h', b'i']);
+        assert_eq!(pdu.to_user_data(), user_data);
+
+        let report = SdsTlPdu::Report(SdsReport::new(0x82, DeliveryStatus::ReceiptAcknowledgedByDestination, 7));
+        assert_eq!(report.to_user_data(), SdsUserData::Type4(32, vec![0x82, 0x10, 0x00, 0x07]));
+    }
+
+    #[test]
+    fn test_non_sds_tl_user_data() {
+        // Simple text messaging does not use SDS-TL
+        let user_data = SdsUserData::Type4(24, vec![0x02, 0x01, b'h']);
+        assert!(SdsTlPdu::from_user_data(&user_data).is_err());
+        assert!(SdsTlPdu::from_user_data(&SdsUserData::Type1(0x8210)).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, PduParseErr, expect_pdu_type};
+
+use crate::cmce::enums::{delivery_status::DeliveryStatus, sds_tl_message_type::SdsTlMessageType};
+
+/// Clause 29.4.2.1 SDS-ACK
+/// This PDU shall be used to acknowledge an SDS-REPORT requiring acknowledgement.
+/// Response expected: -
+/// Response to: SDS-REPORT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdsAck {
+    /// 8 bits, the protocol identifier of the acknowledged SDS-REPORT
+    pub protocol_identifier: u8,
+    /// 8 bits, see DeliveryStatus. Kept raw as reserved values may be received
+    pub delivery_status: u8,
+    /// 8 bits. The same value as in the acknowledged SDS-REPORT
+    pub message_reference: u8,
+}
+
+impl SdsAck {
+    pub fn new(protocol_identifier: u8, delivery_status: DeliveryStatus, message_reference: u8) -> Self {
+        SdsAck {
+            protocol_identifier,
+            delivery_status: delivery_status.into_raw() as u8,
+            message_reference,
+        }
+    }
+
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let protocol_identifier = buffer.read_field(8, "protocol_identifier")? as u8;
+        let message_type = buffer.read_field(4, "message_type")?;
+        expect_pdu_type!(message_type, SdsTlMessageType::SdsAck)?;
+
+        buffer.read_field(4, "reserved")?;
+        let delivery_status = buffer.read_field(8, "delivery_status")? as u8;
+        let message_reference = buffer.read_field(8, "message_reference")? as u8;
+
+        Ok(SdsAck {
+            protocol_identifier,
+            delivery_status,
+            message_reference,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.protocol_identifier as u64, 8);
+        buffer.write_bits(SdsTlMessageType::SdsAck.into_raw(), 4);
+        buffer.write_bits(0, 4);
+        buffer.write_bits(self.delivery_status as u64, 8);
+        buffer.write_bits(self.message_reference as u64, 8);
+    }
+}
+
+impl fmt::Display for SdsAck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SdsAck {{ protocol_identifier: {} delivery_status: 0x{:02x} message_reference: {} }}",
+            self.protocol_identifier, self.delivery_status, self.message_reference,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sds_ack_roundtrip() {
+        let pdu = SdsAck::new(0x82, DeliveryStatus::ReceiptReportAcknowledgement, 0x42);
+        let mut buffer = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buffer);
+        assert_eq!(buffer.to_bitstr(), "10000010001000000000000101000010");
+
+        buffer.seek(0);
+        assert_eq!(SdsAck::from_bitbuf(&mut buffer).unwrap(), pdu);
+    }
+}
@@ -0,0 +1,136 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, PduParseErr, expect_pdu_type};
+
+use crate::cmce::enums::{delivery_status::DeliveryStatus, sds_tl_message_type::SdsTlMessageType};
+use crate::cmce::sds_tl::{forward_address::ForwardAddress, read_user_data, write_user_data};
+
+/// Clause 29.4.2.2 SDS-REPORT
+/// This PDU shall be used to report on the delivery of an SDS-TRANSFER.
+/// Response expected: SDS-ACK, if acknowledgement is required
+/// Response to: SDS-TRANSFER
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdsReport {
+    /// 8 bits, the protocol identifier of the reported SDS-TRANSFER
+    pub protocol_identifier: u8,
+    /// 1 bit
+    pub ack_required: bool,
+    /// 8 bits, see DeliveryStatus. Kept raw as reserved values may be received
+    pub delivery_status: u8,
+    /// 8 bits. The same value as in the reported SDS-TRANSFER
+    pub message_reference: u8,
+    /// Conditional 5 bits, present if storage/forward control is available
+    pub validity_period: Option<u8>,
+    /// Conditional, may only be present if storage/forward control is available
+    pub forward_address: Option<ForwardAddress>,
+    /// Optional user data, in whole octets
+    pub user_data: Vec<u8>,
+}
+
+impl SdsReport {
+    /// A report without acknowledgement request, storage information or user data
+    pub fn new(protocol_identifier: u8, delivery_status: DeliveryStatus, message_reference: u8) -> Self {
+        SdsReport {
+            protocol_identifier,
+            ack_required: false,
+            delivery_status: delivery_status.into_raw() as u8,
+            message_reference,
+            validity_period: None,
+            forward_address: None,
+            user_data: Vec::new(),
+        }
+    }
+
+    /// Parse from BitBuffer, taking all remaining bits of the buffer as user data
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let protocol_identifier = buffer.read_field(8, "protocol_identifier")? as u8;
+        let message_type = buffer.read_field(4, "message_type")?;
+        expect_pdu_type!(message_type, SdsTlMessageType::SdsReport)?;
+
+        let ack_required = buffer.read_field(1, "ack_required")? == 1;
+        buffer.read_field(2, "reserved")?;
+        let storage = buffer.read_field(1, "storage_forward_control")? == 1;
+        let delivery_status = buffer.read_field(8, "delivery_status")? as u8;
+        let message_reference = buffer.read_field(8, "message_reference")? as u8;
+
+        // Conditional
+        let (validity_period, forward_address) = if storage {
+            let validity_period = buffer.read_field(5, "validity_period")? as u8;
+            (Some(validity_period), ForwardAddress::from_bitbuf(buffer)?)
+        } else {
+            (None, None)
+        };
+
+        // Optional
+        let user_data = read_user_data(buffer)?;
+
+        Ok(SdsReport {
+            protocol_identifier,
+            ack_required,
+            delivery_status,
+            message_reference,
+            validity_period,
+            forward_address,
+            user_data,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.protocol_identifier as u64, 8);
+        buffer.write_bits(SdsTlMessageType::SdsReport.into_raw(), 4);
+        buffer.write_bits(self.ack_required as u64, 1);
+        buffer.write_bits(0, 2);
+        buffer.write_bits(self.validity_period.is_some() as u64, 1);
+        buffer.write_bits(self.delivery_status as u64, 8);
+        buffer.write_bits(self.message_reference as u64, 8);
+
+        // Conditional
+        if let Some(validity_period) = self.validity_period {
+            buffer.write_bits(validity_period as u64, 5);
+            ForwardAddress::to_bitbuf(self.forward_address.as_ref(), buffer);
+        }
+
+        write_user_data(buffer, &self.user_data);
+    }
+}
+
+impl fmt::Display for SdsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SdsReport {{ protocol_identifier: {} ack_required: {} delivery_status: 0x{:02x} message_reference: {} validity_period: {:?} forward_address: {:?} user_data: {:02x?} }}",
+            self.protocol_identifier,
+            self.ack_required,
+            self.delivery_status,
+            self.message_reference,
+            self.validity_period,
+            self.forward_address,
+            self.user_data,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sds_report_roundtrip() {
+        // Stored by SwMI, acknowledgement required
+        let mut buffer = BitBuffer::from_bytes(&[0x82, 0x18, 0x22, 0x07]);
+        let pdu = SdsReport::from_bitbuf(&mut buffer).unwrap();
+        assert!(pdu.ack_required);
+        assert_eq!(
+            DeliveryStatus::try_from(pdu.delivery_status as u64),
+            Ok(DeliveryStatus::DestinationNotReachableMessageStored)
+        );
+        assert_eq!(pdu.message_reference, 7);
+        assert_eq!(pdu.validity_period, None);
+        assert!(pdu.user_data.is_empty());
+
+        let mut buffer_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buffer_out);
+        assert_eq!(buffer_out.into_bytes(), vec![0x82, 0x18, 0x22, 0x07]);
+    }
+}
@@ -0,0 +1,123 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, PduParseErr, expect_pdu_type};
+
+use crate::cmce::enums::{delivery_report_request::DeliveryReportRequest, sds_tl_message_type::SdsTlMessageType};
+use crate::cmce::sds_tl::{forward_address::ForwardAddress, read_user_data, write_user_data};
+
+/// Clause 29.4.2.4 SDS-TRANSFER
+/// This PDU shall be used to send user data, with a request for delivery reports from the destination.
+/// Response expected: SDS-REPORT or SDS-SHORT REPORT, if a delivery report is requested
+/// Response to: -
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdsTransfer {
+    /// 8 bits, see SdsProtocolId. Kept raw as values may be user defined
+    pub protocol_identifier: u8,
+    /// 2 bits
+    pub delivery_report_request: DeliveryReportRequest,
+    /// 1 bit, service selection / short form report. Allows the destination to answer with an SDS-SHORT REPORT
+    pub short_form_report: bool,
+    /// 8 bits. Identifies the message in the reports on it
+    pub message_reference: u8,
+    /// Conditional 5 bits, present if storage/forward control is available
+    pub validity_period: Option<u8>,
+    /// Conditional, may only be present if storage/forward control is available
+    pub forward_address: Option<ForwardAddress>,
+    /// User data of the protocol, in whole octets
+    pub user_data: Vec<u8>,
+}
+
+impl SdsTransfer {
+    /// Parse from BitBuffer, taking all remaining bits of the buffer as user data
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let protocol_identifier = buffer.read_field(8, "protocol_identifier")? as u8;
+        let message_type = buffer.read_field(4, "message_type")?;
+        expect_pdu_type!(message_type, SdsTlMessageType::SdsTransfer)?;
+
+        let delivery_report_request = DeliveryReportRequest::try_from(buffer.read_field(2, "delivery_report_request")?).unwrap(); // never fails
+        let short_form_report = buffer.read_field(1, "short_form_report")? == 1;
+        let storage = buffer.read_field(1, "storage_forward_control")? == 1;
+        let message_reference = buffer.read_field(8, "message_reference")? as u8;
+
+        // Conditional
+        let (validity_period, forward_address) = if storage {
+            let validity_period = buffer.read_field(5, "validity_period")? as u8;
+            (Some(validity_period), ForwardAddress::from_bitbuf(buffer)?)
+        } else {
+            (None, None)
+        };
+
+        let user_data = read_user_data(buffer)?;
+
+        Ok(SdsTransfer {
+            protocol_identifier,
+            delivery_report_request,
+            short_form_report,
+            message_reference,
+            validity_period,
+            forward_address,
+            user_data,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.protocol_identifier as u64, 8);
+        buffer.write_bits(SdsTlMessageType::SdsTransfer.into_raw(), 4);
+        buffer.write_bits(self.delivery_report_request.into_raw(), 2);
+        buffer.write_bits(self.short_form_report as u64, 1);
+        buffer.write_bits(self.validity_period.is_some() as u64, 1);
+        buffer.write_bits(self.message_reference as u64, 8);
+
+        // Conditional
+        if let Some(validity_period) = self.validity_period {
+            buffer.write_bits(validity_period as u64, 5);
+            ForwardAddress::to_bitbuf(self.forward_address.as_ref(), buffer);
+        }
+
+        write_user_data(buffer, &self.user_data);
+    }
+}
+
+impl fmt::Display for SdsTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SdsTransfer {{ protocol_identifier: {} delivery_report_request: {} short_form_report: {} message_reference: {} validity_period: {:?} forward_address: {:?} user_data: {:02x?} }}",
+            self.protocol_identifier,
+            self.delivery_report_request,
+            self.short_form_report,
+            self.message_reference,
+            self.validity_period,
+            self.forward_address,
+            self.user_data,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sds_transfer_with_storage() {
+        let pdu = SdsTransfer {
+            protocol_identifier: 0x82,
+            delivery_report_request: DeliveryReportRequest::MessageReceivedAndConsumedReportRequested,
+            short_form_report: true,
+            message_reference: 0x42,
+            validity_period: Some(31),
+            forward_address: Some(ForwardAddress::Ssi(2000001)),
+            user_data: vec![0x01, b'h', b'i'],
+        };
+
+        let mut buffer = BitBuffer::new_autoexpand(96);
+        pdu.to_bitbuf(&mut buffer);
+        // 24 bits header, 5 bits validity period, 3 + 24 bits forward address, 24 bits user data
+        assert_eq!(buffer.get_len(), 80);
+        assert_eq!(buffer.peek_bits_startoffset(8, 16), Some(0x0F42));
+
+        buffer.seek(0);
+        assert_eq!(SdsTransfer::from_bitbuf(&mut buffer).unwrap(), pdu);
+    }
+}
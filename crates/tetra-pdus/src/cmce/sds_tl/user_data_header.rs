@@ -0,0 +1,166 @@
+use tetra_core::PduParseErr;
+
+/// Information element identifier of a concatenated message part with an 8-bit reference
+const IEI_CONCATENATION_8BIT_REFERENCE: u8 = 0x00;
+/// Information element identifier of a concatenated message part with a 16-bit reference
+const IEI_CONCATENATION_16BIT_REFERENCE: u8 = 0x08;
+
+/// User data header, starting the SDS-TRANSFER user data of the MessageWithUserDataHeader and
+/// ConcatenatedSdsMessageSdsTl protocols. An octet with the header length is followed by the information
+/// elements, each an identifier octet, a length octet and the value. The information elements are those
+/// of 3GPP TS 23.040 clause 9.2.3.24.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserDataHeader {
+    /// Information elements as (identifier, value), in order of appearance
+    pub elements: Vec<(u8, Vec<u8>)>,
+}
+
+/// The place of a message part in a concatenated message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Concatenation {
+    /// Identifies the concatenated message among the messages of the sender, 8 or 16 bits
+    pub reference: u16,
+    /// Number of parts in the concatenated message
+    pub total_parts: u8,
+    /// Sequence number of this part, starting at 1
+    pub part_number: u8,
+}
+
+impl UserDataHeader {
+    /// Splits SDS-TRANSFER user data into the header and the payload that follows it
+    pub fn split(user_data: &[u8]) -> Result<(Self, &[u8]), PduParseErr> {
+        let (&header_len, rest) = user_data.split_first().ok_or(PduParseErr::BufferEnded {
+            field: Some("user_data_header_length"),
+        })?;
+        if rest.len() < header_len as usize {
+            return Err(PduParseErr::InconsistentLength {
+                expected: header_len as usize,
+                found: rest.len(),
+            });
+        }
+        let (mut header, payload) = rest.split_at(header_len as usize);
+
+        let mut elements = Vec::new();
+        while let [identifier, len, tail @ ..] = header {
+            if tail.len() < *len as usize {
+                return Err(PduParseErr::InconsistentLength {
+                    expected: *len as usize,
+                    found: tail.len(),
+                });
+            }
+            let (value, tail) = tail.split_at(*len as usize);
+            elements.push((*identifier, value.to_vec()));
+            header = tail;
+        }
+        if !header.is_empty() {
+            return Err(PduParseErr::Inconsistency {
+                field: "user_data_header",
+                reason: "truncated information element",
+            });
+        }
+        Ok((UserDataHeader { elements }, payload))
+    }
+
+    /// Serializes the header, including its length octet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = vec![0u8];
+        for (identifier, value) in &self.elements {
+            data.push(*identifier);
+            data.push(value.len() as u8);
+            data.extend_from_slice(value);
+        }
+        data[0] = (data.len() - 1) as u8;
+        data
+    }
+
+    /// The concatenation information element, if this header belongs to a part of a concatenated message
+    pub fn concatenation(&self) -> Option<Concatenation> {
+        self.elements
+            .iter()
+            .find_map(|(identifier, value)| match (*identifier, value.as_slice()) {
+                (IEI_CONCATENATION_8BIT_REFERENCE, [reference, total_parts, part_number]) => Some(Concatenation {
+                    reference: *reference as u16,
+                    total_parts: *total_parts,
+                    part_number: *part_number,
+                }),
+                (IEI_CONCATENATION_16BIT_REFERENCE, [reference_hi, reference_lo, total_parts, part_number]) => Some(Concatenation {
+                    reference: u16::from_be_bytes([*reference_hi, *reference_lo]),
+                    total_parts: *total_parts,
+                    part_number: *part_number,
+                }),
+                _ => None,
+            })
+    }
+
+    /// Marks the message as a part of a concatenated message, replacing any earlier concatenation information
+    pub fn set_concatenation(&mut self, concatenation: Concatenation) {
+        self.remove_concatenation();
+        let element = if concatenation.reference > u8::MAX as u16 {
+            let [reference_hi, reference_lo] = concatenation.reference.to_be_bytes();
+            (
+                IEI_CONCATENATION_16BIT_REFERENCE,
+                vec![reference_hi, reference_lo, concatenation.total_parts, concatenation.part_number],
+            )
+        } else {
+            (
+                IEI_CONCATENATION_8BIT_REFERENCE,
+                vec![concatenation.reference as u8, concatenation.total_parts, concatenation.part_number],
+            )
+        };
+        self.elements.insert(0, element);
+    }
+
+    pub fn remove_concatenation(&mut self) {
+        self.elements
+            .retain(|(identifier, _)| *identifier != IEI_CONCATENATION_8BIT_REFERENCE && *identifier != IEI_CONCATENATION_16BIT_REFERENCE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concatenation_part() {
+        // Part 2 of 3 of message 0x42, followed by an unknown element and the payload
+        let user_data = [0x08, 0x00, 0x03, 0x42, 0x03, 0x02, 0x70, 0x01, 0xAA, b'h', b'i'];
+        let (mut header, payload) = UserDataHeader::split(&user_data).unwrap();
+        assert_eq!(payload, b"hi");
+        assert_eq!(
+            header.concatenation(),
+            Some(Concatenation {
+                reference: 0x42,
+                total_parts: 3,
+                part_number: 2
+            })
+        );
+        assert_eq!(header.to_bytes(), user_data[..9]);
+
+        header.remove_concatenation();
+        assert_eq!(header.concatenation(), None);
+        assert_eq!(header.to_bytes(), vec![0x03, 0x70, 0x01, 0xAA]);
+    }
+
+    #[test]
+    fn test_concatenation_16bit_reference() {
+        let mut header = UserDataHeader::default();
+        let concatenation = Concatenation {
+            reference: 0x1234,
+            total_parts: 2,
+            part_number: 1,
+        };
+        header.set_concatenation(concatenation);
+        let data = header.to_bytes();
+        assert_eq!(data, vec![0x06, 0x08, 0x04, 0x12, 0x34, 0x02, 0x01]);
+
+        let (parsed, payload) = UserDataHeader::split(&data).unwrap();
+        assert!(payload.is_empty());
+        assert_eq!(parsed.concatenation(), Some(concatenation));
+    }
+
+    #[test]
+    fn test_truncated_header() {
+        assert!(UserDataHeader::split(&[0x05, 0x00, 0x03]).is_err());
+        assert!(UserDataHeader::split(&[0x02, 0x00, 0x03]).is_err());
+    }
+}
@@ -50,6 +50,9 @@ pub struct CmceCircuit {
     pub speech_service: Option<u8>,
     /// Whether end-to-end encryption is enabled on this circuit
     pub etee_encrypted: bool,
+
+    /// Duplex individual calls use one circuit per party; this is the timeslot of the other party's circuit
+    pub peer_ts: Option<u8>,
 }
 
 // impl CmceCircuit {
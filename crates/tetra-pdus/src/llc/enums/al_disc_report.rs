@@ -0,0 +1,46 @@
+/// Clause 21.2.3.4 Disconnection report, in AL-DISC
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlDiscReport {
+    /// Asks the peer to release the advanced link
+    Request = 0,
+    /// The advanced link has been released
+    Confirm = 1,
+}
+
+impl std::convert::TryFrom<u64> for AlDiscReport {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AlDiscReport::Request),
+            1 => Ok(AlDiscReport::Confirm),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AlDiscReport {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            AlDiscReport::Request => 0,
+            AlDiscReport::Confirm => 1,
+        }
+    }
+}
+
+impl From<AlDiscReport> for u64 {
+    fn from(e: AlDiscReport) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AlDiscReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlDiscReport::Request => write!(f, "Request"),
+            AlDiscReport::Confirm => write!(f, "Confirm"),
+        }
+    }
+}
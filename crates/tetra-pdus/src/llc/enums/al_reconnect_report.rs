@@ -0,0 +1,51 @@
+/// Clause 21.2.3.5 Reconnection report, in AL-RECONNECT
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlReconnectReport {
+    /// Asks the peer to resume the advanced link, e.g. after a cell change
+    Propose = 0,
+    /// The advanced link is resumed with its sequence state kept
+    Confirm = 1,
+    /// The peer has no such advanced link
+    Fail = 2,
+}
+
+impl std::convert::TryFrom<u64> for AlReconnectReport {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AlReconnectReport::Propose),
+            1 => Ok(AlReconnectReport::Confirm),
+            2 => Ok(AlReconnectReport::Fail),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AlReconnectReport {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            AlReconnectReport::Propose => 0,
+            AlReconnectReport::Confirm => 1,
+            AlReconnectReport::Fail => 2,
+        }
+    }
+}
+
+impl From<AlReconnectReport> for u64 {
+    fn from(e: AlReconnectReport) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AlReconnectReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlReconnectReport::Propose => write!(f, "Propose"),
+            AlReconnectReport::Confirm => write!(f, "Confirm"),
+            AlReconnectReport::Fail => write!(f, "Fail"),
+        }
+    }
+}
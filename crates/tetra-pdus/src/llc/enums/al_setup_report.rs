@@ -0,0 +1,51 @@
+/// Clause 21.2.3.6 Set-up report, in AL-SETUP
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlSetupReport {
+    /// Proposes the advanced link service and its parameters
+    ServiceDefinition = 0,
+    /// Counter-proposal with different parameters
+    ServiceChange = 1,
+    /// Accepts the parameters, which are in force from then on
+    ServiceAccept = 2,
+}
+
+impl std::convert::TryFrom<u64> for AlSetupReport {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AlSetupReport::ServiceDefinition),
+            1 => Ok(AlSetupReport::ServiceChange),
+            2 => Ok(AlSetupReport::ServiceAccept),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AlSetupReport {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            AlSetupReport::ServiceDefinition => 0,
+            AlSetupReport::ServiceChange => 1,
+            AlSetupReport::ServiceAccept => 2,
+        }
+    }
+}
+
+impl From<AlSetupReport> for u64 {
+    fn from(e: AlSetupReport) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AlSetupReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlSetupReport::ServiceDefinition => write!(f, "ServiceDefinition"),
+            AlSetupReport::ServiceChange => write!(f, "ServiceChange"),
+            AlSetupReport::ServiceAccept => write!(f, "ServiceAccept"),
+        }
+    }
+}
@@ -1 +1,4 @@
+pub mod al_disc_report;
+pub mod al_reconnect_report;
+pub mod al_setup_report;
 pub mod llc_pdu_type;
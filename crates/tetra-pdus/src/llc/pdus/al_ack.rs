@@ -0,0 +1,118 @@
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.1 AL-ACK, AL-RNR
+/// Acknowledges the TL-SDU N(R), either completely or segment by segment for selective retransmission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlAck {
+    /// 1 bit. Set for AL-RNR, the receiver can't take further TL-SDUs for now
+    pub rnr: bool,
+    /// 3 bits. TL-SDU sequence number N(R)
+    pub nr: u8,
+    /// Present if the TL-SDU was not received completely. One flag per segment from S(S) 0 on, set if the
+    /// segment was received. Segments beyond the map are still outstanding.
+    /// 1 bit presence flag, 8 bits number of segments minus one, then 1 bit per segment
+    pub segments_received: Option<Vec<bool>>,
+}
+
+impl AlAck {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlAckAlRnr)?;
+
+        let_field!(buf, rnr, 1);
+        let_field!(buf, nr, 3);
+        let_field!(buf, has_segment_map, 1);
+        let segments_received = if has_segment_map != 0 {
+            let_field!(buf, num_segments, 8);
+            let mut map = Vec::with_capacity(num_segments as usize + 1);
+            for _ in 0..=num_segments {
+                let_field!(buf, received, 1);
+                map.push(received != 0);
+            }
+            Some(map)
+        } else {
+            None
+        };
+
+        Ok(AlAck {
+            rnr: rnr != 0,
+            nr: nr as u8,
+            segments_received,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlAckAlRnr.into_raw(), 4);
+        buf.write_bits(self.rnr as u64, 1);
+        buf.write_bits(self.nr as u64, 3);
+        match &self.segments_received {
+            Some(map) => {
+                assert!(!map.is_empty() && map.len() <= 256, "invalid segment map length {}", map.len());
+                buf.write_bits(1, 1);
+                buf.write_bits(map.len() as u64 - 1, 8);
+                for received in map {
+                    buf.write_bits(*received as u64, 1);
+                }
+            }
+            None => buf.write_bits(0, 1),
+        }
+    }
+}
+
+impl core::fmt::Display for AlAck {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "al_ack {{")?;
+        write!(f, "  rnr: {}", self.rnr)?;
+        write!(f, "  nr: {}", self.nr)?;
+        if let Some(map) = &self.segments_received {
+            let map: String = map.iter().map(|received| if *received { '1' } else { '0' }).collect();
+            write!(f, "  segments_received: {}", map)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_al_ack_complete() {
+        let test_vec = "101101100";
+        let mut buf = BitBuffer::from_bitstr(test_vec);
+        let pdu = AlAck::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert_eq!(
+            pdu,
+            AlAck {
+                rnr: false,
+                nr: 6,
+                segments_received: None
+            }
+        );
+        assert_eq!(buf.get_len_remaining(), 0);
+
+        let mut buf_out = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf_out);
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_al_ack_segment_map() {
+        let pdu = AlAck {
+            rnr: true,
+            nr: 2,
+            segments_received: Some(vec![true, false, true, true, false]),
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.to_bitstr(), "1011101010000010010110");
+
+        buf.seek(0);
+        assert_eq!(AlAck::from_bitbuf(&mut buf).expect("Failed parsing"), pdu);
+        assert_eq!(buf.get_len_remaining(), 0);
+    }
+}
@@ -0,0 +1,82 @@
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.3 AL-DATA, AL-DATA-AR, and Clause 21.2.3.2 AL-FINAL, AL-FINAL-AR
+/// Header of one segment of a TL-SDU on an acknowledged advanced link. The segment follows the header,
+/// the FCS of the whole TL-SDU ends the last segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlData {
+    /// 1 bit. Set for AL-FINAL, the last segment of the TL-SDU
+    pub is_final: bool,
+    /// 1 bit. Acknowledgement request, set for the -AR variants
+    pub ar: bool,
+    /// 3 bits. TL-SDU sequence number N(S)
+    pub ns: u8,
+    /// 8 bits. Segment sequence number S(S)
+    pub ss: u8,
+}
+
+impl AlData {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlDataAlFinal)?;
+
+        let_field!(buf, is_final, 1);
+        let_field!(buf, ar, 1);
+        let_field!(buf, ns, 3);
+        let_field!(buf, ss, 8);
+
+        Ok(AlData {
+            is_final: is_final != 0,
+            ar: ar != 0,
+            ns: ns as u8,
+            ss: ss as u8,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlDataAlFinal.into_raw(), 4);
+        buf.write_bits(self.is_final as u64, 1);
+        buf.write_bits(self.ar as u64, 1);
+        buf.write_bits(self.ns as u64, 3);
+        buf.write_bits(self.ss as u64, 8);
+    }
+}
+
+impl core::fmt::Display for AlData {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "al_data {{")?;
+        write!(f, "  is_final: {}", self.is_final)?;
+        write!(f, "  ar: {}", self.ar)?;
+        write!(f, "  ns: {}", self.ns)?;
+        write!(f, "  ss: {}", self.ss)?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_al_final_ar() {
+        let pdu = AlData {
+            is_final: true,
+            ar: true,
+            ns: 5,
+            ss: 130,
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        buf.write_bits(0b1011, 4);
+        assert_eq!(buf.to_bitstr(), "100111101100000101011");
+
+        buf.seek(0);
+        assert_eq!(AlData::from_bitbuf(&mut buf).expect("Failed parsing"), pdu);
+        // The segment follows the header
+        assert_eq!(buf.get_len_remaining(), 4);
+    }
+}
@@ -0,0 +1,43 @@
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::al_disc_report::AlDiscReport;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.4 AL-DISC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlDisc {
+    // 3
+    pub disc_report: AlDiscReport,
+}
+
+impl AlDisc {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlDisc)?;
+
+        let_field!(buf, disc_report, 3);
+        let Ok(disc_report) = AlDiscReport::try_from(disc_report) else {
+            return Err(PduParseErr::InvalidValue {
+                field: "disc_report",
+                value: disc_report,
+            });
+        };
+
+        Ok(AlDisc { disc_report })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlDisc.into_raw(), 4);
+        buf.write_bits(self.disc_report.into_raw(), 3);
+    }
+}
+
+impl core::fmt::Display for AlDisc {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "al_disc {{")?;
+        write!(f, "  disc_report: {}", self.disc_report)?;
+        write!(f, "}}")
+    }
+}
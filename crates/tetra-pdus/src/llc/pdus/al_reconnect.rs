@@ -0,0 +1,43 @@
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::al_reconnect_report::AlReconnectReport;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.5 AL-RECONNECT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlReconnect {
+    // 3
+    pub reconnect_report: AlReconnectReport,
+}
+
+impl AlReconnect {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlReconnect)?;
+
+        let_field!(buf, reconnect_report, 3);
+        let Ok(reconnect_report) = AlReconnectReport::try_from(reconnect_report) else {
+            return Err(PduParseErr::InvalidValue {
+                field: "reconnect_report",
+                value: reconnect_report,
+            });
+        };
+
+        Ok(AlReconnect { reconnect_report })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlReconnect.into_raw(), 4);
+        buf.write_bits(self.reconnect_report.into_raw(), 3);
+    }
+}
+
+impl core::fmt::Display for AlReconnect {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "al_reconnect {{")?;
+        write!(f, "  reconnect_report: {}", self.reconnect_report)?;
+        write!(f, "}}")
+    }
+}
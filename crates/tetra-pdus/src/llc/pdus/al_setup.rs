@@ -0,0 +1,99 @@
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::al_setup_report::AlSetupReport;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.6 AL-SETUP
+/// Negotiates the parameters of an advanced link. The same PDU proposes, counter-proposes and accepts them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlSetup {
+    // 1
+    pub unacknowledged: bool,
+    /// 3 bits. Maximum length of a TL-SDU including the FCS (N.271), as 32 << n octets
+    pub max_tlsdu_len: u8,
+    // 1
+    pub upgrade: bool,
+    /// 2 bits. Number of timeslots used per TDMA frame (N.264), 1 to 4
+    pub num_timeslots: u8,
+    /// 3 bits. Maximum number of TL-SDU retransmissions (N.273), or repetitions for the unacknowledged service (N.282)
+    pub max_tlsdu_retransmissions: u8,
+    /// 4 bits. Maximum number of segment retransmissions (N.274)
+    pub max_segment_retransmissions: u8,
+    /// 2 bits. Window size for TL-SDUs (N.272 or N.281), 1 to 3
+    pub window_size: u8,
+    // 2
+    pub setup_report: AlSetupReport,
+}
+
+impl AlSetup {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlSetup)?;
+
+        let_field!(buf, unacknowledged, 1);
+        let_field!(buf, max_tlsdu_len, 3);
+        let_field!(buf, upgrade, 1);
+        let_field!(buf, num_timeslots, 2);
+        let_field!(buf, max_tlsdu_retransmissions, 3);
+        let_field!(buf, max_segment_retransmissions, 4);
+        let_field!(buf, window_size, 2);
+        if window_size == 0 {
+            return Err(PduParseErr::InvalidValue {
+                field: "window_size",
+                value: window_size,
+            });
+        }
+        let_field!(buf, setup_report, 2);
+        let Ok(setup_report) = AlSetupReport::try_from(setup_report) else {
+            return Err(PduParseErr::InvalidValue {
+                field: "setup_report",
+                value: setup_report,
+            });
+        };
+
+        Ok(AlSetup {
+            unacknowledged: unacknowledged != 0,
+            max_tlsdu_len: max_tlsdu_len as u8,
+            upgrade: upgrade != 0,
+            num_timeslots: num_timeslots as u8 + 1,
+            max_tlsdu_retransmissions: max_tlsdu_retransmissions as u8,
+            max_segment_retransmissions: max_segment_retransmissions as u8,
+            window_size: window_size as u8,
+            setup_report,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlSetup.into_raw(), 4);
+        buf.write_bits(self.unacknowledged as u64, 1);
+        buf.write_bits(self.max_tlsdu_len as u64, 3);
+        buf.write_bits(self.upgrade as u64, 1);
+        buf.write_bits(self.num_timeslots as u64 - 1, 2);
+        buf.write_bits(self.max_tlsdu_retransmissions as u64, 3);
+        buf.write_bits(self.max_segment_retransmissions as u64, 4);
+        buf.write_bits(self.window_size as u64, 2);
+        buf.write_bits(self.setup_report.into_raw(), 2);
+    }
+
+    /// Maximum length of a TL-SDU including the FCS, in octets
+    pub fn max_tlsdu_len_octets(&self) -> u32 {
+        32 << self.max_tlsdu_len
+    }
+}
+
+impl core::fmt::Display for AlSetup {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "al_setup {{")?;
+        write!(f, "  unacknowledged: {}", self.unacknowledged)?;
+        write!(f, "  max_tlsdu_len: {}", self.max_tlsdu_len_octets())?;
+        write!(f, "  upgrade: {}", self.upgrade)?;
+        write!(f, "  num_timeslots: {}", self.num_timeslots)?;
+        write!(f, "  max_tlsdu_retransmissions: {}", self.max_tlsdu_retransmissions)?;
+        write!(f, "  max_segment_retransmissions: {}", self.max_segment_retransmissions)?;
+        write!(f, "  window_size: {}", self.window_size)?;
+        write!(f, "  setup_report: {}", self.setup_report)?;
+        write!(f, "}}")
+    }
+}
@@ -0,0 +1,52 @@
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.7 AL-UDATA, AL-UFINAL
+/// Header of one segment of a TL-SDU on the unacknowledged advanced link. The segment follows the header,
+/// the FCS of the whole TL-SDU ends the last segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlUdata {
+    /// 1 bit. Set for AL-UFINAL, the last segment of the TL-SDU
+    pub is_final: bool,
+    /// 3 bits. TL-SDU sequence number N(S)
+    pub ns: u8,
+    /// 8 bits. Segment sequence number S(S)
+    pub ss: u8,
+}
+
+impl AlUdata {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlAlUdataAlUfinal)?;
+
+        let_field!(buf, is_final, 1);
+        let_field!(buf, ns, 3);
+        let_field!(buf, ss, 8);
+
+        Ok(AlUdata {
+            is_final: is_final != 0,
+            ns: ns as u8,
+            ss: ss as u8,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlAlUdataAlUfinal.into_raw(), 4);
+        buf.write_bits(self.is_final as u64, 1);
+        buf.write_bits(self.ns as u64, 3);
+        buf.write_bits(self.ss as u64, 8);
+    }
+}
+
+impl core::fmt::Display for AlUdata {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "al_udata {{")?;
+        write!(f, "  is_final: {}", self.is_final)?;
+        write!(f, "  ns: {}", self.ns)?;
+        write!(f, "  ss: {}", self.ss)?;
+        write!(f, "}}")
+    }
+}
@@ -0,0 +1,52 @@
+/// Clause 18.5.8 Fail cause, used in D-PREPARE-FAIL and D-RESTORE-FAIL
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MleFailCause {
+    CellReselectionTypeNotSupported = 0,
+    NeighbourCellEnquiryNotAvailable = 1,
+    RestorationCannotBeDoneOnCell = 2,
+    Reserved = 3,
+}
+
+impl std::convert::TryFrom<u64> for MleFailCause {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(MleFailCause::CellReselectionTypeNotSupported),
+            1 => Ok(MleFailCause::NeighbourCellEnquiryNotAvailable),
+            2 => Ok(MleFailCause::RestorationCannotBeDoneOnCell),
+            3 => Ok(MleFailCause::Reserved),
+            _ => Err(()),
+        }
+    }
+}
+
+impl MleFailCause {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            MleFailCause::CellReselectionTypeNotSupported => 0,
+            MleFailCause::NeighbourCellEnquiryNotAvailable => 1,
+            MleFailCause::RestorationCannotBeDoneOnCell => 2,
+            MleFailCause::Reserved => 3,
+        }
+    }
+}
+
+impl From<MleFailCause> for u64 {
+    fn from(e: MleFailCause) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for MleFailCause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MleFailCause::CellReselectionTypeNotSupported => write!(f, "CellReselectionTypeNotSupported"),
+            MleFailCause::NeighbourCellEnquiryNotAvailable => write!(f, "NeighbourCellEnquiryNotAvailable"),
+            MleFailCause::RestorationCannotBeDoneOnCell => write!(f, "RestorationCannotBeDoneOnCell"),
+            MleFailCause::Reserved => write!(f, "Reserved"),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod channel_command_valid;
+pub mod mle_fail_cause;
 pub mod mle_pdu_type_dl;
 pub mod mle_pdu_type_ul;
 
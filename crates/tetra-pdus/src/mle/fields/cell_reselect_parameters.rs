@@ -0,0 +1,64 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+/// Clause 18.5.4 Cell re-select parameters information element
+/// Thresholds and hysteresis values, 4 bits each, in steps of 2 dB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellReselectParameters {
+    // 4
+    pub slow_reselect_threshold: u8,
+    // 4
+    pub slow_reselect_hysteresis: u8,
+    // 4
+    pub fast_reselect_threshold: u8,
+    // 4
+    pub fast_reselect_hysteresis: u8,
+}
+
+impl CellReselectParameters {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let slow_reselect_threshold = buf.read_field(4, "slow_reselect_threshold")? as u8;
+        let slow_reselect_hysteresis = buf.read_field(4, "slow_reselect_hysteresis")? as u8;
+        let fast_reselect_threshold = buf.read_field(4, "fast_reselect_threshold")? as u8;
+        let fast_reselect_hysteresis = buf.read_field(4, "fast_reselect_hysteresis")? as u8;
+
+        Ok(CellReselectParameters {
+            slow_reselect_threshold,
+            slow_reselect_hysteresis,
+            fast_reselect_threshold,
+            fast_reselect_hysteresis,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(self.slow_reselect_threshold as u64, 4);
+        buf.write_bits(self.slow_reselect_hysteresis as u64, 4);
+        buf.write_bits(self.fast_reselect_threshold as u64, 4);
+        buf.write_bits(self.fast_reselect_hysteresis as u64, 4);
+    }
+
+    /// Builds the element from values in dB, rounded down to the 2 dB steps and capped at 30 dB
+    pub fn from_db(slow_threshold: u8, slow_hysteresis: u8, fast_threshold: u8, fast_hysteresis: u8) -> Self {
+        let code = |db: u8| (db / 2).min(15);
+        CellReselectParameters {
+            slow_reselect_threshold: code(slow_threshold),
+            slow_reselect_hysteresis: code(slow_hysteresis),
+            fast_reselect_threshold: code(fast_threshold),
+            fast_reselect_hysteresis: code(fast_hysteresis),
+        }
+    }
+}
+
+impl fmt::Display for CellReselectParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CellReselectParameters {{ slow_threshold: {} dB, slow_hysteresis: {} dB, fast_threshold: {} dB, fast_hysteresis: {} dB }}",
+            self.slow_reselect_threshold * 2,
+            self.slow_reselect_hysteresis * 2,
+            self.fast_reselect_threshold * 2,
+            self.fast_reselect_hysteresis * 2,
+        )
+    }
+}
@@ -1 +1,3 @@
 pub mod bs_service_details;
+pub mod cell_reselect_parameters;
+pub mod neighbour_cell_info;
@@ -0,0 +1,146 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mle::fields::bs_service_details::BsServiceDetails;
+
+/// Clause 18.5.17 Neighbour cell information for CA information element
+/// Describes one neighbour cell in D-NWRK-BROADCAST. The optional elements default to the values of the serving cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeighbourCellInfoCa {
+    /// 5 bits, identifies the neighbour cell within this PDU and in U-PREPARE
+    pub cell_identifier_ca: u8,
+    /// 2 bits, Clause 18.5.6
+    pub cell_reselection_types_supported: u8,
+    // 1
+    pub neighbour_cell_synchronized: bool,
+    /// 2 bits, Clause 18.5.7
+    pub cell_service_level: u8,
+    // 12
+    pub main_carrier_number: u16,
+    /// Type2, 10 bits: frequency band 4, offset 2, duplex spacing 3, reverse operation 1
+    pub main_carrier_number_extension: Option<u16>,
+    /// Type2, 10 bits
+    pub mcc: Option<u16>,
+    /// Type2, 14 bits
+    pub mnc: Option<u16>,
+    /// Type2, 14 bits
+    pub location_area: Option<u16>,
+    /// Type2, 3 bits
+    pub maximum_ms_transmit_power: Option<u8>,
+    /// Type2, 4 bits
+    pub minimum_rx_access_level: Option<u8>,
+    /// Type2, 16 bits
+    pub subscriber_class: Option<u16>,
+    /// Type2, 12 bits
+    pub bs_service_details: Option<BsServiceDetails>,
+    /// Type2, 5 bits
+    pub timeshare_cell_or_security_parameters: Option<u8>,
+    /// Type2, 6 bits
+    pub tdma_frame_offset: Option<u8>,
+}
+
+impl NeighbourCellInfoCa {
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let cell_identifier_ca = buffer.read_field(5, "cell_identifier_ca")? as u8;
+        let cell_reselection_types_supported = buffer.read_field(2, "cell_reselection_types_supported")? as u8;
+        let neighbour_cell_synchronized = buffer.read_field(1, "neighbour_cell_synchronized")? != 0;
+        let cell_service_level = buffer.read_field(2, "cell_service_level")? as u8;
+        let main_carrier_number = buffer.read_field(12, "main_carrier_number")? as u16;
+
+        let obit = delimiters::read_obit(buffer)?;
+        let main_carrier_number_extension =
+            typed::parse_type2_generic(obit, buffer, 10, "main_carrier_number_extension")?.map(|v| v as u16);
+        let mcc = typed::parse_type2_generic(obit, buffer, 10, "mcc")?.map(|v| v as u16);
+        let mnc = typed::parse_type2_generic(obit, buffer, 14, "mnc")?.map(|v| v as u16);
+        let location_area = typed::parse_type2_generic(obit, buffer, 14, "location_area")?.map(|v| v as u16);
+        let maximum_ms_transmit_power = typed::parse_type2_generic(obit, buffer, 3, "maximum_ms_transmit_power")?.map(|v| v as u8);
+        let minimum_rx_access_level = typed::parse_type2_generic(obit, buffer, 4, "minimum_rx_access_level")?.map(|v| v as u8);
+        let subscriber_class = typed::parse_type2_generic(obit, buffer, 16, "subscriber_class")?.map(|v| v as u16);
+        let bs_service_details = typed::parse_type2_struct(obit, buffer, BsServiceDetails::from_bitbuf)?;
+        let timeshare_cell_or_security_parameters =
+            typed::parse_type2_generic(obit, buffer, 5, "timeshare_cell_or_security_parameters")?.map(|v| v as u8);
+        let tdma_frame_offset = typed::parse_type2_generic(obit, buffer, 6, "tdma_frame_offset")?.map(|v| v as u8);
+
+        Ok(NeighbourCellInfoCa {
+            cell_identifier_ca,
+            cell_reselection_types_supported,
+            neighbour_cell_synchronized,
+            cell_service_level,
+            main_carrier_number,
+            main_carrier_number_extension,
+            mcc,
+            mnc,
+            location_area,
+            maximum_ms_transmit_power,
+            minimum_rx_access_level,
+            subscriber_class,
+            bs_service_details,
+            timeshare_cell_or_security_parameters,
+            tdma_frame_offset,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        buffer.write_bits(self.cell_identifier_ca as u64, 5);
+        buffer.write_bits(self.cell_reselection_types_supported as u64, 2);
+        buffer.write_bits(self.neighbour_cell_synchronized as u64, 1);
+        buffer.write_bits(self.cell_service_level as u64, 2);
+        buffer.write_bits(self.main_carrier_number as u64, 12);
+
+        let obit = self.main_carrier_number_extension.is_some()
+            || self.mcc.is_some()
+            || self.mnc.is_some()
+            || self.location_area.is_some()
+            || self.maximum_ms_transmit_power.is_some()
+            || self.minimum_rx_access_level.is_some()
+            || self.subscriber_class.is_some()
+            || self.bs_service_details.is_some()
+            || self.timeshare_cell_or_security_parameters.is_some()
+            || self.tdma_frame_offset.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        typed::write_type2_generic(obit, buffer, self.main_carrier_number_extension.map(u64::from), 10);
+        typed::write_type2_generic(obit, buffer, self.mcc.map(u64::from), 10);
+        typed::write_type2_generic(obit, buffer, self.mnc.map(u64::from), 14);
+        typed::write_type2_generic(obit, buffer, self.location_area.map(u64::from), 14);
+        typed::write_type2_generic(obit, buffer, self.maximum_ms_transmit_power.map(u64::from), 3);
+        typed::write_type2_generic(obit, buffer, self.minimum_rx_access_level.map(u64::from), 4);
+        typed::write_type2_generic(obit, buffer, self.subscriber_class.map(u64::from), 16);
+        typed::write_type2_struct(obit, buffer, &self.bs_service_details, |v, buf| {
+            v.to_bitbuf(buf);
+            Ok(())
+        })?;
+        typed::write_type2_generic(obit, buffer, self.timeshare_cell_or_security_parameters.map(u64::from), 5);
+        typed::write_type2_generic(obit, buffer, self.tdma_frame_offset.map(u64::from), 6);
+        Ok(())
+    }
+}
+
+impl fmt::Display for NeighbourCellInfoCa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NeighbourCellInfoCa {{ cell_identifier_ca: {} cell_reselection_types_supported: {} neighbour_cell_synchronized: {} cell_service_level: {} main_carrier_number: {} main_carrier_number_extension: {:?} mcc: {:?} mnc: {:?} location_area: {:?} maximum_ms_transmit_power: {:?} minimum_rx_access_level: {:?} subscriber_class: {:?} bs_service_details: {:?} timeshare_cell_or_security_parameters: {:?} tdma_frame_offset: {:?} }}",
+            self.cell_identifier_ca,
+            self.cell_reselection_types_supported,
+            self.neighbour_cell_synchronized,
+            self.cell_service_level,
+            self.main_carrier_number,
+            self.main_carrier_number_extension,
+            self.mcc,
+            self.mnc,
+            self.location_area,
+            self.maximum_ms_transmit_power,
+            self.minimum_rx_access_level,
+            self.subscriber_class,
+            self.bs_service_details,
+            self.timeshare_cell_or_security_parameters,
+            self.tdma_frame_offset,
+        )
+    }
+}
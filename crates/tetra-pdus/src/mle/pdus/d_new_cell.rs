@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::pdus::{read_sdu, write_sdu};
 
 /// Representation of the D-NEW-CELL PDU (Clause 18.4.1.4.2).
 /// Upon receipt from the SwMI the message shall inform the MS-MLE that it can select a new cell as previously indicated in the U-PREPARE or U-PREPARE-DA PDU.
@@ -11,16 +12,14 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 /// Response to: U-PREPARE/U-PREPARE-DA
 
 // note 1: The SDU may carry an MM registration PDU which is used to forward register to a new cell during announced type 1 cell reselection or a D-OTAR CCK PROVIDE PDU which is used to identify the current CCK; it may also provide the future CCK for the LA which the MS has indicated in the U-OTAR CCK DEMAND PDU and whether the CCK provided is in use in other LAs or is used throughout the SwMI. The SDU is coded according to the MM protocol description. There shall be no P-bit in the PDU coding preceding the SDU information element.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DNewCell {
     /// Type1, 2 bits, Channel command valid
     pub channel_command_valid: u8,
-    /// Conditional SDU
-    pub sdu: Option<u64>,
+    /// Conditional, MM PDU taking up the rest of the PDU, see note 1
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DNewCell {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -29,27 +28,10 @@ impl DNewCell {
 
         // Type1
         let channel_command_valid = buffer.read_field(2, "channel_command_valid")? as u8;
-        // Exceptional case: obit required for SDU field.
-        // SDU takes rest of slot, but still ends with 0-bit (closing obit)
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of the SDU, which takes up the rest of the PDU
         let obit = delimiters::read_obit(buffer)?;
-
-        let sdu = if buffer.get_len_remaining() > 0 {
-            Some(buffer.read_field(buffer.get_len_remaining() - 1, "sdu")?)
-        } else {
-            None
-        };
-        unimplemented!(); // read closing obit
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(DNewCell {
             channel_command_valid,
@@ -63,14 +45,12 @@ impl DNewCell {
         buffer.write_bits(MlePduTypeDl::DNewCell.into_raw(), 3);
         // Type1
         buffer.write_bits(self.channel_command_valid as u64, 2);
-        // TODO FIXME: sdu handling
+
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        delimiters::write_obit(buffer, self.sdu.is_some() as u8);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -80,7 +60,29 @@ impl fmt::Display for DNewCell {
         write!(
             f,
             "DNewCell {{ channel_command_valid: {:?} sdu: {:?} }}",
-            self.channel_command_valid, self.sdu,
+            self.channel_command_valid,
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_new_cell_roundtrip() {
+        let pdu = DNewCell {
+            channel_command_valid: 2,
+            sdu: Some(BitBuffer::from_bitstr("0101100")),
+        };
+        let mut buf = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.to_bitstr(), concat!("000", "10", "1", "0101100"));
+
+        buf.seek(0);
+        let parsed = DNewCell::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed.channel_command_valid, 2);
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), "0101100");
+    }
+}
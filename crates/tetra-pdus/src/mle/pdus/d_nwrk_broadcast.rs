@@ -4,6 +4,8 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::cell_reselect_parameters::CellReselectParameters;
+use crate::mle::fields::neighbour_cell_info::NeighbourCellInfoCa;
 
 /// Representation of the D-NWRK-BROADCAST PDU (Clause 18.4.1.4.1).
 /// Upon receipt from the SwMI, the message shall inform the MS-MLE about parameters for the CA serving cell and parameters for one or more CA neighbour cells.
@@ -16,7 +18,7 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 #[derive(Debug)]
 pub struct DNwrkBroadcast {
     /// Type1, 16 bits, See note 1,
-    pub cell_re_select_parameters: u16,
+    pub cell_re_select_parameters: CellReselectParameters,
     /// Type1, 2 bits, See note 1,
     pub cell_load_ca: u8,
     /// Type2, 48 bits, TETRA network time
@@ -24,10 +26,9 @@ pub struct DNwrkBroadcast {
     /// Type2, 3 bits, See note 2,
     pub number_of_ca_neighbour_cells: Option<u64>,
     /// Conditional See note 3, condition: number_of_ca_neighbour_cells > Some(0)
-    pub neighbour_cell_information_for_ca: Option<u64>,
+    pub neighbour_cell_information_for_ca: Vec<NeighbourCellInfoCa>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DNwrkBroadcast {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -35,7 +36,7 @@ impl DNwrkBroadcast {
         expect_pdu_type!(pdu_type, MlePduTypeDl::DNwrkBroadcast)?;
 
         // Type1
-        let cell_re_select_parameters = buffer.read_field(16, "cell_re_select_parameters")? as u16;
+        let cell_re_select_parameters = CellReselectParameters::from_bitbuf(buffer)?;
         // Type1
         let cell_load_ca = buffer.read_field(2, "cell_load_ca")? as u8;
 
@@ -48,12 +49,10 @@ impl DNwrkBroadcast {
         let number_of_ca_neighbour_cells = typed::parse_type2_generic(obit, buffer, 3, "number_of_ca_neighbour_cells")?;
 
         // Conditional
-        let neighbour_cell_information_for_ca = if obit && number_of_ca_neighbour_cells > Some(0) {
-            unimplemented!();
-            Some(buffer.read_field(999, "neighbour_cell_information_for_ca")?)
-        } else {
-            None
-        };
+        let mut neighbour_cell_information_for_ca = Vec::new();
+        for _ in 0..number_of_ca_neighbour_cells.unwrap_or(0) {
+            neighbour_cell_information_for_ca.push(NeighbourCellInfoCa::from_bitbuf(buffer)?);
+        }
 
         // MLE PDUs do not use M-bits (Annex E.2.1) — no trailing delimiter to read
 
@@ -71,7 +70,7 @@ impl DNwrkBroadcast {
         // PDU Type
         buffer.write_bits(MlePduTypeDl::DNwrkBroadcast.into_raw(), 3);
         // Type1
-        buffer.write_bits(self.cell_re_select_parameters as u64, 16);
+        self.cell_re_select_parameters.to_bitbuf(buffer);
         // Type1
         buffer.write_bits(self.cell_load_ca as u64, 2);
 
@@ -88,10 +87,13 @@ impl DNwrkBroadcast {
         // Type2
         typed::write_type2_generic(obit, buffer, self.number_of_ca_neighbour_cells, 3);
 
-        // Conditional
-        if let Some(ref _value) = self.neighbour_cell_information_for_ca {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
+        // Conditional, no P-bit preceding each element
+        assert_eq!(
+            self.number_of_ca_neighbour_cells.unwrap_or(0) as usize,
+            self.neighbour_cell_information_for_ca.len()
+        );
+        for cell in &self.neighbour_cell_information_for_ca {
+            cell.to_bitbuf(buffer)?;
         }
         // MLE PDUs do not use M-bits (Annex E.2.1) — PDU ends after last Type 2 element
         Ok(())
@@ -102,7 +104,7 @@ impl fmt::Display for DNwrkBroadcast {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "DNwrkBroadcast {{ cell_re_select_parameters: {:?} cell_load_ca: {:?} tetra_network_time: {:?} number_of_ca_neighbour_cells: {:?} neighbour_cell_information_for_ca: {:?} }}",
+            "DNwrkBroadcast {{ cell_re_select_parameters: {} cell_load_ca: {:?} tetra_network_time: {:?} number_of_ca_neighbour_cells: {:?} neighbour_cell_information_for_ca: {:?} }}",
             self.cell_re_select_parameters,
             self.cell_load_ca,
             self.tetra_network_time,
@@ -111,3 +113,69 @@ impl fmt::Display for DNwrkBroadcast {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_nwrk_broadcast_neighbour_cells() {
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: CellReselectParameters::from_db(10, 6, 6, 4),
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: vec![
+                NeighbourCellInfoCa {
+                    cell_identifier_ca: 1,
+                    cell_reselection_types_supported: 1,
+                    neighbour_cell_synchronized: false,
+                    cell_service_level: 0,
+                    main_carrier_number: 1521,
+                    main_carrier_number_extension: None,
+                    mcc: None,
+                    mnc: None,
+                    location_area: Some(2),
+                    maximum_ms_transmit_power: None,
+                    minimum_rx_access_level: None,
+                    subscriber_class: None,
+                    bs_service_details: None,
+                    timeshare_cell_or_security_parameters: None,
+                    tdma_frame_offset: None,
+                },
+                NeighbourCellInfoCa {
+                    cell_identifier_ca: 2,
+                    cell_reselection_types_supported: 1,
+                    neighbour_cell_synchronized: true,
+                    cell_service_level: 2,
+                    main_carrier_number: 1530,
+                    main_carrier_number_extension: None,
+                    mcc: None,
+                    mnc: None,
+                    location_area: None,
+                    maximum_ms_transmit_power: None,
+                    minimum_rx_access_level: None,
+                    subscriber_class: None,
+                    bs_service_details: None,
+                    timeshare_cell_or_security_parameters: None,
+                    tdma_frame_offset: None,
+                },
+            ],
+        };
+        let mut buf = BitBuffer::new_autoexpand(128);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        let expected = concat!(
+            "010010100110011001000101010",                     // Header, re-select parameters, 2 neighbour cells
+            "00001010000101111100011000100000000000010000000", // Cell 1 with location area
+            "00010011100101111110100",                         // Cell 2
+        );
+        assert_eq!(buf.to_bitstr(), expected);
+
+        buf.seek(0);
+        let parsed = DNwrkBroadcast::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed.cell_re_select_parameters, pdu.cell_re_select_parameters);
+        assert_eq!(parsed.number_of_ca_neighbour_cells, Some(2));
+        assert_eq!(parsed.neighbour_cell_information_for_ca, pdu.neighbour_cell_information_for_ca);
+        assert_eq!(buf.get_len_remaining(), 0);
+    }
+}
@@ -10,14 +10,12 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 /// Response expected: -
 /// Response to: U-RESTORE
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DRestoreFail {
     /// Type1, 2 bits, Fail cause
     pub fail_cause: u8,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DRestoreFail {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -27,13 +25,10 @@ impl DRestoreFail {
         // Type1
         let fail_cause = buffer.read_field(2, "fail_cause")? as u8;
 
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        // obit designates presence of any further type2, type3 or type4 fields, of which there are none
+        let obit = delimiters::read_obit(buffer)?;
         if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
+            return Err(PduParseErr::InvalidValue { field: "obit", value: 1 });
         }
 
         Ok(DRestoreFail { fail_cause })
@@ -45,8 +40,8 @@ impl DRestoreFail {
         buffer.write_bits(MlePduTypeDl::DRestoreFail.into_raw(), 3);
         // Type1
         buffer.write_bits(self.fail_cause as u64, 2);
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
+        // No optional elements
+        delimiters::write_obit(buffer, 0);
         Ok(())
     }
 }
@@ -11,3 +11,25 @@ pub mod d_restore_fail;
 pub mod u_channel_class_advice;
 pub mod u_prepare;
 pub mod u_restore;
+
+use tetra_core::BitBuffer;
+
+/// Takes the remainder of the buffer as the SDU carried by an MLE PDU, if anything is left.
+/// The SDU is the last element of the PDUs carrying one, without a P-bit.
+pub(crate) fn read_sdu(buffer: &mut BitBuffer) -> Option<BitBuffer> {
+    let len = buffer.get_len_remaining();
+    if len == 0 {
+        return None;
+    }
+    let sdu = BitBuffer::from_bitbuffer_pos(buffer);
+    buffer.seek_rel(len as isize);
+    Some(sdu)
+}
+
+/// Appends the SDU carried by an MLE PDU, from its start regardless of its read position
+pub(crate) fn write_sdu(buffer: &mut BitBuffer, sdu: &BitBuffer) {
+    let mut sdu = sdu.clone();
+    sdu.seek(0);
+    let len = sdu.get_len();
+    buffer.copy_bits(&mut sdu, len);
+}
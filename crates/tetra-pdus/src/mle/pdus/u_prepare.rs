@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
+use crate::mle::pdus::{read_sdu, write_sdu};
 
 /// Representation of the U-PREPARE PDU (Clause 18.4.1.4.6).
 /// The message shall be sent on the serving cell to the SwMI by the MS-MLE, when preparation of cell reselection to a neighbour cell is in progress.
@@ -11,37 +12,28 @@ use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 /// Response to: -
 
 // note 1: The SDU may carry an MM registration PDU which is used to forward register to a new CA cell during announced type 1 cell reselection or a U-OTAR CCK DEMAND PDU which is used to request the Common Cipher Key (CCK) of the new cell. The SDU is coded according to the MM protocol description. There shall be no P-bit in the PDU coding preceding the SDU information element.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UPrepare {
     /// Type2, 5 bits, Cell identifier CA
     pub cell_identifier_ca: Option<u64>,
-    /// Conditional See note,
-    pub sdu: Option<u64>,
+    /// Conditional, MM PDU taking up the rest of the PDU, see note 1
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl UPrepare {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
         expect_pdu_type!(pdu_type, MlePduTypeUl::UPrepare)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of any further type2 fields or the SDU
         let obit = delimiters::read_obit(buffer)?;
 
         // Type2
         let cell_identifier_ca = typed::parse_type2_generic(obit, buffer, 5, "cell_identifier_ca")?;
 
-        // Conditional
-        unimplemented!();
-        let sdu = if obit { Some(0) } else { None };
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, no P-bit
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(UPrepare { cell_identifier_ca, sdu })
     }
@@ -52,7 +44,7 @@ impl UPrepare {
         buffer.write_bits(MlePduTypeUl::UPrepare.into_raw(), 3);
 
         // Check if any optional field present and place o-bit
-        let obit = self.cell_identifier_ca.is_some();
+        let obit = self.cell_identifier_ca.is_some() || self.sdu.is_some();
         delimiters::write_obit(buffer, obit as u8);
         if !obit {
             return Ok(());
@@ -62,12 +54,9 @@ impl UPrepare {
         typed::write_type2_generic(obit, buffer, self.cell_identifier_ca, 5);
 
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -77,7 +66,37 @@ impl fmt::Display for UPrepare {
         write!(
             f,
             "UPrepare {{ cell_identifier_ca: {:?} sdu: {:?} }}",
-            self.cell_identifier_ca, self.sdu,
+            self.cell_identifier_ca,
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u_prepare_with_sdu() {
+        let pdu = UPrepare {
+            cell_identifier_ca: Some(5),
+            sdu: Some(BitBuffer::from_bitstr("0010110011")),
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.to_bitstr(), concat!("000", "1", "1", "00101", "0010110011"));
+
+        buf.seek(0);
+        let parsed = UPrepare::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed.cell_identifier_ca, Some(5));
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), "0010110011");
+    }
+
+    #[test]
+    fn test_u_prepare_without_cell() {
+        let mut buf = BitBuffer::from_bitstr("0000");
+        let parsed = UPrepare::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed.cell_identifier_ca, None);
+        assert!(parsed.sdu.is_none());
+    }
+}
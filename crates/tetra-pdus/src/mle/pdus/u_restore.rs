@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
+use crate::mle::pdus::{read_sdu, write_sdu};
 
 /// Representation of the U-RESTORE PDU (Clause 18.4.1.4.7).
 /// The message shall be sent by the MS-MLE, when restoration of the C-Plane towards a new cell is in progress.
@@ -13,7 +14,7 @@ use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 // note 1: The element is present in the PDU if its value on the new cell is different from that on the old cell.
 // note 2: When included, this element gives the value for the old cell.
 // note 3: This PDU shall carry a CMCE U-CALL RESTORE PDU which shall be used to restore a call after cell reselection. There shall be no P-bit in the PDU coding preceding the "SDU" information element.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct URestore {
     /// Type2, 10 bits, See notes 1 and 2,
     pub mcc: Option<u64>,
@@ -21,19 +22,17 @@ pub struct URestore {
     pub mnc: Option<u64>,
     /// Type2, 14 bits, See notes 1 and 2,
     pub la: Option<u64>,
-    /// Conditional This PDU shall carry a CMCE U-CALL RESTORE PDU which shall be used to restore a call after cell reselection. The SDU is coded according to the CMCE protocol. There shall be no P-bit in the PDU coding preceding the "SDU" information element.,
-    pub sdu: Option<u64>,
+    /// Conditional, CMCE U-CALL RESTORE PDU taking up the rest of the PDU, see note 3
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl URestore {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
         expect_pdu_type!(pdu_type, MlePduTypeUl::URestore)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of any further type2 fields or the SDU
         let obit = delimiters::read_obit(buffer)?;
 
         // Type2
@@ -42,15 +41,9 @@ impl URestore {
         let mnc = typed::parse_type2_generic(obit, buffer, 14, "mnc")?;
         // Type2
         let la = typed::parse_type2_generic(obit, buffer, 14, "la")?;
-        // Conditional
-        unimplemented!();
-        let sdu = if obit { Some(0) } else { None };
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, no P-bit
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(URestore { mcc, mnc, la, sdu })
     }
@@ -61,7 +54,7 @@ impl URestore {
         buffer.write_bits(MlePduTypeUl::URestore.into_raw(), 3);
 
         // Check if any optional field present and place o-bit
-        let obit = self.mcc.is_some() || self.mnc.is_some() || self.la.is_some();
+        let obit = self.mcc.is_some() || self.mnc.is_some() || self.la.is_some() || self.sdu.is_some();
         delimiters::write_obit(buffer, obit as u8);
         if !obit {
             return Ok(());
@@ -77,12 +70,9 @@ impl URestore {
         typed::write_type2_generic(obit, buffer, self.la, 14);
 
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -92,7 +82,34 @@ impl fmt::Display for URestore {
         write!(
             f,
             "URestore {{ mcc: {:?} mnc: {:?} la: {:?} sdu: {:?} }}",
-            self.mcc, self.mnc, self.la, self.sdu,
+            self.mcc,
+            self.mnc,
+            self.la,
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u_restore_roundtrip() {
+        let pdu = URestore {
+            mcc: None,
+            mnc: None,
+            la: Some(3),
+            sdu: Some(BitBuffer::from_bitstr("01001110")),
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.to_bitstr(), concat!("100", "1", "0", "0", "1", "00000000000011", "01001110"));
+
+        buf.seek(0);
+        let parsed = URestore::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed.mcc, None);
+        assert_eq!(parsed.la, Some(3));
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), "01001110");
+    }
+}
@@ -1,6 +1,7 @@
 pub mod mm_pdu_type_dl;
 pub mod mm_pdu_type_ul;
 
+pub mod authentication_sub_type;
 pub mod energy_saving_mode;
 pub mod location_update_accept_type;
 pub mod location_update_type;
@@ -0,0 +1,43 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::fields::authentication_values::{read_auth_random, write_auth_random};
+
+/// Authentication downlink element (EN 300 392-7 clause 6.5).
+/// The infrastructure includes it in D-LOCATION UPDATE ACCEPT to answer the challenge of an
+/// Authentication uplink element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationDownlink {
+    /// 80 bits, Random seed RS
+    pub random_seed: u128,
+    /// 32 bits, Response value RES2
+    pub response_value: u32,
+}
+
+impl AuthenticationDownlink {
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let random_seed = read_auth_random(buffer, "random_seed")?;
+        let response_value = buffer.read_field(32, "response_value")? as u32;
+        Ok(AuthenticationDownlink {
+            random_seed,
+            response_value,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        write_auth_random(buffer, self.random_seed);
+        buffer.write_bits(self.response_value as u64, 32);
+        Ok(())
+    }
+}
+
+impl fmt::Display for AuthenticationDownlink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "AuthenticationDownlink {{ random_seed: {:020x} response_value: {:08x} }}",
+            self.random_seed, self.response_value
+        )
+    }
+}
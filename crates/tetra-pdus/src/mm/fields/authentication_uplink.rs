@@ -0,0 +1,31 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::fields::authentication_values::{read_auth_random, write_auth_random};
+
+/// Authentication uplink element (EN 300 392-7 clause 6.5).
+/// The MS includes it in U-LOCATION UPDATE DEMAND to authenticate the infrastructure while registering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationUplink {
+    /// 80 bits, Random challenge RAND2
+    pub random_challenge: u128,
+}
+
+impl AuthenticationUplink {
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let random_challenge = read_auth_random(buffer, "random_challenge")?;
+        Ok(AuthenticationUplink { random_challenge })
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        write_auth_random(buffer, self.random_challenge);
+        Ok(())
+    }
+}
+
+impl fmt::Display for AuthenticationUplink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AuthenticationUplink {{ random_challenge: {:020x} }}", self.random_challenge)
+    }
+}
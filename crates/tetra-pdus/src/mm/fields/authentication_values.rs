@@ -0,0 +1,20 @@
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+/// Random challenges (RAND1, RAND2) and random seeds (RS) are 80 bits wide, EN 300 392-7 clause 6.5.
+/// They are carried in the low 80 bits of a u128.
+pub const AUTH_RANDOM_BITS: usize = 80;
+const AUTH_RANDOM_MASK: u128 = (1u128 << AUTH_RANDOM_BITS) - 1;
+
+/// Read an 80-bit random challenge or random seed
+pub fn read_auth_random(buffer: &mut BitBuffer, field: &'static str) -> Result<u128, PduParseErr> {
+    let hi = buffer.read_field(AUTH_RANDOM_BITS - 64, field)? as u128;
+    let lo = buffer.read_field(64, field)? as u128;
+    Ok((hi << 64) | lo)
+}
+
+/// Write an 80-bit random challenge or random seed. Bits above bit 80 are ignored.
+pub fn write_auth_random(buffer: &mut BitBuffer, value: u128) {
+    let value = value & AUTH_RANDOM_MASK;
+    buffer.write_bits((value >> 64) as u64, AUTH_RANDOM_BITS - 64);
+    buffer.write_bits(value as u64, 64);
+}
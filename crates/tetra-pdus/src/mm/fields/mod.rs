@@ -1,3 +1,6 @@
+pub mod authentication_downlink;
+pub mod authentication_uplink;
+pub mod authentication_values;
 pub mod energy_saving_information;
 pub mod group_identity_attachment;
 pub mod group_identity_downlink;
@@ -0,0 +1,117 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::authentication_values::{read_auth_random, write_auth_random};
+
+/// Representation of the D-AUTHENTICATION DEMAND PDU (EN 300 392-7 clause 6.5.1.1.1).
+/// The infrastructure sends this message to the MS to challenge it during authentication.
+/// Response expected: U-AUTHENTICATION RESPONSE
+/// Response to: -/U-LOCATION UPDATE DEMAND
+#[derive(Debug)]
+pub struct DAuthenticationDemand {
+    /// 80 bits, Random challenge RAND1
+    pub random_challenge: u128,
+    /// 80 bits, Random seed RS
+    pub random_seed: u128,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DAuthenticationDemand {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Demand)?;
+
+        let random_challenge = read_auth_random(buffer, "random_challenge")?;
+        let random_seed = read_auth_random(buffer, "random_seed")?;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DAuthenticationDemand {
+            random_challenge,
+            random_seed,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Demand.into_raw(), 2);
+
+        write_auth_random(buffer, self.random_challenge);
+        write_auth_random(buffer, self.random_seed);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DAuthenticationDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DAuthenticationDemand {{ random_challenge: {:020x} random_seed: {:020x} proprietary: {:?} }}",
+            self.random_challenge, self.random_seed, self.proprietary,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_authentication_demand_roundtrip() {
+        debug::setup_logging_verbose();
+        let pdu = DAuthenticationDemand {
+            random_challenge: 0x8123_4567_89ab_cdef_0011,
+            random_seed: 0x0fed_cba9_8765_4321_ff01,
+            proprietary: None,
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(170);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+        assert_eq!(buf.get_len_written(), 4 + 2 + 80 + 80 + 1);
+
+        buf.seek(0);
+        let parsed = DAuthenticationDemand::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert_eq!(buf.get_len_remaining(), 0, "Buffer not fully consumed");
+        assert_eq!(parsed.random_challenge, pdu.random_challenge);
+        assert_eq!(parsed.random_seed, pdu.random_seed);
+    }
+}
@@ -0,0 +1,146 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::authentication_values::{read_auth_random, write_auth_random};
+
+/// Representation of the D-AUTHENTICATION RESPONSE PDU (EN 300 392-7 clause 6.5.1.1.2).
+/// The infrastructure sends this message to the MS to answer a U-AUTHENTICATION DEMAND,
+/// optionally challenging the MS in turn (mutual authentication).
+/// Response expected: U-AUTHENTICATION RESULT
+/// Response to: U-AUTHENTICATION DEMAND
+
+// note 1: Random challenge RAND1 is present only if Mutual authentication flag is set.
+#[derive(Debug)]
+pub struct DAuthenticationResponse {
+    /// 80 bits, Random seed RS
+    pub random_seed: u128,
+    /// 32 bits, Response value RES2
+    pub response_value: u32,
+    /// 1 bits, Mutual authentication flag
+    pub mutual_authentication_flag: bool,
+    /// Conditional 80 bits, Random challenge RAND1, see note 1
+    pub random_challenge: Option<u128>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DAuthenticationResponse {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Response)?;
+
+        let random_seed = read_auth_random(buffer, "random_seed")?;
+        let response_value = buffer.read_field(32, "response_value")? as u32;
+        let mutual_authentication_flag = buffer.read_field(1, "mutual_authentication_flag")? != 0;
+        // Conditional
+        let random_challenge = if mutual_authentication_flag {
+            Some(read_auth_random(buffer, "random_challenge")?)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DAuthenticationResponse {
+            random_seed,
+            response_value,
+            mutual_authentication_flag,
+            random_challenge,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Response.into_raw(), 2);
+
+        write_auth_random(buffer, self.random_seed);
+        buffer.write_bits(self.response_value as u64, 32);
+        buffer.write_bits(self.mutual_authentication_flag as u64, 1);
+        // Conditional
+        if self.mutual_authentication_flag {
+            let Some(rand1) = self.random_challenge else {
+                return Err(PduParseErr::FieldNotPresent {
+                    field: Some("random_challenge"),
+                });
+            };
+            write_auth_random(buffer, rand1);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DAuthenticationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DAuthenticationResponse {{ random_seed: {:020x} response_value: {:08x} mutual_authentication_flag: {:?} random_challenge: {:?} proprietary: {:?} }}",
+            self.random_seed, self.response_value, self.mutual_authentication_flag, self.random_challenge, self.proprietary,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_authentication_response_roundtrip() {
+        debug::setup_logging_verbose();
+        let pdu = DAuthenticationResponse {
+            random_seed: 0x0fed_cba9_8765_4321_ff01,
+            response_value: 0xdead_beef,
+            mutual_authentication_flag: false,
+            random_challenge: None,
+            proprietary: None,
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(130);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+        assert_eq!(buf.get_len_written(), 4 + 2 + 80 + 32 + 1 + 1);
+
+        buf.seek(0);
+        let parsed = DAuthenticationResponse::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert_eq!(buf.get_len_remaining(), 0, "Buffer not fully consumed");
+        assert_eq!(parsed.random_seed, pdu.random_seed);
+        assert_eq!(parsed.response_value, pdu.response_value);
+        assert!(!parsed.mutual_authentication_flag);
+    }
+}
@@ -0,0 +1,109 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+
+/// Representation of the D-AUTHENTICATION RESULT PDU (EN 300 392-7 clause 6.5.1.1.3).
+/// The infrastructure sends this message to the MS to report the outcome of the authentication,
+/// optionally answering a challenge from the MS (mutual authentication).
+/// Response expected: -/U-AUTHENTICATION RESULT
+/// Response to: U-AUTHENTICATION RESPONSE
+
+// note 1: Response value RES2 is present only if Mutual authentication flag is set.
+#[derive(Debug)]
+pub struct DAuthenticationResult {
+    /// 1 bits, Authentication result. True if the MS was successfully authenticated
+    pub authentication_result: bool,
+    /// 1 bits, Mutual authentication flag
+    pub mutual_authentication_flag: bool,
+    /// Conditional 32 bits, Response value RES2, see note 1
+    pub response_value: Option<u32>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DAuthenticationResult {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Result)?;
+
+        let authentication_result = buffer.read_field(1, "authentication_result")? != 0;
+        let mutual_authentication_flag = buffer.read_field(1, "mutual_authentication_flag")? != 0;
+        // Conditional
+        let response_value = if mutual_authentication_flag {
+            Some(buffer.read_field(32, "response_value")? as u32)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DAuthenticationResult {
+            authentication_result,
+            mutual_authentication_flag,
+            response_value,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Result.into_raw(), 2);
+
+        buffer.write_bits(self.authentication_result as u64, 1);
+        buffer.write_bits(self.mutual_authentication_flag as u64, 1);
+        // Conditional
+        if self.mutual_authentication_flag {
+            let Some(res2) = self.response_value else {
+                return Err(PduParseErr::FieldNotPresent {
+                    field: Some("response_value"),
+                });
+            };
+            buffer.write_bits(res2 as u64, 32);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DAuthenticationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DAuthenticationResult {{ authentication_result: {:?} mutual_authentication_flag: {:?} response_value: {:?} proprietary: {:?} }}",
+            self.authentication_result, self.mutual_authentication_flag, self.response_value, self.proprietary,
+        )
+    }
+}
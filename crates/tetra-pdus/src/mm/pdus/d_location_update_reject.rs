@@ -47,8 +47,11 @@ impl DLocationUpdateReject {
         // Type1
         let cipher_control = buffer.read_field(1, "cipher_control")? != 0;
         // Conditional
-        unimplemented!();
-        let ciphering_parameters = if true { Some(0) } else { None };
+        let ciphering_parameters = if cipher_control {
+            Some(buffer.read_field(10, "ciphering_parameters")?)
+        } else {
+            None
+        };
 
         // obit designates presence of any further type2, type3 or type4 fields
         let mut obit = delimiters::read_obit(buffer)?;
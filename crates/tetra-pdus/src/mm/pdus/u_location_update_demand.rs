@@ -8,6 +8,7 @@ use crate::mm::enums::energy_saving_mode::EnergySavingMode;
 use crate::mm::enums::location_update_type::LocationUpdateType;
 use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
 use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+use crate::mm::fields::authentication_uplink::AuthenticationUplink;
 use crate::mm::fields::group_identity_location_demand::GroupIdentityLocationDemand;
 
 /// Representation of the U-LOCATION UPDATE DEMAND PDU (Clause 16.9.3.4).
@@ -41,8 +42,8 @@ pub struct ULocationUpdateDemand {
     pub group_identity_location_demand: Option<GroupIdentityLocationDemand>,
     /// Type3, 3 bits, Group report response
     pub group_report_response: Option<Type3FieldGeneric>,
-    /// Type3, 3 bits, See ETSI EN 300 392-7 [8],
-    pub authentication_uplink: Option<Type3FieldGeneric>,
+    /// Type3, See ETSI EN 300 392-7 [8],
+    pub authentication_uplink: Option<AuthenticationUplink>,
     /// Type3, 3 bits, See note 2,
     pub extended_capabilities: Option<Type3FieldGeneric>,
     /// Type3, 3 bits, Proprietary
@@ -119,7 +120,12 @@ impl ULocationUpdateDemand {
         let group_report_response = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::GroupReportResponse)?;
 
         // Type3
-        let authentication_uplink = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::AuthenticationUplink)?;
+        let authentication_uplink = typed::parse_type3_struct(
+            obit,
+            buffer,
+            MmType34ElemIdUl::AuthenticationUplink,
+            AuthenticationUplink::from_bitbuf,
+        )?;
 
         // Type3
         let extended_capabilities = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::ExtendedCapabilities)?;
@@ -217,7 +223,13 @@ impl ULocationUpdateDemand {
         typed::write_type3_generic(obit, buffer, &self.group_report_response, MmType34ElemIdUl::GroupReportResponse)?;
 
         // Type3
-        typed::write_type3_generic(obit, buffer, &self.authentication_uplink, MmType34ElemIdUl::AuthenticationUplink)?;
+        typed::write_type3_struct(
+            obit,
+            buffer,
+            &self.authentication_uplink,
+            MmType34ElemIdUl::AuthenticationUplink,
+            AuthenticationUplink::to_bitbuf,
+        )?;
 
         // Type3
 
@@ -302,6 +314,38 @@ mod tests {
         assert_eq!(giu0.gssi, Some(26));
     }
 
+    #[test]
+    fn test_u_location_update_demand_with_authentication_uplink() {
+        debug::setup_logging_verbose();
+        let pdu = ULocationUpdateDemand {
+            location_update_type: LocationUpdateType::ItsiAttach,
+            request_to_append_la: false,
+            cipher_control: false,
+            ciphering_parameters: None,
+            class_of_ms: None,
+            energy_saving_mode: None,
+            la_information: None,
+            ssi: None,
+            address_extension: None,
+            group_identity_location_demand: None,
+            group_report_response: None,
+            authentication_uplink: Some(AuthenticationUplink {
+                random_challenge: 0x8123_4567_89ab_cdef_0011,
+            }),
+            extended_capabilities: None,
+            proprietary: None,
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(128);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+
+        buf.seek(0);
+        let parsed = ULocationUpdateDemand::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert_eq!(buf.get_len_remaining(), 0, "Buffer not fully consumed");
+        assert_eq!(parsed.authentication_uplink, pdu.authentication_uplink);
+    }
+
     #[test]
     fn test_u_location_update_demand_with_gild_and_esm() {
         // Vec from moto upon registration
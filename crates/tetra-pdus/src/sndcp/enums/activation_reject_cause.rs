@@ -0,0 +1,72 @@
+/// Clause 28 (SNDCP) Activation reject cause
+/// Bits: 8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ActivationRejectCause {
+    AnyReason = 0,
+    MsNotProvisionedForPacketData = 1,
+    Ipv4NotSupported = 2,
+    Ipv6NotSupported = 3,
+    Ipv4DynamicAddressNotSupported = 4,
+    Ipv4StaticAddressNotCorrect = 5,
+    Ipv4StaticAddressNotProvisioned = 6,
+    NsapiAlreadyInUse = 10,
+    InsufficientResources = 12,
+}
+
+impl std::convert::TryFrom<u64> for ActivationRejectCause {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(ActivationRejectCause::AnyReason),
+            1 => Ok(ActivationRejectCause::MsNotProvisionedForPacketData),
+            2 => Ok(ActivationRejectCause::Ipv4NotSupported),
+            3 => Ok(ActivationRejectCause::Ipv6NotSupported),
+            4 => Ok(ActivationRejectCause::Ipv4DynamicAddressNotSupported),
+            5 => Ok(ActivationRejectCause::Ipv4StaticAddressNotCorrect),
+            6 => Ok(ActivationRejectCause::Ipv4StaticAddressNotProvisioned),
+            10 => Ok(ActivationRejectCause::NsapiAlreadyInUse),
+            12 => Ok(ActivationRejectCause::InsufficientResources),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ActivationRejectCause {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            ActivationRejectCause::AnyReason => 0,
+            ActivationRejectCause::MsNotProvisionedForPacketData => 1,
+            ActivationRejectCause::Ipv4NotSupported => 2,
+            ActivationRejectCause::Ipv6NotSupported => 3,
+            ActivationRejectCause::Ipv4DynamicAddressNotSupported => 4,
+            ActivationRejectCause::Ipv4StaticAddressNotCorrect => 5,
+            ActivationRejectCause::Ipv4StaticAddressNotProvisioned => 6,
+            ActivationRejectCause::NsapiAlreadyInUse => 10,
+            ActivationRejectCause::InsufficientResources => 12,
+        }
+    }
+}
+
+impl From<ActivationRejectCause> for u64 {
+    fn from(e: ActivationRejectCause) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for ActivationRejectCause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ActivationRejectCause::AnyReason => write!(f, "AnyReason"),
+            ActivationRejectCause::MsNotProvisionedForPacketData => write!(f, "MsNotProvisionedForPacketData"),
+            ActivationRejectCause::Ipv4NotSupported => write!(f, "Ipv4NotSupported"),
+            ActivationRejectCause::Ipv6NotSupported => write!(f, "Ipv6NotSupported"),
+            ActivationRejectCause::Ipv4DynamicAddressNotSupported => write!(f, "Ipv4DynamicAddressNotSupported"),
+            ActivationRejectCause::Ipv4StaticAddressNotCorrect => write!(f, "Ipv4StaticAddressNotCorrect"),
+            ActivationRejectCause::Ipv4StaticAddressNotProvisioned => write!(f, "Ipv4StaticAddressNotProvisioned"),
+            ActivationRejectCause::NsapiAlreadyInUse => write!(f, "NsapiAlreadyInUse"),
+            ActivationRejectCause::InsufficientResources => write!(f, "InsufficientResources"),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+/// Clause 28 (SNDCP) Address type identifier in SN-ACTIVATE PDP CONTEXT DEMAND and ACCEPT
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SndcpAddressType {
+    /// IPv4 static address
+    Ipv4Static = 0,
+    /// IPv4 dynamic address
+    Ipv4Dynamic = 1,
+    /// Mobile IPv4, foreign agent care-of address requested
+    MobileIpv4ForeignAgent = 2,
+    /// Mobile IPv4, co-located care-of address requested
+    MobileIpv4CoLocated = 3,
+    /// IPv6 address
+    Ipv6 = 4,
+    /// No address, secondary PDP context
+    NoAddress = 7,
+}
+
+impl std::convert::TryFrom<u64> for SndcpAddressType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(SndcpAddressType::Ipv4Static),
+            1 => Ok(SndcpAddressType::Ipv4Dynamic),
+            2 => Ok(SndcpAddressType::MobileIpv4ForeignAgent),
+            3 => Ok(SndcpAddressType::MobileIpv4CoLocated),
+            4 => Ok(SndcpAddressType::Ipv6),
+            7 => Ok(SndcpAddressType::NoAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SndcpAddressType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SndcpAddressType::Ipv4Static => 0,
+            SndcpAddressType::Ipv4Dynamic => 1,
+            SndcpAddressType::MobileIpv4ForeignAgent => 2,
+            SndcpAddressType::MobileIpv4CoLocated => 3,
+            SndcpAddressType::Ipv6 => 4,
+            SndcpAddressType::NoAddress => 7,
+        }
+    }
+}
+
+impl From<SndcpAddressType> for u64 {
+    fn from(e: SndcpAddressType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SndcpAddressType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SndcpAddressType::Ipv4Static => write!(f, "Ipv4Static"),
+            SndcpAddressType::Ipv4Dynamic => write!(f, "Ipv4Dynamic"),
+            SndcpAddressType::MobileIpv4ForeignAgent => write!(f, "MobileIpv4ForeignAgent"),
+            SndcpAddressType::MobileIpv4CoLocated => write!(f, "MobileIpv4CoLocated"),
+            SndcpAddressType::Ipv6 => write!(f, "Ipv6"),
+            SndcpAddressType::NoAddress => write!(f, "NoAddress"),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod sndcp_pdu_type;
+
+pub mod activation_reject_cause;
+pub mod address_type;
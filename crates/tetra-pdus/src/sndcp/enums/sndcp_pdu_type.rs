@@ -0,0 +1,93 @@
+/// Clause 28 (SNDCP) SN PDU type
+/// Bits: 4
+/// Value 0 is SN-ACTIVATE PDP CONTEXT DEMAND on the uplink and SN-ACTIVATE PDP CONTEXT ACCEPT on the downlink
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SndcpPduType {
+    SnActivatePdpContext = 0,
+    SnDeactivatePdpContextAccept = 1,
+    SnDeactivatePdpContextDemand = 2,
+    SnActivatePdpContextReject = 3,
+    SnUnitdata = 4,
+    SnData = 5,
+    SnDataTransmitRequest = 6,
+    SnDataTransmitResponse = 7,
+    SnEndOfData = 8,
+    SnReconnect = 9,
+    SnPage = 10,
+    SnNotSupported = 11,
+    SnDataPriority = 12,
+    SnModify = 13,
+}
+
+impl std::convert::TryFrom<u64> for SndcpPduType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(SndcpPduType::SnActivatePdpContext),
+            1 => Ok(SndcpPduType::SnDeactivatePdpContextAccept),
+            2 => Ok(SndcpPduType::SnDeactivatePdpContextDemand),
+            3 => Ok(SndcpPduType::SnActivatePdpContextReject),
+            4 => Ok(SndcpPduType::SnUnitdata),
+            5 => Ok(SndcpPduType::SnData),
+            6 => Ok(SndcpPduType::SnDataTransmitRequest),
+            7 => Ok(SndcpPduType::SnDataTransmitResponse),
+            8 => Ok(SndcpPduType::SnEndOfData),
+            9 => Ok(SndcpPduType::SnReconnect),
+            10 => Ok(SndcpPduType::SnPage),
+            11 => Ok(SndcpPduType::SnNotSupported),
+            12 => Ok(SndcpPduType::SnDataPriority),
+            13 => Ok(SndcpPduType::SnModify),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SndcpPduType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SndcpPduType::SnActivatePdpContext => 0,
+            SndcpPduType::SnDeactivatePdpContextAccept => 1,
+            SndcpPduType::SnDeactivatePdpContextDemand => 2,
+            SndcpPduType::SnActivatePdpContextReject => 3,
+            SndcpPduType::SnUnitdata => 4,
+            SndcpPduType::SnData => 5,
+            SndcpPduType::SnDataTransmitRequest => 6,
+            SndcpPduType::SnDataTransmitResponse => 7,
+            SndcpPduType::SnEndOfData => 8,
+            SndcpPduType::SnReconnect => 9,
+            SndcpPduType::SnPage => 10,
+            SndcpPduType::SnNotSupported => 11,
+            SndcpPduType::SnDataPriority => 12,
+            SndcpPduType::SnModify => 13,
+        }
+    }
+}
+
+impl From<SndcpPduType> for u64 {
+    fn from(e: SndcpPduType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SndcpPduType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SndcpPduType::SnActivatePdpContext => write!(f, "SnActivatePdpContext"),
+            SndcpPduType::SnDeactivatePdpContextAccept => write!(f, "SnDeactivatePdpContextAccept"),
+            SndcpPduType::SnDeactivatePdpContextDemand => write!(f, "SnDeactivatePdpContextDemand"),
+            SndcpPduType::SnActivatePdpContextReject => write!(f, "SnActivatePdpContextReject"),
+            SndcpPduType::SnUnitdata => write!(f, "SnUnitdata"),
+            SndcpPduType::SnData => write!(f, "SnData"),
+            SndcpPduType::SnDataTransmitRequest => write!(f, "SnDataTransmitRequest"),
+            SndcpPduType::SnDataTransmitResponse => write!(f, "SnDataTransmitResponse"),
+            SndcpPduType::SnEndOfData => write!(f, "SnEndOfData"),
+            SndcpPduType::SnReconnect => write!(f, "SnReconnect"),
+            SndcpPduType::SnPage => write!(f, "SnPage"),
+            SndcpPduType::SnNotSupported => write!(f, "SnNotSupported"),
+            SndcpPduType::SnDataPriority => write!(f, "SnDataPriority"),
+            SndcpPduType::SnModify => write!(f, "SnModify"),
+        }
+    }
+}
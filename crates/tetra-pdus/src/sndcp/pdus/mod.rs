@@ -0,0 +1,7 @@
+pub mod sn_activate_pdp_context_accept;
+pub mod sn_activate_pdp_context_demand;
+pub mod sn_activate_pdp_context_reject;
+pub mod sn_data;
+pub mod sn_deactivate_pdp_context_accept;
+pub mod sn_deactivate_pdp_context_demand;
+pub mod sn_unitdata;
@@ -0,0 +1,185 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::address_type::SndcpAddressType;
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+
+/// Representation of the SN-ACTIVATE PDP CONTEXT ACCEPT PDU (Clause 28.4).
+/// The infrastructure sends this message to the MS to confirm activation of a PDP context.
+/// Response expected: -
+/// Response to: SN-ACTIVATE PDP CONTEXT DEMAND
+
+// note 1: The IP address is present if the address type identifier is "IPv4 static address" or "IPv4 dynamic address".
+#[derive(Debug)]
+pub struct SnActivatePdpContextAccept {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 3 bits, PDU priority max
+    pub pdu_priority_max: u8,
+    /// Type1, 4 bits, READY timer
+    pub ready_timer: u8,
+    /// Type1, 4 bits, STANDBY timer
+    pub standby_timer: u8,
+    /// Type1, 4 bits, Response wait time
+    pub response_wait_time: u8,
+    /// Type1, 3 bits, Address type identifier in accept
+    pub address_type: u8,
+    /// Conditional 32 bits, See note 1
+    pub ip_address: Option<u32>,
+    /// Type1, 8 bits, PCOMP negotiation
+    pub pcomp_negotiation: u8,
+    /// Type2, 3 bits, Maximum transmission unit
+    pub mtu: Option<u64>,
+}
+
+impl SnActivatePdpContextAccept {
+    fn has_ip_address(address_type: u8) -> bool {
+        address_type as u64 == SndcpAddressType::Ipv4Static.into_raw() || address_type as u64 == SndcpAddressType::Ipv4Dynamic.into_raw()
+    }
+
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnActivatePdpContext)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let pdu_priority_max = buffer.read_field(3, "pdu_priority_max")? as u8;
+        // Type1
+        let ready_timer = buffer.read_field(4, "ready_timer")? as u8;
+        // Type1
+        let standby_timer = buffer.read_field(4, "standby_timer")? as u8;
+        // Type1
+        let response_wait_time = buffer.read_field(4, "response_wait_time")? as u8;
+        // Type1
+        let address_type = buffer.read_field(3, "address_type")? as u8;
+        // Conditional
+        let ip_address = if Self::has_ip_address(address_type) {
+            Some(buffer.read_field(32, "ip_address")? as u32)
+        } else {
+            None
+        };
+        // Type1
+        let pcomp_negotiation = buffer.read_field(8, "pcomp_negotiation")? as u8;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type2
+        let mtu = typed::parse_type2_generic(obit, buffer, 3, "mtu")?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(SnActivatePdpContextAccept {
+            nsapi,
+            pdu_priority_max,
+            ready_timer,
+            standby_timer,
+            response_wait_time,
+            address_type,
+            ip_address,
+            pcomp_negotiation,
+            mtu,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnActivatePdpContext.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.pdu_priority_max as u64, 3);
+        // Type1
+        buffer.write_bits(self.ready_timer as u64, 4);
+        // Type1
+        buffer.write_bits(self.standby_timer as u64, 4);
+        // Type1
+        buffer.write_bits(self.response_wait_time as u64, 4);
+        // Type1
+        buffer.write_bits(self.address_type as u64, 3);
+        // Conditional
+        if Self::has_ip_address(self.address_type) {
+            let Some(ip_address) = self.ip_address else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("ip_address") });
+            };
+            buffer.write_bits(ip_address as u64, 32);
+        }
+        // Type1
+        buffer.write_bits(self.pcomp_negotiation as u64, 8);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.mtu.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type2
+        typed::write_type2_generic(obit, buffer, self.mtu, 3);
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnActivatePdpContextAccept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnActivatePdpContextAccept {{ nsapi: {:?} pdu_priority_max: {:?} ready_timer: {:?} standby_timer: {:?} response_wait_time: {:?} address_type: {:?} ip_address: {:?} pcomp_negotiation: {:?} mtu: {:?} }}",
+            self.nsapi,
+            self.pdu_priority_max,
+            self.ready_timer,
+            self.standby_timer,
+            self.response_wait_time,
+            self.address_type,
+            self.ip_address,
+            self.pcomp_negotiation,
+            self.mtu,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_activate_pdp_context_accept_roundtrip() {
+        debug::setup_logging_verbose();
+        let pdu = SnActivatePdpContextAccept {
+            nsapi: 5,
+            pdu_priority_max: 7,
+            ready_timer: 6,
+            standby_timer: 4,
+            response_wait_time: 3,
+            address_type: SndcpAddressType::Ipv4Dynamic.into_raw() as u8,
+            ip_address: Some(0x0a00000a),
+            pcomp_negotiation: 0,
+            mtu: Some(3),
+        };
+        let mut buf = BitBuffer::new_autoexpand(80);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+
+        buf.seek(0);
+        let parsed = SnActivatePdpContextAccept::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(buf.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(parsed.nsapi, 5);
+        assert_eq!(parsed.ip_address, Some(0x0a00000a));
+        assert_eq!(parsed.mtu, Some(3));
+    }
+}
@@ -0,0 +1,132 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::address_type::SndcpAddressType;
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+
+/// Representation of the SN-ACTIVATE PDP CONTEXT DEMAND PDU (Clause 28.4).
+/// The MS sends this message to the infrastructure to request activation of a PDP context.
+/// Response expected: SN-ACTIVATE PDP CONTEXT ACCEPT/SN-ACTIVATE PDP CONTEXT REJECT
+/// Response to: -
+
+// note 1: The IP address is present if the address type identifier is "IPv4 static address".
+// note 2: Optional elements (protocol configuration options, DCOMP negotiation, proprietary) are not interpreted.
+#[derive(Debug)]
+pub struct SnActivatePdpContextDemand {
+    /// Type1, 4 bits, SNDCP version
+    pub sndcp_version: u8,
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 3 bits, Address type identifier in demand
+    pub address_type: u8,
+    /// Conditional 32 bits, See note 1
+    pub ip_address: Option<u32>,
+    /// Type1, 4 bits, Packet data MS type
+    pub packet_data_ms_type: u8,
+    /// Type1, 8 bits, PCOMP negotiation
+    pub pcomp_negotiation: u8,
+}
+
+impl SnActivatePdpContextDemand {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnActivatePdpContext)?;
+
+        // Type1
+        let sndcp_version = buffer.read_field(4, "sndcp_version")? as u8;
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let address_type = buffer.read_field(3, "address_type")? as u8;
+        // Conditional
+        let ip_address = if address_type as u64 == SndcpAddressType::Ipv4Static.into_raw() {
+            Some(buffer.read_field(32, "ip_address")? as u32)
+        } else {
+            None
+        };
+        // Type1
+        let packet_data_ms_type = buffer.read_field(4, "packet_data_ms_type")? as u8;
+        // Type1
+        let pcomp_negotiation = buffer.read_field(8, "pcomp_negotiation")? as u8;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            // See note 2
+            tracing::debug!("SnActivatePdpContextDemand: ignoring optional elements: {}", buffer.dump_bin());
+            buffer.seek(buffer.get_len());
+        }
+
+        Ok(SnActivatePdpContextDemand {
+            sndcp_version,
+            nsapi,
+            address_type,
+            ip_address,
+            packet_data_ms_type,
+            pcomp_negotiation,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnActivatePdpContext.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.sndcp_version as u64, 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.address_type as u64, 3);
+        // Conditional
+        if let Some(ip_address) = self.ip_address {
+            buffer.write_bits(ip_address as u64, 32);
+        }
+        // Type1
+        buffer.write_bits(self.packet_data_ms_type as u64, 4);
+        // Type1
+        buffer.write_bits(self.pcomp_negotiation as u64, 8);
+
+        // No optional fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnActivatePdpContextDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnActivatePdpContextDemand {{ sndcp_version: {:?} nsapi: {:?} address_type: {:?} ip_address: {:?} packet_data_ms_type: {:?} pcomp_negotiation: {:?} }}",
+            self.sndcp_version, self.nsapi, self.address_type, self.ip_address, self.packet_data_ms_type, self.pcomp_negotiation,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_activate_pdp_context_demand() {
+        debug::setup_logging_verbose();
+        // Version 1, NSAPI 5, IPv4 static address 10.0.0.10, MS type 1, no PCOMP
+        let test_vec = "000000010101000000010100000000000000000000010100001000000000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnActivatePdpContextDemand::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.nsapi, 5);
+        assert_eq!(pdu.ip_address, Some(0x0a00000a));
+
+        let mut buf_out = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
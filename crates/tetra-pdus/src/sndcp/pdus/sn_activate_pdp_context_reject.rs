@@ -0,0 +1,66 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+
+/// Representation of the SN-ACTIVATE PDP CONTEXT REJECT PDU (Clause 28.4).
+/// The infrastructure sends this message to the MS to reject activation of a PDP context.
+/// Response expected: -
+/// Response to: SN-ACTIVATE PDP CONTEXT DEMAND
+#[derive(Debug)]
+pub struct SnActivatePdpContextReject {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 8 bits, Activation reject cause
+    pub reject_cause: u8,
+}
+
+impl SnActivatePdpContextReject {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnActivatePdpContextReject)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let reject_cause = buffer.read_field(8, "reject_cause")? as u8;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            return Err(PduParseErr::NotImplemented {
+                field: Some("optional elements"),
+            });
+        }
+
+        Ok(SnActivatePdpContextReject { nsapi, reject_cause })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnActivatePdpContextReject.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.reject_cause as u64, 8);
+
+        // No optional fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnActivatePdpContextReject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnActivatePdpContextReject {{ nsapi: {:?} reject_cause: {:?} }}",
+            self.nsapi, self.reject_cause,
+        )
+    }
+}
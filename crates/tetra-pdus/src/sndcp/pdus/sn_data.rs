@@ -0,0 +1,83 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+
+/// Representation of the SN-DATA PDU (Clause 28.4).
+/// Carries an N-PDU over a PDP context using acknowledged service.
+/// Response expected: -
+/// Response to: -
+
+// note 1: The N-PDU fills the remainder of the SN-PDU. Trailing bits short of a full octet are ignored.
+#[derive(Debug)]
+pub struct SnData {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 4 bits, Data compression (DCOMP) identifier, 0 when not compressed
+    pub dcomp: u8,
+    /// Type1, 4 bits, Protocol control information compression (PCOMP) identifier, 0 when not compressed
+    pub pcomp: u8,
+    /// Variable, N-PDU (IP packet), See note 1
+    pub n_pdu: Vec<u8>,
+}
+
+impl SnData {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnData)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let dcomp = buffer.read_field(4, "dcomp")? as u8;
+        // Type1
+        let pcomp = buffer.read_field(4, "pcomp")? as u8;
+
+        // N-PDU
+        let num_bytes = buffer.get_len_remaining() / 8;
+        let mut n_pdu = vec![0u8; num_bytes];
+        buffer
+            .read_bits_into_slice(num_bytes * 8, &mut n_pdu)
+            .ok_or(PduParseErr::BufferEnded { field: Some("n_pdu") })?;
+
+        Ok(SnData {
+            nsapi,
+            dcomp,
+            pcomp,
+            n_pdu,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnData.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.dcomp as u64, 4);
+        // Type1
+        buffer.write_bits(self.pcomp as u64, 4);
+        // N-PDU
+        for byte in &self.n_pdu {
+            buffer.write_bits(*byte as u64, 8);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnData {{ nsapi: {:?} dcomp: {:?} pcomp: {:?} n_pdu: {} bytes }}",
+            self.nsapi,
+            self.dcomp,
+            self.pcomp,
+            self.n_pdu.len(),
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+use crate::sndcp::pdus::sn_deactivate_pdp_context_demand::DEACTIVATE_NSAPI;
+
+/// Representation of the SN-DEACTIVATE PDP CONTEXT ACCEPT PDU (Clause 28.4).
+/// The MS or the infrastructure sends this message to confirm deactivation of one or all PDP contexts.
+/// Response expected: -
+/// Response to: SN-DEACTIVATE PDP CONTEXT DEMAND
+
+// note 1: NSAPI is present if the deactivation type is "deactivate the NSAPI given in this PDU".
+#[derive(Debug)]
+pub struct SnDeactivatePdpContextAccept {
+    /// Type1, 8 bits, Deactivation type
+    pub deactivation_type: u8,
+    /// Conditional 4 bits, See note 1
+    pub nsapi: Option<u8>,
+}
+
+impl SnDeactivatePdpContextAccept {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnDeactivatePdpContextAccept)?;
+
+        // Type1
+        let deactivation_type = buffer.read_field(8, "deactivation_type")? as u8;
+        // Conditional
+        let nsapi = if deactivation_type == DEACTIVATE_NSAPI {
+            Some(buffer.read_field(4, "nsapi")? as u8)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            return Err(PduParseErr::NotImplemented {
+                field: Some("optional elements"),
+            });
+        }
+
+        Ok(SnDeactivatePdpContextAccept { deactivation_type, nsapi })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnDeactivatePdpContextAccept.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.deactivation_type as u64, 8);
+        // Conditional
+        if self.deactivation_type == DEACTIVATE_NSAPI {
+            let Some(nsapi) = self.nsapi else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("nsapi") });
+            };
+            buffer.write_bits(nsapi as u64, 4);
+        }
+
+        // No optional fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnDeactivatePdpContextAccept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnDeactivatePdpContextAccept {{ deactivation_type: {:?} nsapi: {:?} }}",
+            self.deactivation_type, self.nsapi,
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+
+/// Value of the deactivation type field requesting deactivation of all PDP contexts of the MS
+pub const DEACTIVATE_ALL_NSAPIS: u8 = 0;
+/// Value of the deactivation type field requesting deactivation of the PDP context given in the NSAPI field
+pub const DEACTIVATE_NSAPI: u8 = 1;
+
+/// Representation of the SN-DEACTIVATE PDP CONTEXT DEMAND PDU (Clause 28.4).
+/// The MS or the infrastructure sends this message to deactivate one or all PDP contexts of the MS.
+/// Response expected: SN-DEACTIVATE PDP CONTEXT ACCEPT
+/// Response to: -
+
+// note 1: NSAPI is present if the deactivation type is "deactivate the NSAPI given in this PDU".
+#[derive(Debug)]
+pub struct SnDeactivatePdpContextDemand {
+    /// Type1, 8 bits, Deactivation type
+    pub deactivation_type: u8,
+    /// Conditional 4 bits, See note 1
+    pub nsapi: Option<u8>,
+}
+
+impl SnDeactivatePdpContextDemand {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnDeactivatePdpContextDemand)?;
+
+        // Type1
+        let deactivation_type = buffer.read_field(8, "deactivation_type")? as u8;
+        // Conditional
+        let nsapi = if deactivation_type == DEACTIVATE_NSAPI {
+            Some(buffer.read_field(4, "nsapi")? as u8)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            return Err(PduParseErr::NotImplemented {
+                field: Some("optional elements"),
+            });
+        }
+
+        Ok(SnDeactivatePdpContextDemand { deactivation_type, nsapi })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnDeactivatePdpContextDemand.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.deactivation_type as u64, 8);
+        // Conditional
+        if self.deactivation_type == DEACTIVATE_NSAPI {
+            let Some(nsapi) = self.nsapi else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("nsapi") });
+            };
+            buffer.write_bits(nsapi as u64, 4);
+        }
+
+        // No optional fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnDeactivatePdpContextDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnDeactivatePdpContextDemand {{ deactivation_type: {:?} nsapi: {:?} }}",
+            self.deactivation_type, self.nsapi,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_deactivate_pdp_context_demand() {
+        debug::setup_logging_verbose();
+        // Deactivate NSAPI 5
+        let test_vec = "00100000000101010";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnDeactivatePdpContextDemand::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.nsapi, Some(5));
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
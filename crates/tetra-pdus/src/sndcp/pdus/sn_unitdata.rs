@@ -0,0 +1,108 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sndcp_pdu_type::SndcpPduType;
+
+/// Representation of the SN-UNITDATA PDU (Clause 28.4).
+/// Carries an N-PDU over a PDP context using unacknowledged service.
+/// Response expected: -
+/// Response to: -
+
+// note 1: The N-PDU fills the remainder of the SN-PDU. Trailing bits short of a full octet are ignored.
+#[derive(Debug)]
+pub struct SnUnitdata {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 4 bits, Data compression (DCOMP) identifier, 0 when not compressed
+    pub dcomp: u8,
+    /// Type1, 4 bits, Protocol control information compression (PCOMP) identifier, 0 when not compressed
+    pub pcomp: u8,
+    /// Variable, N-PDU (IP packet), See note 1
+    pub n_pdu: Vec<u8>,
+}
+
+impl SnUnitdata {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SndcpPduType::SnUnitdata)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let dcomp = buffer.read_field(4, "dcomp")? as u8;
+        // Type1
+        let pcomp = buffer.read_field(4, "pcomp")? as u8;
+
+        // N-PDU
+        let num_bytes = buffer.get_len_remaining() / 8;
+        let mut n_pdu = vec![0u8; num_bytes];
+        buffer
+            .read_bits_into_slice(num_bytes * 8, &mut n_pdu)
+            .ok_or(PduParseErr::BufferEnded { field: Some("n_pdu") })?;
+
+        Ok(SnUnitdata {
+            nsapi,
+            dcomp,
+            pcomp,
+            n_pdu,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SndcpPduType::SnUnitdata.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.dcomp as u64, 4);
+        // Type1
+        buffer.write_bits(self.pcomp as u64, 4);
+        // N-PDU
+        for byte in &self.n_pdu {
+            buffer.write_bits(*byte as u64, 8);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnUnitdata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnUnitdata {{ nsapi: {:?} dcomp: {:?} pcomp: {:?} n_pdu: {} bytes }}",
+            self.nsapi,
+            self.dcomp,
+            self.pcomp,
+            self.n_pdu.len(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_unitdata() {
+        debug::setup_logging_verbose();
+        // NSAPI 5, uncompressed, 2-byte N-PDU
+        let test_vec = "01000101000000000100010100000000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnUnitdata::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.nsapi, 5);
+        assert_eq!(pdu.n_pdu, vec![0x45, 0x00]);
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
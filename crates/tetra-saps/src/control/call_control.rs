@@ -25,6 +25,14 @@ pub struct Circuit {
     pub speech_service: Option<u8>,
     /// Whether end-to-end encryption is enabled on this circuit
     pub etee_encrypted: bool,
+
+    /// For duplex individual calls: the timeslot on which the UL traffic of this circuit is
+    /// sent out on the DL. None means UL traffic is repeated on the DL of the same timeslot.
+    pub peer_ts: Option<u8>,
+
+    /// For individual calls: ISSIs of the MS transmitting on the UL and the MS receiving the DL of this
+    /// circuit, whose derived cipher keys protect its traffic in security class 3. None for group calls.
+    pub individual_parties: Option<(u32, u32)>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +53,8 @@ pub enum CallControl {
         source_issi: u32,
         dest_gssi: u32,
         ts: u8,
+        /// Call priority (ETSI 14.8.14), 15 marks an emergency call
+        priority: u8,
     },
     /// Floor released: speaker stopped transmitting (entering hangtime).
     /// Sent to UMAC to enter hangtime signalling mode and to Brew to stop forwarding audio.
@@ -76,4 +86,41 @@ pub enum CallControl {
     /// UL inactivity detected on a traffic timeslot — no voice frames received
     /// for the timeout period. Sent by UMAC to CMCE.
     UlInactivityTimeout { ts: u8 },
+    /// Signalling of an individual call between a local MS and a network subscriber.
+    /// Sent by Brew to CMCE for the network party, and by CMCE to Brew for the local party.
+    NetworkIndividualCall {
+        brew_uuid: uuid::Uuid, // Brew session UUID of the call
+        signal: IndividualCallSignal,
+    },
+    /// DTMF digits dialled during a call (ETSI 14.8.17), as characters 0-9, *, # and A-D.
+    /// Sent by CMCE to Brew for digits from a local MS, and by Brew to CMCE for digits from the network.
+    CallDtmf { call_id: u16, digits: String },
+}
+
+/// Individual call signalling exchanged with the network, mirroring the Brew call states
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndividualCallSignal {
+    /// Call set-up request from the calling party
+    Setup {
+        calling_issi: u32,
+        called_issi: u32,
+        /// Call priority (ETSI 14.8.14)
+        priority: u8,
+        /// Hook method selection: true for hook signalling, false for direct call set-up
+        hook_method: bool,
+    },
+    /// The called party is reachable and is being paged
+    Accept,
+    /// The called user is being alerted
+    Alert,
+    /// The called party answered
+    Connect,
+    /// The calling party is through-connected to the answering called party
+    ConnectConfirm,
+    /// The floor of the simplex call was granted to the given party
+    TxGranted { issi: u32 },
+    /// The party holding the floor stopped transmitting
+    TxCeased,
+    /// Call set-up rejected or call released, with the disconnect cause (ETSI 14.8.18)
+    Release { cause: u8 },
 }
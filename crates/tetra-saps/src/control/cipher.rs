@@ -0,0 +1,8 @@
+/// Derived cipher key update for an MS, sent by MM to UMAC.
+/// Carries the DCK after successful authentication, or None once the MS is no longer registered.
+#[derive(Debug, Clone)]
+pub struct MmCipherKeyUpdate {
+    pub issi: u32,
+    /// 80-bit derived cipher key, carried in the low bits of a u128
+    pub dck: Option<u128>,
+}
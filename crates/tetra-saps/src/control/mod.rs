@@ -1,4 +1,5 @@
 pub mod brew;
 pub mod call_control;
+pub mod cipher;
 pub mod enums;
 pub mod sds;
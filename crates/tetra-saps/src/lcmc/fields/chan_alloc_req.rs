@@ -1,4 +1,4 @@
-use tetra_core::Todo;
+use tetra_core::{channel_carrier, channel_timeslot, traffic_channel};
 
 use crate::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment};
 
@@ -6,8 +6,8 @@ use crate::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssign
 pub struct CmceChanAllocReq {
     /// Set for new allocation, None for QuitAndGo
     pub usage: Option<u8>,
-    /// Carrier frequency; by default, uses self
-    pub carrier: Option<Todo>,
+    /// Index of the carrier in the cell, 0 being the main carrier; by default, uses the main carrier
+    pub carrier: Option<u8>,
     /// Bitmap of slots to use.
     pub timeslots: [bool; 4],
     /// Alloc type.
@@ -17,3 +17,19 @@ pub struct CmceChanAllocReq {
     pub alloc_type: ChanAllocType,
     pub ul_dl_assigned: UlDlAssignment,
 }
+
+impl CmceChanAllocReq {
+    /// Carrier and timeslot bitmap addressing a single traffic channel, see tetra_core::traffic_channel
+    pub fn channel_to_carrier_and_timeslots(chan: u8) -> (Option<u8>, [bool; 4]) {
+        let mut timeslots = [false; 4];
+        timeslots[channel_timeslot(chan) as usize - 1] = true;
+        let carrier = channel_carrier(chan);
+        ((carrier != 0).then_some(carrier), timeslots)
+    }
+
+    /// Traffic channel of the first timeslot allocated, if any
+    pub fn first_channel(&self) -> Option<u8> {
+        let ts = self.timeslots.iter().position(|&set| set)? as u8 + 1;
+        Some(traffic_channel(self.carrier.unwrap_or(0), ts))
+    }
+}
@@ -204,4 +204,6 @@ pub struct LcmcMleUnitdataInd {
     pub received_tetra_address: TetraAddress, // ITSI/GTSI
     pub chan_change_resp_req: bool,
     pub chan_change_handle: Option<Todo>,
+    /// Channel allocation that came along with the PDU, MS side only
+    pub chan_alloc: Option<CmceChanAllocReq>,
 }
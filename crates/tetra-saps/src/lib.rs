@@ -8,6 +8,7 @@ pub mod lcmc;
 pub mod lmm;
 pub mod ltpd;
 pub mod sapmsg;
+pub mod sn;
 pub mod tla;
 pub mod tle;
 pub mod tlmb;
@@ -17,6 +18,8 @@ pub mod tmv;
 pub mod tp;
 pub mod tpc;
 
+pub mod tncc;
 pub mod tnmm;
+pub mod tnsds;
 
 pub use sapmsg::*;
@@ -197,7 +197,7 @@ pub struct LtpdMleResumeInd {
 
 #[derive(Debug, Clone)]
 pub struct LtpdMleUnitdataReq {
-    pub sdu: Todo,
+    pub sdu: BitBuffer,
     pub handle: Todo,
     pub layer2service: Layer2Service,
     pub unacked_bl_repetitions: Todo,
@@ -214,6 +214,9 @@ pub struct LtpdMleUnitdataReq {
     pub scheduled_data_status: Todo,
     pub max_schedule_interval: Todo,
     pub fcs_flag: bool,
+
+    /// Custom field, address of the MS the SN-PDU is destined for
+    pub main_address: TetraAddress,
 }
 
 #[derive(Debug, Clone)]
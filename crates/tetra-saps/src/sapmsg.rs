@@ -6,15 +6,20 @@ use tetra_core::tetra_entities::TetraEntity;
 
 use crate::control::brew::MmSubscriberUpdate;
 use crate::control::call_control::CallControl;
+use crate::control::cipher::MmCipherKeyUpdate;
 use crate::control::sds::CmceSdsData;
 use crate::tmd::TmdCircuitDataInd;
 use crate::tmd::TmdCircuitDataReq;
+use crate::tncc::*;
+use crate::tnmm::TnmmRegistrationInd;
 use crate::tnmm::TnmmTestDemand;
 use crate::tnmm::TnmmTestResponse;
+use crate::tnsds::*;
 
 use super::lcmc::*;
 use super::lmm::*;
 use super::ltpd::*;
+use super::sn::*;
 use super::tla::*;
 use super::tlmb::*;
 use super::tlmc::*;
@@ -66,10 +71,24 @@ pub enum SapMsgInner {
     TlaTlReportInd(TlaTlReportInd),
     TlaTlUnitdataIndBl(TlaTlUnitdataIndBl),
     TlaTlUnitdataReqBl(TlaTlUnitdataReqBl),
+    // TLA-SAP advanced link
+    TlaTlConnectReq(TlaTlConnectReq),
+    TlaTlConnectInd(TlaTlConnectInd),
+    TlaTlConnectConf(TlaTlConnectConf),
+    TlaTlDataReqAl(TlaTlDataReqAl),
+    TlaTlDataIndAl(TlaTlDataIndAl),
+    TlaTlDisconnectReq(TlaTlDisconnectReq),
+    TlaTlDisconnectInd(TlaTlDisconnectInd),
+    TlaTlDisconnectConf(TlaTlDisconnectConf),
+    TlaTlReconnectReq(TlaTlReconnectReq),
+    TlaTlReconnectConf(TlaTlReconnectConf),
+    TlaTlUnitdataReqAl(TlaTlUnitdataReqAl),
+    TlaTlUnitdataIndAl(TlaTlUnitdataIndAl),
 
     // LMM-SAP (MLE-MM)
     LmmMleUnitdataInd(LmmMleUnitdataInd),
     LmmMleUnitdataReq(LmmMleUnitdataReq),
+    LmmMleActivateConf(LmmMleActivateConf),
 
     // LCMC-SAP (MLE-CMCE)
     LcmcMleUnitdataInd(LcmcMleUnitdataInd),
@@ -81,15 +100,38 @@ pub enum SapMsgInner {
     // MM -> Brew/CMCE subscriber update
     MmSubscriberUpdate(MmSubscriberUpdate),
 
+    // MM -> UMAC derived cipher key update
+    MmCipherKeyUpdate(MmCipherKeyUpdate),
+
     // CMCE SDS <-> Brew SDS routing
     CmceSdsData(CmceSdsData),
 
-    // LTPD-SAP (MLE-LTPD)
+    // LTPD-SAP (MLE-SNDCP)
     LtpdMleUnitdataInd(LtpdMleUnitdataInd),
+    LtpdMleUnitdataReq(LtpdMleUnitdataReq),
+
+    // SN-SAP (SNDCP-packet data user)
+    SnDataInd(SnDataInd),
+    SnDataReq(SnDataReq),
+    SnPdpContextInd(SnPdpContextInd),
 
     // TNMM-SAP (MM-User)
     TnmmTestDemand(TnmmTestDemand),
     TnmmTestResponse(TnmmTestResponse),
+    TnmmRegistrationInd(TnmmRegistrationInd),
+
+    // TNCC-SAP (CMCE CC-User)
+    TnccSetupReq(TnccSetupReq),
+    TnccSetupInd(TnccSetupInd),
+    TnccSetupCon(TnccSetupCon),
+    TnccTxReq(TnccTxReq),
+    TnccTxInd(TnccTxInd),
+    TnccReleaseReq(TnccReleaseReq),
+    TnccReleaseInd(TnccReleaseInd),
+
+    // TNSDS-SAP (CMCE SDS-User)
+    TnsdsUnitdataReq(TnsdsUnitdataReq),
+    TnsdsUnitdataInd(TnsdsUnitdataInd),
 }
 
 impl Display for SapMsgInner {
@@ -114,6 +156,28 @@ impl Display for SapMsgInner {
 
             // Control/Brew
             SapMsgInner::MmSubscriberUpdate(_) => write!(f, "MmSubscriberUpdate"),
+            SapMsgInner::MmCipherKeyUpdate(_) => write!(f, "MmCipherKeyUpdate"),
+
+            // SN-SAP
+            SapMsgInner::SnDataInd(_) => write!(f, "SnDataInd"),
+            SapMsgInner::SnDataReq(_) => write!(f, "SnDataReq"),
+            SapMsgInner::SnPdpContextInd(_) => write!(f, "SnPdpContextInd"),
+
+            // TNMM-SAP
+            SapMsgInner::TnmmRegistrationInd(_) => write!(f, "TnmmRegistrationInd"),
+
+            // TNCC-SAP
+            SapMsgInner::TnccSetupReq(_) => write!(f, "TnccSetupReq"),
+            SapMsgInner::TnccSetupInd(_) => write!(f, "TnccSetupInd"),
+            SapMsgInner::TnccSetupCon(_) => write!(f, "TnccSetupCon"),
+            SapMsgInner::TnccTxReq(_) => write!(f, "TnccTxReq"),
+            SapMsgInner::TnccTxInd(_) => write!(f, "TnccTxInd"),
+            SapMsgInner::TnccReleaseReq(_) => write!(f, "TnccReleaseReq"),
+            SapMsgInner::TnccReleaseInd(_) => write!(f, "TnccReleaseInd"),
+
+            // TNSDS-SAP
+            SapMsgInner::TnsdsUnitdataReq(_) => write!(f, "TnsdsUnitdataReq"),
+            SapMsgInner::TnsdsUnitdataInd(_) => write!(f, "TnsdsUnitdataInd"),
 
             // TLB-SAP
             // SapMsgInner::TlbTlSyncInd(_) => write!(f, "TlbTlSyncInd"),
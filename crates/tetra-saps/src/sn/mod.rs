@@ -0,0 +1,27 @@
+// Clause 28.2 SN-SAP (SNDCP-packet data user)
+// The primitives carry IPv4 packets between SNDCP and the packet data gateway.
+
+use std::net::Ipv4Addr;
+
+/// SN-DATA / SN-UNITDATA indication: an IP packet received from an MS over one of its PDP contexts
+#[derive(Debug, Clone)]
+pub struct SnDataInd {
+    pub issi: u32,
+    pub nsapi: u8,
+    pub packet: Vec<u8>,
+}
+
+/// SN-DATA request: an IP packet to be delivered to the MS owning its destination address
+#[derive(Debug, Clone)]
+pub struct SnDataReq {
+    pub packet: Vec<u8>,
+}
+
+/// Custom primitive, informs the packet data user of PDP context activation and deactivation
+#[derive(Debug, Clone)]
+pub struct SnPdpContextInd {
+    pub issi: u32,
+    pub nsapi: u8,
+    pub ip_addr: Ipv4Addr,
+    pub active: bool,
+}
@@ -8,43 +8,54 @@ pub struct TlCancelReq {
     pub handle: Todo,
 }
 
-/// advanced link
+/// Outcome of an advanced link procedure, reported to the layer 2 service user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlReport {
+    Success,
+    /// The peer did not answer within the allowed number of retries
+    NoResponse,
+    /// The peer has no such advanced link, or refused the procedure
+    Rejected,
+    /// The peer released the advanced link
+    PeerDisconnect,
+    /// The retransmission limits were exhausted without the data being acknowledged
+    LinkFailure,
+}
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT request: this primitive shall be used by the layer 2 service user to initiate the set-up of an advanced link.
 #[derive(Debug, Clone)]
-pub struct TlConnectReq {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    pdu_prio: Todo,
-    stealing_permission: bool,
-    subscriber_class: Todo,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    req_handle: Todo,
-    setup_report: Todo,
+pub struct TlaTlConnectReq {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    // pub scrambling_code: Todo,
+    // pub pdu_prio: Todo,
+    // pub stealing_permission: bool,
+    // pub subscriber_class: Todo,
+    // pub qos: Todo,
+    /// Requests the unacknowledged advanced link service
+    pub unacknowledged: bool,
+    // pub air_interface_encryption: Todo,
+    pub req_handle: Todo,
 }
-/// advanced link
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT indication: this primitive shall be used by the layer 2 to inform the layer 2 service user that the peer
+/// entity has set up an advanced link.
 #[derive(Debug, Clone)]
-pub struct TlConnectInd {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    new_endpoint_id: Option<Todo>,
-    css_endpoint_id: Option<Todo>,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    chan_change_resp_req: bool,
-    chan_change_handle: Option<Todo>,
-    chan_info: Option<Todo>,
-    req_handle: Todo,
-    setup_report: Todo,
+pub struct TlaTlConnectInd {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub unacknowledged: bool,
 }
-/// advanced link
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT response: this primitive shall be used by the layer 2 service user to accept the set-up of an
+/// advanced link. Incoming set-ups are accepted by the layer 2 on behalf of the service user.
 #[derive(Debug, Clone)]
 pub struct TlConnectResp {
     // address_type: Todo,
@@ -61,31 +72,51 @@ pub struct TlConnectResp {
     req_handle: Todo,
     setup_report: Todo,
 }
-/// advanced link
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT confirm: this primitive shall be used by the layer 2 to inform the layer 2 service user of the outcome
+/// of the advanced link set-up it requested.
 #[derive(Debug, Clone)]
-pub struct TlConnectConf {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    new_endpoint_id: Option<Todo>,
-    css_endpoint_id: Option<Todo>,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    chan_change_resp_req: bool,
-    chan_change_handle: Option<Todo>,
-    chan_info: Option<Todo>,
-    req_handle: Todo,
-    setup_report: Todo,
+pub struct TlaTlConnectConf {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub req_handle: Todo,
+    pub report: AlReport,
 }
 
-/// advanced link only
+/// Clause 20.3.5.1.3
+/// TL-DATA request: this primitive shall be used by the layer 2 service user to request transmission of a TL-SDU on an
+/// advanced link. The TL-SDU is segmented and acknowledged by the peer entity.
 #[derive(Debug, Clone)]
-pub struct TlDataReqAl;
+pub struct TlaTlDataReqAl {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub tl_sdu: BitBuffer,
+    // pub pdu_prio: Todo,
+    // pub subscriber_class: Todo,
+    // pub air_interface_encryption: Todo,
+    pub req_handle: Todo,
+
+    /// Optional TxReporter, marked acknowledged once the peer has received the whole TL-SDU
+    pub tx_reporter: Option<TxReporter>,
+}
+
+/// Clause 20.3.5.1.3
+/// TL-DATA indication: this primitive shall be used by the layer 2 to deliver a TL-SDU received on an advanced link.
 #[derive(Debug, Clone)]
-pub struct TlDataIndAl;
+pub struct TlaTlDataIndAl {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub tl_sdu: Option<BitBuffer>,
+}
+
+/// Advanced link. The TxReporter of the request is used instead
 #[derive(Debug, Clone)]
 pub struct TlDataConfAl;
 
@@ -135,7 +166,7 @@ pub struct TlaTlDataIndBl {
     pub air_interface_encryption: Todo,
     pub chan_change_resp_req: bool,
     pub chan_change_handle: Option<Todo>,
-    pub chan_info: Option<Todo>,
+    pub chan_info: Option<CmceChanAllocReq>,
     pub req_handle: Todo,
 }
 
@@ -184,15 +215,39 @@ pub struct TlDataConfBl {
     pub report: Todo,
 }
 
-/// Advanced link only
+/// Clause 20.3.5.1.5
+/// TL-DISCONNECT request: this primitive shall be used by the layer 2 service user to release an advanced link.
 #[derive(Debug, Clone)]
-pub struct TlDisconnectReq;
-/// Advanced link only
+pub struct TlaTlDisconnectReq {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+}
+
+/// Clause 20.3.5.1.5
+/// TL-DISCONNECT indication: this primitive shall be used by the layer 2 to inform the layer 2 service user that an
+/// advanced link was released by the peer entity, or had to be released after a failure.
 #[derive(Debug, Clone)]
-pub struct TlDisconnectInd;
-/// Advanced link only
+pub struct TlaTlDisconnectInd {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub report: AlReport,
+}
+
+/// Clause 20.3.5.1.5
+/// TL-DISCONNECT confirm: this primitive shall be used by the layer 2 to inform the layer 2 service user that the
+/// advanced link it asked to release is released.
 #[derive(Debug, Clone)]
-pub struct TlDisconnectConf;
+pub struct TlaTlDisconnectConf {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub report: AlReport,
+}
 
 /// advanced link, BS only
 #[derive(Debug, Clone)]
@@ -213,10 +268,30 @@ pub struct TlReleaseInd {
     pub endpoint_id: EndpointId,
 }
 
-/// advanced link
+/// Clause 20.3.5.1.7
+/// TL-RECONNECT request: this primitive shall be used by the layer 2 service user to resume an advanced link, e.g.
+/// after a cell change, without losing the TL-SDUs in transfer.
+#[derive(Debug, Clone)]
+pub struct TlaTlReconnectReq {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+}
+
+/// Clause 20.3.5.1.7
+/// TL-RECONNECT confirm: this primitive shall be used by the layer 2 to inform the layer 2 service user of the outcome
+/// of the reconnection. The advanced link is released if the reconnection failed.
 #[derive(Debug, Clone)]
-pub struct TlReconnectReq;
-/// advanced link
+pub struct TlaTlReconnectConf {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub report: AlReport,
+}
+
+/// advanced link. Reconnections are answered by the layer 2 on behalf of the service user
 #[derive(Debug, Clone)]
 pub struct TlReconnectResp;
 
@@ -285,7 +360,7 @@ pub struct TlaTlUnitdataIndBl {
     pub air_interface_encryption: Todo,
     pub chan_change_resp_req: bool,
     pub chan_change_handle: Option<Todo>,
-    pub chan_info: Option<Todo>,
+    pub chan_info: Option<CmceChanAllocReq>,
     pub report: Option<Todo>,
 }
 
@@ -302,12 +377,36 @@ pub struct TlUnitdataConfBl {
     pub report: Option<Todo>,
 }
 
-/// Advanced link
+/// Clause 20.3.5.1.10
+/// TL-UNITDATA request: this primitive shall be used in the unacknowledged advanced link service to request
+/// transmission of a TL-SDU, which is segmented and repeated N.282 times.
 #[derive(Debug, Clone)]
-pub struct TlUnitdataReqAl;
-/// Advanced link
+pub struct TlaTlUnitdataReqAl {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub tl_sdu: BitBuffer,
+    // pub pdu_prio: Todo,
+    // pub subscriber_class: Todo,
+    // pub air_interface_encryption: Todo,
+    pub req_handle: Todo,
+
+    /// Optional TxReporter, marked transmitted once all segments have been sent
+    pub tx_reporter: Option<TxReporter>,
+}
+
+/// Clause 20.3.5.1.10
+/// TL-UNITDATA indication: this primitive shall be used in the unacknowledged advanced link service to deliver a
+/// received TL-SDU.
 #[derive(Debug, Clone)]
-pub struct TlUnitdataIndAl;
+pub struct TlaTlUnitdataIndAl {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub tl_sdu: Option<BitBuffer>,
+}
 /// Advanced link, optional?
 #[derive(Debug, Clone)]
 pub struct TlUnitdataConfAl;
@@ -84,5 +84,6 @@ pub struct TmaUnitdataInd {
     pub air_interface_encryption: Todo,
     pub chan_change_response_req: bool,
     pub chan_change_handle: Option<Todo>,
-    pub chan_info: Option<Todo>,
+    /// Channel allocation carried in the MAC header, MS side only
+    pub chan_info: Option<CmceChanAllocReq>,
 }
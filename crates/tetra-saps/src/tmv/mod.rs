@@ -1,6 +1,6 @@
 pub mod enums;
 
-use tetra_core::{BitBuffer, PhyBlockNum, PhysicalChannel, TdmaTime, Todo};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, Todo};
 
 use crate::tmv::enums::logical_chans::LogicalChannel;
 
@@ -16,6 +16,8 @@ pub struct TmvUnitdataReq {
 pub struct TmvUnitdataReqSlot {
     /// Timeslot at which this block is to be transmitted
     pub ts: TdmaTime,
+    /// Index of the carrier to transmit on, 0 being the main carrier
+    pub carrier: u8,
     pub ul_phy_chan: PhysicalChannel,
 
     /// First MAC block in this timeslot. May be received from LLC
@@ -46,6 +48,10 @@ pub struct TmvUnitdataInd {
     /// If no CRC is present on this message type (for example, for AACH), crc_pass is set to True
     pub crc_pass: bool,
     pub scrambling_code: u32,
+
+    /// Not in the spec either. A BS only receives uplink and an MS only downlink blocks,
+    /// but a monitor receives both and needs to know which parser applies.
+    pub direction: Direction,
 }
 
 /// Clause 23.2.1
@@ -0,0 +1,69 @@
+// Clause 11.3 TNCC-SAP (CMCE CC-user), MS side
+// Simplified to simplex group calls; the MS takes part in at most one call at a time.
+
+/// TNCC-SETUP request: start a group call. Transmission is requested along with the setup
+#[derive(Debug, Clone)]
+pub struct TnccSetupReq {
+    pub called_ssi: u32,
+}
+
+/// TNCC-SETUP indication: a call we are a party of was set up by the SwMI
+#[derive(Debug, Clone)]
+pub struct TnccSetupInd {
+    pub call_id: u16,
+    pub calling_ssi: u32,
+    pub called_ssi: u32,
+}
+
+/// TNCC-SETUP confirm: our own call was connected
+#[derive(Debug, Clone)]
+pub struct TnccSetupCon {
+    pub call_id: u16,
+    pub called_ssi: u32,
+}
+
+/// TNCC-TX request: ask for (demand) or give up (cease) permission to transmit
+#[derive(Debug, Clone)]
+pub struct TnccTxReq {
+    pub call_id: u16,
+    pub demand: bool,
+}
+
+/// Transmission state of a call, as seen by the MS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnccTxState {
+    /// We may transmit
+    Granted,
+    /// Our request was rejected
+    NotGranted,
+    /// Our request was queued
+    Queued,
+    /// Another party is transmitting
+    OtherParty,
+    /// Nobody is transmitting
+    Ceased,
+}
+
+/// TNCC-TX indication / confirm: transmission state changed
+#[derive(Debug, Clone)]
+pub struct TnccTxInd {
+    pub call_id: u16,
+    pub state: TnccTxState,
+    /// Transmitting party, if known
+    pub talker_ssi: Option<u32>,
+}
+
+/// TNCC-RELEASE request: leave or end the call
+#[derive(Debug, Clone)]
+pub struct TnccReleaseReq {
+    pub call_id: u16,
+}
+
+/// TNCC-RELEASE indication: the call was released. Also sent when a setup request failed
+#[derive(Debug, Clone)]
+pub struct TnccReleaseInd {
+    /// None if the call was released before a call identifier was assigned
+    pub call_id: Option<u16>,
+    /// Disconnect cause (Clause 14.8.18)
+    pub cause: u8,
+}
@@ -8,3 +8,12 @@ pub struct TnmmTestResponse {
     pub issi: u32,
     pub data: u32,
 }
+
+/// TNMM-REGISTRATION indication / confirm: the MS registration state changed
+#[derive(Debug, Clone)]
+pub struct TnmmRegistrationInd {
+    pub issi: u32,
+    pub registered: bool,
+    /// Groups the SwMI accepted our attachment to
+    pub groups: Vec<u32>,
+}
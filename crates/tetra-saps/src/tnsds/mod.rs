@@ -0,0 +1,20 @@
+// Clause 13.3 TNSDS-SAP (CMCE SDS-user), MS side
+// Simplified to the unacknowledged user data service.
+
+use crate::control::enums::sds_user_data::SdsUserData;
+
+/// TNSDS-UNITDATA request: user data to be sent to an individual or group
+#[derive(Debug, Clone)]
+pub struct TnsdsUnitdataReq {
+    pub called_ssi: u32,
+    pub user_data: SdsUserData,
+}
+
+/// TNSDS-UNITDATA indication: user data received from the SwMI
+#[derive(Debug, Clone)]
+pub struct TnsdsUnitdataInd {
+    pub calling_ssi: u32,
+    /// Our own ISSI, or one of our groups
+    pub called_ssi: u32,
+    pub user_data: SdsUserData,
+}
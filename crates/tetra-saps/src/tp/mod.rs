@@ -8,6 +8,8 @@ pub struct TpUnitdataInd {
     /// Undefined for BBK. For all others: [ Block1 | Block2 | Both ]
     pub block_num: PhyBlockNum,
     pub block: BitBuffer,
+    /// Index of the carrier the block was received on, 0 being the main carrier
+    pub carrier: u8,
 }
 
 #[derive(Debug, Clone)]
@@ -17,4 +19,6 @@ pub struct TpUnitdataReqSlot {
     pub bbk: Option<BitBuffer>,
     pub blk1: Option<BitBuffer>,
     pub blk2: Option<BitBuffer>,
+    /// Index of the carrier to transmit on, 0 being the main carrier
+    pub carrier: u8,
 }